@@ -0,0 +1,379 @@
+//! Building and editing `VALARM`s (RFC 5545 §3.6.6) on existing calendar objects, including
+//! acknowledgement and snoozing (RFC 9074) and the client-specific variants of both.
+//!
+//! Edits apply to the master component of the object: the first `VEVENT` or `VTODO` without
+//! a `RECURRENCE-ID`. Alarm indices refer to the order of its `VALARM`s.
+
+use anyhow::{Result, anyhow};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::caldav::event::generate_uid;
+use crate::common::contentline::{Component, ContentLine, escape_text};
+use crate::common::datetime::{format_utc_datetime, parse_duration, parse_utc_datetime};
+
+/// How acknowledgements and snoozes are written, matching what common clients read back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AlarmQuirks {
+    /// RFC 9074: `ACKNOWLEDGED` on the alarm, snoozes as a new `VALARM` with
+    /// `RELATED-TO;RELTYPE=SNOOZE` pointing at the alarm `UID`.
+    #[default]
+    Standard,
+    /// Thunderbird/Lightning: `X-MOZ-LASTACK` and `X-MOZ-SNOOZE-TIME` on the component.
+    Mozilla,
+    /// Apple Calendar: the RFC 9074 form, with alarm UIDs mirrored in `X-WR-ALARMUID`.
+    Apple,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Trigger {
+    Relative { duration: String, related_end: bool },
+    Absolute(String),
+}
+
+/// Builder for a `VALARM` component.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::AlarmBuilder;
+///
+/// let alarm = AlarmBuilder::display("-PT15M")
+///     .description("Stand-up in 15 minutes")
+///     .repeat(2, "PT5M")
+///     .build()?;
+/// assert_eq!(alarm.value("TRIGGER"), Some("-PT15M"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlarmBuilder {
+    action: String,
+    trigger: Trigger,
+    description: Option<String>,
+    summary: Option<String>,
+    attendees: Vec<String>,
+    repeat: Option<(u32, String)>,
+    uid: Option<String>,
+}
+
+impl AlarmBuilder {
+    /// `DISPLAY` alarm firing `duration` relative to the start, e.g. `-PT15M`.
+    pub fn display(duration: &str) -> Self {
+        Self {
+            action: "DISPLAY".to_string(),
+            trigger: Trigger::Relative {
+                duration: duration.to_string(),
+                related_end: false,
+            },
+            description: None,
+            summary: None,
+            attendees: Vec::new(),
+            repeat: None,
+            uid: None,
+        }
+    }
+
+    /// `ACTION` other than `DISPLAY`, e.g. `AUDIO` or `EMAIL`.
+    pub fn action(mut self, action: &str) -> Self {
+        self.action = action.to_ascii_uppercase();
+        self
+    }
+
+    /// Fire relative to the end (`DTEND`/`DUE`) instead of the start.
+    pub fn related_end(mut self) -> Self {
+        if let Trigger::Relative { related_end, .. } = &mut self.trigger {
+            *related_end = true;
+        }
+        self
+    }
+
+    /// Fire at an absolute UTC date-time (e.g. `20240101T083000Z`) instead.
+    pub fn absolute(mut self, at: &str) -> Self {
+        self.trigger = Trigger::Absolute(at.to_string());
+        self
+    }
+
+    /// `DESCRIPTION`; required for `DISPLAY` and `EMAIL` alarms, defaulting to `Reminder`.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// `SUMMARY`, the subject of `EMAIL` alarms.
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+
+    /// Add a recipient of an `EMAIL` alarm; a bare address gets a `mailto:` prefix.
+    pub fn attendee(mut self, address: &str) -> Self {
+        self.attendees.push(if address.contains(':') {
+            address.to_string()
+        } else {
+            format!("mailto:{address}")
+        });
+        self
+    }
+
+    /// Repeat `count` more times, `interval` apart (`REPEAT` and `DURATION`).
+    pub fn repeat(mut self, count: u32, interval: &str) -> Self {
+        self.repeat = Some((count, interval.to_string()));
+        self
+    }
+
+    /// Alarm `UID` (RFC 9074); generated when unset.
+    pub fn uid(mut self, uid: &str) -> Self {
+        self.uid = Some(uid.to_string());
+        self
+    }
+
+    /// Build the `VALARM` component.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the trigger or repeat interval is malformed, or an `EMAIL` alarm
+    /// has no attendee.
+    pub fn build(&self) -> Result<Component> {
+        let mut alarm = Component::new("VALARM");
+        alarm.set_value("UID", self.uid.clone().unwrap_or_else(generate_uid));
+        alarm.set_value("ACTION", self.action.clone());
+        match &self.trigger {
+            Trigger::Relative {
+                duration,
+                related_end,
+            } => {
+                if parse_duration(duration).is_none() {
+                    return Err(anyhow!("invalid alarm trigger {duration:?}"));
+                }
+                let mut trigger = ContentLine::new("TRIGGER", duration.to_ascii_uppercase());
+                if *related_end {
+                    trigger.set_param("RELATED", "END");
+                }
+                alarm.properties.push(trigger);
+            }
+            Trigger::Absolute(at) => {
+                if parse_utc_datetime(at).is_none() || !at.ends_with('Z') {
+                    return Err(anyhow!("invalid alarm trigger {at:?}"));
+                }
+                let mut trigger = ContentLine::new("TRIGGER", at.clone());
+                trigger.set_param("VALUE", "DATE-TIME");
+                alarm.properties.push(trigger);
+            }
+        }
+        if matches!(self.action.as_str(), "DISPLAY" | "EMAIL") {
+            let description = self.description.as_deref().unwrap_or("Reminder");
+            alarm.set_value("DESCRIPTION", escape_text(description));
+        }
+        if self.action == "EMAIL" {
+            if self.attendees.is_empty() {
+                return Err(anyhow!("EMAIL alarm needs at least one attendee"));
+            }
+            let summary = self.summary.as_deref().unwrap_or("Reminder");
+            alarm.set_value("SUMMARY", escape_text(summary));
+            for attendee in &self.attendees {
+                alarm
+                    .properties
+                    .push(ContentLine::new("ATTENDEE", attendee.clone()));
+            }
+        }
+        if let Some((count, interval)) = &self.repeat {
+            if parse_duration(interval).is_none_or(|d| d <= 0) {
+                return Err(anyhow!("invalid alarm repeat interval {interval:?}"));
+            }
+            alarm.set_value("REPEAT", count.to_string());
+            alarm.set_value("DURATION", interval.to_ascii_uppercase());
+        }
+        Ok(alarm)
+    }
+}
+
+/// Add an alarm to the master component of a calendar object.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar, contains no `VEVENT`/`VTODO`,
+/// or the alarm is invalid (see [`AlarmBuilder::build`]).
+pub fn add_alarm(ics: &str, alarm: &AlarmBuilder, quirks: AlarmQuirks) -> Result<String> {
+    let mut alarm = alarm.build()?;
+    mirror_alarm_uid(&mut alarm, quirks);
+    edit_master(ics, |component| {
+        component.components.push(alarm);
+        Ok(())
+    })
+}
+
+/// Remove the alarm at `index`, together with its RFC 9074 snoozes.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar, contains no `VEVENT`/`VTODO`,
+/// or has no alarm at `index`.
+pub fn remove_alarm(ics: &str, index: usize) -> Result<String> {
+    edit_master(ics, |component| {
+        let position = alarm_position(component, index)?;
+        let removed = component.components.remove(position);
+        if let Some(uid) = alarm_uid(&removed) {
+            component
+                .components
+                .retain(|c| !(c.name.eq_ignore_ascii_case("VALARM") && is_snooze_of(c, &uid)));
+        }
+        Ok(())
+    })
+}
+
+/// Remove every alarm from the master component.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar or contains no `VEVENT`/`VTODO`.
+pub fn remove_alarms(ics: &str) -> Result<String> {
+    edit_master(ics, |component| {
+        component
+            .components
+            .retain(|c| !c.name.eq_ignore_ascii_case("VALARM"));
+        Ok(())
+    })
+}
+
+/// Mark the alarm at `index` as acknowledged now, clearing pending snoozes.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar, contains no `VEVENT`/`VTODO`,
+/// or has no alarm at `index`.
+pub fn acknowledge_alarm(ics: &str, index: usize, quirks: AlarmQuirks) -> Result<String> {
+    let now = now_utc();
+    edit_master(ics, |component| {
+        let position = alarm_position(component, index)?;
+        match quirks {
+            AlarmQuirks::Mozilla => {
+                component.set_value("X-MOZ-LASTACK", now.clone());
+                component.remove_properties("X-MOZ-SNOOZE-TIME");
+            }
+            AlarmQuirks::Standard | AlarmQuirks::Apple => {
+                let alarm = &mut component.components[position];
+                alarm.set_value("ACKNOWLEDGED", now.clone());
+                if let Some(uid) = alarm_uid(alarm) {
+                    component.components.retain(|c| {
+                        !(c.name.eq_ignore_ascii_case("VALARM") && is_snooze_of(c, &uid))
+                    });
+                }
+            }
+        }
+        component.set_value("DTSTAMP", now.clone());
+        Ok(())
+    })
+}
+
+/// Snooze the alarm at `index` until `until`, an iCalendar UTC date-time.
+///
+/// The alarm is acknowledged and, in the RFC 9074 form, a snooze `VALARM` firing at `until`
+/// replaces any earlier snooze of the same alarm.
+///
+/// # Errors
+///
+/// Returns an error when `until` is not a UTC date-time, the payload is not valid iCalendar,
+/// contains no `VEVENT`/`VTODO`, or has no alarm at `index`.
+pub fn snooze_alarm(ics: &str, index: usize, until: &str, quirks: AlarmQuirks) -> Result<String> {
+    if parse_utc_datetime(until).is_none() || !until.ends_with('Z') {
+        return Err(anyhow!("invalid snooze time {until:?}"));
+    }
+    let now = now_utc();
+    edit_master(ics, |component| {
+        let position = alarm_position(component, index)?;
+        if quirks == AlarmQuirks::Mozilla {
+            component.set_value("X-MOZ-LASTACK", now.clone());
+            component.set_value("X-MOZ-SNOOZE-TIME", until);
+            component.set_value("DTSTAMP", now.clone());
+            return Ok(());
+        }
+
+        let alarm = &mut component.components[position];
+        let uid = match alarm_uid(alarm) {
+            Some(uid) => uid,
+            None => {
+                let uid = generate_uid();
+                alarm.set_value("UID", uid.clone());
+                uid
+            }
+        };
+        mirror_alarm_uid(alarm, quirks);
+        alarm.set_value("ACKNOWLEDGED", now.clone());
+
+        let mut snooze = Component::new("VALARM");
+        snooze.set_value("UID", generate_uid());
+        let mut related = ContentLine::new("RELATED-TO", uid.clone());
+        related.set_param("RELTYPE", "SNOOZE");
+        snooze.properties.push(related);
+        for name in ["ACTION", "DESCRIPTION", "SUMMARY"] {
+            if let Some(line) = alarm.property(name) {
+                snooze.properties.push(line.clone());
+            }
+        }
+        let mut trigger = ContentLine::new("TRIGGER", until);
+        trigger.set_param("VALUE", "DATE-TIME");
+        snooze.properties.push(trigger);
+        mirror_alarm_uid(&mut snooze, quirks);
+
+        component
+            .components
+            .retain(|c| !(c.name.eq_ignore_ascii_case("VALARM") && is_snooze_of(c, &uid)));
+        component.components.push(snooze);
+        component.set_value("DTSTAMP", now.clone());
+        Ok(())
+    })
+}
+
+fn edit_master(ics: &str, edit: impl FnOnce(&mut Component) -> Result<()>) -> Result<String> {
+    let mut calendars = Component::parse_all(ics)?;
+    let master = calendars
+        .iter_mut()
+        .flat_map(|c| c.components.iter_mut())
+        .filter(|c| c.name.eq_ignore_ascii_case("VEVENT") || c.name.eq_ignore_ascii_case("VTODO"))
+        .min_by_key(|c| c.property("RECURRENCE-ID").is_some())
+        .ok_or_else(|| anyhow!("calendar object contains no VEVENT or VTODO"))?;
+    edit(master)?;
+    Ok(calendars.iter().map(Component::to_ics).collect())
+}
+
+fn alarm_position(component: &Component, index: usize) -> Result<usize> {
+    component
+        .components
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.name.eq_ignore_ascii_case("VALARM"))
+        .nth(index)
+        .map(|(position, _)| position)
+        .ok_or_else(|| anyhow!("no alarm at index {index}"))
+}
+
+fn alarm_uid(alarm: &Component) -> Option<String> {
+    alarm
+        .value("UID")
+        .or_else(|| alarm.value("X-WR-ALARMUID"))
+        .map(str::to_string)
+}
+
+fn is_snooze_of(alarm: &Component, uid: &str) -> bool {
+    alarm.properties_named("RELATED-TO").any(|line| {
+        line.value == uid
+            && line
+                .param("RELTYPE")
+                .is_some_and(|t| t.eq_ignore_ascii_case("SNOOZE"))
+    })
+}
+
+fn mirror_alarm_uid(alarm: &mut Component, quirks: AlarmQuirks) {
+    if quirks == AlarmQuirks::Apple
+        && let Some(uid) = alarm_uid(alarm)
+    {
+        alarm.set_value("X-WR-ALARMUID", uid);
+    }
+}
+
+fn now_utc() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    format_utc_datetime(now)
+}
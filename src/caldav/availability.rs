@@ -0,0 +1,128 @@
+//! Parsing and building of `VAVAILABILITY` components (RFC 7953), as stored in the
+//! `CALDAV:calendar-availability` property of a schedule inbox.
+
+use anyhow::{Result, anyhow};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::caldav::types::{Availability, AvailableSlot, FreeBusyType};
+use crate::common::contentline::{Component, ContentLine, escape_text, unescape_text};
+use crate::common::datetime::format_utc_datetime;
+
+/// Parse every `VAVAILABILITY` of an iCalendar payload.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar, or a component lacks its `UID`
+/// or an `AVAILABLE` slot lacks its `DTSTART`.
+pub fn parse_availability(ics: &str) -> Result<Vec<Availability>> {
+    let calendars = Component::parse_all(ics)?;
+    calendars
+        .iter()
+        .flat_map(|c| c.components_named("VAVAILABILITY"))
+        .map(parse_vavailability)
+        .collect()
+}
+
+fn parse_vavailability(component: &Component) -> Result<Availability> {
+    let uid = component
+        .value("UID")
+        .ok_or_else(|| anyhow!("VAVAILABILITY without UID"))?;
+    let mut availability = Availability::new(uid);
+    if let Some(busy_type) = component.value("BUSYTYPE") {
+        availability.busy_type = FreeBusyType::from_param(busy_type);
+    }
+    availability.start = component.value("DTSTART").map(str::to_string);
+    availability.end = component.value("DTEND").map(str::to_string);
+    availability.priority = component
+        .value("PRIORITY")
+        .and_then(|p| p.trim().parse().ok());
+    availability.summary = component.value("SUMMARY").map(unescape_text);
+    for slot in component.components_named("AVAILABLE") {
+        let uid = slot
+            .value("UID")
+            .ok_or_else(|| anyhow!("AVAILABLE without UID"))?;
+        let dtstart = slot
+            .property("DTSTART")
+            .ok_or_else(|| anyhow!("AVAILABLE {uid} without DTSTART"))?;
+        let mut available = AvailableSlot::new(uid, &dtstart.value);
+        available.tzid = dtstart.param("TZID").map(str::to_string);
+        available.end = slot.value("DTEND").map(str::to_string);
+        available.duration = slot.value("DURATION").map(str::to_string);
+        available.rrule = slot.value("RRULE").map(str::to_string);
+        available.summary = slot.value("SUMMARY").map(unescape_text);
+        availability.available.push(available);
+    }
+    Ok(availability)
+}
+
+/// Serialize availability components into a `VCALENDAR` suitable for
+/// [`crate::caldav::CalDavClient::set_calendar_availability`].
+///
+/// `DTSTAMP` is set to the current time. Slots using a `TZID` reference the zone by name
+/// only; servers without time zones by reference (RFC 7809) may also expect a matching
+/// `VTIMEZONE`.
+pub fn build_availability(components: &[Availability]) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let dtstamp = format_utc_datetime(now);
+
+    let mut calendar = Component::new("VCALENDAR");
+    calendar.properties.push(ContentLine::new("VERSION", "2.0"));
+    calendar
+        .properties
+        .push(ContentLine::new("PRODID", "-//fast-dav-rs//EN"));
+    for availability in components {
+        let mut component = Component::new("VAVAILABILITY");
+        component.set_value("UID", availability.uid.clone());
+        component.set_value("DTSTAMP", dtstamp.clone());
+        if availability.busy_type != FreeBusyType::BusyUnavailable {
+            component.set_value("BUSYTYPE", availability.busy_type.as_str());
+        }
+        if let Some(start) = &availability.start {
+            component.set_value("DTSTART", start.clone());
+        }
+        if let Some(end) = &availability.end {
+            component.set_value("DTEND", end.clone());
+        }
+        if let Some(priority) = availability.priority {
+            component.set_value("PRIORITY", priority.to_string());
+        }
+        if let Some(summary) = &availability.summary {
+            component.set_value("SUMMARY", escape_text(summary));
+        }
+        for slot in &availability.available {
+            let mut available = Component::new("AVAILABLE");
+            available.set_value("UID", slot.uid.clone());
+            available.set_value("DTSTAMP", dtstamp.clone());
+            let mut dtstart = ContentLine::new("DTSTART", slot.start.clone());
+            let mut dtend = slot
+                .end
+                .as_ref()
+                .map(|e| ContentLine::new("DTEND", e.clone()));
+            if let Some(tzid) = &slot.tzid {
+                dtstart.set_param("TZID", tzid);
+                if let Some(dtend) = dtend.as_mut() {
+                    dtend.set_param("TZID", tzid);
+                }
+            }
+            available.properties.push(dtstart);
+            available.properties.extend(dtend);
+            if slot.end.is_none()
+                && let Some(duration) = &slot.duration
+            {
+                available.set_value("DURATION", duration.clone());
+            }
+            if let Some(rrule) = &slot.rrule {
+                available.set_value("RRULE", rrule.clone());
+            }
+            if let Some(summary) = &slot.summary {
+                available.set_value("SUMMARY", escape_text(summary));
+            }
+            component.components.push(available);
+        }
+        calendar.components.push(component);
+    }
+    calendar.to_ics()
+}
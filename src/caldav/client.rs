@@ -1,17 +1,71 @@
 use anyhow::{Result, anyhow};
 use bytes::Bytes;
+use futures::{Stream, StreamExt, future, stream};
+use http_body_util::BodyExt;
 use hyper::body::Incoming;
-use hyper::{HeaderMap, Method, Response, Uri, header};
+use hyper::{HeaderMap, Method, Response, StatusCode, Uri, header};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::Duration;
 
-use crate::caldav::streaming::parse_multistatus_bytes;
+use crate::caldav::alarm::{
+    AlarmBuilder, AlarmQuirks, acknowledge_alarm, add_alarm, remove_alarm, snooze_alarm,
+};
+use crate::caldav::availability::{build_availability, parse_availability};
+use crate::caldav::collection::{CalendarPropPatch, MkCalendar, check_supported_components};
+use crate::caldav::export::CalendarMerger;
+use crate::caldav::instance::{delete_instance, update_instance};
+use crate::caldav::journal::{journals_filter, map_journals};
+#[cfg(feature = "query-builder")]
+use crate::caldav::query::CalendarQuery;
+use crate::caldav::query::{CalendarData, CompFilter, uid_filter};
+use crate::caldav::scheduling::{
+    add_attendee, build_free_busy_query_body, build_free_busy_request, itip_method,
+    merge_busy_periods, parse_free_busy, parse_schedule_response, remove_attendee,
+    set_attendee_partstat,
+};
+use crate::caldav::sharing::{
+    build_invite_reply_body, build_share_body, map_shared_calendars, parse_calendar_invite,
+    parse_invite_notification, parse_shared_as,
+};
+use crate::caldav::streaming::{
+    ParseResult, STREAM_READ_IDLE_TIMEOUT, SyncItemStream, decoded_reader,
+    parse_multistatus_bytes_with_limits,
+};
+use crate::caldav::tasks::{
+    complete_todo, incomplete_todos_filter, overdue_todos_filter, todo_is_open,
+};
 use crate::caldav::types::{
-    BatchItem, CalendarInfo, CalendarObject, DavItem, Depth, SyncItem, SyncResponse,
+    AttendeeRole, Availability, BatchItem, CalendarInfo, CalendarInvite, CalendarObject, DavItem,
+    Depth, FreeBusy, Journal, Partstat, PutResult, ScheduleRecipientStatus, ScheduleTransparency,
+    ScheduleUrls, SchedulingObject, ShareInvitation, ShareInvite, SharedCalendar, SyncItem,
+    SyncResponse,
+};
+use crate::caldav::validate::validate_ics;
+use crate::common::compression::{
+    ContentEncoding, DecompressedBody, DecompressionOptions, detect_encodings,
+};
+use crate::common::contentline::Component;
+use crate::common::datetime::format_utc_datetime;
+use crate::sync::{
+    CachedGet, ChangeStream, ConflictPolicy, ConflictResolution, EtagCache, MemoryTokenStore,
+    MutationQueue, ReplayReport, SyncEngine, TokenStore,
 };
-use crate::common::compression::ContentEncoding;
 use crate::webdav::client::WebDavClient;
-use crate::webdav::types::http_status_code;
+use crate::webdav::condition::sync_collection_error;
+use crate::webdav::href::{normalize_href, resolve_href};
+use crate::webdav::lock::IfHeader;
+use crate::webdav::mkcol::ExtendedMkcol;
+use crate::webdav::property::PropertyRegistry;
+use crate::webdav::proppatch::{PropPatch, parse_proppatch_response};
+use crate::webdav::types::{
+    Ace, DavCapabilities, Lock, LockScope, LockTimeout, PrincipalInfo, PropNames, PropPatchResult,
+    PropertyName, PushInfo, PushRegistration, RecursiveReport, ResourceProps, VersionEntry,
+    WebPushSubscription,
+};
+use crate::webdav::types::{ParseLimits, http_status_code};
 use crate::webdav::xml::{validate_component_name, validate_utc_datetime};
 
 pub use crate::webdav::client::RequestCompressionMode;
@@ -31,8 +85,17 @@ pub use crate::webdav::client::RequestCompressionMode;
 #[derive(Clone)]
 pub struct CalDavClient {
     webdav: WebDavClient,
+    multiget_chunk_size: usize,
+    multiget_concurrency: usize,
+    validate_before_put: bool,
+    parse_limits: ParseLimits,
 }
 
+/// Default number of hrefs per `calendar-multiget` REPORT.
+pub const DEFAULT_MULTIGET_CHUNK_SIZE: usize = 100;
+/// Default number of `calendar-multiget` chunks in flight at once.
+pub const DEFAULT_MULTIGET_CONCURRENCY: usize = 4;
+
 impl CalDavClient {
     /// Create a new client from a **base URL** (collection/home-set) and optional **Basic** credentials.
     ///
@@ -76,9 +139,53 @@ impl CalDavClient {
     pub fn new(base_url: &str, basic_user: Option<&str>, basic_pass: Option<&str>) -> Result<Self> {
         Ok(Self {
             webdav: WebDavClient::new(base_url, basic_user, basic_pass)?,
+            multiget_chunk_size: DEFAULT_MULTIGET_CHUNK_SIZE,
+            multiget_concurrency: DEFAULT_MULTIGET_CONCURRENCY,
+            validate_before_put: false,
+            parse_limits: ParseLimits::default(),
         })
     }
 
+    /// Configure how [`calendar_multiget`](Self::calendar_multiget) splits large href lists:
+    /// at most `chunk_size` hrefs per REPORT, with up to `max_concurrency` REPORTs in flight.
+    ///
+    /// Defaults to [`DEFAULT_MULTIGET_CHUNK_SIZE`] and [`DEFAULT_MULTIGET_CONCURRENCY`]; zero
+    /// values are treated as one.
+    pub fn set_multiget_chunking(&mut self, chunk_size: usize, max_concurrency: usize) {
+        self.multiget_chunk_size = chunk_size.max(1);
+        self.multiget_concurrency = max_concurrency.max(1);
+    }
+
+    /// Run [`validate_ics`] on every iCalendar body before the `PUT` helpers send it, so
+    /// malformed objects fail locally with an
+    /// [`IcsValidationError`](crate::caldav::IcsValidationError) instead of an opaque `400`.
+    ///
+    /// Disabled by default.
+    pub fn set_validate_before_put(&mut self, enabled: bool) {
+        self.validate_before_put = enabled;
+    }
+
+    /// Bounds on the XML responses this client parses (element depth, text size, number
+    /// of items), including the WebDAV ones of its inner [`WebDavClient`]; responses
+    /// exceeding them fail with a [`ParseLimitError`](crate::webdav::ParseLimitError).
+    ///
+    /// Defaults to [`ParseLimits::default`].
+    pub fn set_parse_limits(&mut self, limits: ParseLimits) {
+        self.parse_limits = limits;
+        self.webdav.set_parse_limits(limits);
+    }
+
+    pub(crate) fn parse_multistatus(&self, body: &[u8]) -> Result<ParseResult<Vec<DavItem>>> {
+        parse_multistatus_bytes_with_limits(body, self.parse_limits)
+    }
+
+    fn check_before_put(&self, ical_bytes: &Bytes) -> Result<()> {
+        if self.validate_before_put {
+            validate_ics(ical_bytes)?;
+        }
+        Ok(())
+    }
+
     /// Configure request compression for this client.
     ///
     /// # Arguments
@@ -119,6 +226,27 @@ impl CalDavClient {
         self.webdav.disable_request_compression();
     }
 
+    /// Attach an ETag cache.
+    ///
+    /// See [`WebDavClient::set_etag_cache`].
+    pub fn set_etag_cache(&mut self, cache: Arc<dyn EtagCache>) {
+        self.webdav.set_etag_cache(cache);
+    }
+
+    /// Configure how compressed responses are decoded.
+    ///
+    /// See [`WebDavClient::set_decompression_options`].
+    pub fn set_decompression_options(&mut self, options: DecompressionOptions) {
+        self.webdav.set_decompression_options(options);
+    }
+
+    /// Request body size from which request compression runs on a blocking thread.
+    ///
+    /// See [`WebDavClient::set_compression_blocking_threshold`].
+    pub fn set_compression_blocking_threshold(&mut self, threshold: Option<usize>) {
+        self.webdav.set_compression_blocking_threshold(threshold);
+    }
+
     /// Get the current request compression strategy.
     pub fn request_compression_mode(&self) -> RequestCompressionMode {
         self.webdav.request_compression_mode()
@@ -169,7 +297,9 @@ impl CalDavClient {
     /// Generic **streaming send**. Returns a `Response<Incoming>` (not aggregated).
     ///
     /// Use this when you want to parse the response on the fly, e.g. with
-    /// [`crate::streaming::parse_multistatus_stream`].
+    /// [`crate::caldav::parse_multistatus_stream`]. The body is still compressed when the
+    /// server used a `Content-Encoding`: pass [`detect_encodings`] of the headers to the
+    /// parser, or use [`send_stream_decompressed`](Self::send_stream_decompressed).
     pub async fn send_stream(
         &self,
         method: Method,
@@ -183,6 +313,38 @@ impl CalDavClient {
             .await
     }
 
+    /// [`send_stream`](Self::send_stream) with the body decompressed as it is read.
+    ///
+    /// See [`WebDavClient::send_stream_decompressed`].
+    ///
+    /// ```no_run
+    /// # use fast_dav_rs::CalDavClient;
+    /// # use futures::TryStreamExt;
+    /// # use hyper::{HeaderMap, Method};
+    /// # async fn demo(cli: &CalDavClient) -> anyhow::Result<()> {
+    /// let res = cli
+    ///     .send_stream_decompressed(Method::GET, "Calendars/Personal/export.ics", HeaderMap::new(), None, None)
+    ///     .await?;
+    /// let mut body = res.into_body();
+    /// while let Some(chunk) = body.try_next().await? {
+    ///     println!("{} bytes", chunk.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_stream_decompressed(
+        &self,
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+        body_bytes: Option<Bytes>,
+        per_req_timeout: Option<Duration>,
+    ) -> Result<Response<DecompressedBody>> {
+        self.webdav
+            .send_stream_decompressed(method, path, headers, body_bytes, per_req_timeout)
+            .await
+    }
+
     // ----------- HTTP/WebDAV Verbs -----------
 
     /// Send an `OPTIONS` request.
@@ -191,6 +353,12 @@ impl CalDavClient {
     pub async fn options(&self, path: &str) -> Result<Response<Bytes>> {
         self.webdav.options(path).await
     }
+    /// Read the capabilities `path` advertises with `OPTIONS`.
+    ///
+    /// See [`WebDavClient::capabilities`].
+    pub async fn capabilities(&self, path: &str) -> Result<DavCapabilities> {
+        self.webdav.capabilities(path).await
+    }
     /// Send a `HEAD` request.
     ///
     /// Often used to retrieve an `ETag` before a conditional update/delete.
@@ -201,10 +369,76 @@ impl CalDavClient {
     pub async fn get(&self, path: &str) -> Result<Response<Bytes>> {
         self.webdav.get(path).await
     }
+    /// `GET` a calendar object with its `ETag` and `Schedule-Tag`, e.g. before an attendee
+    /// update with [`put_if_schedule_tag_match`](Self::put_if_schedule_tag_match).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, the response is not successful or the body is
+    /// not valid UTF-8.
+    pub async fn get_scheduling_object(&self, path: &str) -> Result<SchedulingObject> {
+        let resp = self.get(path).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("GET {path} failed with {}", resp.status()));
+        }
+        Ok(SchedulingObject {
+            etag: Self::etag_from_headers(resp.headers()),
+            schedule_tag: Self::schedule_tag_from_headers(resp.headers()),
+            calendar_data: String::from_utf8(resp.into_body().to_vec())
+                .map_err(|e| anyhow!("calendar object {path} is not valid UTF-8: {e}"))?,
+        })
+    }
+    /// `GET` skipping the download of a resource unchanged since it was cached.
+    ///
+    /// See [`WebDavClient::get_cached`].
+    pub async fn get_cached(&self, path: &str) -> Result<CachedGet> {
+        self.webdav.get_cached(path).await
+    }
+    /// `PUT` of a iCalendar body guarded by the cached ETag of `path`, if any.
+    ///
+    /// See [`WebDavClient::put_cached`].
+    pub async fn put_cached(&self, path: &str, ical_bytes: Bytes) -> Result<Response<Bytes>> {
+        self.check_before_put(&ical_bytes)?;
+        self.webdav
+            .put_cached(path, "text/calendar; charset=utf-8", ical_bytes)
+            .await
+    }
+    /// `DELETE` guarded by the cached ETag of `path`, if any.
+    ///
+    /// See [`WebDavClient::delete_cached`].
+    pub async fn delete_cached(&self, path: &str) -> Result<Response<Bytes>> {
+        self.webdav.delete_cached(path).await
+    }
+    /// Settle a conditional write of the iCalendar body `ical_bytes` rejected with
+    /// `412 Precondition Failed`, according to `policy`.
+    ///
+    /// See [`WebDavClient::resolve_conflict`].
+    pub async fn resolve_conflict(
+        &self,
+        path: &str,
+        ical_bytes: Bytes,
+        policy: &ConflictPolicy,
+    ) -> Result<ConflictResolution> {
+        self.check_before_put(&ical_bytes)?;
+        self.webdav
+            .resolve_conflict(path, "text/calendar; charset=utf-8", ical_bytes, policy)
+            .await
+    }
+    /// Replay the `PUT`/`DELETE` mutations recorded in `queue` while offline.
+    ///
+    /// See [`WebDavClient::replay_mutations`].
+    pub async fn replay_mutations<Q: MutationQueue + ?Sized>(
+        &self,
+        queue: &Q,
+        policy: &ConflictPolicy,
+    ) -> Result<ReplayReport> {
+        self.webdav.replay_mutations(queue, policy).await
+    }
     /// Send a `PUT` with an iCalendar body (`text/calendar`).
     ///
     /// Use [`put_if_match`] or [`put_if_none_match`] for safer conditional writes.
     pub async fn put(&self, path: &str, ical_bytes: Bytes) -> Result<Response<Bytes>> {
+        self.check_before_put(&ical_bytes)?;
         let mut h = HeaderMap::new();
         h.insert(
             header::CONTENT_TYPE,
@@ -213,6 +447,29 @@ impl CalDavClient {
         self.send(Method::PUT, path, h, Some(ical_bytes), None)
             .await
     }
+    /// `PUT` an iCalendar object into `calendar` after checking its component types against
+    /// the calendar's `supported-calendar-component-set` (see [`check_supported_components`]).
+    ///
+    /// `calendar` is typically cached from [`list_calendars`](Self::list_calendars); `path`
+    /// is the object's resource path inside it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`UnsupportedComponentError`](crate::caldav::UnsupportedComponentError)
+    /// **before any network I/O** when the calendar does not accept the object, instead of
+    /// the server's `403`; otherwise as [`put`](Self::put).
+    pub async fn put_checked(
+        &self,
+        calendar: &CalendarInfo,
+        path: &str,
+        ical_bytes: Bytes,
+    ) -> Result<Response<Bytes>> {
+        let ics = std::str::from_utf8(&ical_bytes)
+            .map_err(|e| anyhow!("calendar object is not valid UTF-8: {e}"))?;
+        check_supported_components(ics, calendar)?;
+        self.put(path, ical_bytes).await
+    }
+
     /// Conditional `PUT` guarded by `If-Match`.
     ///
     /// The write only succeeds if the current resource ETag matches.
@@ -239,6 +496,7 @@ impl CalDavClient {
         if etag.is_empty() {
             return Err(anyhow!("ETag cannot be empty"));
         }
+        self.check_before_put(&ical_bytes)?;
 
         let mut h = HeaderMap::new();
         h.insert(
@@ -249,6 +507,45 @@ impl CalDavClient {
         self.send(Method::PUT, path, h, Some(ical_bytes), None)
             .await
     }
+    /// Conditional `PUT` guarded by `If-Schedule-Tag-Match` (RFC 6638 §3.2).
+    ///
+    /// Attendee-side updates (e.g. changing `PARTSTAT`) should use this instead of
+    /// [`put_if_match`](Self::put_if_match): the `Schedule-Tag` only changes when the organizer
+    /// modifies the event, so the write succeeds across server-side bookkeeping updates that
+    /// bump the ETag but fails if the organizer changed the event in the meantime.
+    ///
+    /// Returns the new `ETag` and `Schedule-Tag` of the object, when the server sent them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schedule tag is empty or contains invalid header characters,
+    /// if the request fails, or if the response is not successful (`412 Precondition Failed`
+    /// when the organizer changed the event).
+    pub async fn put_if_schedule_tag_match(
+        &self,
+        path: &str,
+        ical_bytes: Bytes,
+        schedule_tag: &str,
+    ) -> Result<PutResult> {
+        if schedule_tag.is_empty() {
+            return Err(anyhow!("Schedule-Tag cannot be empty"));
+        }
+        self.check_before_put(&ical_bytes)?;
+
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("text/calendar; charset=utf-8"),
+        );
+        h.insert(
+            header::HeaderName::from_static("if-schedule-tag-match"),
+            header::HeaderValue::from_str(schedule_tag)?,
+        );
+        let resp = self
+            .send(Method::PUT, path, h, Some(ical_bytes), None)
+            .await?;
+        Self::put_result(path, &resp)
+    }
     /// Create-only `PUT` guarded by `If-None-Match: *`.
     ///
     /// Fails if the resource already exists.
@@ -257,6 +554,7 @@ impl CalDavClient {
         path: &str,
         ical_bytes: Bytes,
     ) -> Result<Response<Bytes>> {
+        self.check_before_put(&ical_bytes)?;
         let mut h = HeaderMap::new();
         h.insert(
             header::CONTENT_TYPE,
@@ -266,6 +564,24 @@ impl CalDavClient {
         self.send(Method::PUT, path, h, Some(ical_bytes), None)
             .await
     }
+    /// `PUT` submitting an [`IfHeader`], e.g. the lock token of a locked resource combined
+    /// with its expected ETag.
+    pub async fn put_if(
+        &self,
+        path: &str,
+        ical_bytes: Bytes,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        self.check_before_put(&ical_bytes)?;
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("text/calendar; charset=utf-8"),
+        );
+        h.insert("If", header::HeaderValue::from_str(&if_header.build()?)?);
+        self.send(Method::PUT, path, h, Some(ical_bytes), None)
+            .await
+    }
     /// Send a `DELETE` request.
     ///
     /// Prefer [`delete_if_match`] when you want to ensure you delete the expected version.
@@ -304,6 +620,62 @@ impl CalDavClient {
             .r#move(src_path, dest_absolute_url, overwrite)
             .await
     }
+    /// `COPY` submitting an [`IfHeader`].
+    ///
+    /// See [`WebDavClient::copy_if`].
+    pub async fn copy_if(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        self.webdav
+            .copy_if(src_path, dest_absolute_url, overwrite, if_header)
+            .await
+    }
+    /// `MOVE` submitting an [`IfHeader`].
+    ///
+    /// See [`WebDavClient::move_if`].
+    pub async fn move_if(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        self.webdav
+            .move_if(src_path, dest_absolute_url, overwrite, if_header)
+            .await
+    }
+    /// `DELETE` submitting an [`IfHeader`].
+    pub async fn delete_if(&self, path: &str, if_header: &IfHeader) -> Result<Response<Bytes>> {
+        self.webdav.delete_if(path, if_header).await
+    }
+    /// `DELETE` a collection tree, retrying the members the server could not delete.
+    ///
+    /// See [`WebDavClient::delete_recursive`].
+    pub async fn delete_recursive(
+        &self,
+        path: &str,
+        max_retries: usize,
+    ) -> Result<RecursiveReport> {
+        self.webdav.delete_recursive(path, max_retries).await
+    }
+    /// `COPY` a collection tree, retrying the members the server could not copy.
+    ///
+    /// See [`WebDavClient::copy_recursive`].
+    pub async fn copy_recursive(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        max_retries: usize,
+    ) -> Result<RecursiveReport> {
+        self.webdav
+            .copy_recursive(src_path, dest_absolute_url, overwrite, max_retries)
+            .await
+    }
 
     /// Send a WebDAV `PROPFIND` with a custom XML body and `Depth` header.
     ///
@@ -317,10 +689,79 @@ impl CalDavClient {
     ) -> Result<Response<Bytes>> {
         self.webdav.propfind(path, depth, xml_body).await
     }
+    /// `PROPFIND` with `<D:allprop/>`, also requesting the `include` properties.
+    ///
+    /// See [`WebDavClient::propfind_allprop`].
+    pub async fn propfind_allprop(
+        &self,
+        path: &str,
+        depth: Depth,
+        include: &[PropertyName],
+    ) -> Result<Response<Bytes>> {
+        self.webdav.propfind_allprop(path, depth, include).await
+    }
+    /// List the names of the properties exposed by `path`.
+    ///
+    /// See [`WebDavClient::propfind_propname`].
+    pub async fn propfind_propname(&self, path: &str, depth: Depth) -> Result<Vec<PropNames>> {
+        self.webdav.propfind_propname(path, depth).await
+    }
+    /// `PROPFIND` the properties of `registry`, including vendor properties.
+    ///
+    /// See [`WebDavClient::propfind_properties`].
+    pub async fn propfind_properties(
+        &self,
+        path: &str,
+        depth: Depth,
+        registry: &PropertyRegistry,
+    ) -> Result<Vec<ResourceProps>> {
+        self.webdav.propfind_properties(path, depth, registry).await
+    }
+    /// `PROPFIND` the whole tree below `path`, falling back to a `Depth: 1` crawl when the
+    /// server forbids `Depth: infinity`.
+    ///
+    /// See [`WebDavClient::propfind_infinity`].
+    pub async fn propfind_infinity(&self, path: &str, xml_body: &str) -> Result<Vec<Bytes>> {
+        self.webdav.propfind_infinity(path, xml_body).await
+    }
     /// Send a WebDAV `PROPPATCH` with a custom XML body.
     pub async fn proppatch(&self, path: &str, xml_body: &str) -> Result<Response<Bytes>> {
         self.webdav.proppatch(path, xml_body).await
     }
+    /// Set and remove arbitrary properties of `path` with a [`PropPatch`].
+    ///
+    /// See [`WebDavClient::proppatch_properties`].
+    pub async fn proppatch_properties(
+        &self,
+        path: &str,
+        patch: &PropPatch,
+    ) -> Result<PropPatchResult> {
+        self.webdav.proppatch_properties(path, patch).await
+    }
+
+    /// Update calendar properties with a typed [`CalendarPropPatch`] and report the outcome
+    /// of every property.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if the patch is empty, and afterwards if
+    /// the request fails, the server responds with a non-success status, or the
+    /// `207 Multi-Status` body cannot be parsed. Rejected properties are not an error; check
+    /// [`PropPatchResult::failed`].
+    pub async fn proppatch_calendar(
+        &self,
+        path: &str,
+        patch: &CalendarPropPatch,
+    ) -> Result<PropPatchResult> {
+        if patch.is_empty() {
+            return Err(anyhow!("PROPPATCH requires at least one property"));
+        }
+        let resp = self.proppatch(path, &patch.build()).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("PROPPATCH failed with {}", resp.status()));
+        }
+        parse_proppatch_response(resp.body())
+    }
     /// Send a CalDAV `REPORT` (e.g. `calendar-query`) with a custom XML body and `Depth`.
     ///
     /// This is the primary way to query events with time ranges.
@@ -348,10 +789,55 @@ impl CalDavClient {
         )
         .await
     }
+    /// Create a calendar collection from a typed [`MkCalendar`] description.
+    ///
+    /// Sends `MKCALENDAR`; if the server answers `501 Not Implemented` or
+    /// `405 Method Not Allowed`, retries with the equivalent extended `MKCOL` (RFC 5689).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if the description is invalid (see
+    /// [`MkCalendar::build`]), and otherwise only for transport failures; inspect the returned
+    /// status for the outcome.
+    pub async fn create_calendar(
+        &self,
+        path: &str,
+        calendar: &MkCalendar,
+    ) -> Result<Response<Bytes>> {
+        let body = calendar.build()?;
+        let fallback_body = calendar.build_extended_mkcol()?;
+
+        let resp = self.mkcalendar(path, &body).await?;
+        if resp.status() == StatusCode::NOT_IMPLEMENTED
+            || resp.status() == StatusCode::METHOD_NOT_ALLOWED
+        {
+            let mut h = HeaderMap::new();
+            h.insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("application/xml; charset=utf-8"),
+            );
+            return self
+                .send(
+                    Method::from_bytes(b"MKCOL")?,
+                    path,
+                    h,
+                    Some(Bytes::from(fallback_body)),
+                    None,
+                )
+                .await;
+        }
+        Ok(resp)
+    }
     /// Send a WebDAV `MKCOL` to create a generic collection. Some servers accept an optional XML body.
     pub async fn mkcol(&self, path: &str, xml_body: Option<&str>) -> Result<Response<Bytes>> {
         self.webdav.mkcol(path, xml_body).await
     }
+    /// Create a collection with an extended `MKCOL` (RFC 5689).
+    ///
+    /// See [`WebDavClient::mkcol_extended`].
+    pub async fn mkcol_extended(&self, path: &str, mkcol: &ExtendedMkcol) -> Result<()> {
+        self.webdav.mkcol_extended(path, mkcol).await
+    }
 
     /// Discover the current user's principal URL via `current-user-principal`.
     ///
@@ -373,7 +859,7 @@ impl CalDavClient {
         }
         let body = resp.into_body();
         let mut principal = None;
-        for item in parse_multistatus_bytes(&body)?.items {
+        for item in self.parse_multistatus(&body)?.items {
             if let Some(found) = item
                 .current_user_principal
                 .into_iter()
@@ -404,7 +890,7 @@ impl CalDavClient {
         }
         let body = resp.into_body();
         let mut homes = Vec::new();
-        for mut item in parse_multistatus_bytes(&body)?.items {
+        for mut item in self.parse_multistatus(&body)?.items {
             homes.append(&mut item.calendar_home_set);
         }
         homes.sort();
@@ -412,81 +898,1147 @@ impl CalDavClient {
         Ok(homes)
     }
 
-    /// List CalDAV collections under a calendar home-set (`Depth: 1` PROPFIND).
-    pub async fn list_calendars(&self, home_set_path: &str) -> Result<Vec<CalendarInfo>> {
+    /// Discover the scheduling inbox and outbox (RFC 6638) for the provided principal path.
+    ///
+    /// Both URLs are `None` when the server does not support CalDAV scheduling. The outbox is
+    /// the target of iTIP `POST` requests (free-busy lookups, invitations), and the inbox is
+    /// where the server delivers incoming scheduling messages.
+    pub async fn discover_schedule_urls(&self, principal_path: &str) -> Result<ScheduleUrls> {
         let body = r#"
-<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav" xmlns:A="http://apple.com/ns/ical/">
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
   <D:prop>
-    <D:displayname/>
-    <C:calendar-description/>
-    <C:calendar-timezone/>
-    <C:calendar-color/>
-    <A:calendar-color/>
-    <C:supported-calendar-component-set/>
-    <D:getetag/>
-    <D:resourcetype/>
-    <D:sync-token/>
+    <C:schedule-inbox-URL/>
+    <C:schedule-outbox-URL/>
   </D:prop>
 </D:propfind>
 "#;
-        let resp = self.propfind(home_set_path, Depth::One, body).await?;
+        let resp = self.propfind(principal_path, Depth::Zero, body).await?;
         if !resp.status().is_success() {
-            return Err(anyhow!("PROPFIND calendars failed with {}", resp.status()));
+            return Err(anyhow!(
+                "PROPFIND schedule-inbox-URL/schedule-outbox-URL failed with {}",
+                resp.status()
+            ));
         }
         let body = resp.into_body();
-        Ok(map_calendar_list(parse_multistatus_bytes(&body)?.items))
+        Ok(map_schedule_urls(self.parse_multistatus(&body)?.items))
     }
 
-    /// Execute a CalDAV `calendar-query` with an optional time-range filter.
-    ///
-    /// `component` should be `VEVENT`, `VTODO`, … while `start`/`end` are ISO-8601
-    /// timestamps in the format required by CalDAV (e.g. `20240101T000000Z`).
-    ///
-    /// Inputs are validated before any request is sent, and additionally
-    /// XML-escaped when the request body is built (defense in depth).
+    /// Read the `CALDAV:calendar-availability` property (RFC 7953 §7.2.4) of a schedule
+    /// inbox, e.g. the owner's working hours. Returns an empty list when it is not set.
     ///
     /// # Errors
     ///
-    /// Returns an error **before any network I/O** if:
-    /// - `component` is empty or contains characters outside ASCII
-    ///   alphanumerics and `-` (e.g. `VEVENT` and `X-CUSTOM` are valid)
-    /// - `start` or `end` is provided but is not a structurally valid
-    ///   iCalendar UTC date-time (`YYYYMMDDTHHMMSSZ`)
-    ///
-    /// Also returns an error if the REPORT request fails or the server
-    /// responds with a non-success status.
-    pub async fn calendar_query_timerange(
-        &self,
-        calendar_path: &str,
-        component: &str,
-        start: Option<&str>,
-        end: Option<&str>,
-        include_data: bool,
-    ) -> Result<Vec<CalendarObject>> {
-        validate_component_name(component)
-            .map_err(|e| anyhow!("invalid calendar-query component: {e}"))?;
-        if let Some(s) = start {
-            validate_utc_datetime(s).map_err(|e| anyhow!("invalid calendar-query start: {e}"))?;
-        }
-        if let Some(e) = end {
-            validate_utc_datetime(e).map_err(|e| anyhow!("invalid calendar-query end: {e}"))?;
-        }
-
-        let xml = build_calendar_query_body(component, start, end, include_data);
-
-        let resp = self.report(calendar_path, Depth::One, &xml).await?;
+    /// Returns an error if the PROPFIND fails or is not successful, or the stored iCalendar
+    /// cannot be parsed.
+    pub async fn calendar_availability(&self, inbox_path: &str) -> Result<Vec<Availability>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:calendar-availability/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(inbox_path, Depth::Zero, body).await?;
         if !resp.status().is_success() {
             return Err(anyhow!(
-                "REPORT calendar-query failed with {}",
+                "PROPFIND calendar-availability failed with {}",
                 resp.status()
             ));
         }
         let body = resp.into_body();
-        Ok(map_calendar_objects(parse_multistatus_bytes(&body)?.items))
+        let mut availability = Vec::new();
+        for item in self.parse_multistatus(&body)?.items {
+            if let Some(ics) = item.calendar_availability {
+                availability.extend(parse_availability(&ics)?);
+            }
+        }
+        Ok(availability)
     }
 
-    /// Fetch specific calendar objects via `calendar-multiget`.
-    pub async fn calendar_multiget<I, S>(
+    /// Publish `availability` as the `CALDAV:calendar-availability` property of a schedule
+    /// inbox, replacing the previous value. See [`build_availability`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PROPPATCH fails, is not successful, or the `207 Multi-Status`
+    /// cannot be parsed; inspect the result for per-property failures.
+    pub async fn set_calendar_availability(
+        &self,
+        inbox_path: &str,
+        availability: &[Availability],
+    ) -> Result<PropPatchResult> {
+        let body = format!(
+            r#"<D:propertyupdate xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav"><D:set><D:prop><C:calendar-availability>{}</C:calendar-availability></D:prop></D:set></D:propertyupdate>"#,
+            escape_xml(&build_availability(availability))
+        );
+        let resp = self.proppatch(inbox_path, &body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("PROPPATCH failed with {}", resp.status()));
+        }
+        parse_proppatch_response(resp.body())
+    }
+
+    /// Read the `CALDAV:schedule-default-calendar-URL` of a schedule inbox (RFC 6638 §9.2):
+    /// the calendar where the server files new invitations. `None` when it is not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PROPFIND fails or is not successful.
+    pub async fn schedule_default_calendar(&self, inbox_path: &str) -> Result<Option<String>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:schedule-default-calendar-URL/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(inbox_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND schedule-default-calendar-URL failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(self
+            .parse_multistatus(&body)?
+            .items
+            .into_iter()
+            .find_map(|item| item.schedule_default_calendar_url))
+    }
+
+    /// Point the `CALDAV:schedule-default-calendar-URL` of a schedule inbox at
+    /// `calendar_href`, e.g. after provisioning a new calendar.
+    ///
+    /// Servers only accept calendars of the same home that support `VEVENT`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PROPPATCH fails, is not successful, or the `207 Multi-Status`
+    /// cannot be parsed; inspect the result for per-property failures.
+    pub async fn set_schedule_default_calendar(
+        &self,
+        inbox_path: &str,
+        calendar_href: &str,
+    ) -> Result<PropPatchResult> {
+        let body = format!(
+            r#"<D:propertyupdate xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav"><D:set><D:prop><C:schedule-default-calendar-URL><D:href>{}</D:href></C:schedule-default-calendar-URL></D:prop></D:set></D:propertyupdate>"#,
+            escape_xml(calendar_href)
+        );
+        let resp = self.proppatch(inbox_path, &body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("PROPPATCH failed with {}", resp.status()));
+        }
+        parse_proppatch_response(resp.body())
+    }
+
+    /// Read the `CALDAV:schedule-calendar-transp` of a calendar (RFC 6638 §9.1). `None` when
+    /// the server does not report it, which RFC 6638 treats as opaque.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PROPFIND fails or is not successful.
+    pub async fn schedule_calendar_transp(
+        &self,
+        calendar_path: &str,
+    ) -> Result<Option<ScheduleTransparency>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:schedule-calendar-transp/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(calendar_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND schedule-calendar-transp failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(self
+            .parse_multistatus(&body)?
+            .items
+            .into_iter()
+            .find_map(|item| item.schedule_calendar_transp))
+    }
+
+    /// Set the `CALDAV:schedule-calendar-transp` of a calendar, i.e. whether its events
+    /// block time in free-busy lookups.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PROPPATCH fails, is not successful, or the `207 Multi-Status`
+    /// cannot be parsed.
+    pub async fn set_schedule_calendar_transp(
+        &self,
+        calendar_path: &str,
+        transparency: ScheduleTransparency,
+    ) -> Result<PropPatchResult> {
+        self.proppatch_calendar(
+            calendar_path,
+            &CalendarPropPatch::new().transparency(transparency),
+        )
+        .await
+    }
+
+    /// Share the calendar at `calendar_path` (calendarserver-sharing), inviting `invites`.
+    ///
+    /// Re-inviting an existing sharee updates its access. Check the outcome with
+    /// [`calendar_invite`](Self::calendar_invite).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `POST` fails or is not successful, e.g. when the server does
+    /// not support sharing.
+    pub async fn share_calendar(&self, calendar_path: &str, invites: &[ShareInvite]) -> Result<()> {
+        self.sharing_post(calendar_path, build_share_body::<&str>(invites, &[]))
+            .await
+            .map(|_| ())
+    }
+
+    /// Revoke the share of `calendar_path` for the sharees `hrefs`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `POST` fails or is not successful.
+    pub async fn unshare_calendar<I, S>(&self, calendar_path: &str, hrefs: I) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let hrefs: Vec<S> = hrefs.into_iter().collect();
+        self.sharing_post(calendar_path, build_share_body(&[], &hrefs))
+            .await
+            .map(|_| ())
+    }
+
+    /// Read the `CS:invite` property of a calendar: its owner and sharees with their access
+    /// and reply status.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PROPFIND fails or is not successful, or the response is not
+    /// well-formed XML.
+    pub async fn calendar_invite(&self, calendar_path: &str) -> Result<CalendarInvite> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:CS="http://calendarserver.org/ns/">
+  <D:prop>
+    <CS:invite/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(calendar_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("PROPFIND invite failed with {}", resp.status()));
+        }
+        parse_calendar_invite(resp.body())
+    }
+
+    /// Discover the notification collection (`CS:notification-URL`) of a principal, where
+    /// share invitations are delivered.
+    pub async fn discover_notification_url(&self, principal_path: &str) -> Result<Option<String>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:CS="http://calendarserver.org/ns/">
+  <D:prop>
+    <CS:notification-URL/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(principal_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND notification-URL failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(self
+            .parse_multistatus(&body)?
+            .items
+            .into_iter()
+            .find_map(|item| item.notification_url))
+    }
+
+    /// List the share invitations in a notification collection (see
+    /// [`discover_notification_url`](Self::discover_notification_url)).
+    ///
+    /// Each notification is fetched with `GET`; other notification types are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request fails or is not successful, or a notification is not
+    /// well-formed XML.
+    pub async fn share_invitations(&self, notification_path: &str) -> Result<Vec<ShareInvitation>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:">
+  <D:prop>
+    <D:resourcetype/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(notification_path, Depth::One, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND notifications failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        let mut invitations = Vec::new();
+        for item in self.parse_multistatus(&body)?.items {
+            if item.is_collection {
+                continue;
+            }
+            let resp = self.get(&item.href).await?;
+            if !resp.status().is_success() {
+                return Err(anyhow!("GET {} failed with {}", item.href, resp.status()));
+            }
+            invitations.extend(parse_invite_notification(&item.href, resp.body())?);
+        }
+        Ok(invitations)
+    }
+
+    /// Accept or decline a share invitation by posting a `CS:invite-reply` to the sharee's
+    /// calendar home.
+    ///
+    /// Returns where the shared calendar was mounted (`CS:shared-as`) when the server reports
+    /// it for an accepted invitation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `POST` fails or is not successful, or its response is not
+    /// well-formed XML.
+    pub async fn reply_to_share(
+        &self,
+        home_set_path: &str,
+        invitation: &ShareInvitation,
+        accept: bool,
+    ) -> Result<Option<String>> {
+        let resp = self
+            .sharing_post(home_set_path, build_invite_reply_body(invitation, accept))
+            .await?;
+        if resp.body().is_empty() {
+            return Ok(None);
+        }
+        parse_shared_as(resp.body())
+    }
+
+    /// List the calendars shared with the current user in `home_set_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PROPFIND fails or is not successful, or the response cannot
+    /// be parsed.
+    pub async fn shared_calendars(&self, home_set_path: &str) -> Result<Vec<SharedCalendar>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:CS="http://calendarserver.org/ns/" xmlns:A="http://apple.com/ns/ical/">
+  <D:prop>
+    <D:displayname/>
+    <D:resourcetype/>
+    <D:owner/>
+    <A:calendar-color/>
+    <CS:shared-url/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(home_set_path, Depth::One, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND shared calendars failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(map_shared_calendars(self.parse_multistatus(&body)?.items))
+    }
+
+    async fn sharing_post(&self, path: &str, body: String) -> Result<Response<Bytes>> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/xml; charset=utf-8"),
+        );
+        let resp = self
+            .send(Method::POST, path, h, Some(Bytes::from(body)), None)
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("sharing POST failed with {}", resp.status()));
+        }
+        Ok(resp)
+    }
+
+    /// Discover the calendar user addresses (`CALDAV:calendar-user-address-set`, RFC 6638 §2.4.1)
+    /// of the provided principal, e.g. `mailto:jane@example.com`.
+    pub async fn discover_calendar_user_addresses(
+        &self,
+        principal_path: &str,
+    ) -> Result<Vec<String>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:calendar-user-address-set/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(principal_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND calendar-user-address-set failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        let mut addresses = Vec::new();
+        for mut item in self.parse_multistatus(&body)?.items {
+            addresses.append(&mut item.calendar_user_address_set);
+        }
+        addresses.dedup();
+        Ok(addresses)
+    }
+
+    /// Reply to a scheduling invitation as the current user.
+    ///
+    /// Discovers the current user's calendar user addresses and delegates to
+    /// [`respond_to_invitation_as`](Self::respond_to_invitation_as).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fast_dav_rs::CalDavClient;
+    /// use fast_dav_rs::caldav::Partstat;
+    ///
+    /// # async fn demo(cli: &CalDavClient) -> anyhow::Result<()> {
+    /// let written = cli
+    ///     .respond_to_invitation("calendars/jane/default/meeting.ics", Partstat::Accepted)
+    ///     .await?;
+    /// println!("new Schedule-Tag: {:?}", written.schedule_tag);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the principal or its addresses cannot be discovered, and otherwise
+    /// as [`respond_to_invitation_as`](Self::respond_to_invitation_as).
+    pub async fn respond_to_invitation(&self, href: &str, partstat: Partstat) -> Result<PutResult> {
+        let principal = self
+            .discover_current_user_principal()
+            .await?
+            .ok_or_else(|| anyhow!("server did not return a current-user-principal"))?;
+        let addresses = self.discover_calendar_user_addresses(&principal).await?;
+        if addresses.is_empty() {
+            return Err(anyhow!(
+                "principal {principal} has no calendar-user-address-set"
+            ));
+        }
+        self.respond_to_invitation_as(href, &addresses, partstat)
+            .await
+    }
+
+    /// Set the `PARTSTAT` of the attendee matching `addresses` on the scheduling object at
+    /// `href` and write it back.
+    ///
+    /// The object is fetched with `GET`, rewritten with [`set_attendee_partstat`], and stored
+    /// with [`put_if_schedule_tag_match`](Self::put_if_schedule_tag_match) when the server sent
+    /// a `Schedule-Tag` (falling back to `If-Match` on the ETag), so a concurrent organizer
+    /// change surfaces as `412 Precondition Failed` instead of being overwritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` or `PUT` fails or is not successful, the object is not
+    /// valid iCalendar, or no `ATTENDEE` matches `addresses`.
+    pub async fn respond_to_invitation_as<I, S>(
+        &self,
+        href: &str,
+        addresses: I,
+        partstat: Partstat,
+    ) -> Result<PutResult>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let object = self.get_scheduling_object(href).await?;
+        let updated = set_attendee_partstat(&object.calendar_data, addresses, partstat)?;
+        let body = Bytes::from(updated);
+
+        let resp = match (object.schedule_tag, object.etag) {
+            (Some(tag), _) => return self.put_if_schedule_tag_match(href, body, &tag).await,
+            (None, Some(etag)) => self.put_if_match(href, body, &etag).await?,
+            (None, None) => self.put(href, body).await?,
+        };
+        Self::put_result(href, &resp)
+    }
+
+    /// Invite `address` with `role` to the event at `href`, as its organizer.
+    ///
+    /// The object is fetched with `GET`, rewritten with [`add_attendee`] (which bumps
+    /// `SEQUENCE`) and stored with `If-Match` on the returned ETag; the server then sends the
+    /// invitation (RFC 6638 implicit scheduling).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` or `PUT` fails or is not successful (a concurrent edit
+    /// shows up as `412`), or the object is not valid iCalendar.
+    pub async fn add_attendee(
+        &self,
+        href: &str,
+        address: &str,
+        role: AttendeeRole,
+    ) -> Result<PutResult> {
+        self.rewrite_scheduling_object(href, |ics| add_attendee(ics, address, role))
+            .await
+    }
+
+    /// Remove the attendee matching `address` from the event at `href`, as its organizer.
+    ///
+    /// Works like [`add_attendee`](Self::add_attendee) with [`remove_attendee`]; the server
+    /// sends the removed attendee a cancellation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` or `PUT` fails or is not successful, the object is not
+    /// valid iCalendar, or no `ATTENDEE` matches `address`.
+    pub async fn remove_attendee(&self, href: &str, address: &str) -> Result<PutResult> {
+        self.rewrite_scheduling_object(href, |ics| remove_attendee(ics, address))
+            .await
+    }
+
+    async fn rewrite_scheduling_object(
+        &self,
+        href: &str,
+        rewrite: impl FnOnce(&str) -> Result<String>,
+    ) -> Result<PutResult> {
+        let resp = self.rewrite_object(href, rewrite).await?;
+        Self::put_result(href, &resp)
+    }
+
+    /// Validators of a `PUT` response, or an error when it is not successful.
+    fn put_result(path: &str, resp: &Response<Bytes>) -> Result<PutResult> {
+        if !resp.status().is_success() {
+            return Err(anyhow!("PUT {path} failed with {}", resp.status()));
+        }
+        Ok(PutResult {
+            etag: Self::etag_from_headers(resp.headers()),
+            schedule_tag: Self::schedule_tag_from_headers(resp.headers()),
+        })
+    }
+
+    /// Discover the time zone distribution services (`CALDAV:timezone-service-set`,
+    /// RFC 7809 §5.1) advertised on a calendar home.
+    pub async fn discover_timezone_service_set(&self, home_set_path: &str) -> Result<Vec<String>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:timezone-service-set/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(home_set_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND timezone-service-set failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        let mut services = Vec::new();
+        for mut item in self.parse_multistatus(&body)?.items {
+            services.append(&mut item.timezone_service_set);
+        }
+        Ok(services)
+    }
+
+    /// Check whether the server supports time zones by reference (RFC 7809), i.e. advertises
+    /// `calendar-no-timezone` in the `DAV` header of an `OPTIONS` response.
+    ///
+    /// When supported, clients may omit `VTIMEZONE` components from stored objects and use
+    /// [`MkCalendar::timezone_id`] / [`CalendarPropPatch::timezone_id`] instead of full
+    /// `calendar-timezone` values.
+    pub async fn supports_timezones_by_reference(&self) -> Result<bool> {
+        let resp = self.options("").await?;
+        Ok(DavCapabilities::from_headers(resp.headers()).has("calendar-no-timezone"))
+    }
+
+    /// List CalDAV collections under a calendar home-set (`Depth: 1` PROPFIND).
+    pub async fn list_calendars(&self, home_set_path: &str) -> Result<Vec<CalendarInfo>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav" xmlns:A="http://apple.com/ns/ical/">
+  <D:prop>
+    <D:displayname/>
+    <C:calendar-description/>
+    <C:calendar-timezone/>
+    <C:calendar-timezone-id/>
+    <C:schedule-calendar-transp/>
+    <C:calendar-color/>
+    <A:calendar-color/>
+    <C:supported-calendar-component-set/>
+    <D:getetag/>
+    <D:resourcetype/>
+    <D:sync-token/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(home_set_path, Depth::One, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("PROPFIND calendars failed with {}", resp.status()));
+        }
+        let body = resp.into_body();
+        Ok(map_calendar_list(self.parse_multistatus(&body)?.items))
+    }
+
+    /// Read the `CS:getctag` of a calendar: an opaque token the server changes whenever an
+    /// object in the collection is added, changed or removed.
+    ///
+    /// Servers without `sync-collection` (see
+    /// [`supports_webdav_sync`](Self::supports_webdav_sync)) often still expose it, so
+    /// pollers can skip unchanged calendars. `None` when the server does not.
+    pub async fn get_ctag(&self, calendar_path: &str) -> Result<Option<String>> {
+        let getctag = PropertyName::new("http://calendarserver.org/ns/", "getctag");
+        let registry = PropertyRegistry::new().register(getctag.clone());
+        let resources = self
+            .webdav
+            .propfind_properties(calendar_path, Depth::Zero, &registry)
+            .await?;
+        Ok(resources
+            .iter()
+            .find_map(|resource| resource.get(&getctag).and_then(|value| value.text())))
+    }
+
+    /// Execute a CalDAV `calendar-query` with an optional time-range filter.
+    ///
+    /// `component` should be `VEVENT`, `VTODO`, … while `start`/`end` are ISO-8601
+    /// timestamps in the format required by CalDAV (e.g. `20240101T000000Z`).
+    ///
+    /// Inputs are validated before any request is sent, and additionally
+    /// XML-escaped when the request body is built (defense in depth).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if:
+    /// - `component` is empty or contains characters outside ASCII
+    ///   alphanumerics and `-` (e.g. `VEVENT` and `X-CUSTOM` are valid)
+    /// - `start` or `end` is provided but is not a structurally valid
+    ///   iCalendar UTC date-time (`YYYYMMDDTHHMMSSZ`)
+    ///
+    /// Also returns an error if the REPORT request fails or the server
+    /// responds with a non-success status.
+    pub async fn calendar_query_timerange(
+        &self,
+        calendar_path: &str,
+        component: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+        include_data: bool,
+    ) -> Result<Vec<CalendarObject>> {
+        validate_component_name(component)
+            .map_err(|e| anyhow!("invalid calendar-query component: {e}"))?;
+        if let Some(s) = start {
+            validate_utc_datetime(s).map_err(|e| anyhow!("invalid calendar-query start: {e}"))?;
+        }
+        if let Some(e) = end {
+            validate_utc_datetime(e).map_err(|e| anyhow!("invalid calendar-query end: {e}"))?;
+        }
+
+        let xml = build_calendar_query_body(component, start, end, include_data);
+
+        let resp = self.report(calendar_path, Depth::One, &xml).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "REPORT calendar-query failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(map_calendar_objects(self.parse_multistatus(&body)?.items))
+    }
+
+    /// List the todos of `calendar_path` that are neither completed nor cancelled, with their
+    /// `calendar-data`.
+    ///
+    /// The server filters on a missing `COMPLETED` property (see
+    /// [`incomplete_todos_filter`]); todos whose `STATUS` is `COMPLETED` or `CANCELLED` are
+    /// then dropped client-side.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the REPORT fails or the server responds with a non-success status.
+    pub async fn incomplete_todos(&self, calendar_path: &str) -> Result<Vec<CalendarObject>> {
+        self.query_open_todos(calendar_path, &incomplete_todos_filter())
+            .await
+    }
+
+    /// List the incomplete todos of `calendar_path` whose `DUE` has passed, with their
+    /// `calendar-data`. See [`incomplete_todos`](Self::incomplete_todos).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the REPORT fails or the server responds with a non-success status.
+    pub async fn overdue_todos(&self, calendar_path: &str) -> Result<Vec<CalendarObject>> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        self.query_open_todos(
+            calendar_path,
+            &overdue_todos_filter(&format_utc_datetime(now)),
+        )
+        .await
+    }
+
+    async fn query_open_todos(
+        &self,
+        calendar_path: &str,
+        filter: &CompFilter,
+    ) -> Result<Vec<CalendarObject>> {
+        Ok(self
+            .query_with_data(calendar_path, filter)
+            .await?
+            .into_iter()
+            .filter(|o| o.calendar_data.as_deref().is_some_and(todo_is_open))
+            .collect())
+    }
+
+    /// Find the object of `calendar_path` whose `UID` is `uid`, with its `calendar-data`.
+    ///
+    /// Resource names are picked by whichever client created the object (and some servers
+    /// rename on upload), so guessing `{uid}.ics` is unreliable. This sends one
+    /// `calendar-query` per component type (`VEVENT`, `VTODO`, `VJOURNAL`) with an exact
+    /// [`uid_filter`], then re-checks the returned data, as servers that only implement
+    /// substring matching may return near misses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `uid` is empty, and afterwards if a
+    /// REPORT fails or several objects of the calendar carry the `UID`.
+    pub async fn get_object_by_uid(
+        &self,
+        calendar_path: &str,
+        uid: &str,
+    ) -> Result<Option<CalendarObject>> {
+        if uid.is_empty() {
+            return Err(anyhow!("UID cannot be empty"));
+        }
+        let filters = ["VEVENT", "VTODO", "VJOURNAL"].map(|component| uid_filter(component, uid));
+        let queries = filters
+            .iter()
+            .map(|filter| self.query_with_data(calendar_path, filter));
+        let mut matches: Vec<CalendarObject> = Vec::new();
+        for object in future::try_join_all(queries).await?.into_iter().flatten() {
+            let exact = object
+                .calendar_data
+                .as_deref()
+                .is_some_and(|ics| has_uid(ics, uid));
+            if exact && !matches.iter().any(|m| m.href == object.href) {
+                matches.push(object);
+            }
+        }
+        match matches.len() {
+            0 | 1 => Ok(matches.pop()),
+            n => Err(anyhow!("{n} objects in {calendar_path} have UID {uid}")),
+        }
+    }
+
+    /// Delete the object of `calendar_path` whose `UID` is `uid` (see
+    /// [`get_object_by_uid`](Self::get_object_by_uid)), guarded by its ETag.
+    ///
+    /// Returns `false` when no object carries the `UID`.
+    ///
+    /// # Errors
+    ///
+    /// As [`get_object_by_uid`](Self::get_object_by_uid), and when the `DELETE` fails
+    /// (including `412` if the object changed since it was looked up).
+    pub async fn delete_by_uid(&self, calendar_path: &str, uid: &str) -> Result<bool> {
+        let Some(object) = self.get_object_by_uid(calendar_path, uid).await? else {
+            return Ok(false);
+        };
+        let resp = match &object.etag {
+            Some(etag) => self.delete_if_match(&object.href, etag).await?,
+            None => self.delete(&object.href).await?,
+        };
+        match resp.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            status => Err(anyhow!("DELETE {} failed with {}", object.href, status)),
+        }
+    }
+
+    /// `calendar-query` REPORT (`Depth: 1`) returning the full `calendar-data` of matches.
+    async fn query_with_data(
+        &self,
+        calendar_path: &str,
+        filter: &CompFilter,
+    ) -> Result<Vec<CalendarObject>> {
+        let xml = calendar_query_body(filter, Some(&CalendarData::default()), None);
+        let resp = self.report(calendar_path, Depth::One, &xml).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "REPORT calendar-query failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(map_calendar_objects(self.parse_multistatus(&body)?.items))
+    }
+
+    /// List the journal entries (`VJOURNAL`) of `calendar_path`, optionally only those whose
+    /// `DTSTART` falls in `[start, end)`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fast_dav_rs::CalDavClient;
+    /// # async fn run(cli: CalDavClient) -> anyhow::Result<()> {
+    /// for note in cli.journals("calendars/jane/notes/", None, None).await? {
+    ///     println!("{}: {:?}", note.href, note.summary);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `start` or `end` is not a structurally
+    /// valid iCalendar UTC date-time, and afterwards if the REPORT fails or the server
+    /// responds with a non-success status.
+    pub async fn journals(
+        &self,
+        calendar_path: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Vec<Journal>> {
+        let filter = journals_filter(start, end);
+        filter.validate()?;
+        Ok(map_journals(
+            self.query_with_data(calendar_path, &filter).await?,
+        ))
+    }
+
+    /// Mark the todo at `href` as completed and write it back.
+    ///
+    /// The object is fetched with `GET`, rewritten with [`complete_todo`] and stored with
+    /// [`put_if_match`](Self::put_if_match) on the returned ETag, so a concurrent edit
+    /// surfaces as `412 Precondition Failed` instead of being overwritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` fails or is not successful, or the object is not valid
+    /// iCalendar or contains no `VTODO`.
+    pub async fn complete_task(&self, href: &str) -> Result<Response<Bytes>> {
+        self.rewrite_object(href, complete_todo).await
+    }
+
+    /// Add an alarm to the calendar object at `href` and write it back.
+    ///
+    /// Fetched, rewritten with [`add_alarm`] and stored with `If-Match` like
+    /// [`complete_task`](Self::complete_task).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the alarm is invalid, the `GET` fails or is not successful, or the
+    /// object is not valid iCalendar or contains no `VEVENT`/`VTODO`.
+    pub async fn add_alarm(
+        &self,
+        href: &str,
+        alarm: &AlarmBuilder,
+        quirks: AlarmQuirks,
+    ) -> Result<Response<Bytes>> {
+        alarm.build()?;
+        self.rewrite_object(href, |ics| add_alarm(ics, alarm, quirks))
+            .await
+    }
+
+    /// Remove the alarm at `index` (in `VALARM` order) from the calendar object at `href`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` fails or is not successful, the object is not valid
+    /// iCalendar, or it has no alarm at `index`.
+    pub async fn remove_alarm(&self, href: &str, index: usize) -> Result<Response<Bytes>> {
+        self.rewrite_object(href, |ics| remove_alarm(ics, index))
+            .await
+    }
+
+    /// Acknowledge the alarm at `index` on the calendar object at `href`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` fails or is not successful, the object is not valid
+    /// iCalendar, or it has no alarm at `index`.
+    pub async fn acknowledge_alarm(
+        &self,
+        href: &str,
+        index: usize,
+        quirks: AlarmQuirks,
+    ) -> Result<Response<Bytes>> {
+        self.rewrite_object(href, |ics| acknowledge_alarm(ics, index, quirks))
+            .await
+    }
+
+    /// Snooze the alarm at `index` on the calendar object at `href` until `until`, an
+    /// iCalendar UTC date-time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `until` is not a valid UTC date-time,
+    /// and afterwards if the `GET` fails or is not successful, the object is not valid
+    /// iCalendar, or it has no alarm at `index`.
+    pub async fn snooze_alarm(
+        &self,
+        href: &str,
+        index: usize,
+        until: &str,
+        quirks: AlarmQuirks,
+    ) -> Result<Response<Bytes>> {
+        validate_utc_datetime(until).map_err(|e| anyhow!("invalid snooze time: {e}"))?;
+        self.rewrite_object(href, |ics| snooze_alarm(ics, index, until, quirks))
+            .await
+    }
+
+    /// `GET` the object at `href`, rewrite its iCalendar with `rewrite` and store it with
+    /// `If-Match` on the returned ETag (unconditionally when the server sent none).
+    /// Create or update the override of one instance of the recurring object at `href` and
+    /// write it back.
+    ///
+    /// Fetched, rewritten with [`update_instance`] and stored with `If-Match` like
+    /// [`complete_task`](Self::complete_task).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fast_dav_rs::CalDavClient;
+    /// # async fn run(cli: CalDavClient) -> anyhow::Result<()> {
+    /// cli.update_instance("calendars/jane/work/sync.ics", "20240108T090000", |instance| {
+    ///     instance.set_value("LOCATION", "Room 2");
+    ///     Ok(())
+    /// })
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` fails or is not successful, or see [`update_instance`].
+    pub async fn update_instance(
+        &self,
+        href: &str,
+        recurrence_id: &str,
+        edit: impl FnOnce(&mut Component) -> Result<()>,
+    ) -> Result<Response<Bytes>> {
+        self.rewrite_object(href, |ics| update_instance(ics, recurrence_id, edit))
+            .await
+    }
+
+    /// Cancel one instance of the recurring object at `href` with an `EXDATE` (see
+    /// [`delete_instance`]) and write it back with `If-Match`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` fails or is not successful, or see [`delete_instance`].
+    pub async fn delete_instance(
+        &self,
+        href: &str,
+        recurrence_id: &str,
+    ) -> Result<Response<Bytes>> {
+        self.rewrite_object(href, |ics| delete_instance(ics, recurrence_id))
+            .await
+    }
+
+    async fn rewrite_object(
+        &self,
+        href: &str,
+        rewrite: impl FnOnce(&str) -> Result<String>,
+    ) -> Result<Response<Bytes>> {
+        let resp = self.get(href).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("GET {href} failed with {}", resp.status()));
+        }
+        let etag = Self::etag_from_headers(resp.headers());
+        let body = Bytes::from(rewrite(&String::from_utf8_lossy(resp.body()))?);
+
+        match etag {
+            Some(etag) => self.put_if_match(href, body, &etag).await,
+            None => self.put(href, body).await,
+        }
+    }
+
+    /// Start a fluent `calendar-query` against `calendar_path`.
+    ///
+    /// See [`CalendarQuery`] for the available filters and options.
+    #[cfg(feature = "query-builder")]
+    pub fn query(&self, calendar_path: &str) -> CalendarQuery<'_> {
+        CalendarQuery::new(self, calendar_path)
+    }
+
+    /// Query free-busy information for `[start, end)` via a `free-busy-query` REPORT.
+    ///
+    /// The server answers with a `VFREEBUSY` covering every calendar object in
+    /// `calendar_path`; it is parsed into typed [`FreeBusy`] periods. `start`/`end` are
+    /// iCalendar UTC date-times (e.g. `20240101T000000Z`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `start` or `end` is not a structurally
+    /// valid iCalendar UTC date-time, and afterwards if the REPORT fails, the server responds
+    /// with a non-success status, or the returned iCalendar cannot be parsed.
+    pub async fn free_busy_query(
+        &self,
+        calendar_path: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<FreeBusy> {
+        validate_utc_datetime(start).map_err(|e| anyhow!("invalid free-busy-query start: {e}"))?;
+        validate_utc_datetime(end).map_err(|e| anyhow!("invalid free-busy-query end: {e}"))?;
+
+        let xml = build_free_busy_query_body(start, end);
+        let resp = self.report(calendar_path, Depth::One, &xml).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "REPORT free-busy-query failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        parse_free_busy(&String::from_utf8_lossy(&body))
+    }
+
+    /// Run [`free_busy_query`](Self::free_busy_query) on every calendar in `calendar_paths`
+    /// concurrently and merge the busy time into one timeline over `[start, end)` (see
+    /// [`merge_busy_periods`]); [`FreeBusy::free_periods`] then yields candidate meeting
+    /// slots.
+    ///
+    /// At most as many REPORTs as the multiget concurrency (see
+    /// [`set_multiget_chunking`](Self::set_multiget_chunking)) are in flight at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `start`/`end` are not UTC date-times,
+    /// and afterwards if any calendar's REPORT fails.
+    pub async fn aggregate_free_busy<I, S>(
+        &self,
+        calendar_paths: I,
+        start: &str,
+        end: &str,
+    ) -> Result<FreeBusy>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        validate_utc_datetime(start).map_err(|e| anyhow!("invalid free-busy-query start: {e}"))?;
+        validate_utc_datetime(end).map_err(|e| anyhow!("invalid free-busy-query end: {e}"))?;
+
+        let paths: Vec<String> = calendar_paths
+            .into_iter()
+            .map(|p| p.as_ref().to_string())
+            .collect();
+        let mut responses = stream::iter(paths)
+            .map(|path| async move { self.free_busy_query(&path, start, end).await })
+            .buffer_unordered(self.multiget_concurrency);
+
+        let mut periods = Vec::new();
+        while let Some(free_busy) = responses.next().await {
+            periods.extend(free_busy?.periods);
+        }
+        Ok(FreeBusy {
+            start: Some(start.to_string()),
+            end: Some(end.to_string()),
+            periods: merge_busy_periods(periods),
+        })
+    }
+
+    /// `POST` an iTIP message (RFC 5546) to a schedule outbox and return the per-recipient
+    /// statuses from the `schedule-response` (RFC 6638 §3.2.10).
+    ///
+    /// The `Content-Type` carries the message's `METHOD` (e.g. `REQUEST`, `REPLY`, `CANCEL`);
+    /// the server derives the recipients from the `ORGANIZER`/`ATTENDEE` properties.
+    /// Use [`discover_schedule_urls`](Self::discover_schedule_urls) to find `outbox_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `itip` has no valid `METHOD` property,
+    /// and afterwards if the POST fails, the server responds with a non-success status, or the
+    /// `schedule-response` cannot be parsed.
+    pub async fn schedule_post(
+        &self,
+        outbox_path: &str,
+        itip: &str,
+    ) -> Result<Vec<ScheduleRecipientStatus>> {
+        let method =
+            itip_method(itip).ok_or_else(|| anyhow!("iTIP message has no METHOD property"))?;
+        validate_component_name(&method).map_err(|e| anyhow!("invalid iTIP METHOD: {e}"))?;
+
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_str(&format!(
+                "text/calendar; charset=utf-8; method={method}"
+            ))?,
+        );
+        let resp = self
+            .send(
+                Method::POST,
+                outbox_path,
+                h,
+                Some(Bytes::from(itip.to_owned())),
+                None,
+            )
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "POST to schedule outbox failed with {}",
+                resp.status()
+            ));
+        }
+        parse_schedule_response(resp.body())
+    }
+
+    /// Ask the server for the free-busy time of `attendees` over `[start, end)` via the
+    /// organizer's schedule outbox.
+    ///
+    /// Each returned status carries the recipient's `VFREEBUSY` in `calendar_data`, which can
+    /// be turned into typed periods with [`parse_free_busy`](crate::caldav::parse_free_busy).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `start` or `end` is not a structurally
+    /// valid iCalendar UTC date-time, and otherwise as [`schedule_post`](Self::schedule_post).
+    pub async fn schedule_free_busy<I, S>(
+        &self,
+        outbox_path: &str,
+        organizer: &str,
+        attendees: I,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<ScheduleRecipientStatus>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        validate_utc_datetime(start).map_err(|e| anyhow!("invalid free-busy start: {e}"))?;
+        validate_utc_datetime(end).map_err(|e| anyhow!("invalid free-busy end: {e}"))?;
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let uid = format!("freebusy-{nanos}@fast-dav-rs");
+        let itip = build_free_busy_request(organizer, attendees, start, end, &uid);
+        self.schedule_post(outbox_path, &itip).await
+    }
+
+    /// Fetch specific calendar objects via `calendar-multiget`.
+    ///
+    /// Servers reject bodies with thousands of hrefs, so the list is split into chunks (see
+    /// [`set_multiget_chunking`](Self::set_multiget_chunking)) sent concurrently with
+    /// bounded parallelism. Results are merged in chunk order, each chunk keeping the
+    /// server's response order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any REPORT fails or the server responds with a non-success status.
+    pub async fn calendar_multiget<I, S>(
         &self,
         calendar_path: &str,
         hrefs: I,
@@ -496,19 +2048,123 @@ impl CalDavClient {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let Some(body) = build_calendar_multiget_body(hrefs, include_data) else {
-            return Ok(Vec::new());
-        };
+        let bodies = build_calendar_multiget_bodies(hrefs, include_data, self.multiget_chunk_size);
+        let mut responses = stream::iter(bodies)
+            .map(|body| async move {
+                let resp = self.report(calendar_path, Depth::One, &body).await?;
+                if !resp.status().is_success() {
+                    return Err(anyhow!(
+                        "REPORT calendar-multiget failed with {}",
+                        resp.status()
+                    ));
+                }
+                let body = resp.into_body();
+                Ok(map_calendar_objects(self.parse_multistatus(&body)?.items))
+            })
+            .buffered(self.multiget_concurrency);
 
-        let resp = self.report(calendar_path, Depth::One, &body).await?;
+        let mut objects = Vec::new();
+        while let Some(chunk) = responses.next().await {
+            objects.extend(chunk?);
+        }
+        Ok(objects)
+    }
+
+    /// Export the calendar at `calendar_path` as one `VCALENDAR`, streamed as bytes suitable
+    /// for writing to an `.ics` backup.
+    ///
+    /// Object hrefs are listed with a `Depth: 1` `PROPFIND`, then fetched with chunked
+    /// `calendar-multiget`s (see [`calendar_multiget`](Self::calendar_multiget)) that are
+    /// merged as they arrive by a [`CalendarMerger`]: one wrapper, each `VTIMEZONE` once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listing `PROPFIND` fails. Errors of later `REPORT`s, or
+    /// objects that cannot be parsed, are yielded by the stream; the output is incomplete
+    /// after the first error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fast_dav_rs::CalDavClient;
+    /// use futures::StreamExt;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// # async fn run(client: CalDavClient) -> anyhow::Result<()> {
+    /// let mut file = tokio::fs::File::create("work.ics").await?;
+    /// let mut export = Box::pin(client.export_calendar("/calendars/jane/work/").await?);
+    /// while let Some(chunk) = export.next().await {
+    ///     file.write_all(&chunk?).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_calendar(
+        &self,
+        calendar_path: &str,
+    ) -> Result<impl Stream<Item = Result<Bytes>> + '_> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:">
+  <D:prop>
+    <D:resourcetype/>
+    <D:getetag/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(calendar_path, Depth::One, body).await?;
         if !resp.status().is_success() {
             return Err(anyhow!(
-                "REPORT calendar-multiget failed with {}",
+                "PROPFIND calendar objects failed with {}",
                 resp.status()
             ));
         }
         let body = resp.into_body();
-        Ok(map_calendar_objects(parse_multistatus_bytes(&body)?.items))
+        let hrefs: Vec<String> = self
+            .parse_multistatus(&body)?
+            .items
+            .into_iter()
+            .filter(|item| !item.is_collection)
+            .map(|item| item.href)
+            .collect();
+
+        let calendar_path: Arc<str> = Arc::from(calendar_path);
+        let bodies = build_calendar_multiget_bodies(hrefs, true, self.multiget_chunk_size);
+        let merger = CalendarMerger::new();
+        let header = merger.header();
+        let footer = merger.footer();
+
+        let objects = stream::iter(bodies)
+            .map(move |body| {
+                let calendar_path = Arc::clone(&calendar_path);
+                async move {
+                    let resp = self.report(&calendar_path, Depth::One, &body).await?;
+                    if !resp.status().is_success() {
+                        return Err(anyhow!(
+                            "REPORT calendar-multiget failed with {}",
+                            resp.status()
+                        ));
+                    }
+                    let body = resp.into_body();
+                    Ok(map_calendar_objects(self.parse_multistatus(&body)?.items))
+                }
+            })
+            .buffered(self.multiget_concurrency)
+            .scan(merger, |merger, chunk| {
+                let merged = chunk.and_then(|objects| {
+                    let mut out = String::new();
+                    for data in objects.into_iter().filter_map(|o| o.calendar_data) {
+                        out.push_str(&merger.push(&data)?);
+                    }
+                    Ok(Bytes::from(out))
+                });
+                std::future::ready(Some(merged))
+            });
+
+        Ok(stream::once(std::future::ready(Ok(Bytes::from(header))))
+            .chain(objects)
+            .chain(stream::once(std::future::ready(Ok(Bytes::from_static(
+                footer.as_bytes(),
+            ))))))
     }
 
     /// Incrementally synchronise a calendar collection using `sync-collection`.
@@ -523,18 +2179,179 @@ impl CalDavClient {
 
         let resp = self.report(calendar_path, Depth::One, &body).await?;
         if !resp.status().is_success() {
-            return Err(anyhow!(
-                "REPORT sync-collection failed with {}",
-                resp.status()
+            return Err(sync_collection_error(
+                sync_token,
+                resp.status(),
+                resp.body(),
             ));
         }
         let headers = resp.headers().clone();
         let body = resp.into_body();
 
-        let parsed = parse_multistatus_bytes(&body)?;
+        let mut parsed = self.parse_multistatus(&body)?;
+        let base = self.build_uri(calendar_path)?;
+        for item in &mut parsed.items {
+            item.href = resolve_href(base.path(), &item.href);
+        }
         Ok(map_sync_response(&headers, parsed.items, parsed.sync_token))
     }
 
+    /// Streaming variant of [`sync_collection`](Self::sync_collection): changes are parsed
+    /// and yielded one by one while the response is still arriving.
+    ///
+    /// Read the stream to the end, then take the new token from
+    /// [`SyncItemStream::sync_token`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fast_dav_rs::CalDavClient;
+    /// use futures::TryStreamExt;
+    ///
+    /// # async fn demo(cli: &CalDavClient, token: Option<&str>) -> anyhow::Result<()> {
+    /// let mut changes = cli
+    ///     .sync_collection_stream("calendars/jane/work/", token, None, true)
+    ///     .await?;
+    /// while let Some(item) = changes.try_next().await? {
+    ///     println!("{} deleted={}", item.href, item.is_deleted);
+    /// }
+    /// let next_token = changes.sync_token();
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the REPORT fails or the server responds with a non-success status;
+    /// parse errors and idle timeouts ([`STREAM_READ_IDLE_TIMEOUT`]) surface as stream items.
+    pub async fn sync_collection_stream(
+        &self,
+        calendar_path: &str,
+        sync_token: Option<&str>,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> Result<SyncItemStream> {
+        let body = build_sync_collection_body(sync_token, limit, include_data);
+
+        let resp = self.report_stream(calendar_path, Depth::One, &body).await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp
+                .into_body()
+                .collect()
+                .await
+                .map(|body| body.to_bytes())
+                .unwrap_or_default();
+            return Err(sync_collection_error(sync_token, status, &body));
+        }
+        let header_token = resp
+            .headers()
+            .get("Sync-Token")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let encodings = detect_encodings(resp.headers());
+        let reader = decoded_reader(
+            resp.into_body(),
+            &encodings,
+            self.webdav.decompression_options(),
+        );
+        Ok(SyncItemStream::with_limits(
+            reader,
+            header_token,
+            STREAM_READ_IDLE_TIMEOUT,
+            self.parse_limits,
+        ))
+    }
+
+    /// [`sync_collection`](Self::sync_collection) following truncated responses: while the
+    /// server reports more changes than it returned ([`SyncResponse::truncated`]), sync again
+    /// from the new token, up to the final token.
+    ///
+    /// `limit` applies to each round. Changes are merged by href, the latest round winning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a round fails, or a truncated response does not advance the sync
+    /// token.
+    pub async fn sync_collection_all(
+        &self,
+        calendar_path: &str,
+        sync_token: Option<&str>,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> Result<SyncResponse> {
+        let mut response = self
+            .sync_collection(calendar_path, sync_token, limit, include_data)
+            .await?;
+        let mut positions: HashMap<String, usize> = response
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (item.href.clone(), i))
+            .collect();
+        let mut previous = sync_token.map(str::to_string);
+        while response.truncated {
+            let token = response
+                .sync_token
+                .take()
+                .filter(|token| previous.as_ref() != Some(token))
+                .ok_or_else(|| {
+                    anyhow!("truncated sync-collection did not advance the sync token")
+                })?;
+            let next = self
+                .sync_collection(calendar_path, Some(&token), limit, include_data)
+                .await?;
+            for item in next.items {
+                match positions.get(&item.href) {
+                    Some(&i) => response.items[i] = item,
+                    None => {
+                        positions.insert(item.href.clone(), response.items.len());
+                        response.items.push(item);
+                    }
+                }
+            }
+            response.sync_token = next.sync_token;
+            response.truncated = next.truncated;
+            previous = Some(token);
+        }
+        Ok(response)
+    }
+
+    /// [`sync_collection`](Self::sync_collection) resuming from the token `store` holds for
+    /// `calendar_path`, and saving the new token once the response is parsed.
+    ///
+    /// The first call (no stored token) performs a full sync.
+    pub async fn sync_collection_with_store<S: TokenStore + ?Sized>(
+        &self,
+        calendar_path: &str,
+        store: &S,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> Result<SyncResponse> {
+        let token = store.sync_token(calendar_path).await?;
+        let response = self
+            .sync_collection(calendar_path, token.as_deref(), limit, include_data)
+            .await?;
+        if response.sync_token.is_some() {
+            store
+                .set_sync_token(calendar_path, response.sync_token.clone())
+                .await?;
+        }
+        Ok(response)
+    }
+
+    /// Watch the calendar at `calendar_path`, polling every `interval` with `sync-collection`
+    /// or, on servers without it, `getctag` and ETag listings.
+    ///
+    /// State is kept in memory, so the first poll reports every existing member as
+    /// created. See [`SyncEngine::watch`]; build the engine yourself to persist the state
+    /// or receive the data.
+    pub fn watch(&self, calendar_path: &str, interval: Duration) -> ChangeStream {
+        SyncEngine::new(self.clone(), Arc::new(MemoryTokenStore::new()))
+            .include_data(false)
+            .watch(calendar_path, interval)
+    }
+
     // ----------- ETag helpers -----------
 
     /// Extract the `ETag` from a response header map, if present.
@@ -542,6 +2359,17 @@ impl CalDavClient {
         WebDavClient::etag_from_headers(headers)
     }
 
+    /// Extract the `Schedule-Tag` (RFC 6638 §3.2.10) from a `GET`/`PUT` response header map.
+    ///
+    /// Servers only send it for scheduling objects (events with an `ORGANIZER`); pass it to
+    /// [`put_if_schedule_tag_match`](Self::put_if_schedule_tag_match) for attendee updates.
+    pub fn schedule_tag_from_headers(headers: &HeaderMap) -> Option<String> {
+        headers
+            .get("schedule-tag")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string())
+    }
+
     // ----------- Batch (limited concurrency) -----------
 
     /// Run many `PROPFIND`s concurrently with a semaphore-bound concurrency limit.
@@ -606,6 +2434,141 @@ impl CalDavClient {
         self.webdav.supports_webdav_sync().await
     }
 
+    /// Discover the push transports offered for the collection at `path`.
+    ///
+    /// See [`WebDavClient::discover_push`].
+    pub async fn discover_push(&self, path: &str) -> Result<PushInfo> {
+        self.webdav.discover_push(path).await
+    }
+
+    /// Register (or renew) a WebDAV-Push subscription on the collection at `path`.
+    ///
+    /// See [`WebDavClient::register_push`].
+    pub async fn register_push(
+        &self,
+        path: &str,
+        subscription: &WebPushSubscription,
+    ) -> Result<PushRegistration> {
+        self.webdav.register_push(path, subscription).await
+    }
+
+    /// Remove a push subscription by its registration URL.
+    pub async fn unregister_push(&self, registration_url: &str) -> Result<()> {
+        self.webdav.unregister_push(registration_url).await
+    }
+
+    /// Read the access control list of `path`.
+    ///
+    /// See [`WebDavClient::get_acl`].
+    pub async fn get_acl(&self, path: &str) -> Result<Vec<Ace>> {
+        self.webdav.get_acl(path).await
+    }
+
+    /// Replace the access control list of `path` with `aces`.
+    ///
+    /// See [`WebDavClient::acl`].
+    pub async fn acl(&self, path: &str, aces: &[Ace]) -> Result<()> {
+        self.webdav.acl(path, aces).await
+    }
+
+    /// Discover the collections holding the server's principals.
+    ///
+    /// See [`WebDavClient::principal_collection_set`].
+    pub async fn principal_collection_set(&self, path: &str) -> Result<Vec<String>> {
+        self.webdav.principal_collection_set(path).await
+    }
+
+    /// List the principals inside the principal collection at `collection_path`.
+    ///
+    /// See [`WebDavClient::list_principals`].
+    pub async fn list_principals(&self, collection_path: &str) -> Result<Vec<PrincipalInfo>> {
+        self.webdav.list_principals(collection_path).await
+    }
+
+    /// List the principals referenced by the ACL of `path`.
+    ///
+    /// See [`WebDavClient::acl_principal_prop_set`].
+    pub async fn acl_principal_prop_set(&self, path: &str) -> Result<Vec<PrincipalInfo>> {
+        self.webdav.acl_principal_prop_set(path).await
+    }
+
+    /// List the versions of the event at `path` (its history).
+    ///
+    /// See [`WebDavClient::version_tree`].
+    pub async fn version_tree(&self, path: &str) -> Result<Vec<VersionEntry>> {
+        self.webdav.version_tree(path).await
+    }
+
+    /// List the versions of the event at `path` through its version history.
+    ///
+    /// See [`WebDavClient::version_history`].
+    pub async fn version_history(&self, path: &str) -> Result<Vec<VersionEntry>> {
+        self.webdav.version_history(path).await
+    }
+
+    /// Make the resource at `href` also reachable as `segment` in `collection`.
+    ///
+    /// See [`WebDavClient::bind`].
+    pub async fn bind(
+        &self,
+        collection: &str,
+        segment: &str,
+        href: &str,
+        overwrite: bool,
+    ) -> Result<()> {
+        self.webdav.bind(collection, segment, href, overwrite).await
+    }
+
+    /// Remove the binding `segment` from `collection`.
+    ///
+    /// See [`WebDavClient::unbind`].
+    pub async fn unbind(&self, collection: &str, segment: &str) -> Result<()> {
+        self.webdav.unbind(collection, segment).await
+    }
+
+    /// Move the binding at `href` to `segment` in `collection`.
+    ///
+    /// See [`WebDavClient::rebind`].
+    pub async fn rebind(
+        &self,
+        collection: &str,
+        segment: &str,
+        href: &str,
+        overwrite: bool,
+    ) -> Result<()> {
+        self.webdav
+            .rebind(collection, segment, href, overwrite)
+            .await
+    }
+
+    /// Take a write lock on `path`.
+    ///
+    /// See [`WebDavClient::lock`].
+    pub async fn lock(
+        &self,
+        path: &str,
+        scope: LockScope,
+        timeout: LockTimeout,
+        owner: Option<&str>,
+    ) -> Result<Lock> {
+        self.webdav.lock(path, scope, timeout, owner).await
+    }
+
+    /// Renew `lock` on `path` for `timeout`.
+    pub async fn refresh_lock(
+        &self,
+        path: &str,
+        lock: &Lock,
+        timeout: LockTimeout,
+    ) -> Result<Lock> {
+        self.webdav.refresh_lock(path, lock, timeout).await
+    }
+
+    /// Release `lock` on `path`.
+    pub async fn unlock(&self, path: &str, lock: &Lock) -> Result<()> {
+        self.webdav.unlock(path, lock).await
+    }
+
     /// Streaming variant of `PROPFIND`, returning the non-aggregated body.
     ///
     /// Combine with [`crate::streaming::parse_multistatus_stream`].
@@ -639,30 +2602,67 @@ pub fn build_calendar_query_body(
     start: Option<&str>,
     end: Option<&str>,
     include_data: bool,
+) -> String {
+    let data = include_data.then(CalendarData::default);
+    build_calendar_query_body_with(component, start, end, data.as_ref())
+}
+
+/// Like [`build_calendar_query_body`], with control over the returned `calendar-data`
+/// (e.g. [`CalendarData::expand`]). `None` only requests ETags.
+pub fn build_calendar_query_body_with(
+    component: &str,
+    start: Option<&str>,
+    end: Option<&str>,
+    data: Option<&CalendarData>,
+) -> String {
+    let filter = CompFilter::new(component).time_range(start, end);
+    calendar_query_body(&filter, data, None)
+}
+
+/// Build a `calendar-query` body from a [`CompFilter`] placed under `VCALENDAR`, allowing
+/// property and nested component filters. `None` for `data` only requests ETags.
+///
+/// The filter is not validated; see [`CalDavClient::query`] for a validating builder.
+pub fn build_calendar_query_body_filtered(
+    filter: &CompFilter,
+    data: Option<&CalendarData>,
+) -> String {
+    calendar_query_body(filter, data, None)
+}
+
+/// Full `calendar-query` body, optionally naming the time zone used to interpret floating
+/// date-times in the time-range filter (`CALDAV:timezone-id`, RFC 7809 §5.2).
+/// `true` when a component of the calendar object `ics` has exactly this `UID`.
+fn has_uid(ics: &str, uid: &str) -> bool {
+    Component::parse_all(ics).is_ok_and(|calendars| {
+        calendars
+            .iter()
+            .flat_map(|calendar| &calendar.components)
+            .any(|component| component.value("UID").map(str::trim) == Some(uid))
+    })
+}
+
+pub(crate) fn calendar_query_body(
+    filter: &CompFilter,
+    data: Option<&CalendarData>,
+    timezone_id: Option<&str>,
 ) -> String {
     let mut prop = String::from("<D:prop><D:getetag/>");
-    if include_data {
-        prop.push_str("<C:calendar-data/>");
+    if let Some(data) = data {
+        prop.push_str(&data.to_xml());
     }
     prop.push_str("</D:prop>");
 
     let mut filter = format!(
-        "<C:filter>\
-           <C:comp-filter name=\"VCALENDAR\">\
-             <C:comp-filter name=\"{}\">",
-        escape_xml(component)
+        r#"<C:filter><C:comp-filter name="VCALENDAR">{}</C:comp-filter></C:filter>"#,
+        filter.to_xml()
     );
-    if start.is_some() || end.is_some() {
-        filter.push_str("<C:time-range");
-        if let Some(s) = start {
-            filter.push_str(&format!(" start=\"{}\"", escape_xml(s)));
-        }
-        if let Some(e) = end {
-            filter.push_str(&format!(" end=\"{}\"", escape_xml(e)));
-        }
-        filter.push_str("/>");
+    if let Some(tzid) = timezone_id {
+        filter.push_str(&format!(
+            "<C:timezone-id>{}</C:timezone-id>",
+            escape_xml(tzid)
+        ));
     }
-    filter.push_str("</C:comp-filter></C:comp-filter></C:filter>");
 
     format!(
         r#"<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">{prop}{filter}</C:calendar-query>"#
@@ -702,6 +2702,28 @@ where
     Some(body)
 }
 
+/// Split `hrefs` into `calendar-multiget` bodies of at most `chunk_size` hrefs each (a zero
+/// `chunk_size` is treated as one). Empty hrefs are skipped; returns no body when none remain.
+pub fn build_calendar_multiget_bodies<I, S>(
+    hrefs: I,
+    include_data: bool,
+    chunk_size: usize,
+) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let hrefs: Vec<String> = hrefs
+        .into_iter()
+        .map(|h| h.as_ref().to_string())
+        .filter(|h| !h.is_empty())
+        .collect();
+    hrefs
+        .chunks(chunk_size.max(1))
+        .filter_map(|chunk| build_calendar_multiget_body(chunk, include_data))
+        .collect()
+}
+
 pub fn build_sync_collection_body(
     sync_token: Option<&str>,
     limit: Option<u32>,
@@ -735,7 +2757,9 @@ pub fn map_calendar_list(mut items: Vec<DavItem>) -> Vec<CalendarInfo> {
                 displayname: item.displayname,
                 description,
                 timezone,
+                timezone_id: item.calendar_timezone_id,
                 color: item.calendar_color,
+                transparency: item.schedule_calendar_transp,
                 etag: item.etag,
                 sync_token: item.sync_token,
                 supported_components: item.supported_components,
@@ -746,6 +2770,19 @@ pub fn map_calendar_list(mut items: Vec<DavItem>) -> Vec<CalendarInfo> {
     calendars
 }
 
+pub fn map_schedule_urls(items: Vec<DavItem>) -> ScheduleUrls {
+    let mut urls = ScheduleUrls::default();
+    for item in items {
+        if urls.inbox.is_none() {
+            urls.inbox = item.schedule_inbox_url;
+        }
+        if urls.outbox.is_none() {
+            urls.outbox = item.schedule_outbox_url;
+        }
+    }
+    urls
+}
+
 pub fn map_calendar_objects(items: Vec<DavItem>) -> Vec<CalendarObject> {
     let mut out = Vec::with_capacity(items.len());
     for mut item in items {
@@ -759,6 +2796,28 @@ pub fn map_calendar_objects(items: Vec<DavItem>) -> Vec<CalendarObject> {
     out
 }
 
+/// Map one `sync-collection` response; `None` for the collection itself.
+pub(crate) fn sync_item(mut item: DavItem) -> Option<SyncItem> {
+    let is_collection = item.is_collection
+        || (item.sync_token.is_some() && item.etag.is_none() && item.calendar_data.is_none());
+    if is_collection {
+        return None;
+    }
+    let status = item.status.clone();
+    let code = status.as_deref().and_then(http_status_code);
+    let is_deleted = matches!(code, Some(404) | Some(410));
+
+    Some(SyncItem {
+        href: normalize_href(&item.href),
+        etag: item.etag,
+        calendar_data: item.calendar_data.take(),
+        status,
+        is_deleted,
+    })
+}
+
+/// Map the responses of a `sync-collection` REPORT, with hrefs in the canonical form of
+/// [`normalize_href`] and one item per member.
 pub fn map_sync_response(
     headers: &HeaderMap,
     items: Vec<DavItem>,
@@ -771,34 +2830,37 @@ pub fn map_sync_response(
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string())
     });
-    let mut out = Vec::new();
+    let mut out: Vec<SyncItem> = Vec::new();
+    let mut positions = HashMap::new();
+    let mut truncated = false;
 
-    for mut item in items {
+    for item in items {
         // Capture per-item sync token if we don't have a top-level one (fallback)
         if item.sync_token.is_some() && sync_token.is_none() {
             sync_token = item.sync_token.clone();
         }
 
-        let is_collection = item.is_collection
-            || (item.sync_token.is_some() && item.etag.is_none() && item.calendar_data.is_none());
-        if is_collection {
+        if item.status.as_deref().and_then(http_status_code) == Some(507) {
+            truncated = true;
             continue;
         }
-        let status = item.status.clone();
-        let code = status.as_deref().and_then(http_status_code);
-        let is_deleted = matches!(code, Some(404) | Some(410));
-
-        out.push(SyncItem {
-            href: item.href,
-            etag: item.etag,
-            calendar_data: item.calendar_data.take(),
-            status,
-            is_deleted,
-        });
+        let Some(item) = sync_item(item) else {
+            continue;
+        };
+        // A member reported twice (e.g. under another spelling of its href) keeps its
+        // latest state.
+        match positions.entry(item.href.clone()) {
+            Entry::Occupied(position) => out[*position.get()] = item,
+            Entry::Vacant(position) => {
+                position.insert(out.len());
+                out.push(item);
+            }
+        }
     }
 
     SyncResponse {
         sync_token,
         items: out,
+        truncated,
     }
 }
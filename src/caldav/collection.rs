@@ -0,0 +1,302 @@
+//! Typed builders for creating calendar collections (`MKCALENDAR`, RFC 4791 §5.3.1, with an
+//! extended `MKCOL` fallback, RFC 5689) and updating their properties (`PROPPATCH`).
+
+use anyhow::{Result, anyhow};
+
+use crate::caldav::types::{CalendarInfo, ScheduleTransparency, UnsupportedComponentError};
+use crate::common::contentline::Component;
+use crate::webdav::xml::{escape_xml, validate_component_name};
+
+const COLLECTION_NAMESPACES: &str =
+    r#"xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav" xmlns:A="http://apple.com/ns/ical/""#;
+
+/// Check that every component of the iCalendar object `ics` (`VTIMEZONE` aside) is accepted
+/// by `calendar`, as reported by [`crate::caldav::CalDavClient::list_calendars`].
+///
+/// # Errors
+///
+/// Returns an [`UnsupportedComponentError`] for the first component the calendar does not
+/// support, or a parse error when `ics` is not valid iCalendar.
+pub fn check_supported_components(ics: &str, calendar: &CalendarInfo) -> Result<()> {
+    for root in Component::parse_all(ics)? {
+        for component in &root.components {
+            if component.name == "VTIMEZONE" || calendar.supports_component(&component.name) {
+                continue;
+            }
+            return Err(UnsupportedComponentError {
+                calendar: calendar.href.clone(),
+                component: component.name.clone(),
+                supported: calendar.supported_components.clone(),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Builder for `MKCALENDAR` request bodies.
+///
+/// Use it with [`crate::caldav::CalDavClient::create_calendar`], which also falls back to an
+/// extended `MKCOL` on servers that do not implement `MKCALENDAR`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::MkCalendar;
+///
+/// let body = MkCalendar::new()
+///     .displayname("Work")
+///     .description("Team meetings")
+///     .color("#FF5733FF")
+///     .components(["VEVENT", "VTODO"])
+///     .build()?;
+/// assert!(body.contains("<D:displayname>Work</D:displayname>"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MkCalendar {
+    props: CalendarProps,
+    components: Vec<String>,
+}
+
+impl MkCalendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `DAV:displayname`.
+    pub fn displayname(mut self, name: &str) -> Self {
+        self.props.displayname = Some(name.to_string());
+        self
+    }
+
+    /// `CALDAV:calendar-description`.
+    pub fn description(mut self, description: &str) -> Self {
+        self.props.description = Some(description.to_string());
+        self
+    }
+
+    /// Apple `calendar-color`, e.g. `#FF5733` or `#FF5733FF`.
+    pub fn color(mut self, color: &str) -> Self {
+        self.props.color = Some(color.to_string());
+        self
+    }
+
+    /// `CALDAV:calendar-timezone`: a complete `VCALENDAR` containing one `VTIMEZONE`.
+    pub fn timezone(mut self, vtimezone: &str) -> Self {
+        self.props.timezone = Some(vtimezone.to_string());
+        self
+    }
+
+    /// `CALDAV:calendar-timezone-id` (RFC 7809): the time zone by TZID (e.g. `Europe/Paris`)
+    /// instead of a full `VTIMEZONE`.
+    pub fn timezone_id(mut self, tzid: &str) -> Self {
+        self.props.timezone_id = Some(tzid.to_string());
+        self
+    }
+
+    /// `CALDAV:schedule-calendar-transp` (RFC 6638): whether events count as busy time.
+    pub fn transparency(mut self, transparency: ScheduleTransparency) -> Self {
+        self.props.transparency = Some(transparency);
+        self
+    }
+
+    /// `CALDAV:supported-calendar-component-set`, e.g. `["VEVENT", "VTODO"]`.
+    ///
+    /// When unset the server picks its default (usually every component type).
+    pub fn components<I, S>(mut self, components: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.components = components
+            .into_iter()
+            .map(|c| c.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Build the `MKCALENDAR` body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a component name is invalid.
+    pub fn build(&self) -> Result<String> {
+        let props = self.props_xml()?;
+        if props.is_empty() {
+            return Ok(format!("<C:mkcalendar {COLLECTION_NAMESPACES}/>"));
+        }
+        Ok(format!(
+            "<C:mkcalendar {COLLECTION_NAMESPACES}><D:set><D:prop>{props}</D:prop></D:set></C:mkcalendar>"
+        ))
+    }
+
+    /// Build the equivalent extended `MKCOL` body (RFC 5689), declaring the calendar
+    /// `resourcetype` explicitly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a component name is invalid.
+    pub fn build_extended_mkcol(&self) -> Result<String> {
+        let props = self.props_xml()?;
+        Ok(format!(
+            "<D:mkcol {COLLECTION_NAMESPACES}><D:set><D:prop><D:resourcetype><D:collection/><C:calendar/></D:resourcetype>{props}</D:prop></D:set></D:mkcol>"
+        ))
+    }
+
+    fn props_xml(&self) -> Result<String> {
+        let mut props = self.props.to_xml();
+        if !self.components.is_empty() {
+            props.push_str("<C:supported-calendar-component-set>");
+            for component in &self.components {
+                validate_component_name(component)
+                    .map_err(|e| anyhow!("invalid supported calendar component: {e}"))?;
+                props.push_str(&format!(r#"<C:comp name="{}"/>"#, escape_xml(component)));
+            }
+            props.push_str("</C:supported-calendar-component-set>");
+        }
+        Ok(props)
+    }
+}
+
+/// Builder for `PROPPATCH` bodies updating common calendar properties.
+///
+/// Use it with [`crate::caldav::CalDavClient::proppatch_calendar`], which parses the
+/// `207 Multi-Status` result into a [`PropPatchResult`](crate::webdav::PropPatchResult).
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::CalendarPropPatch;
+///
+/// let body = CalendarPropPatch::new()
+///     .displayname("Renamed")
+///     .color("#00AA00")
+///     .order(3)
+///     .build();
+/// assert!(body.contains("<A:calendar-order>3</A:calendar-order>"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarPropPatch {
+    props: CalendarProps,
+}
+
+impl CalendarPropPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `DAV:displayname`.
+    pub fn displayname(mut self, name: &str) -> Self {
+        self.props.displayname = Some(name.to_string());
+        self
+    }
+
+    /// `CALDAV:calendar-description`.
+    pub fn description(mut self, description: &str) -> Self {
+        self.props.description = Some(description.to_string());
+        self
+    }
+
+    /// Apple `calendar-color`, e.g. `#FF5733` or `#FF5733FF`.
+    pub fn color(mut self, color: &str) -> Self {
+        self.props.color = Some(color.to_string());
+        self
+    }
+
+    /// Apple `calendar-order`, the position of the calendar in client lists.
+    pub fn order(mut self, order: i32) -> Self {
+        self.props.order = Some(order);
+        self
+    }
+
+    /// `CALDAV:calendar-timezone`: a complete `VCALENDAR` containing one `VTIMEZONE`.
+    pub fn timezone(mut self, vtimezone: &str) -> Self {
+        self.props.timezone = Some(vtimezone.to_string());
+        self
+    }
+
+    /// `CALDAV:calendar-timezone-id` (RFC 7809): the time zone by TZID (e.g. `Europe/Paris`)
+    /// instead of a full `VTIMEZONE`.
+    pub fn timezone_id(mut self, tzid: &str) -> Self {
+        self.props.timezone_id = Some(tzid.to_string());
+        self
+    }
+
+    /// `CALDAV:schedule-calendar-transp` (RFC 6638): whether events count as busy time.
+    pub fn transparency(mut self, transparency: ScheduleTransparency) -> Self {
+        self.props.transparency = Some(transparency);
+        self
+    }
+
+    /// `true` when no property has been set.
+    pub fn is_empty(&self) -> bool {
+        self.props == CalendarProps::default()
+    }
+
+    /// Build the `PROPPATCH` body.
+    pub fn build(&self) -> String {
+        format!(
+            "<D:propertyupdate {COLLECTION_NAMESPACES}><D:set><D:prop>{}</D:prop></D:set></D:propertyupdate>",
+            self.props.to_xml()
+        )
+    }
+}
+
+/// Calendar collection properties shared by [`MkCalendar`] and [`CalendarPropPatch`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CalendarProps {
+    displayname: Option<String>,
+    description: Option<String>,
+    color: Option<String>,
+    order: Option<i32>,
+    timezone: Option<String>,
+    timezone_id: Option<String>,
+    transparency: Option<ScheduleTransparency>,
+}
+
+impl CalendarProps {
+    fn to_xml(&self) -> String {
+        let mut props = String::new();
+        if let Some(name) = &self.displayname {
+            props.push_str(&format!(
+                "<D:displayname>{}</D:displayname>",
+                escape_xml(name)
+            ));
+        }
+        if let Some(description) = &self.description {
+            props.push_str(&format!(
+                "<C:calendar-description>{}</C:calendar-description>",
+                escape_xml(description)
+            ));
+        }
+        if let Some(color) = &self.color {
+            props.push_str(&format!(
+                "<A:calendar-color>{}</A:calendar-color>",
+                escape_xml(color)
+            ));
+        }
+        if let Some(order) = self.order {
+            props.push_str(&format!("<A:calendar-order>{order}</A:calendar-order>"));
+        }
+        if let Some(timezone) = &self.timezone {
+            props.push_str(&format!(
+                "<C:calendar-timezone>{}</C:calendar-timezone>",
+                escape_xml(timezone)
+            ));
+        }
+        if let Some(tzid) = &self.timezone_id {
+            props.push_str(&format!(
+                "<C:calendar-timezone-id>{}</C:calendar-timezone-id>",
+                escape_xml(tzid)
+            ));
+        }
+        if let Some(transparency) = self.transparency {
+            props.push_str(&format!(
+                "<C:schedule-calendar-transp><C:{}/></C:schedule-calendar-transp>",
+                transparency.as_str()
+            ));
+        }
+        props
+    }
+}
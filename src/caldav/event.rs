@@ -0,0 +1,399 @@
+//! Typed builder for `VEVENT` calendar objects, ready to upload with
+//! [`crate::caldav::CalDavClient::put_if_none_match`].
+//!
+//! Text is escaped and lines are folded by the content-line serializer, so callers never
+//! assemble iCalendar by hand.
+
+use anyhow::{Result, anyhow};
+use bytes::Bytes;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "ical")]
+use crate::caldav::recurrence::RecurrenceRule;
+use crate::common::contentline::{Component, ContentLine, escape_text};
+use crate::common::datetime::{format_utc_datetime, parse_duration, parse_local_datetime};
+
+static UID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Builder for a `VCALENDAR` holding a single `VEVENT`.
+///
+/// A `UID` is generated unless one is set, and `DTSTAMP` is the build time. Date-time
+/// values are passed in iCalendar form: `20240101` (all-day), `20240101T090000Z` (UTC) or
+/// `20240101T090000` combined with [`tzid`](Self::tzid).
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::EventBuilder;
+///
+/// let event = EventBuilder::new("Team sync; weekly")
+///     .uid("sync-1@example.com")
+///     .start("20240101T090000")
+///     .end("20240101T093000")
+///     .tzid("Europe/Paris")
+///     .rrule("FREQ=WEEKLY;BYDAY=MO")
+///     .build()?;
+/// assert!(event.contains("SUMMARY:Team sync\\; weekly\r\n"));
+/// assert!(event.contains("DTSTART;TZID=Europe/Paris:20240101T090000\r\n"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventBuilder {
+    uid: String,
+    summary: String,
+    start: Option<String>,
+    end: Option<String>,
+    duration: Option<String>,
+    tzid: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+    status: Option<String>,
+    categories: Vec<String>,
+    organizer: Option<(String, Option<String>)>,
+    attendees: Vec<(String, Option<String>)>,
+    rrule: Option<String>,
+    rdates: Vec<String>,
+    exdates: Vec<String>,
+    alarms: Vec<(String, String)>,
+    properties: Vec<ContentLine>,
+}
+
+impl EventBuilder {
+    /// Start an event with the given `SUMMARY` and a generated `UID`.
+    pub fn new(summary: &str) -> Self {
+        Self {
+            uid: generate_uid(),
+            summary: summary.to_string(),
+            start: None,
+            end: None,
+            duration: None,
+            tzid: None,
+            description: None,
+            location: None,
+            status: None,
+            categories: Vec::new(),
+            organizer: None,
+            attendees: Vec::new(),
+            rrule: None,
+            rdates: Vec::new(),
+            exdates: Vec::new(),
+            alarms: Vec::new(),
+            properties: Vec::new(),
+        }
+    }
+
+    /// Replace the generated `UID`.
+    pub fn uid(mut self, uid: &str) -> Self {
+        self.uid = uid.to_string();
+        self
+    }
+
+    /// `DTSTART` (required).
+    pub fn start(mut self, start: &str) -> Self {
+        self.start = Some(start.to_string());
+        self
+    }
+
+    /// `DTEND`; exclusive with [`duration`](Self::duration).
+    pub fn end(mut self, end: &str) -> Self {
+        self.end = Some(end.to_string());
+        self
+    }
+
+    /// `DURATION`, e.g. `PT1H`; exclusive with [`end`](Self::end).
+    pub fn duration(mut self, duration: &str) -> Self {
+        self.duration = Some(duration.to_string());
+        self
+    }
+
+    /// All-day event from `start` to the exclusive `end` date, e.g. `20240101`–`20240102`.
+    pub fn all_day(self, start: &str, end: &str) -> Self {
+        self.start(start).end(end)
+    }
+
+    /// `TZID` applied to `DTSTART`, `DTEND`, `RDATE` and `EXDATE`, whose values are then local
+    /// times. The zone is referenced by name only; servers without time zones by reference
+    /// (RFC 7809) may also expect a matching `VTIMEZONE`.
+    pub fn tzid(mut self, tzid: &str) -> Self {
+        self.tzid = Some(tzid.to_string());
+        self
+    }
+
+    /// `DESCRIPTION`.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// `LOCATION`.
+    pub fn location(mut self, location: &str) -> Self {
+        self.location = Some(location.to_string());
+        self
+    }
+
+    /// `STATUS`, e.g. `CONFIRMED`, `TENTATIVE` or `CANCELLED`.
+    pub fn status(mut self, status: &str) -> Self {
+        self.status = Some(status.to_string());
+        self
+    }
+
+    /// Add a `CATEGORIES` value.
+    pub fn category(mut self, category: &str) -> Self {
+        self.categories.push(category.to_string());
+        self
+    }
+
+    /// `ORGANIZER`; a bare e-mail address gets a `mailto:` prefix.
+    pub fn organizer(mut self, address: &str, common_name: Option<&str>) -> Self {
+        self.organizer = Some((address.to_string(), common_name.map(str::to_string)));
+        self
+    }
+
+    /// Add an `ATTENDEE` (`REQ-PARTICIPANT`, `NEEDS-ACTION`, `RSVP=TRUE`).
+    pub fn attendee(mut self, address: &str, common_name: Option<&str>) -> Self {
+        self.attendees
+            .push((address.to_string(), common_name.map(str::to_string)));
+        self
+    }
+
+    /// `RRULE` value, e.g. `FREQ=WEEKLY;BYDAY=MO,WE;COUNT=10`.
+    pub fn rrule(mut self, rrule: &str) -> Self {
+        self.rrule = Some(rrule.to_string());
+        self
+    }
+
+    /// `RRULE` from a typed [`RecurrenceRule`].
+    #[cfg(feature = "ical")]
+    pub fn recurrence(mut self, rule: &RecurrenceRule) -> Self {
+        self.rrule = Some(rule.to_string());
+        self
+    }
+
+    /// Add an `RDATE`, in the same form as `DTSTART`.
+    pub fn rdate(mut self, date: &str) -> Self {
+        self.rdates.push(date.to_string());
+        self
+    }
+
+    /// Add an `EXDATE`, in the same form as `DTSTART`.
+    pub fn exdate(mut self, date: &str) -> Self {
+        self.exdates.push(date.to_string());
+        self
+    }
+
+    /// Add a `DISPLAY` alarm triggered relative to the start, e.g. `-PT15M`.
+    pub fn alarm(mut self, trigger: &str, description: &str) -> Self {
+        self.alarms
+            .push((trigger.to_string(), description.to_string()));
+        self
+    }
+
+    /// Add an arbitrary property to the `VEVENT`, e.g. `X-` extensions. The value is
+    /// written as is.
+    pub fn property(mut self, line: ContentLine) -> Self {
+        self.properties.push(line);
+        self
+    }
+
+    /// File name for the object within its calendar collection: the `UID` with characters
+    /// outside `[A-Za-z0-9._-]` replaced, plus `.ics`.
+    pub fn resource_name(&self) -> String {
+        let name: String = self
+            .uid
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{name}.ics")
+    }
+
+    /// Build the `VCALENDAR` text.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `DTSTART` is missing, a date-time or duration is malformed, `DTEND`
+    /// does not follow `DTSTART`, both `DTEND` and `DURATION` are set, `TZID` is combined
+    /// with `DATE` or UTC values, or the `RRULE` has no `FREQ`.
+    pub fn build(&self) -> Result<String> {
+        self.validate()?;
+        let start = self.start.as_deref().unwrap_or_default();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        let mut event = Component::new("VEVENT");
+        event.set_value("UID", self.uid.clone());
+        event.set_value("DTSTAMP", format_utc_datetime(now));
+        event.properties.push(self.date_line("DTSTART", start));
+        if let Some(end) = &self.end {
+            event.properties.push(self.date_line("DTEND", end));
+        }
+        if let Some(duration) = &self.duration {
+            event.set_value("DURATION", duration.clone());
+        }
+        event.set_value("SUMMARY", escape_text(&self.summary));
+        if let Some(description) = &self.description {
+            event.set_value("DESCRIPTION", escape_text(description));
+        }
+        if let Some(location) = &self.location {
+            event.set_value("LOCATION", escape_text(location));
+        }
+        if let Some(status) = &self.status {
+            event.set_value("STATUS", status.to_ascii_uppercase());
+        }
+        if !self.categories.is_empty() {
+            let categories: Vec<String> = self.categories.iter().map(|c| escape_text(c)).collect();
+            event.set_value("CATEGORIES", categories.join(","));
+        }
+        if let Some((address, common_name)) = &self.organizer {
+            let mut line = ContentLine::new("ORGANIZER", calendar_address(address));
+            if let Some(cn) = common_name {
+                line.set_param("CN", cn);
+            }
+            event.properties.push(line);
+        }
+        for (address, common_name) in &self.attendees {
+            let mut line = ContentLine::new("ATTENDEE", calendar_address(address));
+            if let Some(cn) = common_name {
+                line.set_param("CN", cn);
+            }
+            line.set_param("ROLE", "REQ-PARTICIPANT");
+            line.set_param("PARTSTAT", "NEEDS-ACTION");
+            line.set_param("RSVP", "TRUE");
+            event.properties.push(line);
+        }
+        if let Some(rrule) = &self.rrule {
+            event.set_value("RRULE", rrule.to_ascii_uppercase());
+        }
+        for rdate in &self.rdates {
+            event.properties.push(self.date_line("RDATE", rdate));
+        }
+        for exdate in &self.exdates {
+            event.properties.push(self.date_line("EXDATE", exdate));
+        }
+        event.properties.extend(self.properties.iter().cloned());
+        for (trigger, description) in &self.alarms {
+            let mut alarm = Component::new("VALARM");
+            alarm.set_value("ACTION", "DISPLAY");
+            alarm.set_value("TRIGGER", trigger.clone());
+            alarm.set_value("DESCRIPTION", escape_text(description));
+            event.components.push(alarm);
+        }
+
+        let mut calendar = Component::new("VCALENDAR");
+        calendar.properties.push(ContentLine::new("VERSION", "2.0"));
+        calendar
+            .properties
+            .push(ContentLine::new("PRODID", "-//fast-dav-rs//EN"));
+        calendar.components.push(event);
+        Ok(calendar.to_ics())
+    }
+
+    /// Build the `VCALENDAR` as a request body.
+    ///
+    /// # Errors
+    ///
+    /// See [`build`](Self::build).
+    pub fn build_bytes(&self) -> Result<Bytes> {
+        self.build().map(Bytes::from)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let start = self
+            .start
+            .as_deref()
+            .ok_or_else(|| anyhow!("event needs a DTSTART"))?;
+        let start_kind = value_kind(start).ok_or_else(|| anyhow!("invalid DTSTART {start:?}"))?;
+        if self.tzid.is_some() && start_kind != ValueKind::Local {
+            return Err(anyhow!("TZID needs local DTSTART, got {start:?}"));
+        }
+        for (name, value) in self
+            .end
+            .iter()
+            .map(|v| ("DTEND", v))
+            .chain(self.rdates.iter().map(|v| ("RDATE", v)))
+            .chain(self.exdates.iter().map(|v| ("EXDATE", v)))
+        {
+            if value_kind(value) != Some(start_kind) {
+                return Err(anyhow!("{name} {value:?} does not match DTSTART {start:?}"));
+            }
+        }
+        if let Some(end) = &self.end {
+            if self.duration.is_some() {
+                return Err(anyhow!("event cannot have both DTEND and DURATION"));
+            }
+            if parse_local_datetime(end) <= parse_local_datetime(start) {
+                return Err(anyhow!("DTEND {end:?} must be after DTSTART {start:?}"));
+            }
+        }
+        if let Some(duration) = &self.duration
+            && parse_duration(duration).is_none_or(|d| d < 0)
+        {
+            return Err(anyhow!("invalid DURATION {duration:?}"));
+        }
+        if let Some(rrule) = &self.rrule
+            && !rrule
+                .split(';')
+                .any(|part| part.to_ascii_uppercase().starts_with("FREQ="))
+        {
+            return Err(anyhow!("RRULE {rrule:?} has no FREQ"));
+        }
+        for (trigger, _) in &self.alarms {
+            if parse_duration(trigger.trim_start_matches(['+', '-'])).is_none() {
+                return Err(anyhow!("invalid alarm trigger {trigger:?}"));
+            }
+        }
+        Ok(())
+    }
+
+    fn date_line(&self, name: &str, value: &str) -> ContentLine {
+        let mut line = ContentLine::new(name, value.to_ascii_uppercase());
+        if value_kind(value) == Some(ValueKind::Date) {
+            line.set_param("VALUE", "DATE");
+        } else if let Some(tzid) = &self.tzid {
+            line.set_param("TZID", tzid);
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Date,
+    Local,
+    Utc,
+}
+
+fn value_kind(value: &str) -> Option<ValueKind> {
+    parse_local_datetime(value)?;
+    match value.len() {
+        8 => Some(ValueKind::Date),
+        15 => Some(ValueKind::Local),
+        _ => Some(ValueKind::Utc),
+    }
+}
+
+fn calendar_address(address: &str) -> String {
+    if address.contains(':') {
+        address.to_string()
+    } else {
+        format!("mailto:{address}")
+    }
+}
+
+/// Unique within the process and, through the timestamp, across runs.
+pub(crate) fn generate_uid() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let counter = UID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{nanos:x}-{counter}@fast-dav-rs")
+}
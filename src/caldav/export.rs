@@ -0,0 +1,98 @@
+//! Merging calendar objects into a single `VCALENDAR`, e.g. for `.ics` backups (see
+//! [`crate::caldav::CalDavClient::export_calendar`]).
+
+use std::collections::HashSet;
+
+use anyhow::{Result, anyhow};
+
+use crate::common::contentline::Component;
+
+/// Incremental merger of calendar objects into one `VCALENDAR`.
+///
+/// Each object's `VCALENDAR` wrapper (`VERSION`, `PRODID`, `CALSCALE`, `METHOD`, …) is
+/// dropped in favour of a single header, and every `VTIMEZONE` is written once, by TZID,
+/// before the first component that follows it. Output is produced piecewise so large
+/// calendars never need to be held in memory at once.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::CalendarMerger;
+///
+/// let mut merger = CalendarMerger::new();
+/// let mut ics = merger.header();
+/// ics.push_str(&merger.push("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:a\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n")?);
+/// ics.push_str(&merger.push("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:b\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n")?);
+/// ics.push_str(merger.footer());
+/// assert_eq!(ics.matches("BEGIN:VCALENDAR").count(), 1);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CalendarMerger {
+    timezones: HashSet<String>,
+}
+
+impl CalendarMerger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opening lines of the merged calendar.
+    pub fn header(&self) -> String {
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//fast-dav-rs//EN\r\nCALSCALE:GREGORIAN\r\n"
+            .to_string()
+    }
+
+    /// Closing line of the merged calendar.
+    pub fn footer(&self) -> &'static str {
+        "END:VCALENDAR\r\n"
+    }
+
+    /// Serialize the components of one calendar object, skipping time zones already written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `ics` is not a valid iCalendar stream of `VCALENDAR`s.
+    pub fn push(&mut self, ics: &str) -> Result<String> {
+        let mut out = String::new();
+        for calendar in Component::parse_all(ics)? {
+            if calendar.name != "VCALENDAR" {
+                return Err(anyhow!(
+                    "expected VCALENDAR, found top-level {}",
+                    calendar.name
+                ));
+            }
+            for component in calendar.components {
+                if component.name == "VTIMEZONE" {
+                    let Some(tzid) = component.value("TZID") else {
+                        continue;
+                    };
+                    if !self.timezones.insert(tzid.to_string()) {
+                        continue;
+                    }
+                }
+                out.push_str(&component.to_ics());
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Merge complete calendar objects into one `VCALENDAR` (see [`CalendarMerger`]).
+///
+/// # Errors
+///
+/// Returns an error when an object is not a valid iCalendar stream.
+pub fn merge_calendars<I, S>(objects: I) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut merger = CalendarMerger::new();
+    let mut ics = merger.header();
+    for object in objects {
+        ics.push_str(&merger.push(object.as_ref())?);
+    }
+    ics.push_str(merger.footer());
+    Ok(ics)
+}
@@ -0,0 +1,266 @@
+//! Typed view of iCalendar payloads (RFC 5545) returned in `calendar-data`.
+//!
+//! Available with the `ical` feature. Parsing is built on the crate's own content-line
+//! parser, so no extra dependency is pulled in. Only the commonly used properties are typed;
+//! the full [`Component`] tree stays available for everything else.
+
+use anyhow::{Result, anyhow};
+
+use crate::caldav::types::CalendarObject;
+use crate::common::contentline::{Component, ContentLine, split_text_list, unescape_text};
+
+/// A parsed `VCALENDAR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalendar {
+    pub prodid: Option<String>,
+    /// iTIP `METHOD`, present on scheduling messages.
+    pub method: Option<String>,
+    /// `VEVENT`, `VTODO` and `VJOURNAL` components, in document order.
+    pub components: Vec<ICalComponent>,
+    /// Embedded `VTIMEZONE` definitions, untouched.
+    pub timezones: Vec<Component>,
+    /// The complete component tree the typed view was built from.
+    pub raw: Component,
+}
+
+/// A `VEVENT`, `VTODO` or `VJOURNAL` with its commonly used properties typed.
+///
+/// Text values are unescaped; date-times keep their original form and `TZID`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalComponent {
+    /// Component name (`VEVENT`, `VTODO` or `VJOURNAL`).
+    pub kind: String,
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub status: Option<String>,
+    pub dtstart: Option<ICalDateTime>,
+    pub dtend: Option<ICalDateTime>,
+    /// `DUE` of a `VTODO`.
+    pub due: Option<ICalDateTime>,
+    /// `DURATION`, e.g. `PT1H`.
+    pub duration: Option<String>,
+    /// `RRULE` value, e.g. `FREQ=WEEKLY;BYDAY=MO`.
+    pub rrule: Option<String>,
+    pub rdates: Vec<ICalDateTime>,
+    pub exdates: Vec<ICalDateTime>,
+    /// `RECURRENCE-ID` of an overridden instance.
+    pub recurrence_id: Option<ICalDateTime>,
+    pub sequence: Option<u32>,
+    pub categories: Vec<String>,
+    pub organizer: Option<CalendarUser>,
+    pub attendees: Vec<Attendee>,
+    pub alarms: Vec<Alarm>,
+}
+
+/// A `DATE` or `DATE-TIME` value with its time zone reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ICalDateTime {
+    /// Raw value: `20240101` (date), `20240101T090000` (floating or `tzid`-local) or
+    /// `20240101T090000Z` (UTC).
+    pub value: String,
+    pub tzid: Option<String>,
+}
+
+impl ICalDateTime {
+    /// `true` for all-day `DATE` values.
+    pub fn is_date(&self) -> bool {
+        !self.value.contains('T')
+    }
+
+    /// `true` for UTC date-times (`…Z`).
+    pub fn is_utc(&self) -> bool {
+        self.value.ends_with('Z') || self.value.ends_with('z')
+    }
+
+    /// `true` for date-times with neither UTC marker nor `TZID`.
+    pub fn is_floating(&self) -> bool {
+        !self.is_date() && !self.is_utc() && self.tzid.is_none()
+    }
+
+    fn from_line(line: &ContentLine) -> Self {
+        Self {
+            value: line.value.trim().to_string(),
+            tzid: line.param("TZID").map(str::to_string),
+        }
+    }
+
+    /// Split a multi-valued `RDATE`/`EXDATE` line (comma-separated) sharing one `TZID`.
+    fn list_from_line(line: &ContentLine) -> Vec<Self> {
+        let tzid = line.param("TZID").map(str::to_string);
+        line.value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|value| Self {
+                value: value.to_string(),
+                tzid: tzid.clone(),
+            })
+            .collect()
+    }
+}
+
+/// A calendar user (`ORGANIZER`), e.g. `mailto:jane@example.com`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarUser {
+    pub address: String,
+    /// `CN` display name.
+    pub common_name: Option<String>,
+}
+
+/// An `ATTENDEE` with its scheduling parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attendee {
+    pub address: String,
+    pub common_name: Option<String>,
+    /// `PARTSTAT`, e.g. `ACCEPTED`; `None` means `NEEDS-ACTION`.
+    pub partstat: Option<String>,
+    /// `ROLE`, e.g. `REQ-PARTICIPANT`.
+    pub role: Option<String>,
+    pub rsvp: bool,
+}
+
+/// When a `VALARM` fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlarmTrigger {
+    /// Duration relative to the start (or the end when `related_end`), e.g. `-PT15M`.
+    Relative { duration: String, related_end: bool },
+    /// Absolute UTC date-time.
+    Absolute(ICalDateTime),
+}
+
+/// A `VALARM` sub-component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alarm {
+    /// `ACTION`, e.g. `DISPLAY`, `AUDIO` or `EMAIL`.
+    pub action: Option<String>,
+    pub trigger: Option<AlarmTrigger>,
+    pub description: Option<String>,
+}
+
+/// Parse an iCalendar payload into a typed [`ICalendar`].
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar or its top-level component is
+/// not a `VCALENDAR`.
+pub fn parse_icalendar(ics: &str) -> Result<ICalendar> {
+    let raw = Component::parse(ics)?;
+    if raw.name != "VCALENDAR" {
+        return Err(anyhow!("expected VCALENDAR, found {}", raw.name));
+    }
+    Ok(ICalendar {
+        prodid: raw.value("PRODID").map(str::to_string),
+        method: raw.value("METHOD").map(str::to_string),
+        components: raw
+            .components
+            .iter()
+            .filter(|c| matches!(c.name.as_str(), "VEVENT" | "VTODO" | "VJOURNAL"))
+            .map(parse_component)
+            .collect(),
+        timezones: raw.components_named("VTIMEZONE").cloned().collect(),
+        raw,
+    })
+}
+
+fn parse_component(component: &Component) -> ICalComponent {
+    let text = |name: &str| component.value(name).map(unescape_text);
+    let date = |name: &str| component.property(name).map(ICalDateTime::from_line);
+    ICalComponent {
+        kind: component.name.clone(),
+        uid: component.value("UID").map(str::to_string),
+        summary: text("SUMMARY"),
+        description: text("DESCRIPTION"),
+        location: text("LOCATION"),
+        status: component.value("STATUS").map(str::to_string),
+        dtstart: date("DTSTART"),
+        dtend: date("DTEND"),
+        due: date("DUE"),
+        duration: component.value("DURATION").map(str::to_string),
+        rrule: component.value("RRULE").map(str::to_string),
+        rdates: component
+            .properties_named("RDATE")
+            .flat_map(ICalDateTime::list_from_line)
+            .collect(),
+        exdates: component
+            .properties_named("EXDATE")
+            .flat_map(ICalDateTime::list_from_line)
+            .collect(),
+        recurrence_id: date("RECURRENCE-ID"),
+        sequence: component
+            .value("SEQUENCE")
+            .and_then(|s| s.trim().parse().ok()),
+        categories: component
+            .properties_named("CATEGORIES")
+            .flat_map(|p| split_text_list(&p.value))
+            .filter(|c| !c.is_empty())
+            .collect(),
+        organizer: component.property("ORGANIZER").map(|p| CalendarUser {
+            address: p.value.clone(),
+            common_name: p.param("CN").map(str::to_string),
+        }),
+        attendees: component
+            .properties_named("ATTENDEE")
+            .map(|p| Attendee {
+                address: p.value.clone(),
+                common_name: p.param("CN").map(str::to_string),
+                partstat: p.param("PARTSTAT").map(str::to_string),
+                role: p.param("ROLE").map(str::to_string),
+                rsvp: p
+                    .param("RSVP")
+                    .is_some_and(|v| v.eq_ignore_ascii_case("TRUE")),
+            })
+            .collect(),
+        alarms: component
+            .components_named("VALARM")
+            .map(parse_alarm)
+            .collect(),
+    }
+}
+
+fn parse_alarm(alarm: &Component) -> Alarm {
+    let trigger = alarm.property("TRIGGER").map(|line| {
+        if line
+            .param("VALUE")
+            .is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME"))
+        {
+            AlarmTrigger::Absolute(ICalDateTime::from_line(line))
+        } else {
+            AlarmTrigger::Relative {
+                duration: line.value.trim().to_string(),
+                related_end: line
+                    .param("RELATED")
+                    .is_some_and(|v| v.eq_ignore_ascii_case("END")),
+            }
+        }
+    });
+    Alarm {
+        action: alarm.value("ACTION").map(str::to_string),
+        trigger,
+        description: alarm.value("DESCRIPTION").map(unescape_text),
+    }
+}
+
+impl CalendarObject {
+    /// Parse [`calendar_data`](Self::calendar_data) into a typed [`ICalendar`].
+    ///
+    /// With the `jcal` feature, jCal data (see [`CalendarData::json`](crate::caldav::CalendarData::json))
+    /// is recognised and parsed as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the object was fetched without `calendar-data` or the data is
+    /// not valid iCalendar.
+    pub fn parse_ical(&self) -> Result<ICalendar> {
+        let data = self
+            .calendar_data
+            .as_deref()
+            .ok_or_else(|| anyhow!("{} has no calendar-data", self.href))?;
+        #[cfg(feature = "jcal")]
+        if data.trim_start().starts_with('[') {
+            return crate::caldav::jcal::parse_jcal(data);
+        }
+        parse_icalendar(data)
+    }
+}
@@ -0,0 +1,226 @@
+//! Editing single instances of recurring events and todos (RFC 5545 §3.8.4.4).
+//!
+//! An instance is addressed by its `RECURRENCE-ID`: the original start of the occurrence,
+//! written like the master's `DTSTART` (`20240108T090000` with the master's `TZID`,
+//! `20240108T090000Z` for UTC masters, `20240108` for all-day ones). Whether the rule actually
+//! produces that occurrence is not checked.
+
+use anyhow::{Result, anyhow};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::common::contentline::{Component, ContentLine};
+use crate::common::datetime::{format_utc_datetime, parse_local_datetime};
+
+/// Create or update the override of the instance starting at `recurrence_id` and apply
+/// `edit` to it.
+///
+/// A new override is a copy of the master without its recurrence properties, moved to the
+/// instance (`DTSTART`, and `DTEND`/`DUE` by the same offset). `DTSTAMP` is refreshed;
+/// anything else, such as `SEQUENCE`, is up to `edit`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::update_instance;
+///
+/// let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:w\r\n\
+///            DTSTART;TZID=Europe/Paris:20240101T090000\r\nDTEND;TZID=Europe/Paris:20240101T093000\r\n\
+///            RRULE:FREQ=WEEKLY\r\nSUMMARY:Sync\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+/// let updated = update_instance(ics, "20240108T090000", |instance| {
+///     instance.set_value("SUMMARY", "Sync (moved)");
+///     Ok(())
+/// })?;
+/// assert!(updated.contains("RECURRENCE-ID;TZID=Europe/Paris:20240108T090000\r\n"));
+/// assert!(updated.contains("DTEND;TZID=Europe/Paris:20240108T093000\r\n"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar, has no recurring `VEVENT` or
+/// `VTODO`, `recurrence_id` does not have the form of the master's `DTSTART`, or `edit` fails.
+pub fn update_instance(
+    ics: &str,
+    recurrence_id: &str,
+    edit: impl FnOnce(&mut Component) -> Result<()>,
+) -> Result<String> {
+    let mut calendars = Component::parse_all(ics)?;
+    let (calendar, master) = find_master(&calendars)?;
+    let master_start = calendars[calendar].components[master]
+        .property("DTSTART")
+        .cloned()
+        .ok_or_else(|| anyhow!("recurring component has no DTSTART"))?;
+    let recurrence_id = normalize(recurrence_id, &master_start.value)?;
+    let components = &mut calendars[calendar].components;
+    let now = format_utc_datetime(now());
+
+    let position = match override_position(components, &components[master].name, &recurrence_id) {
+        Some(position) => position,
+        None => {
+            let instance = new_override(&components[master], &master_start, &recurrence_id)?;
+            components.insert(master + 1, instance);
+            master + 1
+        }
+    };
+    let instance = &mut components[position];
+    edit(instance)?;
+    instance.set_value("DTSTAMP", now);
+    Ok(calendars.iter().map(Component::to_ics).collect())
+}
+
+/// Cancel the instance starting at `recurrence_id`: add it as an `EXDATE` to the master and
+/// drop its override, if any.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar, has no recurring `VEVENT` or
+/// `VTODO`, or `recurrence_id` does not have the form of the master's `DTSTART`.
+pub fn delete_instance(ics: &str, recurrence_id: &str) -> Result<String> {
+    let mut calendars = Component::parse_all(ics)?;
+    let (calendar, master) = find_master(&calendars)?;
+    let components = &mut calendars[calendar].components;
+    let master_start = components[master]
+        .property("DTSTART")
+        .cloned()
+        .ok_or_else(|| anyhow!("recurring component has no DTSTART"))?;
+    let recurrence_id = normalize(recurrence_id, &master_start.value)?;
+
+    if let Some(position) = override_position(components, &components[master].name, &recurrence_id)
+    {
+        components.remove(position);
+    }
+    let master = &mut components[master];
+    let already_excluded = master.properties_named("EXDATE").any(|line| {
+        line.value
+            .split(',')
+            .any(|v| same_instant(v.trim(), &recurrence_id))
+    });
+    if !already_excluded {
+        let mut exdate = retimed(&master_start, "EXDATE", recurrence_id);
+        exdate.remove_param("VALUE");
+        if !exdate.value.contains('T') {
+            exdate.set_param("VALUE", "DATE");
+        }
+        master.properties.push(exdate);
+    }
+    master.set_value("DTSTAMP", format_utc_datetime(now()));
+    Ok(calendars.iter().map(Component::to_ics).collect())
+}
+
+/// Indices of the calendar and component holding the recurring master.
+fn find_master(calendars: &[Component]) -> Result<(usize, usize)> {
+    calendars
+        .iter()
+        .enumerate()
+        .find_map(|(i, calendar)| {
+            calendar
+                .components
+                .iter()
+                .position(|c| {
+                    matches!(c.name.as_str(), "VEVENT" | "VTODO")
+                        && c.property("RECURRENCE-ID").is_none()
+                        && (c.property("RRULE").is_some() || c.property("RDATE").is_some())
+                })
+                .map(|j| (i, j))
+        })
+        .ok_or_else(|| anyhow!("calendar object contains no recurring VEVENT or VTODO"))
+}
+
+fn override_position(components: &[Component], kind: &str, recurrence_id: &str) -> Option<usize> {
+    components.iter().position(|c| {
+        c.name == kind
+            && c.value("RECURRENCE-ID")
+                .is_some_and(|v| same_instant(v.trim(), recurrence_id))
+    })
+}
+
+fn new_override(
+    master: &Component,
+    master_start: &ContentLine,
+    recurrence_id: &str,
+) -> Result<Component> {
+    let offset = parse_local_datetime(recurrence_id)
+        .zip(parse_local_datetime(&master_start.value))
+        .map(|(instance, start)| instance - start)
+        .ok_or_else(|| anyhow!("invalid DTSTART {}", master_start.value))?;
+
+    let mut instance = master.clone();
+    for name in ["RRULE", "RDATE", "EXDATE", "EXRULE"] {
+        instance.remove_properties(name);
+    }
+    for line in &mut instance.properties {
+        match line.base_name() {
+            "DTSTART" => line.value = recurrence_id.to_string(),
+            "DTEND" | "DUE" => {
+                line.value = shift(&line.value, offset)
+                    .ok_or_else(|| anyhow!("invalid {} {}", line.name, line.value))?;
+            }
+            _ => {}
+        }
+    }
+    let position = instance
+        .properties
+        .iter()
+        .position(|p| p.base_name() == "DTSTART")
+        .map_or(instance.properties.len(), |p| p + 1);
+    instance.properties.insert(
+        position,
+        retimed(master_start, "RECURRENCE-ID", recurrence_id.to_string()),
+    );
+    Ok(instance)
+}
+
+/// `line` (with its `TZID`/`VALUE` parameters) renamed and given a new value.
+fn retimed(line: &ContentLine, name: &str, value: String) -> ContentLine {
+    let mut line = line.clone();
+    line.name = name.to_string();
+    line.value = value;
+    line
+}
+
+/// Bring a user-supplied `RECURRENCE-ID` into the form of the master's `DTSTART`.
+fn normalize(recurrence_id: &str, master_start: &str) -> Result<String> {
+    let recurrence_id = recurrence_id.trim();
+    let master_start = master_start.trim();
+    if parse_local_datetime(recurrence_id).is_none() {
+        return Err(anyhow!("invalid RECURRENCE-ID {recurrence_id}"));
+    }
+    let date = !master_start.contains('T');
+    let utc = master_start.ends_with('Z');
+    match (
+        date,
+        recurrence_id.contains('T'),
+        recurrence_id.ends_with('Z'),
+    ) {
+        (true, false, _) => Ok(recurrence_id.to_string()),
+        (false, true, true) if utc => Ok(recurrence_id.to_string()),
+        (false, true, false) if utc => Ok(format!("{recurrence_id}Z")),
+        (false, true, false) => Ok(recurrence_id.to_string()),
+        _ => Err(anyhow!(
+            "RECURRENCE-ID {recurrence_id} does not match the form of DTSTART {master_start}"
+        )),
+    }
+}
+
+fn same_instant(a: &str, b: &str) -> bool {
+    parse_local_datetime(a).is_some() && parse_local_datetime(a) == parse_local_datetime(b)
+}
+
+/// Move a `DATE` or `DATE-TIME` value by `offset` seconds, keeping its form.
+fn shift(value: &str, offset: i64) -> Option<String> {
+    let formatted = format_utc_datetime(parse_local_datetime(value)? + offset);
+    Some(if !value.contains('T') {
+        formatted[..8].to_string()
+    } else if value.ends_with('Z') {
+        formatted
+    } else {
+        formatted.trim_end_matches('Z').to_string()
+    })
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
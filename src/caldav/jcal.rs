@@ -0,0 +1,178 @@
+//! jCal (RFC 7265, `application/calendar+json`) support.
+//!
+//! jCal documents are converted into the crate's iCalendar model, so calendar data
+//! requested as JSON (see [`crate::caldav::CalendarData::json`]) parses into the same
+//! [`ICalendar`] as `text/calendar` data.
+
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+use crate::caldav::ical::{ICalendar, parse_icalendar};
+use crate::common::contentline::{Component, ContentLine, Param, escape_text};
+
+/// Parse a jCal document into a typed [`ICalendar`].
+///
+/// # Errors
+///
+/// Returns an error when `json` is not valid jCal.
+pub fn parse_jcal(json: &str) -> Result<ICalendar> {
+    parse_icalendar(&jcal_to_ics(json)?)
+}
+
+/// Convert a jCal document into iCalendar text (CRLF line endings, folded).
+///
+/// # Errors
+///
+/// Returns an error when `json` is not valid jCal.
+pub fn jcal_to_ics(json: &str) -> Result<String> {
+    Ok(jcal_to_component(json)?.to_ics())
+}
+
+/// Convert a jCal document into a generic [`Component`] tree.
+///
+/// # Errors
+///
+/// Returns an error when `json` is not valid jCal.
+pub fn jcal_to_component(json: &str) -> Result<Component> {
+    let value: Value = serde_json::from_str(json).map_err(|e| anyhow!("invalid jCal JSON: {e}"))?;
+    component(&value)
+}
+
+fn component(value: &Value) -> Result<Component> {
+    let [name, properties, components] = value
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or_else(|| anyhow!("jCal component must be an array"))?
+    else {
+        return Err(anyhow!(
+            "jCal component must be [name, properties, components]"
+        ));
+    };
+    let name = name
+        .as_str()
+        .ok_or_else(|| anyhow!("jCal component name must be a string"))?;
+    let mut out = Component::new(name);
+    for p in array(properties, "properties")? {
+        out.properties.push(property(p)?);
+    }
+    for c in array(components, "components")? {
+        out.components.push(component(c)?);
+    }
+    Ok(out)
+}
+
+fn property(value: &Value) -> Result<ContentLine> {
+    let items = array(value, "property")?;
+    let [name, params, kind, values @ ..] = items.as_slice() else {
+        return Err(anyhow!(
+            "jCal property must be [name, parameters, type, value...]"
+        ));
+    };
+    let name = name
+        .as_str()
+        .ok_or_else(|| anyhow!("jCal property name must be a string"))?;
+    let kind = kind
+        .as_str()
+        .ok_or_else(|| anyhow!("jCal type of {name} must be a string"))?
+        .to_ascii_lowercase();
+
+    let rendered = values
+        .iter()
+        .map(|v| render_value(v, &kind))
+        .collect::<Result<Vec<_>>>()?;
+    let mut line = ContentLine::new(name, rendered.join(","));
+
+    let params = params
+        .as_object()
+        .ok_or_else(|| anyhow!("jCal parameters of {name} must be an object"))?;
+    for (pname, pvalue) in params {
+        let values = match pvalue {
+            Value::Array(values) => values.iter().map(scalar).collect(),
+            other => vec![scalar(other)],
+        };
+        line.params.push(Param {
+            name: pname.to_ascii_uppercase(),
+            values,
+        });
+    }
+    // Types that are never the default of the properties using them must be announced.
+    if matches!(kind.as_str(), "date" | "period" | "binary") && line.param("VALUE").is_none() {
+        line.set_param("VALUE", kind.to_ascii_uppercase());
+    }
+    Ok(line)
+}
+
+fn render_value(value: &Value, kind: &str) -> Result<String> {
+    match (kind, value) {
+        // FREQ goes first for RFC 2445 era parsers.
+        ("recur", Value::Object(parts)) => Ok(parts
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case("freq"))
+            .chain(
+                parts
+                    .iter()
+                    .filter(|(key, _)| !key.eq_ignore_ascii_case("freq")),
+            )
+            .map(|(key, part)| {
+                let rendered = match part {
+                    Value::Array(items) => items
+                        .iter()
+                        .map(|v| recur_part(key, v))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    other => recur_part(key, other),
+                };
+                format!("{}={rendered}", key.to_ascii_uppercase())
+            })
+            .collect::<Vec<_>>()
+            .join(";")),
+        ("period", Value::Array(bounds)) => Ok(bounds
+            .iter()
+            .map(|b| compact_datetime(&scalar(b)))
+            .collect::<Vec<_>>()
+            .join("/")),
+        // Structured values (e.g. REQUEST-STATUS) are arrays of components.
+        (_, Value::Array(parts)) => Ok(parts
+            .iter()
+            .map(|p| render_value(p, kind))
+            .collect::<Result<Vec<_>>>()?
+            .join(";")),
+        ("text", Value::String(text)) => Ok(escape_text(text)),
+        ("date" | "date-time" | "time", Value::String(text)) => Ok(compact_datetime(text)),
+        ("utc-offset", Value::String(text)) => Ok(text.replace(':', "")),
+        ("boolean", Value::Bool(flag)) => Ok(if *flag { "TRUE" } else { "FALSE" }.to_string()),
+        (_, Value::Object(_)) => Err(anyhow!("unexpected object value for jCal type {kind}")),
+        (_, other) => Ok(scalar(other)),
+    }
+}
+
+fn recur_part(key: &str, value: &Value) -> String {
+    let text = scalar(value);
+    if key.eq_ignore_ascii_case("until") {
+        compact_datetime(&text)
+    } else {
+        text
+    }
+}
+
+/// `2024-01-05T09:00:00Z` → `20240105T090000Z`; durations and other values pass through.
+fn compact_datetime(text: &str) -> String {
+    if text.starts_with(['P', '+', '-']) {
+        return text.to_string();
+    }
+    text.chars().filter(|c| !matches!(c, '-' | ':')).collect()
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn array<'a>(value: &'a Value, what: &str) -> Result<&'a Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| anyhow!("jCal {what} must be an array"))
+}
@@ -0,0 +1,60 @@
+//! Journal (`VJOURNAL`) helpers: query filters and mapping into typed [`Journal`] entries.
+
+use anyhow::Result;
+
+use crate::caldav::query::CompFilter;
+use crate::caldav::types::{CalendarObject, Journal};
+use crate::common::contentline::{Component, split_text_list, unescape_text};
+
+/// `VJOURNAL` filter, optionally restricted to entries whose `DTSTART` falls in
+/// `[start, end)` (iCalendar UTC date-times).
+pub fn journals_filter(start: Option<&str>, end: Option<&str>) -> CompFilter {
+    CompFilter::new("VJOURNAL").time_range(start, end)
+}
+
+/// Parse the `VJOURNAL` components of a calendar object.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar.
+pub fn parse_journals(href: &str, etag: Option<&str>, ics: &str) -> Result<Vec<Journal>> {
+    let calendars = Component::parse_all(ics)?;
+    Ok(calendars
+        .iter()
+        .flat_map(|c| c.components_named("VJOURNAL"))
+        .map(|journal| {
+            let descriptions: Vec<String> = journal
+                .properties_named("DESCRIPTION")
+                .map(|p| unescape_text(&p.value))
+                .collect();
+            Journal {
+                href: href.to_string(),
+                etag: etag.map(str::to_string),
+                uid: journal.value("UID").map(str::to_string),
+                summary: journal.value("SUMMARY").map(unescape_text),
+                description: (!descriptions.is_empty()).then(|| descriptions.join("\n\n")),
+                dtstart: journal.value("DTSTART").map(str::to_string),
+                status: journal.value("STATUS").map(str::to_string),
+                categories: journal
+                    .properties_named("CATEGORIES")
+                    .flat_map(|p| split_text_list(&p.value))
+                    .filter(|c| !c.is_empty())
+                    .collect(),
+                recurrence_id: journal.value("RECURRENCE-ID").map(str::to_string),
+            }
+        })
+        .collect())
+}
+
+/// Map `REPORT` results into journal entries, skipping objects without `calendar-data`,
+/// invalid iCalendar, or no `VJOURNAL`.
+pub fn map_journals(objects: Vec<CalendarObject>) -> Vec<Journal> {
+    objects
+        .into_iter()
+        .filter_map(|o| {
+            let data = o.calendar_data.as_deref()?;
+            parse_journals(&o.href, o.etag.as_deref(), data).ok()
+        })
+        .flatten()
+        .collect()
+}
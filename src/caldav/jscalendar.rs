@@ -0,0 +1,1210 @@
+//! JSCalendar (RFC 8984) conversion of iCalendar objects.
+//!
+//! Available with the `jscalendar` feature. [`JsEvent`] and [`JsTask`] mirror the JSCalendar
+//! `Event` and `Task` objects: they are built from a typed [`ICalComponent`], serialize back
+//! to iCalendar for upload, and convert to and from their JSON form, so JMAP-style
+//! applications can keep their own data model on top of a CalDAV store.
+//!
+//! Only the properties of the typed iCalendar view are mapped. Date-times are wall-clock
+//! values in their time zone (no time zone database is involved), so a `DTEND` in another
+//! zone than `DTSTART`, or an `UNTIL` in UTC, is carried over by its wall-clock value.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value, json};
+
+use crate::caldav::ical::{Alarm, AlarmTrigger, ICalComponent, ICalDateTime, ICalendar};
+use crate::caldav::recurrence::{RecurrenceRule, Weekday};
+use crate::caldav::types::CalendarObject;
+use crate::common::contentline::{Component, ContentLine, escape_text};
+use crate::common::datetime::{format_utc_datetime, parse_local_datetime};
+
+/// Time zone JSCalendar uses for UTC date-times.
+const UTC: &str = "Etc/UTC";
+
+/// A JSCalendar `Event`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::{JsEvent, parse_icalendar};
+///
+/// let calendar = parse_icalendar(
+///     "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:a@example.com\r\n\
+///      DTSTART;TZID=Europe/Paris:20240105T090000\r\nDTEND;TZID=Europe/Paris:20240105T103000\r\n\
+///      SUMMARY:Review\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+/// )?;
+/// let event = JsEvent::from_ical(&calendar.components[0])?;
+/// assert_eq!(event.start, "2024-01-05T09:00:00");
+/// assert_eq!(event.duration.as_deref(), Some("PT1H30M"));
+/// assert_eq!(event.to_json()["timeZone"], "Europe/Paris");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsEvent {
+    pub uid: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// `LocalDateTime` start, e.g. `2024-01-05T09:00:00`.
+    pub start: String,
+    /// Time zone of the date-times; `None` for floating time, `Etc/UTC` for UTC.
+    pub time_zone: Option<String>,
+    /// e.g. `PT1H`; all-day events without an end last `P1D`.
+    pub duration: Option<String>,
+    /// `true` for all-day events.
+    pub show_without_time: bool,
+    /// `confirmed`, `tentative` or `cancelled`.
+    pub status: Option<String>,
+    pub location: Option<String>,
+    pub keywords: Vec<String>,
+    pub recurrence: JsRecurrence,
+    pub sequence: Option<u32>,
+    pub participants: Vec<JsParticipant>,
+    pub alerts: Vec<JsAlert>,
+}
+
+/// A JSCalendar `Task`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsTask {
+    pub uid: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// `LocalDateTime` start, if any.
+    pub start: Option<String>,
+    /// `LocalDateTime` due date, if any.
+    pub due: Option<String>,
+    /// Time zone of the date-times; `None` for floating time, `Etc/UTC` for UTC.
+    pub time_zone: Option<String>,
+    /// `true` when the dates carry no time.
+    pub show_without_time: bool,
+    /// `needs-action`, `in-process`, `completed`, `failed` or `cancelled`.
+    pub progress: Option<String>,
+    pub keywords: Vec<String>,
+    pub recurrence: JsRecurrence,
+    pub sequence: Option<u32>,
+    pub participants: Vec<JsParticipant>,
+    pub alerts: Vec<JsAlert>,
+}
+
+/// Recurrence of an event or task. Dates are `LocalDateTime`s in the object's time zone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsRecurrence {
+    /// `recurrenceRules`; `UNTIL` keeps its iCalendar form.
+    pub rules: Vec<RecurrenceRule>,
+    /// `recurrenceId` of an overridden instance.
+    pub recurrence_id: Option<String>,
+    /// `recurrenceOverrides` marked `excluded` (`EXDATE`).
+    pub excluded: Vec<String>,
+    /// Empty `recurrenceOverrides` adding an instance (`RDATE`).
+    pub added: Vec<String>,
+}
+
+/// A JSCalendar `Participant`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsParticipant {
+    pub name: Option<String>,
+    /// Calendar user address (`sendTo`), e.g. `mailto:jane@example.com`.
+    pub address: String,
+    /// e.g. `owner`, `attendee`, `chair`, `optional` or `informational`.
+    pub roles: Vec<String>,
+    /// e.g. `accepted`; `None` for an organizer that does not attend.
+    pub participation_status: Option<String>,
+    pub expect_reply: bool,
+}
+
+impl JsParticipant {
+    /// `true` when the participant has `role`.
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r.eq_ignore_ascii_case(role))
+    }
+}
+
+/// A JSCalendar `Alert`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsAlert {
+    pub trigger: JsTrigger,
+    /// `display` or `email`.
+    pub action: String,
+}
+
+/// When a [`JsAlert`] fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsTrigger {
+    /// Signed duration relative to the start (or the end when `relative_to_end`).
+    Offset {
+        offset: String,
+        relative_to_end: bool,
+    },
+    /// `UTCDateTime`, e.g. `2024-01-05T08:45:00Z`.
+    Absolute(String),
+}
+
+/// A JSCalendar object converted from a calendar component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsCalendarObject {
+    Event(JsEvent),
+    Task(JsTask),
+}
+
+impl JsEvent {
+    /// Convert a typed `VEVENT`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the component is not a `VEVENT`, has no `UID` or `DTSTART`, or its
+    /// `RRULE` cannot be parsed.
+    pub fn from_ical(component: &ICalComponent) -> Result<Self> {
+        if component.kind != "VEVENT" {
+            return Err(anyhow!("expected VEVENT, found {}", component.kind));
+        }
+        let uid = required_uid(component)?;
+        let start = component
+            .dtstart
+            .as_ref()
+            .ok_or_else(|| anyhow!("VEVENT {uid} has no DTSTART"))?;
+        let duration = component
+            .duration
+            .clone()
+            .or_else(|| {
+                let end = component.dtend.as_ref()?;
+                let seconds =
+                    parse_local_datetime(&end.value)? - parse_local_datetime(&start.value)?;
+                Some(format_duration(seconds))
+            })
+            .or_else(|| start.is_date().then(|| "P1D".to_string()));
+        Ok(Self {
+            title: component.summary.clone().unwrap_or_default(),
+            description: component.description.clone(),
+            start: local_datetime(&start.value),
+            time_zone: time_zone(start),
+            duration,
+            show_without_time: start.is_date(),
+            status: component.status.as_deref().map(str::to_ascii_lowercase),
+            location: component.location.clone(),
+            keywords: component.categories.clone(),
+            recurrence: recurrence_from_ical(component)?,
+            sequence: component.sequence,
+            participants: participants_from_ical(component),
+            alerts: alerts_from_ical(&component.alarms),
+            uid,
+        })
+    }
+
+    /// Serialize as a `VCALENDAR` holding one `VEVENT`, ready for upload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a date is not a valid `LocalDateTime`.
+    pub fn to_ical(&self) -> Result<String> {
+        let time_zone = self.time_zone.as_deref();
+        let mut event = Component::new("VEVENT");
+        event.set_value("UID", self.uid.clone());
+        event.set_value("DTSTAMP", format_utc_datetime(now()));
+        event.properties.push(date_line(
+            "DTSTART",
+            &self.start,
+            time_zone,
+            self.show_without_time,
+        )?);
+        if let Some(duration) = &self.duration {
+            event.set_value("DURATION", duration.clone());
+        }
+        write_text(&mut event, &self.title, self.description.as_deref());
+        if let Some(location) = &self.location {
+            event.set_value("LOCATION", escape_text(location));
+        }
+        if let Some(status) = &self.status {
+            event.set_value("STATUS", status.to_ascii_uppercase());
+        }
+        write_common(
+            &mut event,
+            &self.keywords,
+            self.sequence,
+            &self.participants,
+        );
+        write_recurrence(
+            &mut event,
+            &self.recurrence,
+            time_zone,
+            self.show_without_time,
+        )?;
+        write_alerts(&mut event, &self.alerts, &self.title)?;
+        Ok(wrap(event))
+    }
+
+    /// The JSCalendar JSON form (`"@type": "Event"`).
+    pub fn to_json(&self) -> Value {
+        let mut map = Map::new();
+        map.insert("@type".into(), json!("Event"));
+        set_text(
+            &mut map,
+            &self.uid,
+            &self.title,
+            self.description.as_deref(),
+        );
+        map.insert("start".into(), json!(self.start));
+        map.insert("timeZone".into(), json!(self.time_zone));
+        if let Some(duration) = &self.duration {
+            map.insert("duration".into(), json!(duration));
+        }
+        if self.show_without_time {
+            map.insert("showWithoutTime".into(), json!(true));
+        }
+        if let Some(status) = &self.status {
+            map.insert("status".into(), json!(status));
+        }
+        if let Some(location) = &self.location {
+            map.insert(
+                "locations".into(),
+                json!({ "1": { "@type": "Location", "name": location } }),
+            );
+        }
+        set_common(
+            &mut map,
+            &self.keywords,
+            self.sequence,
+            &self.participants,
+            &self.alerts,
+        );
+        set_recurrence(&mut map, &self.recurrence);
+        Value::Object(map)
+    }
+
+    /// Read the JSCalendar JSON form. Keywords and roles are JSON sets, so their order is
+    /// not preserved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `value` is not a JSCalendar `Event` with `uid` and `start`, or a
+    /// recurrence rule is invalid.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let map = object_of_type(value, "Event")?;
+        let uid = string(map, "uid").ok_or_else(|| anyhow!("JSCalendar Event has no uid"))?;
+        let start =
+            string(map, "start").ok_or_else(|| anyhow!("JSCalendar Event {uid} has no start"))?;
+        let time_zone = string(map, "timeZone");
+        let show_without_time = map
+            .get("showWithoutTime")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        Ok(Self {
+            title: string(map, "title").unwrap_or_default(),
+            description: string(map, "description"),
+            start,
+            duration: string(map, "duration"),
+            show_without_time,
+            status: string(map, "status"),
+            location: map
+                .get("locations")
+                .and_then(Value::as_object)
+                .and_then(|locations| locations.values().find_map(|l| l.get("name")))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            keywords: keywords_from_json(map),
+            recurrence: recurrence_from_json(map, time_zone.is_some() && !show_without_time)?,
+            sequence: sequence_from_json(map),
+            participants: participants_from_json(map),
+            alerts: alerts_from_json(map),
+            time_zone,
+            uid,
+        })
+    }
+}
+
+impl JsTask {
+    /// Convert a typed `VTODO`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the component is not a `VTODO`, has no `UID`, or its `RRULE`
+    /// cannot be parsed.
+    pub fn from_ical(component: &ICalComponent) -> Result<Self> {
+        if component.kind != "VTODO" {
+            return Err(anyhow!("expected VTODO, found {}", component.kind));
+        }
+        let uid = required_uid(component)?;
+        let anchor = component.dtstart.as_ref().or(component.due.as_ref());
+        Ok(Self {
+            title: component.summary.clone().unwrap_or_default(),
+            description: component.description.clone(),
+            start: component.dtstart.as_ref().map(|d| local_datetime(&d.value)),
+            due: component.due.as_ref().map(|d| local_datetime(&d.value)),
+            time_zone: anchor.and_then(time_zone),
+            show_without_time: anchor.is_some_and(ICalDateTime::is_date),
+            progress: component.status.as_deref().map(str::to_ascii_lowercase),
+            keywords: component.categories.clone(),
+            recurrence: recurrence_from_ical(component)?,
+            sequence: component.sequence,
+            participants: participants_from_ical(component),
+            alerts: alerts_from_ical(&component.alarms),
+            uid,
+        })
+    }
+
+    /// Serialize as a `VCALENDAR` holding one `VTODO`, ready for upload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a date is not a valid `LocalDateTime`.
+    pub fn to_ical(&self) -> Result<String> {
+        let time_zone = self.time_zone.as_deref();
+        let mut todo = Component::new("VTODO");
+        todo.set_value("UID", self.uid.clone());
+        todo.set_value("DTSTAMP", format_utc_datetime(now()));
+        if let Some(start) = &self.start {
+            todo.properties.push(date_line(
+                "DTSTART",
+                start,
+                time_zone,
+                self.show_without_time,
+            )?);
+        }
+        if let Some(due) = &self.due {
+            todo.properties
+                .push(date_line("DUE", due, time_zone, self.show_without_time)?);
+        }
+        write_text(&mut todo, &self.title, self.description.as_deref());
+        if let Some(progress) = &self.progress {
+            // `failed` has no iCalendar counterpart.
+            let status = match progress.as_str() {
+                "failed" => "CANCELLED".to_string(),
+                other => other.to_ascii_uppercase(),
+            };
+            todo.set_value("STATUS", status);
+        }
+        write_common(&mut todo, &self.keywords, self.sequence, &self.participants);
+        write_recurrence(
+            &mut todo,
+            &self.recurrence,
+            time_zone,
+            self.show_without_time,
+        )?;
+        write_alerts(&mut todo, &self.alerts, &self.title)?;
+        Ok(wrap(todo))
+    }
+
+    /// The JSCalendar JSON form (`"@type": "Task"`).
+    pub fn to_json(&self) -> Value {
+        let mut map = Map::new();
+        map.insert("@type".into(), json!("Task"));
+        set_text(
+            &mut map,
+            &self.uid,
+            &self.title,
+            self.description.as_deref(),
+        );
+        if let Some(start) = &self.start {
+            map.insert("start".into(), json!(start));
+        }
+        if let Some(due) = &self.due {
+            map.insert("due".into(), json!(due));
+        }
+        map.insert("timeZone".into(), json!(self.time_zone));
+        if self.show_without_time {
+            map.insert("showWithoutTime".into(), json!(true));
+        }
+        if let Some(progress) = &self.progress {
+            map.insert("progress".into(), json!(progress));
+        }
+        set_common(
+            &mut map,
+            &self.keywords,
+            self.sequence,
+            &self.participants,
+            &self.alerts,
+        );
+        set_recurrence(&mut map, &self.recurrence);
+        Value::Object(map)
+    }
+
+    /// Read the JSCalendar JSON form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `value` is not a JSCalendar `Task` with a `uid`, or a recurrence
+    /// rule is invalid.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let map = object_of_type(value, "Task")?;
+        let uid = string(map, "uid").ok_or_else(|| anyhow!("JSCalendar Task has no uid"))?;
+        let time_zone = string(map, "timeZone");
+        let show_without_time = map
+            .get("showWithoutTime")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        Ok(Self {
+            title: string(map, "title").unwrap_or_default(),
+            description: string(map, "description"),
+            start: string(map, "start"),
+            due: string(map, "due"),
+            show_without_time,
+            progress: string(map, "progress"),
+            keywords: keywords_from_json(map),
+            recurrence: recurrence_from_json(map, time_zone.is_some() && !show_without_time)?,
+            sequence: sequence_from_json(map),
+            participants: participants_from_json(map),
+            alerts: alerts_from_json(map),
+            time_zone,
+            uid,
+        })
+    }
+}
+
+impl JsCalendarObject {
+    /// Convert a typed `VEVENT` or `VTODO`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for other components (`VJOURNAL` has no JSCalendar counterpart) and
+    /// see [`JsEvent::from_ical`] and [`JsTask::from_ical`].
+    pub fn from_ical(component: &ICalComponent) -> Result<Self> {
+        match component.kind.as_str() {
+            "VEVENT" => JsEvent::from_ical(component).map(Self::Event),
+            "VTODO" => JsTask::from_ical(component).map(Self::Task),
+            other => Err(anyhow!("{other} has no JSCalendar equivalent")),
+        }
+    }
+
+    pub fn uid(&self) -> &str {
+        match self {
+            Self::Event(event) => &event.uid,
+            Self::Task(task) => &task.uid,
+        }
+    }
+
+    /// Serialize as a `VCALENDAR`, ready for upload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a date is not a valid `LocalDateTime`.
+    pub fn to_ical(&self) -> Result<String> {
+        match self {
+            Self::Event(event) => event.to_ical(),
+            Self::Task(task) => task.to_ical(),
+        }
+    }
+
+    /// The JSCalendar JSON form.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Event(event) => event.to_json(),
+            Self::Task(task) => task.to_json(),
+        }
+    }
+
+    /// Read a JSCalendar `Event` or `Task`, dispatching on `@type`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for other object types and see [`JsEvent::from_json`] and
+    /// [`JsTask::from_json`].
+    pub fn from_json(value: &Value) -> Result<Self> {
+        match value.get("@type").and_then(Value::as_str) {
+            Some("Event") => JsEvent::from_json(value).map(Self::Event),
+            Some("Task") => JsTask::from_json(value).map(Self::Task),
+            Some(other) => Err(anyhow!("unsupported JSCalendar type {other}")),
+            None => Err(anyhow!("JSCalendar object has no @type")),
+        }
+    }
+}
+
+/// Convert every `VEVENT` and `VTODO` of a calendar, in document order. `VJOURNAL`s are
+/// skipped.
+///
+/// # Errors
+///
+/// Returns an error when a component cannot be converted (see [`JsCalendarObject::from_ical`]).
+pub fn to_jscalendar(calendar: &ICalendar) -> Result<Vec<JsCalendarObject>> {
+    calendar
+        .components
+        .iter()
+        .filter(|c| c.kind != "VJOURNAL")
+        .map(JsCalendarObject::from_ical)
+        .collect()
+}
+
+impl CalendarObject {
+    /// Parse [`calendar_data`](Self::calendar_data) and convert it to JSCalendar objects.
+    ///
+    /// # Errors
+    ///
+    /// See [`parse_ical`](Self::parse_ical) and [`to_jscalendar`].
+    pub fn to_jscalendar(&self) -> Result<Vec<JsCalendarObject>> {
+        to_jscalendar(&self.parse_ical()?)
+    }
+}
+
+fn required_uid(component: &ICalComponent) -> Result<String> {
+    component
+        .uid
+        .clone()
+        .ok_or_else(|| anyhow!("{} has no UID", component.kind))
+}
+
+fn time_zone(value: &ICalDateTime) -> Option<String> {
+    if value.is_utc() {
+        Some(UTC.to_string())
+    } else {
+        value.tzid.clone()
+    }
+}
+
+fn is_utc_zone(time_zone: &str) -> bool {
+    time_zone.eq_ignore_ascii_case(UTC) || time_zone.eq_ignore_ascii_case("UTC")
+}
+
+/// `20240105T090000Z` → `2024-01-05T09:00:00`, `20240105` → `2024-01-05T00:00:00`; other
+/// values (e.g. periods) pass through.
+fn local_datetime(value: &str) -> String {
+    let value = value.trim().trim_end_matches(['Z', 'z']);
+    let (date, time) = value.split_once('T').unwrap_or((value, "000000"));
+    if !value.is_ascii() || date.len() != 8 || time.len() != 6 {
+        return value.to_string();
+    }
+    format!(
+        "{}-{}-{}T{}:{}:{}",
+        &date[0..4],
+        &date[4..6],
+        &date[6..8],
+        &time[0..2],
+        &time[2..4],
+        &time[4..6]
+    )
+}
+
+/// Build a date-time property from a `LocalDateTime` in `time_zone`.
+fn date_line(name: &str, local: &str, time_zone: Option<&str>, date: bool) -> Result<ContentLine> {
+    let compact: String = local.chars().filter(|c| !matches!(c, '-' | ':')).collect();
+    if compact.len() != 15 || parse_local_datetime(&compact).is_none() {
+        return Err(anyhow!("invalid LocalDateTime {local} for {name}"));
+    }
+    if date {
+        let mut line = ContentLine::new(name, &compact[..8]);
+        line.set_param("VALUE", "DATE");
+        return Ok(line);
+    }
+    Ok(match time_zone {
+        Some(tz) if is_utc_zone(tz) => ContentLine::new(name, format!("{compact}Z")),
+        Some(tz) => {
+            let mut line = ContentLine::new(name, compact);
+            line.set_param("TZID", tz);
+            line
+        }
+        None => ContentLine::new(name, compact),
+    })
+}
+
+/// Seconds as a JSCalendar `Duration`, e.g. `P1DT1H30M`. Negative spans are clamped to zero.
+fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    if seconds == 0 {
+        return "PT0S".to_string();
+    }
+    let (days, rest) = (seconds / 86_400, seconds % 86_400);
+    let mut out = String::from("P");
+    if days > 0 {
+        out.push_str(&format!("{days}D"));
+    }
+    if rest > 0 {
+        out.push('T');
+        for (amount, unit) in [
+            (rest / 3_600, 'H'),
+            (rest % 3_600 / 60, 'M'),
+            (rest % 60, 'S'),
+        ] {
+            if amount > 0 {
+                out.push_str(&format!("{amount}{unit}"));
+            }
+        }
+    }
+    out
+}
+
+fn recurrence_from_ical(component: &ICalComponent) -> Result<JsRecurrence> {
+    let local = |dates: &[ICalDateTime]| dates.iter().map(|d| local_datetime(&d.value)).collect();
+    Ok(JsRecurrence {
+        rules: component
+            .rrule
+            .as_deref()
+            .map(RecurrenceRule::parse)
+            .transpose()?
+            .into_iter()
+            .collect(),
+        recurrence_id: component
+            .recurrence_id
+            .as_ref()
+            .map(|d| local_datetime(&d.value)),
+        excluded: local(&component.exdates),
+        added: local(&component.rdates),
+    })
+}
+
+fn participants_from_ical(component: &ICalComponent) -> Vec<JsParticipant> {
+    let mut participants: Vec<JsParticipant> = component
+        .attendees
+        .iter()
+        .map(|attendee| {
+            let roles: &[&str] = match attendee.role.as_deref().map(str::to_ascii_uppercase) {
+                Some(role) if role == "CHAIR" => &["attendee", "chair"],
+                Some(role) if role == "OPT-PARTICIPANT" => &["attendee", "optional"],
+                Some(role) if role == "NON-PARTICIPANT" => &["informational"],
+                _ => &["attendee"],
+            };
+            JsParticipant {
+                name: attendee.common_name.clone(),
+                address: attendee.address.clone(),
+                roles: roles.iter().map(|r| r.to_string()).collect(),
+                participation_status: Some(
+                    attendee
+                        .partstat
+                        .as_deref()
+                        .unwrap_or("NEEDS-ACTION")
+                        .to_ascii_lowercase(),
+                ),
+                expect_reply: attendee.rsvp,
+            }
+        })
+        .collect();
+    if let Some(organizer) = &component.organizer {
+        match participants
+            .iter_mut()
+            .find(|p| p.address.eq_ignore_ascii_case(&organizer.address))
+        {
+            Some(participant) => participant.roles.insert(0, "owner".to_string()),
+            None => participants.insert(
+                0,
+                JsParticipant {
+                    name: organizer.common_name.clone(),
+                    address: organizer.address.clone(),
+                    roles: vec!["owner".to_string()],
+                    participation_status: None,
+                    expect_reply: false,
+                },
+            ),
+        }
+    }
+    participants
+}
+
+fn alerts_from_ical(alarms: &[Alarm]) -> Vec<JsAlert> {
+    alarms
+        .iter()
+        .filter_map(|alarm| {
+            let trigger = match alarm.trigger.as_ref()? {
+                AlarmTrigger::Relative {
+                    duration,
+                    related_end,
+                } => JsTrigger::Offset {
+                    offset: duration.clone(),
+                    relative_to_end: *related_end,
+                },
+                AlarmTrigger::Absolute(when) => {
+                    JsTrigger::Absolute(format!("{}Z", local_datetime(&when.value)))
+                }
+            };
+            let email = alarm
+                .action
+                .as_deref()
+                .is_some_and(|a| a.eq_ignore_ascii_case("EMAIL"));
+            Some(JsAlert {
+                trigger,
+                action: if email { "email" } else { "display" }.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn wrap(component: Component) -> String {
+    let mut calendar = Component::new("VCALENDAR");
+    calendar.properties.push(ContentLine::new("VERSION", "2.0"));
+    calendar
+        .properties
+        .push(ContentLine::new("PRODID", "-//fast-dav-rs//EN"));
+    calendar.components.push(component);
+    calendar.to_ics()
+}
+
+fn write_text(component: &mut Component, title: &str, description: Option<&str>) {
+    if !title.is_empty() {
+        component.set_value("SUMMARY", escape_text(title));
+    }
+    if let Some(description) = description {
+        component.set_value("DESCRIPTION", escape_text(description));
+    }
+}
+
+fn write_common(
+    component: &mut Component,
+    keywords: &[String],
+    sequence: Option<u32>,
+    participants: &[JsParticipant],
+) {
+    if !keywords.is_empty() {
+        let categories: Vec<String> = keywords.iter().map(|k| escape_text(k)).collect();
+        component.set_value("CATEGORIES", categories.join(","));
+    }
+    if let Some(sequence) = sequence {
+        component.set_value("SEQUENCE", sequence.to_string());
+    }
+    for participant in participants {
+        if participant.has_role("owner") {
+            let mut line = ContentLine::new("ORGANIZER", participant.address.clone());
+            if let Some(name) = &participant.name {
+                line.set_param("CN", name.clone());
+            }
+            component.properties.push(line);
+        }
+        let role = if participant.has_role("chair") {
+            "CHAIR"
+        } else if participant.has_role("optional") {
+            "OPT-PARTICIPANT"
+        } else if participant.has_role("attendee") {
+            "REQ-PARTICIPANT"
+        } else if participant.has_role("informational") {
+            "NON-PARTICIPANT"
+        } else {
+            continue;
+        };
+        let mut line = ContentLine::new("ATTENDEE", participant.address.clone());
+        if let Some(name) = &participant.name {
+            line.set_param("CN", name.clone());
+        }
+        line.set_param("ROLE", role);
+        line.set_param(
+            "PARTSTAT",
+            participant
+                .participation_status
+                .as_deref()
+                .unwrap_or("needs-action")
+                .to_ascii_uppercase(),
+        );
+        if participant.expect_reply {
+            line.set_param("RSVP", "TRUE");
+        }
+        component.properties.push(line);
+    }
+}
+
+fn write_recurrence(
+    component: &mut Component,
+    recurrence: &JsRecurrence,
+    time_zone: Option<&str>,
+    date: bool,
+) -> Result<()> {
+    for rule in &recurrence.rules {
+        component
+            .properties
+            .push(ContentLine::new("RRULE", rule.to_string()));
+    }
+    if let Some(id) = &recurrence.recurrence_id {
+        component
+            .properties
+            .push(date_line("RECURRENCE-ID", id, time_zone, date)?);
+    }
+    for added in &recurrence.added {
+        component
+            .properties
+            .push(date_line("RDATE", added, time_zone, date)?);
+    }
+    for excluded in &recurrence.excluded {
+        component
+            .properties
+            .push(date_line("EXDATE", excluded, time_zone, date)?);
+    }
+    Ok(())
+}
+
+fn write_alerts(component: &mut Component, alerts: &[JsAlert], title: &str) -> Result<()> {
+    for alert in alerts {
+        let mut alarm = Component::new("VALARM");
+        alarm.set_value("ACTION", alert.action.to_ascii_uppercase());
+        let trigger = match &alert.trigger {
+            JsTrigger::Offset {
+                offset,
+                relative_to_end,
+            } => {
+                let mut line = ContentLine::new("TRIGGER", offset.clone());
+                if *relative_to_end {
+                    line.set_param("RELATED", "END");
+                }
+                line
+            }
+            JsTrigger::Absolute(when) => {
+                let mut line = date_line("TRIGGER", when.trim_end_matches('Z'), Some(UTC), false)?;
+                line.set_param("VALUE", "DATE-TIME");
+                line
+            }
+        };
+        alarm.properties.push(trigger);
+        // DISPLAY needs a DESCRIPTION and EMAIL a SUMMARY as well.
+        alarm.set_value("DESCRIPTION", escape_text(title));
+        if alert.action.eq_ignore_ascii_case("email") {
+            alarm.set_value("SUMMARY", escape_text(title));
+        }
+        component.components.push(alarm);
+    }
+    Ok(())
+}
+
+fn set_text(map: &mut Map<String, Value>, uid: &str, title: &str, description: Option<&str>) {
+    map.insert("uid".into(), json!(uid));
+    map.insert("title".into(), json!(title));
+    if let Some(description) = description {
+        map.insert("description".into(), json!(description));
+    }
+}
+
+fn set_common(
+    map: &mut Map<String, Value>,
+    keywords: &[String],
+    sequence: Option<u32>,
+    participants: &[JsParticipant],
+    alerts: &[JsAlert],
+) {
+    if !keywords.is_empty() {
+        let keywords: Map<String, Value> =
+            keywords.iter().map(|k| (k.clone(), json!(true))).collect();
+        map.insert("keywords".into(), Value::Object(keywords));
+    }
+    if let Some(sequence) = sequence {
+        map.insert("sequence".into(), json!(sequence));
+    }
+    if let Some(owner) = participants.iter().find(|p| p.has_role("owner")) {
+        map.insert("replyTo".into(), json!({ "imip": owner.address }));
+    }
+    if !participants.is_empty() {
+        let participants: Map<String, Value> = participants
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (format!("p{}", i + 1), participant_json(p)))
+            .collect();
+        map.insert("participants".into(), Value::Object(participants));
+    }
+    if !alerts.is_empty() {
+        let alerts: Map<String, Value> = alerts
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (format!("a{}", i + 1), alert_json(a)))
+            .collect();
+        map.insert("alerts".into(), Value::Object(alerts));
+    }
+}
+
+fn participant_json(participant: &JsParticipant) -> Value {
+    let mut map = Map::new();
+    map.insert("@type".into(), json!("Participant"));
+    if let Some(name) = &participant.name {
+        map.insert("name".into(), json!(name));
+    }
+    if let Some(email) = participant
+        .address
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map(|_| &participant.address[7..])
+    {
+        map.insert("email".into(), json!(email));
+    }
+    map.insert("sendTo".into(), json!({ "imip": participant.address }));
+    let roles: Map<String, Value> = participant
+        .roles
+        .iter()
+        .map(|r| (r.clone(), json!(true)))
+        .collect();
+    map.insert("roles".into(), Value::Object(roles));
+    if let Some(status) = &participant.participation_status {
+        map.insert("participationStatus".into(), json!(status));
+    }
+    if participant.expect_reply {
+        map.insert("expectReply".into(), json!(true));
+    }
+    Value::Object(map)
+}
+
+fn alert_json(alert: &JsAlert) -> Value {
+    let trigger = match &alert.trigger {
+        JsTrigger::Offset {
+            offset,
+            relative_to_end,
+        } => json!({
+            "@type": "OffsetTrigger",
+            "offset": offset,
+            "relativeTo": if *relative_to_end { "end" } else { "start" },
+        }),
+        JsTrigger::Absolute(when) => json!({ "@type": "AbsoluteTrigger", "when": when }),
+    };
+    json!({ "@type": "Alert", "trigger": trigger, "action": alert.action })
+}
+
+fn set_recurrence(map: &mut Map<String, Value>, recurrence: &JsRecurrence) {
+    if !recurrence.rules.is_empty() {
+        let rules = recurrence.rules.iter().map(rule_json).collect();
+        map.insert("recurrenceRules".into(), Value::Array(rules));
+    }
+    if let Some(id) = &recurrence.recurrence_id {
+        map.insert("recurrenceId".into(), json!(id));
+    }
+    if !recurrence.excluded.is_empty() || !recurrence.added.is_empty() {
+        let overrides: Map<String, Value> = recurrence
+            .added
+            .iter()
+            .map(|d| (d.clone(), json!({})))
+            .chain(
+                recurrence
+                    .excluded
+                    .iter()
+                    .map(|d| (d.clone(), json!({ "excluded": true }))),
+            )
+            .collect();
+        map.insert("recurrenceOverrides".into(), Value::Object(overrides));
+    }
+}
+
+fn rule_json(rule: &RecurrenceRule) -> Value {
+    let mut map = Map::new();
+    map.insert("@type".into(), json!("RecurrenceRule"));
+    map.insert(
+        "frequency".into(),
+        json!(rule.freq.as_str().to_ascii_lowercase()),
+    );
+    if rule.interval != 1 {
+        map.insert("interval".into(), json!(rule.interval));
+    }
+    if rule.week_start != Weekday::Monday {
+        map.insert(
+            "firstDayOfWeek".into(),
+            json!(rule.week_start.as_str().to_ascii_lowercase()),
+        );
+    }
+    if !rule.by_day.is_empty() {
+        let days = rule
+            .by_day
+            .iter()
+            .map(|d| {
+                let mut day =
+                    json!({ "@type": "NDay", "day": d.weekday.as_str().to_ascii_lowercase() });
+                if let Some(nth) = d.ordinal {
+                    day["nthOfPeriod"] = json!(nth);
+                }
+                day
+            })
+            .collect();
+        map.insert("byDay".into(), Value::Array(days));
+    }
+    if !rule.by_month_day.is_empty() {
+        map.insert("byMonthDay".into(), json!(rule.by_month_day));
+    }
+    if !rule.by_month.is_empty() {
+        let months: Vec<String> = rule.by_month.iter().map(u32::to_string).collect();
+        map.insert("byMonth".into(), json!(months));
+    }
+    if !rule.by_set_pos.is_empty() {
+        map.insert("bySetPosition".into(), json!(rule.by_set_pos));
+    }
+    if let Some(count) = rule.count {
+        map.insert("count".into(), json!(count));
+    }
+    if let Some(until) = &rule.until {
+        map.insert("until".into(), json!(local_datetime(until)));
+    }
+    Value::Object(map)
+}
+
+/// Rebuild an `RRULE` from its JSON form. `UNTIL` gets the UTC marker required alongside
+/// zoned start dates when `zoned`.
+fn rule_from_json(value: &Value, zoned: bool) -> Result<RecurrenceRule> {
+    let frequency = value
+        .get("frequency")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("JSCalendar RecurrenceRule has no frequency"))?;
+    let mut parts = vec![format!("FREQ={}", frequency.to_ascii_uppercase())];
+    if let Some(interval) = value.get("interval").and_then(Value::as_u64) {
+        parts.push(format!("INTERVAL={interval}"));
+    }
+    if let Some(count) = value.get("count").and_then(Value::as_u64) {
+        parts.push(format!("COUNT={count}"));
+    }
+    if let Some(until) = value.get("until").and_then(Value::as_str) {
+        let compact: String = until.chars().filter(|c| !matches!(c, '-' | ':')).collect();
+        let marker = if zoned && !compact.ends_with('Z') {
+            "Z"
+        } else {
+            ""
+        };
+        parts.push(format!("UNTIL={compact}{marker}"));
+    }
+    if let Some(days) = value.get("byDay").and_then(Value::as_array) {
+        let days: Vec<String> = days
+            .iter()
+            .filter_map(|d| {
+                let day = d.get("day")?.as_str()?.to_ascii_uppercase();
+                Some(match d.get("nthOfPeriod").and_then(Value::as_i64) {
+                    Some(nth) => format!("{nth}{day}"),
+                    None => day,
+                })
+            })
+            .collect();
+        parts.push(format!("BYDAY={}", days.join(",")));
+    }
+    for (key, part) in [
+        ("byMonthDay", "BYMONTHDAY"),
+        ("byMonth", "BYMONTH"),
+        ("bySetPosition", "BYSETPOS"),
+    ] {
+        if let Some(values) = value.get(key).and_then(Value::as_array) {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                .collect();
+            parts.push(format!("{part}={}", values.join(",")));
+        }
+    }
+    if let Some(week_start) = value.get("firstDayOfWeek").and_then(Value::as_str) {
+        parts.push(format!("WKST={}", week_start.to_ascii_uppercase()));
+    }
+    RecurrenceRule::parse(&parts.join(";"))
+}
+
+fn object_of_type<'a>(value: &'a Value, kind: &str) -> Result<&'a Map<String, Value>> {
+    let map = value
+        .as_object()
+        .ok_or_else(|| anyhow!("JSCalendar {kind} must be an object"))?;
+    match map.get("@type").and_then(Value::as_str) {
+        Some(found) if found == kind => Ok(map),
+        found => Err(anyhow!(
+            "expected JSCalendar {kind}, found {}",
+            found.unwrap_or("no @type")
+        )),
+    }
+}
+
+fn string(map: &Map<String, Value>, key: &str) -> Option<String> {
+    map.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn sequence_from_json(map: &Map<String, Value>) -> Option<u32> {
+    map.get("sequence")
+        .and_then(Value::as_u64)
+        .and_then(|s| u32::try_from(s).ok())
+}
+
+fn keywords_from_json(map: &Map<String, Value>) -> Vec<String> {
+    map.get("keywords")
+        .and_then(Value::as_object)
+        .map(|keywords| {
+            keywords
+                .iter()
+                .filter(|(_, set)| set.as_bool() == Some(true))
+                .map(|(k, _)| k.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn recurrence_from_json(map: &Map<String, Value>, zoned: bool) -> Result<JsRecurrence> {
+    let mut recurrence = JsRecurrence {
+        rules: map
+            .get("recurrenceRules")
+            .and_then(Value::as_array)
+            .map(|rules| {
+                rules
+                    .iter()
+                    .map(|r| rule_from_json(r, zoned))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default(),
+        recurrence_id: string(map, "recurrenceId"),
+        ..JsRecurrence::default()
+    };
+    if let Some(overrides) = map.get("recurrenceOverrides").and_then(Value::as_object) {
+        for (date, patch) in overrides {
+            if patch.get("excluded").and_then(Value::as_bool) == Some(true) {
+                recurrence.excluded.push(date.clone());
+            } else {
+                recurrence.added.push(date.clone());
+            }
+        }
+    }
+    Ok(recurrence)
+}
+
+fn participants_from_json(map: &Map<String, Value>) -> Vec<JsParticipant> {
+    let Some(participants) = map.get("participants").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    participants
+        .values()
+        .filter_map(|p| {
+            let send_to = p.get("sendTo").and_then(Value::as_object);
+            let address = send_to
+                .and_then(|s| s.get("imip").or_else(|| s.values().next()))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| {
+                    let email = p.get("email")?.as_str()?;
+                    Some(format!("mailto:{email}"))
+                })?;
+            let roles = p
+                .get("roles")
+                .and_then(Value::as_object)
+                .map(|roles| {
+                    roles
+                        .iter()
+                        .filter(|(_, set)| set.as_bool() == Some(true))
+                        .map(|(r, _)| r.clone())
+                        .collect()
+                })
+                .unwrap_or_else(|| vec!["attendee".to_string()]);
+            Some(JsParticipant {
+                name: p.get("name").and_then(Value::as_str).map(str::to_string),
+                address,
+                roles,
+                participation_status: p
+                    .get("participationStatus")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                expect_reply: p
+                    .get("expectReply")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+fn alerts_from_json(map: &Map<String, Value>) -> Vec<JsAlert> {
+    let Some(alerts) = map.get("alerts").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    alerts
+        .values()
+        .filter_map(|a| {
+            let trigger = a.get("trigger")?;
+            let trigger = match trigger.get("@type").and_then(Value::as_str) {
+                Some("AbsoluteTrigger") => {
+                    JsTrigger::Absolute(trigger.get("when")?.as_str()?.to_string())
+                }
+                _ => JsTrigger::Offset {
+                    offset: trigger.get("offset")?.as_str()?.to_string(),
+                    relative_to_end: trigger.get("relativeTo").and_then(Value::as_str)
+                        == Some("end"),
+                },
+            };
+            Some(JsAlert {
+                trigger,
+                action: a
+                    .get("action")
+                    .and_then(Value::as_str)
+                    .unwrap_or("display")
+                    .to_string(),
+            })
+        })
+        .collect()
+}
@@ -1,14 +1,94 @@
+pub mod alarm;
+pub mod availability;
 pub mod client;
+pub mod collection;
+pub mod event;
+pub mod export;
+#[cfg(feature = "ical")]
+pub mod ical;
+pub mod instance;
+#[cfg(feature = "jcal")]
+pub mod jcal;
+pub mod journal;
+#[cfg(feature = "jscalendar")]
+pub mod jscalendar;
+pub mod query;
+#[cfg(feature = "ical")]
+pub mod recurrence;
+pub mod scheduling;
+pub mod sharing;
+pub mod sort;
 pub mod streaming;
+pub mod tasks;
+#[cfg(feature = "ical")]
+pub mod timezone;
 pub mod types;
+pub mod validate;
 
+pub use alarm::{
+    AlarmBuilder, AlarmQuirks, acknowledge_alarm, add_alarm, remove_alarm, remove_alarms,
+    snooze_alarm,
+};
+pub use availability::{build_availability, parse_availability};
 pub use client::{
-    CalDavClient, build_calendar_multiget_body, build_calendar_query_body,
-    build_sync_collection_body, map_calendar_list, map_calendar_objects, map_sync_response,
+    CalDavClient, DEFAULT_MULTIGET_CHUNK_SIZE, DEFAULT_MULTIGET_CONCURRENCY,
+    build_calendar_multiget_bodies, build_calendar_multiget_body, build_calendar_query_body,
+    build_calendar_query_body_filtered, build_calendar_query_body_with, build_sync_collection_body,
+    map_calendar_list, map_calendar_objects, map_schedule_urls, map_sync_response,
+};
+pub use collection::{CalendarPropPatch, MkCalendar, check_supported_components};
+pub use event::EventBuilder;
+pub use export::{CalendarMerger, merge_calendars};
+#[cfg(feature = "ical")]
+pub use ical::{
+    Alarm, AlarmTrigger, Attendee, CalendarUser, ICalComponent, ICalDateTime, ICalendar,
+    parse_icalendar,
+};
+pub use instance::{delete_instance, update_instance};
+#[cfg(feature = "jcal")]
+pub use jcal::{jcal_to_component, jcal_to_ics, parse_jcal};
+pub use journal::{journals_filter, map_journals, parse_journals};
+#[cfg(feature = "jscalendar")]
+pub use jscalendar::{
+    JsAlert, JsCalendarObject, JsEvent, JsParticipant, JsRecurrence, JsTask, JsTrigger,
+    to_jscalendar,
+};
+#[cfg(feature = "query-builder")]
+pub use query::CalendarQuery;
+pub use query::{
+    CalendarData, CompFilter, ComponentSelection, ParamFilter, PropFilter, uid_filter,
 };
+#[cfg(feature = "ical")]
+pub use recurrence::{Frequency, Instance, RecurrenceRule, Weekday, WeekdayNum, expand_instances};
+pub use scheduling::{
+    ItipBuilder, add_attendee, build_free_busy_query_body, build_free_busy_request, itip_method,
+    merge_busy_periods, parse_free_busy, parse_schedule_response, remove_attendee,
+    set_attendee_partstat,
+};
+pub use sharing::{
+    build_invite_reply_body, build_share_body, map_shared_calendars, parse_calendar_invite,
+    parse_invite_notification, parse_shared_as,
+};
+pub use sort::{SortKey, sort_calendar_objects};
 pub use streaming::{
-    parse_multistatus_bytes, parse_multistatus_bytes_visit, parse_multistatus_stream,
-    parse_multistatus_stream_visit, parse_multistatus_stream_visit_with_timeout,
-    parse_multistatus_stream_with_timeout,
+    DavItemStream, SyncItemStream, Visit, parse_multistatus_bytes, parse_multistatus_bytes_visit,
+    parse_multistatus_bytes_visit_until, parse_multistatus_bytes_with_limits,
+    parse_multistatus_item_stream, parse_multistatus_item_stream_with_timeout,
+    parse_multistatus_stream, parse_multistatus_stream_visit, parse_multistatus_stream_visit_until,
+    parse_multistatus_stream_visit_until_with_timeout, parse_multistatus_stream_visit_with_timeout,
+    parse_multistatus_stream_with_limits, parse_multistatus_stream_with_timeout,
+};
+pub use tasks::{complete_todo, incomplete_todos_filter, overdue_todos_filter, todo_is_open};
+#[cfg(feature = "ical")]
+pub use timezone::{
+    Observance, ObservanceKind, VTimeZone, find_timezone, normalize_tzid, parse_timezones,
+};
+pub use types::{
+    AttendeeRole, Availability, AvailableSlot, BatchItem, CalendarInfo, CalendarInvite,
+    CalendarObject, DavItem, Depth, FreeBusy, FreeBusyPeriod, FreeBusyType, IcsIssue,
+    IcsValidationError, InviteStatus, ItipMethod, Journal, Partstat, PutResult,
+    ScheduleRecipientStatus, ScheduleTransparency, ScheduleUrls, SchedulingObject, ShareAccess,
+    ShareInvitation, ShareInvite, SharedCalendar, Sharee, SyncItem, SyncResponse,
+    UnsupportedComponentError,
 };
-pub use types::{BatchItem, CalendarInfo, CalendarObject, DavItem, Depth, SyncItem, SyncResponse};
+pub use validate::validate_ics;
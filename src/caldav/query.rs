@@ -0,0 +1,842 @@
+//! `calendar-query` building blocks (RFC 4791 §7.8) and the fluent [`CalendarQuery`] API.
+//!
+//! [`CalendarData`] describes what the server should return inside `<C:calendar-data>` and is
+//! shared by the raw body builders and the fluent query builder. The fluent builder itself is
+//! available with the `query-builder` feature (enabled by default).
+
+use anyhow::{Result, anyhow};
+
+use crate::webdav::types::{MatchType, TextMatch};
+use crate::webdav::xml::{
+    escape_xml, text_match_xml, validate_component_name, validate_utc_datetime,
+};
+
+#[cfg(feature = "query-builder")]
+use crate::caldav::client::{CalDavClient, calendar_query_body, map_calendar_objects};
+#[cfg(feature = "query-builder")]
+use crate::caldav::sort::{SortKey, sort_calendar_objects};
+#[cfg(feature = "query-builder")]
+#[cfg(feature = "query-builder")]
+use crate::caldav::types::{CalendarObject, Depth};
+
+/// Options for the `<C:calendar-data>` element of a `calendar-query` or `calendar-multiget`.
+///
+/// The default requests the full, unmodified calendar object.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::CalendarData;
+///
+/// let data = CalendarData::new().expand("20240101T000000Z", "20240201T000000Z");
+/// assert_eq!(
+///     data.to_xml(),
+///     r#"<C:calendar-data><C:expand start="20240101T000000Z" end="20240201T000000Z"/></C:calendar-data>"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarData {
+    expand: Option<(String, String)>,
+    limit_freebusy_set: Option<(String, String)>,
+    select: Vec<ComponentSelection>,
+    json: bool,
+}
+
+impl CalendarData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the server to expand recurring events into individual instances within
+    /// `[start, end)` (`<C:expand>`, RFC 4791 §9.6.5).
+    ///
+    /// Instead of a master component with an `RRULE` plus overrides, the server returns one
+    /// component per occurrence, each with a `RECURRENCE-ID` and times converted to UTC.
+    pub fn expand(mut self, start: &str, end: &str) -> Self {
+        self.expand = Some((start.to_string(), end.to_string()));
+        self
+    }
+
+    /// Trim `FREEBUSY` properties of returned `VFREEBUSY` components to `[start, end)`
+    /// (`<C:limit-freebusy-set>`, RFC 4791 §9.6.7).
+    ///
+    /// Useful when mirroring availability calendars that store long-running `VFREEBUSY`
+    /// objects but only a window of busy time is needed.
+    pub fn limit_freebusy_set(mut self, start: &str, end: &str) -> Self {
+        self.limit_freebusy_set = Some((start.to_string(), end.to_string()));
+        self
+    }
+
+    /// Only return the selected components and properties (`<C:comp>`/`<C:prop>`,
+    /// RFC 4791 §9.6.1), e.g. just `UID`, `DTSTART` and `SUMMARY` of each `VEVENT` for a list
+    /// view, leaving out large `DESCRIPTION`/`ATTACH` values.
+    ///
+    /// Can be called several times (e.g. `VEVENT` plus `VTIMEZONE`). The enclosing `VCALENDAR`
+    /// always keeps all of its own properties (`VERSION`, `PRODID`, …).
+    ///
+    /// ```
+    /// use fast_dav_rs::caldav::{CalendarData, ComponentSelection};
+    ///
+    /// let data = CalendarData::new()
+    ///     .select(ComponentSelection::new("VEVENT").props(["UID", "DTSTART", "SUMMARY"]))
+    ///     .select(ComponentSelection::new("VTIMEZONE").all());
+    /// assert!(data.to_xml().contains(r#"<C:comp name="VEVENT"><C:prop name="UID"/>"#));
+    /// ```
+    pub fn select(mut self, component: ComponentSelection) -> Self {
+        self.select.push(component);
+        self
+    }
+
+    /// Request the data as jCal (`content-type="application/calendar+json"`, RFC 7265)
+    /// instead of iCalendar text, on servers that support it (e.g. SabreDAV). With the
+    /// `jcal` feature, `CalendarObject::parse_ical` reads both formats.
+    pub fn json(mut self) -> Self {
+        self.json = true;
+        self
+    }
+
+    /// Check that every date-time is a structurally valid iCalendar UTC date-time and every
+    /// selected component/property name is well-formed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the offending option.
+    pub fn validate(&self) -> Result<()> {
+        for component in &self.select {
+            component.validate()?;
+        }
+        if let Some((start, end)) = &self.expand {
+            validate_utc_datetime(start).map_err(|e| anyhow!("invalid expand start: {e}"))?;
+            validate_utc_datetime(end).map_err(|e| anyhow!("invalid expand end: {e}"))?;
+        }
+        if let Some((start, end)) = &self.limit_freebusy_set {
+            validate_utc_datetime(start)
+                .map_err(|e| anyhow!("invalid limit-freebusy-set start: {e}"))?;
+            validate_utc_datetime(end)
+                .map_err(|e| anyhow!("invalid limit-freebusy-set end: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Serialize as a `<C:calendar-data>` element (the `C` prefix must be bound to the CalDAV
+    /// namespace).
+    pub fn to_xml(&self) -> String {
+        let mut inner = String::new();
+        if let Some((start, end)) = &self.expand {
+            inner.push_str(&format!(
+                r#"<C:expand start="{}" end="{}"/>"#,
+                escape_xml(start),
+                escape_xml(end)
+            ));
+        }
+        if let Some((start, end)) = &self.limit_freebusy_set {
+            inner.push_str(&format!(
+                r#"<C:limit-freebusy-set start="{}" end="{}"/>"#,
+                escape_xml(start),
+                escape_xml(end)
+            ));
+        }
+        if !self.select.is_empty() {
+            inner.push_str(r#"<C:comp name="VCALENDAR"><C:allprop/>"#);
+            for component in &self.select {
+                component.write_xml(&mut inner);
+            }
+            inner.push_str("</C:comp>");
+        }
+        let open = if self.json {
+            r#"<C:calendar-data content-type="application/calendar+json" version="2.0""#
+        } else {
+            "<C:calendar-data"
+        };
+        if inner.is_empty() {
+            format!("{open}/>")
+        } else {
+            format!("{open}>{inner}</C:calendar-data>")
+        }
+    }
+}
+
+/// Component (and property) selection for partial `calendar-data` retrieval.
+///
+/// A new selection returns the component with no properties and no sub-components; add
+/// what is needed with [`props`](Self::props), [`comp`](Self::comp), or [`all`](Self::all).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentSelection {
+    name: String,
+    all_props: bool,
+    props: Vec<String>,
+    all_comps: bool,
+    comps: Vec<ComponentSelection>,
+}
+
+impl ComponentSelection {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            all_props: false,
+            props: Vec::new(),
+            all_comps: false,
+            comps: Vec::new(),
+        }
+    }
+
+    /// Return these properties of the component.
+    pub fn props<I, S>(mut self, props: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.props
+            .extend(props.into_iter().map(|p| p.as_ref().to_string()));
+        self
+    }
+
+    /// Return every property of the component.
+    pub fn all_props(mut self) -> Self {
+        self.all_props = true;
+        self
+    }
+
+    /// Return a selected sub-component (e.g. `VALARM` inside `VEVENT`).
+    pub fn comp(mut self, component: ComponentSelection) -> Self {
+        self.comps.push(component);
+        self
+    }
+
+    /// Return every sub-component in full.
+    pub fn all_comps(mut self) -> Self {
+        self.all_comps = true;
+        self
+    }
+
+    /// Return the component in full (all properties and sub-components).
+    pub fn all(self) -> Self {
+        self.all_props().all_comps()
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_component_name(&self.name)
+            .map_err(|e| anyhow!("invalid calendar-data component: {e}"))?;
+        for prop in &self.props {
+            validate_component_name(prop)
+                .map_err(|e| anyhow!("invalid calendar-data property: {e}"))?;
+        }
+        for comp in &self.comps {
+            comp.validate()?;
+        }
+        Ok(())
+    }
+
+    fn write_xml(&self, out: &mut String) {
+        let name = escape_xml(&self.name);
+        if !self.all_props && self.props.is_empty() && !self.all_comps && self.comps.is_empty() {
+            out.push_str(&format!(r#"<C:comp name="{name}"/>"#));
+            return;
+        }
+        out.push_str(&format!(r#"<C:comp name="{name}">"#));
+        if self.all_props {
+            out.push_str("<C:allprop/>");
+        } else {
+            for prop in &self.props {
+                out.push_str(&format!(r#"<C:prop name="{}"/>"#, escape_xml(prop)));
+            }
+        }
+        if self.all_comps {
+            out.push_str("<C:allcomp/>");
+        } else {
+            for comp in &self.comps {
+                comp.write_xml(out);
+            }
+        }
+        out.push_str("</C:comp>");
+    }
+}
+
+/// A `prop-filter` (RFC 4791 §9.7.2) matching a property of the queried component.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::{ParamFilter, PropFilter};
+/// use fast_dav_rs::webdav::TextMatch;
+///
+/// let filter = PropFilter::new("SUMMARY").text_match(TextMatch::new("standup").collation("i;octet"));
+/// assert_eq!(
+///     filter.to_xml(),
+///     r#"<C:prop-filter name="SUMMARY"><C:text-match collation="i;octet">standup</C:text-match></C:prop-filter>"#
+/// );
+///
+/// // Attendees who have not answered yet.
+/// let pending = PropFilter::new("ATTENDEE")
+///     .param_filter(ParamFilter::new("PARTSTAT").text_match(TextMatch::new("NEEDS-ACTION")));
+/// assert!(pending.to_xml().contains(r#"<C:param-filter name="PARTSTAT">"#));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropFilter {
+    name: String,
+    is_not_defined: bool,
+    start: Option<String>,
+    end: Option<String>,
+    text_match: Option<TextMatch>,
+    params: Vec<ParamFilter>,
+}
+
+impl PropFilter {
+    /// Match components that have the property `name` (e.g. `SUMMARY`, `STATUS`).
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            is_not_defined: false,
+            start: None,
+            end: None,
+            text_match: None,
+            params: Vec::new(),
+        }
+    }
+
+    /// Match components that do **not** have the property (`is-not-defined`), e.g. events
+    /// without `CATEGORIES`. Overrides any text-match or param-filter.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Require a date-time property (e.g. `DUE`, `COMPLETED`) to fall within `[start, end)`;
+    /// either bound may be open. Takes precedence over [`text_match`](Self::text_match).
+    pub fn time_range(mut self, start: Option<&str>, end: Option<&str>) -> Self {
+        self.start = start.map(str::to_string);
+        self.end = end.map(str::to_string);
+        self
+    }
+
+    /// Additionally require the property value to match `text_match`.
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_match = Some(text_match);
+        self
+    }
+
+    /// Additionally require a parameter of the property to match. All param-filters must match.
+    pub fn param_filter(mut self, filter: ParamFilter) -> Self {
+        self.params.push(filter);
+        self
+    }
+
+    /// Check the property/parameter names and date-times before sending the filter.
+    pub fn validate(&self) -> Result<()> {
+        validate_component_name(&self.name)
+            .map_err(|e| anyhow!("invalid prop-filter name: {e}"))?;
+        if let Some(s) = &self.start {
+            validate_utc_datetime(s).map_err(|e| anyhow!("invalid prop-filter start: {e}"))?;
+        }
+        if let Some(e) = &self.end {
+            validate_utc_datetime(e).map_err(|e| anyhow!("invalid prop-filter end: {e}"))?;
+        }
+        for param in &self.params {
+            param.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Serialize as a `<C:prop-filter>` element.
+    pub fn to_xml(&self) -> String {
+        let name = escape_xml(&self.name);
+        if self.is_not_defined {
+            return format!(r#"<C:prop-filter name="{name}"><C:is-not-defined/></C:prop-filter>"#);
+        }
+        let mut inner = time_range_xml(self.start.as_deref(), self.end.as_deref());
+        if inner.is_empty()
+            && let Some(text_match) = &self.text_match
+        {
+            inner.push_str(&text_match_xml("C", text_match));
+        }
+        for param in &self.params {
+            inner.push_str(&param.to_xml());
+        }
+        if inner.is_empty() {
+            format!(r#"<C:prop-filter name="{name}"/>"#)
+        } else {
+            format!(r#"<C:prop-filter name="{name}">{inner}</C:prop-filter>"#)
+        }
+    }
+}
+
+/// A `param-filter` (RFC 4791 §9.7.3) matching a parameter of a property, e.g. `PARTSTAT`
+/// on `ATTENDEE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamFilter {
+    name: String,
+    is_not_defined: bool,
+    text_match: Option<TextMatch>,
+}
+
+impl ParamFilter {
+    /// Match properties that carry the parameter `name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            is_not_defined: false,
+            text_match: None,
+        }
+    }
+
+    /// Match properties that do **not** carry the parameter. Overrides any text-match.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Additionally require the parameter value to match `text_match`.
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_match = Some(text_match);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_component_name(&self.name).map_err(|e| anyhow!("invalid param-filter name: {e}"))
+    }
+
+    /// Serialize as a `<C:param-filter>` element.
+    pub fn to_xml(&self) -> String {
+        let name = escape_xml(&self.name);
+        if self.is_not_defined {
+            return format!(
+                r#"<C:param-filter name="{name}"><C:is-not-defined/></C:param-filter>"#
+            );
+        }
+        match &self.text_match {
+            Some(text_match) => format!(
+                r#"<C:param-filter name="{name}">{}</C:param-filter>"#,
+                text_match_xml("C", text_match)
+            ),
+            None => format!(r#"<C:param-filter name="{name}"/>"#),
+        }
+    }
+}
+
+/// A `comp-filter` (RFC 4791 §9.7.1) matching a component inside `VCALENDAR`, optionally
+/// with nested component filters such as a `VALARM` inside a `VEVENT`.
+///
+/// Sibling filters are combined with AND: a `VEVENT` filter next to a `VTODO` filter only
+/// matches objects containing both. To fetch several component types, send one query per
+/// type, as [`CalendarQuery::components`] does.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::{CompFilter, build_calendar_query_body_filtered};
+///
+/// // Events of January that carry an alarm.
+/// let filter = CompFilter::new("VEVENT")
+///     .time_range(Some("20240101T000000Z"), Some("20240201T000000Z"))
+///     .comp_filter(CompFilter::new("VALARM"));
+/// let body = build_calendar_query_body_filtered(&filter, None);
+/// assert!(body.contains(r#"<C:comp-filter name="VALARM"/></C:comp-filter>"#));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompFilter {
+    name: String,
+    is_not_defined: bool,
+    start: Option<String>,
+    end: Option<String>,
+    props: Vec<PropFilter>,
+    comps: Vec<CompFilter>,
+}
+
+impl CompFilter {
+    /// Match components named `name` (e.g. `VEVENT`, `VALARM`).
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            is_not_defined: false,
+            start: None,
+            end: None,
+            props: Vec::new(),
+            comps: Vec::new(),
+        }
+    }
+
+    /// Match when the component is **absent**, e.g. events without a `VALARM`. Overrides
+    /// every other condition.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Restrict matches to components overlapping `[start, end)`; either bound may be open.
+    pub fn time_range(mut self, start: Option<&str>, end: Option<&str>) -> Self {
+        self.start = start.map(str::to_string);
+        self.end = end.map(str::to_string);
+        self
+    }
+
+    /// Additionally require a property filter to match. All filters must match.
+    pub fn prop_filter(mut self, filter: PropFilter) -> Self {
+        self.props.push(filter);
+        self
+    }
+
+    /// Additionally require a nested component filter to match. All filters must match.
+    pub fn comp_filter(mut self, filter: CompFilter) -> Self {
+        self.comps.push(filter);
+        self
+    }
+
+    /// Check every name and date-time of the filter tree before sending it.
+    pub fn validate(&self) -> Result<()> {
+        validate_component_name(&self.name)
+            .map_err(|e| anyhow!("invalid comp-filter name: {e}"))?;
+        if let Some(s) = &self.start {
+            validate_utc_datetime(s).map_err(|e| anyhow!("invalid comp-filter start: {e}"))?;
+        }
+        if let Some(e) = &self.end {
+            validate_utc_datetime(e).map_err(|e| anyhow!("invalid comp-filter end: {e}"))?;
+        }
+        for prop in &self.props {
+            prop.validate()?;
+        }
+        for comp in &self.comps {
+            comp.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Serialize as a `<C:comp-filter>` element.
+    pub fn to_xml(&self) -> String {
+        let name = escape_xml(&self.name);
+        if self.is_not_defined {
+            return format!(r#"<C:comp-filter name="{name}"><C:is-not-defined/></C:comp-filter>"#);
+        }
+        let mut inner = time_range_xml(self.start.as_deref(), self.end.as_deref());
+        for prop in &self.props {
+            inner.push_str(&prop.to_xml());
+        }
+        for comp in &self.comps {
+            inner.push_str(&comp.to_xml());
+        }
+        if inner.is_empty() {
+            format!(r#"<C:comp-filter name="{name}"/>"#)
+        } else {
+            format!(r#"<C:comp-filter name="{name}">{inner}</C:comp-filter>"#)
+        }
+    }
+}
+
+/// `<C:time-range>` with the given bounds, or an empty string when both are open.
+fn time_range_xml(start: Option<&str>, end: Option<&str>) -> String {
+    if start.is_none() && end.is_none() {
+        return String::new();
+    }
+    let mut xml = String::from("<C:time-range");
+    if let Some(s) = start {
+        xml.push_str(&format!(r#" start="{}""#, escape_xml(s)));
+    }
+    if let Some(e) = end {
+        xml.push_str(&format!(r#" end="{}""#, escape_xml(e)));
+    }
+    xml.push_str("/>");
+    xml
+}
+
+/// `component` filter matching the exact `UID` (case-sensitive `equals` on `i;octet`), e.g.
+/// for [`CalDavClient::get_object_by_uid`](crate::caldav::CalDavClient::get_object_by_uid).
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::caldav::uid_filter;
+///
+/// assert_eq!(
+///     uid_filter("VEVENT", "abc@example.com").to_xml(),
+///     r#"<C:comp-filter name="VEVENT"><C:prop-filter name="UID"><C:text-match collation="i;octet" match-type="equals">abc@example.com</C:text-match></C:prop-filter></C:comp-filter>"#
+/// );
+/// ```
+pub fn uid_filter(component: &str, uid: &str) -> CompFilter {
+    CompFilter::new(component).prop_filter(
+        PropFilter::new("UID").text_match(
+            TextMatch::new(uid)
+                .collation("i;octet")
+                .match_type(MatchType::Equals),
+        ),
+    )
+}
+
+/// Fluent builder for `calendar-query` REPORTs, created with [`CalDavClient::query`].
+///
+/// # Example
+///
+/// ```no_run
+/// use fast_dav_rs::CalDavClient;
+///
+/// # async fn demo(cli: &CalDavClient) -> anyhow::Result<()> {
+/// let instances = cli
+///     .query("calendars/jane/work/")
+///     .component("VEVENT")
+///     .time_range(Some("20240101T000000Z"), Some("20240201T000000Z"))
+///     .expand("20240101T000000Z", "20240201T000000Z")
+///     .execute()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "query-builder")]
+#[derive(Clone)]
+pub struct CalendarQuery<'a> {
+    client: &'a CalDavClient,
+    path: String,
+    components: Vec<String>,
+    start: Option<String>,
+    end: Option<String>,
+    props: Vec<PropFilter>,
+    comps: Vec<CompFilter>,
+    data: Option<CalendarData>,
+    timezone_id: Option<String>,
+    order: Option<SortKey>,
+}
+
+#[cfg(feature = "query-builder")]
+impl<'a> CalendarQuery<'a> {
+    pub(crate) fn new(client: &'a CalDavClient, path: &str) -> Self {
+        Self {
+            client,
+            path: path.to_string(),
+            components: vec!["VEVENT".to_string()],
+            start: None,
+            end: None,
+            props: Vec::new(),
+            comps: Vec::new(),
+            data: None,
+            timezone_id: None,
+            order: None,
+        }
+    }
+
+    /// Component to match inside `VCALENDAR` (defaults to `VEVENT`).
+    pub fn component(mut self, component: &str) -> Self {
+        self.components = vec![component.to_string()];
+        self
+    }
+
+    /// Match several component types, e.g. `["VEVENT", "VTODO"]`.
+    ///
+    /// RFC 4791 combines sibling component filters with AND, so [`execute`](Self::execute)
+    /// sends one REPORT per component type (same time range and filters) and merges the
+    /// results.
+    pub fn components<I, S>(mut self, components: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.components = components
+            .into_iter()
+            .map(|c| c.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Restrict matches to components overlapping `[start, end)`; either bound may be open.
+    pub fn time_range(mut self, start: Option<&str>, end: Option<&str>) -> Self {
+        self.start = start.map(str::to_string);
+        self.end = end.map(str::to_string);
+        self
+    }
+
+    /// Require the property `name` to contain `text` (case-insensitive by default), e.g.
+    /// `.prop_text_match("SUMMARY", "standup")`.
+    pub fn prop_text_match(self, name: &str, text: &str) -> Self {
+        self.prop_filter(PropFilter::new(name).text_match(TextMatch::new(text)))
+    }
+
+    /// Require the property `name` to equal `value`, e.g. `.prop_equals("STATUS", "CANCELLED")`.
+    ///
+    /// Sends `match-type="equals"`; servers that only implement RFC 4791 treat it as a
+    /// substring match.
+    pub fn prop_equals(self, name: &str, value: &str) -> Self {
+        self.prop_filter(
+            PropFilter::new(name).text_match(TextMatch::new(value).match_type(MatchType::Equals)),
+        )
+    }
+
+    /// Require the component to lack the property `name`, e.g.
+    /// `.prop_not_defined("CATEGORIES")` for uncategorised events.
+    pub fn prop_not_defined(self, name: &str) -> Self {
+        self.prop_filter(PropFilter::new(name).is_not_defined())
+    }
+
+    /// Require a `prop` whose parameter `param` contains `text`, e.g.
+    /// `.param_text_match("ATTENDEE", "PARTSTAT", "NEEDS-ACTION")` for pending invitations.
+    ///
+    /// This matches any attendee; to restrict it to one calendar user combine a
+    /// [`PropFilter`] text-match on the address with the param-filter via
+    /// [`CalendarQuery::prop_filter`].
+    pub fn param_text_match(self, prop: &str, param: &str, text: &str) -> Self {
+        self.prop_filter(
+            PropFilter::new(prop)
+                .param_filter(ParamFilter::new(param).text_match(TextMatch::new(text))),
+        )
+    }
+
+    /// Add an arbitrary `prop-filter` (collation, negation, param-filters, …). All filters
+    /// must match.
+    pub fn prop_filter(mut self, filter: PropFilter) -> Self {
+        self.props.push(filter);
+        self
+    }
+
+    /// Add a nested component filter to the queried component, e.g.
+    /// `.comp_filter(CompFilter::new("VALARM"))` for events with alarms.
+    pub fn comp_filter(mut self, filter: CompFilter) -> Self {
+        self.comps.push(filter);
+        self
+    }
+
+    /// Interpret floating date-times in the time-range filter in this time zone, given by
+    /// TZID (`CALDAV:timezone-id`, RFC 7809 §5.2) instead of a full `VTIMEZONE`.
+    pub fn timezone_id(mut self, tzid: &str) -> Self {
+        self.timezone_id = Some(tzid.to_string());
+        self
+    }
+
+    /// Return the full `calendar-data` of every match.
+    pub fn include_data(mut self) -> Self {
+        self.data.get_or_insert_with(CalendarData::default);
+        self
+    }
+
+    /// Return recurring events expanded into instances within `[start, end)`; implies
+    /// [`include_data`](Self::include_data). See [`CalendarData::expand`].
+    pub fn expand(mut self, start: &str, end: &str) -> Self {
+        self.data = Some(self.data.take().unwrap_or_default().expand(start, end));
+        self
+    }
+
+    /// Trim returned `VFREEBUSY` data to `[start, end)`; implies
+    /// [`include_data`](Self::include_data). See [`CalendarData::limit_freebusy_set`].
+    pub fn limit_freebusy_set(mut self, start: &str, end: &str) -> Self {
+        self.data = Some(
+            self.data
+                .take()
+                .unwrap_or_default()
+                .limit_freebusy_set(start, end),
+        );
+        self
+    }
+
+    /// Only return the selected components/properties; implies
+    /// [`include_data`](Self::include_data). See [`CalendarData::select`].
+    pub fn select(mut self, component: ComponentSelection) -> Self {
+        self.data = Some(self.data.take().unwrap_or_default().select(component));
+        self
+    }
+
+    /// Return `calendar-data` as jCal; implies [`include_data`](Self::include_data). See
+    /// [`CalendarData::json`].
+    pub fn json(mut self) -> Self {
+        self.data = Some(self.data.take().unwrap_or_default().json());
+        self
+    }
+
+    /// Use a custom `calendar-data` specification (implies returning data).
+    pub fn calendar_data(mut self, data: CalendarData) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Sort the results client-side by `key` (see [`sort_calendar_objects`]); sorting by
+    /// anything but [`SortKey::Href`] needs [`include_data`](Self::include_data).
+    pub fn order_by(mut self, key: SortKey) -> Self {
+        self.order = Some(key);
+        self
+    }
+
+    /// Validate the inputs and build the REPORT body without sending it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name or date-time is invalid, or if several component types
+    /// were requested (use [`build_bodies`](Self::build_bodies)).
+    pub fn build_body(&self) -> Result<String> {
+        let mut bodies = self.build_bodies()?;
+        if bodies.len() != 1 {
+            return Err(anyhow!(
+                "calendar-query over {} components needs one REPORT each; use build_bodies",
+                bodies.len()
+            ));
+        }
+        Ok(bodies.remove(0))
+    }
+
+    /// Validate the inputs and build one REPORT body per requested component type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no component was requested or a name or date-time is invalid.
+    pub fn build_bodies(&self) -> Result<Vec<String>> {
+        if self.components.is_empty() {
+            return Err(anyhow!("calendar-query needs at least one component"));
+        }
+        for component in &self.components {
+            validate_component_name(component)
+                .map_err(|e| anyhow!("invalid calendar-query component: {e}"))?;
+        }
+        if let Some(s) = &self.start {
+            validate_utc_datetime(s).map_err(|e| anyhow!("invalid calendar-query start: {e}"))?;
+        }
+        if let Some(e) = &self.end {
+            validate_utc_datetime(e).map_err(|e| anyhow!("invalid calendar-query end: {e}"))?;
+        }
+        for prop in &self.props {
+            prop.validate()?;
+        }
+        for comp in &self.comps {
+            comp.validate()?;
+        }
+        if let Some(data) = &self.data {
+            data.validate()?;
+        }
+        Ok(self
+            .components
+            .iter()
+            .map(|component| {
+                let filter = CompFilter {
+                    name: component.clone(),
+                    is_not_defined: false,
+                    start: self.start.clone(),
+                    end: self.end.clone(),
+                    props: self.props.clone(),
+                    comps: self.comps.clone(),
+                };
+                calendar_query_body(&filter, self.data.as_ref(), self.timezone_id.as_deref())
+            })
+            .collect())
+    }
+
+    /// Send the REPORT (`Depth: 1`) and map the matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if [`build_bodies`](Self::build_bodies) fails,
+    /// and afterwards if the REPORT fails or the server responds with a non-success status.
+    pub async fn execute(self) -> Result<Vec<CalendarObject>> {
+        let bodies = self.build_bodies()?;
+        let mut objects: Vec<CalendarObject> = Vec::new();
+        for xml in bodies {
+            let resp = self.client.report(&self.path, Depth::One, &xml).await?;
+            if !resp.status().is_success() {
+                return Err(anyhow!(
+                    "REPORT calendar-query failed with {}",
+                    resp.status()
+                ));
+            }
+            let body = resp.into_body();
+            for object in map_calendar_objects(self.client.parse_multistatus(&body)?.items) {
+                if !objects.iter().any(|o| o.href == object.href) {
+                    objects.push(object);
+                }
+            }
+        }
+        if let Some(key) = self.order {
+            sort_calendar_objects(&mut objects, key);
+        }
+        Ok(objects)
+    }
+}
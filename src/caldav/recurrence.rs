@@ -0,0 +1,690 @@
+//! Client-side recurrence expansion (RFC 5545 §3.3.10, §3.8.5) for servers that ignore the
+//! `CALDAV:expand` element.
+//!
+//! Available with the `ical` feature. Rules are evaluated on wall-clock values in the time
+//! zone of `DTSTART`, which is what RFC 5545 prescribes for `TZID`-local and floating
+//! recurrences, so no time zone database is needed.
+
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::caldav::ical::{ICalComponent, ICalDateTime, ICalendar};
+use crate::common::datetime::{
+    civil_from_days, days_from_civil, days_in_month, parse_duration, parse_local_datetime,
+    parse_utc_datetime, weekday_from_days,
+};
+
+/// Upper bound on recurrence periods evaluated for one rule, guarding against rules that
+/// never match (e.g. `FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30`) or tiny intervals far in the past.
+const MAX_PERIODS: usize = 500_000;
+
+/// `FREQ` of a recurrence rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Secondly => "SECONDLY",
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+/// Day of the week, as used by `BYDAY` and `WKST`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Two-letter iCalendar code (`MO` … `SU`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "MO",
+            Weekday::Tuesday => "TU",
+            Weekday::Wednesday => "WE",
+            Weekday::Thursday => "TH",
+            Weekday::Friday => "FR",
+            Weekday::Saturday => "SA",
+            Weekday::Sunday => "SU",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.as_str().eq_ignore_ascii_case(code))
+    }
+
+    /// Monday = 0 … Sunday = 6.
+    fn index(self) -> i64 {
+        self as i64
+    }
+}
+
+/// A `BYDAY` entry: a weekday, optionally restricted to its n-th occurrence in the month or
+/// year (`2MO`, `-1FR`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// A parsed `RRULE` value.
+///
+/// `UNTIL` is kept in its original form (`DATE` or `DATE-TIME`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<String>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    /// A rule repeating every period of `freq`.
+    pub fn new(freq: Frequency) -> Self {
+        Self {
+            freq,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Monday,
+        }
+    }
+
+    /// Parse an `RRULE` value such as `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed rules and for the parts this crate does not evaluate
+    /// (`BYYEARDAY`, `BYWEEKNO`, `BYHOUR`, `BYMINUTE`, `BYSECOND`).
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut freq = None;
+        let mut rule = Self::new(Frequency::Daily);
+        for part in value.trim().split(';').filter(|p| !p.is_empty()) {
+            let (key, val) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("malformed RRULE part {part:?}"))?;
+            let invalid = || anyhow!("invalid RRULE {key} value {val:?}");
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(match val.to_ascii_uppercase().as_str() {
+                        "SECONDLY" => Frequency::Secondly,
+                        "MINUTELY" => Frequency::Minutely,
+                        "HOURLY" => Frequency::Hourly,
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid()),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = val.parse().ok().filter(|i| *i > 0).ok_or_else(invalid)?
+                }
+                "COUNT" => rule.count = Some(val.parse().map_err(|_| invalid())?),
+                "UNTIL" => {
+                    parse_local_datetime(val).ok_or_else(invalid)?;
+                    rule.until = Some(val.to_string());
+                }
+                "BYDAY" => {
+                    rule.by_day = val
+                        .split(',')
+                        .map(|d| parse_weekday_num(d).ok_or_else(invalid))
+                        .collect::<Result<_>>()?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day =
+                        parse_list(val, |n: i32| n != 0 && n.abs() <= 31).ok_or_else(invalid)?
+                }
+                "BYMONTH" => {
+                    rule.by_month =
+                        parse_list(val, |n: u32| (1..=12).contains(&n)).ok_or_else(invalid)?
+                }
+                "BYSETPOS" => {
+                    rule.by_set_pos =
+                        parse_list(val, |n: i32| n != 0 && n.abs() <= 366).ok_or_else(invalid)?
+                }
+                "WKST" => rule.week_start = Weekday::from_code(val).ok_or_else(invalid)?,
+                "BYYEARDAY" | "BYWEEKNO" | "BYHOUR" | "BYMINUTE" | "BYSECOND" => {
+                    return Err(anyhow!("unsupported RRULE part {key}"));
+                }
+                _ => {}
+            }
+        }
+        rule.freq = freq.ok_or_else(|| anyhow!("RRULE without FREQ"))?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err(anyhow!("RRULE cannot have both COUNT and UNTIL"));
+        }
+        Ok(rule)
+    }
+
+    /// Occurrence start times (wall-clock seconds) from `dtstart` up to, but excluding,
+    /// `horizon`. `dtstart` is always the first occurrence.
+    pub(crate) fn occurrences(&self, dtstart: i64, horizon: i64) -> Result<Vec<i64>> {
+        let until = self.until.as_deref().and_then(parse_local_datetime);
+        let limit = |t: i64| t >= horizon || until.is_some_and(|u| t > u);
+
+        let mut out = Vec::new();
+        if limit(dtstart) || self.count == Some(0) {
+            return Ok(out);
+        }
+        out.push(dtstart);
+        let mut emitted = 1u32;
+        if self.count.is_some_and(|c| emitted >= c) {
+            return Ok(out);
+        }
+
+        let time_of_day = dtstart.rem_euclid(86_400);
+        let start_day = dtstart.div_euclid(86_400);
+        let (year, month, day) = civil_from_days(start_day);
+        let interval = i64::from(self.interval);
+
+        for k in 0..MAX_PERIODS as i64 {
+            let step = k * interval;
+            let (period_start, mut candidates) = match self.freq {
+                Frequency::Yearly => {
+                    let y = year + step;
+                    (days_from_civil(y, 1, 1), self.yearly_days(y, month, day))
+                }
+                Frequency::Monthly => {
+                    let index = year * 12 + (month - 1) + step;
+                    let (y, m) = (index.div_euclid(12), index.rem_euclid(12) + 1);
+                    (days_from_civil(y, m, 1), self.monthly_days(y, m, day))
+                }
+                Frequency::Weekly => {
+                    let week = start_day
+                        - (weekday_from_days(start_day) - self.week_start.index()).rem_euclid(7)
+                        + 7 * step;
+                    (week, self.weekly_days(week, start_day))
+                }
+                Frequency::Daily => {
+                    let d = start_day + step;
+                    (
+                        d,
+                        self.matches_filters(d).then_some(d).into_iter().collect(),
+                    )
+                }
+                Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+                    let unit = match self.freq {
+                        Frequency::Hourly => 3_600,
+                        Frequency::Minutely => 60,
+                        _ => 1,
+                    };
+                    let t = dtstart + step * unit;
+                    if limit(t) {
+                        return Ok(out);
+                    }
+                    if t > dtstart && self.matches_filters(t.div_euclid(86_400)) {
+                        out.push(t);
+                        emitted += 1;
+                        if self.count.is_some_and(|c| emitted >= c) {
+                            return Ok(out);
+                        }
+                    }
+                    continue;
+                }
+            };
+            if limit(period_start * 86_400) && period_start > start_day {
+                return Ok(out);
+            }
+
+            candidates.sort_unstable();
+            candidates.dedup();
+            let mut times: Vec<i64> = candidates
+                .into_iter()
+                .map(|d| d * 86_400 + time_of_day)
+                .collect();
+            if !self.by_set_pos.is_empty() {
+                times = select_positions(&times, &self.by_set_pos);
+            }
+            for t in times.into_iter().filter(|t| *t > dtstart) {
+                if limit(t) {
+                    return Ok(out);
+                }
+                out.push(t);
+                emitted += 1;
+                if self.count.is_some_and(|c| emitted >= c) {
+                    return Ok(out);
+                }
+            }
+        }
+        Err(anyhow!(
+            "RRULE {} expansion exceeded {MAX_PERIODS} periods",
+            self
+        ))
+    }
+
+    fn yearly_days(&self, year: i64, month: i64, day: i64) -> Vec<i64> {
+        let months: Vec<i64> = if self.by_month.is_empty() {
+            if self.by_day.is_empty() && !self.by_month_day.is_empty() {
+                (1..=12).collect()
+            } else {
+                vec![month]
+            }
+        } else {
+            self.by_month.iter().map(|m| i64::from(*m)).collect()
+        };
+        if !self.by_day.is_empty() {
+            let mut days: Vec<i64> = if self.by_month.is_empty() {
+                let first = days_from_civil(year, 1, 1);
+                let last = days_from_civil(year + 1, 1, 1) - 1;
+                self.by_day
+                    .iter()
+                    .flat_map(|wd| weekdays_between(first, last, wd))
+                    .collect()
+            } else {
+                months
+                    .iter()
+                    .flat_map(|m| self.month_weekdays(year, *m))
+                    .collect()
+            };
+            if !self.by_month_day.is_empty() {
+                days.retain(|d| self.matches_month_day(*d));
+            }
+            return days;
+        }
+        if !self.by_month_day.is_empty() {
+            return months
+                .iter()
+                .flat_map(|m| self.month_days(year, *m))
+                .collect();
+        }
+        months
+            .iter()
+            .filter(|m| day <= days_in_month(year, **m))
+            .map(|m| days_from_civil(year, *m, day))
+            .collect()
+    }
+
+    fn monthly_days(&self, year: i64, month: i64, day: i64) -> Vec<i64> {
+        if !self.by_month.is_empty() && !self.by_month.contains(&(month as u32)) {
+            return Vec::new();
+        }
+        if !self.by_day.is_empty() {
+            let mut days = self.month_weekdays(year, month);
+            if !self.by_month_day.is_empty() {
+                days.retain(|d| self.matches_month_day(*d));
+            }
+            return days;
+        }
+        if !self.by_month_day.is_empty() {
+            return self.month_days(year, month);
+        }
+        if day <= days_in_month(year, month) {
+            vec![days_from_civil(year, month, day)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn weekly_days(&self, week_start: i64, start_day: i64) -> Vec<i64> {
+        let weekdays: Vec<i64> = if self.by_day.is_empty() {
+            vec![weekday_from_days(start_day)]
+        } else {
+            self.by_day.iter().map(|wd| wd.weekday.index()).collect()
+        };
+        weekdays
+            .into_iter()
+            .map(|wd| week_start + (wd - self.week_start.index()).rem_euclid(7))
+            .filter(|d| self.matches_month(*d))
+            .collect()
+    }
+
+    fn month_weekdays(&self, year: i64, month: i64) -> Vec<i64> {
+        let first = days_from_civil(year, month, 1);
+        let last = first + days_in_month(year, month) - 1;
+        self.by_day
+            .iter()
+            .flat_map(|wd| weekdays_between(first, last, wd))
+            .collect()
+    }
+
+    fn month_days(&self, year: i64, month: i64) -> Vec<i64> {
+        let len = days_in_month(year, month);
+        self.by_month_day
+            .iter()
+            .filter_map(|md| {
+                let md = i64::from(*md);
+                let day = if md > 0 { md } else { len + md + 1 };
+                (1..=len)
+                    .contains(&day)
+                    .then(|| days_from_civil(year, month, day))
+            })
+            .collect()
+    }
+
+    fn matches_month(&self, days: i64) -> bool {
+        let (_, month, _) = civil_from_days(days);
+        self.by_month.is_empty() || self.by_month.contains(&(month as u32))
+    }
+
+    fn matches_month_day(&self, days: i64) -> bool {
+        let (year, month, day) = civil_from_days(days);
+        let len = days_in_month(year, month);
+        self.by_month_day.iter().any(|md| {
+            let md = i64::from(*md);
+            md == day || md == day - len - 1
+        })
+    }
+
+    /// `BYMONTH`/`BYMONTHDAY`/`BYDAY` as filters, for `DAILY` and shorter frequencies.
+    fn matches_filters(&self, days: i64) -> bool {
+        self.matches_month(days)
+            && (self.by_month_day.is_empty() || self.matches_month_day(days))
+            && (self.by_day.is_empty()
+                || self
+                    .by_day
+                    .iter()
+                    .any(|wd| wd.weekday.index() == weekday_from_days(days)))
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    /// Serialize back to an `RRULE` value.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.freq.as_str())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = &self.until {
+            write!(f, ";UNTIL={until}")?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|wd| match wd.ordinal {
+                    Some(n) => format!("{n}{}", wd.weekday.as_str()),
+                    None => wd.weekday.as_str().to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        write_list(f, "BYMONTHDAY", &self.by_month_day)?;
+        write_list(f, "BYMONTH", &self.by_month)?;
+        write_list(f, "BYSETPOS", &self.by_set_pos)?;
+        if self.week_start != Weekday::Monday {
+            write!(f, ";WKST={}", self.week_start.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, key: &str, values: &[T]) -> fmt::Result {
+    if values.is_empty() {
+        return Ok(());
+    }
+    let values: Vec<String> = values.iter().map(T::to_string).collect();
+    write!(f, ";{key}={}", values.join(","))
+}
+
+fn parse_weekday_num(value: &str) -> Option<WeekdayNum> {
+    let value = value.trim();
+    let split = value.len().checked_sub(2)?;
+    let weekday = Weekday::from_code(value.get(split..)?)?;
+    let ordinal = match &value[..split] {
+        "" => None,
+        n => Some(n.parse::<i32>().ok().filter(|n| *n != 0 && n.abs() <= 53)?),
+    };
+    Some(WeekdayNum { ordinal, weekday })
+}
+
+fn parse_list<T: std::str::FromStr + Copy>(
+    value: &str,
+    valid: impl Fn(T) -> bool,
+) -> Option<Vec<T>> {
+    value
+        .split(',')
+        .map(|v| v.trim().parse::<T>().ok().filter(|n| valid(*n)))
+        .collect()
+}
+
+/// Days in `[first, last]` falling on `wd.weekday`, narrowed to the n-th one when an
+/// ordinal is given (negative ordinals count from the end).
+fn weekdays_between(first: i64, last: i64, wd: &WeekdayNum) -> Vec<i64> {
+    let offset = (wd.weekday.index() - weekday_from_days(first)).rem_euclid(7);
+    let all: Vec<i64> = (first + offset..=last).step_by(7).collect();
+    match wd.ordinal {
+        None => all,
+        Some(n) if n > 0 => all.get(n as usize - 1).copied().into_iter().collect(),
+        Some(n) => all
+            .len()
+            .checked_sub(n.unsigned_abs() as usize)
+            .and_then(|i| all.get(i).copied())
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn select_positions(sorted: &[i64], positions: &[i32]) -> Vec<i64> {
+    let len = sorted.len() as i64;
+    let mut picked: Vec<i64> = positions
+        .iter()
+        .filter_map(|p| {
+            let p = i64::from(*p);
+            let index = if p > 0 { p - 1 } else { len + p };
+            (0..len).contains(&index).then(|| sorted[index as usize])
+        })
+        .collect();
+    picked.sort_unstable();
+    picked.dedup();
+    picked
+}
+
+/// One concrete occurrence of a (possibly recurring) component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+    pub uid: Option<String>,
+    /// Identifies the occurrence in the series (its original start), in the form of
+    /// `DTSTART`.
+    pub recurrence_id: ICalDateTime,
+    pub start: ICalDateTime,
+    /// End of the occurrence (`DTEND`/`DUE`, or `DTSTART` plus `DURATION`).
+    pub end: Option<ICalDateTime>,
+    /// The component describing this occurrence: the master, or the override carrying a
+    /// matching `RECURRENCE-ID`.
+    pub component: ICalComponent,
+    pub is_override: bool,
+}
+
+/// Expand the `VEVENT`/`VTODO`/`VJOURNAL` series of a calendar object into the occurrences
+/// overlapping `[start, end)`, sorted by start.
+///
+/// Evaluates `RRULE`, `RDATE` and `EXDATE`, and substitutes overridden occurrences
+/// (`RECURRENCE-ID`). `start`/`end` are iCalendar UTC date-times; for `TZID`-local and
+/// floating series they are compared with the local wall-clock times, so widen the window
+/// by a day when the zone offset matters. `RANGE=THISANDFUTURE` overrides only replace
+/// their own occurrence.
+///
+/// # Errors
+///
+/// Returns an error if `start`/`end` are not UTC date-times or a rule cannot be evaluated
+/// (see [`RecurrenceRule::parse`]).
+pub fn expand_instances(calendar: &ICalendar, start: &str, end: &str) -> Result<Vec<Instance>> {
+    let window_start =
+        parse_utc_datetime(start).ok_or_else(|| anyhow!("invalid expansion start {start:?}"))?;
+    let window_end =
+        parse_utc_datetime(end).ok_or_else(|| anyhow!("invalid expansion end {end:?}"))?;
+
+    let mut overrides: HashMap<(Option<&str>, i64), &ICalComponent> = HashMap::new();
+    for component in &calendar.components {
+        if let Some(rid) = component
+            .recurrence_id
+            .as_ref()
+            .and_then(|r| parse_local_datetime(&r.value))
+        {
+            overrides.insert((component.uid.as_deref(), rid), component);
+        }
+    }
+
+    let mut instances = Vec::new();
+    for master in calendar
+        .components
+        .iter()
+        .filter(|c| c.recurrence_id.is_none())
+    {
+        let Some(dtstart) = &master.dtstart else {
+            continue;
+        };
+        let first = parse_local_datetime(&dtstart.value)
+            .ok_or_else(|| anyhow!("invalid DTSTART {:?}", dtstart.value))?;
+        let duration = component_duration(master, first);
+
+        let mut starts = match &master.rrule {
+            Some(rrule) => RecurrenceRule::parse(rrule)?.occurrences(first, window_end)?,
+            None => vec![first],
+        };
+        starts.extend(
+            master
+                .rdates
+                .iter()
+                .filter_map(|r| parse_local_datetime(r.value.split('/').next().unwrap_or("")))
+                .filter(|t| *t < window_end),
+        );
+        let excluded: Vec<i64> = master
+            .exdates
+            .iter()
+            .filter_map(|e| parse_local_datetime(&e.value))
+            .collect();
+        starts.retain(|t| !excluded.contains(t));
+        starts.sort_unstable();
+        starts.dedup();
+
+        for t in starts {
+            if overrides.contains_key(&(master.uid.as_deref(), t)) {
+                continue;
+            }
+            if overlaps(t, duration, window_start, window_end) {
+                instances.push(Instance {
+                    uid: master.uid.clone(),
+                    recurrence_id: like(dtstart, t),
+                    start: like(dtstart, t),
+                    end: (duration > 0 || dtstart.is_date()).then(|| like(dtstart, t + duration)),
+                    component: master.clone(),
+                    is_override: false,
+                });
+            }
+        }
+    }
+
+    for component in overrides.into_values() {
+        let (Some(rid), Some(dtstart)) = (&component.recurrence_id, &component.dtstart) else {
+            continue;
+        };
+        let Some(t) = parse_local_datetime(&dtstart.value) else {
+            continue;
+        };
+        let duration = component_duration(component, t);
+        if overlaps(t, duration, window_start, window_end) {
+            instances.push(Instance {
+                uid: component.uid.clone(),
+                recurrence_id: rid.clone(),
+                start: dtstart.clone(),
+                end: (duration > 0 || dtstart.is_date()).then(|| like(dtstart, t + duration)),
+                component: component.clone(),
+                is_override: true,
+            });
+        }
+    }
+
+    instances.sort_by_key(|i| parse_local_datetime(&i.start.value).unwrap_or_default());
+    Ok(instances)
+}
+
+/// Length of each occurrence in seconds: `DTEND`/`DUE` minus `DTSTART`, else `DURATION`,
+/// else one day for all-day components and zero otherwise.
+fn component_duration(component: &ICalComponent, start: i64) -> i64 {
+    let end = component.dtend.as_ref().or(component.due.as_ref());
+    if let Some(end) = end.and_then(|e| parse_local_datetime(&e.value)) {
+        return (end - start).max(0);
+    }
+    if let Some(duration) = component.duration.as_deref().and_then(parse_duration) {
+        return duration.max(0);
+    }
+    if component
+        .dtstart
+        .as_ref()
+        .is_some_and(ICalDateTime::is_date)
+    {
+        86_400
+    } else {
+        0
+    }
+}
+
+fn overlaps(start: i64, duration: i64, window_start: i64, window_end: i64) -> bool {
+    start < window_end
+        && if duration > 0 {
+            start + duration > window_start
+        } else {
+            start >= window_start
+        }
+}
+
+/// Format `t` in the same form (date, floating/local or UTC) and zone as `template`.
+fn like(template: &ICalDateTime, t: i64) -> ICalDateTime {
+    let days = t.div_euclid(86_400);
+    let secs = t.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    let mut value = format!("{year:04}{month:02}{day:02}");
+    if !template.is_date() {
+        value.push_str(&format!(
+            "T{:02}{:02}{:02}",
+            secs / 3_600,
+            (secs % 3_600) / 60,
+            secs % 60
+        ));
+        if template.is_utc() {
+            value.push('Z');
+        }
+    }
+    ICalDateTime {
+        value,
+        tzid: template.tzid.clone(),
+    }
+}
@@ -0,0 +1,675 @@
+//! Request builders and response parsers for CalDAV free-busy and scheduling (RFC 4791 §7.10,
+//! RFC 6638).
+
+use anyhow::{Result, anyhow};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::caldav::types::{
+    AttendeeRole, FreeBusy, FreeBusyPeriod, FreeBusyType, ItipMethod, Partstat,
+    ScheduleRecipientStatus,
+};
+use crate::common::contentline::{Component, ContentLine, escape_text, fold_line};
+use crate::common::datetime::{format_utc_datetime, parse_duration, parse_utc_datetime};
+use crate::webdav::xml::{escape_xml, event_text, local_name};
+
+/// Build a `CALDAV:free-busy-query` REPORT body for the window `[start, end)`.
+///
+/// `start` and `end` are iCalendar UTC date-times (e.g. `20240101T000000Z`).
+pub fn build_free_busy_query_body(start: &str, end: &str) -> String {
+    format!(
+        r#"<C:free-busy-query xmlns:C="urn:ietf:params:xml:ns:caldav"><C:time-range start="{}" end="{}"/></C:free-busy-query>"#,
+        escape_xml(start),
+        escape_xml(end)
+    )
+}
+
+/// Parse the `VFREEBUSY` component of an iCalendar payload into typed periods.
+///
+/// Every `FREEBUSY` property may carry several comma-separated periods; each is returned
+/// separately with its `FBTYPE` (defaulting to `BUSY`). Both `start/end` and `start/duration`
+/// forms are accepted and normalized to UTC `start`/`end` pairs. A payload without a
+/// `VFREEBUSY` yields an empty [`FreeBusy`].
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar or a period is malformed.
+pub fn parse_free_busy(ics: &str) -> Result<FreeBusy> {
+    let mut out = FreeBusy::default();
+    for calendar in Component::parse_all(ics)? {
+        for vfreebusy in calendar.components_named("VFREEBUSY") {
+            out.start = out
+                .start
+                .or_else(|| vfreebusy.value("DTSTART").map(str::to_string));
+            out.end = out
+                .end
+                .or_else(|| vfreebusy.value("DTEND").map(str::to_string));
+            for property in vfreebusy.properties_named("FREEBUSY") {
+                let kind = property
+                    .param("FBTYPE")
+                    .map(FreeBusyType::from_param)
+                    .unwrap_or(FreeBusyType::Busy);
+                for period in property.value.split(',').filter(|p| !p.trim().is_empty()) {
+                    let (start, end) = parse_period(period.trim())?;
+                    out.periods.push(FreeBusyPeriod {
+                        start,
+                        end,
+                        kind: kind.clone(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Merge busy periods (e.g. from several calendars) into one normalized timeline.
+///
+/// `FREE` and empty periods are dropped; the rest are cut into non-overlapping periods
+/// sorted by start, and adjacent periods of the same type are joined. Where periods of
+/// different types overlap the strongest wins: `BUSY-UNAVAILABLE`, then `BUSY` (and
+/// unknown types), then `BUSY-TENTATIVE`. Bounds must be UTC date-times as returned by
+/// [`parse_free_busy`].
+pub fn merge_busy_periods<I>(periods: I) -> Vec<FreeBusyPeriod>
+where
+    I: IntoIterator<Item = FreeBusyPeriod>,
+{
+    let busy: Vec<FreeBusyPeriod> = periods
+        .into_iter()
+        .filter(|p| p.kind.is_busy() && p.start < p.end)
+        .collect();
+    let mut bounds: Vec<&str> = busy
+        .iter()
+        .flat_map(|p| [p.start.as_str(), p.end.as_str()])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut merged: Vec<FreeBusyPeriod> = Vec::new();
+    for window in bounds.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let Some(strongest) = busy
+            .iter()
+            .filter(|p| p.start.as_str() <= start && p.end.as_str() >= end)
+            .max_by_key(|p| busy_rank(&p.kind))
+        else {
+            continue;
+        };
+        match merged.last_mut() {
+            Some(last) if last.end == start && last.kind == strongest.kind => {
+                last.end = end.to_string()
+            }
+            _ => merged.push(FreeBusyPeriod {
+                start: start.to_string(),
+                end: end.to_string(),
+                kind: strongest.kind.clone(),
+            }),
+        }
+    }
+    merged
+}
+
+fn busy_rank(kind: &FreeBusyType) -> u8 {
+    match kind {
+        FreeBusyType::Free => 0,
+        FreeBusyType::BusyTentative => 1,
+        FreeBusyType::Busy | FreeBusyType::Other(_) => 2,
+        FreeBusyType::BusyUnavailable => 3,
+    }
+}
+
+/// Parse an iCalendar `PERIOD` (`start/end` or `start/duration`) into normalized UTC bounds.
+pub(crate) fn parse_period(period: &str) -> Result<(String, String)> {
+    let (start, rest) = period.split_once('/').ok_or_else(|| {
+        anyhow!("invalid period {period:?}: expected start/end or start/duration")
+    })?;
+    let start_ts = parse_utc_datetime(start)
+        .ok_or_else(|| anyhow!("invalid period start {start:?}: expected YYYYMMDDTHHMMSSZ"))?;
+    let end_ts = if rest.trim_start_matches(['+', '-']).starts_with('P') {
+        start_ts
+            + parse_duration(rest).ok_or_else(|| anyhow!("invalid period duration {rest:?}"))?
+    } else {
+        parse_utc_datetime(rest)
+            .ok_or_else(|| anyhow!("invalid period end {rest:?}: expected YYYYMMDDTHHMMSSZ"))?
+    };
+    Ok((format_utc_datetime(start_ts), format_utc_datetime(end_ts)))
+}
+
+/// Build an iTIP `REQUEST` asking the server for the free-busy time of `attendees`.
+///
+/// `organizer` and `attendees` are calendar user addresses (e.g. `mailto:jane@example.com`);
+/// `start`/`end` are iCalendar UTC date-times. The result is meant to be `POST`ed to the
+/// organizer's schedule outbox, see [`crate::caldav::CalDavClient::schedule_post`].
+pub fn build_free_busy_request<I, S>(
+    organizer: &str,
+    attendees: I,
+    start: &str,
+    end: &str,
+    uid: &str,
+) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//fast-dav-rs//EN".to_string(),
+        "METHOD:REQUEST".to_string(),
+        "BEGIN:VFREEBUSY".to_string(),
+        format!("UID:{}", escape_text(uid)),
+        format!("DTSTAMP:{}", format_utc_datetime(now)),
+        format!("DTSTART:{start}"),
+        format!("DTEND:{end}"),
+        format!("ORGANIZER:{organizer}"),
+    ];
+    lines.extend(
+        attendees
+            .into_iter()
+            .map(|a| format!("ATTENDEE:{}", a.as_ref())),
+    );
+    lines.push("END:VFREEBUSY".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|l| fold_line(l)).collect()
+}
+
+/// Return the iTIP `METHOD` of an iCalendar payload (e.g. `REQUEST`, `REPLY`, `CANCEL`).
+pub fn itip_method(ics: &str) -> Option<String> {
+    Component::parse_all(ics)
+        .ok()?
+        .iter()
+        .find_map(|c| c.value("METHOD").map(|m| m.trim().to_ascii_uppercase()))
+}
+
+/// Set the `PARTSTAT` of the attendee(s) matching `addresses` in every scheduling component
+/// (`VEVENT`, `VTODO`, `VJOURNAL`, including overridden instances) and return the updated
+/// iCalendar.
+///
+/// `addresses` are the current user's calendar user addresses (see
+/// [`crate::caldav::CalDavClient::discover_calendar_user_addresses`]); they are compared
+/// case-insensitively and with or without the `mailto:` scheme.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar or no `ATTENDEE` matches
+/// `addresses`.
+pub fn set_attendee_partstat<I, S>(ics: &str, addresses: I, partstat: Partstat) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let addresses: Vec<String> = addresses
+        .into_iter()
+        .map(|a| normalize_cal_address(a.as_ref()))
+        .collect();
+    let mut calendars = Component::parse_all(ics)?;
+
+    let mut matched = false;
+    for calendar in &mut calendars {
+        for component in calendar
+            .components
+            .iter_mut()
+            .filter(|c| matches!(c.name.as_str(), "VEVENT" | "VTODO" | "VJOURNAL"))
+        {
+            for attendee in component
+                .properties
+                .iter_mut()
+                .filter(|p| p.base_name() == "ATTENDEE")
+            {
+                if addresses.contains(&normalize_cal_address(&attendee.value)) {
+                    attendee.set_param("PARTSTAT", partstat.as_str());
+                    matched = true;
+                }
+            }
+        }
+    }
+    if !matched {
+        return Err(anyhow!(
+            "no ATTENDEE matches the calendar user addresses {addresses:?}"
+        ));
+    }
+
+    Ok(calendars.iter().map(Component::to_ics).collect())
+}
+
+/// Invite `address` to every scheduling component (`VEVENT`, `VTODO`, `VJOURNAL`, including
+/// overridden instances) and bump their `SEQUENCE`.
+///
+/// The attendee is added with `PARTSTAT=NEEDS-ACTION` and `RSVP=TRUE`; if already present,
+/// only its `ROLE` is updated. A bare address gets a `mailto:` prefix.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar or has no scheduling component.
+pub fn add_attendee(ics: &str, address: &str, role: AttendeeRole) -> Result<String> {
+    let wanted = normalize_cal_address(address);
+    let address = if address.contains(':') {
+        address.trim().to_string()
+    } else {
+        format!("mailto:{}", address.trim())
+    };
+    edit_scheduling_components(ics, |component| {
+        match component
+            .properties
+            .iter_mut()
+            .find(|p| p.base_name() == "ATTENDEE" && normalize_cal_address(&p.value) == wanted)
+        {
+            Some(attendee) => attendee.set_param("ROLE", role.as_str()),
+            None => {
+                let mut attendee = ContentLine::new("ATTENDEE", address.clone());
+                attendee.set_param("ROLE", role.as_str());
+                attendee.set_param("PARTSTAT", Partstat::NeedsAction.as_str());
+                attendee.set_param("RSVP", "TRUE");
+                component.properties.push(attendee);
+            }
+        }
+        true
+    })
+}
+
+/// Remove the attendee matching `address` (compared like [`set_attendee_partstat`]) from every
+/// scheduling component and bump the `SEQUENCE` of the components that listed it.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar or no `ATTENDEE` matches.
+pub fn remove_attendee(ics: &str, address: &str) -> Result<String> {
+    let wanted = normalize_cal_address(address);
+    let mut matched = false;
+    let updated = edit_scheduling_components(ics, |component| {
+        let before = component.properties.len();
+        component.properties.retain(|p| {
+            !(p.base_name() == "ATTENDEE" && normalize_cal_address(&p.value) == wanted)
+        });
+        let removed = component.properties.len() != before;
+        matched |= removed;
+        removed
+    })?;
+    if !matched {
+        return Err(anyhow!("no ATTENDEE matches {address}"));
+    }
+    Ok(updated)
+}
+
+/// Apply `edit` to each scheduling component, incrementing `SEQUENCE` where it reports a change.
+fn edit_scheduling_components(
+    ics: &str,
+    mut edit: impl FnMut(&mut Component) -> bool,
+) -> Result<String> {
+    let mut calendars = Component::parse_all(ics)?;
+    let mut found = false;
+    for component in calendars
+        .iter_mut()
+        .flat_map(|c| c.components.iter_mut())
+        .filter(|c| matches!(c.name.as_str(), "VEVENT" | "VTODO" | "VJOURNAL"))
+    {
+        found = true;
+        if edit(component) {
+            let current: u32 = component
+                .value("SEQUENCE")
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or(0);
+            component.set_value("SEQUENCE", (current + 1).to_string());
+        }
+    }
+    if !found {
+        return Err(anyhow!("calendar object contains no scheduling component"));
+    }
+    Ok(calendars.iter().map(Component::to_ics).collect())
+}
+
+fn normalize_cal_address(address: &str) -> String {
+    let address = address.trim();
+    let address = match address.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => &address[7..],
+        _ => address,
+    };
+    address.to_ascii_lowercase()
+}
+
+/// Scheduling parameters set by the server (RFC 6638 §7) that must not leak into iTIP messages.
+const SERVER_SCHEDULING_PARAMS: [&str; 3] =
+    ["SCHEDULE-AGENT", "SCHEDULE-STATUS", "SCHEDULE-FORCE-SEND"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SequenceChange {
+    Keep,
+    Increment,
+    Set(u32),
+}
+
+/// Builds iTIP `REQUEST`/`REPLY`/`CANCEL`/`COUNTER` messages (RFC 5546) from an existing event.
+///
+/// The output is a plain iCalendar payload: `POST` it to a schedule outbox with
+/// [`crate::caldav::CalDavClient::schedule_post`] or attach it to an email (iMIP, RFC 6047).
+///
+/// For every `VEVENT` (including overridden instances) the builder sets `METHOD`, refreshes
+/// `DTSTAMP`, applies the requested `SEQUENCE` change, strips server-side scheduling
+/// parameters and personal `VALARM`s, and adjusts the attendee list for the method:
+///
+/// - `REQUEST` keeps (or, with [`attendees`](Self::attendees), replaces) the invitee list.
+/// - `CANCEL` additionally sets `STATUS:CANCELLED`; [`attendees`](Self::attendees) restricts
+///   the cancellation to a subset of invitees.
+/// - `REPLY` and `COUNTER` keep only the sending attendee set with
+///   [`from_attendee`](Self::from_attendee).
+///
+/// # Example
+///
+/// ```no_run
+/// use fast_dav_rs::caldav::{ItipBuilder, ItipMethod};
+///
+/// # fn demo(event_ics: &str) -> anyhow::Result<()> {
+/// let request = ItipBuilder::new(ItipMethod::Request, event_ics)?
+///     .attendees(["mailto:jane@example.com", "mailto:bob@example.com"])
+///     .increment_sequence()
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ItipBuilder {
+    method: ItipMethod,
+    calendar: Component,
+    attendees: Option<Vec<String>>,
+    from_attendee: Option<(String, Partstat)>,
+    sequence: SequenceChange,
+}
+
+impl ItipBuilder {
+    /// Start from an existing `VCALENDAR` containing at least one `VEVENT`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `ics` is not a single valid `VCALENDAR` or has no `VEVENT`.
+    pub fn new(method: ItipMethod, ics: &str) -> Result<Self> {
+        let calendar = Component::parse(ics)?;
+        if calendar.name != "VCALENDAR" {
+            return Err(anyhow!("expected a VCALENDAR, found {}", calendar.name));
+        }
+        if calendar.components_named("VEVENT").next().is_none() {
+            return Err(anyhow!("iTIP messages require at least one VEVENT"));
+        }
+        Ok(Self {
+            method,
+            calendar,
+            attendees: None,
+            from_attendee: None,
+            sequence: SequenceChange::Keep,
+        })
+    }
+
+    /// Replace the invitee list (`REQUEST`) or restrict the recipients (`CANCEL`).
+    ///
+    /// Attendees already on the event keep their parameters; new ones are added with
+    /// `PARTSTAT=NEEDS-ACTION;RSVP=TRUE`.
+    pub fn attendees<I, S>(mut self, attendees: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.attendees = Some(
+            attendees
+                .into_iter()
+                .map(|a| a.as_ref().trim().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// The attendee sending a `REPLY` or `COUNTER`, and the participation status to report.
+    pub fn from_attendee(mut self, address: &str, partstat: Partstat) -> Self {
+        self.from_attendee = Some((address.trim().to_string(), partstat));
+        self
+    }
+
+    /// Increment `SEQUENCE` (missing counts as `0`), as required for significant organizer
+    /// changes and cancellations.
+    pub fn increment_sequence(mut self) -> Self {
+        self.sequence = SequenceChange::Increment;
+        self
+    }
+
+    /// Set `SEQUENCE` to an explicit value.
+    pub fn sequence(mut self, sequence: u32) -> Self {
+        self.sequence = SequenceChange::Set(sequence);
+        self
+    }
+
+    /// Produce the iTIP payload (CRLF line endings, folded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a `VEVENT` has no `ORGANIZER`, when `REPLY`/`COUNTER` is built
+    /// without [`from_attendee`](Self::from_attendee), or when a `REQUEST`/`CANCEL` ends up
+    /// without any `ATTENDEE`.
+    pub fn build(self) -> Result<String> {
+        let Self {
+            method,
+            mut calendar,
+            attendees,
+            from_attendee,
+            sequence,
+        } = self;
+
+        if matches!(method, ItipMethod::Reply | ItipMethod::Counter) && from_attendee.is_none() {
+            return Err(anyhow!(
+                "{} messages require the sending attendee (from_attendee)",
+                method.as_str()
+            ));
+        }
+
+        calendar.remove_properties("METHOD");
+        let method_pos = calendar
+            .properties
+            .iter()
+            .rposition(|p| matches!(p.base_name(), "VERSION" | "PRODID" | "CALSCALE"))
+            .map_or(0, |idx| idx + 1);
+        calendar
+            .properties
+            .insert(method_pos, ContentLine::new("METHOD", method.as_str()));
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        let dtstamp = format_utc_datetime(now);
+
+        for event in calendar
+            .components
+            .iter_mut()
+            .filter(|c| c.name == "VEVENT")
+        {
+            if event.property("ORGANIZER").is_none() {
+                return Err(anyhow!(
+                    "VEVENT {} has no ORGANIZER",
+                    event.value("UID").unwrap_or("<no UID>")
+                ));
+            }
+            event.set_value("DTSTAMP", dtstamp.clone());
+            match sequence {
+                SequenceChange::Keep => {}
+                SequenceChange::Increment => {
+                    let current = event
+                        .value("SEQUENCE")
+                        .and_then(|v| v.trim().parse::<u32>().ok())
+                        .unwrap_or(0);
+                    event.set_value("SEQUENCE", (current + 1).to_string());
+                }
+                SequenceChange::Set(n) => event.set_value("SEQUENCE", n.to_string()),
+            }
+            event.components.retain(|c| c.name != "VALARM");
+            for property in event
+                .properties
+                .iter_mut()
+                .filter(|p| matches!(p.base_name(), "ORGANIZER" | "ATTENDEE"))
+            {
+                for param in SERVER_SCHEDULING_PARAMS {
+                    property.remove_param(param);
+                }
+            }
+
+            match (&from_attendee, &attendees) {
+                (Some((address, partstat)), _)
+                    if matches!(method, ItipMethod::Reply | ItipMethod::Counter) =>
+                {
+                    let wanted = normalize_cal_address(address);
+                    let mut line = event
+                        .properties_named("ATTENDEE")
+                        .find(|a| normalize_cal_address(&a.value) == wanted)
+                        .cloned()
+                        .unwrap_or_else(|| ContentLine::new("ATTENDEE", address.clone()));
+                    line.set_param("PARTSTAT", partstat.as_str());
+                    line.remove_param("RSVP");
+                    replace_attendees(event, vec![line]);
+                }
+                (_, Some(list)) => {
+                    let lines = list
+                        .iter()
+                        .map(|address| {
+                            let wanted = normalize_cal_address(address);
+                            event
+                                .properties_named("ATTENDEE")
+                                .find(|a| normalize_cal_address(&a.value) == wanted)
+                                .cloned()
+                                .unwrap_or_else(|| {
+                                    let mut line = ContentLine::new("ATTENDEE", address.clone());
+                                    line.set_param("PARTSTAT", Partstat::NeedsAction.as_str());
+                                    line.set_param("RSVP", "TRUE");
+                                    line
+                                })
+                        })
+                        .collect();
+                    replace_attendees(event, lines);
+                }
+                _ => {}
+            }
+
+            if method == ItipMethod::Cancel {
+                event.set_value("STATUS", "CANCELLED");
+            }
+            if matches!(method, ItipMethod::Request | ItipMethod::Cancel)
+                && event.property("ATTENDEE").is_none()
+            {
+                return Err(anyhow!(
+                    "{} for VEVENT {} has no ATTENDEE",
+                    method.as_str(),
+                    event.value("UID").unwrap_or("<no UID>")
+                ));
+            }
+        }
+
+        Ok(calendar.to_ics())
+    }
+}
+
+/// Replace all `ATTENDEE` properties, keeping them where the first one used to be.
+fn replace_attendees(event: &mut Component, attendees: Vec<ContentLine>) {
+    let pos = event
+        .properties
+        .iter()
+        .position(|p| p.base_name() == "ATTENDEE")
+        .unwrap_or(event.properties.len());
+    event.remove_properties("ATTENDEE");
+    let pos = pos.min(event.properties.len());
+    event.properties.splice(pos..pos, attendees);
+}
+
+/// Parse a `CALDAV:schedule-response` body into per-recipient statuses.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_schedule_response(body: &[u8]) -> Result<Vec<ScheduleRecipientStatus>> {
+    let mut xml = Reader::from_reader(body);
+    xml.config_mut().trim_text(false);
+
+    let mut buf = Vec::new();
+    let mut out = Vec::new();
+    let mut current: Option<ScheduleRecipientStatus> = None;
+    let mut field: Option<ScheduleField> = None;
+    // Text arrives in pieces around entity references; it is applied at the next tag.
+    let mut text = String::new();
+
+    loop {
+        let event = xml.read_event_into(&mut buf);
+        if let Ok(Event::Start(_) | Event::End(_) | Event::Empty(_)) = event {
+            push_schedule_text(&mut current, field, &text);
+            text.clear();
+        }
+        match event {
+            Ok(Event::Start(e)) => match local_name(e.name().as_ref()).as_str() {
+                "response" => current = Some(ScheduleRecipientStatus::default()),
+                "recipient" => field = Some(ScheduleField::Recipient),
+                "request-status" => field = Some(ScheduleField::RequestStatus),
+                "calendar-data" => field = Some(ScheduleField::CalendarData),
+                "responsedescription" => field = Some(ScheduleField::Description),
+                _ => {}
+            },
+            Ok(Event::End(e)) => match local_name(e.name().as_ref()).as_str() {
+                "response" => {
+                    if let Some(item) = current.take() {
+                        out.push(item);
+                    }
+                }
+                "recipient" | "request-status" | "calendar-data" | "responsedescription" => {
+                    field = None
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Ok(event) => {
+                if let Some(piece) = event_text(&event)? {
+                    text.push_str(&piece);
+                }
+            }
+            Err(e) => return Err(anyhow!("XML error: {e}")),
+        }
+        buf.clear();
+    }
+
+    Ok(out)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScheduleField {
+    Recipient,
+    RequestStatus,
+    CalendarData,
+    Description,
+}
+
+fn push_schedule_text(
+    current: &mut Option<ScheduleRecipientStatus>,
+    field: Option<ScheduleField>,
+    text: &str,
+) {
+    let (Some(item), Some(field)) = (current.as_mut(), field) else {
+        return;
+    };
+    let target = match field {
+        ScheduleField::Recipient => {
+            item.recipient.push_str(text.trim());
+            return;
+        }
+        ScheduleField::RequestStatus => &mut item.request_status,
+        ScheduleField::CalendarData => &mut item.calendar_data,
+        ScheduleField::Description => &mut item.description,
+    };
+    // Keep the exact calendar-data payload.
+    let text = if field == ScheduleField::CalendarData {
+        text
+    } else {
+        text.trim()
+    };
+    if text.is_empty() {
+        return;
+    }
+    target.get_or_insert_with(String::new).push_str(text);
+}
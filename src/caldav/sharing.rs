@@ -0,0 +1,274 @@
+//! Calendar sharing via the calendarserver-sharing extension
+//! (`http://calendarserver.org/ns/`), as implemented by SabreDAV, Nextcloud and iCloud.
+//!
+//! Owners `POST` a `CS:share` request to the calendar and read the sharees back from its
+//! `CS:invite` property. Sharees receive invite notifications in their `CS:notification-URL`
+//! collection and answer with a `CS:invite-reply` `POST` to their calendar home.
+
+use anyhow::{Result, anyhow};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::caldav::types::{
+    CalendarInvite, DavItem, InviteStatus, ShareAccess, ShareInvitation, ShareInvite,
+    SharedCalendar, Sharee,
+};
+use crate::webdav::xml::{escape_xml, event_text, local_name};
+
+const SHARING_NAMESPACES: &str = r#"xmlns:D="DAV:" xmlns:CS="http://calendarserver.org/ns/""#;
+
+/// Build a `CS:share` request body inviting `invites` and revoking `removals` (sharee hrefs).
+pub fn build_share_body<S: AsRef<str>>(invites: &[ShareInvite], removals: &[S]) -> String {
+    let mut xml =
+        format!(r#"<?xml version="1.0" encoding="utf-8"?><CS:share {SHARING_NAMESPACES}>"#);
+    for invite in invites {
+        xml.push_str("<CS:set><D:href>");
+        xml.push_str(&escape_xml(&invite.href));
+        xml.push_str("</D:href>");
+        if let Some(name) = &invite.common_name {
+            xml.push_str(&format!(
+                "<CS:common-name>{}</CS:common-name>",
+                escape_xml(name)
+            ));
+        }
+        if let Some(summary) = &invite.summary {
+            xml.push_str(&format!("<CS:summary>{}</CS:summary>", escape_xml(summary)));
+        }
+        xml.push_str(&format!("<CS:{}/></CS:set>", invite.access.as_str()));
+    }
+    for href in removals {
+        xml.push_str(&format!(
+            "<CS:remove><D:href>{}</D:href></CS:remove>",
+            escape_xml(href.as_ref())
+        ));
+    }
+    xml.push_str("</CS:share>");
+    xml
+}
+
+/// Build the `CS:invite-reply` body accepting or declining `invitation`.
+pub fn build_invite_reply_body(invitation: &ShareInvitation, accept: bool) -> String {
+    let answer = if accept {
+        "invite-accepted"
+    } else {
+        "invite-declined"
+    };
+    let organizer = invitation.organizer.as_deref().unwrap_or(&invitation.href);
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><CS:invite-reply {SHARING_NAMESPACES}><D:href>{}</D:href><CS:{answer}/><CS:hosturl><D:href>{}</D:href></CS:hosturl><CS:in-reply-to>{}</CS:in-reply-to>"#,
+        escape_xml(organizer),
+        escape_xml(&invitation.host_url),
+        escape_xml(&invitation.uid),
+    );
+    if let Some(summary) = &invitation.summary {
+        xml.push_str(&format!("<CS:summary>{}</CS:summary>", escape_xml(summary)));
+    }
+    xml.push_str("</CS:invite-reply>");
+    xml
+}
+
+/// Parse the `CS:invite` property from a `PROPFIND` multistatus.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_calendar_invite(body: &[u8]) -> Result<CalendarInvite> {
+    let mut invite = CalendarInvite::default();
+    let mut sharee: Option<Sharee> = None;
+    walk(body, |path, node| {
+        let Some(at) = path.iter().position(|n| n == "invite") else {
+            return;
+        };
+        let path = &path[at + 1..];
+        match (path, node) {
+            ([user], Node::Start) if user == "user" => sharee = Some(Sharee::default()),
+            ([user], Node::End) if user == "user" => invite.sharees.extend(sharee.take()),
+            ([organizer, field], Node::Text(text)) if organizer == "organizer" => {
+                match field.as_str() {
+                    "href" => invite.organizer = Some(text.to_string()),
+                    "common-name" => invite.organizer_name = Some(text.to_string()),
+                    _ => {}
+                }
+            }
+            ([user, rest @ ..], node) if user == "user" => {
+                if let Some(sharee) = sharee.as_mut() {
+                    apply_sharee_field(sharee, rest, node);
+                }
+            }
+            _ => {}
+        }
+    })?;
+    Ok(invite)
+}
+
+fn apply_sharee_field(sharee: &mut Sharee, path: &[String], node: Node<'_>) {
+    let names: Vec<&str> = path.iter().map(String::as_str).collect();
+    match (names.as_slice(), node) {
+        (["href"], Node::Text(text)) => sharee.href = text.to_string(),
+        (["common-name"], Node::Text(text)) => sharee.common_name = Some(text.to_string()),
+        (["summary"], Node::Text(text)) => sharee.summary = Some(text.to_string()),
+        ([status], Node::Start) => {
+            if let Some(status) = invite_status(status) {
+                sharee.status = status;
+            }
+        }
+        (["access", access], Node::Start) => {
+            if let Some(access) = share_access(access) {
+                sharee.access = access;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parse a notification resource (`GET` from the notification collection) into a share
+/// invitation; other notification types yield `None`.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_invite_notification(
+    notification_href: &str,
+    body: &[u8],
+) -> Result<Option<ShareInvitation>> {
+    let mut invitation: Option<ShareInvitation> = None;
+    walk(body, |path, node| {
+        let Some(at) = path.iter().position(|n| n == "invite-notification") else {
+            return;
+        };
+        let names: Vec<&str> = path[at + 1..].iter().map(String::as_str).collect();
+        let invitation = invitation.get_or_insert_with(|| ShareInvitation {
+            notification_href: notification_href.to_string(),
+            ..ShareInvitation::default()
+        });
+        match (names.as_slice(), node) {
+            (["uid"], Node::Text(text)) => invitation.uid = text.to_string(),
+            (["href"], Node::Text(text)) => invitation.href = text.to_string(),
+            (["summary"], Node::Text(text)) => invitation.summary = Some(text.to_string()),
+            (["hosturl", "href"], Node::Text(text)) => invitation.host_url = text.to_string(),
+            (["organizer", "href"], Node::Text(text)) => {
+                invitation.organizer = Some(text.to_string())
+            }
+            (["organizer", "common-name"], Node::Text(text)) => {
+                invitation.organizer_name = Some(text.to_string())
+            }
+            (["access", access], Node::Start) => {
+                if let Some(access) = share_access(access) {
+                    invitation.access = access;
+                }
+            }
+            ([status], Node::Start) => {
+                if let Some(status) = invite_status(status) {
+                    invitation.status = status;
+                }
+            }
+            _ => {}
+        }
+    })?;
+    Ok(invitation.filter(|i| !i.uid.is_empty() && !i.host_url.is_empty()))
+}
+
+/// Extract the `CS:shared-as` href returned when an invitation is accepted: where the shared
+/// calendar now appears in the sharee's calendar home.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_shared_as(body: &[u8]) -> Result<Option<String>> {
+    let mut href = None;
+    walk(body, |path, node| {
+        if let (Node::Text(text), [.., parent, leaf]) = (node, path)
+            && parent == "shared-as"
+            && leaf == "href"
+        {
+            href = Some(text.to_string());
+        }
+    })?;
+    Ok(href)
+}
+
+/// Keep the calendars shared with the current user (`resourcetype` `CS:shared`).
+pub fn map_shared_calendars(items: Vec<DavItem>) -> Vec<SharedCalendar> {
+    let mut calendars: Vec<SharedCalendar> = items
+        .into_iter()
+        .filter(|item| item.is_calendar && item.is_shared)
+        .map(|item| SharedCalendar {
+            href: item.href,
+            displayname: item.displayname,
+            color: item.calendar_color,
+            shared_url: item.shared_url,
+            owner: item.owner,
+        })
+        .collect();
+    calendars.sort_by(|a, b| a.href.cmp(&b.href));
+    calendars
+}
+
+fn invite_status(name: &str) -> Option<InviteStatus> {
+    match name {
+        "invite-noresponse" => Some(InviteStatus::NoResponse),
+        "invite-accepted" => Some(InviteStatus::Accepted),
+        "invite-declined" => Some(InviteStatus::Declined),
+        "invite-invalid" => Some(InviteStatus::Invalid),
+        _ => None,
+    }
+}
+
+fn share_access(name: &str) -> Option<ShareAccess> {
+    match name {
+        "read" => Some(ShareAccess::Read),
+        "read-write" => Some(ShareAccess::ReadWrite),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Start,
+    End,
+    Text(&'a str),
+}
+
+/// Walk the document, reporting element starts/ends and trimmed text with the path of
+/// lower-cased local names leading to them (the element itself included).
+fn walk(body: &[u8], mut visit: impl FnMut(&[String], Node<'_>)) -> Result<()> {
+    let mut xml = Reader::from_reader(body);
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    loop {
+        let event = xml
+            .read_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        if matches!(event, Event::Start(_) | Event::Empty(_) | Event::End(_)) {
+            if !text.trim().is_empty() {
+                visit(&path, Node::Text(text.trim()));
+            }
+            text.clear();
+        }
+        match event {
+            Event::Start(e) => {
+                path.push(local_name(e.name().as_ref()));
+                visit(&path, Node::Start);
+            }
+            Event::Empty(e) => {
+                path.push(local_name(e.name().as_ref()));
+                visit(&path, Node::Start);
+                visit(&path, Node::End);
+                path.pop();
+            }
+            Event::End(_) => {
+                visit(&path, Node::End);
+                path.pop();
+            }
+            Event::Eof => break,
+            event => {
+                if let Some(piece) = event_text(&event)? {
+                    text.push_str(&piece);
+                }
+            }
+        }
+        buf.clear();
+    }
+    Ok(())
+}
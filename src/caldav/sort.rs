@@ -0,0 +1,84 @@
+//! Client-side ordering of calendar objects, from a lightweight scan of their
+//! `calendar-data` (no full iCalendar parsing).
+
+use crate::caldav::types::CalendarObject;
+use crate::common::contentline::{ContentLine, unescape_text, unfold_lines};
+
+/// What to order calendar objects by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// `DTSTART` of the first component. Date-times are compared as written; floating and
+    /// `TZID` times are not converted to UTC, which is enough for same-zone listings.
+    Start,
+    /// `SUMMARY`, case-insensitive.
+    Summary,
+    /// `LAST-MODIFIED` of the first component.
+    LastModified,
+    /// Resource href only.
+    Href,
+}
+
+/// Sort `objects` by `key`, in ascending order.
+///
+/// Objects without the property (or without `calendar-data`) come last, and ties are
+/// ordered by href, so the result is the same on every sync regardless of server order.
+pub fn sort_calendar_objects(objects: &mut [CalendarObject], key: SortKey) {
+    objects.sort_by_cached_key(|object| {
+        let value = match key {
+            SortKey::Href => None,
+            _ => object
+                .calendar_data
+                .as_deref()
+                .and_then(|data| sort_value(data, key)),
+        };
+        (
+            key != SortKey::Href && value.is_none(),
+            value,
+            object.href.clone(),
+        )
+    });
+}
+
+/// Value of the sort property in the first non-`VTIMEZONE` component.
+fn sort_value(data: &str, key: SortKey) -> Option<String> {
+    let wanted = match key {
+        SortKey::Start => "DTSTART",
+        SortKey::Summary => "SUMMARY",
+        SortKey::LastModified => "LAST-MODIFIED",
+        SortKey::Href => return None,
+    };
+    let mut stack: Vec<String> = Vec::new();
+    for line in unfold_lines(data) {
+        let Ok(line) = ContentLine::parse(&line) else {
+            continue;
+        };
+        match line.name.as_str() {
+            "BEGIN" => stack.push(line.value.trim().to_ascii_uppercase()),
+            "END" => {
+                if stack.len() == 2 && stack[1] != "VTIMEZONE" {
+                    // The first component ended without the property.
+                    return None;
+                }
+                stack.pop();
+            }
+            name if stack.len() == 2 && stack[1] != "VTIMEZONE" && name == wanted => {
+                return Some(match key {
+                    SortKey::Summary => unescape_text(&line.value).to_lowercase(),
+                    _ => normalize_datetime(&line.value),
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Give `DATE` values a midnight time so they sort alongside `DATE-TIME`s.
+fn normalize_datetime(value: &str) -> String {
+    let value = value.trim();
+    if value.len() == 8 {
+        format!("{value}T000000")
+    } else {
+        value.to_string()
+    }
+}
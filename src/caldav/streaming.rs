@@ -1,18 +1,26 @@
-use crate::caldav::types::DavItem;
-use crate::common::compression::ContentEncoding;
-use crate::webdav::streaming::{CommonParser, path_ends_with};
+use crate::caldav::client::sync_item;
+use crate::caldav::types::{DavItem, ScheduleTransparency, SyncItem};
+use crate::common::compression::{ContentEncoding, DecompressionOptions, decompressing_reader};
+pub use crate::webdav::streaming::Visit;
+use crate::webdav::streaming::{CommonElement, CommonParser, ElementNames, path_ends_with};
+use crate::webdav::types::{ParseLimit, ParseLimits, http_status_code};
+use crate::webdav::xml::event_text;
 use anyhow::{Result, anyhow};
-use futures::TryStreamExt;
-use http_body_util::BodyStream;
+use futures::StreamExt;
+use futures::stream::{self, Stream};
 use hyper::body::Incoming;
 use quick_xml::escape::unescape;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::{Decoder, Reader, XmlVersion};
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::io::{BufRead, Cursor};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 use std::time::Duration;
 use tokio::io::AsyncBufRead;
-use tokio::io::BufReader;
-use tokio_util::io::StreamReader;
 
 /// Default **idle** timeout for streaming multistatus reads.
 ///
@@ -35,14 +43,28 @@ pub enum ElementName {
     Resourcetype,
     Collection,
     Calendar,
+    Shared,
+    SharedOwner,
+    SharedUrl,
+    NotificationUrl,
     SupportedCalendarComponentSet,
     Comp,
     CalendarData,
     CalendarDescription,
     CalendarTimezone,
+    CalendarTimezoneId,
+    CalendarAvailability,
+    TimezoneServiceSet,
     CalendarColor,
     SyncToken,
     CalendarHomeSet,
+    ScheduleInboxUrl,
+    ScheduleOutboxUrl,
+    ScheduleDefaultCalendarUrl,
+    ScheduleCalendarTransp,
+    Opaque,
+    Transparent,
+    CalendarUserAddressSet,
     CurrentUserPrincipal,
     Owner,
     Getcontenttype,
@@ -50,6 +72,30 @@ pub enum ElementName {
     Other,
 }
 
+impl ElementName {
+    /// The same element as seen by the [`CommonParser`], so names are classified once.
+    pub(crate) fn common(self) -> CommonElement {
+        match self {
+            ElementName::Multistatus => CommonElement::Multistatus,
+            ElementName::Response => CommonElement::Response,
+            ElementName::Propstat => CommonElement::Propstat,
+            ElementName::Prop => CommonElement::Prop,
+            ElementName::Href => CommonElement::Href,
+            ElementName::Status => CommonElement::Status,
+            ElementName::Displayname => CommonElement::Displayname,
+            ElementName::Getetag => CommonElement::Getetag,
+            ElementName::Resourcetype => CommonElement::Resourcetype,
+            ElementName::Collection => CommonElement::Collection,
+            ElementName::SyncToken => CommonElement::SyncToken,
+            ElementName::CurrentUserPrincipal => CommonElement::CurrentUserPrincipal,
+            ElementName::Owner => CommonElement::Owner,
+            ElementName::Getcontenttype => CommonElement::Getcontenttype,
+            ElementName::Getlastmodified => CommonElement::Getlastmodified,
+            _ => CommonElement::Other,
+        }
+    }
+}
+
 pub fn element_from_bytes(raw: &[u8]) -> ElementName {
     let local = match raw.iter().position(|b| *b == b':') {
         Some(idx) => &raw[idx + 1..],
@@ -78,6 +124,14 @@ pub fn element_from_bytes(raw: &[u8]) -> ElementName {
         ElementName::Collection
     } else if local.eq_ignore_ascii_case(b"calendar") {
         ElementName::Calendar
+    } else if local.eq_ignore_ascii_case(b"shared") {
+        ElementName::Shared
+    } else if local.eq_ignore_ascii_case(b"shared-owner") {
+        ElementName::SharedOwner
+    } else if local.eq_ignore_ascii_case(b"shared-url") {
+        ElementName::SharedUrl
+    } else if local.eq_ignore_ascii_case(b"notification-url") {
+        ElementName::NotificationUrl
     } else if local.eq_ignore_ascii_case(b"supported-calendar-component-set") {
         ElementName::SupportedCalendarComponentSet
     } else if local.eq_ignore_ascii_case(b"comp") {
@@ -88,12 +142,32 @@ pub fn element_from_bytes(raw: &[u8]) -> ElementName {
         ElementName::CalendarDescription
     } else if local.eq_ignore_ascii_case(b"calendar-timezone") {
         ElementName::CalendarTimezone
+    } else if local.eq_ignore_ascii_case(b"calendar-timezone-id") {
+        ElementName::CalendarTimezoneId
+    } else if local.eq_ignore_ascii_case(b"calendar-availability") {
+        ElementName::CalendarAvailability
+    } else if local.eq_ignore_ascii_case(b"timezone-service-set") {
+        ElementName::TimezoneServiceSet
     } else if local.eq_ignore_ascii_case(b"calendar-color") {
         ElementName::CalendarColor
     } else if local.eq_ignore_ascii_case(b"sync-token") {
         ElementName::SyncToken
     } else if local.eq_ignore_ascii_case(b"calendar-home-set") {
         ElementName::CalendarHomeSet
+    } else if local.eq_ignore_ascii_case(b"schedule-inbox-url") {
+        ElementName::ScheduleInboxUrl
+    } else if local.eq_ignore_ascii_case(b"schedule-outbox-url") {
+        ElementName::ScheduleOutboxUrl
+    } else if local.eq_ignore_ascii_case(b"schedule-default-calendar-url") {
+        ElementName::ScheduleDefaultCalendarUrl
+    } else if local.eq_ignore_ascii_case(b"schedule-calendar-transp") {
+        ElementName::ScheduleCalendarTransp
+    } else if local.eq_ignore_ascii_case(b"opaque") {
+        ElementName::Opaque
+    } else if local.eq_ignore_ascii_case(b"transparent") {
+        ElementName::Transparent
+    } else if local.eq_ignore_ascii_case(b"calendar-user-address-set") {
+        ElementName::CalendarUserAddressSet
     } else if local.eq_ignore_ascii_case(b"current-user-principal") {
         ElementName::CurrentUserPrincipal
     } else if local.eq_ignore_ascii_case(b"owner") {
@@ -108,21 +182,21 @@ pub fn element_from_bytes(raw: &[u8]) -> ElementName {
 }
 
 pub(crate) trait ItemConsumer {
-    fn consume(&mut self, item: DavItem) -> Result<()>;
+    fn consume(&mut self, item: DavItem) -> Result<Visit>;
 }
 
 impl ItemConsumer for Vec<DavItem> {
-    fn consume(&mut self, item: DavItem) -> Result<()> {
+    fn consume(&mut self, item: DavItem) -> Result<Visit> {
         self.push(item);
-        Ok(())
+        Ok(Visit::Continue)
     }
 }
 
 impl<F> ItemConsumer for F
 where
-    F: FnMut(DavItem) -> Result<()>,
+    F: FnMut(DavItem) -> Result<Visit>,
 {
-    fn consume(&mut self, item: DavItem) -> Result<()> {
+    fn consume(&mut self, item: DavItem) -> Result<Visit> {
         (self)(item)
     }
 }
@@ -138,23 +212,38 @@ pub(crate) struct MultistatusParser<C> {
     pub stack: Vec<ElementName>,
     pub current: DavItem,
     pub sync_token: Option<String>,
+    /// Set once the sink asked to [`Visit::Stop`]; the rest of the body is not parsed.
+    pub stopped: bool,
+    /// Text of the innermost open element read so far, reused from element to element.
+    text: String,
+    limits: ParseLimits,
+    /// `<D:response>`s parsed so far.
+    items: usize,
     common: CommonParser,
+    names: ElementNames<ElementName>,
     sink: C,
 }
 
 impl<C: ItemConsumer> MultistatusParser<C> {
-    pub fn new(sink: C) -> Self {
+    pub fn new(sink: C, limits: ParseLimits) -> Self {
         Self {
             stack: Vec::with_capacity(16),
             current: DavItem::new(),
             sync_token: None,
+            stopped: false,
+            text: String::with_capacity(256),
+            limits,
+            items: 0,
             common: CommonParser::new(),
+            names: ElementNames::new(),
             sink,
         }
     }
 
     fn finish(self) -> Result<ParseResult<C>> {
-        if let Some(unclosed) = self.stack.last() {
+        if !self.stopped
+            && let Some(unclosed) = self.stack.last()
+        {
             return Err(anyhow!(
                 "XML structure error: unexpected end of input with unclosed element {unclosed:?}"
             ));
@@ -171,8 +260,9 @@ impl<C: ItemConsumer> MultistatusParser<C> {
     }
 
     fn on_start(&mut self, event: &BytesStart<'_>, decoder: Decoder) -> Result<()> {
-        self.common.on_start(event.name().as_ref());
-        let element = element_from_bytes(event.name().as_ref());
+        self.limits.check(ParseLimit::Depth, self.stack.len() + 1)?;
+        let element = self.names.get(event.name().as_ref(), element_from_bytes);
+        self.common.on_start_element(element.common());
         self.stack.push(element);
 
         match element {
@@ -190,6 +280,37 @@ impl<C: ItemConsumer> MultistatusParser<C> {
             {
                 self.current.is_calendar = true;
             }
+            ElementName::Shared
+                if self.path_ends_with(&[
+                    ElementName::Prop,
+                    ElementName::Resourcetype,
+                    ElementName::Shared,
+                ]) =>
+            {
+                self.current.is_shared = true;
+            }
+            ElementName::SharedOwner
+                if self.path_ends_with(&[
+                    ElementName::Prop,
+                    ElementName::Resourcetype,
+                    ElementName::SharedOwner,
+                ]) =>
+            {
+                self.current.is_shared_owner = true;
+            }
+            ElementName::Opaque | ElementName::Transparent
+                if self.path_ends_with(&[
+                    ElementName::Prop,
+                    ElementName::ScheduleCalendarTransp,
+                    element,
+                ]) =>
+            {
+                self.current.schedule_calendar_transp = Some(if element == ElementName::Opaque {
+                    ScheduleTransparency::Opaque
+                } else {
+                    ScheduleTransparency::Transparent
+                });
+            }
             ElementName::Comp
                 if self.path_ends_with(&[
                     ElementName::Response,
@@ -230,55 +351,94 @@ impl<C: ItemConsumer> MultistatusParser<C> {
         if let Some(popped) = self.stack.pop()
             && popped == ElementName::Response
         {
+            self.items += 1;
+            self.limits.check(ParseLimit::Items, self.items)?;
             let common = self.common.finish_response();
             self.current.apply_common(common);
             let finished = std::mem::take(&mut self.current);
-            self.sink.consume(finished)?;
+            self.stopped = self.sink.consume(finished)? == Visit::Stop;
         }
         Ok(())
     }
 
-    fn on_text(&mut self, text: String) {
-        self.handle_text(text);
+    /// Feed one event read by the [`Reader`], other than `Eof`.
+    ///
+    /// The text of an element may come as several events (escapes, CDATA sections, chunks
+    /// of a large payload): it is gathered in one buffer and handled when the next element
+    /// starts or ends.
+    fn on_event(&mut self, event: Event<'_>, decoder: Decoder) -> Result<()> {
+        match event {
+            Event::Start(e) => {
+                self.flush_text();
+                self.on_start(&e, decoder)
+            }
+            Event::Empty(e) => {
+                self.flush_text();
+                self.on_start(&e, decoder)?;
+                self.on_end(e.name().as_ref())
+            }
+            Event::End(e) => {
+                self.flush_text();
+                self.on_end(e.name().as_ref())
+            }
+            event => match event_text(&event)? {
+                Some(text) => self.push_text(&text),
+                None => Ok(()),
+            },
+        }
     }
 
-    fn on_cdata(&mut self, text: String) {
-        self.handle_text(text);
+    fn push_text(&mut self, chunk: &str) -> Result<()> {
+        self.limits
+            .check(ParseLimit::TextSize, self.text.len() + chunk.len())?;
+        self.text.push_str(chunk);
+        Ok(())
     }
 
-    fn handle_text(&mut self, text: String) {
-        if text.is_empty() {
+    fn flush_text(&mut self) {
+        if self.text.is_empty() {
             return;
         }
+        let mut text = std::mem::take(&mut self.text);
+        self.handle_text(&text);
+        text.clear();
+        self.text = text;
+    }
 
-        self.common.on_text(&text);
-
-        // calendar-data is often multi-line and may arrive in chunks; keep exact payload.
-        if self.path_ends_with(&[
+    /// The field collecting the text of the current element when it is an iCalendar payload.
+    fn payload(&mut self) -> Option<&mut Option<String>> {
+        let [
+            ..,
             ElementName::Response,
             ElementName::Propstat,
             ElementName::Prop,
-            ElementName::CalendarData,
-        ]) {
-            if let Some(existing) = self.current.calendar_data.as_mut() {
-                existing.push_str(&text);
-            } else {
-                self.current.calendar_data = Some(text);
-            }
+            last,
+        ] = self.stack[..]
+        else {
+            return None;
+        };
+        match last {
+            ElementName::CalendarData => Some(&mut self.current.calendar_data),
+            ElementName::CalendarTimezone => Some(&mut self.current.calendar_timezone),
+            ElementName::CalendarAvailability => Some(&mut self.current.calendar_availability),
+            _ => None,
+        }
+    }
+
+    /// Text is only copied into the field it ends up in.
+    fn handle_text(&mut self, text: &str) {
+        if text.is_empty() {
             return;
         }
 
-        // calendar-timezone can also contain multi-line iCalendar content; preserve it.
-        if self.path_ends_with(&[
-            ElementName::Response,
-            ElementName::Propstat,
-            ElementName::Prop,
-            ElementName::CalendarTimezone,
-        ]) {
-            if let Some(existing) = self.current.calendar_timezone.as_mut() {
-                existing.push_str(&text);
-            } else {
-                self.current.calendar_timezone = Some(text.clone());
+        self.common.on_text(text);
+
+        // calendar-data, calendar-timezone and calendar-availability hold multi-line
+        // iCalendar content that may arrive in chunks; keep the exact payload.
+        if let Some(payload) = self.payload() {
+            match payload {
+                Some(existing) => existing.push_str(text),
+                None => *payload = Some(text.to_owned()),
             }
             return;
         }
@@ -313,8 +473,326 @@ impl<C: ItemConsumer> MultistatusParser<C> {
             ElementName::Href,
         ]) {
             self.current.calendar_home_set.push(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::ScheduleInboxUrl,
+            ElementName::Href,
+        ]) {
+            self.current.schedule_inbox_url = Some(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::ScheduleOutboxUrl,
+            ElementName::Href,
+        ]) {
+            self.current.schedule_outbox_url = Some(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::ScheduleDefaultCalendarUrl,
+            ElementName::Href,
+        ]) {
+            self.current.schedule_default_calendar_url = Some(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::CalendarUserAddressSet,
+            ElementName::Href,
+        ]) {
+            self.current
+                .calendar_user_address_set
+                .push(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::CalendarTimezoneId,
+        ]) {
+            self.current.calendar_timezone_id = Some(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::TimezoneServiceSet,
+            ElementName::Href,
+        ]) {
+            self.current.timezone_service_set.push(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::SharedUrl,
+            ElementName::Href,
+        ]) {
+            self.current.shared_url = Some(trimmed.to_string());
+        } else if self.path_ends_with(&[
+            ElementName::Response,
+            ElementName::Propstat,
+            ElementName::Prop,
+            ElementName::NotificationUrl,
+            ElementName::Href,
+        ]) {
+            self.current.notification_url = Some(trimmed.to_string());
+        }
+    }
+}
+
+/// The response body as a reader, undoing the given content encodings.
+pub(crate) fn decoded_reader(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    options: &DecompressionOptions,
+) -> Box<dyn AsyncBufRead + Unpin + Send> {
+    decompressing_reader(resp_body, encodings, options)
+}
+
+impl ItemConsumer for VecDeque<DavItem> {
+    fn consume(&mut self, item: DavItem) -> Result<Visit> {
+        self.push_back(item);
+        Ok(Visit::Continue)
+    }
+}
+
+/// The `<D:response>`s of a `207 Multi-Status` body, each yielded as soon as it is parsed,
+/// see [`parse_multistatus_item_stream`].
+///
+/// Only the response being parsed is held in memory, so responses with any number of
+/// items are processed in constant memory. The stream ends after the first error.
+pub struct DavItemStream {
+    inner: Pin<Box<dyn Stream<Item = Result<DavItem>> + Send>>,
+    sync_token: Arc<Mutex<Option<String>>>,
+}
+
+impl DavItemStream {
+    /// Parse a multistatus from `reader`, waiting at most `idle_timeout` for each read to
+    /// make progress.
+    pub fn new<R>(reader: R, idle_timeout: Duration) -> Self
+    where
+        R: AsyncBufRead + Unpin + Send + 'static,
+    {
+        Self::with_limits(reader, idle_timeout, ParseLimits::default())
+    }
+
+    /// [`new`](Self::new) with other [`ParseLimits`] than the default ones.
+    pub fn with_limits<R>(reader: R, idle_timeout: Duration, limits: ParseLimits) -> Self
+    where
+        R: AsyncBufRead + Unpin + Send + 'static,
+    {
+        struct State<R> {
+            xml: Reader<R>,
+            buf: Vec<u8>,
+            parser: MultistatusParser<VecDeque<DavItem>>,
+            done: bool,
+        }
+
+        let sync_token = Arc::new(Mutex::new(None));
+        let shared = Arc::clone(&sync_token);
+        let mut xml = Reader::from_reader(reader);
+        xml.config_mut().trim_text(false);
+        let state = State {
+            xml,
+            buf: Vec::with_capacity(8 * 1024),
+            parser: MultistatusParser::new(VecDeque::new(), limits),
+            done: false,
+        };
+
+        let inner = stream::unfold(state, move |mut state| {
+            let shared = Arc::clone(&shared);
+            async move {
+                loop {
+                    if let Some(item) = state.parser.sink.pop_front() {
+                        return Some((Ok(item), state));
+                    }
+                    if state.done {
+                        return None;
+                    }
+                    let event = match tokio::time::timeout(
+                        idle_timeout,
+                        state.xml.read_event_into_async(&mut state.buf),
+                    )
+                    .await
+                    {
+                        Ok(event) => event,
+                        Err(_) => {
+                            state.done = true;
+                            let err = anyhow!(
+                                "streaming read timed out after {idle_timeout:?} of inactivity"
+                            );
+                            return Some((Err(err), state));
+                        }
+                    };
+                    let handled = match event {
+                        Ok(Event::Eof) => {
+                            state.done = true;
+                            match state.parser.stack.last() {
+                                Some(unclosed) => Err(anyhow!(
+                                    "XML structure error: unexpected end of input with unclosed element {unclosed:?}"
+                                )),
+                                None => {
+                                    *shared.lock().unwrap_or_else(|e| e.into_inner()) =
+                                        state.parser.sync_token.take();
+                                    Ok(())
+                                }
+                            }
+                        }
+                        Ok(event) => state.parser.on_event(event, state.xml.decoder()),
+                        Err(e) => Err(anyhow!("XML parsing error: {e}")),
+                    };
+                    state.buf.clear();
+                    if let Err(err) = handled {
+                        state.done = true;
+                        return Some((Err(err), state));
+                    }
+                }
+            }
+        });
+
+        Self {
+            inner: Box::pin(inner),
+            sync_token,
         }
     }
+
+    /// The top-level `<D:sync-token>` (RFC 6578), available once the stream has been read
+    /// to the end.
+    pub fn sync_token(&self) -> Option<String> {
+        self.sync_token
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Stream for DavItemStream {
+    type Item = Result<DavItem>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+/// `sync-collection` changes parsed as they arrive, see
+/// [`CardDavClient::sync_collection_stream`](crate::carddav::CardDavClient::sync_collection_stream).
+///
+/// Each `<D:response>` is mapped to a [`SyncItem`] as soon as it is complete (with the rules
+/// of [`map_sync_response`](crate::caldav::map_sync_response)), so memory use does not grow
+/// with the size of the collection. The new sync token is only known once the stream has
+/// ended: servers send it after the last response.
+pub struct SyncItemStream {
+    inner: Pin<Box<dyn Stream<Item = Result<SyncItem>> + Send>>,
+    sync_token: Arc<Mutex<Option<String>>>,
+    truncated: Arc<AtomicBool>,
+}
+
+impl SyncItemStream {
+    /// Parse a `sync-collection` multistatus from `reader`. `header_token` is the
+    /// `Sync-Token` response header, used when the body carries none.
+    pub fn new<R>(reader: R, header_token: Option<String>, idle_timeout: Duration) -> Self
+    where
+        R: AsyncBufRead + Unpin + Send + 'static,
+    {
+        Self::with_limits(reader, header_token, idle_timeout, ParseLimits::default())
+    }
+
+    /// [`new`](Self::new) with other [`ParseLimits`] than the default ones.
+    pub fn with_limits<R>(
+        reader: R,
+        header_token: Option<String>,
+        idle_timeout: Duration,
+        limits: ParseLimits,
+    ) -> Self
+    where
+        R: AsyncBufRead + Unpin + Send + 'static,
+    {
+        struct State {
+            items: DavItemStream,
+            header_token: Option<String>,
+            item_token: Option<String>,
+            truncated: Arc<AtomicBool>,
+            failed: bool,
+        }
+
+        let sync_token = Arc::new(Mutex::new(None));
+        let truncated = Arc::new(AtomicBool::new(false));
+        let shared = Arc::clone(&sync_token);
+        let state = State {
+            items: DavItemStream::with_limits(reader, idle_timeout, limits),
+            header_token,
+            item_token: None,
+            truncated: Arc::clone(&truncated),
+            failed: false,
+        };
+
+        let inner = stream::unfold(state, move |mut state| {
+            let shared = Arc::clone(&shared);
+            async move {
+                loop {
+                    let item = match state.items.next().await {
+                        Some(Ok(item)) => item,
+                        Some(Err(err)) => {
+                            state.failed = true;
+                            return Some((Err(err), state));
+                        }
+                        None => {
+                            if !state.failed {
+                                let token = state
+                                    .items
+                                    .sync_token()
+                                    .or(state.header_token.take())
+                                    .or(state.item_token.take());
+                                *shared.lock().unwrap_or_else(|e| e.into_inner()) = token;
+                            }
+                            return None;
+                        }
+                    };
+                    if state.item_token.is_none() {
+                        state.item_token = item.sync_token.clone();
+                    }
+                    if item.status.as_deref().and_then(http_status_code) == Some(507) {
+                        state.truncated.store(true, Ordering::Relaxed);
+                        continue;
+                    }
+                    if let Some(item) = sync_item(item) {
+                        return Some((Ok(item), state));
+                    }
+                }
+            }
+        });
+
+        Self {
+            inner: Box::pin(inner),
+            sync_token,
+            truncated,
+        }
+    }
+
+    /// The new sync token, available once the stream has been read to the end.
+    pub fn sync_token(&self) -> Option<String> {
+        self.sync_token
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Whether the server cut the changes at its result limit (`507`, see
+    /// [`SyncResponse::truncated`](crate::caldav::SyncResponse::truncated)); known once the
+    /// response carrying the flag has been read.
+    pub fn truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
+    }
+}
+
+impl Stream for SyncItemStream {
+    type Item = Result<SyncItem>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
 }
 
 async fn parse_multistatus_stream_with<C>(
@@ -322,32 +800,20 @@ async fn parse_multistatus_stream_with<C>(
     encodings: &[ContentEncoding],
     sink: C,
     idle_timeout: Duration,
+    limits: ParseLimits,
 ) -> Result<ParseResult<C>>
 where
     C: ItemConsumer + Send,
 {
-    use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZstdDecoder};
-
-    // Non-data frames (e.g. HTTP/2 trailers) are intentionally skipped.
-    let stream = BodyStream::new(resp_body)
-        .try_filter_map(|frame| std::future::ready(Ok(frame.into_data().ok())))
-        .map_err(std::io::Error::other);
-    let mut reader: Box<dyn AsyncBufRead + Unpin + Send> =
-        Box::new(BufReader::new(StreamReader::new(stream)));
-    for encoding in encodings.iter().rev() {
-        reader = match encoding {
-            ContentEncoding::Identity => reader,
-            ContentEncoding::Br => Box::new(BufReader::new(BrotliDecoder::new(reader))),
-            ContentEncoding::Gzip => Box::new(BufReader::new(GzipDecoder::new(reader))),
-            ContentEncoding::Zstd => Box::new(BufReader::new(ZstdDecoder::new(reader))),
-        };
-    }
-
-    let mut xml = Reader::from_reader(reader);
+    let mut xml = Reader::from_reader(decoded_reader(
+        resp_body,
+        encodings,
+        &DecompressionOptions::default(),
+    ));
     xml.config_mut().trim_text(false);
 
     let mut buf = Vec::with_capacity(8 * 1024);
-    let mut parser = MultistatusParser::new(sink);
+    let mut parser = MultistatusParser::new(sink, limits);
 
     loop {
         let event = tokio::time::timeout(idle_timeout, xml.read_event_into_async(&mut buf))
@@ -356,23 +822,12 @@ where
                 anyhow!("streaming read timed out after {idle_timeout:?} of inactivity")
             })?;
         match event {
-            Ok(Event::Start(e)) => parser.on_start(&e, xml.decoder())?,
-            Ok(Event::Empty(e)) => {
-                parser.on_start(&e, xml.decoder())?;
-                parser.on_end(e.name().as_ref())?;
-            }
-            Ok(Event::Text(e)) => {
-                let text = decode_text(e.as_ref())?;
-                parser.on_text(text);
-            }
-            Ok(Event::CData(e)) => {
-                let text = String::from_utf8_lossy(e.as_ref()).into_owned();
-                parser.on_cdata(text);
-            }
-            Ok(Event::End(e)) => parser.on_end(e.name().as_ref())?,
             Ok(Event::Eof) => break,
+            Ok(event) => parser.on_event(event, xml.decoder())?,
             Err(e) => return Err(anyhow!("XML parsing error: {e}")),
-            _ => {}
+        }
+        if parser.stopped {
+            break;
         }
         buf.clear();
     }
@@ -380,7 +835,11 @@ where
     parser.finish()
 }
 
-fn parse_multistatus_bytes_with<R, C>(reader: R, sink: C) -> Result<ParseResult<C>>
+fn parse_multistatus_bytes_with<R, C>(
+    reader: R,
+    sink: C,
+    limits: ParseLimits,
+) -> Result<ParseResult<C>>
 where
     R: BufRead,
     C: ItemConsumer,
@@ -389,27 +848,16 @@ where
     xml.config_mut().trim_text(false);
 
     let mut buf = Vec::with_capacity(8 * 1024);
-    let mut parser = MultistatusParser::new(sink);
+    let mut parser = MultistatusParser::new(sink, limits);
 
     loop {
         match xml.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => parser.on_start(&e, xml.decoder())?,
-            Ok(Event::Empty(e)) => {
-                parser.on_start(&e, xml.decoder())?;
-                parser.on_end(e.name().as_ref())?;
-            }
-            Ok(Event::Text(e)) => {
-                let text = decode_text(e.as_ref())?;
-                parser.on_text(text);
-            }
-            Ok(Event::CData(e)) => {
-                let text = String::from_utf8_lossy(e.as_ref()).into_owned();
-                parser.on_cdata(text);
-            }
-            Ok(Event::End(e)) => parser.on_end(e.name().as_ref())?,
             Ok(Event::Eof) => break,
+            Ok(event) => parser.on_event(event, xml.decoder())?,
             Err(e) => return Err(anyhow!("XML error: {e}")),
-            _ => {}
+        }
+        if parser.stopped {
+            break;
         }
         buf.clear();
     }
@@ -443,7 +891,31 @@ pub async fn parse_multistatus_stream_with_timeout(
     encodings: &[ContentEncoding],
     idle_timeout: Duration,
 ) -> Result<ParseResult<Vec<DavItem>>> {
-    parse_multistatus_stream_with(resp_body, encodings, Vec::<DavItem>::new(), idle_timeout).await
+    parse_multistatus_stream_with_limits(resp_body, encodings, idle_timeout, ParseLimits::default())
+        .await
+}
+
+/// Variant of [`parse_multistatus_stream_with_timeout`] with other [`ParseLimits`] than the
+/// default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, besides the errors of [`parse_multistatus_stream_with_timeout`].
+pub async fn parse_multistatus_stream_with_limits(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    idle_timeout: Duration,
+    limits: ParseLimits,
+) -> Result<ParseResult<Vec<DavItem>>> {
+    parse_multistatus_stream_with(
+        resp_body,
+        encodings,
+        Vec::<DavItem>::new(),
+        idle_timeout,
+        limits,
+    )
+    .await
 }
 
 /// Stream parse a WebDAV `207 Multi-Status` response and invoke a callback for each item.
@@ -477,36 +949,137 @@ pub async fn parse_multistatus_stream_visit_with_timeout<F>(
     resp_body: Incoming,
     encodings: &[ContentEncoding],
     idle_timeout: Duration,
-    on_item: F,
+    mut on_item: F,
 ) -> Result<Option<String>>
 where
     F: FnMut(DavItem) -> Result<()> + Send,
 {
-    let result = parse_multistatus_stream_with(resp_body, encodings, on_item, idle_timeout).await?;
+    parse_multistatus_stream_visit_until_with_timeout(
+        resp_body,
+        encodings,
+        idle_timeout,
+        move |item| on_item(item).map(|()| Visit::Continue),
+    )
+    .await
+}
+
+/// Variant of [`parse_multistatus_stream_visit`] whose callback decides whether to go on:
+/// on [`Visit::Stop`] the rest of the body is left unread and the response dropped, which
+/// closes the connection. The returned sync token is then `None` unless it came before
+/// the stopping item.
+pub async fn parse_multistatus_stream_visit_until<F>(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    on_item: F,
+) -> Result<Option<String>>
+where
+    F: FnMut(DavItem) -> Result<Visit> + Send,
+{
+    parse_multistatus_stream_visit_until_with_timeout(
+        resp_body,
+        encodings,
+        STREAM_READ_IDLE_TIMEOUT,
+        on_item,
+    )
+    .await
+}
+
+/// Variant of [`parse_multistatus_stream_visit_until`] with a caller-provided **idle**
+/// timeout (see [`parse_multistatus_stream_visit_with_timeout`]).
+pub async fn parse_multistatus_stream_visit_until_with_timeout<F>(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    idle_timeout: Duration,
+    on_item: F,
+) -> Result<Option<String>>
+where
+    F: FnMut(DavItem) -> Result<Visit> + Send,
+{
+    let result = parse_multistatus_stream_with(
+        resp_body,
+        encodings,
+        on_item,
+        idle_timeout,
+        ParseLimits::default(),
+    )
+    .await?;
     Ok(result.sync_token)
 }
 
+/// Parse a WebDAV `207 Multi-Status` response as a stream of its items, each yielded as
+/// soon as its `<D:response>` is parsed, with optional decompression (br, gzip, zstd).
+///
+/// Unlike [`parse_multistatus_stream`], which returns once the whole body is parsed, this
+/// keeps memory constant whatever the number of items: process them as they arrive, then
+/// read the top-level sync token from [`DavItemStream::sync_token`].
+///
+/// Reads are bounded by the default idle timeout ([`STREAM_READ_IDLE_TIMEOUT`]); use
+/// [`parse_multistatus_item_stream_with_timeout`] to customize it.
+pub fn parse_multistatus_item_stream(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+) -> DavItemStream {
+    parse_multistatus_item_stream_with_timeout(resp_body, encodings, STREAM_READ_IDLE_TIMEOUT)
+}
+
+/// Variant of [`parse_multistatus_item_stream`] with a caller-provided **idle** timeout
+/// (see [`parse_multistatus_stream_with_timeout`]).
+pub fn parse_multistatus_item_stream_with_timeout(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    idle_timeout: Duration,
+) -> DavItemStream {
+    DavItemStream::new(
+        decoded_reader(resp_body, encodings, &DecompressionOptions::default()),
+        idle_timeout,
+    )
+}
+
 /// Parse a WebDAV `207 Multi-Status` XML body from an already aggregated buffer.
 pub fn parse_multistatus_bytes(body: &[u8]) -> Result<ParseResult<Vec<DavItem>>> {
-    let cursor = Cursor::new(body);
-    parse_multistatus_bytes_with(cursor, Vec::<DavItem>::new())
+    parse_multistatus_bytes_with_limits(body, ParseLimits::default())
+}
+
+/// Variant of [`parse_multistatus_bytes`] with other [`ParseLimits`] than the default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when it is not well-formed XML.
+pub fn parse_multistatus_bytes_with_limits(
+    body: &[u8],
+    limits: ParseLimits,
+) -> Result<ParseResult<Vec<DavItem>>> {
+    parse_multistatus_bytes_with(Cursor::new(body), Vec::<DavItem>::new(), limits)
 }
 
 /// Stream parse an aggregated multistatus body via callback.
-pub fn parse_multistatus_bytes_visit<F>(body: &[u8], on_item: F) -> Result<Option<String>>
+pub fn parse_multistatus_bytes_visit<F>(body: &[u8], mut on_item: F) -> Result<Option<String>>
 where
     F: FnMut(DavItem) -> Result<()>,
+{
+    parse_multistatus_bytes_visit_until(body, move |item| on_item(item).map(|()| Visit::Continue))
+}
+
+/// Variant of [`parse_multistatus_bytes_visit`] whose callback decides whether to go on,
+/// with the rules of [`parse_multistatus_stream_visit_until`].
+pub fn parse_multistatus_bytes_visit_until<F>(body: &[u8], on_item: F) -> Result<Option<String>>
+where
+    F: FnMut(DavItem) -> Result<Visit>,
 {
     let cursor = Cursor::new(body);
-    let result = parse_multistatus_bytes_with(cursor, on_item)?;
+    let result = parse_multistatus_bytes_with(cursor, on_item, ParseLimits::default())?;
     Ok(result.sync_token)
 }
 
 pub fn decode_text(raw: &[u8]) -> Result<String> {
+    decode_text_cow(raw).map(Cow::into_owned)
+}
+
+/// [`decode_text`], borrowing `raw` when it holds no escapes.
+pub(crate) fn decode_text_cow(raw: &[u8]) -> Result<Cow<'_, str>> {
     match std::str::from_utf8(raw) {
-        Ok(s) => Ok(unescape(s)
-            .map_err(|err| anyhow!("XML decode error: {err}"))?
-            .into_owned()),
-        Err(_) => Ok(String::from_utf8_lossy(raw).into_owned()),
+        Ok(s) => unescape(s).map_err(|err| anyhow!("XML decode error: {err}")),
+        Err(_) => Ok(String::from_utf8_lossy(raw)),
     }
 }
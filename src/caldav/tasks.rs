@@ -0,0 +1,80 @@
+//! Helpers for task (`VTODO`) workflows: filters for open and overdue todos and marking a
+//! todo as completed (RFC 5545 §3.6.2).
+
+use anyhow::{Result, anyhow};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::caldav::query::{CompFilter, PropFilter};
+use crate::common::contentline::Component;
+use crate::common::datetime::format_utc_datetime;
+
+/// `VTODO` filter matching todos without a `COMPLETED` date-time.
+///
+/// Todos flagged `STATUS:COMPLETED` or `STATUS:CANCELLED` without a `COMPLETED` property
+/// still match; use [`todo_is_open`] on the returned data to drop them (a server-side
+/// `STATUS` filter would also exclude the many todos that carry no `STATUS` at all).
+pub fn incomplete_todos_filter() -> CompFilter {
+    CompFilter::new("VTODO").prop_filter(PropFilter::new("COMPLETED").is_not_defined())
+}
+
+/// `VTODO` filter matching incomplete todos whose `DUE` is before `now`, an iCalendar UTC
+/// date-time (e.g. `20240101T000000Z`).
+pub fn overdue_todos_filter(now: &str) -> CompFilter {
+    incomplete_todos_filter().prop_filter(PropFilter::new("DUE").time_range(None, Some(now)))
+}
+
+/// `true` when the calendar object contains a todo that is neither completed nor cancelled.
+///
+/// Returns `false` for payloads that are not valid iCalendar.
+pub fn todo_is_open(ics: &str) -> bool {
+    let Ok(calendars) = Component::parse_all(ics) else {
+        return false;
+    };
+    calendars
+        .iter()
+        .flat_map(|c| c.components_named("VTODO"))
+        .any(|todo| {
+            todo.property("COMPLETED").is_none()
+                && !todo.value("STATUS").is_some_and(|s| {
+                    s.eq_ignore_ascii_case("COMPLETED") || s.eq_ignore_ascii_case("CANCELLED")
+                })
+        })
+}
+
+/// Mark every `VTODO` of a calendar object (including recurrence overrides) as completed.
+///
+/// Sets `STATUS:COMPLETED`, `PERCENT-COMPLETE:100` and `COMPLETED`, and refreshes `DTSTAMP`
+/// and `LAST-MODIFIED`, all to the current time. An existing `COMPLETED` date-time is kept.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid iCalendar or contains no `VTODO`.
+pub fn complete_todo(ics: &str) -> Result<String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let now = format_utc_datetime(now);
+
+    let mut calendars = Component::parse_all(ics)?;
+    let mut found = false;
+    for todo in calendars
+        .iter_mut()
+        .flat_map(|c| c.components.iter_mut())
+        .filter(|c| c.name.eq_ignore_ascii_case("VTODO"))
+    {
+        found = true;
+        todo.set_value("STATUS", "COMPLETED");
+        todo.set_value("PERCENT-COMPLETE", "100");
+        if todo.property("COMPLETED").is_none() {
+            todo.set_value("COMPLETED", now.clone());
+        }
+        todo.set_value("DTSTAMP", now.clone());
+        todo.set_value("LAST-MODIFIED", now.clone());
+    }
+    if !found {
+        return Err(anyhow!("calendar object contains no VTODO"));
+    }
+
+    Ok(calendars.iter().map(Component::to_ics).collect())
+}
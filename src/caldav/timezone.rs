@@ -0,0 +1,334 @@
+//! `VTIMEZONE` parsing (RFC 5545 §3.6.5) and conversion of `TZID`-local date-times to UTC.
+//!
+//! Available with the `ical` feature. Offsets are computed from the `STANDARD`/`DAYLIGHT`
+//! observances embedded in the calendar object, so no time zone database is required.
+//! With the `chrono` feature, [`ICalDateTime`] also converts into `chrono` types.
+
+use anyhow::{Result, anyhow};
+
+use crate::caldav::ical::{ICalDateTime, ICalendar};
+use crate::caldav::recurrence::RecurrenceRule;
+use crate::common::contentline::Component;
+use crate::common::datetime::{format_utc_datetime, parse_local_datetime, parse_utc_datetime};
+
+/// Whether an observance is standard time or daylight saving time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObservanceKind {
+    Standard,
+    Daylight,
+}
+
+/// A `STANDARD` or `DAYLIGHT` sub-component: the UTC offset in effect from its onset(s).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observance {
+    pub kind: ObservanceKind,
+    /// First onset, local time before the transition (e.g. `19701025T030000`).
+    pub start: String,
+    /// `TZOFFSETFROM` / `TZOFFSETTO` in seconds east of UTC.
+    pub offset_from: i32,
+    pub offset_to: i32,
+    /// Later onsets, e.g. `FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU`.
+    pub rrule: Option<String>,
+    pub rdates: Vec<String>,
+    /// `TZNAME`, e.g. `CET`.
+    pub name: Option<String>,
+}
+
+/// A parsed `VTIMEZONE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VTimeZone {
+    pub tzid: String,
+    pub observances: Vec<Observance>,
+}
+
+impl VTimeZone {
+    /// Parse a `VTIMEZONE` component.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the component is not a `VTIMEZONE`, has no `TZID` or no
+    /// observance, or an observance lacks `DTSTART` or a valid offset.
+    pub fn parse(component: &Component) -> Result<Self> {
+        if component.name != "VTIMEZONE" {
+            return Err(anyhow!("expected VTIMEZONE, found {}", component.name));
+        }
+        let tzid = component
+            .value("TZID")
+            .ok_or_else(|| anyhow!("VTIMEZONE without TZID"))?
+            .to_string();
+        let mut observances = Vec::new();
+        for observance in &component.components {
+            let kind = match observance.name.as_str() {
+                "STANDARD" => ObservanceKind::Standard,
+                "DAYLIGHT" => ObservanceKind::Daylight,
+                _ => continue,
+            };
+            let offset = |name: &str| {
+                observance
+                    .value(name)
+                    .and_then(parse_utc_offset)
+                    .ok_or_else(|| anyhow!("{tzid} observance without valid {name}"))
+            };
+            observances.push(Observance {
+                kind,
+                start: observance
+                    .value("DTSTART")
+                    .ok_or_else(|| anyhow!("{tzid} observance without DTSTART"))?
+                    .to_string(),
+                offset_from: offset("TZOFFSETFROM")?,
+                offset_to: offset("TZOFFSETTO")?,
+                rrule: observance.value("RRULE").map(str::to_string),
+                rdates: observance
+                    .properties_named("RDATE")
+                    .flat_map(|p| p.value.split(',').map(|v| v.trim().to_string()))
+                    .collect(),
+                name: observance.value("TZNAME").map(str::to_string),
+            });
+        }
+        if observances.is_empty() {
+            return Err(anyhow!("VTIMEZONE {tzid} has no STANDARD or DAYLIGHT"));
+        }
+        Ok(Self { tzid, observances })
+    }
+
+    /// UTC offset, in seconds east of UTC, in effect at the local wall-clock time `local`
+    /// (e.g. `20240701T120000`).
+    ///
+    /// Local times skipped or repeated by a transition resolve to the offset of the
+    /// observance starting at that transition.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `local` is not a date-time or an observance rule cannot be
+    /// evaluated.
+    pub fn utc_offset_at(&self, local: &str) -> Result<i32> {
+        let at = parse_local_datetime(local)
+            .ok_or_else(|| anyhow!("invalid local date-time {local:?}"))?;
+        let mut latest: Option<(i64, i32)> = None;
+        let mut earliest: Option<(i64, i32)> = None;
+        for observance in &self.observances {
+            let Some(first) = parse_local_datetime(&observance.start) else {
+                continue;
+            };
+            if earliest.is_none_or(|(t, _)| first < t) {
+                earliest = Some((first, observance.offset_from));
+            }
+            let mut onsets = match &observance.rrule {
+                Some(rrule) => RecurrenceRule::parse(rrule)?.occurrences(first, at + 1)?,
+                None if first <= at => vec![first],
+                None => Vec::new(),
+            };
+            onsets.extend(
+                observance
+                    .rdates
+                    .iter()
+                    .filter_map(|r| parse_local_datetime(r))
+                    .filter(|t| *t <= at),
+            );
+            if let Some(onset) = onsets.into_iter().max()
+                && latest.is_none_or(|(t, _)| onset > t)
+            {
+                latest = Some((onset, observance.offset_to));
+            }
+        }
+        latest
+            .or(earliest)
+            .map(|(_, offset)| offset)
+            .ok_or_else(|| anyhow!("VTIMEZONE {} has no usable observance", self.tzid))
+    }
+
+    /// Convert a local wall-clock date-time to a UTC date-time (`YYYYMMDDTHHMMSSZ`).
+    ///
+    /// # Errors
+    ///
+    /// See [`utc_offset_at`](Self::utc_offset_at).
+    pub fn to_utc(&self, local: &str) -> Result<String> {
+        let offset = self.utc_offset_at(local)?;
+        let at = parse_local_datetime(local).unwrap_or_default();
+        Ok(format_utc_datetime(at - i64::from(offset)))
+    }
+}
+
+/// Parse every embedded `VTIMEZONE` of a calendar.
+///
+/// # Errors
+///
+/// Returns an error when a `VTIMEZONE` is malformed (see [`VTimeZone::parse`]).
+pub fn parse_timezones(calendar: &ICalendar) -> Result<Vec<VTimeZone>> {
+    calendar.timezones.iter().map(VTimeZone::parse).collect()
+}
+
+/// Find the zone for `tzid` among `zones`, comparing exact `TZID`s first and then their
+/// [`normalize_tzid`] forms.
+pub fn find_timezone<'a>(zones: &'a [VTimeZone], tzid: &str) -> Option<&'a VTimeZone> {
+    zones.iter().find(|z| z.tzid == tzid).or_else(|| {
+        let wanted = normalize_tzid(tzid);
+        zones.iter().find(|z| normalize_tzid(&z.tzid) == wanted)
+    })
+}
+
+/// Windows (Outlook/Exchange) time zone names and their IANA equivalents (CLDR primary zone).
+const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("FLE Standard Time", "Europe/Kiev"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("Argentina Standard Time", "America/Buenos_Aires"),
+];
+
+/// Map a `TZID` to its IANA name where it can be inferred.
+///
+/// Handles vendor-prefixed identifiers (`/mozilla.org/20050126_1/Europe/Paris`,
+/// `/softwarestudio.org/Olson_20011030_5/America/New_York`) and common Windows names
+/// (`W. Europe Standard Time`). Anything else is returned unchanged.
+pub fn normalize_tzid(tzid: &str) -> String {
+    let tzid = tzid.trim().trim_matches('"');
+    if let Some((_, iana)) = WINDOWS_ZONES
+        .iter()
+        .find(|(windows, _)| windows.eq_ignore_ascii_case(tzid))
+    {
+        return (*iana).to_string();
+    }
+    if tzid.starts_with('/') {
+        // Keep the trailing `Area/Location` (or `Area/Sub/Location`) segments.
+        let segments: Vec<&str> = tzid.split('/').filter(|s| !s.is_empty()).collect();
+        if let Some(start) = segments.iter().position(|s| is_iana_area(s)) {
+            return segments[start..].join("/");
+        }
+    }
+    tzid.to_string()
+}
+
+fn is_iana_area(segment: &str) -> bool {
+    matches!(
+        segment,
+        "Africa"
+            | "America"
+            | "Antarctica"
+            | "Arctic"
+            | "Asia"
+            | "Atlantic"
+            | "Australia"
+            | "Europe"
+            | "Indian"
+            | "Pacific"
+            | "Etc"
+    )
+}
+
+/// Parse a `UTC-OFFSET` value (`+0100`, `-0530`, `+013045`) into seconds east of UTC.
+fn parse_utc_offset(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let (sign, digits) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    if !matches!(digits.len(), 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[0..2].parse().ok()?;
+    let minutes: i32 = digits[2..4].parse().ok()?;
+    let seconds: i32 = digits.get(4..6).map_or(Some(0), |s| s.parse().ok())?;
+    Some(sign * (hours * 3_600 + minutes * 60 + seconds))
+}
+
+impl ICalDateTime {
+    /// Convert to a UTC date-time (`YYYYMMDDTHHMMSSZ`) using the embedded `zones`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for `DATE` and floating values, which have no time zone, and for
+    /// `TZID`s without a matching `VTIMEZONE` (e.g. with time zones by reference, RFC 7809).
+    pub fn to_utc(&self, zones: &[VTimeZone]) -> Result<String> {
+        if self.is_date() {
+            return Err(anyhow!("DATE value {} has no time of day", self.value));
+        }
+        if self.is_utc() {
+            return parse_utc_datetime(&self.value.to_ascii_uppercase())
+                .map(format_utc_datetime)
+                .ok_or_else(|| anyhow!("invalid date-time {:?}", self.value));
+        }
+        let tzid = self
+            .tzid
+            .as_deref()
+            .ok_or_else(|| anyhow!("floating date-time {} has no time zone", self.value))?;
+        find_timezone(zones, tzid)
+            .ok_or_else(|| anyhow!("no VTIMEZONE for TZID {tzid}"))?
+            .to_utc(&self.value)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ICalDateTime {
+    /// The wall-clock value as a `chrono::NaiveDateTime` (midnight for `DATE` values),
+    /// ignoring any time zone.
+    ///
+    /// Useful to resolve `TZID`s with an external database, e.g.
+    /// `chrono_tz::Tz::from_local_datetime`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the value is not a valid `DATE` or `DATE-TIME`.
+    pub fn to_naive_datetime(&self) -> Result<chrono::NaiveDateTime> {
+        let seconds = parse_local_datetime(&self.value)
+            .ok_or_else(|| anyhow!("invalid date-time {:?}", self.value))?;
+        chrono::DateTime::from_timestamp(seconds, 0)
+            .map(|dt| dt.naive_utc())
+            .ok_or_else(|| anyhow!("date-time {:?} out of range", self.value))
+    }
+
+    /// Convert to a `chrono::DateTime<Utc>` using the embedded `zones`. See
+    /// [`to_utc`](Self::to_utc).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`to_utc`](Self::to_utc).
+    pub fn to_utc_datetime(&self, zones: &[VTimeZone]) -> Result<chrono::DateTime<chrono::Utc>> {
+        let utc = self.to_utc(zones)?;
+        let seconds = parse_utc_datetime(&utc).unwrap_or_default();
+        chrono::DateTime::from_timestamp(seconds, 0)
+            .ok_or_else(|| anyhow!("date-time {utc:?} out of range"))
+    }
+
+    /// Convert into the time zone `tz` (any `chrono::TimeZone`, e.g. `chrono::Local`,
+    /// a `FixedOffset` or a `chrono_tz::Tz`).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`to_utc`](Self::to_utc).
+    pub fn to_zone<Tz: chrono::TimeZone>(
+        &self,
+        zones: &[VTimeZone],
+        tz: &Tz,
+    ) -> Result<chrono::DateTime<Tz>> {
+        Ok(self.to_utc_datetime(zones)?.with_timezone(tz))
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::webdav::types::DavItemCommon;
 pub use crate::webdav::types::{BatchItem, Depth};
 
@@ -10,13 +12,31 @@ pub struct DavItem {
     pub etag: Option<String>,
     pub is_collection: bool,
     pub is_calendar: bool,
+    /// `resourcetype` contains `CS:shared`: a calendar shared with the current user.
+    pub is_shared: bool,
+    /// `resourcetype` contains `CS:shared-owner`: the user's own calendar, shared with others.
+    pub is_shared_owner: bool,
+    /// `CS:shared-url`: the owner's original of a shared calendar.
+    pub shared_url: Option<String>,
+    /// `CS:notification-URL` of a principal.
+    pub notification_url: Option<String>,
     pub supported_components: Vec<String>,
     pub calendar_data: Option<String>,
     pub calendar_home_set: Vec<String>,
+    pub schedule_inbox_url: Option<String>,
+    pub schedule_outbox_url: Option<String>,
+    pub calendar_user_address_set: Vec<String>,
     pub current_user_principal: Vec<String>,
     pub owner: Option<String>,
     pub calendar_description: Option<String>,
     pub calendar_timezone: Option<String>,
+    pub calendar_timezone_id: Option<String>,
+    pub calendar_availability: Option<String>,
+    /// `CALDAV:schedule-calendar-transp` (RFC 6638 §9.1).
+    pub schedule_calendar_transp: Option<ScheduleTransparency>,
+    /// `CALDAV:schedule-default-calendar-URL` of a schedule inbox (RFC 6638 §9.2).
+    pub schedule_default_calendar_url: Option<String>,
+    pub timezone_service_set: Vec<String>,
     pub calendar_color: Option<String>,
     pub sync_token: Option<String>,
     pub content_type: Option<String>,
@@ -38,13 +58,25 @@ impl DavItem {
             etag: None,
             is_collection: false,
             is_calendar: false,
+            is_shared: false,
+            is_shared_owner: false,
+            shared_url: None,
+            notification_url: None,
             supported_components: Vec::new(),
             calendar_data: None,
             calendar_home_set: Vec::new(),
+            schedule_inbox_url: None,
+            schedule_outbox_url: None,
+            calendar_user_address_set: Vec::new(),
             current_user_principal: Vec::new(),
             owner: None,
             calendar_description: None,
             calendar_timezone: None,
+            calendar_timezone_id: None,
+            calendar_availability: None,
+            schedule_calendar_transp: None,
+            schedule_default_calendar_url: None,
+            timezone_service_set: Vec::new(),
             calendar_color: None,
             sync_token: None,
             content_type: None,
@@ -67,18 +99,519 @@ impl DavItem {
 }
 
 /// Summary of a calendar (collection) returned by a `PROPFIND` depth=1.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CalendarInfo {
     pub href: String,
     pub displayname: Option<String>,
     pub description: Option<String>,
     pub timezone: Option<String>,
+    /// `CALDAV:calendar-timezone-id` (RFC 7809), the calendar's time zone by reference.
+    pub timezone_id: Option<String>,
     pub color: Option<String>,
+    /// `CALDAV:schedule-calendar-transp`; servers treat a missing value as opaque.
+    pub transparency: Option<ScheduleTransparency>,
     pub etag: Option<String>,
     pub sync_token: Option<String>,
     pub supported_components: Vec<String>,
 }
 
+impl CalendarInfo {
+    /// Whether the calendar accepts `component` (e.g. `VTODO`), per its
+    /// `supported-calendar-component-set`. An empty set means the server reported no
+    /// restriction.
+    pub fn supports_component(&self, component: &str) -> bool {
+        self.supported_components.is_empty()
+            || self
+                .supported_components
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(component))
+    }
+}
+
+/// A calendar object was rejected before upload because the target calendar's
+/// `supported-calendar-component-set` does not include its component type.
+///
+/// Returned wrapped in [`anyhow::Error`]; recover it with `downcast_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedComponentError {
+    /// Href of the target calendar.
+    pub calendar: String,
+    /// Offending component type, upper-cased (e.g. `VTODO`).
+    pub component: String,
+    /// Components the calendar accepts.
+    pub supported: Vec<String>,
+}
+
+impl fmt::Display for UnsupportedComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "calendar {} does not support {} components (supported: {})",
+            self.calendar,
+            self.component,
+            self.supported.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedComponentError {}
+
+/// A problem found by [`crate::caldav::validate_ics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsIssue {
+    /// 1-based physical line number.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for IcsIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// An iCalendar object failed validation before upload.
+///
+/// Returned wrapped in [`anyhow::Error`]; recover it with `downcast_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsValidationError {
+    /// Every problem found, in document order.
+    pub issues: Vec<IcsIssue>,
+}
+
+impl fmt::Display for IcsValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid iCalendar")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            write!(f, "{}{issue}", if i == 0 { ": " } else { "; " })?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IcsValidationError {}
+
+/// Scheduling collections (RFC 6638) advertised on a principal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleUrls {
+    /// `CALDAV:schedule-inbox-URL`, where incoming iTIP messages are delivered.
+    pub inbox: Option<String>,
+    /// `CALDAV:schedule-outbox-URL`, the target for outgoing iTIP `POST`s.
+    pub outbox: Option<String>,
+}
+
+/// Whether the events of a calendar count as busy time in free-busy lookups
+/// (`CALDAV:schedule-calendar-transp`, RFC 6638 §9.1).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScheduleTransparency {
+    /// Events block time (the RFC 6638 default).
+    #[default]
+    Opaque,
+    /// Events are ignored by free-busy, e.g. holiday or subscribed calendars.
+    Transparent,
+}
+
+impl ScheduleTransparency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduleTransparency::Opaque => "opaque",
+            ScheduleTransparency::Transparent => "transparent",
+        }
+    }
+}
+
+/// Per-recipient outcome of an iTIP `POST` to the schedule outbox (RFC 6638 §3.2.10).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleRecipientStatus {
+    /// The `CALDAV:recipient` href, usually a `mailto:` URI.
+    pub recipient: String,
+    /// The iTIP `REQUEST-STATUS` value, e.g. `2.0;Success` or `3.7;Invalid calendar user`.
+    pub request_status: Option<String>,
+    /// iCalendar returned for this recipient, e.g. the `VFREEBUSY` of a free-busy request.
+    pub calendar_data: Option<String>,
+    /// `DAV:responsedescription`, when the server adds a human-readable explanation.
+    pub description: Option<String>,
+}
+
+impl ScheduleRecipientStatus {
+    /// The numeric part of `request_status` (e.g. `2.0`).
+    pub fn status_code(&self) -> Option<&str> {
+        self.request_status
+            .as_deref()
+            .map(|s| s.split(';').next().unwrap_or(s).trim())
+    }
+
+    /// `true` when the request status is in the `2.x` (success) class.
+    pub fn is_success(&self) -> bool {
+        self.status_code().is_some_and(|c| c.starts_with('2'))
+    }
+}
+
+/// Attendee participation status (`PARTSTAT`, RFC 5545 §3.2.12).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Partstat {
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+}
+
+impl Partstat {
+    /// The `PARTSTAT` parameter value, e.g. `ACCEPTED`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Partstat::NeedsAction => "NEEDS-ACTION",
+            Partstat::Accepted => "ACCEPTED",
+            Partstat::Declined => "DECLINED",
+            Partstat::Tentative => "TENTATIVE",
+            Partstat::Delegated => "DELEGATED",
+        }
+    }
+}
+
+/// Attendee participation role (`ROLE`, RFC 5545 §3.2.16).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AttendeeRole {
+    Chair,
+    #[default]
+    ReqParticipant,
+    OptParticipant,
+    NonParticipant,
+}
+
+impl AttendeeRole {
+    /// The `ROLE` parameter value, e.g. `REQ-PARTICIPANT`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttendeeRole::Chair => "CHAIR",
+            AttendeeRole::ReqParticipant => "REQ-PARTICIPANT",
+            AttendeeRole::OptParticipant => "OPT-PARTICIPANT",
+            AttendeeRole::NonParticipant => "NON-PARTICIPANT",
+        }
+    }
+}
+
+/// Validators returned by a successful `PUT`.
+///
+/// Servers may omit the `ETag` when they altered the stored data; refetch the object then.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PutResult {
+    pub etag: Option<String>,
+    /// `Schedule-Tag` (RFC 6638 §3.2.10), sent for scheduling objects.
+    pub schedule_tag: Option<String>,
+}
+
+/// Calendar object fetched with
+/// [`get_scheduling_object`](crate::CalDavClient::get_scheduling_object), with the
+/// validators to write it back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchedulingObject {
+    pub calendar_data: String,
+    pub etag: Option<String>,
+    /// `Schedule-Tag` (RFC 6638 §3.2.10), sent for scheduling objects; pass it to
+    /// [`put_if_schedule_tag_match`](crate::CalDavClient::put_if_schedule_tag_match).
+    pub schedule_tag: Option<String>,
+}
+
+/// iTIP method (RFC 5546 §1.4) of a scheduling message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItipMethod {
+    Request,
+    Reply,
+    Cancel,
+    Counter,
+}
+
+impl ItipMethod {
+    /// The `METHOD` property value, e.g. `REQUEST`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItipMethod::Request => "REQUEST",
+            ItipMethod::Reply => "REPLY",
+            ItipMethod::Cancel => "CANCEL",
+            ItipMethod::Counter => "COUNTER",
+        }
+    }
+}
+
+/// `FBTYPE` of a free-busy period (RFC 5545 §3.2.9).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FreeBusyType {
+    Free,
+    Busy,
+    BusyUnavailable,
+    BusyTentative,
+    /// Experimental or IANA-registered value not known to this crate.
+    Other(String),
+}
+
+impl FreeBusyType {
+    /// Parse an `FBTYPE` parameter value (case-insensitive).
+    pub fn from_param(value: &str) -> Self {
+        match value.to_ascii_uppercase().as_str() {
+            "FREE" => Self::Free,
+            "BUSY" => Self::Busy,
+            "BUSY-UNAVAILABLE" => Self::BusyUnavailable,
+            "BUSY-TENTATIVE" => Self::BusyTentative,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Free => "FREE",
+            Self::Busy => "BUSY",
+            Self::BusyUnavailable => "BUSY-UNAVAILABLE",
+            Self::BusyTentative => "BUSY-TENTATIVE",
+            Self::Other(value) => value,
+        }
+    }
+
+    /// Whether the period blocks time (every type except `FREE`).
+    pub fn is_busy(&self) -> bool {
+        !matches!(self, Self::Free)
+    }
+}
+
+/// A `VAVAILABILITY` component (RFC 7953 §3.1): the time during which a calendar user is
+/// normally available (e.g. working hours), expressed as [`AvailableSlot`]s.
+///
+/// Time outside the slots but inside `[start, end)` is reported with `busy_type` in
+/// free-busy lookups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Availability {
+    pub uid: String,
+    /// `BUSYTYPE`; RFC 7953 defaults to `BUSY-UNAVAILABLE`.
+    pub busy_type: FreeBusyType,
+    /// `DTSTART`/`DTEND` as UTC date-times; `None` means unbounded.
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// `PRIORITY` (1 highest … 9 lowest, 0 undefined) used to resolve overlapping components.
+    pub priority: Option<u8>,
+    pub summary: Option<String>,
+    pub available: Vec<AvailableSlot>,
+}
+
+impl Availability {
+    pub fn new(uid: &str) -> Self {
+        Self {
+            uid: uid.to_string(),
+            busy_type: FreeBusyType::BusyUnavailable,
+            start: None,
+            end: None,
+            priority: None,
+            summary: None,
+            available: Vec::new(),
+        }
+    }
+}
+
+/// An `AVAILABLE` sub-component (RFC 7953 §3.2): one, possibly recurring, available slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailableSlot {
+    pub uid: String,
+    /// `DTSTART` value, local to `tzid` when set (e.g. `20240101T090000`), UTC otherwise.
+    pub start: String,
+    pub tzid: Option<String>,
+    /// `DTEND` value, in the same time zone as `start`.
+    pub end: Option<String>,
+    /// `DURATION`, used when there is no `end` (e.g. `PT8H`).
+    pub duration: Option<String>,
+    /// `RRULE`, e.g. `FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR`.
+    pub rrule: Option<String>,
+    pub summary: Option<String>,
+}
+
+impl AvailableSlot {
+    pub fn new(uid: &str, start: &str) -> Self {
+        Self {
+            uid: uid.to_string(),
+            start: start.to_string(),
+            tzid: None,
+            end: None,
+            duration: None,
+            rrule: None,
+            summary: None,
+        }
+    }
+}
+
+/// A single `FREEBUSY` period with both bounds as UTC date-times (`YYYYMMDDTHHMMSSZ`).
+///
+/// Periods expressed as `start/duration` are normalized to an explicit end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeBusyPeriod {
+    pub start: String,
+    pub end: String,
+    pub kind: FreeBusyType,
+}
+
+/// Parsed `VFREEBUSY` returned by a `free-busy-query` REPORT.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FreeBusy {
+    /// `DTSTART` of the `VFREEBUSY`, i.e. the queried window start.
+    pub start: Option<String>,
+    /// `DTEND` of the `VFREEBUSY`, i.e. the queried window end.
+    pub end: Option<String>,
+    pub periods: Vec<FreeBusyPeriod>,
+}
+
+impl FreeBusy {
+    /// Iterate over the periods that block time (anything but `FBTYPE=FREE`).
+    pub fn busy(&self) -> impl Iterator<Item = &FreeBusyPeriod> {
+        self.periods.iter().filter(|p| p.kind.is_busy())
+    }
+
+    /// The gaps between busy periods inside `[start, end)`, as `FBTYPE=FREE` periods: the
+    /// candidate slots of a "find a meeting time" search.
+    ///
+    /// Without `start`/`end` only the gaps between busy periods are reported.
+    pub fn free_periods(&self) -> Vec<FreeBusyPeriod> {
+        let busy = crate::caldav::scheduling::merge_busy_periods(self.periods.iter().cloned());
+        let mut cursor = self
+            .start
+            .clone()
+            .or_else(|| busy.first().map(|p| p.start.clone()));
+        let mut free = Vec::new();
+        let mut push_gap = |from: &str, to: &str| {
+            let to = match &self.end {
+                Some(end) if end.as_str() < to => end.as_str(),
+                _ => to,
+            };
+            if from < to {
+                free.push(FreeBusyPeriod {
+                    start: from.to_string(),
+                    end: to.to_string(),
+                    kind: FreeBusyType::Free,
+                });
+            }
+        };
+        for period in &busy {
+            if let Some(from) = &cursor {
+                push_gap(from, &period.start);
+            }
+            if cursor.as_ref().is_none_or(|c| *c < period.end) {
+                cursor = Some(period.end.clone());
+            }
+        }
+        if let (Some(from), Some(end)) = (&cursor, &self.end) {
+            push_gap(from, end);
+        }
+        free
+    }
+}
+
+/// Access granted to a sharee of a calendar (calendarserver-sharing).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShareAccess {
+    #[default]
+    Read,
+    ReadWrite,
+}
+
+impl ShareAccess {
+    /// The `CS:` element name, e.g. `read-write`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShareAccess::Read => "read",
+            ShareAccess::ReadWrite => "read-write",
+        }
+    }
+}
+
+/// A sharee's answer to a share invitation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InviteStatus {
+    #[default]
+    NoResponse,
+    Accepted,
+    Declined,
+    /// The server could not resolve the invited address.
+    Invalid,
+}
+
+/// An invitation to share a calendar, sent with
+/// [`crate::caldav::CalDavClient::share_calendar`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareInvite {
+    /// Sharee address, e.g. `mailto:jane@example.com` or a principal URL.
+    pub href: String,
+    pub access: ShareAccess,
+    pub common_name: Option<String>,
+    /// Message shown to the sharee.
+    pub summary: Option<String>,
+}
+
+impl ShareInvite {
+    pub fn new(href: &str, access: ShareAccess) -> Self {
+        Self {
+            href: href.to_string(),
+            access,
+            common_name: None,
+            summary: None,
+        }
+    }
+
+    pub fn common_name(mut self, name: &str) -> Self {
+        self.common_name = Some(name.to_string());
+        self
+    }
+
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+}
+
+/// A sharee listed in a calendar's `CS:invite` property.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sharee {
+    pub href: String,
+    pub common_name: Option<String>,
+    pub access: ShareAccess,
+    pub status: InviteStatus,
+    pub summary: Option<String>,
+}
+
+/// The `CS:invite` property of a shared calendar: its owner and sharees.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarInvite {
+    /// Owner address or principal URL.
+    pub organizer: Option<String>,
+    pub organizer_name: Option<String>,
+    pub sharees: Vec<Sharee>,
+}
+
+/// A share invitation received in the notification collection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShareInvitation {
+    /// Notification `CS:uid`, echoed in the reply.
+    pub uid: String,
+    /// Href of the notification resource.
+    pub notification_href: String,
+    /// The invited (current) user's address.
+    pub href: String,
+    pub status: InviteStatus,
+    /// The shared calendar on the owner's side.
+    pub host_url: String,
+    pub organizer: Option<String>,
+    pub organizer_name: Option<String>,
+    pub access: ShareAccess,
+    pub summary: Option<String>,
+}
+
+/// A calendar shared with the current user, as listed in their calendar home.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SharedCalendar {
+    pub href: String,
+    pub displayname: Option<String>,
+    pub color: Option<String>,
+    /// The owner's original calendar (`CS:shared-url`).
+    pub shared_url: Option<String>,
+    pub owner: Option<String>,
+}
+
 /// Calendar object (event or task) returned by a `REPORT`.
 #[derive(Debug, Clone)]
 pub struct CalendarObject {
@@ -88,6 +621,26 @@ pub struct CalendarObject {
     pub status: Option<String>,
 }
 
+/// Journal entry (`VJOURNAL`, RFC 5545 §3.6.3), e.g. a note synced from Nextcloud.
+///
+/// Text values are unescaped. A calendar object holding several `VJOURNAL`s (recurrence
+/// overrides) yields one entry per component, all sharing `href` and `etag`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Journal {
+    pub href: String,
+    pub etag: Option<String>,
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    /// `DESCRIPTION` values; a journal may carry several, joined here with blank lines.
+    pub description: Option<String>,
+    /// `DTSTART` as sent by the server (date or date-time).
+    pub dtstart: Option<String>,
+    /// `STATUS`: `DRAFT`, `FINAL` or `CANCELLED`.
+    pub status: Option<String>,
+    pub categories: Vec<String>,
+    pub recurrence_id: Option<String>,
+}
+
 /// Detail of an item returned by `sync-collection`.
 #[derive(Debug, Clone)]
 pub struct SyncItem {
@@ -103,4 +656,7 @@ pub struct SyncItem {
 pub struct SyncResponse {
     pub sync_token: Option<String>,
     pub items: Vec<SyncItem>,
+    /// The server stopped at its result limit and flagged the collection with
+    /// `507 Insufficient Storage` (RFC 6578 §3.6): sync again from `sync_token` for the rest.
+    pub truncated: bool,
 }
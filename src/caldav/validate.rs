@@ -0,0 +1,230 @@
+//! Pre-upload linting of iCalendar objects.
+//!
+//! Servers tend to answer malformed objects with a bare `400 Bad Request`.
+//! [`validate_ics`] finds the usual culprits locally, with line numbers, so they can be fixed
+//! before the `PUT` (see also [`crate::caldav::CalDavClient::set_validate_before_put`]).
+
+use anyhow::Result;
+use bytes::Bytes;
+
+use crate::caldav::types::{IcsIssue, IcsValidationError};
+use crate::common::contentline::ContentLine;
+
+/// Maximum line length in octets, excluding the CRLF (RFC 5545 §3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+/// `TEXT` properties holding a single value, where `,` must be escaped as well.
+const SINGLE_TEXT: &[&str] = &["SUMMARY", "DESCRIPTION", "LOCATION", "COMMENT", "CONTACT"];
+/// `TEXT` properties holding a comma-separated list.
+const LIST_TEXT: &[&str] = &["CATEGORIES", "RESOURCES"];
+
+/// Properties each component must carry.
+fn required_properties(component: &str) -> &'static [&'static str] {
+    match component {
+        "VCALENDAR" => &["PRODID", "VERSION"],
+        "VEVENT" | "VTODO" | "VJOURNAL" | "VFREEBUSY" => &["UID", "DTSTAMP"],
+        _ => &[],
+    }
+}
+
+/// Validate an iCalendar object before upload.
+///
+/// Checks for:
+/// - line endings other than CRLF and a missing final CRLF,
+/// - lines longer than 75 octets (unfolded), empty lines and folds at the start of the data,
+/// - malformed content lines and unbalanced `BEGIN`/`END`,
+/// - missing `PRODID`/`VERSION` on `VCALENDAR` and `UID`/`DTSTAMP` on `VEVENT`, `VTODO`,
+///   `VJOURNAL` and `VFREEBUSY`,
+/// - unescaped `;` (and `,` in single-valued properties) or invalid `\` escapes in text
+///   values such as `SUMMARY` and `DESCRIPTION`.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use fast_dav_rs::caldav::{IcsValidationError, validate_ics};
+///
+/// let ics = Bytes::from("BEGIN:VCALENDAR\nVERSION:2.0\r\nBEGIN:VEVENT\r\nSUMMARY:a;b\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n");
+/// let err = validate_ics(&ics).unwrap_err();
+/// let issues = &err.downcast_ref::<IcsValidationError>().unwrap().issues;
+/// assert_eq!(issues[0].line, 1);
+/// assert!(issues.iter().any(|i| i.message.contains("UID")));
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`IcsValidationError`] listing every issue found.
+pub fn validate_ics(ics: &Bytes) -> Result<()> {
+    let issues = lint(ics);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(IcsValidationError { issues }.into())
+    }
+}
+
+fn lint(ics: &[u8]) -> Vec<IcsIssue> {
+    let mut issues = Vec::new();
+
+    let text = match std::str::from_utf8(ics) {
+        Ok(text) => text,
+        Err(e) => {
+            let line = ics[..e.valid_up_to()]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count()
+                + 1;
+            flag(&mut issues, line, "data is not valid UTF-8".to_string());
+            return issues;
+        }
+    };
+    if text.is_empty() {
+        flag(&mut issues, 1, "empty iCalendar object".to_string());
+        return issues;
+    }
+
+    // Physical lines, checked for line endings and length, then unfolded.
+    let mut logical: Vec<(usize, String)> = Vec::new();
+    let mut segments: Vec<&str> = text.split('\n').collect();
+    if segments.last() == Some(&"") {
+        segments.pop();
+    } else {
+        flag(
+            &mut issues,
+            segments.len(),
+            "last line is not terminated by CRLF".to_string(),
+        );
+    }
+    let terminated = text.ends_with('\n');
+    for (index, segment) in segments.iter().enumerate() {
+        let number = index + 1;
+        let is_last = index + 1 == segments.len();
+        let line = match segment.strip_suffix('\r') {
+            Some(line) => line,
+            None if is_last && !terminated => segment,
+            None => {
+                flag(
+                    &mut issues,
+                    number,
+                    "line ends with LF instead of CRLF".to_string(),
+                );
+                segment
+            }
+        };
+        if line.contains('\r') {
+            flag(&mut issues, number, "bare CR inside line".to_string());
+        }
+        if line.len() > MAX_LINE_OCTETS {
+            flag(
+                &mut issues,
+                number,
+                format!(
+                    "line is {} octets long; fold lines at {MAX_LINE_OCTETS}",
+                    line.len()
+                ),
+            );
+        }
+        if let Some(rest) = line.strip_prefix([' ', '\t']) {
+            match logical.last_mut() {
+                Some((_, previous)) => previous.push_str(rest),
+                None => flag(
+                    &mut issues,
+                    number,
+                    "folded continuation without a preceding line".to_string(),
+                ),
+            }
+        } else if line.is_empty() {
+            flag(&mut issues, number, "empty line".to_string());
+        } else {
+            logical.push((number, line.to_string()));
+        }
+    }
+
+    // Component structure, required properties and text escaping.
+    let mut stack: Vec<(String, usize, Vec<String>)> = Vec::new();
+    let mut roots = 0;
+    for (number, line) in &logical {
+        let number = *number;
+        let parsed = match ContentLine::parse(line) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                flag(&mut issues, number, e.to_string());
+                continue;
+            }
+        };
+        match parsed.name.as_str() {
+            "BEGIN" => {
+                if stack.is_empty() {
+                    roots += 1;
+                }
+                stack.push((parsed.value.trim().to_ascii_uppercase(), number, Vec::new()));
+            }
+            "END" => {
+                let name = parsed.value.trim().to_ascii_uppercase();
+                match stack.pop() {
+                    Some((open, begin, properties)) if open == name => {
+                        for required in required_properties(&open) {
+                            if !properties.iter().any(|p| p == required) {
+                                flag(&mut issues, begin, format!("{open} is missing {required}"));
+                            }
+                        }
+                    }
+                    Some((open, begin, _)) => {
+                        flag(
+                            &mut issues,
+                            number,
+                            format!("END:{name} closes {open} opened on line {begin}"),
+                        );
+                    }
+                    None => flag(&mut issues, number, format!("END:{name} without BEGIN")),
+                }
+            }
+            name => {
+                let Some((_, _, properties)) = stack.last_mut() else {
+                    flag(
+                        &mut issues,
+                        number,
+                        format!("{name} outside of any component"),
+                    );
+                    continue;
+                };
+                let base = parsed.base_name().to_ascii_uppercase();
+                properties.push(base.clone());
+                if SINGLE_TEXT.contains(&base.as_str()) {
+                    check_text(&mut issues, number, &base, &parsed.value, true);
+                } else if LIST_TEXT.contains(&base.as_str()) {
+                    check_text(&mut issues, number, &base, &parsed.value, false);
+                }
+            }
+        }
+    }
+    for (open, begin, _) in stack {
+        flag(&mut issues, begin, format!("BEGIN:{open} is never closed"));
+    }
+    if roots == 0 {
+        flag(&mut issues, 1, "no BEGIN:VCALENDAR".to_string());
+    }
+    issues
+}
+
+fn flag(issues: &mut Vec<IcsIssue>, line: usize, message: String) {
+    issues.push(IcsIssue { line, message });
+}
+
+fn check_text(issues: &mut Vec<IcsIssue>, line: usize, name: &str, value: &str, single: bool) {
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let message = match c {
+            '\\' => match chars.next() {
+                Some('\\' | ';' | ',' | 'n' | 'N') => continue,
+                Some(other) => format!("{name} has an invalid escape \\{other}"),
+                None => format!("{name} ends with a lone backslash"),
+            },
+            ';' => format!("{name} has an unescaped ';'"),
+            ',' if single => format!("{name} has an unescaped ','"),
+            _ => continue,
+        };
+        flag(issues, line, message);
+        return;
+    }
+}
@@ -1,17 +1,57 @@
 use anyhow::{Result, anyhow};
 use bytes::Bytes;
+use futures::{Stream, StreamExt, stream};
+use http_body_util::BodyExt;
 use hyper::body::Incoming;
 use hyper::{HeaderMap, Method, Response, StatusCode, Uri, header};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::time::Duration;
 
-use crate::carddav::streaming::parse_multistatus_bytes;
+use crate::caldav::client::{DEFAULT_MULTIGET_CHUNK_SIZE, DEFAULT_MULTIGET_CONCURRENCY};
+use crate::carddav::collection::{AddressBookPropPatch, MkAddressBook};
+#[cfg(feature = "vcard")]
+use crate::carddav::import::duplicate_filter;
+use crate::carddav::import::{card_uid, href_for_uid, split_vcards};
+use crate::carddav::phone::{card_has_phone, phone_filter};
+#[cfg(feature = "vcard")]
+use crate::carddav::photo::{PhotoSource, photo_stream, replace_photo};
+#[cfg(feature = "query-builder")]
+use crate::carddav::query::AddressbookQuery;
+use crate::carddav::query::{AddressData, AddressbookFilter, limit_xml};
+use crate::carddav::streaming::{
+    ParseResult, STREAM_READ_IDLE_TIMEOUT, SyncItemStream, decoded_reader,
+    parse_multistatus_bytes_with_limits,
+};
 use crate::carddav::types::{
-    AddressBookInfo, AddressObject, BatchItem, DavItem, Depth, SyncItem, SyncResponse,
+    AddressBookInfo, AddressObject, BatchItem, DavItem, Depth, DirectoryInfo, DirectorySearch,
+    ImportOutcome, ImportResult, ResourceLimits, SyncItem, SyncResponse,
+};
+#[cfg(feature = "vcard")]
+use crate::carddav::vcard::{Contact, VCardVersion, vcard_to_v3, vcard_to_v4};
+use crate::common::compression::{
+    ContentEncoding, DecompressedBody, DecompressionOptions, detect_encodings,
+};
+use crate::sync::{
+    CachedGet, ChangeStream, ConflictPolicy, ConflictResolution, EtagCache, MemoryTokenStore,
+    MutationQueue, ReplayReport, SyncEngine, TokenStore,
 };
-use crate::common::compression::ContentEncoding;
 use crate::webdav::client::WebDavClient;
-use crate::webdav::types::http_status_code;
+use crate::webdav::condition::sync_collection_error;
+use crate::webdav::href::{normalize_href, resolve_href};
+use crate::webdav::lock::IfHeader;
+use crate::webdav::mkcol::ExtendedMkcol;
+use crate::webdav::property::PropertyRegistry;
+use crate::webdav::proppatch::{PropPatch, parse_proppatch_response};
+use crate::webdav::types::{
+    Ace, DavCapabilities, Lock, LockScope, LockTimeout, PrincipalInfo, PropNames, PropPatchResult,
+    PropertyName, PushInfo, PushRegistration, RecursiveReport, ResourceProps, VersionEntry,
+    WebPushSubscription,
+};
+use crate::webdav::types::{MatchType, TextMatch};
+use crate::webdav::types::{ParseLimits, http_status_code};
+use crate::webdav::xml::text_match_xml;
 
 pub use crate::webdav::client::RequestCompressionMode;
 
@@ -30,6 +70,8 @@ pub use crate::webdav::client::RequestCompressionMode;
 #[derive(Clone)]
 pub struct CardDavClient {
     webdav: WebDavClient,
+    limits: ResourceLimits,
+    parse_limits: ParseLimits,
 }
 
 impl CardDavClient {
@@ -75,9 +117,35 @@ impl CardDavClient {
     pub fn new(base_url: &str, basic_user: Option<&str>, basic_pass: Option<&str>) -> Result<Self> {
         Ok(Self {
             webdav: WebDavClient::new(base_url, basic_user, basic_pass)?,
+            limits: ResourceLimits::default(),
+            parse_limits: ParseLimits::default(),
         })
     }
 
+    /// Check vCard bodies against `limits` before the `PUT` helpers send them, and photos in
+    /// [`set_photo`](Self::set_photo), so oversized payloads fail locally with a
+    /// [`TooLargeError`](crate::carddav::TooLargeError) instead of a `413` or `507`.
+    ///
+    /// Read the limits of an addressbook with [`resource_limits`](Self::resource_limits).
+    /// No limits are enforced by default.
+    pub fn set_resource_limits(&mut self, limits: ResourceLimits) {
+        self.limits = limits;
+    }
+
+    /// Bounds on the XML responses this client parses (element depth, text size, number
+    /// of items), including the WebDAV ones of its inner [`WebDavClient`]; responses
+    /// exceeding them fail with a [`ParseLimitError`](crate::webdav::ParseLimitError).
+    ///
+    /// Defaults to [`ParseLimits::default`].
+    pub fn set_parse_limits(&mut self, limits: ParseLimits) {
+        self.parse_limits = limits;
+        self.webdav.set_parse_limits(limits);
+    }
+
+    pub(crate) fn parse_multistatus(&self, body: &[u8]) -> Result<ParseResult<Vec<DavItem>>> {
+        parse_multistatus_bytes_with_limits(body, self.parse_limits)
+    }
+
     /// Configure request compression for this client.
     ///
     /// # Arguments
@@ -118,6 +186,27 @@ impl CardDavClient {
         self.webdav.disable_request_compression();
     }
 
+    /// Attach an ETag cache.
+    ///
+    /// See [`WebDavClient::set_etag_cache`].
+    pub fn set_etag_cache(&mut self, cache: Arc<dyn EtagCache>) {
+        self.webdav.set_etag_cache(cache);
+    }
+
+    /// Configure how compressed responses are decoded.
+    ///
+    /// See [`WebDavClient::set_decompression_options`].
+    pub fn set_decompression_options(&mut self, options: DecompressionOptions) {
+        self.webdav.set_decompression_options(options);
+    }
+
+    /// Request body size from which request compression runs on a blocking thread.
+    ///
+    /// See [`WebDavClient::set_compression_blocking_threshold`].
+    pub fn set_compression_blocking_threshold(&mut self, threshold: Option<usize>) {
+        self.webdav.set_compression_blocking_threshold(threshold);
+    }
+
     /// Get the current request compression strategy.
     pub fn request_compression_mode(&self) -> RequestCompressionMode {
         self.webdav.request_compression_mode()
@@ -168,7 +257,9 @@ impl CardDavClient {
     /// Generic **streaming send**. Returns a `Response<Incoming>` (not aggregated).
     ///
     /// Use this when you want to parse the response on the fly, e.g. with
-    /// [`crate::streaming::parse_multistatus_stream`].
+    /// [`crate::carddav::parse_multistatus_stream`]. The body is still compressed when the
+    /// server used a `Content-Encoding`: pass [`detect_encodings`] of the headers to the
+    /// parser, or use [`send_stream_decompressed`](Self::send_stream_decompressed).
     pub async fn send_stream(
         &self,
         method: Method,
@@ -182,6 +273,22 @@ impl CardDavClient {
             .await
     }
 
+    /// [`send_stream`](Self::send_stream) with the body decompressed as it is read.
+    ///
+    /// See [`WebDavClient::send_stream_decompressed`].
+    pub async fn send_stream_decompressed(
+        &self,
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+        body_bytes: Option<Bytes>,
+        per_req_timeout: Option<Duration>,
+    ) -> Result<Response<DecompressedBody>> {
+        self.webdav
+            .send_stream_decompressed(method, path, headers, body_bytes, per_req_timeout)
+            .await
+    }
+
     // ----------- HTTP/WebDAV Verbs -----------
 
     /// Send an `OPTIONS` request.
@@ -190,6 +297,12 @@ impl CardDavClient {
     pub async fn options(&self, path: &str) -> Result<Response<Bytes>> {
         self.webdav.options(path).await
     }
+    /// Read the capabilities `path` advertises with `OPTIONS`.
+    ///
+    /// See [`WebDavClient::capabilities`].
+    pub async fn capabilities(&self, path: &str) -> Result<DavCapabilities> {
+        self.webdav.capabilities(path).await
+    }
     /// Send a `HEAD` request.
     ///
     /// Often used to retrieve an `ETag` before a conditional update/delete.
@@ -200,10 +313,57 @@ impl CardDavClient {
     pub async fn get(&self, path: &str) -> Result<Response<Bytes>> {
         self.webdav.get(path).await
     }
+    /// `GET` skipping the download of a resource unchanged since it was cached.
+    ///
+    /// See [`WebDavClient::get_cached`].
+    pub async fn get_cached(&self, path: &str) -> Result<CachedGet> {
+        self.webdav.get_cached(path).await
+    }
+    /// `PUT` of a vCard body guarded by the cached ETag of `path`, if any.
+    ///
+    /// See [`WebDavClient::put_cached`].
+    pub async fn put_cached(&self, path: &str, vcard_bytes: Bytes) -> Result<Response<Bytes>> {
+        self.limits.check_vcard(&vcard_bytes)?;
+        self.webdav
+            .put_cached(path, "text/vcard; charset=utf-8", vcard_bytes)
+            .await
+    }
+    /// `DELETE` guarded by the cached ETag of `path`, if any.
+    ///
+    /// See [`WebDavClient::delete_cached`].
+    pub async fn delete_cached(&self, path: &str) -> Result<Response<Bytes>> {
+        self.webdav.delete_cached(path).await
+    }
+    /// Settle a conditional write of the vCard body `vcard_bytes` rejected with
+    /// `412 Precondition Failed`, according to `policy`.
+    ///
+    /// See [`WebDavClient::resolve_conflict`].
+    pub async fn resolve_conflict(
+        &self,
+        path: &str,
+        vcard_bytes: Bytes,
+        policy: &ConflictPolicy,
+    ) -> Result<ConflictResolution> {
+        self.limits.check_vcard(&vcard_bytes)?;
+        self.webdav
+            .resolve_conflict(path, "text/vcard; charset=utf-8", vcard_bytes, policy)
+            .await
+    }
+    /// Replay the `PUT`/`DELETE` mutations recorded in `queue` while offline.
+    ///
+    /// See [`WebDavClient::replay_mutations`].
+    pub async fn replay_mutations<Q: MutationQueue + ?Sized>(
+        &self,
+        queue: &Q,
+        policy: &ConflictPolicy,
+    ) -> Result<ReplayReport> {
+        self.webdav.replay_mutations(queue, policy).await
+    }
     /// Send a `PUT` with a vCard body (`text/vcard`).
     ///
     /// Use [`put_if_match`] or [`put_if_none_match`] for safer conditional writes.
     pub async fn put(&self, path: &str, vcard_bytes: Bytes) -> Result<Response<Bytes>> {
+        self.limits.check_vcard(&vcard_bytes)?;
         let mut h = HeaderMap::new();
         h.insert(
             header::CONTENT_TYPE,
@@ -238,6 +398,7 @@ impl CardDavClient {
         if etag.is_empty() {
             return Err(anyhow!("ETag cannot be empty"));
         }
+        self.limits.check_vcard(&vcard_bytes)?;
 
         let mut h = HeaderMap::new();
         h.insert(
@@ -256,6 +417,7 @@ impl CardDavClient {
         path: &str,
         vcard_bytes: Bytes,
     ) -> Result<Response<Bytes>> {
+        self.limits.check_vcard(&vcard_bytes)?;
         let mut h = HeaderMap::new();
         h.insert(
             header::CONTENT_TYPE,
@@ -265,6 +427,24 @@ impl CardDavClient {
         self.send(Method::PUT, path, h, Some(vcard_bytes), None)
             .await
     }
+    /// `PUT` submitting an [`IfHeader`], e.g. the lock token of a locked resource combined
+    /// with its expected ETag.
+    pub async fn put_if(
+        &self,
+        path: &str,
+        vcard_bytes: Bytes,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        self.limits.check_vcard(&vcard_bytes)?;
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("text/vcard; charset=utf-8"),
+        );
+        h.insert("If", header::HeaderValue::from_str(&if_header.build()?)?);
+        self.send(Method::PUT, path, h, Some(vcard_bytes), None)
+            .await
+    }
     /// Send a `DELETE` request.
     ///
     /// Prefer [`delete_if_match`] when you want to ensure you delete the expected version.
@@ -303,6 +483,62 @@ impl CardDavClient {
             .r#move(src_path, dest_absolute_url, overwrite)
             .await
     }
+    /// `COPY` submitting an [`IfHeader`].
+    ///
+    /// See [`WebDavClient::copy_if`].
+    pub async fn copy_if(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        self.webdav
+            .copy_if(src_path, dest_absolute_url, overwrite, if_header)
+            .await
+    }
+    /// `MOVE` submitting an [`IfHeader`].
+    ///
+    /// See [`WebDavClient::move_if`].
+    pub async fn move_if(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        self.webdav
+            .move_if(src_path, dest_absolute_url, overwrite, if_header)
+            .await
+    }
+    /// `DELETE` submitting an [`IfHeader`].
+    pub async fn delete_if(&self, path: &str, if_header: &IfHeader) -> Result<Response<Bytes>> {
+        self.webdav.delete_if(path, if_header).await
+    }
+    /// `DELETE` a collection tree, retrying the members the server could not delete.
+    ///
+    /// See [`WebDavClient::delete_recursive`].
+    pub async fn delete_recursive(
+        &self,
+        path: &str,
+        max_retries: usize,
+    ) -> Result<RecursiveReport> {
+        self.webdav.delete_recursive(path, max_retries).await
+    }
+    /// `COPY` a collection tree, retrying the members the server could not copy.
+    ///
+    /// See [`WebDavClient::copy_recursive`].
+    pub async fn copy_recursive(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        max_retries: usize,
+    ) -> Result<RecursiveReport> {
+        self.webdav
+            .copy_recursive(src_path, dest_absolute_url, overwrite, max_retries)
+            .await
+    }
 
     /// Send a WebDAV `PROPFIND` with a custom XML body and `Depth` header.
     ///
@@ -316,6 +552,41 @@ impl CardDavClient {
     ) -> Result<Response<Bytes>> {
         self.webdav.propfind(path, depth, xml_body).await
     }
+    /// `PROPFIND` with `<D:allprop/>`, also requesting the `include` properties.
+    ///
+    /// See [`WebDavClient::propfind_allprop`].
+    pub async fn propfind_allprop(
+        &self,
+        path: &str,
+        depth: Depth,
+        include: &[PropertyName],
+    ) -> Result<Response<Bytes>> {
+        self.webdav.propfind_allprop(path, depth, include).await
+    }
+    /// List the names of the properties exposed by `path`.
+    ///
+    /// See [`WebDavClient::propfind_propname`].
+    pub async fn propfind_propname(&self, path: &str, depth: Depth) -> Result<Vec<PropNames>> {
+        self.webdav.propfind_propname(path, depth).await
+    }
+    /// `PROPFIND` the properties of `registry`, including vendor properties.
+    ///
+    /// See [`WebDavClient::propfind_properties`].
+    pub async fn propfind_properties(
+        &self,
+        path: &str,
+        depth: Depth,
+        registry: &PropertyRegistry,
+    ) -> Result<Vec<ResourceProps>> {
+        self.webdav.propfind_properties(path, depth, registry).await
+    }
+    /// `PROPFIND` the whole tree below `path`, falling back to a `Depth: 1` crawl when the
+    /// server forbids `Depth: infinity`.
+    ///
+    /// See [`WebDavClient::propfind_infinity`].
+    pub async fn propfind_infinity(&self, path: &str, xml_body: &str) -> Result<Vec<Bytes>> {
+        self.webdav.propfind_infinity(path, xml_body).await
+    }
     /// Send a WebDAV `PROPPATCH` with a custom XML body.
     pub async fn proppatch(&self, path: &str, xml_body: &str) -> Result<Response<Bytes>> {
         self.webdav.proppatch(path, xml_body).await
@@ -368,10 +639,94 @@ impl CardDavClient {
 
         Ok(resp)
     }
+    /// Create an addressbook collection from a typed [`MkAddressBook`] description.
+    ///
+    /// Sends `MKADDRESSBOOK`; if the server answers `501 Not Implemented` or
+    /// `405 Method Not Allowed`, retries with the equivalent extended `MKCOL` (RFC 6352
+    /// §6.3.1).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only for transport failures; inspect the returned status for the
+    /// outcome.
+    pub async fn create_addressbook(
+        &self,
+        path: &str,
+        addressbook: &MkAddressBook,
+    ) -> Result<Response<Bytes>> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/xml; charset=utf-8"),
+        );
+        let resp = self
+            .send(
+                Method::from_bytes(b"MKADDRESSBOOK")?,
+                path,
+                h.clone(),
+                Some(Bytes::from(addressbook.build())),
+                None,
+            )
+            .await?;
+        if resp.status() == StatusCode::NOT_IMPLEMENTED
+            || resp.status() == StatusCode::METHOD_NOT_ALLOWED
+        {
+            return self
+                .send(
+                    Method::from_bytes(b"MKCOL")?,
+                    path,
+                    h,
+                    Some(Bytes::from(addressbook.build_extended_mkcol())),
+                    None,
+                )
+                .await;
+        }
+        Ok(resp)
+    }
+
+    /// Set and remove arbitrary properties of `path` with a [`PropPatch`].
+    ///
+    /// See [`WebDavClient::proppatch_properties`].
+    pub async fn proppatch_properties(
+        &self,
+        path: &str,
+        patch: &PropPatch,
+    ) -> Result<PropPatchResult> {
+        self.webdav.proppatch_properties(path, patch).await
+    }
+
+    /// Rename or describe an addressbook with a typed [`AddressBookPropPatch`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if the patch is empty, and afterwards if
+    /// the request fails, the server responds with a non-success status, or the
+    /// `207 Multi-Status` body cannot be parsed. Rejected properties are not an error; check
+    /// [`PropPatchResult::failed`].
+    pub async fn proppatch_addressbook(
+        &self,
+        path: &str,
+        patch: &AddressBookPropPatch,
+    ) -> Result<PropPatchResult> {
+        if patch.is_empty() {
+            return Err(anyhow!("PROPPATCH requires at least one property"));
+        }
+        let resp = self.proppatch(path, &patch.build()).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("PROPPATCH failed with {}", resp.status()));
+        }
+        parse_proppatch_response(resp.body())
+    }
     /// Send a WebDAV `MKCOL` to create a generic collection. Some servers accept an optional XML body.
     pub async fn mkcol(&self, path: &str, xml_body: Option<&str>) -> Result<Response<Bytes>> {
         self.webdav.mkcol(path, xml_body).await
     }
+    /// Create a collection with an extended `MKCOL` (RFC 5689).
+    ///
+    /// See [`WebDavClient::mkcol_extended`].
+    pub async fn mkcol_extended(&self, path: &str, mkcol: &ExtendedMkcol) -> Result<()> {
+        self.webdav.mkcol_extended(path, mkcol).await
+    }
 
     /// Discover the current user's principal URL via `current-user-principal`.
     ///
@@ -393,7 +748,7 @@ impl CardDavClient {
         }
         let body = resp.into_body();
         let mut principal = None;
-        for item in parse_multistatus_bytes(&body)?.items {
+        for item in self.parse_multistatus(&body)?.items {
             if let Some(found) = item
                 .current_user_principal
                 .into_iter()
@@ -424,7 +779,7 @@ impl CardDavClient {
         }
         let body = resp.into_body();
         let mut homes = Vec::new();
-        for mut item in parse_multistatus_bytes(&body)?.items {
+        for mut item in self.parse_multistatus(&body)?.items {
             homes.append(&mut item.addressbook_home_set);
         }
         homes.sort();
@@ -432,10 +787,121 @@ impl CardDavClient {
         Ok(homes)
     }
 
+    /// Discover the principal's own vCard and the directory gateways (RFC 6352 §7.1.2,
+    /// `CARDDAV:directory-gateway`) it can search.
+    ///
+    /// Directories are usually large, read-only addressbooks backed by LDAP; query them with
+    /// [`directory_search`](Self::directory_search) instead of listing them.
+    pub async fn discover_directories(&self, principal_path: &str) -> Result<DirectoryInfo> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:prop>
+    <C:principal-address/>
+    <C:directory-gateway/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(principal_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND directory-gateway failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        let mut info = DirectoryInfo::default();
+        for mut item in self.parse_multistatus(&body)?.items {
+            info.principal_address = info.principal_address.or(item.principal_address);
+            info.gateways.append(&mut item.directory_gateway);
+        }
+        info.gateways.sort();
+        info.gateways.dedup();
+        Ok(info)
+    }
+
+    /// Read the upload size limits of an addressbook: `CARDDAV:max-resource-size` and, where
+    /// the server exposes it, `max-image-size`. Pass them to
+    /// [`set_resource_limits`](Self::set_resource_limits) to check payloads before upload.
+    pub async fn resource_limits(&self, addressbook_path: &str) -> Result<ResourceLimits> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav" xmlns:CS="http://calendarserver.org/ns/">
+  <D:prop>
+    <C:max-resource-size/>
+    <CS:max-image-size/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(addressbook_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND max-resource-size failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        let mut limits = ResourceLimits::default();
+        for item in self.parse_multistatus(&body)?.items {
+            limits.max_resource_size = limits.max_resource_size.or(item.max_resource_size);
+            limits.max_image_size = limits.max_image_size.or(item.max_image_size);
+        }
+        Ok(limits)
+    }
+
+    /// Search a directory gateway with an `addressbook-query` returning at most `limit` cards.
+    ///
+    /// Directories cannot be listed page by page: when more cards match, the server returns
+    /// the first `limit` and flags the rest with `507 Insufficient Storage`, reported as
+    /// [`DirectorySearch::truncated`]. Servers that refuse the whole query with a `507`, or
+    /// ignore the limit, are reported the same way. Narrow the filter (e.g. a longer `FN`
+    /// prefix) to see more.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `limit` is zero or a filter or
+    /// `address-data` name is invalid, and afterwards if the REPORT fails or the server
+    /// responds with another non-success status.
+    pub async fn directory_search(
+        &self,
+        directory_path: &str,
+        filter: &AddressbookFilter,
+        data: Option<&AddressData>,
+        limit: u32,
+    ) -> Result<DirectorySearch> {
+        if limit == 0 {
+            return Err(anyhow!("directory search limit must be at least 1"));
+        }
+        filter.validate()?;
+        if let Some(data) = data {
+            data.validate()?;
+        }
+        let mut filter_xml = filter.to_xml();
+        filter_xml.push_str(&limit_xml(limit));
+        let xml = build_addressbook_query_body_with(&filter_xml, data);
+
+        let resp = self.report(directory_path, Depth::One, &xml).await?;
+        if resp.status() == StatusCode::INSUFFICIENT_STORAGE {
+            return Ok(DirectorySearch {
+                objects: Vec::new(),
+                truncated: true,
+            });
+        }
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "REPORT addressbook-query failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(map_directory_search(
+            self.parse_multistatus(&body)?.items,
+            limit,
+        ))
+    }
+
     /// List CardDAV collections under an addressbook home-set (`Depth: 1` PROPFIND).
     pub async fn list_addressbooks(&self, home_set_path: &str) -> Result<Vec<AddressBookInfo>> {
         let body = r#"
-<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav" xmlns:A="http://apple.com/ns/ical/">
+<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav" xmlns:A="http://apple.com/ns/ical/" xmlns:CS="http://calendarserver.org/ns/">
   <D:prop>
     <D:displayname/>
     <C:addressbook-description/>
@@ -443,6 +909,7 @@ impl CardDavClient {
     <A:addressbook-color/>
     <C:supported-address-data/>
     <D:getetag/>
+    <CS:getctag/>
     <D:resourcetype/>
     <D:sync-token/>
   </D:prop>
@@ -456,7 +923,47 @@ impl CardDavClient {
             ));
         }
         let body = resp.into_body();
-        Ok(map_addressbook_list(parse_multistatus_bytes(&body)?.items))
+        Ok(map_addressbook_list(self.parse_multistatus(&body)?.items))
+    }
+
+    /// Read the `CS:getctag` of an addressbook: an opaque token the server changes whenever
+    /// a card in the collection is added, changed or removed.
+    ///
+    /// Servers without `sync-collection` (see
+    /// [`supports_webdav_sync`](Self::supports_webdav_sync)) often still expose it, so
+    /// pollers can skip unchanged addressbooks. `None` when the server does not.
+    pub async fn get_ctag(&self, addressbook_path: &str) -> Result<Option<String>> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:" xmlns:CS="http://calendarserver.org/ns/">
+  <D:prop>
+    <CS:getctag/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(addressbook_path, Depth::Zero, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("PROPFIND getctag failed with {}", resp.status()));
+        }
+        let body = resp.into_body();
+        Ok(self
+            .parse_multistatus(&body)?
+            .items
+            .into_iter()
+            .find_map(|item| item.ctag))
+    }
+
+    /// Whether the addressbook changed since `known_ctag` was read with
+    /// [`get_ctag`](Self::get_ctag) or [`list_addressbooks`](Self::list_addressbooks).
+    ///
+    /// A server that no longer reports a ctag is treated as changed, so callers fall back
+    /// to a full comparison rather than miss updates.
+    pub async fn addressbook_changed(
+        &self,
+        addressbook_path: &str,
+        known_ctag: &str,
+    ) -> Result<bool> {
+        let current = self.get_ctag(addressbook_path).await?;
+        Ok(current.as_deref() != Some(known_ctag))
     }
 
     /// Execute a CardDAV `addressbook-query` with a custom filter.
@@ -476,7 +983,7 @@ impl CardDavClient {
             ));
         }
         let body = resp.into_body();
-        Ok(map_address_objects(parse_multistatus_bytes(&body)?.items))
+        Ok(map_address_objects(self.parse_multistatus(&body)?.items))
     }
 
     /// Addressbook query helper: match a specific `UID`.
@@ -515,6 +1022,66 @@ impl CardDavClient {
             .await
     }
 
+    /// Addressbook query helper: find cards with a `TEL` matching `number`, whatever the
+    /// formatting on either side.
+    ///
+    /// The server is asked for cards whose `TEL` contains one of the
+    /// [`phone_variants`](crate::carddav::phone_variants) of the number (including its last
+    /// two digits, which survive any grouping), and the candidates are checked locally with
+    /// [`phone_numbers_match`](crate::carddav::phone_numbers_match). Address data is always
+    /// fetched for that check and dropped again unless `include_data` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `number` has no digits, and afterwards
+    /// if the REPORT fails or the server responds with a non-success status.
+    pub async fn addressbook_query_phone(
+        &self,
+        addressbook_path: &str,
+        number: &str,
+        include_data: bool,
+    ) -> Result<Vec<AddressObject>> {
+        let filter =
+            phone_filter(number).ok_or_else(|| anyhow!("phone number {number:?} has no digits"))?;
+        let mut objects = self
+            .addressbook_query(addressbook_path, &filter.to_xml(), true)
+            .await?;
+        objects.retain(|object| {
+            object
+                .address_data
+                .as_deref()
+                .is_some_and(|card| card_has_phone(card, number))
+        });
+        if !include_data {
+            for object in &mut objects {
+                object.address_data = None;
+            }
+        }
+        Ok(objects)
+    }
+
+    /// Addressbook query helper: match `prop` with custom text-match options, e.g. emails
+    /// ending with a domain. See [`build_addressbook_query_filter_text_match`].
+    pub async fn addressbook_query_text_match(
+        &self,
+        addressbook_path: &str,
+        prop: &str,
+        text_match: &TextMatch,
+        include_data: bool,
+    ) -> Result<Vec<AddressObject>> {
+        let filter = build_addressbook_query_filter_text_match(prop, text_match);
+        self.addressbook_query(addressbook_path, &filter, include_data)
+            .await
+    }
+
+    /// Start a fluent `addressbook-query` against `addressbook_path`.
+    ///
+    /// See [`AddressbookQuery`] for the available conditions and options.
+    #[cfg(feature = "query-builder")]
+    pub fn query(&self, addressbook_path: &str) -> AddressbookQuery<'_> {
+        AddressbookQuery::new(self, addressbook_path)
+    }
+
     /// Fetch specific address objects via `addressbook-multiget`.
     pub async fn addressbook_multiget<I, S>(
         &self,
@@ -526,7 +1093,27 @@ impl CardDavClient {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let Some(body) = build_addressbook_multiget_body(hrefs, include_data) else {
+        let data = include_data.then(AddressData::default);
+        self.addressbook_multiget_with(addressbook_path, hrefs, data.as_ref())
+            .await
+    }
+
+    /// Fetch specific address objects via `addressbook-multiget`, with control over the
+    /// returned `address-data` (e.g. only `FN` and `EMAIL` for a list view).
+    pub async fn addressbook_multiget_with<I, S>(
+        &self,
+        addressbook_path: &str,
+        hrefs: I,
+        data: Option<&AddressData>,
+    ) -> Result<Vec<AddressObject>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if let Some(data) = data {
+            data.validate()?;
+        }
+        let Some(body) = build_addressbook_multiget_body_with(hrefs, data) else {
             return Ok(Vec::new());
         };
 
@@ -538,7 +1125,217 @@ impl CardDavClient {
             ));
         }
         let body = resp.into_body();
-        Ok(map_address_objects(parse_multistatus_bytes(&body)?.items))
+        Ok(map_address_objects(self.parse_multistatus(&body)?.items))
+    }
+
+    /// Export the addressbook at `addressbook_path` as one concatenated VCF, streamed as
+    /// bytes suitable for writing to a `.vcf` backup.
+    ///
+    /// Card hrefs are listed with a `Depth: 1` `PROPFIND`, then fetched with chunked
+    /// `addressbook-multiget`s of [`DEFAULT_MULTIGET_CHUNK_SIZE`] hrefs, at most
+    /// [`DEFAULT_MULTIGET_CONCURRENCY`] at a time. Cards are written as the server returns
+    /// them; use [`export_addressbook_as`](Self::export_addressbook_as) to normalize their
+    /// version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listing `PROPFIND` fails. Errors of later `REPORT`s are
+    /// yielded by the stream; the output is incomplete after the first error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fast_dav_rs::CardDavClient;
+    /// use futures::StreamExt;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// # async fn run(client: CardDavClient) -> anyhow::Result<()> {
+    /// let mut file = tokio::fs::File::create("contacts.vcf").await?;
+    /// let mut export = Box::pin(client.export_addressbook("/addressbooks/jane/contacts/").await?);
+    /// while let Some(chunk) = export.next().await {
+    ///     file.write_all(&chunk?).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_addressbook(
+        &self,
+        addressbook_path: &str,
+    ) -> Result<impl Stream<Item = Result<Bytes>> + '_> {
+        self.export_cards(addressbook_path, None).await
+    }
+
+    /// Like [`export_addressbook`](Self::export_addressbook), converting every card to
+    /// `version` (see [`vcard_to_v3`](crate::carddav::vcard_to_v3) and
+    /// [`vcard_to_v4`](crate::carddav::vcard_to_v4)), e.g. for importers that only read
+    /// vCard 3.0.
+    ///
+    /// # Errors
+    ///
+    /// As for [`export_addressbook`](Self::export_addressbook); a card that cannot be
+    /// converted is yielded as an error.
+    #[cfg(feature = "vcard")]
+    pub async fn export_addressbook_as(
+        &self,
+        addressbook_path: &str,
+        version: VCardVersion,
+    ) -> Result<impl Stream<Item = Result<Bytes>> + '_> {
+        let convert: fn(&str) -> Result<String> = match version {
+            VCardVersion::V3 => vcard_to_v3,
+            VCardVersion::V4 => vcard_to_v4,
+        };
+        self.export_cards(addressbook_path, Some(convert)).await
+    }
+
+    async fn export_cards(
+        &self,
+        addressbook_path: &str,
+        convert: Option<fn(&str) -> Result<String>>,
+    ) -> Result<impl Stream<Item = Result<Bytes>> + '_> {
+        let body = r#"
+<D:propfind xmlns:D="DAV:">
+  <D:prop>
+    <D:resourcetype/>
+    <D:getetag/>
+  </D:prop>
+</D:propfind>
+"#;
+        let resp = self.propfind(addressbook_path, Depth::One, body).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "PROPFIND address objects failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        let hrefs: Vec<String> = self
+            .parse_multistatus(&body)?
+            .items
+            .into_iter()
+            .filter(|item| !item.is_collection)
+            .map(|item| item.href)
+            .collect();
+
+        let addressbook_path: Arc<str> = Arc::from(addressbook_path);
+        let bodies: Vec<String> = hrefs
+            .chunks(DEFAULT_MULTIGET_CHUNK_SIZE)
+            .filter_map(|chunk| build_addressbook_multiget_body(chunk, true))
+            .collect();
+
+        Ok(stream::iter(bodies)
+            .map(move |body| {
+                let addressbook_path = Arc::clone(&addressbook_path);
+                async move {
+                    let resp = self.report(&addressbook_path, Depth::One, &body).await?;
+                    if !resp.status().is_success() {
+                        return Err(anyhow!(
+                            "REPORT addressbook-multiget failed with {}",
+                            resp.status()
+                        ));
+                    }
+                    let body = resp.into_body();
+                    let mut out = String::new();
+                    for data in map_address_objects(self.parse_multistatus(&body)?.items)
+                        .into_iter()
+                        .filter_map(|o| o.address_data)
+                    {
+                        let card = match convert {
+                            Some(convert) => convert(&data)?,
+                            None => data,
+                        };
+                        out.push_str(&card);
+                        if !card.ends_with('\n') {
+                            out.push_str("\r\n");
+                        }
+                    }
+                    Ok(Bytes::from(out))
+                }
+            })
+            .buffered(DEFAULT_MULTIGET_CONCURRENCY))
+    }
+    /// Hrefs of cards in `addressbook_path` that `contact` would duplicate, found with one
+    /// `addressbook-query` on its `UID` and exact `EMAIL`/`TEL` values (see
+    /// [`duplicate_filter`]).
+    ///
+    /// Run it before creating a card so an import can merge into an existing one instead.
+    /// A contact with none of these properties has no candidates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the REPORT fails or the server responds with a non-success status.
+    #[cfg(feature = "vcard")]
+    pub async fn find_duplicates(
+        &self,
+        addressbook_path: &str,
+        contact: &Contact,
+    ) -> Result<Vec<String>> {
+        let Some(filter) = duplicate_filter(contact) else {
+            return Ok(Vec::new());
+        };
+        filter.validate()?;
+        let mut hrefs: Vec<String> = self
+            .addressbook_query(addressbook_path, &filter.to_xml(), false)
+            .await?
+            .into_iter()
+            .map(|object| object.href)
+            .collect();
+        hrefs.sort();
+        hrefs.dedup();
+        Ok(hrefs)
+    }
+
+    /// Import a multi-card VCF into the addressbook at `addressbook_path`.
+    ///
+    /// The file is split with [`split_vcards`], each card is stored at
+    /// [`href_for_uid`] with [`put_if_none_match`](Self::put_if_none_match) so existing
+    /// cards are never overwritten, and at most `max_concurrency` uploads run at a time.
+    /// Results are returned in file order, one per card: a `412 Precondition Failed`, a
+    /// `CARDDAV:no-uid-conflict` error or a `UID` repeated in the file is reported as
+    /// [`ImportOutcome::Duplicate`], and cards without a `UID` or rejected by the server as
+    /// [`ImportOutcome::Failed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if the file cannot be split into cards.
+    /// Failures of single uploads are reported in their [`ImportResult`].
+    pub async fn import_vcards(
+        &self,
+        addressbook_path: &str,
+        vcf: &str,
+        max_concurrency: usize,
+    ) -> Result<Vec<ImportResult>> {
+        let mut seen = HashSet::new();
+        let uploads = split_vcards(vcf)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, card)| {
+                let uid = card_uid(&card);
+                let href = uid
+                    .as_deref()
+                    .map(|uid| href_for_uid(addressbook_path, uid));
+                let fresh = href.as_ref().is_some_and(|href| seen.insert(href.clone()));
+                async move {
+                    let outcome = match &href {
+                        None => ImportOutcome::Failed("card has no UID".to_string()),
+                        Some(_) if !fresh => ImportOutcome::Duplicate,
+                        Some(href) => match self.put_if_none_match(href, Bytes::from(card)).await {
+                            Ok(resp) => import_outcome(&resp),
+                            Err(e) => ImportOutcome::Failed(e.to_string()),
+                        },
+                    };
+                    ImportResult {
+                        index,
+                        uid,
+                        href,
+                        outcome,
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(stream::iter(uploads)
+            .buffered(max_concurrency.max(1))
+            .collect()
+            .await)
     }
 
     /// Incrementally synchronise an addressbook collection using `sync-collection`.
@@ -553,18 +1350,181 @@ impl CardDavClient {
 
         let resp = self.report(addressbook_path, Depth::One, &body).await?;
         if !resp.status().is_success() {
-            return Err(anyhow!(
-                "REPORT sync-collection failed with {}",
-                resp.status()
+            return Err(sync_collection_error(
+                sync_token,
+                resp.status(),
+                resp.body(),
             ));
         }
         let headers = resp.headers().clone();
         let body = resp.into_body();
 
-        let parsed = parse_multistatus_bytes(&body)?;
+        let mut parsed = self.parse_multistatus(&body)?;
+        let base = self.build_uri(addressbook_path)?;
+        for item in &mut parsed.items {
+            item.href = resolve_href(base.path(), &item.href);
+        }
         Ok(map_sync_response(&headers, parsed.items, parsed.sync_token))
     }
 
+    /// [`sync_collection`](Self::sync_collection) following truncated responses: while the
+    /// server reports more changes than it returned ([`SyncResponse::truncated`]), sync again
+    /// from the new token, up to the final token.
+    ///
+    /// `limit` applies to each round. Changes are merged by href, the latest round winning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a round fails, or a truncated response does not advance the sync
+    /// token.
+    pub async fn sync_collection_all(
+        &self,
+        addressbook_path: &str,
+        sync_token: Option<&str>,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> Result<SyncResponse> {
+        let mut response = self
+            .sync_collection(addressbook_path, sync_token, limit, include_data)
+            .await?;
+        let mut positions: HashMap<String, usize> = response
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (item.href.clone(), i))
+            .collect();
+        let mut previous = sync_token.map(str::to_string);
+        while response.truncated {
+            let token = response
+                .sync_token
+                .take()
+                .filter(|token| previous.as_ref() != Some(token))
+                .ok_or_else(|| {
+                    anyhow!("truncated sync-collection did not advance the sync token")
+                })?;
+            let next = self
+                .sync_collection(addressbook_path, Some(&token), limit, include_data)
+                .await?;
+            for item in next.items {
+                match positions.get(&item.href) {
+                    Some(&i) => response.items[i] = item,
+                    None => {
+                        positions.insert(item.href.clone(), response.items.len());
+                        response.items.push(item);
+                    }
+                }
+            }
+            response.sync_token = next.sync_token;
+            response.truncated = next.truncated;
+            previous = Some(token);
+        }
+        Ok(response)
+    }
+
+    /// [`sync_collection`](Self::sync_collection) resuming from the token `store` holds for
+    /// `addressbook_path`, and saving the new token once the response is parsed.
+    ///
+    /// The first call (no stored token) performs a full sync.
+    pub async fn sync_collection_with_store<S: TokenStore + ?Sized>(
+        &self,
+        addressbook_path: &str,
+        store: &S,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> Result<SyncResponse> {
+        let token = store.sync_token(addressbook_path).await?;
+        let response = self
+            .sync_collection(addressbook_path, token.as_deref(), limit, include_data)
+            .await?;
+        if response.sync_token.is_some() {
+            store
+                .set_sync_token(addressbook_path, response.sync_token.clone())
+                .await?;
+        }
+        Ok(response)
+    }
+
+    /// Watch the addressbook at `addressbook_path`, polling every `interval` with `sync-collection`
+    /// or, on servers without it, `getctag` and ETag listings.
+    ///
+    /// State is kept in memory, so the first poll reports every existing member as
+    /// created. See [`SyncEngine::watch`]; build the engine yourself to persist the state
+    /// or receive the data.
+    pub fn watch(&self, addressbook_path: &str, interval: Duration) -> ChangeStream {
+        SyncEngine::new(self.clone(), Arc::new(MemoryTokenStore::new()))
+            .include_data(false)
+            .watch(addressbook_path, interval)
+    }
+
+    /// Streaming variant of [`sync_collection`](Self::sync_collection): changes are parsed
+    /// and yielded one by one while the response is still arriving.
+    ///
+    /// Read the stream to the end, then take the new token from
+    /// [`SyncItemStream::sync_token`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use fast_dav_rs::CardDavClient;
+    /// use futures::TryStreamExt;
+    ///
+    /// # async fn demo(cli: &CardDavClient, token: Option<&str>) -> anyhow::Result<()> {
+    /// let mut changes = cli
+    ///     .sync_collection_stream("addressbooks/jane/contacts/", token, None, true)
+    ///     .await?;
+    /// while let Some(item) = changes.try_next().await? {
+    ///     println!("{} deleted={}", item.href, item.is_deleted);
+    /// }
+    /// let next_token = changes.sync_token();
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the REPORT fails or the server responds with a non-success status;
+    /// parse errors and idle timeouts ([`STREAM_READ_IDLE_TIMEOUT`]) surface as stream items.
+    pub async fn sync_collection_stream(
+        &self,
+        addressbook_path: &str,
+        sync_token: Option<&str>,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> Result<SyncItemStream> {
+        let body = build_sync_collection_body(sync_token, limit, include_data);
+
+        let resp = self
+            .report_stream(addressbook_path, Depth::One, &body)
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp
+                .into_body()
+                .collect()
+                .await
+                .map(|body| body.to_bytes())
+                .unwrap_or_default();
+            return Err(sync_collection_error(sync_token, status, &body));
+        }
+        let header_token = resp
+            .headers()
+            .get("Sync-Token")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let encodings = detect_encodings(resp.headers());
+        let reader = decoded_reader(
+            resp.into_body(),
+            &encodings,
+            self.webdav.decompression_options(),
+        );
+        Ok(SyncItemStream::with_limits(
+            reader,
+            header_token,
+            STREAM_READ_IDLE_TIMEOUT,
+            self.parse_limits,
+        ))
+    }
+
     // ----------- ETag helpers -----------
 
     /// Extract the `ETag` from a response header map, if present.
@@ -604,6 +1564,62 @@ impl CardDavClient {
             .await
     }
 
+    /// Fetch the contact at `href` and locate its `PHOTO`.
+    ///
+    /// Inline photos are decoded lazily from the response body, see [`PhotoSource`].
+    /// Returns `Ok(None)` when the contact has no photo.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `GET` fails or the `PHOTO` property is malformed.
+    #[cfg(feature = "vcard")]
+    pub async fn get_photo(&self, href: &str) -> Result<Option<PhotoSource>> {
+        let resp = self.get(href).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("GET {href} failed with {}", resp.status()));
+        }
+        photo_stream(resp.into_body())
+    }
+
+    /// Set or replace the photo of the contact at `href`.
+    ///
+    /// The contact is fetched, the image is base64-encoded in the style of the card's vCard
+    /// version (see [`replace_photo`]) and the result is written back with `If-Match` on the
+    /// `ETag` the server returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if `image` is larger than `max_bytes` or
+    /// the configured [`ResourceLimits`], and afterwards if the `GET` fails or the fetched
+    /// data is not a vCard. A concurrent change
+    /// shows up as a `412 Precondition Failed` response.
+    #[cfg(feature = "vcard")]
+    pub async fn set_photo(
+        &self,
+        href: &str,
+        media_type: &str,
+        image: Bytes,
+        max_bytes: usize,
+    ) -> Result<Response<Bytes>> {
+        if image.len() > max_bytes {
+            return Err(anyhow!(
+                "photo is {} bytes, more than the {max_bytes} allowed",
+                image.len()
+            ));
+        }
+        self.limits.check_image(&image)?;
+        let resp = self.get(href).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("GET {href} failed with {}", resp.status()));
+        }
+        let etag = Self::etag_from_headers(resp.headers());
+        let body = replace_photo(resp.body(), media_type, &image)?;
+        match etag {
+            Some(etag) => self.put_if_match(href, body, &etag).await,
+            None => self.put(href, body).await,
+        }
+    }
+
     // ----------- Public streaming helpers -----------
 
     /// Check if the server supports WebDAV-Sync (RFC 6578).
@@ -636,6 +1652,141 @@ impl CardDavClient {
         self.webdav.supports_webdav_sync().await
     }
 
+    /// Discover the push transports offered for the collection at `path`.
+    ///
+    /// See [`WebDavClient::discover_push`].
+    pub async fn discover_push(&self, path: &str) -> Result<PushInfo> {
+        self.webdav.discover_push(path).await
+    }
+
+    /// Register (or renew) a WebDAV-Push subscription on the collection at `path`.
+    ///
+    /// See [`WebDavClient::register_push`].
+    pub async fn register_push(
+        &self,
+        path: &str,
+        subscription: &WebPushSubscription,
+    ) -> Result<PushRegistration> {
+        self.webdav.register_push(path, subscription).await
+    }
+
+    /// Remove a push subscription by its registration URL.
+    pub async fn unregister_push(&self, registration_url: &str) -> Result<()> {
+        self.webdav.unregister_push(registration_url).await
+    }
+
+    /// Read the access control list of `path`.
+    ///
+    /// See [`WebDavClient::get_acl`].
+    pub async fn get_acl(&self, path: &str) -> Result<Vec<Ace>> {
+        self.webdav.get_acl(path).await
+    }
+
+    /// Replace the access control list of `path` with `aces`.
+    ///
+    /// See [`WebDavClient::acl`].
+    pub async fn acl(&self, path: &str, aces: &[Ace]) -> Result<()> {
+        self.webdav.acl(path, aces).await
+    }
+
+    /// Discover the collections holding the server's principals.
+    ///
+    /// See [`WebDavClient::principal_collection_set`].
+    pub async fn principal_collection_set(&self, path: &str) -> Result<Vec<String>> {
+        self.webdav.principal_collection_set(path).await
+    }
+
+    /// List the principals inside the principal collection at `collection_path`.
+    ///
+    /// See [`WebDavClient::list_principals`].
+    pub async fn list_principals(&self, collection_path: &str) -> Result<Vec<PrincipalInfo>> {
+        self.webdav.list_principals(collection_path).await
+    }
+
+    /// List the principals referenced by the ACL of `path`.
+    ///
+    /// See [`WebDavClient::acl_principal_prop_set`].
+    pub async fn acl_principal_prop_set(&self, path: &str) -> Result<Vec<PrincipalInfo>> {
+        self.webdav.acl_principal_prop_set(path).await
+    }
+
+    /// List the versions of the card at `path` (its history).
+    ///
+    /// See [`WebDavClient::version_tree`].
+    pub async fn version_tree(&self, path: &str) -> Result<Vec<VersionEntry>> {
+        self.webdav.version_tree(path).await
+    }
+
+    /// List the versions of the card at `path` through its version history.
+    ///
+    /// See [`WebDavClient::version_history`].
+    pub async fn version_history(&self, path: &str) -> Result<Vec<VersionEntry>> {
+        self.webdav.version_history(path).await
+    }
+
+    /// Make the resource at `href` also reachable as `segment` in `collection`.
+    ///
+    /// See [`WebDavClient::bind`].
+    pub async fn bind(
+        &self,
+        collection: &str,
+        segment: &str,
+        href: &str,
+        overwrite: bool,
+    ) -> Result<()> {
+        self.webdav.bind(collection, segment, href, overwrite).await
+    }
+
+    /// Remove the binding `segment` from `collection`.
+    ///
+    /// See [`WebDavClient::unbind`].
+    pub async fn unbind(&self, collection: &str, segment: &str) -> Result<()> {
+        self.webdav.unbind(collection, segment).await
+    }
+
+    /// Move the binding at `href` to `segment` in `collection`.
+    ///
+    /// See [`WebDavClient::rebind`].
+    pub async fn rebind(
+        &self,
+        collection: &str,
+        segment: &str,
+        href: &str,
+        overwrite: bool,
+    ) -> Result<()> {
+        self.webdav
+            .rebind(collection, segment, href, overwrite)
+            .await
+    }
+
+    /// Take a write lock on `path`.
+    ///
+    /// See [`WebDavClient::lock`].
+    pub async fn lock(
+        &self,
+        path: &str,
+        scope: LockScope,
+        timeout: LockTimeout,
+        owner: Option<&str>,
+    ) -> Result<Lock> {
+        self.webdav.lock(path, scope, timeout, owner).await
+    }
+
+    /// Renew `lock` on `path` for `timeout`.
+    pub async fn refresh_lock(
+        &self,
+        path: &str,
+        lock: &Lock,
+        timeout: LockTimeout,
+    ) -> Result<Lock> {
+        self.webdav.refresh_lock(path, lock, timeout).await
+    }
+
+    /// Release `lock` on `path`.
+    pub async fn unlock(&self, path: &str, lock: &Lock) -> Result<()> {
+        self.webdav.unlock(path, lock).await
+    }
+
     /// Streaming variant of `PROPFIND`, returning the non-aggregated body.
     ///
     /// Combine with [`crate::streaming::parse_multistatus_stream`].
@@ -713,9 +1864,16 @@ fn extract_prop_inner(xml_body: &str) -> Option<String> {
 }
 
 pub fn build_addressbook_query_body(filter_xml: &str, include_data: bool) -> String {
+    let data = include_data.then(AddressData::default);
+    build_addressbook_query_body_with(filter_xml, data.as_ref())
+}
+
+/// Like [`build_addressbook_query_body`], with control over the returned `address-data`
+/// (e.g. [`AddressData::props`]). `None` only requests ETags.
+pub fn build_addressbook_query_body_with(filter_xml: &str, data: Option<&AddressData>) -> String {
     let mut prop = String::from("<D:prop><D:getetag/>");
-    if include_data {
-        prop.push_str("<C:address-data/>");
+    if let Some(data) = data {
+        prop.push_str(&data.to_xml());
     }
     prop.push_str("</D:prop>");
 
@@ -736,18 +1894,59 @@ pub fn build_addressbook_query_filter_fn(formatted_name: &str) -> String {
     build_addressbook_query_filter("FN", formatted_name)
 }
 
-fn build_addressbook_query_filter(prop: &str, value: &str) -> String {
-    let escaped = escape_xml(value);
+/// Build a `<C:filter>` with a single `prop-filter` on `prop` using the given text-match
+/// options (match type, collation, negation).
+///
+/// The `uid`/`email`/`fn` helpers above are shorthands for a case-insensitive
+/// (`i;unicode-casemap`) `equals` match.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::build_addressbook_query_filter_text_match;
+/// use fast_dav_rs::webdav::{MatchType, TextMatch};
+///
+/// let filter = build_addressbook_query_filter_text_match(
+///     "EMAIL",
+///     &TextMatch::new("@example.com").match_type(MatchType::EndsWith).negate(),
+/// );
+/// assert_eq!(
+///     filter,
+///     r#"<C:filter><C:prop-filter name="EMAIL"><C:text-match match-type="ends-with" negate-condition="yes">@example.com</C:text-match></C:prop-filter></C:filter>"#
+/// );
+/// ```
+pub fn build_addressbook_query_filter_text_match(prop: &str, text_match: &TextMatch) -> String {
     format!(
-        "<C:filter>\
-           <C:prop-filter name=\"{prop}\">\
-             <C:text-match collation=\"i;unicode-casemap\" match-type=\"equals\">{escaped}</C:text-match>\
-           </C:prop-filter>\
-         </C:filter>"
+        r#"<C:filter><C:prop-filter name="{}">{}</C:prop-filter></C:filter>"#,
+        escape_xml(prop),
+        text_match_xml("C", text_match)
+    )
+}
+
+fn build_addressbook_query_filter(prop: &str, value: &str) -> String {
+    build_addressbook_query_filter_text_match(
+        prop,
+        &TextMatch::new(value)
+            .collation("i;unicode-casemap")
+            .match_type(MatchType::Equals),
     )
 }
 
 pub fn build_addressbook_multiget_body<I, S>(hrefs: I, include_data: bool) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let data = include_data.then(AddressData::default);
+    build_addressbook_multiget_body_with(hrefs, data.as_ref())
+}
+
+/// Like [`build_addressbook_multiget_body`], with control over the returned `address-data`.
+/// `None` only requests ETags.
+pub fn build_addressbook_multiget_body_with<I, S>(
+    hrefs: I,
+    data: Option<&AddressData>,
+) -> Option<String>
 where
     I: IntoIterator<Item = S>,
     S: AsRef<str>,
@@ -771,8 +1970,8 @@ where
     let mut body = String::from(
         r#"<C:addressbook-multiget xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav"><D:prop><D:getetag/>"#,
     );
-    if include_data {
-        body.push_str("<C:address-data/>");
+    if let Some(data) = data {
+        body.push_str(&data.to_xml());
     }
     body.push_str("</D:prop>");
     body.push_str(&href_xml);
@@ -780,6 +1979,26 @@ where
     Some(body)
 }
 
+/// Outcome of an import `PUT` with `If-None-Match: *`.
+fn import_outcome(resp: &Response<Bytes>) -> ImportOutcome {
+    let status = resp.status();
+    if status.is_success() {
+        let etag = resp
+            .headers()
+            .get(header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        return ImportOutcome::Created { etag };
+    }
+    let uid_conflict = matches!(status, StatusCode::FORBIDDEN | StatusCode::CONFLICT)
+        && String::from_utf8_lossy(resp.body()).contains("no-uid-conflict");
+    if status == StatusCode::PRECONDITION_FAILED || uid_conflict {
+        ImportOutcome::Duplicate
+    } else {
+        ImportOutcome::Failed(format!("PUT failed with {status}"))
+    }
+}
+
 pub fn build_sync_collection_body(
     sync_token: Option<&str>,
     limit: Option<u32>,
@@ -809,6 +2028,7 @@ pub fn map_addressbook_list(mut items: Vec<DavItem>) -> Vec<AddressBookInfo> {
                 description,
                 color: item.addressbook_color,
                 etag: item.etag,
+                ctag: item.ctag,
                 sync_token: item.sync_token,
                 supported_address_data: item.supported_address_data,
             });
@@ -831,6 +2051,45 @@ pub fn map_address_objects(items: Vec<DavItem>) -> Vec<AddressObject> {
     out
 }
 
+/// Map a directory `addressbook-query` response, dropping the `507` entry servers use to
+/// signal truncation and keeping at most `limit` cards.
+pub fn map_directory_search(items: Vec<DavItem>, limit: u32) -> DirectorySearch {
+    let mut truncated = false;
+    let mut objects = map_address_objects(items);
+    objects.retain(|object| {
+        let limited = object.status.as_deref().and_then(http_status_code) == Some(507);
+        truncated |= limited;
+        !limited
+    });
+    if objects.len() > limit as usize {
+        objects.truncate(limit as usize);
+        truncated = true;
+    }
+    DirectorySearch { objects, truncated }
+}
+
+/// Map one `sync-collection` response; `None` for the collection itself.
+pub(crate) fn sync_item(mut item: DavItem) -> Option<SyncItem> {
+    let is_collection = item.is_collection
+        || (item.sync_token.is_some() && item.etag.is_none() && item.address_data.is_none());
+    if is_collection {
+        return None;
+    }
+    let status = item.status.clone();
+    let code = status.as_deref().and_then(http_status_code);
+    let is_deleted = matches!(code, Some(404) | Some(410));
+
+    Some(SyncItem {
+        href: normalize_href(&item.href),
+        etag: item.etag,
+        address_data: item.address_data.take(),
+        status,
+        is_deleted,
+    })
+}
+
+/// Map the responses of a `sync-collection` REPORT, with hrefs in the canonical form of
+/// [`normalize_href`] and one item per member.
 pub fn map_sync_response(
     headers: &HeaderMap,
     items: Vec<DavItem>,
@@ -843,34 +2102,37 @@ pub fn map_sync_response(
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string())
     });
-    let mut out = Vec::new();
+    let mut out: Vec<SyncItem> = Vec::new();
+    let mut positions = HashMap::new();
+    let mut truncated = false;
 
-    for mut item in items {
+    for item in items {
         // Capture per-item sync token if we don't have a top-level one (fallback)
         if item.sync_token.is_some() && sync_token.is_none() {
             sync_token = item.sync_token.clone();
         }
 
-        let is_collection = item.is_collection
-            || (item.sync_token.is_some() && item.etag.is_none() && item.address_data.is_none());
-        if is_collection {
+        if item.status.as_deref().and_then(http_status_code) == Some(507) {
+            truncated = true;
             continue;
         }
-        let status = item.status.clone();
-        let code = status.as_deref().and_then(http_status_code);
-        let is_deleted = matches!(code, Some(404) | Some(410));
-
-        out.push(SyncItem {
-            href: item.href,
-            etag: item.etag,
-            address_data: item.address_data.take(),
-            status,
-            is_deleted,
-        });
+        let Some(item) = sync_item(item) else {
+            continue;
+        };
+        // A member reported twice (e.g. under another spelling of its href) keeps its
+        // latest state.
+        match positions.entry(item.href.clone()) {
+            Entry::Occupied(position) => out[*position.get()] = item,
+            Entry::Vacant(position) => {
+                position.insert(out.len());
+                out.push(item);
+            }
+        }
     }
 
     SyncResponse {
         sync_token,
         items: out,
+        truncated,
     }
 }
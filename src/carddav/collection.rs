@@ -0,0 +1,169 @@
+//! Typed builders for creating addressbook collections (`MKADDRESSBOOK`, with an extended
+//! `MKCOL` fallback, RFC 6352 §6.3.1) and updating their properties (`PROPPATCH`).
+
+use crate::webdav::xml::escape_xml;
+
+const COLLECTION_NAMESPACES: &str = r#"xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav""#;
+
+/// Builder for `MKADDRESSBOOK` request bodies.
+///
+/// Use it with [`crate::carddav::CardDavClient::create_addressbook`], which also falls back
+/// to an extended `MKCOL` on servers that do not implement `MKADDRESSBOOK`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::MkAddressBook;
+///
+/// let body = MkAddressBook::new()
+///     .displayname("Team")
+///     .description("Shared team contacts")
+///     .address_data_type("text/vcard", "4.0")
+///     .build();
+/// assert!(body.contains("<D:displayname>Team</D:displayname>"));
+/// assert!(body.contains(r#"<C:address-data-type content-type="text/vcard" version="4.0"/>"#));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MkAddressBook {
+    props: AddressBookProps,
+    address_data_types: Vec<(String, String)>,
+}
+
+impl MkAddressBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `DAV:displayname`.
+    pub fn displayname(mut self, name: &str) -> Self {
+        self.props.displayname = Some(name.to_string());
+        self
+    }
+
+    /// `CARDDAV:addressbook-description`.
+    pub fn description(mut self, description: &str) -> Self {
+        self.props.description = Some(description.to_string());
+        self
+    }
+
+    /// Add an entry to `CARDDAV:supported-address-data`, e.g. `("text/vcard", "3.0")`.
+    ///
+    /// When unset the server picks its default (usually vCard 3.0, often 4.0 as well).
+    pub fn address_data_type(mut self, content_type: &str, version: &str) -> Self {
+        self.address_data_types
+            .push((content_type.to_string(), version.to_string()));
+        self
+    }
+
+    /// Build the `MKADDRESSBOOK` body.
+    pub fn build(&self) -> String {
+        let props = self.props_xml();
+        if props.is_empty() {
+            return format!("<C:mkaddressbook {COLLECTION_NAMESPACES}/>");
+        }
+        format!(
+            "<C:mkaddressbook {COLLECTION_NAMESPACES}><D:set><D:prop>{props}</D:prop></D:set></C:mkaddressbook>"
+        )
+    }
+
+    /// Build the equivalent extended `MKCOL` body (RFC 5689), declaring the addressbook
+    /// `resourcetype` explicitly.
+    pub fn build_extended_mkcol(&self) -> String {
+        format!(
+            "<D:mkcol {COLLECTION_NAMESPACES}><D:set><D:prop><D:resourcetype><D:collection/><C:addressbook/></D:resourcetype>{}</D:prop></D:set></D:mkcol>",
+            self.props_xml()
+        )
+    }
+
+    fn props_xml(&self) -> String {
+        let mut props = self.props.to_xml();
+        if !self.address_data_types.is_empty() {
+            props.push_str("<C:supported-address-data>");
+            for (content_type, version) in &self.address_data_types {
+                props.push_str(&format!(
+                    r#"<C:address-data-type content-type="{}" version="{}"/>"#,
+                    escape_xml(content_type),
+                    escape_xml(version)
+                ));
+            }
+            props.push_str("</C:supported-address-data>");
+        }
+        props
+    }
+}
+
+/// Builder for `PROPPATCH` bodies renaming or describing an addressbook.
+///
+/// Use it with [`crate::carddav::CardDavClient::proppatch_addressbook`], which parses the
+/// `207 Multi-Status` result into a [`PropPatchResult`](crate::webdav::PropPatchResult).
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::AddressBookPropPatch;
+///
+/// let body = AddressBookPropPatch::new().displayname("Renamed").build();
+/// assert!(body.contains("<D:set><D:prop><D:displayname>Renamed</D:displayname></D:prop></D:set>"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressBookPropPatch {
+    props: AddressBookProps,
+}
+
+impl AddressBookPropPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `DAV:displayname`.
+    pub fn displayname(mut self, name: &str) -> Self {
+        self.props.displayname = Some(name.to_string());
+        self
+    }
+
+    /// `CARDDAV:addressbook-description`.
+    pub fn description(mut self, description: &str) -> Self {
+        self.props.description = Some(description.to_string());
+        self
+    }
+
+    /// `true` when no property has been set.
+    pub fn is_empty(&self) -> bool {
+        self.props == AddressBookProps::default()
+    }
+
+    /// Build the `PROPPATCH` body.
+    pub fn build(&self) -> String {
+        format!(
+            "<D:propertyupdate {COLLECTION_NAMESPACES}><D:set><D:prop>{}</D:prop></D:set></D:propertyupdate>",
+            self.props.to_xml()
+        )
+    }
+}
+
+/// Addressbook collection properties shared by [`MkAddressBook`] and
+/// [`AddressBookPropPatch`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct AddressBookProps {
+    displayname: Option<String>,
+    description: Option<String>,
+}
+
+impl AddressBookProps {
+    fn to_xml(&self) -> String {
+        let mut props = String::new();
+        if let Some(name) = &self.displayname {
+            props.push_str(&format!(
+                "<D:displayname>{}</D:displayname>",
+                escape_xml(name)
+            ));
+        }
+        if let Some(description) = &self.description {
+            props.push_str(&format!(
+                "<C:addressbook-description>{}</C:addressbook-description>",
+                escape_xml(description)
+            ));
+        }
+        props
+    }
+}
@@ -0,0 +1,149 @@
+//! Bulk import helpers: splitting multi-card VCF files, hrefs from UIDs (see
+//! [`crate::carddav::CardDavClient::import_vcards`]) and duplicate detection (see
+//! [`crate::carddav::CardDavClient::find_duplicates`]).
+
+use anyhow::{Result, anyhow};
+
+#[cfg(feature = "vcard")]
+use crate::carddav::query::{AddressbookFilter, PropFilter};
+#[cfg(feature = "vcard")]
+use crate::carddav::vcard::Contact;
+use crate::common::contentline::{ContentLine, unfold_lines};
+#[cfg(feature = "vcard")]
+use crate::webdav::types::{MatchType, TextMatch};
+
+/// Split a VCF file holding several cards into one payload per card.
+///
+/// Cards are cut at their `BEGIN:VCARD`/`END:VCARD` lines and kept byte for byte; anything
+/// between cards is ignored. Nested cards (vCard 2.1 `AGENT`) stay inside their parent.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::split_vcards;
+///
+/// let vcf = "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:a\r\nEND:VCARD\r\n\
+///            BEGIN:VCARD\r\nVERSION:4.0\r\nUID:b\r\nEND:VCARD\r\n";
+/// let cards = split_vcards(vcf)?;
+/// assert_eq!(cards.len(), 2);
+/// assert!(cards[1].starts_with("BEGIN:VCARD\r\nVERSION:4.0\r\nUID:b"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when a card is never closed or an `END:VCARD` has no `BEGIN`.
+pub fn split_vcards(vcf: &str) -> Result<Vec<String>> {
+    let mut cards = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    let mut offset = 0usize;
+    for line in vcf.split_inclusive('\n') {
+        let name = line.trim_end().to_ascii_uppercase();
+        if name == "BEGIN:VCARD" {
+            if depth == 0 {
+                start = offset;
+            }
+            depth += 1;
+        } else if name == "END:VCARD" {
+            match depth {
+                0 => return Err(anyhow!("END:VCARD without BEGIN:VCARD")),
+                1 => cards.push(vcf[start..offset + line.len()].to_string()),
+                _ => {}
+            }
+            depth -= 1;
+        }
+        offset += line.len();
+    }
+    if depth > 0 {
+        return Err(anyhow!("BEGIN:VCARD is never closed"));
+    }
+    Ok(cards)
+}
+
+/// `UID` of a single card, if any.
+pub fn card_uid(card: &str) -> Option<String> {
+    unfold_lines(card)
+        .iter()
+        .filter_map(|line| ContentLine::parse(line).ok())
+        .find(|line| line.base_name() == "UID")
+        .map(|line| line.value.trim().to_string())
+        .filter(|uid| !uid.is_empty())
+}
+
+/// Href of the card with `uid` inside `addressbook_path`: `{addressbook_path}/{uid}.vcf`.
+///
+/// A `urn:uuid:` prefix is dropped and characters outside `A-Z a-z 0-9 - . _ ~ @` are
+/// percent-encoded, so any UID yields a single, safe path segment.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::href_for_uid;
+///
+/// assert_eq!(
+///     href_for_uid("/addressbooks/jane/contacts/", "urn:uuid:1234"),
+///     "/addressbooks/jane/contacts/1234.vcf"
+/// );
+/// assert_eq!(href_for_uid("contacts", "a b/c"), "contacts/a%20b%2Fc.vcf");
+/// ```
+pub fn href_for_uid(addressbook_path: &str, uid: &str) -> String {
+    let uid = uid.strip_prefix("urn:uuid:").unwrap_or(uid);
+    let mut href = addressbook_path.trim_end_matches('/').to_string();
+    href.push('/');
+    for byte in uid.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'@') {
+            href.push(byte as char);
+        } else {
+            href.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    href.push_str(".vcf");
+    href
+}
+
+/// Filter matching cards that would duplicate `contact`: the same `UID`, or an `EMAIL` or
+/// `TEL` equal to one of its values (EMAIL case-insensitively, TEL as written).
+///
+/// Returns `None` when the contact has none of these properties.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::{duplicate_filter, parse_vcard};
+///
+/// let contact = parse_vcard("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nEMAIL:jane@example.com\r\nEND:VCARD\r\n")?;
+/// let xml = duplicate_filter(&contact).unwrap().to_xml();
+/// assert!(xml.contains(r#"<C:prop-filter name="EMAIL" test="anyof">"#));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[cfg(feature = "vcard")]
+pub fn duplicate_filter(contact: &Contact) -> Option<AddressbookFilter> {
+    let exact = |value: &str, collation: &str| {
+        TextMatch::new(value)
+            .collation(collation)
+            .match_type(MatchType::Equals)
+    };
+    let mut filter = AddressbookFilter::new().any_of();
+    let mut empty = true;
+    if let Some(uid) = contact.uid.as_deref().filter(|uid| !uid.is_empty()) {
+        filter = filter.prop_filter(PropFilter::new("UID").text_match(exact(uid, "i;octet")));
+        empty = false;
+    }
+    for (name, values, collation) in [
+        ("EMAIL", &contact.emails, "i;unicode-casemap"),
+        ("TEL", &contact.phones, "i;octet"),
+    ] {
+        let mut prop = PropFilter::new(name).any_of();
+        let mut matched = false;
+        for value in values.iter().filter(|v| !v.value.trim().is_empty()) {
+            prop = prop.text_match(exact(value.value.trim(), collation));
+            matched = true;
+        }
+        if matched {
+            filter = filter.prop_filter(prop);
+            empty = false;
+        }
+    }
+    (!empty).then_some(filter)
+}
@@ -0,0 +1,185 @@
+//! jCard (RFC 7095, `application/vcard+json`) support.
+//!
+//! jCard documents are converted into the crate's vCard model, so address data requested as
+//! JSON (see [`crate::carddav::AddressData::json`]) parses into the same [`Contact`] as
+//! `text/vcard` data.
+
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+use crate::carddav::vcard::{Contact, parse_vcard};
+use crate::common::contentline::{Component, ContentLine, Param, escape_text};
+
+/// Parse a jCard document into a typed [`Contact`].
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::parse_jcard;
+///
+/// let contact = parse_jcard(r#"["vcard", [
+///     ["version", {}, "text", "4.0"],
+///     ["fn", {}, "text", "Jane Doe"],
+///     ["email", {"type": "work"}, "text", "jane@example.com"]
+/// ]]"#)?;
+/// assert_eq!(contact.formatted_name.as_deref(), Some("Jane Doe"));
+/// assert_eq!(contact.emails[0].types, ["work"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when `json` is not valid jCard.
+pub fn parse_jcard(json: &str) -> Result<Contact> {
+    parse_vcard(&jcard_to_vcf(json)?)
+}
+
+/// Convert a jCard document into vCard text (CRLF line endings, folded).
+///
+/// # Errors
+///
+/// Returns an error when `json` is not valid jCard.
+pub fn jcard_to_vcf(json: &str) -> Result<String> {
+    Ok(jcard_to_component(json)?.to_ics())
+}
+
+/// Convert a jCard document into a generic [`Component`].
+///
+/// # Errors
+///
+/// Returns an error when `json` is not valid jCard.
+pub fn jcard_to_component(json: &str) -> Result<Component> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| anyhow!("invalid jCard JSON: {e}"))?;
+    let [name, properties] = value
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or_else(|| anyhow!("jCard must be an array"))?
+    else {
+        return Err(anyhow!("jCard must be [\"vcard\", properties]"));
+    };
+    if !name
+        .as_str()
+        .is_some_and(|n| n.eq_ignore_ascii_case("vcard"))
+    {
+        return Err(anyhow!("jCard must start with \"vcard\""));
+    }
+    let mut card = Component::new("VCARD");
+    for p in array(properties, "properties")? {
+        card.properties.push(property(p)?);
+    }
+    Ok(card)
+}
+
+fn property(value: &Value) -> Result<ContentLine> {
+    let items = array(value, "property")?;
+    let [name, params, kind, values @ ..] = items.as_slice() else {
+        return Err(anyhow!(
+            "jCard property must be [name, parameters, type, value...]"
+        ));
+    };
+    let name = name
+        .as_str()
+        .ok_or_else(|| anyhow!("jCard property name must be a string"))?;
+    let kind = kind
+        .as_str()
+        .ok_or_else(|| anyhow!("jCard type of {name} must be a string"))?
+        .to_ascii_lowercase();
+
+    let rendered = values
+        .iter()
+        .map(|v| render_value(v, &kind, true))
+        .collect::<Vec<_>>();
+    let params = params
+        .as_object()
+        .ok_or_else(|| anyhow!("jCard parameters of {name} must be an object"))?;
+    // The group of a property is a pseudo-parameter in jCard (RFC 7095 §3.3.1.2).
+    let full_name = match params.get("group").and_then(Value::as_str) {
+        Some(group) => format!("{group}.{name}").to_ascii_uppercase(),
+        None => name.to_ascii_uppercase(),
+    };
+    let mut line = ContentLine::new(&full_name, rendered.join(","));
+    for (pname, pvalue) in params.iter().filter(|(n, _)| n.as_str() != "group") {
+        let values = match pvalue {
+            Value::Array(values) => values.iter().map(scalar).collect(),
+            other => vec![scalar(other)],
+        };
+        line.params.push(Param {
+            name: pname.to_ascii_uppercase(),
+            values,
+        });
+    }
+    // Types that are not the default of their property must be announced (RFC 7095 §3.5).
+    let default_kind = match line.base_name() {
+        "PHOTO" | "LOGO" | "SOUND" | "URL" | "SOURCE" | "MEMBER" | "KEY" | "GEO" | "IMPP"
+        | "CALURI" | "CALADRURI" | "FBURL" | "RELATED" => "uri",
+        "BDAY" | "ANNIVERSARY" => "date-and-or-time",
+        "REV" => "timestamp",
+        "LANG" => "language-tag",
+        _ => "text",
+    };
+    if kind != default_kind && kind != "unknown" && line.param("VALUE").is_none() {
+        line.set_param("VALUE", &kind);
+    }
+    Ok(line)
+}
+
+/// Render one value; `top_level` values of structured properties use `;` between their
+/// components and `,` inside them.
+fn render_value(value: &Value, kind: &str, top_level: bool) -> String {
+    match value {
+        Value::Array(parts) if top_level => parts
+            .iter()
+            .map(|p| render_value(p, kind, false))
+            .collect::<Vec<_>>()
+            .join(";"),
+        Value::Array(parts) => parts
+            .iter()
+            .map(|p| render_value(p, kind, false))
+            .collect::<Vec<_>>()
+            .join(","),
+        Value::String(text) => match kind {
+            "text" => escape_text(text),
+            "date" | "time" | "date-time" | "date-and-or-time" | "timestamp" => {
+                compact_datetime(text)
+            }
+            "utc-offset" => text.replace(':', ""),
+            _ => text.clone(),
+        },
+        Value::Bool(flag) => if *flag { "TRUE" } else { "FALSE" }.to_string(),
+        other => scalar(other),
+    }
+}
+
+/// `1985-04-12` → `19850412`, `--04-12` → `--0412`, `T10:22:00` → `T102200`.
+fn compact_datetime(text: &str) -> String {
+    let (prefix, rest) = match text.strip_prefix("--") {
+        Some(rest) => ("--", rest),
+        None => ("", text),
+    };
+    let compact: String = match rest.find(['T', 't']) {
+        Some(t) => {
+            let (date, time) = rest.split_at(t);
+            // Keep the sign of a time zone offset, drop the separators.
+            let date: String = date.chars().filter(|c| *c != '-').collect();
+            let time: String = time.chars().filter(|c| *c != ':').collect();
+            date + &time
+        }
+        None => rest.chars().filter(|c| *c != '-').collect(),
+    };
+    format!("{prefix}{compact}")
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn array<'a>(value: &'a Value, what: &str) -> Result<&'a Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| anyhow!("jCard {what} must be an array"))
+}
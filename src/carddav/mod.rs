@@ -1,20 +1,53 @@
 //! CardDAV client, streaming helpers, and types for addressbook discovery, queries, and sync.
 
 pub mod client;
+pub mod collection;
+pub mod import;
+#[cfg(feature = "jcard")]
+pub mod jcard;
+pub mod phone;
+#[cfg(feature = "vcard")]
+pub mod photo;
+pub mod query;
 pub mod streaming;
 pub mod types;
+#[cfg(feature = "vcard")]
+pub mod vcard;
 
 pub use client::{
-    CardDavClient, build_addressbook_multiget_body, build_addressbook_query_body,
+    CardDavClient, build_addressbook_multiget_body, build_addressbook_multiget_body_with,
+    build_addressbook_query_body, build_addressbook_query_body_with,
     build_addressbook_query_filter_email, build_addressbook_query_filter_fn,
-    build_addressbook_query_filter_uid, build_sync_collection_body, map_address_objects,
-    map_addressbook_list, map_sync_response,
+    build_addressbook_query_filter_text_match, build_addressbook_query_filter_uid,
+    build_sync_collection_body, map_address_objects, map_addressbook_list, map_directory_search,
+    map_sync_response,
 };
+pub use collection::{AddressBookPropPatch, MkAddressBook};
+#[cfg(feature = "vcard")]
+pub use import::duplicate_filter;
+pub use import::{card_uid, href_for_uid, split_vcards};
+#[cfg(feature = "jcard")]
+pub use jcard::{jcard_to_component, jcard_to_vcf, parse_jcard};
+pub use phone::{normalize_phone, phone_filter, phone_numbers_match, phone_variants};
+#[cfg(feature = "vcard")]
+pub use photo::{PhotoChunks, PhotoSource, photo_stream, replace_photo};
+pub use query::{AddressData, AddressbookFilter, ParamFilter, PropFilter};
+#[cfg(feature = "query-builder")]
+pub use query::{AddressbookQuery, PropCondition};
 pub use streaming::{
-    parse_multistatus_bytes, parse_multistatus_bytes_visit, parse_multistatus_stream,
-    parse_multistatus_stream_visit, parse_multistatus_stream_visit_with_timeout,
-    parse_multistatus_stream_with_timeout,
+    DavItemStream, SyncItemStream, Visit, parse_multistatus_bytes, parse_multistatus_bytes_visit,
+    parse_multistatus_bytes_visit_until, parse_multistatus_bytes_with_limits,
+    parse_multistatus_item_stream, parse_multistatus_item_stream_with_timeout,
+    parse_multistatus_stream, parse_multistatus_stream_visit, parse_multistatus_stream_visit_until,
+    parse_multistatus_stream_visit_until_with_timeout, parse_multistatus_stream_visit_with_timeout,
+    parse_multistatus_stream_with_limits, parse_multistatus_stream_with_timeout,
 };
 pub use types::{
-    AddressBookInfo, AddressObject, BatchItem, DavItem, Depth, SyncItem, SyncResponse,
+    AddressBookInfo, AddressObject, BatchItem, DavItem, Depth, DirectoryInfo, DirectorySearch,
+    ImportOutcome, ImportResult, ResourceLimits, SizeLimit, SyncItem, SyncResponse, TooLargeError,
+};
+#[cfg(feature = "vcard")]
+pub use vcard::{
+    Contact, Organization, Photo, StructuredName, TypedValue, VCardVersion, parse_vcard,
+    vcard_to_v3, vcard_to_v4,
 };
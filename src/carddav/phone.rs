@@ -0,0 +1,134 @@
+//! Phone number normalization for `TEL` searches (see
+//! [`crate::carddav::CardDavClient::addressbook_query_phone`]).
+//!
+//! Numbers are stored in every imaginable format (`+1 (555) 010-0123`, `555.010.0123`,
+//! `tel:+1-555-010-0123`), so exact `text-match`es rarely find them. The query asks the
+//! server for cards whose `TEL` contains any of [`phone_variants`], and the results are
+//! checked locally with [`phone_numbers_match`].
+
+use crate::carddav::query::{AddressbookFilter, PropFilter};
+use crate::common::contentline::{ContentLine, unfold_lines};
+use crate::webdav::types::{MatchType, TextMatch};
+
+/// Number of trailing digits used as the server-side search key: numbers are grouped by
+/// two digits or more in practice (`010-0123`, `01 23 45 67 89`, `01.23.45.67.89`), so
+/// the last two always appear unbroken. The candidates are then narrowed locally.
+const TAIL_DIGITS: usize = 2;
+
+/// Minimum number of digits two numbers must share at their end to be considered the same
+/// when one of them lacks the country code or trunk prefix.
+const MIN_SHARED_DIGITS: usize = 7;
+
+/// Normalize a phone number to E.164 style: digits only, with a leading `+` when the number
+/// is international (`+` or `00` prefix). A `tel:` scheme and parameters such as `;ext=`
+/// are dropped.
+///
+/// Returns `None` when the number has no digits.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::normalize_phone;
+///
+/// assert_eq!(normalize_phone("+1 (555) 010-0123").as_deref(), Some("+15550100123"));
+/// assert_eq!(normalize_phone("0033 1 23 45 67 89").as_deref(), Some("+33123456789"));
+/// assert_eq!(normalize_phone("tel:555-0100;ext=12").as_deref(), Some("5550100"));
+/// ```
+pub fn normalize_phone(number: &str) -> Option<String> {
+    let number = number.trim();
+    let number = number
+        .get(..4)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("tel:"))
+        .map_or(number, |_| &number[4..]);
+    let number = number.split(';').next().unwrap_or_default().trim();
+    let digits: String = number.chars().filter(char::is_ascii_digit).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    if number.starts_with('+') {
+        Some(format!("+{digits}"))
+    } else if let Some(international) = digits.strip_prefix("00") {
+        Some(format!("+{international}"))
+    } else {
+        Some(digits)
+    }
+}
+
+/// Search strings for a `contains` match on `TEL`: the E.164 form, the bare digits and the
+/// last digits of the number, which also match numbers stored with separators.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::phone_variants;
+///
+/// assert_eq!(phone_variants("+1 555-010-0123"), ["+15550100123", "15550100123", "23"]);
+/// ```
+pub fn phone_variants(number: &str) -> Vec<String> {
+    let Some(normalized) = normalize_phone(number) else {
+        return Vec::new();
+    };
+    let digits = normalized.trim_start_matches('+').to_string();
+    let tail = digits[digits.len().saturating_sub(TAIL_DIGITS)..].to_string();
+    let mut variants = vec![normalized, digits, tail];
+    variants.dedup();
+    variants
+}
+
+/// Whether two phone numbers are the same once normalized: equal digits, or one ends with
+/// the other (a missing country code or trunk `0`) and they share at least 7 digits.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::phone_numbers_match;
+///
+/// assert!(phone_numbers_match("+33 1 23 45 67 89", "01.23.45.67.89"));
+/// assert!(!phone_numbers_match("+1 555 0100", "+44 555 0100"));
+/// ```
+pub fn phone_numbers_match(a: &str, b: &str) -> bool {
+    let (Some(a), Some(b)) = (normalize_phone(a), normalize_phone(b)) else {
+        return false;
+    };
+    if a == b {
+        return true;
+    }
+    let (a_international, b_international) = (a.starts_with('+'), b.starts_with('+'));
+    // Two international numbers must match exactly.
+    if a_international && b_international {
+        return false;
+    }
+    let (a, b) = (
+        a.trim_start_matches('+').trim_start_matches('0'),
+        b.trim_start_matches('+').trim_start_matches('0'),
+    );
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    short.len() >= MIN_SHARED_DIGITS && long.ends_with(short)
+}
+
+/// Filter for cards with a `TEL` containing any of the [`phone_variants`] of `number`, or
+/// `None` when the number has no digits.
+pub fn phone_filter(number: &str) -> Option<AddressbookFilter> {
+    let variants = phone_variants(number);
+    if variants.is_empty() {
+        return None;
+    }
+    let mut prop = PropFilter::new("TEL").any_of();
+    for variant in variants {
+        prop = prop.text_match(
+            TextMatch::new(&variant)
+                .collation("i;octet")
+                .match_type(MatchType::Contains),
+        );
+    }
+    Some(AddressbookFilter::new().prop_filter(prop))
+}
+
+/// Whether any `TEL` of the vCard `card` matches `number`.
+pub(crate) fn card_has_phone(card: &str, number: &str) -> bool {
+    unfold_lines(card)
+        .iter()
+        .filter_map(|line| ContentLine::parse(line).ok())
+        .filter(|line| line.base_name() == "TEL")
+        .any(|line| phone_numbers_match(&line.value, number))
+}
@@ -0,0 +1,377 @@
+//! Byte-level access to contact photos.
+//!
+//! Inline photos are often several megabytes of base64. The helpers here work on the raw
+//! vCard bytes: [`photo_stream`] decodes the `PHOTO` value chunk by chunk and
+//! [`replace_photo`] splices a new one in, so neither the vCard nor the image is ever
+//! materialized as a `String`. Available with the `vcard` feature.
+
+use std::ops::Range;
+
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use base64::alphabet::STANDARD;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use bytes::{Bytes, BytesMut};
+use futures::Stream;
+
+use crate::carddav::vcard::legacy_media_type;
+use crate::common::contentline::ContentLine;
+
+/// Base64 characters decoded per chunk (a multiple of 4).
+const CHUNK_CHARS: usize = 64 * 1024;
+/// Maximum line length in octets, excluding the CRLF.
+const FOLD_LIMIT: usize = 75;
+
+/// Base64 as found in the wild: padding is optional on input.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Where a contact's photo lives.
+#[derive(Debug)]
+pub enum PhotoSource {
+    /// A reference to fetch separately (e.g. an `https:` URL).
+    Uri(String),
+    /// Inline data, decoded lazily.
+    Inline {
+        /// e.g. `image/jpeg`, when the vCard states it.
+        media_type: Option<String>,
+        data: PhotoChunks,
+    },
+}
+
+/// Decoded photo bytes, produced in chunks of at most 48 KiB.
+///
+/// An [`Iterator`], or a [`Stream`] through [`into_stream`](Self::into_stream); the source
+/// vCard is shared, not copied.
+#[derive(Debug)]
+pub struct PhotoChunks {
+    source: Bytes,
+    /// Physical segments of the base64 value, folds excluded.
+    segments: Vec<Range<usize>>,
+    segment: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl PhotoChunks {
+    /// Collect every chunk into a single buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the data is not valid base64.
+    pub fn collect_bytes(self) -> Result<Bytes> {
+        let mut out = BytesMut::new();
+        for chunk in self {
+            out.extend_from_slice(&chunk?);
+        }
+        Ok(out.freeze())
+    }
+
+    /// The chunks as a [`Stream`], e.g. to feed an HTTP body or an async writer.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Bytes>> + Send + Unpin {
+        futures::stream::iter(self)
+    }
+}
+
+impl Iterator for PhotoChunks {
+    type Item = Result<Bytes>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut encoded = Vec::with_capacity(CHUNK_CHARS);
+        while encoded.len() < CHUNK_CHARS && self.segment < self.segments.len() {
+            let range = &self.segments[self.segment];
+            let available = &self.source[range.start + self.offset..range.end];
+            let take = available.len().min(CHUNK_CHARS - encoded.len());
+            encoded.extend(
+                available[..take]
+                    .iter()
+                    .filter(|b| !b.is_ascii_whitespace()),
+            );
+            self.offset += take;
+            if range.start + self.offset == range.end {
+                self.segment += 1;
+                self.offset = 0;
+            }
+        }
+        // Chunks are full (a multiple of 4 characters) until the value runs out.
+        if self.segment == self.segments.len() {
+            self.done = true;
+        }
+        if encoded.is_empty() {
+            return None;
+        }
+        Some(
+            BASE64
+                .decode(&encoded)
+                .map(Bytes::from)
+                .map_err(|e| anyhow!("invalid base64 in PHOTO: {e}")),
+        )
+    }
+}
+
+/// Locate the `PHOTO` of a vCard without decoding it.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use fast_dav_rs::carddav::{PhotoSource, photo_stream};
+///
+/// let vcf = Bytes::from("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nPHOTO:data:image/png;base64,aGVs\r\n bG8=\r\nEND:VCARD\r\n");
+/// let Some(PhotoSource::Inline { media_type, data }) = photo_stream(vcf)? else {
+///     panic!("inline photo expected");
+/// };
+/// assert_eq!(media_type.as_deref(), Some("image/png"));
+/// assert_eq!(data.collect_bytes()?, "hello");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the `PHOTO` property is malformed.
+pub fn photo_stream(vcf: Bytes) -> Result<Option<PhotoSource>> {
+    let Some(property) = raw_properties(&vcf).find(|p| p.is("PHOTO")) else {
+        return Ok(None);
+    };
+    let line = ContentLine::parse(&format!("{}:", property.head))?;
+    let encoded = line
+        .param("ENCODING")
+        .is_some_and(|e| e.eq_ignore_ascii_case("b") || e.eq_ignore_ascii_case("base64"));
+
+    // Only the start of the value is needed to tell a `data:` URI from other URIs.
+    let prefix: Vec<u8> = property
+        .value
+        .iter()
+        .flat_map(|r| vcf[r.clone()].iter().copied())
+        .take(256)
+        .collect();
+    let (media_type, skip) = if encoded {
+        let media_type = line.param("TYPE").map(|t| legacy_media_type(t, "image"));
+        (media_type, 0)
+    } else if prefix.starts_with(b"data:") {
+        let comma = prefix
+            .iter()
+            .position(|&b| b == b',')
+            .ok_or_else(|| anyhow!("PHOTO data URI without ','"))?;
+        let header = String::from_utf8_lossy(&prefix[5..comma]).into_owned();
+        let Some(media_type) = header.strip_suffix(";base64") else {
+            return Err(anyhow!("PHOTO data URI is not base64-encoded"));
+        };
+        (
+            (!media_type.is_empty()).then(|| media_type.to_string()),
+            comma + 1,
+        )
+    } else {
+        let uri: Vec<u8> = property
+            .value
+            .iter()
+            .flat_map(|r| vcf[r.clone()].iter().copied())
+            .collect();
+        let uri = String::from_utf8(uri).map_err(|_| anyhow!("PHOTO URI is not UTF-8"))?;
+        return Ok(Some(PhotoSource::Uri(uri.trim().to_string())));
+    };
+
+    let mut segments = property.value;
+    let mut skip = skip;
+    while skip > 0 && !segments.is_empty() {
+        let first = &mut segments[0];
+        let step = skip.min(first.len());
+        first.start += step;
+        skip -= step;
+        if first.start == first.end {
+            segments.remove(0);
+        }
+    }
+    Ok(Some(PhotoSource::Inline {
+        media_type,
+        data: PhotoChunks {
+            source: vcf,
+            segments,
+            segment: 0,
+            offset: 0,
+            done: false,
+        },
+    }))
+}
+
+/// Replace (or add) the `PHOTO` of a vCard with `image`, encoded the way the card's
+/// version expects: `ENCODING=b;TYPE=…` for vCard 3.0, a `data:` URI for 4.0.
+///
+/// The new property is folded at 75 octets and placed where the old one was, or before
+/// `END:VCARD`. `media_type` is a full media type such as `image/jpeg`.
+///
+/// # Errors
+///
+/// Returns an error when the data has no `END:VCARD`.
+pub fn replace_photo(vcf: &[u8], media_type: &str, image: &[u8]) -> Result<Bytes> {
+    let properties: Vec<RawProperty> = raw_properties(vcf).collect();
+    let legacy = properties
+        .iter()
+        .find(|p| p.is("VERSION"))
+        .is_some_and(|p| {
+            p.value
+                .iter()
+                .any(|r| vcf[r.clone()].trim_ascii().starts_with(b"3"))
+        });
+    let end = properties
+        .iter()
+        .rfind(|p| p.head.eq_ignore_ascii_case("END") && p.value_is(vcf, "VCARD"))
+        .ok_or_else(|| anyhow!("vCard has no END:VCARD"))?;
+    let media_type = media_type.trim().to_ascii_lowercase();
+    let head = if legacy {
+        let subtype = media_type.rsplit('/').next().unwrap_or_default();
+        format!("PHOTO;ENCODING=b;TYPE={}:", subtype.to_ascii_uppercase())
+    } else {
+        format!("PHOTO:data:{media_type};base64,")
+    };
+
+    let mut out = BytesMut::with_capacity(vcf.len() + image.len() * 4 / 3 + image.len() / 40);
+    let mut copied = 0;
+    let mut written = false;
+    for photo in properties.iter().filter(|p| p.is("PHOTO")) {
+        out.extend_from_slice(&vcf[copied..photo.start]);
+        if !written {
+            write_photo(&mut out, &head, image);
+            written = true;
+        }
+        copied = photo.end;
+    }
+    if !written {
+        out.extend_from_slice(&vcf[copied..end.start]);
+        write_photo(&mut out, &head, image);
+        copied = end.start;
+    }
+    out.extend_from_slice(&vcf[copied..]);
+    Ok(out.freeze())
+}
+
+/// Write `head` followed by `image` in base64, folded, terminated by CRLF.
+fn write_photo(out: &mut BytesMut, head: &str, image: &[u8]) {
+    let mut column = 0;
+    let mut push = |out: &mut BytesMut, bytes: &[u8]| {
+        for &b in bytes {
+            if column == FOLD_LIMIT {
+                out.extend_from_slice(b"\r\n ");
+                column = 1;
+            }
+            out.extend_from_slice(&[b]);
+            column += 1;
+        }
+    };
+    push(out, head.as_bytes());
+    let mut encoded = String::with_capacity(CHUNK_CHARS);
+    for chunk in image.chunks(CHUNK_CHARS / 4 * 3) {
+        encoded.clear();
+        base64::engine::general_purpose::STANDARD.encode_string(chunk, &mut encoded);
+        push(out, encoded.as_bytes());
+    }
+    out.extend_from_slice(b"\r\n");
+}
+
+/// A content line located in the raw data.
+struct RawProperty {
+    /// Offset of the first physical line.
+    start: usize,
+    /// Offset just after the last physical line, line break included.
+    end: usize,
+    /// Unfolded `NAME;PARAMS`.
+    head: String,
+    /// Physical segments of the value, line breaks and fold markers excluded.
+    value: Vec<Range<usize>>,
+}
+
+impl RawProperty {
+    /// Whether the property has this name, ignoring any group prefix.
+    fn is(&self, name: &str) -> bool {
+        let property = self.head.split(';').next().unwrap_or_default();
+        let base = property.rsplit('.').next().unwrap_or_default();
+        base.trim().eq_ignore_ascii_case(name)
+    }
+
+    fn value_is(&self, data: &[u8], value: &str) -> bool {
+        let bytes: Vec<u8> = self
+            .value
+            .iter()
+            .flat_map(|r| data[r.clone()].iter().copied())
+            .collect();
+        bytes.trim_ascii().eq_ignore_ascii_case(value.as_bytes())
+    }
+}
+
+/// Content lines of `data`, with their byte ranges. Lines without a `:` are skipped.
+fn raw_properties(data: &[u8]) -> impl Iterator<Item = RawProperty> + '_ {
+    // Physical lines as (start, content end, next line start).
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let next = data[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(data.len(), |p| start + p + 1);
+        let mut content_end = next;
+        while content_end > start && matches!(data[content_end - 1], b'\n' | b'\r') {
+            content_end -= 1;
+        }
+        lines.push((start, content_end, next));
+        start = next;
+    }
+
+    let mut index = 0;
+    std::iter::from_fn(move || {
+        loop {
+            let (start, _, _) = *lines.get(index)?;
+            let mut segments = Vec::new();
+            let mut end = start;
+            while let Some(&(line_start, content_end, next)) = lines.get(index) {
+                let folded = matches!(data.get(line_start), Some(b' ' | b'\t'));
+                if !segments.is_empty() && !folded {
+                    break;
+                }
+                let content_start = if segments.is_empty() {
+                    line_start
+                } else {
+                    line_start + 1
+                };
+                segments.push(content_start..content_end.max(content_start));
+                end = next;
+                index += 1;
+            }
+
+            // Split the unfolded line at the first ':' outside quotes.
+            let mut head = Vec::new();
+            let mut in_quotes = false;
+            let mut split = None;
+            'segments: for (i, range) in segments.iter().enumerate() {
+                for (j, &b) in data[range.clone()].iter().enumerate() {
+                    match b {
+                        b'"' => in_quotes = !in_quotes,
+                        b':' if !in_quotes => {
+                            split = Some((i, range.start + j + 1));
+                            break 'segments;
+                        }
+                        _ => {}
+                    }
+                    head.push(b);
+                }
+            }
+            let Some((i, value_start)) = split else {
+                continue;
+            };
+            let mut value = Vec::with_capacity(segments.len() - i);
+            value.push(value_start..segments[i].end);
+            value.extend(segments[i + 1..].iter().cloned());
+            value.retain(|r| r.start < r.end);
+            return Some(RawProperty {
+                start,
+                end,
+                head: String::from_utf8_lossy(&head).trim().to_string(),
+                value,
+            });
+        }
+    })
+}
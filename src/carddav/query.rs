@@ -0,0 +1,527 @@
+//! `addressbook-query` building blocks (RFC 6352 §8.6) and the fluent [`AddressbookQuery`] API.
+//!
+//! [`AddressbookFilter`], [`PropFilter`] and [`ParamFilter`] serialize CardDAV filters; the
+//! XML of an [`AddressbookFilter`] can be passed to
+//! [`crate::carddav::CardDavClient::addressbook_query`] directly. The fluent builder is
+//! available with the `query-builder` feature (enabled by default), like its CalDAV
+//! counterpart [`crate::caldav::CalendarQuery`].
+
+use anyhow::{Result, anyhow};
+
+use crate::webdav::types::TextMatch;
+use crate::webdav::xml::{escape_xml, text_match_xml, validate_component_name};
+
+#[cfg(feature = "query-builder")]
+use crate::carddav::client::{
+    CardDavClient, build_addressbook_query_body_with, map_address_objects,
+};
+#[cfg(feature = "query-builder")]
+#[cfg(feature = "query-builder")]
+use crate::carddav::types::{AddressObject, Depth};
+#[cfg(feature = "query-builder")]
+use crate::webdav::types::MatchType;
+
+/// Options for the `<C:address-data>` element of an `addressbook-query` or
+/// `addressbook-multiget` (RFC 6352 §10.4).
+///
+/// The default requests the full vCard. Selecting properties keeps list views small when
+/// contacts embed large `PHOTO`s.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::AddressData;
+///
+/// let data = AddressData::new().props(["FN", "EMAIL"]);
+/// assert_eq!(
+///     data.to_xml(),
+///     r#"<C:address-data><C:prop name="FN"/><C:prop name="EMAIL"/></C:address-data>"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressData {
+    props: Vec<String>,
+    version: Option<String>,
+    json: bool,
+}
+
+impl AddressData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only return these vCard properties (e.g. `FN`, `EMAIL`, `TEL`). Servers add the
+    /// mandatory `VERSION`, `UID` and often `FN` themselves.
+    pub fn props<I, S>(mut self, props: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.props
+            .extend(props.into_iter().map(|p| p.as_ref().to_string()));
+        self
+    }
+
+    /// Ask for the vCard in this version (`3.0` or `4.0`), for servers that convert; see
+    /// `supported-address-data`.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Request the data as jCard (`content-type="application/vcard+json"`, RFC 7095)
+    /// instead of vCard text, on servers that support it (e.g. SabreDAV). With the `jcard`
+    /// feature, `AddressObject::parse_vcard` reads both formats.
+    pub fn json(mut self) -> Self {
+        self.json = true;
+        self
+    }
+
+    /// Check the property names.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a name is empty or contains characters other than ASCII letters,
+    /// digits and `-`.
+    pub fn validate(&self) -> Result<()> {
+        for prop in &self.props {
+            validate_component_name(prop)
+                .map_err(|e| anyhow!("invalid address-data property: {e}"))?;
+        }
+        Ok(())
+    }
+
+    pub fn to_xml(&self) -> String {
+        let attrs = match (&self.version, self.json) {
+            (version, true) => format!(
+                r#" content-type="application/vcard+json" version="{}""#,
+                escape_xml(version.as_deref().unwrap_or("4.0"))
+            ),
+            (Some(version), false) => format!(
+                r#" content-type="text/vcard" version="{}""#,
+                escape_xml(version)
+            ),
+            (None, false) => String::new(),
+        };
+        if self.props.is_empty() {
+            return format!("<C:address-data{attrs}/>");
+        }
+        let mut out = format!("<C:address-data{attrs}>");
+        for prop in &self.props {
+            out.push_str(&format!(r#"<C:prop name="{}"/>"#, escape_xml(prop)));
+        }
+        out.push_str("</C:address-data>");
+        out
+    }
+}
+
+/// A CardDAV `prop-filter` (RFC 6352 §10.5.1) matching a vCard property.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::{ParamFilter, PropFilter};
+/// use fast_dav_rs::webdav::TextMatch;
+///
+/// let work_email = PropFilter::new("EMAIL")
+///     .text_match(TextMatch::new("@example.com"))
+///     .param_filter(ParamFilter::new("TYPE").text_match(TextMatch::new("work")));
+/// assert_eq!(
+///     work_email.to_xml(),
+///     r#"<C:prop-filter name="EMAIL" test="allof"><C:text-match>@example.com</C:text-match><C:param-filter name="TYPE"><C:text-match>work</C:text-match></C:param-filter></C:prop-filter>"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropFilter {
+    name: String,
+    is_not_defined: bool,
+    any_of: bool,
+    text_matches: Vec<TextMatch>,
+    params: Vec<ParamFilter>,
+}
+
+impl PropFilter {
+    /// Match cards that have the property `name` (e.g. `EMAIL`, `TEL`).
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            is_not_defined: false,
+            any_of: false,
+            text_matches: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Match cards that do **not** have the property (`is-not-defined`). Overrides any
+    /// text-match or param-filter.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Add a `text-match` on the property value. Several text-matches must all match unless
+    /// [`any_of`](Self::any_of) is set.
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_matches.push(text_match);
+        self
+    }
+
+    /// Add a `param-filter` on one of the property's parameters.
+    pub fn param_filter(mut self, filter: ParamFilter) -> Self {
+        self.params.push(filter);
+        self
+    }
+
+    /// Match when any text-match or param-filter matches (`test="anyof"`) instead of all.
+    pub fn any_of(mut self) -> Self {
+        self.any_of = true;
+        self
+    }
+
+    /// Check the property and parameter names.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a name is empty or contains characters other than ASCII letters,
+    /// digits and `-`.
+    pub fn validate(&self) -> Result<()> {
+        validate_component_name(&self.name)
+            .map_err(|e| anyhow!("invalid addressbook-query property: {e}"))?;
+        for param in &self.params {
+            validate_component_name(&param.name)
+                .map_err(|e| anyhow!("invalid addressbook-query parameter: {e}"))?;
+        }
+        Ok(())
+    }
+
+    pub fn to_xml(&self) -> String {
+        let name = escape_xml(&self.name);
+        if self.is_not_defined {
+            return format!(r#"<C:prop-filter name="{name}"><C:is-not-defined/></C:prop-filter>"#);
+        }
+        if self.text_matches.is_empty() && self.params.is_empty() {
+            return format!(r#"<C:prop-filter name="{name}"/>"#);
+        }
+        let test = if self.any_of { "anyof" } else { "allof" };
+        let mut out = format!(r#"<C:prop-filter name="{name}" test="{test}">"#);
+        for text_match in &self.text_matches {
+            out.push_str(&text_match_xml("C", text_match));
+        }
+        for param in &self.params {
+            out.push_str(&param.to_xml());
+        }
+        out.push_str("</C:prop-filter>");
+        out
+    }
+}
+
+/// The `<C:filter>` of an `addressbook-query`: property filters combined with
+/// `test="allof"` (the default here) or `test="anyof"`.
+///
+/// RFC 6352 makes `anyof` the default when the attribute is missing, so it is always sent
+/// explicitly.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::{AddressbookFilter, PropFilter, build_addressbook_query_body};
+/// use fast_dav_rs::webdav::{MatchType, TextMatch};
+///
+/// // FN contains "smith" AND ORG equals "Acme".
+/// let filter = AddressbookFilter::new()
+///     .prop_filter(PropFilter::new("FN").text_match(TextMatch::new("smith")))
+///     .prop_filter(
+///         PropFilter::new("ORG").text_match(TextMatch::new("Acme").match_type(MatchType::Equals)),
+///     );
+/// let body = build_addressbook_query_body(&filter.to_xml(), true);
+/// assert!(body.contains(r#"<C:filter test="allof"><C:prop-filter name="FN" test="allof">"#));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressbookFilter {
+    props: Vec<PropFilter>,
+    any_of: bool,
+}
+
+impl AddressbookFilter {
+    /// An empty filter, matching every card.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn prop_filter(mut self, filter: PropFilter) -> Self {
+        self.props.push(filter);
+        self
+    }
+
+    /// Match cards satisfying any of the property filters instead of all of them.
+    pub fn any_of(mut self) -> Self {
+        self.any_of = true;
+        self
+    }
+
+    /// Check every property filter, see [`PropFilter::validate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a property or parameter name is invalid.
+    pub fn validate(&self) -> Result<()> {
+        self.props.iter().try_for_each(PropFilter::validate)
+    }
+
+    pub fn to_xml(&self) -> String {
+        let test = if self.any_of { "anyof" } else { "allof" };
+        let mut out = format!(r#"<C:filter test="{test}">"#);
+        for prop in &self.props {
+            out.push_str(&prop.to_xml());
+        }
+        out.push_str("</C:filter>");
+        out
+    }
+}
+
+/// `CARDDAV:limit` asking for at most `n` results.
+pub(crate) fn limit_xml(n: u32) -> String {
+    format!("<C:limit><C:nresults>{n}</C:nresults></C:limit>")
+}
+
+/// A CardDAV `param-filter` (RFC 6352 §10.5.2) matching a parameter of a property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamFilter {
+    name: String,
+    is_not_defined: bool,
+    text_match: Option<TextMatch>,
+}
+
+impl ParamFilter {
+    /// Match properties that have the parameter `name` (e.g. `TYPE`).
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            is_not_defined: false,
+            text_match: None,
+        }
+    }
+
+    /// Match properties that do **not** have the parameter.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_match = Some(text_match);
+        self
+    }
+
+    pub fn to_xml(&self) -> String {
+        let name = escape_xml(&self.name);
+        match (&self.text_match, self.is_not_defined) {
+            (_, true) => {
+                format!(r#"<C:param-filter name="{name}"><C:is-not-defined/></C:param-filter>"#)
+            }
+            (Some(text_match), false) => format!(
+                r#"<C:param-filter name="{name}">{}</C:param-filter>"#,
+                text_match_xml("C", text_match)
+            ),
+            (None, false) => format!(r#"<C:param-filter name="{name}"/>"#),
+        }
+    }
+}
+
+/// Fluent builder for `addressbook-query` REPORTs, created with [`CardDavClient::query`].
+///
+/// # Example
+///
+/// ```no_run
+/// use fast_dav_rs::CardDavClient;
+///
+/// # async fn demo(cli: &CardDavClient) -> anyhow::Result<()> {
+/// let contacts = cli
+///     .query("addressbooks/jane/contacts/")
+///     .prop("EMAIL")
+///     .contains("@example.com")
+///     .limit(50)
+///     .include_data()
+///     .execute()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "query-builder")]
+#[derive(Clone)]
+pub struct AddressbookQuery<'a> {
+    client: &'a CardDavClient,
+    path: String,
+    filter: AddressbookFilter,
+    limit: Option<u32>,
+    data: Option<AddressData>,
+}
+
+#[cfg(feature = "query-builder")]
+impl<'a> AddressbookQuery<'a> {
+    pub(crate) fn new(client: &'a CardDavClient, path: &str) -> Self {
+        Self {
+            client,
+            path: path.to_string(),
+            filter: AddressbookFilter::new(),
+            limit: None,
+            data: None,
+        }
+    }
+
+    /// Start a condition on the property `name`; finish it with e.g.
+    /// [`contains`](PropCondition::contains).
+    pub fn prop(self, name: &str) -> PropCondition<'a> {
+        PropCondition {
+            query: self,
+            filter: PropFilter::new(name),
+        }
+    }
+
+    /// Add an arbitrary `prop-filter` (several text-matches, param-filters, …).
+    pub fn prop_filter(mut self, filter: PropFilter) -> Self {
+        self.filter = self.filter.prop_filter(filter);
+        self
+    }
+
+    /// Match cards satisfying any of the property conditions (`test="anyof"`) instead of all
+    /// of them.
+    pub fn any_of(mut self) -> Self {
+        self.filter = self.filter.any_of();
+        self
+    }
+
+    /// Ask the server for at most `n` results (`CARDDAV:limit`). Servers that truncate the
+    /// result set flag it with a `507` response for the collection.
+    pub fn limit(mut self, n: u32) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    /// Return the `address-data` of every match.
+    pub fn include_data(mut self) -> Self {
+        self.data.get_or_insert_with(AddressData::default);
+        self
+    }
+
+    /// Only return these vCard properties of every match, e.g. `["FN", "EMAIL", "TEL"]`;
+    /// implies [`include_data`](Self::include_data). See [`AddressData::props`].
+    pub fn select_props<I, S>(mut self, props: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.data = Some(self.data.take().unwrap_or_default().props(props));
+        self
+    }
+
+    /// Return `address-data` as jCard; implies [`include_data`](Self::include_data). See
+    /// [`AddressData::json`].
+    pub fn json(mut self) -> Self {
+        self.data = Some(self.data.take().unwrap_or_default().json());
+        self
+    }
+
+    /// Use a custom `address-data` specification (implies returning data).
+    pub fn address_data(mut self, data: AddressData) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Validate the inputs and build the REPORT body without sending it.
+    ///
+    /// Without any condition the query matches every card in the collection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a property or parameter name is invalid or the limit is zero.
+    pub fn build_body(&self) -> Result<String> {
+        self.filter.validate()?;
+        if let Some(data) = &self.data {
+            data.validate()?;
+        }
+        if self.limit == Some(0) {
+            return Err(anyhow!("addressbook-query limit must be at least 1"));
+        }
+        let mut filter = self.filter.to_xml();
+        if let Some(n) = self.limit {
+            filter.push_str(&limit_xml(n));
+        }
+        Ok(build_addressbook_query_body_with(
+            &filter,
+            self.data.as_ref(),
+        ))
+    }
+
+    /// Send the REPORT (`Depth: 1`) and map the matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if [`build_body`](Self::build_body) fails,
+    /// and afterwards if the REPORT fails or the server responds with a non-success status.
+    pub async fn execute(self) -> Result<Vec<AddressObject>> {
+        let xml = self.build_body()?;
+        let resp = self.client.report(&self.path, Depth::One, &xml).await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "REPORT addressbook-query failed with {}",
+                resp.status()
+            ));
+        }
+        let body = resp.into_body();
+        Ok(map_address_objects(
+            self.client.parse_multistatus(&body)?.items,
+        ))
+    }
+}
+
+/// A property condition being built by [`AddressbookQuery::prop`].
+///
+/// Text comparisons use the server's default collation (`i;unicode-casemap`, case-insensitive).
+#[cfg(feature = "query-builder")]
+pub struct PropCondition<'a> {
+    query: AddressbookQuery<'a>,
+    filter: PropFilter,
+}
+
+#[cfg(feature = "query-builder")]
+impl<'a> PropCondition<'a> {
+    /// The property value contains `text`.
+    pub fn contains(self, text: &str) -> AddressbookQuery<'a> {
+        self.matching(text, MatchType::Contains)
+    }
+
+    /// The property value equals `text`.
+    pub fn equals(self, text: &str) -> AddressbookQuery<'a> {
+        self.matching(text, MatchType::Equals)
+    }
+
+    /// The property value starts with `text`.
+    pub fn starts_with(self, text: &str) -> AddressbookQuery<'a> {
+        self.matching(text, MatchType::StartsWith)
+    }
+
+    /// The property value ends with `text`.
+    pub fn ends_with(self, text: &str) -> AddressbookQuery<'a> {
+        self.matching(text, MatchType::EndsWith)
+    }
+
+    /// The property is present, whatever its value.
+    pub fn is_defined(self) -> AddressbookQuery<'a> {
+        self.query.prop_filter(self.filter)
+    }
+
+    /// The property is absent, e.g. `.prop("PHOTO").not_defined()`.
+    pub fn not_defined(self) -> AddressbookQuery<'a> {
+        self.query.prop_filter(self.filter.is_not_defined())
+    }
+
+    /// Match with a custom [`TextMatch`] (collation, negation).
+    pub fn text_match(self, text_match: TextMatch) -> AddressbookQuery<'a> {
+        self.query.prop_filter(self.filter.text_match(text_match))
+    }
+
+    fn matching(self, text: &str, match_type: MatchType) -> AddressbookQuery<'a> {
+        self.text_match(TextMatch::new(text).match_type(match_type))
+    }
+}
@@ -1,3 +1,7 @@
+use std::fmt;
+
+use anyhow::Result;
+
 use crate::webdav::types::DavItemCommon;
 pub use crate::webdav::types::{BatchItem, Depth};
 
@@ -13,10 +17,20 @@ pub struct DavItem {
     pub supported_address_data: Vec<String>,
     pub address_data: Option<String>,
     pub addressbook_home_set: Vec<String>,
+    /// `CARDDAV:principal-address`: the principal's own vCard.
+    pub principal_address: Option<String>,
+    /// `CARDDAV:directory-gateway`: directory addressbooks available to the principal.
+    pub directory_gateway: Vec<String>,
+    /// `CARDDAV:max-resource-size`, in octets.
+    pub max_resource_size: Option<u64>,
+    /// Non-standard `max-image-size` (e.g. Apple's CalendarServer), in octets.
+    pub max_image_size: Option<u64>,
     pub current_user_principal: Vec<String>,
     pub owner: Option<String>,
     pub addressbook_description: Option<String>,
     pub addressbook_color: Option<String>,
+    /// CalendarServer `CS:getctag`, changed on every modification of the collection.
+    pub ctag: Option<String>,
     pub sync_token: Option<String>,
     pub content_type: Option<String>,
     pub last_modified: Option<String>,
@@ -40,10 +54,15 @@ impl DavItem {
             supported_address_data: Vec::new(),
             address_data: None,
             addressbook_home_set: Vec::new(),
+            principal_address: None,
+            directory_gateway: Vec::new(),
+            max_resource_size: None,
+            max_image_size: None,
             current_user_principal: Vec::new(),
             owner: None,
             addressbook_description: None,
             addressbook_color: None,
+            ctag: None,
             sync_token: None,
             content_type: None,
             last_modified: None,
@@ -72,6 +91,9 @@ pub struct AddressBookInfo {
     pub description: Option<String>,
     pub color: Option<String>,
     pub etag: Option<String>,
+    /// `CS:getctag`, for servers without `sync-collection`; see
+    /// [`addressbook_changed`](crate::carddav::CardDavClient::addressbook_changed).
+    pub ctag: Option<String>,
     pub sync_token: Option<String>,
     pub supported_address_data: Vec<String>,
 }
@@ -85,6 +107,124 @@ pub struct AddressObject {
     pub status: Option<String>,
 }
 
+/// Directory-related properties of a principal, see
+/// [`CardDavClient::discover_directories`](crate::carddav::CardDavClient::discover_directories).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirectoryInfo {
+    /// Href of the vCard describing the principal itself (`CARDDAV:principal-address`).
+    pub principal_address: Option<String>,
+    /// Hrefs of directory gateways (`CARDDAV:directory-gateway`), typically LDAP-backed
+    /// corporate directories exposed as read-only addressbooks.
+    pub gateways: Vec<String>,
+}
+
+/// Result of a [`directory_search`](crate::carddav::CardDavClient::directory_search).
+#[derive(Debug, Clone)]
+pub struct DirectorySearch {
+    pub objects: Vec<AddressObject>,
+    /// The server returned fewer matches than exist (`507 Insufficient Storage` for the
+    /// directory). Narrow the filter to see the rest.
+    pub truncated: bool,
+}
+
+/// Outcome of one card of an [`import_vcards`](crate::carddav::CardDavClient::import_vcards).
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    /// Position of the card in the imported file.
+    pub index: usize,
+    pub uid: Option<String>,
+    /// Href the card was (or would have been) stored at; `None` without a `UID`.
+    pub href: Option<String>,
+    pub outcome: ImportOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportOutcome {
+    /// The card was stored; `etag` is set when the server returned one.
+    Created { etag: Option<String> },
+    /// A card with the same `UID` already exists on the server or earlier in the file.
+    Duplicate,
+    /// The card was not stored, e.g. it has no `UID` or the server rejected it.
+    Failed(String),
+}
+
+/// Upload size limits of an addressbook, see
+/// [`resource_limits`](crate::carddav::CardDavClient::resource_limits). `None` means the
+/// server does not advertise a limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// `CARDDAV:max-resource-size`: the largest vCard accepted, in octets.
+    pub max_resource_size: Option<u64>,
+    /// Largest decoded contact photo accepted, in octets, where the server exposes it.
+    pub max_image_size: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Check a vCard payload against [`max_resource_size`](Self::max_resource_size).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TooLargeError`] when the payload exceeds the limit.
+    pub fn check_vcard(&self, vcard: &[u8]) -> Result<()> {
+        check(SizeLimit::Resource, vcard.len(), self.max_resource_size)
+    }
+
+    /// Check decoded image data against [`max_image_size`](Self::max_image_size).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TooLargeError`] when the image exceeds the limit.
+    pub fn check_image(&self, image: &[u8]) -> Result<()> {
+        check(SizeLimit::Image, image.len(), self.max_image_size)
+    }
+}
+
+fn check(limit_kind: SizeLimit, size: usize, limit: Option<u64>) -> Result<()> {
+    match limit {
+        Some(limit) if size as u64 > limit => Err(TooLargeError {
+            limit_kind,
+            size: size as u64,
+            limit,
+        }
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Which [`ResourceLimits`] entry a payload exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeLimit {
+    Resource,
+    Image,
+}
+
+/// A payload is larger than the server accepts, detected before the upload instead of a
+/// `413`/`507` from the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooLargeError {
+    pub limit_kind: SizeLimit,
+    /// Size of the payload, in octets.
+    pub size: u64,
+    /// The server's limit, in octets.
+    pub limit: u64,
+}
+
+impl fmt::Display for TooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.limit_kind {
+            SizeLimit::Resource => "vCard",
+            SizeLimit::Image => "image",
+        };
+        write!(
+            f,
+            "{what} is {} octets, larger than the server limit of {} octets",
+            self.size, self.limit
+        )
+    }
+}
+
+impl std::error::Error for TooLargeError {}
+
 /// Detail of an item returned by `sync-collection`.
 #[derive(Debug, Clone)]
 pub struct SyncItem {
@@ -100,4 +240,7 @@ pub struct SyncItem {
 pub struct SyncResponse {
     pub sync_token: Option<String>,
     pub items: Vec<SyncItem>,
+    /// The server stopped at its result limit and flagged the collection with
+    /// `507 Insufficient Storage` (RFC 6578 §3.6): sync again from `sync_token` for the rest.
+    pub truncated: bool,
 }
@@ -0,0 +1,497 @@
+//! Typed view of vCard payloads (RFC 6350 and RFC 2426) returned in `address-data`.
+//!
+//! Available with the `vcard` feature. Like the iCalendar view it is built on the crate's
+//! content-line parser; only the commonly used properties are typed and the full
+//! [`Component`] stays available for everything else. vCard 3.0 and 4.0 parameter styles
+//! are both understood, and [`vcard_to_v3`]/[`vcard_to_v4`] convert between the two.
+
+use anyhow::{Result, anyhow};
+
+use crate::carddav::types::AddressObject;
+use crate::common::contentline::{
+    Component, ContentLine, Param, split_structured, split_text_list, unescape_text,
+};
+
+/// A parsed `VCARD`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contact {
+    /// `VERSION`, e.g. `3.0` or `4.0`.
+    pub version: Option<String>,
+    pub uid: Option<String>,
+    /// `FN`, the display name.
+    pub formatted_name: Option<String>,
+    /// `N`, the structured name.
+    pub name: Option<StructuredName>,
+    pub nicknames: Vec<String>,
+    pub emails: Vec<TypedValue>,
+    /// `TEL` values; vCard 4.0 `tel:` URIs are kept as sent.
+    pub phones: Vec<TypedValue>,
+    pub organization: Option<Organization>,
+    pub title: Option<String>,
+    pub photo: Option<Photo>,
+    /// `KIND` (vCard 4.0), e.g. `individual` or `group`.
+    pub kind: Option<String>,
+    /// The complete component the typed view was built from.
+    pub raw: Component,
+}
+
+/// The components of `N`, unescaped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructuredName {
+    pub family: String,
+    pub given: String,
+    pub additional: String,
+    pub prefixes: String,
+    pub suffixes: String,
+}
+
+/// An `EMAIL` or `TEL` with its types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedValue {
+    pub value: String,
+    /// Lower-cased `TYPE`s, e.g. `work` or `cell`; `pref` and vCard 3.0 `internet` are
+    /// dropped.
+    pub types: Vec<String>,
+    /// Preference from 1 (most preferred) to 100: `PREF` in vCard 4.0, `TYPE=pref` (as 1)
+    /// in vCard 3.0.
+    pub pref: Option<u8>,
+}
+
+/// `ORG`: the organization name followed by its units.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Organization {
+    pub name: String,
+    pub units: Vec<String>,
+}
+
+/// `PHOTO`, either referenced or inline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Photo {
+    Uri(String),
+    /// Base64 data, from a vCard 4.0 `data:` URI or vCard 3.0 `ENCODING=b`.
+    Inline {
+        /// e.g. `image/jpeg`.
+        media_type: Option<String>,
+        data: String,
+    },
+}
+
+/// Parse a vCard into a typed [`Contact`].
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::parse_vcard;
+///
+/// let contact = parse_vcard(
+///     "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:jane\r\nFN:Jane Doe\r\nN:Doe;Jane;;;\r\n\
+///      EMAIL;TYPE=INTERNET,WORK,pref:jane@example.com\r\nEND:VCARD\r\n",
+/// )?;
+/// assert_eq!(contact.name.unwrap().family, "Doe");
+/// assert_eq!(contact.emails[0].types, ["work"]);
+/// assert_eq!(contact.emails[0].pref, Some(1));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid vCard or its top-level component is not a
+/// `VCARD`.
+pub fn parse_vcard(vcf: &str) -> Result<Contact> {
+    let raw = Component::parse(vcf)?;
+    if raw.name != "VCARD" {
+        return Err(anyhow!("expected VCARD, found {}", raw.name));
+    }
+    let text = |name: &str| raw.value(name).map(unescape_text);
+    Ok(Contact {
+        version: raw.value("VERSION").map(|v| v.trim().to_string()),
+        uid: raw.value("UID").map(|v| v.trim().to_string()),
+        formatted_name: text("FN"),
+        name: raw.value("N").map(|value| {
+            let mut parts = split_structured(value).into_iter();
+            let mut next = || parts.next().unwrap_or_default();
+            StructuredName {
+                family: next(),
+                given: next(),
+                additional: next(),
+                prefixes: next(),
+                suffixes: next(),
+            }
+        }),
+        nicknames: raw
+            .properties_named("NICKNAME")
+            .flat_map(|p| split_text_list(&p.value))
+            .filter(|n| !n.is_empty())
+            .collect(),
+        emails: raw.properties_named("EMAIL").map(typed_value).collect(),
+        phones: raw.properties_named("TEL").map(typed_value).collect(),
+        organization: raw.value("ORG").map(|value| {
+            let mut parts = split_structured(value).into_iter();
+            Organization {
+                name: parts.next().unwrap_or_default(),
+                units: parts.filter(|u| !u.is_empty()).collect(),
+            }
+        }),
+        title: text("TITLE"),
+        photo: raw.property("PHOTO").map(photo),
+        kind: raw.value("KIND").map(|k| k.trim().to_ascii_lowercase()),
+        raw,
+    })
+}
+
+fn typed_value(line: &ContentLine) -> TypedValue {
+    let mut pref = line
+        .param("PREF")
+        .and_then(|p| p.trim().parse::<u8>().ok())
+        .filter(|p| (1..=100).contains(p));
+    let mut types = Vec::new();
+    for value in line.param_values("TYPE") {
+        for t in value.split(',') {
+            let t = t.trim().to_ascii_lowercase();
+            match t.as_str() {
+                "pref" => pref = pref.or(Some(1)),
+                "internet" | "" => {}
+                _ => types.push(t),
+            }
+        }
+    }
+    TypedValue {
+        value: unescape_text(line.value.trim()),
+        types,
+        pref,
+    }
+}
+
+fn photo(line: &ContentLine) -> Photo {
+    let value = line.value.trim();
+    if let Some(rest) = value.strip_prefix("data:")
+        && let Some((header, data)) = rest.split_once(',')
+    {
+        let media_type = header.trim_end_matches(";base64");
+        return Photo::Inline {
+            media_type: (!media_type.is_empty()).then(|| media_type.to_string()),
+            data: data.to_string(),
+        };
+    }
+    let encoded = line
+        .param("ENCODING")
+        .is_some_and(|e| e.eq_ignore_ascii_case("b") || e.eq_ignore_ascii_case("base64"));
+    if encoded {
+        return Photo::Inline {
+            media_type: line.param("TYPE").map(|t| legacy_media_type(t, "image")),
+            data: value.to_string(),
+        };
+    }
+    Photo::Uri(value.to_string())
+}
+
+/// vCard 3.0 `TYPE=JPEG` → `image/jpeg`; full media types pass through.
+pub(crate) fn legacy_media_type(kind: &str, top_level: &str) -> String {
+    let kind = kind.trim().to_ascii_lowercase();
+    if kind.contains('/') {
+        kind
+    } else {
+        format!("{top_level}/{kind}")
+    }
+}
+
+/// Binary properties: inline data is `ENCODING=b` in vCard 3.0 and a `data:` URI in 4.0.
+const MEDIA_PROPERTIES: &[(&str, &str)] =
+    &[("PHOTO", "image"), ("LOGO", "image"), ("SOUND", "audio")];
+
+/// Apple's vCard 3.0 stand-ins for the vCard 4.0 group properties.
+const LEGACY_KIND: &str = "X-ADDRESSBOOKSERVER-KIND";
+const LEGACY_MEMBER: &str = "X-ADDRESSBOOKSERVER-MEMBER";
+
+/// Convert a vCard 3.0 (RFC 2426) payload to vCard 4.0 (RFC 6350).
+///
+/// - `TYPE` lists are lower-cased and merged into one parameter; `TYPE=pref` becomes `PREF=1`
+///   and the redundant `TYPE=internet` is dropped,
+/// - inline `PHOTO`, `LOGO` and `SOUND` (`ENCODING=b;TYPE=JPEG`) become `data:` URIs,
+/// - `GEO:lat;lon` becomes a `geo:` URI,
+/// - `X-ADDRESSBOOKSERVER-KIND`/`-MEMBER` become `KIND`/`MEMBER`.
+///
+/// Other properties are kept as they are. Cards already at 4.0 pass through unchanged.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::carddav::vcard_to_v4;
+///
+/// let v4 = vcard_to_v4(
+///     "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Jane\r\nN:;Jane;;;\r\n\
+///      EMAIL;TYPE=INTERNET,WORK,pref:jane@example.com\r\nEND:VCARD\r\n",
+/// )?;
+/// assert!(v4.contains("VERSION:4.0\r\n"));
+/// assert!(v4.contains("EMAIL;PREF=1;TYPE=work:jane@example.com\r\n"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid vCard or its top-level component is not a
+/// `VCARD`.
+pub fn vcard_to_v4(vcf: &str) -> Result<String> {
+    let mut card = parse_card(vcf)?;
+    if version(&card) == Some("4.0") {
+        return Ok(card.to_ics());
+    }
+    for line in &mut card.properties {
+        let base = line.base_name().to_ascii_uppercase();
+        match base.as_str() {
+            "VERSION" => line.value = "4.0".to_string(),
+            "GEO" => {
+                if let Some((lat, lon)) = line.value.split_once(';') {
+                    line.value = format!("geo:{},{}", lat.trim(), lon.trim());
+                }
+            }
+            LEGACY_KIND => {
+                rename(line, "KIND");
+                line.value = line.value.trim().to_ascii_lowercase();
+            }
+            LEGACY_MEMBER => rename(line, "MEMBER"),
+            _ => {
+                if let Some(&(_, top_level)) = MEDIA_PROPERTIES.iter().find(|(n, _)| *n == base) {
+                    media_to_v4(line, top_level);
+                } else {
+                    types_to_v4(line);
+                }
+            }
+        }
+    }
+    Ok(card.to_ics())
+}
+
+/// Target version for [`vcard_to_v3`]/[`vcard_to_v4`], e.g. to normalize an export (see
+/// [`crate::carddav::CardDavClient::export_addressbook_as`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VCardVersion {
+    V3,
+    V4,
+}
+
+impl VCardVersion {
+    /// Convert `vcf` to this version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the payload is not valid vCard or its top-level component is
+    /// not a `VCARD`.
+    pub fn convert(self, vcf: &str) -> Result<String> {
+        match self {
+            Self::V3 => vcard_to_v3(vcf),
+            Self::V4 => vcard_to_v4(vcf),
+        }
+    }
+}
+
+/// Convert a vCard 4.0 (RFC 6350) payload to vCard 3.0 (RFC 2426), the reverse of
+/// [`vcard_to_v4`].
+///
+/// `PREF=1` becomes `TYPE=pref` (3.0 has no ranking, so other preferences are dropped),
+/// `data:` URIs become `ENCODING=b`, `tel:` URIs become plain numbers, `KIND:individual` is
+/// dropped as the 3.0 default and the other kinds use Apple's `X-ADDRESSBOOKSERVER-*`
+/// properties. An empty `N`, mandatory in 3.0, is added when missing. Cards already at 3.0
+/// pass through unchanged.
+///
+/// # Errors
+///
+/// Returns an error when the payload is not valid vCard or its top-level component is not a
+/// `VCARD`.
+pub fn vcard_to_v3(vcf: &str) -> Result<String> {
+    let mut card = parse_card(vcf)?;
+    if version(&card) == Some("3.0") {
+        return Ok(card.to_ics());
+    }
+    card.properties.retain(|line| {
+        !(line.base_name() == "KIND" && line.value.trim().eq_ignore_ascii_case("individual"))
+    });
+    for line in &mut card.properties {
+        let base = line.base_name().to_ascii_uppercase();
+        match base.as_str() {
+            "VERSION" => line.value = "3.0".to_string(),
+            "GEO" => {
+                if let Some(uri) = line.value.trim().strip_prefix("geo:") {
+                    let coordinates = uri.split(';').next().unwrap_or_default();
+                    let mut parts = coordinates.split(',');
+                    let (lat, lon) = (
+                        parts.next().unwrap_or_default(),
+                        parts.next().unwrap_or_default(),
+                    );
+                    line.value = format!("{lat};{lon}");
+                }
+            }
+            "KIND" => rename(line, LEGACY_KIND),
+            "MEMBER" => rename(line, LEGACY_MEMBER),
+            "TEL" => {
+                if let Some(number) = line.value.trim().strip_prefix("tel:") {
+                    line.value = number.to_string();
+                    line.remove_param("VALUE");
+                }
+                types_to_v3(line);
+            }
+            _ => {
+                if MEDIA_PROPERTIES.iter().any(|(n, _)| *n == base) {
+                    media_to_v3(line);
+                } else {
+                    types_to_v3(line);
+                }
+            }
+        }
+    }
+    if card.property("N").is_none() {
+        let position = card
+            .properties
+            .iter()
+            .position(|p| p.base_name() == "FN")
+            .map_or(card.properties.len(), |p| p + 1);
+        card.properties
+            .insert(position, ContentLine::new("N", ";;;;"));
+    }
+    Ok(card.to_ics())
+}
+
+fn parse_card(vcf: &str) -> Result<Component> {
+    let card = Component::parse(vcf)?;
+    if card.name != "VCARD" {
+        return Err(anyhow!("expected VCARD, found {}", card.name));
+    }
+    Ok(card)
+}
+
+fn version(card: &Component) -> Option<&str> {
+    card.value("VERSION").map(str::trim)
+}
+
+/// Rename a property, keeping its group prefix.
+fn rename(line: &mut ContentLine, name: &str) {
+    line.name = match line.name.rsplit_once('.') {
+        Some((group, _)) => format!("{group}.{name}"),
+        None => name.to_string(),
+    };
+}
+
+/// `TYPE` values, split and trimmed. vCard 2.1 style bare parameters (`TEL;WORK:…`), which
+/// many 3.0 producers still emit, are taken as types too and removed along with `TYPE`.
+fn take_types(line: &mut ContentLine) -> Vec<String> {
+    let mut types = Vec::new();
+    line.params.retain(|p| {
+        let bare = p.values.iter().all(String::is_empty);
+        if !p.name.eq_ignore_ascii_case("TYPE") && !bare {
+            return true;
+        }
+        if bare {
+            types.push(p.name.clone());
+        } else {
+            for value in &p.values {
+                types.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(String::from),
+                );
+            }
+        }
+        false
+    });
+    types
+}
+
+fn put_types(line: &mut ContentLine, types: Vec<String>) {
+    if !types.is_empty() {
+        line.params.push(Param {
+            name: "TYPE".to_string(),
+            values: types,
+        });
+    }
+}
+
+fn types_to_v4(line: &mut ContentLine) {
+    let mut types = Vec::new();
+    for t in take_types(line) {
+        let t = t.to_ascii_lowercase();
+        match t.as_str() {
+            "pref" if line.param("PREF").is_none() => line.set_param("PREF", "1"),
+            "pref" => {}
+            "internet" => {}
+            _ if !types.contains(&t) => types.push(t),
+            _ => {}
+        }
+    }
+    put_types(line, types);
+}
+
+fn types_to_v3(line: &mut ContentLine) {
+    let mut types = take_types(line);
+    if line.param("PREF").is_some_and(|p| p.trim() == "1")
+        && !types.iter().any(|t| t.eq_ignore_ascii_case("pref"))
+    {
+        types.push("pref".to_string());
+    }
+    line.remove_param("PREF");
+    put_types(line, types);
+}
+
+fn media_to_v4(line: &mut ContentLine, top_level: &str) {
+    let encoded = line
+        .param("ENCODING")
+        .is_some_and(|e| e.eq_ignore_ascii_case("b") || e.eq_ignore_ascii_case("base64"));
+    if encoded {
+        let media_type = line.param("TYPE").map(|t| legacy_media_type(t, top_level));
+        line.value = format!(
+            "data:{};base64,{}",
+            media_type.unwrap_or_default(),
+            line.value.trim()
+        );
+        line.remove_param("ENCODING");
+        line.remove_param("TYPE");
+    }
+    // URIs are the vCard 4.0 default for binary properties.
+    line.remove_param("VALUE");
+}
+
+fn media_to_v3(line: &mut ContentLine) {
+    let value = line.value.trim().to_string();
+    if let Some(rest) = value.strip_prefix("data:")
+        && let Some((header, data)) = rest.split_once(',')
+        && header.ends_with(";base64")
+    {
+        let media_type = header.trim_end_matches(";base64");
+        let subtype = media_type.rsplit('/').next().unwrap_or_default();
+        line.value = data.to_string();
+        line.remove_param("VALUE");
+        line.set_param("ENCODING", "b");
+        if subtype.is_empty() {
+            line.remove_param("TYPE");
+        } else {
+            line.set_param("TYPE", subtype.to_ascii_uppercase());
+        }
+    } else {
+        line.set_param("VALUE", "uri");
+    }
+}
+
+impl AddressObject {
+    /// Parse [`address_data`](Self::address_data) into a typed [`Contact`].
+    ///
+    /// With the `jcard` feature, jCard data (see
+    /// [`AddressData::json`](crate::carddav::AddressData::json)) is recognised and parsed as
+    /// well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the object was fetched without `address-data` or the data is
+    /// not valid vCard.
+    pub fn parse_vcard(&self) -> Result<Contact> {
+        let data = self
+            .address_data
+            .as_deref()
+            .ok_or_else(|| anyhow!("{} has no address-data", self.href))?;
+        #[cfg(feature = "jcard")]
+        if data.trim_start().starts_with('[') {
+            return crate::carddav::jcard::parse_jcard(data);
+        }
+        parse_vcard(data)
+    }
+}
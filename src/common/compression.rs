@@ -7,11 +7,16 @@ use anyhow::Result;
 use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZstdDecoder};
 use bytes::Bytes;
 use futures::TryStreamExt;
-use http_body_util::BodyStream;
-use hyper::body::Incoming;
+use futures::stream::BoxStream;
+use http_body_util::{BodyExt, BodyStream};
+use hyper::body::{Body, Incoming};
 use hyper::{HeaderMap, header, http};
 use std::io::Cursor;
-use tokio::io::{AsyncBufRead, AsyncReadExt, BufReader};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, BufReader, ReadBuf};
 use tokio_util::io::StreamReader;
 
 /// Supported content encodings for streaming decompression.
@@ -176,35 +181,146 @@ pub fn detect_encoding(headers: &HeaderMap) -> ContentEncoding {
         .unwrap_or(ContentEncoding::Identity)
 }
 
+/// Default capacity of the read buffers of the decompression chain, in bytes.
+pub const DEFAULT_DECOMPRESSION_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Decompressed bytes always allowed before
+/// [`max_expansion_ratio`](DecompressionOptions::max_expansion_ratio) is enforced (1 MiB),
+/// so small bodies that compress unusually well still pass.
+pub const EXPANSION_CHECK_THRESHOLD: u64 = 1024 * 1024;
+
+/// Payload size from which (de)compression runs on a blocking thread (256 KiB), see
+/// [`DecompressionOptions::blocking_threshold`] and [`compress_payload_with`].
+pub const DEFAULT_BLOCKING_THRESHOLD: usize = 256 * 1024;
+
+/// Tuning of response decompression, see [`decompress_stream_with`] and
+/// [`WebDavClient::set_decompression_options`](crate::webdav::WebDavClient::set_decompression_options).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecompressionOptions {
+    /// Capacity of the buffer reading the body and of the one behind each decoder, in
+    /// bytes (default [`DEFAULT_DECOMPRESSION_BUFFER_SIZE`]). Larger buffers mean fewer,
+    /// bigger reads at the cost of memory per response.
+    pub buffer_size: usize,
+    /// Most decompressed bytes allowed per compressed byte, e.g. `100` for 100:1, past
+    /// [`EXPANSION_CHECK_THRESHOLD`]. Bodies growing beyond it fail with an
+    /// [`ExpansionLimitError`], guarding against decompression bombs. No limit by default.
+    pub max_expansion_ratio: Option<u64>,
+    /// Size, in bytes, from which [`decompress_body_with`] decodes on
+    /// [`spawn_blocking`](tokio::task::spawn_blocking) instead of the async worker, so
+    /// large brotli/zstd bodies do not stall other tasks (default
+    /// [`DEFAULT_BLOCKING_THRESHOLD`]). A body announcing a smaller `Content-Length` is
+    /// decoded inline as it arrives; any other is read in full first, then decoded there
+    /// when it reaches the threshold. `None` always decodes inline. Streaming readers
+    /// decode as they are read and ignore it.
+    pub blocking_threshold: Option<usize>,
+}
+
+impl Default for DecompressionOptions {
+    fn default() -> Self {
+        Self {
+            buffer_size: DEFAULT_DECOMPRESSION_BUFFER_SIZE,
+            max_expansion_ratio: None,
+            blocking_threshold: Some(DEFAULT_BLOCKING_THRESHOLD),
+        }
+    }
+}
+
+/// A compressed body expanded beyond [`DecompressionOptions::max_expansion_ratio`];
+/// decompression stopped there.
+///
+/// [`decompress_body_with`] returns it wrapped in [`anyhow::Error`] (recover it with
+/// `downcast_ref`); readers from [`decompress_stream_with`] fail with an
+/// [`std::io::Error`] of kind `InvalidData` wrapping it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpansionLimitError {
+    /// Compressed bytes read so far.
+    pub compressed: u64,
+    /// Decompressed bytes they expanded to.
+    pub decompressed: u64,
+    pub max_ratio: u64,
+}
+
+impl std::fmt::Display for ExpansionLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} compressed bytes expanded to {}, beyond the {}:1 limit",
+            self.compressed, self.decompressed, self.max_ratio
+        )
+    }
+}
+
+impl std::error::Error for ExpansionLimitError {}
+
+/// A response body as decompressed chunks, see
+/// [`WebDavClient::send_stream_decompressed`](crate::webdav::WebDavClient::send_stream_decompressed).
+/// A body expanding beyond the configured ratio ends with an [`ExpansionLimitError`].
+pub type DecompressedBody = BoxStream<'static, Result<Bytes>>;
+
 /// Decompress a response body based on the content encoding.
 ///
 /// This function takes an aggregated response body and decompresses it according
-/// to the specified encoding.
+/// to the specified encoding. Compressed bodies of [`DEFAULT_BLOCKING_THRESHOLD`] bytes or
+/// more are decoded on a blocking thread (see [`DecompressionOptions::blocking_threshold`]).
 pub async fn decompress_body(body: Incoming, encodings: &[ContentEncoding]) -> Result<Bytes> {
-    // Non-data frames (e.g. HTTP/2 trailers) are intentionally skipped.
-    let stream = BodyStream::new(body)
-        .try_filter_map(|frame| std::future::ready(Ok(frame.into_data().ok())))
-        .map_err(std::io::Error::other);
-    let reader = StreamReader::new(stream);
-    let reader = BufReader::new(reader);
-    let mut out = Vec::with_capacity(32 * 1024);
-    let mut current: Box<dyn AsyncBufRead + Unpin + Send> = Box::new(reader);
+    decompress_body_with(body, encodings, &DecompressionOptions::default()).await
+}
 
-    for encoding in encodings.iter().rev() {
-        current = match encoding {
-            ContentEncoding::Identity => current,
-            ContentEncoding::Br => Box::new(BufReader::new(BrotliDecoder::new(current))),
-            ContentEncoding::Gzip => Box::new(BufReader::new(GzipDecoder::new(current))),
-            ContentEncoding::Zstd => Box::new(BufReader::new(ZstdDecoder::new(current))),
-        };
+/// Variant of [`decompress_body`] with other [`DecompressionOptions`] than the default
+/// ones.
+///
+/// # Errors
+///
+/// Returns an [`ExpansionLimitError`] when the body expands beyond
+/// [`max_expansion_ratio`](DecompressionOptions::max_expansion_ratio), or an error when it
+/// cannot be read or decoded.
+pub async fn decompress_body_with(
+    body: Incoming,
+    encodings: &[ContentEncoding],
+    options: &DecompressionOptions,
+) -> Result<Bytes> {
+    let compressed = encodings.iter().any(|e| *e != ContentEncoding::Identity);
+    let announced = body.size_hint().exact();
+    let threshold = match options.blocking_threshold.filter(|_| compressed) {
+        Some(threshold) if announced.is_none_or(|len| len >= threshold as u64) => threshold,
+        _ => return read_decoded(decompressing_reader(body, encodings, options)).await,
+    };
+
+    // A large or unannounced body is read here, off the blocking pool; only decoding the
+    // collected bytes, which never waits on the network, moves there.
+    let raw = body.collect().await?.to_bytes();
+    let blocking = raw.len() >= threshold;
+    let reader = decoding_reader(Box::new(Cursor::new(raw)), encodings, options);
+    if blocking {
+        tokio::task::spawn_blocking(move || futures::executor::block_on(read_decoded(reader)))
+            .await?
+    } else {
+        read_decoded(reader).await
     }
+}
 
-    let mut decoder = current;
-    decoder.read_to_end(&mut out).await?;
+/// Read `decoder` to the end, surfacing an [`ExpansionLimitError`] as such.
+async fn read_decoded(mut decoder: Box<dyn AsyncBufRead + Unpin + Send>) -> Result<Bytes> {
+    let mut out = Vec::with_capacity(32 * 1024);
+    decoder
+        .read_to_end(&mut out)
+        .await
+        .map_err(decoding_error)?;
 
     Ok(Bytes::from(out))
 }
 
+/// `err` of a decompressing reader, as the [`ExpansionLimitError`] it carries if any.
+pub(crate) fn decoding_error(err: std::io::Error) -> anyhow::Error {
+    match err
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<ExpansionLimitError>())
+    {
+        Some(limit) => limit.clone().into(),
+        None => err.into(),
+    }
+}
+
 /// Create a buffered reader with decompression support for streaming.
 ///
 /// This function wraps a stream with the appropriate decompression decoder
@@ -213,24 +329,158 @@ pub fn decompress_stream(
     body: Incoming,
     encodings: &[ContentEncoding],
 ) -> Result<Box<dyn AsyncBufRead + Unpin + Send>> {
+    decompress_stream_with(body, encodings, &DecompressionOptions::default())
+}
+
+/// Variant of [`decompress_stream`] with other [`DecompressionOptions`] than the default
+/// ones.
+pub fn decompress_stream_with(
+    body: Incoming,
+    encodings: &[ContentEncoding],
+    options: &DecompressionOptions,
+) -> Result<Box<dyn AsyncBufRead + Unpin + Send>> {
+    Ok(decompressing_reader(body, encodings, options))
+}
+
+/// The body as a reader, undoing `encodings` (outermost first).
+pub(crate) fn decompressing_reader(
+    body: Incoming,
+    encodings: &[ContentEncoding],
+    options: &DecompressionOptions,
+) -> Box<dyn AsyncBufRead + Unpin + Send> {
     // Non-data frames (e.g. HTTP/2 trailers) are intentionally skipped.
     let stream = BodyStream::new(body)
         .try_filter_map(|frame| std::future::ready(Ok(frame.into_data().ok())))
         .map_err(std::io::Error::other);
-    let reader: Box<dyn AsyncBufRead + Unpin + Send> =
-        Box::new(BufReader::new(StreamReader::new(stream)));
+    let reader = BufReader::with_capacity(options.buffer_size.max(1), StreamReader::new(stream));
+    decoding_reader(Box::new(reader), encodings, options)
+}
 
-    let mut current = reader;
+/// `source` undoing `encodings` (outermost first).
+fn decoding_reader(
+    source: Box<dyn AsyncBufRead + Unpin + Send>,
+    encodings: &[ContentEncoding],
+    options: &DecompressionOptions,
+) -> Box<dyn AsyncBufRead + Unpin + Send> {
+    let buffer_size = options.buffer_size.max(1);
+    let compressed = encodings.iter().any(|e| *e != ContentEncoding::Identity);
+    let guard = options.max_expansion_ratio.filter(|_| compressed);
+    let read = Arc::new(AtomicU64::new(0));
+    let mut current: Box<dyn AsyncBufRead + Unpin + Send> = match guard {
+        Some(_) => Box::new(CountingReader {
+            inner: source,
+            read: Arc::clone(&read),
+        }),
+        None => source,
+    };
     for encoding in encodings.iter().rev() {
         current = match encoding {
             ContentEncoding::Identity => current,
-            ContentEncoding::Br => Box::new(BufReader::new(BrotliDecoder::new(current))),
-            ContentEncoding::Gzip => Box::new(BufReader::new(GzipDecoder::new(current))),
-            ContentEncoding::Zstd => Box::new(BufReader::new(ZstdDecoder::new(current))),
+            ContentEncoding::Br => Box::new(BufReader::with_capacity(
+                buffer_size,
+                BrotliDecoder::new(current),
+            )),
+            ContentEncoding::Gzip => Box::new(BufReader::with_capacity(
+                buffer_size,
+                GzipDecoder::new(current),
+            )),
+            ContentEncoding::Zstd => Box::new(BufReader::with_capacity(
+                buffer_size,
+                ZstdDecoder::new(current),
+            )),
         };
     }
+    match guard {
+        Some(max_ratio) => Box::new(ExpansionGuard {
+            inner: current,
+            compressed: read,
+            decompressed: 0,
+            max_ratio,
+        }),
+        None => current,
+    }
+}
+
+/// Counts the bytes the decoders consume from the compressed body.
+struct CountingReader<R> {
+    inner: R,
+    read: Arc<AtomicU64>,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncRead for CountingReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = available.len().min(buf.remaining());
+        buf.put_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncBufRead for CountingReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Pin::new(&mut self.get_mut().inner).poll_fill_buf(cx)
+    }
 
-    Ok(current)
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.read.fetch_add(amt as u64, Ordering::Relaxed);
+        Pin::new(&mut self.inner).consume(amt);
+    }
+}
+
+/// Fails the decompressed stream once it outgrows the compressed bytes read by more than
+/// `max_ratio`.
+struct ExpansionGuard<R> {
+    inner: R,
+    compressed: Arc<AtomicU64>,
+    /// Decompressed bytes handed out so far.
+    decompressed: u64,
+    max_ratio: u64,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncRead for ExpansionGuard<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let n = available.len().min(buf.remaining());
+        buf.put_slice(&available[..n]);
+        self.consume(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncBufRead for ExpansionGuard<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        let available = ready!(Pin::new(&mut this.inner).poll_fill_buf(cx))?;
+        let decompressed = this.decompressed + available.len() as u64;
+        let compressed = this.compressed.load(Ordering::Relaxed);
+        if decompressed > EXPANSION_CHECK_THRESHOLD
+            && decompressed > compressed.saturating_mul(this.max_ratio)
+        {
+            return Poll::Ready(Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ExpansionLimitError {
+                    compressed,
+                    decompressed,
+                    max_ratio: this.max_ratio,
+                },
+            )));
+        }
+        Poll::Ready(Ok(available))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.decompressed += amt as u64;
+        Pin::new(&mut self.inner).consume(amt);
+    }
 }
 
 /// Compress a byte payload using the specified encoding.
@@ -261,6 +511,29 @@ pub fn decompress_stream(
 /// # }
 /// ```
 pub async fn compress_payload(data: Bytes, encoding: ContentEncoding) -> Result<Bytes> {
+    compress_payload_with(data, encoding, Some(DEFAULT_BLOCKING_THRESHOLD)).await
+}
+
+/// Variant of [`compress_payload`] compressing payloads of `blocking_threshold` bytes or
+/// more on [`spawn_blocking`](tokio::task::spawn_blocking), so large brotli/zstd payloads
+/// do not stall the async worker; `None` always compresses inline.
+pub async fn compress_payload_with(
+    data: Bytes,
+    encoding: ContentEncoding,
+    blocking_threshold: Option<usize>,
+) -> Result<Bytes> {
+    if encoding != ContentEncoding::Identity
+        && blocking_threshold.is_some_and(|threshold| data.len() >= threshold)
+    {
+        return tokio::task::spawn_blocking(move || {
+            futures::executor::block_on(encode(data, encoding))
+        })
+        .await?;
+    }
+    encode(data, encoding).await
+}
+
+async fn encode(data: Bytes, encoding: ContentEncoding) -> Result<Bytes> {
     match encoding {
         ContentEncoding::Identity => Ok(data),
         ContentEncoding::Br => {
@@ -0,0 +1,448 @@
+//! Minimal content-line model shared by iCalendar (RFC 5545) and vCard (RFC 6350).
+//!
+//! This is intentionally small: it unfolds and parses content lines, groups them into
+//! `BEGIN`/`END` components, and serializes them back with CRLF line endings and 75-octet
+//! folding. It does not interpret property values, which keeps round-trips lossless for
+//! properties the crate does not know about.
+
+use anyhow::{Result, anyhow};
+use std::fmt;
+
+const FOLD_LIMIT: usize = 75;
+
+/// A single property parameter, e.g. `PARTSTAT=ACCEPTED` or `TYPE=work,voice`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// An unfolded content line: `NAME;PARAM=VALUE:value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentLine {
+    /// Property name, upper-cased. A vCard group prefix (`item1.EMAIL`) is kept as-is.
+    pub name: String,
+    pub params: Vec<Param>,
+    /// Raw (still escaped) property value.
+    pub value: String,
+}
+
+impl ContentLine {
+    pub fn new(name: &str, value: impl Into<String>) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            params: Vec::new(),
+            value: value.into(),
+        }
+    }
+
+    /// Parse a single **unfolded** content line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the line has no `:` separating the name from the value, or when the
+    /// property name is empty.
+    pub fn parse(line: &str) -> Result<Self> {
+        let mut in_quotes = false;
+        let mut value_start = None;
+        for (idx, ch) in line.char_indices() {
+            match ch {
+                '"' => in_quotes = !in_quotes,
+                ':' if !in_quotes => {
+                    value_start = Some(idx);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let split = value_start.ok_or_else(|| anyhow!("content line without ':': {line:?}"))?;
+        let (head, value) = (&line[..split], &line[split + 1..]);
+
+        let mut segments = split_unquoted(head, ';').into_iter();
+        let name = segments
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_uppercase();
+        if name.is_empty() {
+            return Err(anyhow!("content line without a property name: {line:?}"));
+        }
+
+        let mut params = Vec::new();
+        for segment in segments {
+            let (pname, pvalue) = segment.split_once('=').unwrap_or((segment, ""));
+            let values = split_unquoted(pvalue, ',')
+                .into_iter()
+                .map(|v| v.trim_matches('"').to_string())
+                .collect();
+            params.push(Param {
+                name: pname.trim().to_ascii_uppercase(),
+                values,
+            });
+        }
+
+        Ok(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    /// Property name without a vCard group prefix (`item1.EMAIL` → `EMAIL`).
+    pub fn base_name(&self) -> &str {
+        self.name
+            .rsplit_once('.')
+            .map(|(_, n)| n)
+            .unwrap_or(&self.name)
+    }
+
+    /// First value of the named parameter (case-insensitive).
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .and_then(|p| p.values.first())
+            .map(String::as_str)
+    }
+
+    /// All values of the named parameter (case-insensitive), across repeated parameters.
+    pub fn param_values(&self, name: &str) -> Vec<&str> {
+        self.params
+            .iter()
+            .filter(|p| p.name.eq_ignore_ascii_case(name))
+            .flat_map(|p| p.values.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Replace the named parameter with a single value, appending it when missing.
+    pub fn set_param(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+        if let Some(param) = self
+            .params
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+        {
+            param.values = vec![value];
+        } else {
+            self.params.push(Param {
+                name: name.to_ascii_uppercase(),
+                values: vec![value],
+            });
+        }
+        let mut seen = false;
+        self.params.retain(|p| {
+            if !p.name.eq_ignore_ascii_case(name) {
+                return true;
+            }
+            let keep = !seen;
+            seen = true;
+            keep
+        });
+    }
+
+    /// Remove every occurrence of the named parameter.
+    pub fn remove_param(&mut self, name: &str) {
+        self.params.retain(|p| !p.name.eq_ignore_ascii_case(name));
+    }
+
+    /// Serialize and fold the line to 75 octets, terminated by CRLF.
+    pub fn to_folded(&self) -> String {
+        fold_line(&self.to_string())
+    }
+}
+
+impl fmt::Display for ContentLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        for param in &self.params {
+            write!(f, ";{}=", param.name)?;
+            for (idx, value) in param.values.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(",")?;
+                }
+                if value.contains([':', ';', ',']) {
+                    write!(f, "\"{value}\"")?;
+                } else {
+                    f.write_str(value)?;
+                }
+            }
+        }
+        write!(f, ":{}", self.value)
+    }
+}
+
+/// A `BEGIN:<NAME>` … `END:<NAME>` block with its properties and nested components.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// Component name, upper-cased (`VCALENDAR`, `VEVENT`, `VCARD`, …).
+    pub name: String,
+    pub properties: Vec<ContentLine>,
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_ascii_uppercase(),
+            properties: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    /// Parse every top-level component in `text` (e.g. several `VCARD`s in one `.vcf`).
+    ///
+    /// Blank lines are ignored. Lines outside any component are rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed content lines, unbalanced `BEGIN`/`END` pairs, or
+    /// properties outside a component.
+    pub fn parse_all(text: &str) -> Result<Vec<Component>> {
+        let mut stack: Vec<Component> = Vec::new();
+        let mut out = Vec::new();
+
+        for line in unfold_lines(text) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed = ContentLine::parse(&line)?;
+            match parsed.name.as_str() {
+                "BEGIN" => stack.push(Component::new(parsed.value.trim())),
+                "END" => {
+                    let finished = stack
+                        .pop()
+                        .ok_or_else(|| anyhow!("END:{} without matching BEGIN", parsed.value))?;
+                    if !finished.name.eq_ignore_ascii_case(parsed.value.trim()) {
+                        return Err(anyhow!(
+                            "END:{} does not match BEGIN:{}",
+                            parsed.value.trim(),
+                            finished.name
+                        ));
+                    }
+                    match stack.last_mut() {
+                        Some(parent) => parent.components.push(finished),
+                        None => out.push(finished),
+                    }
+                }
+                _ => match stack.last_mut() {
+                    Some(current) => current.properties.push(parsed),
+                    None => {
+                        return Err(anyhow!("property {} outside of any component", parsed.name));
+                    }
+                },
+            }
+        }
+
+        if let Some(unclosed) = stack.last() {
+            return Err(anyhow!("BEGIN:{} without matching END", unclosed.name));
+        }
+        Ok(out)
+    }
+
+    /// Parse exactly one top-level component.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when parsing fails or `text` does not contain exactly one component.
+    pub fn parse(text: &str) -> Result<Component> {
+        let mut all = Self::parse_all(text)?;
+        if all.len() != 1 {
+            return Err(anyhow!(
+                "expected exactly one top-level component, found {}",
+                all.len()
+            ));
+        }
+        Ok(all.remove(0))
+    }
+
+    /// First property with the given name (case-insensitive, vCard groups ignored).
+    pub fn property(&self, name: &str) -> Option<&ContentLine> {
+        self.properties
+            .iter()
+            .find(|p| p.base_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Mutable access to the first property with the given name.
+    pub fn property_mut(&mut self, name: &str) -> Option<&mut ContentLine> {
+        self.properties
+            .iter_mut()
+            .find(|p| p.base_name().eq_ignore_ascii_case(name))
+    }
+
+    /// All properties with the given name.
+    pub fn properties_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ContentLine> {
+        self.properties
+            .iter()
+            .filter(move |p| p.base_name().eq_ignore_ascii_case(name))
+    }
+
+    /// Raw value of the first property with the given name.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.property(name).map(|p| p.value.as_str())
+    }
+
+    /// Replace the first property with the given name (dropping its parameters), or append it.
+    pub fn set_value(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+        if let Some(existing) = self.property_mut(name) {
+            existing.value = value;
+            existing.params.clear();
+        } else {
+            self.properties.push(ContentLine::new(name, value));
+        }
+    }
+
+    /// Remove every property with the given name.
+    pub fn remove_properties(&mut self, name: &str) {
+        self.properties
+            .retain(|p| !p.base_name().eq_ignore_ascii_case(name));
+    }
+
+    /// Nested components with the given name.
+    pub fn components_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Component> {
+        self.components
+            .iter()
+            .filter(move |c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Serialize with CRLF line endings and 75-octet folding.
+    pub fn to_ics(&self) -> String {
+        let mut out = String::new();
+        self.write_to(&mut out);
+        out
+    }
+
+    fn write_to(&self, out: &mut String) {
+        out.push_str("BEGIN:");
+        out.push_str(&self.name);
+        out.push_str("\r\n");
+        for property in &self.properties {
+            out.push_str(&property.to_folded());
+        }
+        for component in &self.components {
+            component.write_to(out);
+        }
+        out.push_str("END:");
+        out.push_str(&self.name);
+        out.push_str("\r\n");
+    }
+}
+
+/// Unfold content lines: join continuation lines (starting with a space or tab) and accept both
+/// CRLF and bare LF line endings.
+pub fn unfold_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(rest) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+            continue;
+        }
+        lines.push(raw.to_string());
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Fold a single line to at most 75 octets per physical line (RFC 5545 §3.1), never splitting a
+/// UTF-8 sequence, and terminate it with CRLF.
+pub fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + line.len() / FOLD_LIMIT * 3 + 2);
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > FOLD_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += len;
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// Escape a `TEXT` value (backslash, semicolon, comma and newlines).
+pub fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Reverse [`escape_text`].
+pub fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Split a comma-separated `TEXT` list (e.g. `CATEGORIES`) on unescaped commas and
+/// unescape each item.
+pub fn split_text_list(value: &str) -> Vec<String> {
+    split_escaped(value, ',')
+}
+
+/// Split a structured value (e.g. vCard `N` or `ADR`) into its components on unescaped
+/// semicolons and unescape each component.
+pub fn split_structured(value: &str) -> Vec<String> {
+    split_escaped(value, ';')
+}
+
+fn split_escaped(value: &str, sep: char) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (idx, ch) in value.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if ch == sep => {
+                items.push(unescape_text(&value[start..idx]));
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(unescape_text(&value[start..]));
+    items
+}
+
+fn split_unquoted(input: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (idx, ch) in input.char_indices() {
+        if ch == '"' {
+            in_quotes = !in_quotes;
+        } else if ch == sep && !in_quotes {
+            parts.push(&input[start..idx]);
+            start = idx + ch.len_utf8();
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
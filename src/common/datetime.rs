@@ -0,0 +1,144 @@
+//! Small helpers for iCalendar UTC date-times and durations.
+//!
+//! Values are handled as seconds since the Unix epoch, which is all the protocol-level helpers
+//! need (comparing, adding durations, merging intervals) without pulling in a date library.
+
+/// Parse an iCalendar UTC `DATE-TIME` (`YYYYMMDDTHHMMSSZ`) or a `DATE` (`YYYYMMDD`, taken as
+/// midnight UTC) into seconds since the Unix epoch.
+pub(crate) fn parse_utc_datetime(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let bytes = value.as_bytes();
+    let digits = |range: std::ops::Range<usize>| -> Option<i64> {
+        let slice = value.get(range)?;
+        if !slice.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        slice.parse().ok()
+    };
+
+    let (year, month, day) = (digits(0..4)?, digits(4..6)?, digits(6..8)?);
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let (hour, minute, second) = match bytes.len() {
+        8 => (0, 0, 0),
+        16 if bytes[8] == b'T' && bytes[15] == b'Z' => {
+            (digits(9..11)?, digits(11..13)?, digits(13..15)?)
+        }
+        _ => return None,
+    };
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+/// Parse a `DATE` (`YYYYMMDD`) or `DATE-TIME` (`YYYYMMDDTHHMMSS`, optionally `Z`-suffixed)
+/// as wall-clock seconds since the epoch, ignoring any time zone.
+///
+/// Used where values only need to be compared and shifted within one time zone, e.g.
+/// recurrence expansion of `TZID`-local or floating date-times.
+pub(crate) fn parse_local_datetime(value: &str) -> Option<i64> {
+    let value = value.trim();
+    match value.len() {
+        15 => parse_utc_datetime(&format!("{value}Z")),
+        _ => parse_utc_datetime(value),
+    }
+}
+
+/// Format seconds since the Unix epoch as an iCalendar UTC `DATE-TIME` (`YYYYMMDDTHHMMSSZ`).
+pub(crate) fn format_utc_datetime(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86_400);
+    let secs = timestamp.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        secs / 3_600,
+        (secs % 3_600) / 60,
+        secs % 60
+    )
+}
+
+/// Parse an iCalendar `DURATION` (e.g. `PT1H30M`, `-P1D`, `P2W`) into seconds.
+pub(crate) fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'-' => (-1, &value[1..]),
+        b'+' => (1, &value[1..]),
+        _ => (1, value),
+    };
+    let rest = rest.strip_prefix('P')?;
+
+    let mut total = 0i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut saw_component = false;
+    for ch in rest.chars() {
+        match ch {
+            '0'..='9' => number.push(ch),
+            'T' if !in_time && number.is_empty() => in_time = true,
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let factor = match (unit, in_time) {
+                    ('W', false) => 7 * 86_400,
+                    ('D', false) => 86_400,
+                    ('H', true) => 3_600,
+                    ('M', true) => 60,
+                    ('S', true) => 1,
+                    _ => return None,
+                };
+                total += n * factor;
+                saw_component = true;
+            }
+        }
+    }
+    if !number.is_empty() || !saw_component {
+        return None;
+    }
+    Some(sign * total)
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub(crate) fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        _ => 28,
+    }
+}
+
+// Howard Hinnant's `days_from_civil` / `civil_from_days` algorithms.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Day of the week of a day count since the epoch, Monday = 0 … Sunday = 6.
+#[cfg(feature = "ical")]
+pub(crate) fn weekday_from_days(days: i64) -> i64 {
+    (days + 3).rem_euclid(7)
+}
@@ -1,16 +1,44 @@
 use anyhow::Result;
 use bytes::Bytes;
-use http_body_util::Full;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full};
 use hyper_rustls::HttpsConnectorBuilder;
 use hyper_util::client::legacy::{Client, connect::HttpConnector};
 use hyper_util::rt::TokioExecutor;
 
-/// Type alias for the Hyper client used across CalDAV/CardDAV modules.
+/// Request body: either fully buffered or streamed (e.g. a file upload).
+pub type RequestBody = UnsyncBoxBody<Bytes, std::io::Error>;
+
+/// Type alias for a Hyper client sending buffered bodies, see [`build_hyper_client`].
 pub type HyperClient = Client<hyper_rustls::HttpsConnector<HttpConnector>, Full<Bytes>>;
 
+/// Type alias for the Hyper client used across CalDAV/CardDAV modules, which also streams
+/// request bodies.
+pub type StreamingHyperClient = Client<hyper_rustls::HttpsConnector<HttpConnector>, RequestBody>;
+
+/// A buffered request body.
+pub fn full_body(bytes: Bytes) -> RequestBody {
+    Full::new(bytes)
+        .map_err(|never| match never {})
+        .boxed_unsync()
+}
+
 /// Build a Hyper client configured with HTTP/2, connection pooling, and a TLS connector
 /// that prefers native roots but falls back to the bundled WebPKI store.
 pub fn build_hyper_client() -> Result<HyperClient> {
+    build_client()
+}
+
+/// [`build_hyper_client`] for a client sending [`RequestBody`]s, buffered or streamed.
+pub fn build_streaming_hyper_client() -> Result<StreamingHyperClient> {
+    build_client()
+}
+
+fn build_client<B>() -> Result<Client<hyper_rustls::HttpsConnector<HttpConnector>, B>>
+where
+    B: hyper::body::Body + Send,
+    B::Data: Send,
+{
     let https_builder = HttpsConnectorBuilder::new()
         .with_native_roots()
         .unwrap_or_else(|err| {
@@ -30,5 +58,5 @@ pub fn build_hyper_client() -> Result<HyperClient> {
     Ok(Client::builder(TokioExecutor::new())
         .http2_adaptive_window(true)
         .pool_max_idle_per_host(128)
-        .build::<_, Full<Bytes>>(https))
+        .build::<_, B>(https))
 }
@@ -1,8 +1,15 @@
 pub mod compression;
+pub mod contentline;
+pub(crate) mod datetime;
 pub mod http;
 
 pub use compression::{
-    ContentEncoding, add_accept_encoding, add_content_encoding, compress_payload, decompress_body,
-    decompress_stream, detect_encoding, detect_encodings,
+    ContentEncoding, DecompressedBody, DecompressionOptions, ExpansionLimitError,
+    add_accept_encoding, add_content_encoding, compress_payload, compress_payload_with,
+    decompress_body, decompress_body_with, decompress_stream, decompress_stream_with,
+    detect_encoding, detect_encodings,
+};
+pub use http::{
+    HyperClient, RequestBody, StreamingHyperClient, build_hyper_client,
+    build_streaming_hyper_client, full_body,
 };
-pub use http::{HyperClient, build_hyper_client};
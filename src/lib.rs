@@ -26,8 +26,16 @@
 //! - Streaming-friendly APIs for large WebDAV responses
 //! - Batch operations with bounded concurrency
 //! - ETag helpers for safe conditional writes/deletes
+//! - Pluggable sync-token storage (`sync::TokenStore`) for incremental sync
+//! - A sync engine (`sync::SyncEngine`) falling back to getctag polling and ETag diffing
+//!   on servers without WebDAV-Sync
 //! - Streaming XML parsing with minimal memory footprint
 //! - CardDAV addressbook discovery and vCard operations
+//! - CalDAV free-busy queries and RFC 6638 scheduling via the schedule outbox
+//! - Fluent `calendar-query` and `addressbook-query` builders (`query-builder` feature, enabled by default)
+//! - Typed iCalendar parsing of calendar objects (`ical` feature)
+//! - Sync state snapshots (`sync::SyncSnapshot`), serializable with the `serde` feature
+//! - Optional `testing` feature with helpers for integration tests against a live server
 //!
 //! # Examples
 //!
@@ -67,6 +75,7 @@
 //!
 //! ```no_run
 //! use fast_dav_rs::{CalDavClient, Depth};
+//! use fast_dav_rs::caldav::MkCalendar;
 //! use bytes::Bytes;
 //! use anyhow::Result;
 //!
@@ -80,17 +89,12 @@
 //!
 //!     # let home = ""; // Placeholder for calendar home path
 //!     // Create a new calendar
-//!     let calendar_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
-//!     <C:mkcalendar xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
-//!       <D:set>
-//!         <D:prop>
-//!           <D:displayname>My New Calendar</D:displayname>
-//!           <C:calendar-description>Calendar created with fast-dav-rs</C:calendar-description>
-//!         </D:prop>
-//!       </D:set>
-//!     </C:mkcalendar>"#;
-//!
-//!     let response = client.mkcalendar("my-new-calendar/", calendar_xml).await?;
+//!     let calendar = MkCalendar::new()
+//!         .displayname("My New Calendar")
+//!         .description("Calendar created with fast-dav-rs")
+//!         .components(["VEVENT"]);
+//!
+//!     let response = client.create_calendar("my-new-calendar/", &calendar).await?;
 //!     println!("Created calendar with status: {}", response.status());
 //!
 //!     // Delete a calendar
@@ -238,9 +242,9 @@
 //! For processing large collections without loading everything into memory:
 //!
 //! ```no_run
-//! use fast_dav_rs::{CalDavClient, Depth, detect_encoding};
-//! use fast_dav_rs::caldav::parse_multistatus_stream;
-//! use bytes::Bytes;
+//! use fast_dav_rs::{CalDavClient, Depth, detect_encodings};
+//! use fast_dav_rs::caldav::parse_multistatus_item_stream;
+//! use futures::TryStreamExt;
 //! use anyhow::Result;
 //!
 //! #[tokio::main]
@@ -263,11 +267,11 @@
 //!     </D:propfind>"#;
 //!
 //!     let response = client.propfind_stream("large-calendar/", Depth::One, propfind_xml).await?;
-//!     let encoding = detect_encoding(response.headers());
-//!     let result = parse_multistatus_stream(response.into_body(), &[encoding]).await?;
+//!     let encodings = detect_encodings(response.headers());
+//!     let mut items = parse_multistatus_item_stream(response.into_body(), &encodings);
 //!
-//!     // Process items one by one without loading everything into memory
-//!     for item in result.items {
+//!     // Process items one by one as they arrive, without loading everything into memory
+//!     while let Some(item) = items.try_next().await? {
 //!         println!("Found item: {} with etag: {:?}",
 //!                  item.displayname.unwrap_or_default(),
 //!                  item.etag);
@@ -539,6 +543,7 @@
 //!
 //! ```no_run
 //! use fast_dav_rs::CardDavClient;
+//! use fast_dav_rs::carddav::MkAddressBook;
 //! use bytes::Bytes;
 //! use anyhow::Result;
 //!
@@ -551,16 +556,8 @@
 //!     )?;
 //!
 //!     let addressbook_path = "addressbooks/user/team/";
-//!     let addressbook_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
-//!     <C:mkaddressbook xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
-//!       <D:set>
-//!         <D:prop>
-//!           <D:displayname>Team Contacts</D:displayname>
-//!         </D:prop>
-//!       </D:set>
-//!     </C:mkaddressbook>"#;
-//!
-//!     let _ = client.mkaddressbook(addressbook_path, addressbook_xml).await?;
+//!     let addressbook = MkAddressBook::new().displayname("Team Contacts");
+//!     let _ = client.create_addressbook(addressbook_path, &addressbook).await?;
 //!
 //!     let contact_path = format!("{addressbook_path}jane.vcf");
 //!     let vcard = Bytes::from("BEGIN:VCARD\nVERSION:3.0\nFN:Jane Doe\nUID:jane-1\nEMAIL:jane@example.com\nEND:VCARD\n");
@@ -614,7 +611,7 @@
 //! ## CardDAV Streaming Large Responses
 //!
 //! ```no_run
-//! use fast_dav_rs::{CardDavClient, Depth, detect_encoding};
+//! use fast_dav_rs::{CardDavClient, Depth, detect_encodings};
 //! use fast_dav_rs::carddav::parse_multistatus_stream;
 //! use anyhow::Result;
 //!
@@ -643,8 +640,8 @@
 //!     let response = client
 //!         .report_stream(addressbook_path, Depth::One, report_xml)
 //!         .await?;
-//!     let encoding = detect_encoding(response.headers());
-//!     let result = parse_multistatus_stream(response.into_body(), &[encoding]).await?;
+//!     let encodings = detect_encodings(response.headers());
+//!     let result = parse_multistatus_stream(response.into_body(), &encodings).await?;
 //!
 //!     for item in result.items {
 //!         if let Some(data) = item.address_data {
@@ -690,11 +687,14 @@
 pub mod caldav;
 pub mod carddav;
 pub mod common;
+pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod webdav;
 
 // Backwards-compatible re-exports
 pub use caldav::streaming::{
-    parse_multistatus_bytes, parse_multistatus_bytes_visit, parse_multistatus_stream,
+    Visit, parse_multistatus_bytes, parse_multistatus_bytes_visit, parse_multistatus_stream,
     parse_multistatus_stream_visit, parse_multistatus_stream_visit_with_timeout,
     parse_multistatus_stream_with_timeout,
 };
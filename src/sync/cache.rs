@@ -0,0 +1,96 @@
+//! Local ETag cache consulted by the clients to skip unchanged downloads (`If-None-Match`)
+//! and guard writes (`If-Match`), see [`crate::webdav::WebDavClient::set_etag_cache`].
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// What the cache remembers about one resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub etag: String,
+    /// [`body_hash`] of the body last downloaded or uploaded with this ETag.
+    pub body_hash: u64,
+}
+
+/// Storage of [`CacheEntry`] values keyed by resource path (percent-encoded, as sent to the
+/// server).
+///
+/// Implement it over the application's database so the cache survives restarts;
+/// [`MemoryEtagCache`] keeps it for the lifetime of the process.
+pub trait EtagCache: Send + Sync {
+    /// Entry stored for `href`.
+    fn get<'a>(&'a self, href: &'a str) -> BoxFuture<'a, Result<Option<CacheEntry>>>;
+    /// Store the entry of `href`, replacing any previous one.
+    fn put<'a>(&'a self, href: &'a str, entry: CacheEntry) -> BoxFuture<'a, Result<()>>;
+    /// Forget `href`.
+    fn remove<'a>(&'a self, href: &'a str) -> BoxFuture<'a, Result<()>>;
+}
+
+/// In-memory [`EtagCache`].
+#[derive(Debug, Default)]
+pub struct MemoryEtagCache {
+    entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl MemoryEtagCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached resources.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CacheEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl EtagCache for MemoryEtagCache {
+    fn get<'a>(&'a self, href: &'a str) -> BoxFuture<'a, Result<Option<CacheEntry>>> {
+        Box::pin(async move { Ok(self.lock().get(href).cloned()) })
+    }
+
+    fn put<'a>(&'a self, href: &'a str, entry: CacheEntry) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.lock().insert(href.to_string(), entry);
+            Ok(())
+        })
+    }
+
+    fn remove<'a>(&'a self, href: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.lock().remove(href);
+            Ok(())
+        })
+    }
+}
+
+/// Outcome of [`crate::webdav::WebDavClient::get_cached`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CachedGet {
+    /// The server answered `304 Not Modified`: the local copy with this hash is current.
+    NotModified { etag: String, body_hash: u64 },
+    /// The resource was downloaded.
+    Fetched {
+        body: bytes::Bytes,
+        etag: Option<String>,
+    },
+}
+
+/// Hash of a resource body stored in [`CacheEntry::body_hash`].
+///
+/// 64-bit FNV-1a: unlike [`std::hash::DefaultHasher`] it is stable across builds, so it
+/// can be persisted.
+pub fn body_hash(body: &[u8]) -> u64 {
+    body.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,70 @@
+//! Resolution of write conflicts: a conditional `PUT` answered with
+//! `412 Precondition Failed` because the server copy changed since it was read (see
+//! [`crate::webdav::WebDavClient::resolve_conflict`]).
+
+use anyhow::Result;
+use bytes::Bytes;
+use std::fmt;
+use std::sync::Arc;
+
+/// Times [`ConflictPolicy::Merge`] refetches and merges again when its write conflicts
+/// with yet another change.
+pub const MAX_MERGE_ATTEMPTS: usize = 3;
+
+/// Merge callback receiving the local body and the current server body, and returning the
+/// body to write.
+pub type MergeFn = dyn Fn(&[u8], &[u8]) -> Result<Bytes> + Send + Sync;
+
+/// How to settle a conflicting write.
+#[derive(Clone)]
+pub enum ConflictPolicy {
+    /// Keep the server copy: refetch it and drop the local change.
+    ServerWins,
+    /// Keep the local change: write it unconditionally.
+    ClientWins,
+    /// Write the result of merging both bodies, conditioned on the server copy that was
+    /// merged.
+    Merge(Arc<MergeFn>),
+}
+
+impl ConflictPolicy {
+    /// [`ConflictPolicy::Merge`] with `merge(local, server)`.
+    pub fn merge<F>(merge: F) -> Self
+    where
+        F: Fn(&[u8], &[u8]) -> Result<Bytes> + Send + Sync + 'static,
+    {
+        Self::Merge(Arc::new(merge))
+    }
+}
+
+impl fmt::Debug for ConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ServerWins => f.write_str("ServerWins"),
+            Self::ClientWins => f.write_str("ClientWins"),
+            Self::Merge(_) => f.write_str("Merge(..)"),
+        }
+    }
+}
+
+/// Outcome of a resolved conflict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// The server copy was kept; the local change was dropped.
+    ServerKept { body: Bytes, etag: Option<String> },
+    /// The local body was written over the server copy.
+    ClientWritten { etag: Option<String> },
+    /// The merged body was written.
+    Merged { body: Bytes, etag: Option<String> },
+}
+
+impl ConflictResolution {
+    /// ETag of the resource once resolved, if the server sent one.
+    pub fn etag(&self) -> Option<&str> {
+        match self {
+            Self::ServerKept { etag, .. }
+            | Self::ClientWritten { etag }
+            | Self::Merged { etag, .. } => etag.as_deref(),
+        }
+    }
+}
@@ -0,0 +1,624 @@
+//! One incremental sync API over every server: `sync-collection` (RFC 6578) where
+//! supported, `getctag` polling plus ETag diffing otherwise.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::OnceCell;
+
+use crate::sync::snapshot::SyncSnapshot;
+use crate::sync::source::SyncSource;
+use crate::sync::store::{SyncStore, Tombstone};
+use crate::webdav::types::InvalidSyncTokenError;
+
+/// Times [`SyncEngine`] fetches a member again on its own when its data could not be
+/// fetched, unless configured otherwise.
+pub const DEFAULT_ITEM_RETRIES: usize = 2;
+
+/// [`SyncFailure::error`] of a member a multiget response left out.
+const MISSING_MEMBER: &str = "missing from the multiget response";
+
+/// How long [`SyncEngine`] keeps tombstones unless configured otherwise: 30 days.
+pub const DEFAULT_TOMBSTONE_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// How a [`SyncEngine`] detects changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMethod {
+    /// `sync-collection` REPORT from the stored sync token.
+    WebDavSync,
+    /// `getctag` check, then a `PROPFIND` of the member ETags diffed against the stored ones.
+    CtagPolling,
+}
+
+/// What happened to a member of a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// A change to one member of a collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub href: String,
+    pub kind: ChangeKind,
+    /// New ETag, `None` for deletions.
+    pub etag: Option<String>,
+    /// iCalendar or vCard data, when requested and returned.
+    pub data: Option<String>,
+}
+
+impl Change {
+    /// A change as reported by the server, which does not tell creations from updates.
+    pub(crate) fn reported(
+        href: String,
+        etag: Option<String>,
+        data: Option<String>,
+        deleted: bool,
+    ) -> Self {
+        if deleted {
+            return Self::deleted(href);
+        }
+        Self {
+            href,
+            kind: ChangeKind::Updated,
+            etag,
+            data,
+        }
+    }
+
+    fn deleted(href: String) -> Self {
+        Self {
+            href,
+            kind: ChangeKind::Deleted,
+            etag: None,
+            data: None,
+        }
+    }
+}
+
+/// How much member data a sync run brought, see [`SyncEngine::body_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Every created and updated member came with its data.
+    FullBody,
+    /// Some or all created and updated members came without their data, to fetch on demand
+    /// with [`SyncEngine::fetch_bodies`].
+    MetadataFirst,
+}
+
+/// Outcome of [`SyncEngine::sync`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncRun {
+    pub method: SyncMethod,
+    pub changes: Vec<Change>,
+    /// The server rejected the stored sync token (see [`InvalidSyncTokenError`]), so the
+    /// collection was synced again from scratch; `changes` still only lists the differences
+    /// with the stored state.
+    pub full_resync: bool,
+    pub mode: SyncMode,
+    /// Changed members whose data could not be fetched, even when retried on their own;
+    /// they are still listed in `changes`, without data.
+    pub failed: Vec<SyncFailure>,
+}
+
+/// A member of a [`SyncRun`] whose data could not be fetched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncFailure {
+    pub href: String,
+    /// The last error: a failed request, or the member missing from the response.
+    pub error: String,
+}
+
+impl SyncRun {
+    /// `true` when nothing changed since the previous run.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Hrefs of the created and updated members whose data was not downloaded.
+    pub fn deferred(&self) -> impl Iterator<Item = &str> {
+        self.changes
+            .iter()
+            .filter(|c| c.kind != ChangeKind::Deleted && c.data.is_none())
+            .map(|c| c.href.as_str())
+    }
+}
+
+/// Incremental sync of collections, persisting its state in a [`SyncStore`].
+///
+/// The first [`sync`](Self::sync) probes the server with `supports_webdav_sync`: with
+/// WebDAV-Sync, changes come from `sync-collection`; without it, the engine reads the
+/// collection `getctag` and, when it moved (or the server has none), lists the member ETags
+/// and diffs them against the stored ones, fetching changed members with a multiget. Either
+/// way the caller gets the same [`Change`]s, creations told apart from updates by the stored
+/// ETags.
+///
+/// Deletions are also recorded as [`Tombstone`]s, kept for the
+/// [retention](Self::tombstone_retention) period so consumers that fall behind can still
+/// propagate them.
+///
+/// # Example
+///
+/// ```no_run
+/// use fast_dav_rs::CalDavClient;
+/// use fast_dav_rs::sync::{MemoryTokenStore, SyncEngine};
+/// use std::sync::Arc;
+///
+/// # async fn demo() -> anyhow::Result<()> {
+/// let client = CalDavClient::new("https://cal.example.com/dav/", Some("jane"), Some("secret"))?;
+/// let engine = SyncEngine::new(client, Arc::new(MemoryTokenStore::new()));
+/// for change in engine.sync("calendars/jane/work/").await?.changes {
+///     println!("{:?} {}", change.kind, change.href);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SyncEngine<C> {
+    client: C,
+    store: Arc<dyn SyncStore>,
+    include_data: bool,
+    hydrate: bool,
+    body_budget: Option<u64>,
+    item_retries: usize,
+    limit: Option<u32>,
+    tombstone_retention: Duration,
+    method: OnceCell<SyncMethod>,
+}
+
+impl<C: SyncSource> SyncEngine<C> {
+    /// Engine fetching member data, with the sync method probed on first use.
+    pub fn new(client: C, store: Arc<dyn SyncStore>) -> Self {
+        Self {
+            client,
+            store,
+            include_data: true,
+            hydrate: false,
+            body_budget: None,
+            item_retries: DEFAULT_ITEM_RETRIES,
+            limit: None,
+            tombstone_retention: DEFAULT_TOMBSTONE_RETENTION,
+            method: OnceCell::new(),
+        }
+    }
+
+    /// Whether changes carry the member data (default `true`); without it only hrefs and
+    /// ETags are reported.
+    pub fn include_data(mut self, include_data: bool) -> Self {
+        self.include_data = include_data;
+        self
+    }
+
+    /// Fetch the data of created and updated members after the sync instead of with it
+    /// (default `false`), even when [`include_data`](Self::include_data) is off.
+    ///
+    /// `sync-collection` then runs metadata-only and the changed hrefs go through the
+    /// client's multiget, chunked and with bounded concurrency (see
+    /// `CalDavClient::set_multiget_chunking`), so large syncs do not arrive as one huge
+    /// response.
+    pub fn hydrate(mut self, hydrate: bool) -> Self {
+        self.hydrate = hydrate;
+        self
+    }
+
+    /// Download about `bytes` of member data per run at most, for metered or slow links.
+    ///
+    /// Runs then sync metadata first and fetch the data of the changed members in
+    /// multigets sized from the bodies seen so far, until the budget is spent; the other
+    /// members are left without data ([`SyncMode::MetadataFirst`], see
+    /// [`SyncRun::deferred`]). Only applies when data is wanted, through
+    /// [`include_data`](Self::include_data) or [`hydrate`](Self::hydrate).
+    pub fn body_budget(mut self, bytes: u64) -> Self {
+        self.body_budget = Some(bytes);
+        self
+    }
+
+    /// How many times a member whose data could not be fetched (its multiget failed, or
+    /// left it out) is fetched again on its own (default [`DEFAULT_ITEM_RETRIES`]). Members
+    /// still failing are reported in [`SyncRun::failed`] instead of failing the run.
+    pub fn item_retries(mut self, retries: usize) -> Self {
+        self.item_retries = retries;
+        self
+    }
+
+    /// Maximum number of results per `sync-collection` round.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// How long tombstones are kept (default [`DEFAULT_TOMBSTONE_RETENTION`]). Older ones
+    /// are no longer returned by [`tombstones`](Self::tombstones) and leave the store with
+    /// the next sync reporting changes.
+    pub fn tombstone_retention(mut self, retention: Duration) -> Self {
+        self.tombstone_retention = retention;
+        self
+    }
+
+    /// Use `method` instead of probing the server.
+    pub fn method(mut self, method: SyncMethod) -> Self {
+        self.method = OnceCell::new_with(Some(method));
+        self
+    }
+
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    pub fn store(&self) -> &Arc<dyn SyncStore> {
+        &self.store
+    }
+
+    /// The method in use, probing the server on the first call.
+    pub async fn sync_method(&self) -> Result<SyncMethod> {
+        self.method
+            .get_or_try_init(|| async {
+                Ok(if self.client.supports_webdav_sync().await? {
+                    SyncMethod::WebDavSync
+                } else {
+                    SyncMethod::CtagPolling
+                })
+            })
+            .await
+            .copied()
+    }
+
+    /// Changes to `collection` since the previous run (everything on the first one), saving
+    /// the new state once they are known.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a request fails or the store cannot be read or written; the
+    /// stored state is then left unchanged. Failures to fetch the data of single members
+    /// are reported in [`SyncRun::failed`] instead.
+    pub async fn sync(&self, collection: &str) -> Result<SyncRun> {
+        let method = self.sync_method().await?;
+        let mut failed = Vec::new();
+        let (changes, full_resync) = match method {
+            SyncMethod::WebDavSync => self.sync_webdav(collection, &mut failed).await?,
+            SyncMethod::CtagPolling => (self.poll_ctag(collection, &mut failed).await?, false),
+        };
+        let wants_data = self.include_data || self.hydrate;
+        let mode = if wants_data
+            && changes
+                .iter()
+                .all(|c| c.kind == ChangeKind::Deleted || c.data.is_some())
+        {
+            SyncMode::FullBody
+        } else {
+            SyncMode::MetadataFirst
+        };
+        Ok(SyncRun {
+            method,
+            changes,
+            full_resync,
+            mode,
+            failed,
+        })
+    }
+
+    /// Fetch the data of the members at `hrefs`, e.g. those a [`SyncMode::MetadataFirst`]
+    /// run [deferred](SyncRun::deferred). The stored state is left unchanged.
+    pub async fn fetch_bodies(&self, collection: &str, hrefs: &[String]) -> Result<Vec<Change>> {
+        self.client.fetch(collection, hrefs).await
+    }
+
+    /// Tombstones of `collection` still within the retention period, oldest first.
+    pub async fn tombstones(&self, collection: &str) -> Result<Vec<Tombstone>> {
+        let now = SystemTime::now();
+        let mut tombstones: Vec<Tombstone> = self
+            .store
+            .tombstones(collection)
+            .await?
+            .into_iter()
+            .filter(|t| !t.is_expired(self.tombstone_retention, now))
+            .collect();
+        tombstones.sort_by_key(|t| t.deleted_at);
+        Ok(tombstones)
+    }
+
+    /// Snapshot of the stored state of `collections`, see [`SyncSnapshot::export`].
+    pub async fn export_state<I, P>(&self, collections: I) -> Result<SyncSnapshot>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        SyncSnapshot::export(self.store.as_ref(), collections).await
+    }
+
+    /// Replace the stored state of the collections of `snapshot`, see
+    /// [`SyncSnapshot::restore`].
+    pub async fn import_state(&self, snapshot: &SyncSnapshot) -> Result<()> {
+        snapshot.restore(self.store.as_ref()).await
+    }
+
+    async fn sync_webdav(
+        &self,
+        collection: &str,
+        failed: &mut Vec<SyncFailure>,
+    ) -> Result<(Vec<Change>, bool)> {
+        let mut token = self.store.sync_token(collection).await?;
+        let inline_data = self.include_data && !self.hydrate && self.body_budget.is_none();
+        let mut full_resync = false;
+        let (mut changes, new_token) = match self
+            .client
+            .changes_since(collection, token.as_deref(), self.limit, inline_data)
+            .await
+        {
+            Err(err) if err.downcast_ref::<InvalidSyncTokenError>().is_some() => {
+                token = None;
+                full_resync = true;
+                self.client
+                    .changes_since(collection, None, self.limit, inline_data)
+                    .await?
+            }
+            result => result?,
+        };
+        let mut etags = self.store.etags(collection).await?;
+        if token.is_none() {
+            // A full sync lists every member: unchanged ones are not news, and the stored
+            // ones it misses are gone.
+            let listed: HashSet<&str> = changes.iter().map(|c| c.href.as_str()).collect();
+            let mut gone: Vec<String> = etags
+                .keys()
+                .filter(|href| !listed.contains(href.as_str()))
+                .cloned()
+                .collect();
+            gone.sort();
+            changes.retain(|c| c.etag.is_none() || c.etag.as_ref() != etags.get(&c.href));
+            changes.extend(gone.into_iter().map(Change::deleted));
+        }
+        let mut deleted = Vec::new();
+        for change in &mut changes {
+            if change.kind == ChangeKind::Deleted {
+                deleted.push((change.href.clone(), etags.remove(&change.href)));
+                continue;
+            }
+            let etag = change.etag.clone().unwrap_or_default();
+            if etags.insert(change.href.clone(), etag).is_none() {
+                change.kind = ChangeKind::Created;
+            }
+        }
+        if self.hydrate || (self.include_data && !inline_data) {
+            failed.extend(self.fetch_data(collection, &mut changes, &mut etags).await);
+        }
+        self.record_tombstones(collection, deleted, &changes)
+            .await?;
+        self.store.set_etags(collection, etags).await?;
+        if new_token.is_some() {
+            self.store.set_sync_token(collection, new_token).await?;
+        }
+        Ok((changes, full_resync))
+    }
+
+    async fn poll_ctag(
+        &self,
+        collection: &str,
+        failed: &mut Vec<SyncFailure>,
+    ) -> Result<Vec<Change>> {
+        let ctag = self.client.ctag(collection).await?;
+        if ctag.is_some() && ctag == self.store.ctag(collection).await? {
+            return Ok(Vec::new());
+        }
+        let known = self.store.etags(collection).await?;
+        let listing = self.client.etags(collection).await?;
+        let mut changes = diff_etags(&known, listing.iter().map(|(href, etag)| (href, etag)));
+        let mut etags: HashMap<String, String> = listing.into_iter().collect();
+
+        if self.include_data || self.hydrate {
+            failed.extend(self.fetch_data(collection, &mut changes, &mut etags).await);
+        }
+
+        let deleted = changes
+            .iter()
+            .filter(|c| c.kind == ChangeKind::Deleted)
+            .map(|c| (c.href.clone(), known.get(&c.href).cloned()))
+            .collect();
+        self.record_tombstones(collection, deleted, &changes)
+            .await?;
+        self.store.set_etags(collection, etags).await?;
+        self.store.set_ctag(collection, ctag).await?;
+        Ok(changes)
+    }
+
+    /// Fill in the data of the created and updated `changes` with multigets (as many as the
+    /// body budget allows), recording the ETags they return, and return the members that
+    /// could not be fetched even on their own. Members gone in the meantime keep no data.
+    async fn fetch_data(
+        &self,
+        collection: &str,
+        changes: &mut [Change],
+        etags: &mut HashMap<String, String>,
+    ) -> Vec<SyncFailure> {
+        let changed: Vec<String> = changes
+            .iter()
+            .filter(|c| c.kind != ChangeKind::Deleted)
+            .map(|c| c.href.clone())
+            .collect();
+        if changed.is_empty() {
+            return Vec::new();
+        }
+        let mut fetched: HashMap<String, Change> = HashMap::new();
+        let mut failed = Vec::new();
+        let mut pending = &changed[..];
+        let mut spent = 0u64;
+        while !pending.is_empty() {
+            let take = match self.body_budget {
+                None => pending.len(),
+                Some(budget) => {
+                    let remaining = budget.saturating_sub(spent);
+                    match fetched.len() as u64 {
+                        _ if remaining == 0 => break,
+                        // Nothing seen yet: probe with one member.
+                        0 => 1,
+                        seen => (remaining / (spent / seen).max(1)) as usize,
+                    }
+                }
+            };
+            if take == 0 {
+                break;
+            }
+            let (batch, rest) = pending.split_at(take.min(pending.len()));
+            pending = rest;
+            let error = match self.client.fetch(collection, batch).await {
+                Ok(objects) => {
+                    for object in objects {
+                        spent += object.data.as_ref().map_or(0, |data| data.len() as u64);
+                        fetched.insert(object.href.clone(), object);
+                    }
+                    MISSING_MEMBER.to_string()
+                }
+                Err(err) => format!("{err:#}"),
+            };
+            let missing: Vec<&String> = batch
+                .iter()
+                .filter(|href| !fetched.contains_key(*href))
+                .collect();
+            for href in missing {
+                match self.refetch(collection, href, error.clone()).await {
+                    Ok(object) => {
+                        spent += object.data.as_ref().map_or(0, |data| data.len() as u64);
+                        fetched.insert(href.clone(), object);
+                    }
+                    Err(failure) => failed.push(failure),
+                }
+            }
+        }
+        for change in changes {
+            let Some(object) = fetched.remove(&change.href) else {
+                continue;
+            };
+            change.data = object.data;
+            if let Some(etag) = object.etag {
+                etags.insert(change.href.clone(), etag.clone());
+                change.etag = Some(etag);
+            }
+        }
+        failed
+    }
+
+    /// Fetch the member at `href` on its own, up to `item_retries` times; `error` is the
+    /// failure reported when no attempt is made.
+    async fn refetch(
+        &self,
+        collection: &str,
+        href: &String,
+        mut error: String,
+    ) -> std::result::Result<Change, SyncFailure> {
+        for _ in 0..self.item_retries {
+            match self
+                .client
+                .fetch(collection, std::slice::from_ref(href))
+                .await
+            {
+                Ok(objects) => match objects.into_iter().find(|o| o.href == *href) {
+                    Some(object) => return Ok(object),
+                    None => error = MISSING_MEMBER.to_string(),
+                },
+                Err(err) => error = format!("{err:#}"),
+            }
+        }
+        Err(SyncFailure {
+            href: href.clone(),
+            error,
+        })
+    }
+
+    /// Add a tombstone per `(href, last etag)` in `deleted`, drop those of members `changes`
+    /// brought back and those past the retention period.
+    async fn record_tombstones(
+        &self,
+        collection: &str,
+        deleted: Vec<(String, Option<String>)>,
+        changes: &[Change],
+    ) -> Result<()> {
+        let revived: HashSet<&str> = changes
+            .iter()
+            .filter(|c| c.kind != ChangeKind::Deleted)
+            .map(|c| c.href.as_str())
+            .collect();
+        if deleted.is_empty() && revived.is_empty() {
+            return Ok(());
+        }
+        let now = SystemTime::now();
+        let mut tombstones = self.store.tombstones(collection).await?;
+        let before = tombstones.len();
+        tombstones.retain(|t| {
+            !revived.contains(t.href.as_str()) && !t.is_expired(self.tombstone_retention, now)
+        });
+        if deleted.is_empty() && tombstones.len() == before {
+            return Ok(());
+        }
+        tombstones.extend(deleted.into_iter().map(|(href, etag)| Tombstone {
+            href,
+            deleted_at: now,
+            etag,
+        }));
+        self.store.set_tombstones(collection, tombstones).await
+    }
+}
+
+/// Classify a server listing of `(href, etag)` pairs against the `known` ETags of a local
+/// copy: new hrefs are [`ChangeKind::Created`], different ETags [`ChangeKind::Updated`] and
+/// known hrefs missing from the listing [`ChangeKind::Deleted`] (sorted by href, after the
+/// others). Unchanged members are left out.
+///
+/// This is the diff [`SyncEngine`] runs on servers without WebDAV-Sync; use it directly to
+/// reconcile a listing obtained another way, e.g. from `propfind_resources`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::sync::{ChangeKind, diff_etags};
+/// use std::collections::HashMap;
+///
+/// let known = HashMap::from([
+///     ("/cal/a.ics".to_string(), "\"1\"".to_string()),
+///     ("/cal/b.ics".to_string(), "\"1\"".to_string()),
+/// ]);
+/// let listing = [("/cal/a.ics", "\"2\""), ("/cal/c.ics", "\"1\"")];
+///
+/// let changes = diff_etags(&known, listing);
+/// let kinds: Vec<_> = changes.iter().map(|c| (c.href.as_str(), c.kind)).collect();
+/// assert_eq!(
+///     kinds,
+///     [
+///         ("/cal/a.ics", ChangeKind::Updated),
+///         ("/cal/c.ics", ChangeKind::Created),
+///         ("/cal/b.ics", ChangeKind::Deleted),
+///     ]
+/// );
+/// ```
+pub fn diff_etags<I, H, E>(known: &HashMap<String, String>, listing: I) -> Vec<Change>
+where
+    I: IntoIterator<Item = (H, E)>,
+    H: AsRef<str>,
+    E: AsRef<str>,
+{
+    let mut changes = Vec::new();
+    let mut listed = HashSet::new();
+    for (href, etag) in listing {
+        let (href, etag) = (href.as_ref(), etag.as_ref());
+        listed.insert(href.to_string());
+        let kind = match known.get(href) {
+            None => ChangeKind::Created,
+            Some(previous) if previous != etag => ChangeKind::Updated,
+            Some(_) => continue,
+        };
+        changes.push(Change {
+            href: href.to_string(),
+            kind,
+            etag: Some(etag.to_string()),
+            data: None,
+        });
+    }
+    let mut gone: Vec<&String> = known
+        .keys()
+        .filter(|href| !listed.contains(*href))
+        .collect();
+    gone.sort();
+    changes.extend(gone.into_iter().cloned().map(Change::deleted));
+    changes
+}
@@ -0,0 +1,31 @@
+//! Incremental synchronisation helpers shared by the CalDAV and CardDAV clients.
+
+pub mod cache;
+pub mod conflict;
+pub mod engine;
+pub mod queue;
+pub mod scheduler;
+pub mod snapshot;
+pub mod source;
+pub mod store;
+pub mod two_way;
+pub mod watch;
+
+pub use cache::{CacheEntry, CachedGet, EtagCache, MemoryEtagCache, body_hash};
+pub use conflict::{ConflictPolicy, ConflictResolution, MAX_MERGE_ATTEMPTS, MergeFn};
+pub use engine::{
+    Change, ChangeKind, DEFAULT_ITEM_RETRIES, DEFAULT_TOMBSTONE_RETENTION, SyncEngine, SyncFailure,
+    SyncMethod, SyncMode, SyncRun, diff_etags,
+};
+pub use queue::{
+    MemoryMutationQueue, Mutation, MutationQueue, Precondition, QueuedMutation, ReplayOutcome,
+    ReplayReport, ReplayResult,
+};
+pub use scheduler::{
+    DEFAULT_ACTIVITY_WINDOW, DEFAULT_HOST_CONCURRENCY, ScheduledRun, SyncScheduler,
+};
+pub use snapshot::{SNAPSHOT_VERSION, SyncSnapshot};
+pub use source::SyncSource;
+pub use store::{CollectionState, MemoryTokenStore, SyncStore, TokenStore, Tombstone};
+pub use two_way::{LocalChange, PushPullRun};
+pub use watch::{ChangeEvent, ChangeStream, MAX_WATCH_BACKOFF};
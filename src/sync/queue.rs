@@ -0,0 +1,198 @@
+//! Offline mutation queue: `PUT`/`DELETE` intents recorded while the server is unreachable
+//! and replayed later (see [`crate::webdav::WebDavClient::replay_mutations`]).
+
+use anyhow::Result;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::sync::conflict::ConflictResolution;
+
+/// Condition a replayed mutation is sent with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Precondition {
+    /// Unconditional.
+    None,
+    /// `If-Match`: the resource still has this ETag.
+    IfMatch(String),
+    /// `If-None-Match: *`: the resource does not exist yet.
+    IfNoneMatch,
+}
+
+/// A write recorded for later replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mutation {
+    Put {
+        path: String,
+        content_type: String,
+        body: Bytes,
+        precondition: Precondition,
+    },
+    Delete {
+        path: String,
+        precondition: Precondition,
+    },
+}
+
+impl Mutation {
+    /// Unconditional `PUT` of `body`.
+    pub fn put(path: &str, content_type: &str, body: Bytes) -> Self {
+        Self::Put {
+            path: path.to_string(),
+            content_type: content_type.to_string(),
+            body,
+            precondition: Precondition::None,
+        }
+    }
+
+    /// Unconditional `DELETE`.
+    pub fn delete(path: &str) -> Self {
+        Self::Delete {
+            path: path.to_string(),
+            precondition: Precondition::None,
+        }
+    }
+
+    /// Send the mutation with `If-Match: etag`.
+    pub fn if_match(self, etag: &str) -> Self {
+        self.with_precondition(Precondition::IfMatch(etag.to_string()))
+    }
+
+    /// Send the mutation with `If-None-Match: *`, for resources created offline.
+    pub fn if_none_match(self) -> Self {
+        self.with_precondition(Precondition::IfNoneMatch)
+    }
+
+    /// Path of the resource written.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Put { path, .. } | Self::Delete { path, .. } => path,
+        }
+    }
+
+    /// Condition the mutation is sent with.
+    pub fn precondition(&self) -> &Precondition {
+        match self {
+            Self::Put { precondition, .. } | Self::Delete { precondition, .. } => precondition,
+        }
+    }
+
+    fn with_precondition(mut self, condition: Precondition) -> Self {
+        match &mut self {
+            Self::Put { precondition, .. } | Self::Delete { precondition, .. } => {
+                *precondition = condition
+            }
+        }
+        self
+    }
+}
+
+/// A mutation waiting in a [`MutationQueue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedMutation {
+    /// Identifier assigned by the queue.
+    pub id: u64,
+    pub mutation: Mutation,
+}
+
+/// Ordered storage of pending mutations.
+///
+/// Implement it over a database or file so the intents survive restarts;
+/// [`MemoryMutationQueue`] keeps them for the lifetime of the process.
+pub trait MutationQueue: Send + Sync {
+    /// Append `mutation` and return its identifier.
+    fn push<'a>(&'a self, mutation: Mutation) -> BoxFuture<'a, Result<u64>>;
+    /// Pending mutations, oldest first.
+    fn pending<'a>(&'a self) -> BoxFuture<'a, Result<Vec<QueuedMutation>>>;
+    /// Drop the mutation `id` once replayed.
+    fn remove<'a>(&'a self, id: u64) -> BoxFuture<'a, Result<()>>;
+}
+
+/// In-memory [`MutationQueue`].
+#[derive(Debug, Default)]
+pub struct MemoryMutationQueue {
+    inner: Mutex<(u64, VecDeque<QueuedMutation>)>,
+}
+
+impl MemoryMutationQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of pending mutations.
+    pub fn len(&self) -> usize {
+        self.lock().1.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().1.is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, (u64, VecDeque<QueuedMutation>)> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl MutationQueue for MemoryMutationQueue {
+    fn push<'a>(&'a self, mutation: Mutation) -> BoxFuture<'a, Result<u64>> {
+        Box::pin(async move {
+            let mut inner = self.lock();
+            inner.0 += 1;
+            let id = inner.0;
+            inner.1.push_back(QueuedMutation { id, mutation });
+            Ok(id)
+        })
+    }
+
+    fn pending<'a>(&'a self) -> BoxFuture<'a, Result<Vec<QueuedMutation>>> {
+        Box::pin(async move { Ok(self.lock().1.iter().cloned().collect()) })
+    }
+
+    fn remove<'a>(&'a self, id: u64) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.lock().1.retain(|queued| queued.id != id);
+            Ok(())
+        })
+    }
+}
+
+/// What happened to one replayed mutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayResult {
+    /// The server accepted the mutation; `etag` is the new ETag of a `PUT`.
+    Applied { etag: Option<String> },
+    /// A `PUT` conflicted (`412`) and was settled by the conflict policy.
+    Resolved(ConflictResolution),
+    /// The server refused the mutation with this status, including a conflicting `DELETE`
+    /// the policy did not force.
+    Rejected { status: u16 },
+}
+
+/// Outcome of one replayed mutation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayOutcome {
+    pub mutation: QueuedMutation,
+    pub result: ReplayResult,
+}
+
+/// Outcome of [`crate::webdav::WebDavClient::replay_mutations`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Mutations replayed and removed from the queue, in order.
+    pub outcomes: Vec<ReplayOutcome>,
+    /// Error that stopped the replay (e.g. the server is still unreachable); the mutation
+    /// that failed and the following ones stay queued.
+    pub interrupted: Option<String>,
+}
+
+impl ReplayReport {
+    /// `true` when the whole queue was replayed without rejected mutations.
+    pub fn is_success(&self) -> bool {
+        self.interrupted.is_none()
+            && self
+                .outcomes
+                .iter()
+                .all(|outcome| !matches!(outcome.result, ReplayResult::Rejected { .. }))
+    }
+}
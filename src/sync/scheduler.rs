@@ -0,0 +1,271 @@
+//! Scheduler running the [`SyncEngine`] syncs of many collections, spread over time and
+//! within per-host limits.
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, FuturesUnordered, StreamExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{Instant, sleep_until};
+
+use crate::sync::engine::{SyncEngine, SyncRun};
+use crate::sync::source::SyncSource;
+use crate::sync::watch::random_u64;
+
+/// Syncs a host runs at once unless configured otherwise.
+pub const DEFAULT_HOST_CONCURRENCY: usize = 2;
+
+/// How long a collection counts as active after a sync reported changes, unless configured
+/// otherwise: 10 minutes.
+pub const DEFAULT_ACTIVITY_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Outcome of one scheduled sync.
+#[derive(Debug)]
+pub struct ScheduledRun {
+    pub host: String,
+    pub collection: String,
+    pub result: Result<SyncRun>,
+}
+
+struct Job<C> {
+    host: String,
+    collection: String,
+    engine: Arc<SyncEngine<C>>,
+    interval: Duration,
+    due: Instant,
+    last_activity: Option<Instant>,
+    running: bool,
+}
+
+#[derive(Default)]
+struct Host {
+    running: usize,
+    next_start: Option<Instant>,
+}
+
+/// Periodic sync of many collections, for services keeping hundreds of calendars or
+/// addressbooks up to date.
+///
+/// Each collection is synced every `interval`, the first run delayed by a random part of
+/// it so collections added together spread over time. Per host, at most
+/// [`host_concurrency`](Self::host_concurrency) syncs run at once and, with
+/// [`host_rate`](Self::host_rate), they start at a capped rate. Collections whose latest
+/// sync reported changes count as active for the [activity
+/// window](Self::activity_window): they are synced four times as often and go first when
+/// their host is busy.
+///
+/// # Example
+///
+/// ```no_run
+/// use fast_dav_rs::CalDavClient;
+/// use fast_dav_rs::sync::{MemoryTokenStore, SyncEngine, SyncScheduler};
+/// use futures::StreamExt;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// # async fn demo() -> anyhow::Result<()> {
+/// let client = CalDavClient::new("https://cal.example.com/dav/", Some("jane"), Some("secret"))?;
+/// let engine = Arc::new(SyncEngine::new(client, Arc::new(MemoryTokenStore::new())));
+///
+/// let mut scheduler = SyncScheduler::new().host_concurrency(4).host_rate(2.0);
+/// for calendar in ["calendars/jane/work/", "calendars/jane/home/"] {
+///     scheduler.add("cal.example.com", engine.clone(), calendar, Duration::from_secs(300));
+/// }
+/// let mut runs = scheduler.run();
+/// while let Some(run) = runs.next().await {
+///     match run.result {
+///         Ok(sync) => println!("{}: {} changes", run.collection, sync.changes.len()),
+///         Err(err) => eprintln!("{}: {err:#}", run.collection),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SyncScheduler<C> {
+    jobs: Vec<Job<C>>,
+    host_concurrency: usize,
+    host_spacing: Option<Duration>,
+    activity_window: Duration,
+}
+
+impl<C> Default for SyncScheduler<C> {
+    fn default() -> Self {
+        Self {
+            jobs: Vec::new(),
+            host_concurrency: DEFAULT_HOST_CONCURRENCY,
+            host_spacing: None,
+            activity_window: DEFAULT_ACTIVITY_WINDOW,
+        }
+    }
+}
+
+impl<C: SyncSource + 'static> SyncScheduler<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of syncs running at once against one host (default
+    /// [`DEFAULT_HOST_CONCURRENCY`], at least 1).
+    pub fn host_concurrency(mut self, max: usize) -> Self {
+        self.host_concurrency = max.max(1);
+        self
+    }
+
+    /// Maximum number of syncs started per second against one host (unlimited by default).
+    /// A sync sends a few requests, so this caps the request rate at a small multiple.
+    pub fn host_rate(mut self, per_second: f64) -> Self {
+        self.host_spacing = (per_second > 0.0).then(|| Duration::from_secs_f64(1.0 / per_second));
+        self
+    }
+
+    /// How long a collection stays active after a sync reported changes (default
+    /// [`DEFAULT_ACTIVITY_WINDOW`]).
+    pub fn activity_window(mut self, window: Duration) -> Self {
+        self.activity_window = window;
+        self
+    }
+
+    /// Sync `collection` with `engine` every `interval`; `host` groups the collections
+    /// whose syncs share the per-host limits, typically the server host name.
+    pub fn add(
+        &mut self,
+        host: &str,
+        engine: Arc<SyncEngine<C>>,
+        collection: &str,
+        interval: Duration,
+    ) -> &mut Self {
+        let offset = interval.mul_f64((random_u64() % 1000) as f64 / 1000.0);
+        self.jobs.push(Job {
+            host: host.to_string(),
+            collection: collection.to_string(),
+            engine,
+            interval,
+            due: Instant::now() + offset,
+            last_activity: None,
+            running: false,
+        });
+        self
+    }
+
+    /// Number of scheduled collections.
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Start syncing: the stream yields the outcome of every sync as it completes, and ends
+    /// only when no collection was added.
+    pub fn run(self) -> BoxStream<'static, ScheduledRun> {
+        struct State<C> {
+            scheduler: SyncScheduler<C>,
+            hosts: HashMap<String, Host>,
+            running: FuturesUnordered<BoxFuture<'static, (usize, Result<SyncRun>)>>,
+        }
+
+        let state = State {
+            scheduler: self,
+            hosts: HashMap::new(),
+            running: FuturesUnordered::new(),
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if state.scheduler.jobs.is_empty() {
+                    return None;
+                }
+                let wake = state.scheduler.start_due(&mut state.hosts, &state.running);
+                if state.running.is_empty() {
+                    sleep_until(wake).await;
+                    continue;
+                }
+                let finished = tokio::select! {
+                    finished = state.running.next() => finished,
+                    _ = sleep_until(wake) => None,
+                };
+                let Some((index, result)) = finished else {
+                    continue;
+                };
+                let scheduler = &mut state.scheduler;
+                let activity_window = scheduler.activity_window;
+                let job = &mut scheduler.jobs[index];
+                let now = Instant::now();
+                if let Some(host) = state.hosts.get_mut(&job.host) {
+                    host.running -= 1;
+                }
+                job.running = false;
+                if result.as_ref().is_ok_and(|run| !run.is_empty()) {
+                    job.last_activity = Some(now);
+                }
+                let active = job.is_active(now, activity_window);
+                job.due = now
+                    + if active {
+                        job.interval / 4
+                    } else {
+                        job.interval
+                    };
+                let run = ScheduledRun {
+                    host: job.host.clone(),
+                    collection: job.collection.clone(),
+                    result,
+                };
+                return Some((run, state));
+            }
+        })
+        .boxed()
+    }
+
+    /// Start the due syncs the host limits allow, active collections and then the longest
+    /// overdue first, and return when to look again.
+    fn start_due(
+        &mut self,
+        hosts: &mut HashMap<String, Host>,
+        running: &FuturesUnordered<BoxFuture<'static, (usize, Result<SyncRun>)>>,
+    ) -> Instant {
+        let now = Instant::now();
+        let mut order: Vec<usize> = (0..self.jobs.len())
+            .filter(|&i| !self.jobs[i].running)
+            .collect();
+        order.sort_by_key(|&i| {
+            let job = &self.jobs[i];
+            (!job.is_active(now, self.activity_window), job.due)
+        });
+
+        // Without anything due, sleep until the next job is.
+        let mut wake = now + Duration::from_secs(3600);
+        for index in order {
+            let job = &mut self.jobs[index];
+            if job.due > now {
+                wake = wake.min(job.due);
+                continue;
+            }
+            let host = hosts.entry(job.host.clone()).or_default();
+            if host.running >= self.host_concurrency {
+                continue;
+            }
+            if let Some(next_start) = host.next_start.filter(|start| *start > now) {
+                wake = wake.min(next_start);
+                continue;
+            }
+            host.running += 1;
+            host.next_start = self.host_spacing.map(|spacing| now + spacing);
+            job.running = true;
+            let engine = Arc::clone(&job.engine);
+            let collection = job.collection.clone();
+            running.push(Box::pin(
+                async move { (index, engine.sync(&collection).await) },
+            ));
+        }
+        wake
+    }
+}
+
+impl<C> Job<C> {
+    fn is_active(&self, now: Instant, window: Duration) -> bool {
+        self.last_activity
+            .is_some_and(|activity| now.duration_since(activity) < window)
+    }
+}
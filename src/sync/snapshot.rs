@@ -0,0 +1,188 @@
+//! Export and import of the sync state of collections as one [`SyncSnapshot`], serializable
+//! with serde (`serde` feature) to persist it atomically, migrate it between stores or move
+//! it to another device.
+
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+
+use crate::sync::store::{CollectionState, SyncStore};
+
+/// Format version written by [`SyncSnapshot::new`]; snapshots from newer versions are
+/// rejected by [`SyncSnapshot::restore`].
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Sync state of several collections, by collection path.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::sync::{MemoryTokenStore, SyncSnapshot, TokenStore};
+///
+/// # async fn demo() -> anyhow::Result<()> {
+/// let store = MemoryTokenStore::new();
+/// store.set_sync_token("calendars/jane/work/", Some("token-1".into())).await?;
+///
+/// let snapshot = store.snapshot();
+/// let copy = MemoryTokenStore::new();
+/// snapshot.restore(&copy).await?;
+/// assert_eq!(copy.sync_token("calendars/jane/work/").await?.as_deref(), Some("token-1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncSnapshot {
+    pub version: u32,
+    pub collections: BTreeMap<String, CollectionState>,
+}
+
+impl Default for SyncSnapshot {
+    fn default() -> Self {
+        Self::new(BTreeMap::new())
+    }
+}
+
+impl SyncSnapshot {
+    /// Snapshot of `collections` in the current format.
+    pub fn new(collections: BTreeMap<String, CollectionState>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            collections,
+        }
+    }
+
+    /// Read the state of `collections` from `store`.
+    pub async fn export<S, I, P>(store: &S, collections: I) -> Result<Self>
+    where
+        S: SyncStore + ?Sized,
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        let mut states = BTreeMap::new();
+        for collection in collections {
+            let collection = collection.as_ref();
+            let state = CollectionState {
+                sync_token: store.sync_token(collection).await?,
+                ctag: store.ctag(collection).await?,
+                etags: store.etags(collection).await?,
+                tombstones: store.tombstones(collection).await?,
+            };
+            states.insert(collection.to_string(), state);
+        }
+        Ok(Self::new(states))
+    }
+
+    /// Write the state of every collection of the snapshot to `store`, replacing what it
+    /// held for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the snapshot comes from a newer format version, or a write
+    /// fails (leaving the collections before it restored).
+    pub async fn restore<S: SyncStore + ?Sized>(&self, store: &S) -> Result<()> {
+        if self.version > SNAPSHOT_VERSION {
+            return Err(anyhow!(
+                "unsupported sync snapshot version {} (latest is {})",
+                self.version,
+                SNAPSHOT_VERSION
+            ));
+        }
+        for (collection, state) in &self.collections {
+            store
+                .set_sync_token(collection, state.sync_token.clone())
+                .await?;
+            store.set_ctag(collection, state.ctag.clone()).await?;
+            store.set_etags(collection, state.etags.clone()).await?;
+            store
+                .set_tombstones(collection, state.tombstones.clone())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+    use std::fmt;
+
+    use super::SyncSnapshot;
+    use crate::sync::store::{CollectionState, Tombstone};
+
+    /// `Serialize`/`Deserialize` for a struct with public fields, as a struct of the same
+    /// field names (unknown fields are ignored).
+    macro_rules! serde_struct {
+        ($ty:ident { $($field:ident),+ $(,)? }) => {
+            impl Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    const FIELDS: &[&str] = &[$(stringify!($field)),+];
+                    let mut state = serializer.serialize_struct(stringify!($ty), FIELDS.len())?;
+                    $(state.serialize_field(stringify!($field), &self.$field)?;)+
+                    state.end()
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    const FIELDS: &[&str] = &[$(stringify!($field)),+];
+
+                    struct StructVisitor;
+
+                    impl<'de> Visitor<'de> for StructVisitor {
+                        type Value = $ty;
+
+                        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                            write!(f, "struct {}", stringify!($ty))
+                        }
+
+                        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<$ty, A::Error> {
+                            let mut read = 0;
+                            $(
+                                let $field = seq
+                                    .next_element()?
+                                    .ok_or_else(|| de::Error::invalid_length(read, &self))?;
+                                read += 1;
+                            )+
+                            let _ = read;
+                            Ok($ty { $($field),+ })
+                        }
+
+                        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<$ty, A::Error> {
+                            $(let mut $field = None;)+
+                            while let Some(key) = map.next_key::<String>()? {
+                                match key.as_str() {
+                                    $(stringify!($field) => $field = Some(map.next_value()?),)+
+                                    _ => {
+                                        map.next_value::<IgnoredAny>()?;
+                                    }
+                                }
+                            }
+                            Ok($ty {
+                                $($field: $field
+                                    .ok_or_else(|| de::Error::missing_field(stringify!($field)))?,)+
+                            })
+                        }
+                    }
+
+                    deserializer.deserialize_struct(stringify!($ty), FIELDS, StructVisitor)
+                }
+            }
+        };
+    }
+
+    serde_struct!(SyncSnapshot {
+        version,
+        collections
+    });
+    serde_struct!(CollectionState {
+        sync_token,
+        ctag,
+        etags,
+        tombstones
+    });
+    serde_struct!(Tombstone {
+        href,
+        deleted_at,
+        etag
+    });
+}
@@ -0,0 +1,233 @@
+//! The server operations [`crate::sync::SyncEngine`] builds on, implemented by
+//! [`CalDavClient`] and [`CardDavClient`].
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+
+use crate::caldav::CalDavClient;
+use crate::carddav::CardDavClient;
+use crate::sync::conflict::ConflictPolicy;
+use crate::sync::engine::Change;
+use crate::sync::queue::{MutationQueue, ReplayReport};
+use crate::webdav::href::normalize_href;
+use crate::webdav::property::PropertyRegistry;
+use crate::webdav::types::{DavResource, Depth, PropertyName, http_status_code};
+
+/// A client able to report the changes of a collection, either through `sync-collection`
+/// (RFC 6578) or by listing the ETags of its members.
+pub trait SyncSource: Send + Sync {
+    /// Whether the server supports `sync-collection`.
+    fn supports_webdav_sync(&self) -> BoxFuture<'_, Result<bool>>;
+
+    /// Changes to `collection` since `sync_token` (everything when `None`), following
+    /// truncated responses, and the new sync token.
+    ///
+    /// Every non-deleted change is reported as [`ChangeKind::Updated`](crate::sync::ChangeKind::Updated).
+    fn changes_since<'a>(
+        &'a self,
+        collection: &'a str,
+        sync_token: Option<&'a str>,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> BoxFuture<'a, Result<(Vec<Change>, Option<String>)>>;
+
+    /// `getctag` of `collection`, `None` when the server does not expose it.
+    fn ctag<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Option<String>>>;
+
+    /// Href and ETag of every non-collection member of `collection`.
+    fn etags<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Vec<(String, String)>>>;
+
+    /// Fetch the members at `hrefs` with their data, reported as [`ChangeKind::Updated`](crate::sync::ChangeKind::Updated).
+    ///
+    /// Members the server failed to return (a `5xx` status in the multiget, say) are left
+    /// out, so that [`crate::sync::SyncEngine`] retries them.
+    fn fetch<'a>(
+        &'a self,
+        collection: &'a str,
+        hrefs: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Change>>>;
+
+    /// Content type of the member bodies written by [`push`](Self::push).
+    fn content_type(&self) -> &'static str;
+
+    /// Send the mutations of `queue` in order, settling `412` conflicts with `policy`.
+    fn push<'a>(
+        &'a self,
+        queue: &'a dyn MutationQueue,
+        policy: &'a ConflictPolicy,
+    ) -> BoxFuture<'a, Result<ReplayReport>>;
+}
+
+impl SyncSource for CalDavClient {
+    fn supports_webdav_sync(&self) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(CalDavClient::supports_webdav_sync(self))
+    }
+
+    fn changes_since<'a>(
+        &'a self,
+        collection: &'a str,
+        sync_token: Option<&'a str>,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> BoxFuture<'a, Result<(Vec<Change>, Option<String>)>> {
+        Box::pin(async move {
+            let response = self
+                .sync_collection_all(collection, sync_token, limit, include_data)
+                .await?;
+            let changes = response
+                .items
+                .into_iter()
+                .map(|item| {
+                    Change::reported(item.href, item.etag, item.calendar_data, item.is_deleted)
+                })
+                .collect();
+            Ok((changes, response.sync_token))
+        })
+    }
+
+    fn ctag<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(self.get_ctag(collection))
+    }
+
+    fn etags<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(async move {
+            let resources = self
+                .propfind_properties(collection, Depth::One, &etag_registry())
+                .await?;
+            Ok(member_etags(resources.into_iter().map(DavResource::from)))
+        })
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        collection: &'a str,
+        hrefs: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Change>>> {
+        Box::pin(async move {
+            let objects = self.calendar_multiget(collection, hrefs, true).await?;
+            Ok(objects
+                .into_iter()
+                .filter(|object| fetched(object.status.as_deref()))
+                .map(|object| {
+                    Change::reported(
+                        normalize_href(&object.href),
+                        object.etag,
+                        object.calendar_data,
+                        false,
+                    )
+                })
+                .collect())
+        })
+    }
+
+    fn content_type(&self) -> &'static str {
+        "text/calendar; charset=utf-8"
+    }
+
+    fn push<'a>(
+        &'a self,
+        queue: &'a dyn MutationQueue,
+        policy: &'a ConflictPolicy,
+    ) -> BoxFuture<'a, Result<ReplayReport>> {
+        Box::pin(self.replay_mutations(queue, policy))
+    }
+}
+
+impl SyncSource for CardDavClient {
+    fn supports_webdav_sync(&self) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(CardDavClient::supports_webdav_sync(self))
+    }
+
+    fn changes_since<'a>(
+        &'a self,
+        collection: &'a str,
+        sync_token: Option<&'a str>,
+        limit: Option<u32>,
+        include_data: bool,
+    ) -> BoxFuture<'a, Result<(Vec<Change>, Option<String>)>> {
+        Box::pin(async move {
+            let response = self
+                .sync_collection_all(collection, sync_token, limit, include_data)
+                .await?;
+            let changes = response
+                .items
+                .into_iter()
+                .map(|item| {
+                    Change::reported(item.href, item.etag, item.address_data, item.is_deleted)
+                })
+                .collect();
+            Ok((changes, response.sync_token))
+        })
+    }
+
+    fn ctag<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(self.get_ctag(collection))
+    }
+
+    fn etags<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(async move {
+            let resources = self
+                .propfind_properties(collection, Depth::One, &etag_registry())
+                .await?;
+            Ok(member_etags(resources.into_iter().map(DavResource::from)))
+        })
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        collection: &'a str,
+        hrefs: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Change>>> {
+        Box::pin(async move {
+            let objects = self.addressbook_multiget(collection, hrefs, true).await?;
+            Ok(objects
+                .into_iter()
+                .filter(|object| fetched(object.status.as_deref()))
+                .map(|object| {
+                    Change::reported(
+                        normalize_href(&object.href),
+                        object.etag,
+                        object.address_data,
+                        false,
+                    )
+                })
+                .collect())
+        })
+    }
+
+    fn content_type(&self) -> &'static str {
+        "text/vcard; charset=utf-8"
+    }
+
+    fn push<'a>(
+        &'a self,
+        queue: &'a dyn MutationQueue,
+        policy: &'a ConflictPolicy,
+    ) -> BoxFuture<'a, Result<ReplayReport>> {
+        Box::pin(self.replay_mutations(queue, policy))
+    }
+}
+
+fn etag_registry() -> PropertyRegistry {
+    PropertyRegistry::new()
+        .register(PropertyName::dav("getetag"))
+        .register(PropertyName::dav("resourcetype"))
+}
+
+/// Normalized href and ETag of the listed resources that are not collections (which also
+/// drops the listed collection itself).
+fn member_etags(resources: impl Iterator<Item = DavResource>) -> Vec<(String, String)> {
+    resources
+        .filter(|resource| !resource.is_collection())
+        .filter_map(|resource| Some((normalize_href(&resource.href), resource.etag?)))
+        .collect()
+}
+
+/// Whether a multiget response with `status` answers for its member: a success, or the
+/// member being gone.
+fn fetched(status: Option<&str>) -> bool {
+    match status.and_then(http_status_code) {
+        Some(code) => (200..300).contains(&code) || code == 404 || code == 410,
+        None => true,
+    }
+}
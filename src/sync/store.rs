@@ -0,0 +1,205 @@
+//! Persistence of per-collection sync state: the `sync-collection` token (RFC 6578), the
+//! `getctag` of servers without WebDAV-Sync, the ETags of the known members and the
+//! tombstones of deleted ones.
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::sync::snapshot::SyncSnapshot;
+
+/// Sync state remembered for one collection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectionState {
+    pub sync_token: Option<String>,
+    pub ctag: Option<String>,
+    /// ETag of every known member, by href.
+    pub etags: HashMap<String, String>,
+    pub tombstones: Vec<Tombstone>,
+}
+
+/// Record of a member deleted on the server, kept so consumers processing changes late can
+/// still propagate the deletion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tombstone {
+    pub href: String,
+    /// When the sync noticed the deletion.
+    pub deleted_at: SystemTime,
+    /// Last ETag known for the member, if any.
+    pub etag: Option<String>,
+}
+
+impl Tombstone {
+    /// `true` once the tombstone is older than `retention` at `now`.
+    pub fn is_expired(&self, retention: Duration, now: SystemTime) -> bool {
+        now.duration_since(self.deleted_at)
+            .is_ok_and(|age| age > retention)
+    }
+}
+
+/// Storage of the sync state of collections, keyed by collection path.
+///
+/// Implement it over a database or file to resume incremental sync across runs;
+/// [`MemoryTokenStore`] keeps the state for the lifetime of the process.
+pub trait TokenStore: Send + Sync {
+    /// Last sync token stored for `collection`.
+    fn sync_token<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Option<String>>>;
+    /// Store the sync token of `collection`; `None` forgets it, forcing a full sync.
+    fn set_sync_token<'a>(
+        &'a self,
+        collection: &'a str,
+        token: Option<String>,
+    ) -> BoxFuture<'a, Result<()>>;
+    /// Last `getctag` stored for `collection`.
+    fn ctag<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Option<String>>>;
+    /// Store the `getctag` of `collection`; `None` forgets it.
+    fn set_ctag<'a>(
+        &'a self,
+        collection: &'a str,
+        ctag: Option<String>,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+/// [`TokenStore`] also keeping the ETags of the members of each collection, as needed by
+/// [`crate::sync::SyncEngine`] to classify changes and diff listings.
+pub trait SyncStore: TokenStore {
+    /// ETag of every known member of `collection`, by href.
+    fn etags<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<HashMap<String, String>>>;
+    /// Replace the member ETags of `collection`.
+    fn set_etags<'a>(
+        &'a self,
+        collection: &'a str,
+        etags: HashMap<String, String>,
+    ) -> BoxFuture<'a, Result<()>>;
+    /// Tombstones of the members deleted from `collection`.
+    fn tombstones<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Vec<Tombstone>>>;
+    /// Replace the tombstones of `collection`.
+    fn set_tombstones<'a>(
+        &'a self,
+        collection: &'a str,
+        tombstones: Vec<Tombstone>,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+/// In-memory [`TokenStore`] and [`SyncStore`].
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::sync::{MemoryTokenStore, TokenStore};
+///
+/// # async fn demo() -> anyhow::Result<()> {
+/// let store = MemoryTokenStore::new();
+/// store.set_sync_token("calendars/jane/work/", Some("token-1".into())).await?;
+/// assert_eq!(store.sync_token("calendars/jane/work/").await?.as_deref(), Some("token-1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    collections: Mutex<HashMap<String, CollectionState>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stored state of `collection`.
+    pub fn state(&self, collection: &str) -> CollectionState {
+        self.lock().get(collection).cloned().unwrap_or_default()
+    }
+
+    /// State of every collection with a stored state.
+    pub fn snapshot(&self) -> SyncSnapshot {
+        SyncSnapshot::new(
+            self.lock()
+                .iter()
+                .map(|(collection, state)| (collection.clone(), state.clone()))
+                .collect(),
+        )
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CollectionState>> {
+        self.collections.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn read<T: Default>(&self, collection: &str, get: impl FnOnce(&CollectionState) -> T) -> T {
+        self.lock().get(collection).map(get).unwrap_or_default()
+    }
+
+    fn update(&self, collection: &str, apply: impl FnOnce(&mut CollectionState)) {
+        let mut collections = self.lock();
+        let state = collections.entry(collection.to_string()).or_default();
+        apply(state);
+        if *state == CollectionState::default() {
+            collections.remove(collection);
+        }
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn sync_token<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move { Ok(self.read(collection, |state| state.sync_token.clone())) })
+    }
+
+    fn set_sync_token<'a>(
+        &'a self,
+        collection: &'a str,
+        token: Option<String>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.update(collection, |state| state.sync_token = token);
+            Ok(())
+        })
+    }
+
+    fn ctag<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move { Ok(self.read(collection, |state| state.ctag.clone())) })
+    }
+
+    fn set_ctag<'a>(
+        &'a self,
+        collection: &'a str,
+        ctag: Option<String>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.update(collection, |state| state.ctag = ctag);
+            Ok(())
+        })
+    }
+}
+
+impl SyncStore for MemoryTokenStore {
+    fn etags<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<HashMap<String, String>>> {
+        Box::pin(async move { Ok(self.read(collection, |state| state.etags.clone())) })
+    }
+
+    fn set_etags<'a>(
+        &'a self,
+        collection: &'a str,
+        etags: HashMap<String, String>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.update(collection, |state| state.etags = etags);
+            Ok(())
+        })
+    }
+
+    fn tombstones<'a>(&'a self, collection: &'a str) -> BoxFuture<'a, Result<Vec<Tombstone>>> {
+        Box::pin(async move { Ok(self.read(collection, |state| state.tombstones.clone())) })
+    }
+
+    fn set_tombstones<'a>(
+        &'a self,
+        collection: &'a str,
+        tombstones: Vec<Tombstone>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.update(collection, |state| state.tombstones = tombstones);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,160 @@
+//! Two-way sync: local edits written with the preconditions the stored ETags call for, then
+//! the server changes pulled, in one [`SyncEngine::push_and_pull`] call.
+
+use anyhow::Result;
+use bytes::Bytes;
+use std::collections::{HashMap, HashSet};
+
+use crate::sync::conflict::{ConflictPolicy, ConflictResolution};
+use crate::sync::engine::{ChangeKind, SyncEngine, SyncRun};
+use crate::sync::queue::{
+    MemoryMutationQueue, Mutation, MutationQueue, ReplayReport, ReplayResult,
+};
+use crate::sync::source::SyncSource;
+use crate::webdav::href::normalize_href;
+
+/// A local edit to a member of a synced collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalChange {
+    /// New or modified member with its iCalendar or vCard body.
+    Put {
+        href: String,
+        body: Bytes,
+    },
+    Delete {
+        href: String,
+    },
+}
+
+impl LocalChange {
+    pub fn put(href: &str, body: impl Into<Bytes>) -> Self {
+        Self::Put {
+            href: href.to_string(),
+            body: body.into(),
+        }
+    }
+
+    pub fn delete(href: &str) -> Self {
+        Self::Delete {
+            href: href.to_string(),
+        }
+    }
+
+    pub fn href(&self) -> &str {
+        match self {
+            Self::Put { href, .. } | Self::Delete { href } => href,
+        }
+    }
+}
+
+/// Outcome of [`SyncEngine::push_and_pull`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushPullRun {
+    /// What happened to each local change, in order.
+    pub push: ReplayReport,
+    /// Server changes pulled after the push, without the echo of the pushed writes; `None`
+    /// when the push was interrupted and the pull skipped.
+    pub pull: Option<SyncRun>,
+}
+
+impl<C: SyncSource> SyncEngine<C> {
+    /// Write `changes` to `collection`, then [`sync`](Self::sync) it.
+    ///
+    /// Each change is sent with the precondition the stored state calls for: modified
+    /// members with `If-Match` on their stored ETag, new ones (no stored ETag) with
+    /// `If-None-Match: *`, and deletions with `If-Match` when the ETag is known. Writes
+    /// rejected with `412` are settled by `policy`, as in `replay_mutations`.
+    ///
+    /// The ETags the server returns are stored before pulling, so the pull reports the
+    /// changes of other clients (and the server copies kept by
+    /// [`ConflictPolicy::ServerWins`]) but not the local writes themselves.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the store cannot be read or written, or the pull fails. A
+    /// push interrupted by an error is reported in [`PushPullRun::push`] instead, with the
+    /// remaining changes unsent and no pull.
+    pub async fn push_and_pull(
+        &self,
+        collection: &str,
+        changes: Vec<LocalChange>,
+        policy: &ConflictPolicy,
+    ) -> Result<PushPullRun> {
+        let mut etags = self.store().etags(collection).await?;
+        let queue = MemoryMutationQueue::new();
+        for change in changes {
+            let href = normalize_href(change.href());
+            let known = etags.get(&href);
+            let mutation = match change {
+                LocalChange::Put { body, .. } => {
+                    let put = Mutation::put(&href, self.client().content_type(), body);
+                    match known {
+                        Some(etag) => put.if_match(etag),
+                        None => put.if_none_match(),
+                    }
+                }
+                LocalChange::Delete { .. } => {
+                    let delete = Mutation::delete(&href);
+                    match known {
+                        Some(etag) => delete.if_match(etag),
+                        None => delete,
+                    }
+                }
+            };
+            queue.push(mutation).await?;
+        }
+        let push = self.client().push(&queue, policy).await?;
+
+        // What the pushed members look like on the server now, by href: `Some(etag)` for
+        // written ones, `None` for deleted ones.
+        let mut written: HashMap<String, Option<String>> = HashMap::new();
+        for outcome in &push.outcomes {
+            let href = outcome.mutation.mutation.path().to_string();
+            let etag = match &outcome.result {
+                ReplayResult::Applied { etag } => etag.clone(),
+                ReplayResult::Resolved(
+                    resolution @ (ConflictResolution::ClientWritten { .. }
+                    | ConflictResolution::Merged { .. }),
+                ) => resolution.etag().map(str::to_string),
+                ReplayResult::Resolved(ConflictResolution::ServerKept { .. })
+                | ReplayResult::Rejected { .. } => continue,
+            };
+            match (&outcome.mutation.mutation, etag) {
+                (Mutation::Delete { .. }, _) => {
+                    etags.remove(&href);
+                    written.insert(href, None);
+                }
+                (Mutation::Put { .. }, Some(etag)) => {
+                    etags.insert(href.clone(), etag.clone());
+                    written.insert(href, Some(etag));
+                }
+                // Without an ETag the pull cannot tell the write from a later change.
+                (Mutation::Put { .. }, None) => {}
+            }
+        }
+        if !written.is_empty() {
+            self.store().set_etags(collection, etags).await?;
+        }
+        if push.interrupted.is_some() {
+            return Ok(PushPullRun { push, pull: None });
+        }
+
+        let mut pull = self.sync(collection).await?;
+        let deleted: HashSet<&str> = written
+            .iter()
+            .filter(|(_, etag)| etag.is_none())
+            .map(|(href, _)| href.as_str())
+            .collect();
+        pull.changes.retain(|change| match change.kind {
+            ChangeKind::Deleted => !deleted.contains(change.href.as_str()),
+            _ => match written.get(&change.href) {
+                Some(Some(etag)) => change.etag.as_ref() != Some(etag),
+                _ => true,
+            },
+        });
+        Ok(PushPullRun {
+            push,
+            pull: Some(pull),
+        })
+    }
+}
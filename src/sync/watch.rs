@@ -0,0 +1,152 @@
+//! Polling watcher turning repeated [`SyncEngine`] runs into a stream of change events.
+
+use futures::{Stream, stream};
+use std::collections::VecDeque;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::sync::engine::{Change, ChangeKind, SyncEngine};
+use crate::sync::source::SyncSource;
+
+/// Longest wait between two polls after repeated failures.
+pub const MAX_WATCH_BACKOFF: Duration = Duration::from_secs(300);
+
+/// A change to a member of a watched collection, by href.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeEvent {
+    Created(String),
+    Updated(String),
+    Deleted(String),
+}
+
+impl ChangeEvent {
+    pub fn href(&self) -> &str {
+        match self {
+            Self::Created(href) | Self::Updated(href) | Self::Deleted(href) => href,
+        }
+    }
+}
+
+impl From<Change> for ChangeEvent {
+    fn from(change: Change) -> Self {
+        match change.kind {
+            ChangeKind::Created => Self::Created(change.href),
+            ChangeKind::Updated => Self::Updated(change.href),
+            ChangeKind::Deleted => Self::Deleted(change.href),
+        }
+    }
+}
+
+/// Endless stream of the [`ChangeEvent`]s of a collection, see [`SyncEngine::watch`].
+///
+/// Failed polls are not yielded: the watcher waits twice as long after each consecutive
+/// failure (up to [`MAX_WATCH_BACKOFF`]) and [`last_error`](Self::last_error) reports the
+/// latest one until a poll succeeds.
+pub struct ChangeStream {
+    inner: Pin<Box<dyn Stream<Item = ChangeEvent> + Send>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl ChangeStream {
+    /// Error of the latest poll, `None` once a poll succeeded.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Stream for ChangeStream {
+    type Item = ChangeEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl<C: SyncSource + 'static> SyncEngine<C> {
+    /// Poll `collection` every `interval` (plus up to 10% of random jitter, so watchers
+    /// started together spread their requests) and yield its changes as they are found.
+    ///
+    /// The first poll runs immediately and reports the changes since the stored state:
+    /// with an empty store, every existing member is reported as created.
+    pub fn watch(self, collection: &str, interval: Duration) -> ChangeStream {
+        struct State<C> {
+            engine: SyncEngine<C>,
+            collection: String,
+            pending: VecDeque<ChangeEvent>,
+            failures: u32,
+            started: bool,
+            last_error: Arc<Mutex<Option<String>>>,
+        }
+
+        let last_error = Arc::new(Mutex::new(None));
+        let state = State {
+            engine: self,
+            collection: collection.to_string(),
+            pending: VecDeque::new(),
+            failures: 0,
+            started: false,
+            last_error: Arc::clone(&last_error),
+        };
+
+        let inner = stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((event, state));
+                }
+                if state.started {
+                    tokio::time::sleep(poll_delay(interval, state.failures)).await;
+                }
+                state.started = true;
+                let outcome = state.engine.sync(&state.collection).await;
+                let mut last_error = state.last_error.lock().unwrap_or_else(|e| e.into_inner());
+                match outcome {
+                    Ok(run) => {
+                        state.failures = 0;
+                        *last_error = None;
+                        state
+                            .pending
+                            .extend(run.changes.into_iter().map(ChangeEvent::from));
+                    }
+                    Err(err) => {
+                        state.failures = state.failures.saturating_add(1);
+                        *last_error = Some(err.to_string());
+                    }
+                }
+            }
+        });
+
+        ChangeStream {
+            inner: Box::pin(inner),
+            last_error,
+        }
+    }
+}
+
+/// `interval` doubled per consecutive failure (capped at [`MAX_WATCH_BACKOFF`], but never
+/// below `interval`), plus up to 10% of jitter.
+fn poll_delay(interval: Duration, failures: u32) -> Duration {
+    let base = if failures == 0 {
+        interval
+    } else {
+        interval
+            .saturating_mul(1 << failures.min(16))
+            .min(MAX_WATCH_BACKOFF.max(interval))
+    };
+    let jitter_range = base.as_millis() as u64 / 10;
+    if jitter_range == 0 {
+        return base;
+    }
+    base + Duration::from_millis(random_u64() % (jitter_range + 1))
+}
+
+/// Random value from the per-process randomly seeded hasher, enough for jitter.
+pub(crate) fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
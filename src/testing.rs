@@ -0,0 +1,232 @@
+//! Helpers for writing integration tests against a live DAV server.
+//!
+//! This module is only available with the `testing` feature. It exposes the scaffolding used by
+//! this crate's own SabreDAV-based e2e suite — unique name/UID generators, cleanup guards and
+//! server readiness checks — so downstream crates can test against a container without copying
+//! the helpers.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fast_dav_rs::CalDavClient;
+//! use fast_dav_rs::testing::{CleanupGuard, TestServerConfig, unique_calendar_name, wait_for_server};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let config = TestServerConfig::from_env();
+//! let client = CalDavClient::new(&config.url, Some(&config.user), Some(&config.pass))?;
+//! wait_for_server(&client, Duration::from_secs(30)).await?;
+//!
+//! let mut guard = CleanupGuard::new(client.clone());
+//! let calendar_path = format!("calendars/test/{}/", unique_calendar_name("it"));
+//! guard.track(&calendar_path);
+//! // ... create the calendar and exercise it ...
+//! guard.cleanup().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Result, anyhow};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use hyper::{Response, StatusCode};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::caldav::CalDavClient;
+use crate::carddav::CardDavClient;
+use crate::webdav::WebDavClient;
+
+/// Default server URL, matching the `sabredav-test/` docker-compose environment.
+pub const DEFAULT_TEST_URL: &str = "http://localhost:8080/";
+/// Default test user, matching the `sabredav-test/` seed data.
+pub const DEFAULT_TEST_USER: &str = "test";
+/// Default test password, matching the `sabredav-test/` seed data.
+pub const DEFAULT_TEST_PASS: &str = "test";
+
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+static UNIQUE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Connection settings for a test server.
+#[derive(Debug, Clone)]
+pub struct TestServerConfig {
+    pub url: String,
+    pub user: String,
+    pub pass: String,
+}
+
+impl Default for TestServerConfig {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_TEST_URL.to_string(),
+            user: DEFAULT_TEST_USER.to_string(),
+            pass: DEFAULT_TEST_PASS.to_string(),
+        }
+    }
+}
+
+impl TestServerConfig {
+    /// Read settings from `FAST_DAV_TEST_URL`, `FAST_DAV_TEST_USER` and `FAST_DAV_TEST_PASS`,
+    /// falling back to the `sabredav-test/` defaults for unset variables.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            url: std::env::var("FAST_DAV_TEST_URL").unwrap_or(defaults.url),
+            user: std::env::var("FAST_DAV_TEST_USER").unwrap_or(defaults.user),
+            pass: std::env::var("FAST_DAV_TEST_PASS").unwrap_or(defaults.pass),
+        }
+    }
+}
+
+fn unique_suffix() -> (u128, u64) {
+    let counter = UNIQUE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let micros = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros())
+        .unwrap_or_default();
+    (micros, counter)
+}
+
+/// Generate a collection name that is unique across test runs, e.g. `prefix_1700000000000000_0`.
+pub fn unique_calendar_name(prefix: &str) -> String {
+    let (micros, counter) = unique_suffix();
+    format!("{prefix}_{micros}_{counter}")
+}
+
+/// Generate an addressbook name that is unique across test runs.
+pub fn unique_addressbook_name(prefix: &str) -> String {
+    let (micros, counter) = unique_suffix();
+    format!("{prefix}_{micros}_{counter}")
+}
+
+/// Generate an iCalendar/vCard `UID` that is unique across test runs.
+pub fn unique_uid(prefix: &str) -> String {
+    let (micros, counter) = unique_suffix();
+    format!("{prefix}-{micros}-{counter}@example.com")
+}
+
+/// Generate a unique `.vcf` resource name.
+pub fn unique_contact_uri(prefix: &str) -> String {
+    let (micros, counter) = unique_suffix();
+    format!("{prefix}_{micros}_{counter}.vcf")
+}
+
+/// Minimal client surface needed by the test helpers, implemented for every client in the crate.
+pub trait TestClient: Clone + Send + Sync + 'static {
+    /// Send an `OPTIONS` request.
+    fn options<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Response<Bytes>>>;
+    /// Send a `DELETE` request.
+    fn delete<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Response<Bytes>>>;
+}
+
+macro_rules! impl_test_client {
+    ($($client:ty),*) => {
+        $(
+            impl TestClient for $client {
+                fn options<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Response<Bytes>>> {
+                    Box::pin(<$client>::options(self, path))
+                }
+
+                fn delete<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<Response<Bytes>>> {
+                    Box::pin(<$client>::delete(self, path))
+                }
+            }
+        )*
+    };
+}
+
+impl_test_client!(WebDavClient, CalDavClient, CardDavClient);
+
+/// Wait until the server answers `OPTIONS` on the client's base URL with a 2xx status.
+///
+/// Connection errors and non-success statuses are retried until `timeout` elapses.
+///
+/// # Errors
+///
+/// Returns an error describing the last failure when the server is not ready in time.
+pub async fn wait_for_server<C: TestClient>(client: &C, timeout: Duration) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let last_error = match client.options("").await {
+            Ok(resp) if resp.status().is_success() => return Ok(()),
+            Ok(resp) => format!("OPTIONS returned {}", resp.status()),
+            Err(e) => e.to_string(),
+        };
+        if tokio::time::Instant::now() + READINESS_POLL_INTERVAL > deadline {
+            return Err(anyhow!(
+                "test server not ready after {timeout:?}: {last_error}"
+            ));
+        }
+        tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+    }
+}
+
+/// Deletes tracked resources when the test is done.
+///
+/// Call [`CleanupGuard::cleanup`] at the end of a test for deterministic cleanup. If the guard is
+/// dropped without it (e.g. because an assertion panicked), the deletes are spawned as a
+/// best-effort background task on the current tokio runtime.
+pub struct CleanupGuard<C: TestClient> {
+    client: C,
+    paths: Vec<String>,
+}
+
+impl<C: TestClient> CleanupGuard<C> {
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            paths: Vec::new(),
+        }
+    }
+
+    /// Register a path to delete. Paths are deleted in reverse registration order, so children
+    /// tracked after their collection are removed first.
+    pub fn track(&mut self, path: impl Into<String>) {
+        self.paths.push(path.into());
+    }
+
+    /// Stop tracking a path, e.g. after the test deleted it itself.
+    pub fn untrack(&mut self, path: &str) {
+        self.paths.retain(|p| p != path);
+    }
+
+    /// Paths currently tracked for deletion.
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Delete every tracked path and return the ones that could not be removed.
+    ///
+    /// `404 Not Found` counts as removed.
+    pub async fn cleanup(mut self) -> Vec<String> {
+        let paths = std::mem::take(&mut self.paths);
+        delete_all(&self.client, paths).await
+    }
+}
+
+impl<C: TestClient> Drop for CleanupGuard<C> {
+    fn drop(&mut self) {
+        if self.paths.is_empty() {
+            return;
+        }
+        let paths = std::mem::take(&mut self.paths);
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let client = self.client.clone();
+            handle.spawn(async move {
+                delete_all(&client, paths).await;
+            });
+        }
+    }
+}
+
+async fn delete_all<C: TestClient>(client: &C, paths: Vec<String>) -> Vec<String> {
+    let mut failed = Vec::new();
+    for path in paths.into_iter().rev() {
+        match client.delete(&path).await {
+            Ok(resp) if resp.status().is_success() || resp.status() == StatusCode::NOT_FOUND => {}
+            _ => failed.push(path),
+        }
+    }
+    failed
+}
@@ -0,0 +1,210 @@
+//! WebDAV access control (RFC 3744): `ACL` request bodies and parsing of the `DAV:acl`
+//! property (see [`crate::webdav::WebDavClient::acl`]).
+
+use anyhow::{Result, anyhow};
+use quick_xml::NsReader;
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+
+use crate::webdav::streaming::path_ends_with;
+use crate::webdav::types::{Ace, AceAction, AcePrincipal, ParseLimits, Privilege, PropertyName};
+use crate::webdav::xml::{LimitGuard, escape_xml};
+
+pub(crate) const ACL_PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:propfind xmlns:D="DAV:">
+  <D:prop>
+    <D:acl/>
+  </D:prop>
+</D:propfind>"#;
+
+/// Build an `ACL` request body from `aces`.
+///
+/// Protected and inherited entries are left out: servers reject requests that contain them
+/// and keep them regardless, so the result of [`parse_acl`] can be edited and sent back.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{Ace, AcePrincipal, Privilege, build_acl_body};
+///
+/// let body = build_acl_body(&[Ace::grant(AcePrincipal::Authenticated, [Privilege::Read])])?;
+/// assert!(body.contains(
+///     "<D:ace><D:principal><D:authenticated/></D:principal><D:grant><D:privilege><D:read/></D:privilege></D:grant></D:ace>"
+/// ));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when a custom privilege or principal property is not a valid XML name.
+pub fn build_acl_body(aces: &[Ace]) -> Result<String> {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="utf-8"?><D:acl xmlns:D="DAV:">"#);
+    for ace in aces
+        .iter()
+        .filter(|ace| !ace.protected && ace.inherited.is_none())
+    {
+        xml.push_str("<D:ace>");
+        let principal = principal_xml(&ace.principal)?;
+        if ace.invert {
+            xml.push_str(&format!("<D:invert>{principal}</D:invert>"));
+        } else {
+            xml.push_str(&principal);
+        }
+        let action = match ace.action {
+            AceAction::Grant => "grant",
+            AceAction::Deny => "deny",
+        };
+        xml.push_str(&format!("<D:{action}>"));
+        for privilege in &ace.privileges {
+            xml.push_str(&format!(
+                "<D:privilege>{}</D:privilege>",
+                element(privilege.qualified_name())?
+            ));
+        }
+        xml.push_str(&format!("</D:{action}></D:ace>"));
+    }
+    xml.push_str("</D:acl>");
+    Ok(xml)
+}
+
+/// Parse the `DAV:acl` property of a `PROPFIND` response into its entries.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_acl(body: &[u8]) -> Result<Vec<Ace>> {
+    parse_acl_with_limits(body, ParseLimits::default())
+}
+
+/// Variant of [`parse_acl`] with other [`ParseLimits`] than the default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when it is not well-formed XML.
+pub fn parse_acl_with_limits(body: &[u8], limits: ParseLimits) -> Result<Vec<Ace>> {
+    let mut xml = NsReader::from_reader(body);
+
+    let mut buf = Vec::new();
+    let mut guard = LimitGuard::new(limits);
+    let mut stack: Vec<String> = Vec::new();
+    let mut aces = Vec::new();
+    let mut current: Option<Ace> = None;
+    let mut text = String::new();
+
+    loop {
+        let (ns, event) = xml
+            .read_resolved_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        guard.on_event(&event)?;
+        if matches!(event, Event::Start(_) | Event::Empty(_) | Event::End(_)) {
+            on_text(&stack, text.trim(), &mut current);
+            text.clear();
+        }
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let namespace = match ns {
+                    ResolveResult::Bound(ns) => String::from_utf8_lossy(ns.as_ref()).into_owned(),
+                    _ => String::new(),
+                };
+                on_start(&stack, &namespace, &local, &mut current);
+                if matches!(event, Event::Start(_)) {
+                    stack.push(local.to_ascii_lowercase());
+                } else if local.eq_ignore_ascii_case("ace") {
+                    // An empty `<ace/>` carries nothing.
+                    current = None;
+                }
+            }
+            Event::End(_) => {
+                let closed = stack.pop();
+                if closed.as_deref() == Some("ace") {
+                    aces.extend(current.take());
+                }
+            }
+            Event::Eof => break,
+            event => guard.append_text(&mut text, &event)?,
+        }
+        buf.clear();
+    }
+
+    Ok(aces)
+}
+
+fn on_text(stack: &[String], text: &str, current: &mut Option<Ace>) {
+    let Some(ace) = current.as_mut() else {
+        return;
+    };
+    if text.is_empty() {
+        return;
+    }
+    if path_ends_with(stack, &["principal", "href"]) {
+        ace.principal = AcePrincipal::Href(text.to_string());
+    } else if path_ends_with(stack, &["inherited", "href"]) {
+        ace.inherited = Some(text.to_string());
+    }
+}
+
+fn on_start(stack: &[String], namespace: &str, local: &str, current: &mut Option<Ace>) {
+    if namespace == "DAV:" && local.eq_ignore_ascii_case("ace") {
+        *current = Some(Ace::grant(AcePrincipal::All, []));
+        return;
+    }
+    let Some(ace) = current.as_mut() else {
+        return;
+    };
+    let is_dav = namespace == "DAV:";
+    if path_ends_with(stack, &["principal", "property"]) {
+        ace.principal = AcePrincipal::Property {
+            namespace: namespace.to_string(),
+            name: local.to_string(),
+        };
+    } else if path_ends_with(stack, &["principal"]) && is_dav {
+        match local {
+            "all" => ace.principal = AcePrincipal::All,
+            "authenticated" => ace.principal = AcePrincipal::Authenticated,
+            "unauthenticated" => ace.principal = AcePrincipal::Unauthenticated,
+            "self" => ace.principal = AcePrincipal::SelfPrincipal,
+            _ => {}
+        }
+    } else if path_ends_with(stack, &["grant", "privilege"])
+        || path_ends_with(stack, &["deny", "privilege"])
+    {
+        ace.privileges.push(Privilege::from_name(namespace, local));
+    } else if path_ends_with(stack, &["ace"]) && is_dav {
+        match local {
+            "invert" => ace.invert = true,
+            "deny" => ace.action = AceAction::Deny,
+            "grant" => ace.action = AceAction::Grant,
+            "protected" => ace.protected = true,
+            _ => {}
+        }
+    }
+}
+
+fn principal_xml(principal: &AcePrincipal) -> Result<String> {
+    let inner = match principal {
+        AcePrincipal::Href(href) => format!("<D:href>{}</D:href>", escape_xml(href)),
+        AcePrincipal::All => "<D:all/>".to_string(),
+        AcePrincipal::Authenticated => "<D:authenticated/>".to_string(),
+        AcePrincipal::Unauthenticated => "<D:unauthenticated/>".to_string(),
+        AcePrincipal::SelfPrincipal => "<D:self/>".to_string(),
+        AcePrincipal::Property { namespace, name } => {
+            format!("<D:property>{}</D:property>", element((namespace, name))?)
+        }
+    };
+    Ok(format!("<D:principal>{inner}</D:principal>"))
+}
+
+/// Empty element for a qualified name, declaring its namespace when it is not `DAV:`.
+fn element((namespace, name): (&str, &str)) -> Result<String> {
+    let qualified = PropertyName::new(namespace, name);
+    if !qualified.is_valid() {
+        return Err(anyhow!("invalid element name {qualified}"));
+    }
+    Ok(if namespace == "DAV:" {
+        format!("<D:{name}/>")
+    } else {
+        format!(r#"<X:{name} xmlns:X="{}"/>"#, escape_xml(namespace))
+    })
+}
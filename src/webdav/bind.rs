@@ -0,0 +1,69 @@
+//! WebDAV bindings (RFC 5842): `BIND`, `UNBIND` and `REBIND` request bodies, which make one
+//! resource reachable under several hrefs (see [`crate::webdav::WebDavClient::bind`]).
+
+use anyhow::{Result, anyhow};
+
+use crate::webdav::xml::escape_xml;
+
+/// Build a `BIND` body adding the binding `segment` in the request collection to the
+/// resource at `href`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::build_bind_body;
+///
+/// let body = build_bind_body("standup.ics", "/calendars/jane/work/standup.ics")?;
+/// assert!(body.contains("<D:segment>standup.ics</D:segment>"));
+/// assert!(body.contains("<D:href>/calendars/jane/work/standup.ics</D:href>"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when `segment` is not a single path segment.
+pub fn build_bind_body(segment: &str, href: &str) -> Result<String> {
+    validate_segment(segment)?;
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:bind xmlns:D="DAV:"><D:segment>{}</D:segment><D:href>{}</D:href></D:bind>"#,
+        escape_xml(segment),
+        escape_xml(href)
+    ))
+}
+
+/// Build an `UNBIND` body removing the binding `segment` from the request collection.
+///
+/// # Errors
+///
+/// Returns an error when `segment` is not a single path segment.
+pub fn build_unbind_body(segment: &str) -> Result<String> {
+    validate_segment(segment)?;
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:unbind xmlns:D="DAV:"><D:segment>{}</D:segment></D:unbind>"#,
+        escape_xml(segment)
+    ))
+}
+
+/// Build a `REBIND` body moving the binding at `href` to `segment` in the request
+/// collection.
+///
+/// # Errors
+///
+/// Returns an error when `segment` is not a single path segment.
+pub fn build_rebind_body(segment: &str, href: &str) -> Result<String> {
+    validate_segment(segment)?;
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:rebind xmlns:D="DAV:"><D:segment>{}</D:segment><D:href>{}</D:href></D:rebind>"#,
+        escape_xml(segment),
+        escape_xml(href)
+    ))
+}
+
+/// A binding name is one path segment (RFC 5842 §2): not empty, `.` or `..`, and without
+/// `/`.
+fn validate_segment(segment: &str) -> Result<()> {
+    if segment.is_empty() || segment == "." || segment == ".." || segment.contains('/') {
+        return Err(anyhow!("invalid binding segment {segment:?}"));
+    }
+    Ok(())
+}
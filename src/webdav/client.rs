@@ -2,21 +2,58 @@ use anyhow::{Result, anyhow};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as B64;
 use bytes::Bytes;
-use futures::{StreamExt, stream::FuturesOrdered};
-use http_body_util::Full;
+use futures::{StreamExt, TryStreamExt, stream::FuturesOrdered};
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::Frame;
 use hyper::body::Incoming;
 use hyper::{HeaderMap, Method, Request, Response, StatusCode, Uri, header};
+use std::collections::{HashSet, VecDeque};
 use std::sync::{Arc, PoisonError, RwLock};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio::time::{Duration, timeout};
+use tokio_util::io::ReaderStream;
 use zeroize::Zeroize;
 
 use crate::common::compression::{
-    ContentEncoding, add_accept_encoding, add_content_encoding, compress_payload, decompress_body,
+    ContentEncoding, DEFAULT_BLOCKING_THRESHOLD, DecompressedBody, DecompressionOptions,
+    add_accept_encoding, add_content_encoding, compress_payload, compress_payload_with,
+    decoding_error, decompress_body_with, decompress_stream_with, decompressing_reader,
     detect_encodings, detect_request_compression_preference,
 };
-use crate::common::http::{HyperClient, build_hyper_client};
-use crate::webdav::types::{BatchItem, Depth};
+use crate::common::http::{StreamingHyperClient, build_streaming_hyper_client, full_body};
+use crate::sync::cache::{CacheEntry, CachedGet, EtagCache, body_hash};
+use crate::sync::conflict::{ConflictPolicy, ConflictResolution, MAX_MERGE_ATTEMPTS};
+use crate::sync::queue::{
+    Mutation, MutationQueue, Precondition, ReplayOutcome, ReplayReport, ReplayResult,
+};
+use crate::webdav::acl::{ACL_PROPFIND_BODY, build_acl_body, parse_acl_with_limits};
+use crate::webdav::bind::{build_bind_body, build_rebind_body, build_unbind_body};
+use crate::webdav::lock::{
+    IfHeader, build_lockinfo_body, parse_lock_discovery_with_limits, parse_lock_token_header,
+};
+use crate::webdav::mkcol::ExtendedMkcol;
+use crate::webdav::principal::{
+    PRINCIPAL_COLLECTION_SET_BODY, acl_principal_prop_set_body,
+    parse_principal_collection_set_with_limits, parse_principals_with_limits,
+    principal_propfind_body,
+};
+use crate::webdav::property::{PropertyRegistry, parse_properties_with_limits, resource_registry};
+use crate::webdav::propfind::{PROPNAME_BODY, build_allprop_body, parse_propnames};
+use crate::webdav::proppatch::{PropPatch, parse_proppatch_response, parse_propstats};
+use crate::webdav::push::{PUSH_PROPFIND_BODY, build_push_register_body, parse_push_info};
+use crate::webdav::search::BasicSearch;
+use crate::webdav::streaming::parse_common_multistatus;
+use crate::webdav::types::http_status_code;
+use crate::webdav::types::{
+    Ace, BatchItem, CollectionListing, DavCapabilities, DavItemCommon, DavResource, Depth, Lock,
+    LockScope, LockTimeout, MemberFailure, ParseLimits, PrincipalInfo, PropNames, PropPatchResult,
+    PropertyName, PushInfo, PushRegistration, RecursiveReport, ResourceProps, VersionEntry,
+    WebPushSubscription,
+};
+use crate::webdav::versioning::{
+    parse_versions_with_limits, version_history_body, version_tree_body,
+};
 
 /// Strategy for compressing outgoing request bodies.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,7 +83,7 @@ const PROBE_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
 #[derive(Clone)]
 pub struct WebDavClient {
     base: Uri,
-    client: HyperClient,
+    client: StreamingHyperClient,
     /// Pre-built `Authorization: Basic …` value attached to every request, if
     /// credentials were provided.
     ///
@@ -60,6 +97,10 @@ pub struct WebDavClient {
     request_compression_mode: RequestCompressionMode,
     negotiated_request_compression: Arc<RwLock<Option<ContentEncoding>>>,
     request_compression_probe: Arc<Mutex<()>>,
+    etag_cache: Option<Arc<dyn EtagCache>>,
+    decompression: DecompressionOptions,
+    compression_blocking_threshold: Option<usize>,
+    parse_limits: ParseLimits,
 }
 
 impl WebDavClient {
@@ -75,7 +116,7 @@ impl WebDavClient {
     /// network path. Always use `https://` outside isolated test environments
     /// (e.g. a local Docker test server).
     pub fn new(base_url: &str, basic_user: Option<&str>, basic_pass: Option<&str>) -> Result<Self> {
-        let client = build_hyper_client()?;
+        let client = build_streaming_hyper_client()?;
 
         let base: Uri = base_url.parse()?;
         let auth_header = if let (Some(u), Some(p)) = (basic_user, basic_pass) {
@@ -99,6 +140,10 @@ impl WebDavClient {
             request_compression_mode: RequestCompressionMode::Auto,
             negotiated_request_compression: Arc::new(RwLock::new(None)),
             request_compression_probe: Arc::new(Mutex::new(())),
+            etag_cache: None,
+            decompression: DecompressionOptions::default(),
+            compression_blocking_threshold: Some(DEFAULT_BLOCKING_THRESHOLD),
+            parse_limits: ParseLimits::default(),
         })
     }
 
@@ -139,6 +184,45 @@ impl WebDavClient {
         self.resolve_request_encoding()
     }
 
+    /// Attach an ETag cache, consulted by [`get_cached`](Self::get_cached),
+    /// [`put_cached`](Self::put_cached) and [`delete_cached`](Self::delete_cached).
+    pub fn set_etag_cache(&mut self, cache: Arc<dyn EtagCache>) {
+        self.etag_cache = Some(cache);
+    }
+
+    /// The attached ETag cache, if any.
+    pub fn etag_cache(&self) -> Option<&Arc<dyn EtagCache>> {
+        self.etag_cache.as_ref()
+    }
+
+    /// Configure how compressed responses are decoded: buffer sizes and the maximum
+    /// expansion ratio guarding against decompression bombs.
+    pub fn set_decompression_options(&mut self, options: DecompressionOptions) {
+        self.decompression = options;
+    }
+
+    /// Get the current response decompression options.
+    pub fn decompression_options(&self) -> &DecompressionOptions {
+        &self.decompression
+    }
+
+    /// Bounds on the XML responses this client parses (element depth, text size, number
+    /// of `response` elements); responses exceeding them fail with a
+    /// [`ParseLimitError`](crate::webdav::ParseLimitError).
+    ///
+    /// Defaults to [`ParseLimits::default`].
+    pub fn set_parse_limits(&mut self, limits: ParseLimits) {
+        self.parse_limits = limits;
+    }
+
+    /// Request body size from which request compression runs on a blocking thread instead
+    /// of the async worker (default [`DEFAULT_BLOCKING_THRESHOLD`]); `None` always
+    /// compresses inline. Decompression has its own threshold in
+    /// [`DecompressionOptions::blocking_threshold`].
+    pub fn set_compression_blocking_threshold(&mut self, threshold: Option<usize>) {
+        self.compression_blocking_threshold = threshold;
+    }
+
     pub fn build_uri(&self, path: &str) -> Result<Uri> {
         if path.starts_with("http://") || path.starts_with("https://") {
             return Ok(path.parse()?);
@@ -279,7 +363,7 @@ impl WebDavClient {
             req_builder = req_builder.header(k, v);
         }
 
-        let req = match req_builder.body(Full::new(encoded_body)) {
+        let req = match req_builder.body(full_body(encoded_body)) {
             Ok(r) => r,
             Err(_) => {
                 self.set_negotiated_encoding(Some(ContentEncoding::Identity));
@@ -364,7 +448,13 @@ impl WebDavClient {
             return (payload, None);
         }
 
-        match compress_payload(payload.clone(), encoding).await {
+        match compress_payload_with(
+            payload.clone(),
+            encoding,
+            self.compression_blocking_threshold,
+        )
+        .await
+        {
             Ok(compressed) => {
                 add_content_encoding(headers, encoding);
                 (compressed, Some(encoding))
@@ -439,8 +529,8 @@ impl WebDavClient {
             }
 
             let req = match final_body {
-                Some(b) => req_builder.body(Full::new(b))?,
-                None => req_builder.body(Full::new(Bytes::new()))?,
+                Some(b) => req_builder.body(full_body(b))?,
+                None => req_builder.body(full_body(Bytes::new()))?,
             };
 
             let fut = self.client.request(req);
@@ -458,7 +548,7 @@ impl WebDavClient {
             let encodings = detect_encodings(resp.headers());
             let (mut parts, body) = resp.into_parts();
 
-            let decompressed = decompress_body(body, &encodings).await?;
+            let decompressed = decompress_body_with(body, &encodings, &self.decompression).await?;
             self.normalize_decompressed_headers(&mut parts.headers, &encodings, decompressed.len());
 
             break Ok(Response::from_parts(parts, decompressed));
@@ -467,7 +557,41 @@ impl WebDavClient {
 
     // ----------- Streaming send (for parsing on the fly) -----------
 
+    /// [`send_stream`](Self::send_stream) with the body decompressed as it is read,
+    /// according to the [decompression options](Self::set_decompression_options).
+    ///
+    /// Like [`send`](Self::send), the headers describe the decompressed body: for a
+    /// compressed body, `Content-Encoding` is removed, and so is `Content-Length` since the
+    /// decompressed size is unknown until the end. Other bodies keep both.
+    pub async fn send_stream_decompressed(
+        &self,
+        method: Method,
+        path: &str,
+        headers: HeaderMap,
+        body_bytes: Option<Bytes>,
+        per_req_timeout: Option<Duration>,
+    ) -> Result<Response<DecompressedBody>> {
+        let response = self
+            .send_stream(method, path, headers, body_bytes, per_req_timeout)
+            .await?;
+        let encodings = detect_encodings(response.headers());
+        let (mut parts, body) = response.into_parts();
+        if encodings.iter().any(|e| *e != ContentEncoding::Identity) {
+            parts.headers.remove(header::CONTENT_ENCODING);
+            parts.headers.remove(header::CONTENT_LENGTH);
+        }
+        let reader = decompressing_reader(body, &encodings, &self.decompression);
+        let body = ReaderStream::with_capacity(reader, self.decompression.buffer_size.max(1))
+            .map_err(decoding_error)
+            .boxed();
+        Ok(Response::from_parts(parts, body))
+    }
+
     /// Generic **streaming send**. Returns a `Response<Incoming>` (not aggregated).
+    ///
+    /// The body is returned as sent, still compressed when the server used a
+    /// `Content-Encoding`: pass [`detect_encodings`] of the headers to the streaming
+    /// parsers, or use [`send_stream_decompressed`](Self::send_stream_decompressed).
     pub async fn send_stream(
         &self,
         method: Method,
@@ -513,8 +637,8 @@ impl WebDavClient {
             }
 
             let req = match final_body {
-                Some(body) => req_builder.body(Full::new(body))?,
-                None => req_builder.body(Full::new(Bytes::new()))?,
+                Some(body) => req_builder.body(full_body(body))?,
+                None => req_builder.body(full_body(Bytes::new()))?,
             };
 
             let fut = self.client.request(req);
@@ -541,6 +665,17 @@ impl WebDavClient {
             .await
     }
 
+    /// Read the capabilities `path` advertises with `OPTIONS`: DAV compliance classes,
+    /// CalDAV/CardDAV extensions, allowed methods and search grammars.
+    pub async fn capabilities(&self, path: &str) -> Result<DavCapabilities> {
+        let response = self.options(path).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("OPTIONS failed with {}", status));
+        }
+        Ok(DavCapabilities::from_headers(response.headers()))
+    }
+
     /// Send a `HEAD` request.
     pub async fn head(&self, path: &str) -> Result<Response<Bytes>> {
         self.send(Method::HEAD, path, HeaderMap::new(), None, None)
@@ -553,6 +688,187 @@ impl WebDavClient {
             .await
     }
 
+    /// `GET` consulting the ETag cache: a cached resource is requested with `If-None-Match`,
+    /// so an unchanged one is not downloaded again.
+    ///
+    /// Downloads refresh the cache entry. Without a cache this is a plain `GET`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the request or the cache fails, or the server answers neither a
+    /// success status nor `304 Not Modified` for a cached resource.
+    pub async fn get_cached(&self, path: &str) -> Result<CachedGet> {
+        let key = self.build_uri(path)?.path().to_string();
+        let cache = self.etag_cache.as_deref();
+        let cached = match cache {
+            Some(cache) => cache.get(&key).await?,
+            None => None,
+        };
+        let mut headers = HeaderMap::new();
+        if let Some(entry) = &cached {
+            headers.insert(
+                header::IF_NONE_MATCH,
+                header::HeaderValue::from_str(&entry.etag)?,
+            );
+        }
+        let response = self.send(Method::GET, path, headers, None, None).await?;
+        let status = response.status();
+        if let (StatusCode::NOT_MODIFIED, Some(entry)) = (status, cached) {
+            return Ok(CachedGet::NotModified {
+                etag: entry.etag,
+                body_hash: entry.body_hash,
+            });
+        }
+        if !status.is_success() {
+            return Err(anyhow!("GET failed with {}", status));
+        }
+        let etag = Self::etag_from_headers(response.headers());
+        let body = response.into_body();
+        if let Some(cache) = cache {
+            cache_entry(cache, &key, etag.as_deref(), &body).await?;
+        }
+        Ok(CachedGet::Fetched { body, etag })
+    }
+
+    /// `PUT` consulting the ETag cache: a cached resource is only overwritten if it still
+    /// has the cached ETag (`If-Match`), otherwise the server answers
+    /// `412 Precondition Failed`.
+    ///
+    /// A successful write stores the new ETag, or forgets the resource when the server does
+    /// not return one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the request or the cache fails.
+    pub async fn put_cached(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: Bytes,
+    ) -> Result<Response<Bytes>> {
+        let key = self.build_uri(path)?.path().to_string();
+        let cache = self.etag_cache.as_deref();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_str(content_type)?,
+        );
+        if let Some(cache) = cache
+            && let Some(entry) = cache.get(&key).await?
+        {
+            headers.insert(
+                header::IF_MATCH,
+                header::HeaderValue::from_str(&entry.etag)?,
+            );
+        }
+        let response = self
+            .send(Method::PUT, path, headers, Some(body.clone()), None)
+            .await?;
+        if let Some(cache) = cache
+            && response.status().is_success()
+        {
+            let etag = Self::etag_from_headers(response.headers());
+            cache_entry(cache, &key, etag.as_deref(), &body).await?;
+        }
+        Ok(response)
+    }
+
+    /// `DELETE` consulting the ETag cache: a cached resource is only deleted if it still has
+    /// the cached ETag (`If-Match`). The resource is forgotten once deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the request or the cache fails.
+    pub async fn delete_cached(&self, path: &str) -> Result<Response<Bytes>> {
+        let key = self.build_uri(path)?.path().to_string();
+        let cache = self.etag_cache.as_deref();
+        let mut headers = HeaderMap::new();
+        if let Some(cache) = cache
+            && let Some(entry) = cache.get(&key).await?
+        {
+            headers.insert(
+                header::IF_MATCH,
+                header::HeaderValue::from_str(&entry.etag)?,
+            );
+        }
+        let response = self.send(Method::DELETE, path, headers, None, None).await?;
+        let status = response.status();
+        if let Some(cache) = cache
+            && (status.is_success() || status == StatusCode::NOT_FOUND)
+        {
+            cache.remove(&key).await?;
+        }
+        Ok(response)
+    }
+
+    /// Upload `body` to `path` with a streaming `PUT`, without buffering it in memory, and
+    /// return the new `ETag` if the server sent one.
+    ///
+    /// The body is sent with chunked transfer encoding (HTTP/1.1) and is not compressed. No
+    /// timeout applies since large uploads can take arbitrarily long; wrap the call in
+    /// [`tokio::time::timeout`] to bound it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when reading `body` or the request fails, or the server does not
+    /// answer with a success status.
+    pub async fn put_file<R>(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: R,
+    ) -> Result<Option<String>>
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        let mut req_builder = Request::builder()
+            .method(Method::PUT)
+            .uri(self.build_uri(path)?)
+            .header(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_str(content_type)?,
+            );
+        if let Some(auth) = &self.auth_header {
+            req_builder = req_builder.header(header::AUTHORIZATION, auth);
+        }
+        let frames = ReaderStream::new(body).map_ok(Frame::data);
+        let req = req_builder.body(StreamBody::new(frames).boxed_unsync())?;
+        let response = self.client.request(req).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("PUT failed with {}", status));
+        }
+        Ok(Self::etag_from_headers(response.headers()))
+    }
+
+    /// Download `path` into `writer` as the body arrives, decompressing it if needed, and
+    /// return the number of bytes written.
+    ///
+    /// The default timeout only bounds the wait for the response headers, not the transfer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the request fails, the server does not answer with a success
+    /// status, or reading the body or writing to `writer` fails.
+    pub async fn get_to_writer<W>(&self, path: &str, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let response = self
+            .send_stream(Method::GET, path, HeaderMap::new(), None, None)
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("GET failed with {}", status));
+        }
+        let encodings = detect_encodings(response.headers());
+        let mut reader =
+            decompress_stream_with(response.into_body(), &encodings, &self.decompression)?;
+        let written = tokio::io::copy(&mut reader, writer).await?;
+        writer.flush().await?;
+        Ok(written)
+    }
+
     /// Send a `DELETE` request.
     pub async fn delete(&self, path: &str) -> Result<Response<Bytes>> {
         self.send(Method::DELETE, path, HeaderMap::new(), None, None)
@@ -566,6 +882,221 @@ impl WebDavClient {
         self.send(Method::DELETE, path, h, None, None).await
     }
 
+    /// `DELETE` submitting an [`IfHeader`], e.g. the lock token of a locked resource.
+    pub async fn delete_if(&self, path: &str, if_header: &IfHeader) -> Result<Response<Bytes>> {
+        let mut h = HeaderMap::new();
+        h.insert("If", header::HeaderValue::from_str(&if_header.build()?)?);
+        self.send(Method::DELETE, path, h, None, None).await
+    }
+
+    /// Settle a write of `local` to `path` rejected with `412 Precondition Failed`,
+    /// according to `policy`.
+    ///
+    /// A merged write conflicting again is merged anew with the latest server copy, at most
+    /// [`MAX_MERGE_ATTEMPTS`] times. The attached ETag cache, if any, records the outcome.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a request, the merge callback or the cache fails, or the
+    /// server rejects the write.
+    pub async fn resolve_conflict(
+        &self,
+        path: &str,
+        content_type: &str,
+        local: Bytes,
+        policy: &ConflictPolicy,
+    ) -> Result<ConflictResolution> {
+        let key = self.build_uri(path)?.path().to_string();
+        let resolution = match policy {
+            ConflictPolicy::ServerWins => {
+                let (body, etag) = self.fetch_with_etag(path).await?;
+                ConflictResolution::ServerKept { body, etag }
+            }
+            ConflictPolicy::ClientWins => {
+                let etag = self
+                    .put_conflicting(path, content_type, local.clone(), None)
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow!("PUT failed with {}", StatusCode::PRECONDITION_FAILED)
+                    })?;
+                ConflictResolution::ClientWritten { etag }
+            }
+            ConflictPolicy::Merge(merge) => {
+                let mut merged = None;
+                for _ in 0..MAX_MERGE_ATTEMPTS {
+                    let (server, server_etag) = self.fetch_with_etag(path).await?;
+                    let body = merge(&local, &server)?;
+                    if let Some(etag) = self
+                        .put_conflicting(path, content_type, body.clone(), server_etag.as_deref())
+                        .await?
+                    {
+                        merged = Some(ConflictResolution::Merged { body, etag });
+                        break;
+                    }
+                }
+                merged.ok_or_else(|| {
+                    anyhow!("merge still conflicting after {MAX_MERGE_ATTEMPTS} attempts")
+                })?
+            }
+        };
+        if let Some(cache) = self.etag_cache.as_deref() {
+            let body = match &resolution {
+                ConflictResolution::ServerKept { body, .. }
+                | ConflictResolution::Merged { body, .. } => body,
+                ConflictResolution::ClientWritten { .. } => &local,
+            };
+            cache_entry(cache, &key, resolution.etag(), body).await?;
+        }
+        Ok(resolution)
+    }
+
+    /// Replay the mutations pending in `queue`, oldest first, with their preconditions.
+    ///
+    /// A `PUT` rejected with `412 Precondition Failed` is settled with `policy` (see
+    /// [`resolve_conflict`](Self::resolve_conflict)); a conflicting `DELETE` is only forced
+    /// under [`ConflictPolicy::ClientWins`]. Every mutation the server answered is removed
+    /// from the queue and reported. The replay stops at the first request error, keeping
+    /// that mutation and the following ones queued. The attached ETag cache, if any,
+    /// records the writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the queue fails.
+    pub async fn replay_mutations<Q: MutationQueue + ?Sized>(
+        &self,
+        queue: &Q,
+        policy: &ConflictPolicy,
+    ) -> Result<ReplayReport> {
+        let mut report = ReplayReport::default();
+        for queued in queue.pending().await? {
+            let result = match self.replay_mutation(&queued.mutation, policy).await {
+                Ok(result) => result,
+                Err(err) => {
+                    report.interrupted = Some(format!("{err:#}"));
+                    break;
+                }
+            };
+            queue.remove(queued.id).await?;
+            report.outcomes.push(ReplayOutcome {
+                mutation: queued,
+                result,
+            });
+        }
+        Ok(report)
+    }
+
+    async fn replay_mutation(
+        &self,
+        mutation: &Mutation,
+        policy: &ConflictPolicy,
+    ) -> Result<ReplayResult> {
+        let key = self.build_uri(mutation.path())?.path().to_string();
+        let cache = self.etag_cache.as_deref();
+        let mut headers = HeaderMap::new();
+        match mutation.precondition() {
+            Precondition::None => {}
+            Precondition::IfMatch(etag) => {
+                headers.insert(header::IF_MATCH, header::HeaderValue::from_str(etag)?);
+            }
+            Precondition::IfNoneMatch => {
+                headers.insert(header::IF_NONE_MATCH, header::HeaderValue::from_static("*"));
+            }
+        }
+        match mutation {
+            Mutation::Put {
+                path,
+                content_type,
+                body,
+                ..
+            } => {
+                headers.insert(
+                    header::CONTENT_TYPE,
+                    header::HeaderValue::from_str(content_type)?,
+                );
+                let response = self
+                    .send(Method::PUT, path, headers, Some(body.clone()), None)
+                    .await?;
+                let status = response.status();
+                if status == StatusCode::PRECONDITION_FAILED {
+                    let resolution = self
+                        .resolve_conflict(path, content_type, body.clone(), policy)
+                        .await?;
+                    return Ok(ReplayResult::Resolved(resolution));
+                }
+                if !status.is_success() {
+                    return Ok(ReplayResult::Rejected {
+                        status: status.as_u16(),
+                    });
+                }
+                let etag = Self::etag_from_headers(response.headers());
+                if let Some(cache) = cache {
+                    cache_entry(cache, &key, etag.as_deref(), body).await?;
+                }
+                Ok(ReplayResult::Applied { etag })
+            }
+            Mutation::Delete { path, .. } => {
+                let mut response = self.send(Method::DELETE, path, headers, None, None).await?;
+                if response.status() == StatusCode::PRECONDITION_FAILED
+                    && matches!(policy, ConflictPolicy::ClientWins)
+                {
+                    response = self.delete(path).await?;
+                }
+                let status = response.status();
+                // Already gone is what the delete asked for.
+                if !status.is_success() && status != StatusCode::NOT_FOUND {
+                    return Ok(ReplayResult::Rejected {
+                        status: status.as_u16(),
+                    });
+                }
+                if let Some(cache) = cache {
+                    cache.remove(&key).await?;
+                }
+                Ok(ReplayResult::Applied { etag: None })
+            }
+        }
+    }
+
+    /// `GET` `path`, returning its body and ETag.
+    async fn fetch_with_etag(&self, path: &str) -> Result<(Bytes, Option<String>)> {
+        let response = self.get(path).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("GET failed with {}", status));
+        }
+        let etag = Self::etag_from_headers(response.headers());
+        Ok((response.into_body(), etag))
+    }
+
+    /// `PUT` `body`, guarded by `If-Match` when `if_match` is given. Returns the new ETag,
+    /// or `None` when the write conflicted (`412`).
+    async fn put_conflicting(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: Bytes,
+        if_match: Option<&str>,
+    ) -> Result<Option<Option<String>>> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_str(content_type)?,
+        );
+        if let Some(etag) = if_match {
+            headers.insert(header::IF_MATCH, header::HeaderValue::from_str(etag)?);
+        }
+        let response = self
+            .send(Method::PUT, path, headers, Some(body), None)
+            .await?;
+        let status = response.status();
+        if status == StatusCode::PRECONDITION_FAILED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(anyhow!("PUT failed with {}", status));
+        }
+        Ok(Some(Self::etag_from_headers(response.headers())))
+    }
+
     /// Send a WebDAV `COPY` from `src_path` to an absolute `Destination` URL.
     pub async fn copy(
         &self,
@@ -573,19 +1104,147 @@ impl WebDavClient {
         dest_absolute_url: &str,
         overwrite: bool,
     ) -> Result<Response<Bytes>> {
-        let mut h = HeaderMap::new();
-        h.insert(
-            "Destination",
-            header::HeaderValue::from_str(dest_absolute_url)?,
-        );
-        h.insert(
-            "Overwrite",
-            header::HeaderValue::from_static(if overwrite { "T" } else { "F" }),
-        );
+        let h = destination_headers(dest_absolute_url, overwrite)?;
         self.send(Method::from_bytes(b"COPY")?, src_path, h, None, None)
             .await
     }
 
+    /// `COPY` submitting an [`IfHeader`], e.g. the lock token of a locked destination
+    /// collection.
+    pub async fn copy_if(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        let mut h = destination_headers(dest_absolute_url, overwrite)?;
+        h.insert("If", header::HeaderValue::from_str(&if_header.build()?)?);
+        self.send(Method::from_bytes(b"COPY")?, src_path, h, None, None)
+            .await
+    }
+
+    /// `DELETE` the collection at `path` with everything below it, retrying the members
+    /// the server could not delete.
+    ///
+    /// A server failing on some members answers `207 Multi-Status` and keeps them with their
+    /// ancestors (RFC 4918 §9.6.1). Each failing member is then deleted on its own and the
+    /// collection deleted again, for at most `max_retries` rounds; ancestors reported as
+    /// `424 Failed Dependency` are not deleted on their own.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a request fails, or the server answers neither a success status
+    /// nor `207 Multi-Status`.
+    pub async fn delete_recursive(
+        &self,
+        path: &str,
+        max_retries: usize,
+    ) -> Result<RecursiveReport> {
+        let mut first: Option<Vec<String>> = None;
+        let mut round = 0;
+        loop {
+            let response = self.delete(path).await?;
+            let status = response.status();
+            if status != StatusCode::MULTI_STATUS {
+                // A retried member may have taken the collection with it.
+                let gone = first.is_some() && status == StatusCode::NOT_FOUND;
+                if !status.is_success() && !gone {
+                    return Err(anyhow!("DELETE failed with {}", status));
+                }
+                return Ok(RecursiveReport {
+                    status: status.as_u16(),
+                    failed: Vec::new(),
+                    recovered: first.unwrap_or_default(),
+                });
+            }
+            let failed = self.member_failures(response.body())?;
+            let first_hrefs =
+                first.get_or_insert_with(|| failed.iter().map(|f| f.href.clone()).collect());
+            if failed.is_empty() || round == max_retries {
+                return Ok(RecursiveReport {
+                    status: status.as_u16(),
+                    recovered: recovered(first_hrefs.clone(), &failed),
+                    failed,
+                });
+            }
+            for failure in failed.iter().filter(|f| f.status_code() != Some(424)) {
+                // Members still failing are reported by the next round.
+                let _ = self.delete(&failure.href).await;
+            }
+            round += 1;
+        }
+    }
+
+    /// `COPY` the collection at `src_path` with everything below it to an absolute
+    /// `Destination` URL, retrying the members the server could not copy.
+    ///
+    /// A server failing on some members answers `207 Multi-Status` (RFC 4918 §9.8.8); each
+    /// of them, whether reported by its source or destination href, is then copied on its
+    /// own, at most `max_retries` times.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a request fails, or the server answers neither a success status
+    /// nor `207 Multi-Status`.
+    pub async fn copy_recursive(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        max_retries: usize,
+    ) -> Result<RecursiveReport> {
+        let response = self.copy(src_path, dest_absolute_url, overwrite).await?;
+        let status = response.status();
+        if status != StatusCode::MULTI_STATUS {
+            if !status.is_success() {
+                return Err(anyhow!("COPY failed with {}", status));
+            }
+            return Ok(RecursiveReport {
+                status: status.as_u16(),
+                ..RecursiveReport::default()
+            });
+        }
+
+        let src_root = normalize_collection_path(self.build_uri(src_path)?.path());
+        let dest_url = dest_absolute_url.trim_end_matches('/');
+        let dest_root = normalize_collection_path(&href_path(dest_absolute_url));
+        let mut failed = self.member_failures(response.body())?;
+        let first: Vec<String> = failed.iter().map(|f| f.href.clone()).collect();
+        for _ in 0..max_retries {
+            if failed.is_empty() {
+                break;
+            }
+            let mut still_failing = Vec::new();
+            for failure in failed {
+                let path = normalize_collection_path(&href_path(&failure.href));
+                let relative = path
+                    .strip_prefix(&src_root)
+                    .or_else(|| path.strip_prefix(&dest_root))
+                    .filter(|rest| rest.starts_with('/'));
+                let Some(relative) = relative else {
+                    still_failing.push(failure);
+                    continue;
+                };
+                let source = format!("{src_root}{relative}");
+                let destination = format!("{dest_url}{relative}");
+                match self.copy(&source, &destination, overwrite).await {
+                    Ok(r) if r.status() == StatusCode::MULTI_STATUS => {
+                        still_failing.extend(self.member_failures(r.body())?)
+                    }
+                    Ok(r) if r.status().is_success() => {}
+                    _ => still_failing.push(failure),
+                }
+            }
+            failed = still_failing;
+        }
+        Ok(RecursiveReport {
+            status: status.as_u16(),
+            recovered: recovered(first, &failed),
+            failed,
+        })
+    }
+
     /// Send a WebDAV `MOVE` from `src_path` to an absolute `Destination` URL.
     pub async fn r#move(
         &self,
@@ -593,15 +1252,22 @@ impl WebDavClient {
         dest_absolute_url: &str,
         overwrite: bool,
     ) -> Result<Response<Bytes>> {
-        let mut h = HeaderMap::new();
-        h.insert(
-            "Destination",
-            header::HeaderValue::from_str(dest_absolute_url)?,
-        );
-        h.insert(
-            "Overwrite",
-            header::HeaderValue::from_static(if overwrite { "T" } else { "F" }),
-        );
+        let h = destination_headers(dest_absolute_url, overwrite)?;
+        self.send(Method::from_bytes(b"MOVE")?, src_path, h, None, None)
+            .await
+    }
+
+    /// `MOVE` submitting an [`IfHeader`]: the lock tokens of a locked source and of the
+    /// locked collections the move touches.
+    pub async fn move_if(
+        &self,
+        src_path: &str,
+        dest_absolute_url: &str,
+        overwrite: bool,
+        if_header: &IfHeader,
+    ) -> Result<Response<Bytes>> {
+        let mut h = destination_headers(dest_absolute_url, overwrite)?;
+        h.insert("If", header::HeaderValue::from_str(&if_header.build()?)?);
         self.send(Method::from_bytes(b"MOVE")?, src_path, h, None, None)
             .await
     }
@@ -629,6 +1295,145 @@ impl WebDavClient {
         .await
     }
 
+    /// `PROPFIND` with `<D:allprop/>`, also requesting the `include` properties (see
+    /// [`build_allprop_body`]).
+    pub async fn propfind_allprop(
+        &self,
+        path: &str,
+        depth: Depth,
+        include: &[PropertyName],
+    ) -> Result<Response<Bytes>> {
+        self.propfind(path, depth, &build_allprop_body(include)?)
+            .await
+    }
+
+    /// List the names of the properties exposed by `path` (and its members, with
+    /// `Depth::One`) with a `<D:propname/>` `PROPFIND`.
+    pub async fn propfind_propname(&self, path: &str, depth: Depth) -> Result<Vec<PropNames>> {
+        let response = self.propfind(path, depth, PROPNAME_BODY).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("PROPFIND propname failed with {}", status));
+        }
+        parse_propnames(response.body())
+    }
+
+    /// `PROPFIND` the properties of `registry` and return their raw values per resource,
+    /// including vendor properties the typed CalDAV/CardDAV parsers ignore.
+    pub async fn propfind_properties(
+        &self,
+        path: &str,
+        depth: Depth,
+        registry: &PropertyRegistry,
+    ) -> Result<Vec<ResourceProps>> {
+        let body = registry.build_propfind_body()?;
+        let response = self.propfind(path, depth, &body).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("PROPFIND failed with {}", status));
+        }
+        parse_properties_with_limits(response.body(), self.parse_limits)
+    }
+
+    /// `PROPFIND` `path` (and its members, with `Depth::One`) into typed [`DavResource`]s:
+    /// display name, ETag, resource types, content type and length, last modification and
+    /// the `extra` properties.
+    pub async fn propfind_resources(
+        &self,
+        path: &str,
+        depth: Depth,
+        extra: &[PropertyName],
+    ) -> Result<Vec<DavResource>> {
+        Ok(self
+            .propfind_properties(path, depth, &resource_registry(extra))
+            .await?
+            .into_iter()
+            .map(DavResource::from)
+            .collect())
+    }
+
+    /// List the members of the collection at `path` with a `Depth: 1` `PROPFIND`, as typed
+    /// [`DavResource`]s sorted by href; the collection itself is left out.
+    ///
+    /// Narrow the listing with [`CollectionListing::filter`] and walk it with
+    /// [`CollectionListing::pages`].
+    pub async fn list_collection(&self, path: &str) -> Result<CollectionListing> {
+        let own_path = normalize_collection_path(self.build_uri(path)?.path());
+        let members = self
+            .propfind_resources(path, Depth::One, &[])
+            .await?
+            .into_iter()
+            .filter(|resource| normalize_collection_path(&href_path(&resource.href)) != own_path)
+            .collect();
+        Ok(CollectionListing::new(members))
+    }
+
+    /// `PROPFIND` the whole tree below `path`, returning one `207 Multi-Status` body per
+    /// request.
+    ///
+    /// A single `Depth: infinity` request is tried first. Servers that forbid it
+    /// (RFC 4918 §9.1.1, see [`WebDavClient::is_propfind_finite_depth`]) are crawled
+    /// collection by collection with `Depth: 1` instead, so every resource is reported once
+    /// in one of the returned bodies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** when `xml_body` requests neither
+    /// `DAV:resourcetype` nor `allprop`, since the crawl needs it to find sub-collections;
+    /// otherwise when a request fails or is not `207 Multi-Status`.
+    pub async fn propfind_infinity(&self, path: &str, xml_body: &str) -> Result<Vec<Bytes>> {
+        let lower = xml_body.to_ascii_lowercase();
+        if !lower.contains("resourcetype") && !lower.contains("allprop") {
+            return Err(anyhow!(
+                "PROPFIND body must request DAV:resourcetype or allprop for a Depth: infinity crawl"
+            ));
+        }
+        let response = self.propfind(path, Depth::Infinity, xml_body).await?;
+        if response.status() == StatusCode::MULTI_STATUS {
+            return Ok(vec![response.into_body()]);
+        }
+        if !Self::is_propfind_finite_depth(&response) {
+            return Err(anyhow!(
+                "PROPFIND Depth: infinity failed with {}",
+                response.status()
+            ));
+        }
+
+        let mut bodies = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = VecDeque::from([self.build_uri(path)?.path().to_string()]);
+        visited.insert(normalize_collection_path(&pending[0]));
+        while let Some(collection) = pending.pop_front() {
+            let response = self.propfind(&collection, Depth::One, xml_body).await?;
+            if response.status() != StatusCode::MULTI_STATUS {
+                return Err(anyhow!(
+                    "PROPFIND Depth: 1 of {} failed with {}",
+                    collection,
+                    response.status()
+                ));
+            }
+            let body = response.into_body();
+            for item in parse_common_multistatus(&body, self.parse_limits)? {
+                if !item.is_collection {
+                    continue;
+                }
+                let href = href_path(&item.href);
+                if visited.insert(normalize_collection_path(&href)) {
+                    pending.push_back(href);
+                }
+            }
+            bodies.push(body);
+        }
+        Ok(bodies)
+    }
+
+    /// `true` when `response` is the `403` with a `DAV:propfind-finite-depth` precondition
+    /// that servers return for a forbidden `Depth: infinity` `PROPFIND`.
+    pub fn is_propfind_finite_depth(response: &Response<Bytes>) -> bool {
+        response.status() == StatusCode::FORBIDDEN
+            && String::from_utf8_lossy(response.body()).contains("propfind-finite-depth")
+    }
+
     /// Send a WebDAV `PROPPATCH` with a custom XML body.
     pub async fn proppatch(&self, path: &str, xml_body: &str) -> Result<Response<Bytes>> {
         let mut h = HeaderMap::new();
@@ -646,6 +1451,29 @@ impl WebDavClient {
         .await
     }
 
+    /// Set and remove properties of `path` with a [`PropPatch`] and report the outcome of
+    /// every property.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error **before any network I/O** if the patch is empty or invalid, and
+    /// afterwards if the request fails, the server responds with a non-success status, or the
+    /// `207 Multi-Status` body cannot be parsed. Rejected properties are not an error; check
+    /// [`PropPatchResult::failed`].
+    pub async fn proppatch_properties(
+        &self,
+        path: &str,
+        patch: &PropPatch,
+    ) -> Result<PropPatchResult> {
+        let body = patch.build()?;
+        let response = self.proppatch(path, &body).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("PROPPATCH failed with {}", status));
+        }
+        parse_proppatch_response(response.body())
+    }
+
     /// Send a WebDAV `REPORT` with a custom XML body and `Depth`.
     pub async fn report(
         &self,
@@ -683,6 +1511,362 @@ impl WebDavClient {
             .await
     }
 
+    /// Create a collection with an extended `MKCOL` (RFC 5689), setting its resource type
+    /// and initial properties in one request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the body cannot be built or the server does not answer
+    /// `201 Created`; properties the server rejected are listed in the message.
+    pub async fn mkcol_extended(&self, path: &str, mkcol: &ExtendedMkcol) -> Result<()> {
+        let body = mkcol.build()?;
+        let response = self.mkcol(path, Some(&body)).await?;
+        let status = response.status();
+        if status == StatusCode::CREATED {
+            return Ok(());
+        }
+        let rejected: Vec<String> = parse_propstats(response.body())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, prop)| !prop.is_success())
+            .map(|(_, prop)| format!("{{{}}}{}", prop.namespace, prop.name))
+            .collect();
+        if rejected.is_empty() {
+            Err(anyhow!("Extended MKCOL failed with {}", status))
+        } else {
+            Err(anyhow!(
+                "Extended MKCOL failed with {}: rejected {}",
+                status,
+                rejected.join(", ")
+            ))
+        }
+    }
+
+    /// Make the resource at `href` also reachable as `segment` in the collection at
+    /// `collection` with `BIND` (RFC 5842 §4).
+    ///
+    /// Unlike `COPY`, both hrefs then refer to the same resource: changes through one are
+    /// visible through the other. An existing binding named `segment` is replaced only when
+    /// `overwrite` is set.
+    pub async fn bind(
+        &self,
+        collection: &str,
+        segment: &str,
+        href: &str,
+        overwrite: bool,
+    ) -> Result<()> {
+        let body = build_bind_body(segment, href)?;
+        self.send_binding("BIND", collection, body, Some(overwrite))
+            .await
+    }
+
+    /// Remove the binding `segment` from the collection at `collection` with `UNBIND`
+    /// (RFC 5842 §5). The resource itself is only deleted with its last binding.
+    pub async fn unbind(&self, collection: &str, segment: &str) -> Result<()> {
+        let body = build_unbind_body(segment)?;
+        self.send_binding("UNBIND", collection, body, None).await
+    }
+
+    /// Atomically move the binding at `href` to `segment` in the collection at `collection`
+    /// with `REBIND` (RFC 5842 §6), keeping the identity of the resource (unlike `MOVE`).
+    pub async fn rebind(
+        &self,
+        collection: &str,
+        segment: &str,
+        href: &str,
+        overwrite: bool,
+    ) -> Result<()> {
+        let body = build_rebind_body(segment, href)?;
+        self.send_binding("REBIND", collection, body, Some(overwrite))
+            .await
+    }
+
+    async fn send_binding(
+        &self,
+        method: &str,
+        collection: &str,
+        body: String,
+        overwrite: Option<bool>,
+    ) -> Result<()> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/xml; charset=utf-8"),
+        );
+        if let Some(overwrite) = overwrite {
+            h.insert(
+                "Overwrite",
+                header::HeaderValue::from_static(if overwrite { "T" } else { "F" }),
+            );
+        }
+        let response = self
+            .send(
+                Method::from_bytes(method.as_bytes())?,
+                collection,
+                h,
+                Some(Bytes::from(body)),
+                None,
+            )
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("{} failed with {}", method, status));
+        }
+        Ok(())
+    }
+
+    /// Take a write lock on `path` with `LOCK`.
+    ///
+    /// Without a `Depth` header, locking a collection locks its whole tree (RFC 4918 §9.10.3).
+    /// The granted lifetime may be shorter than `timeout`; renew the lock with
+    /// [`WebDavClient::refresh_lock`] before it expires.
+    pub async fn lock(
+        &self,
+        path: &str,
+        scope: LockScope,
+        timeout: LockTimeout,
+        owner: Option<&str>,
+    ) -> Result<Lock> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/xml; charset=utf-8"),
+        );
+        h.insert(
+            "Timeout",
+            header::HeaderValue::from_str(&timeout.as_header())?,
+        );
+        let body = build_lockinfo_body(scope, owner);
+        let response = self
+            .send(
+                Method::from_bytes(b"LOCK")?,
+                path,
+                h,
+                Some(Bytes::from(body)),
+                None,
+            )
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("LOCK failed with {}", status));
+        }
+        let token = response
+            .headers()
+            .get("Lock-Token")
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_lock_token_header);
+        let locks = parse_lock_discovery_with_limits(response.body(), self.parse_limits)?;
+        // Shared locks list every holder; the `Lock-Token` header tells which one is ours.
+        let lock = match token {
+            Some(token) => locks
+                .into_iter()
+                .find(|lock| lock.token == token)
+                .unwrap_or(Lock {
+                    token,
+                    timeout: None,
+                }),
+            None => locks
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("LOCK response carries no lock token"))?,
+        };
+        Ok(lock)
+    }
+
+    /// Renew `lock` on `path` for `timeout` with a body-less `LOCK` (RFC 4918 §9.10.2).
+    pub async fn refresh_lock(
+        &self,
+        path: &str,
+        lock: &Lock,
+        timeout: LockTimeout,
+    ) -> Result<Lock> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            "If",
+            header::HeaderValue::from_str(&IfHeader::lock(lock).build()?)?,
+        );
+        h.insert(
+            "Timeout",
+            header::HeaderValue::from_str(&timeout.as_header())?,
+        );
+        let response = self
+            .send(Method::from_bytes(b"LOCK")?, path, h, None, None)
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("LOCK refresh failed with {}", status));
+        }
+        let refreshed = parse_lock_discovery_with_limits(response.body(), self.parse_limits)?
+            .into_iter()
+            .find(|active| active.token == lock.token);
+        Ok(refreshed.unwrap_or_else(|| lock.clone()))
+    }
+
+    /// Release `lock` on `path` with `UNLOCK`.
+    pub async fn unlock(&self, path: &str, lock: &Lock) -> Result<()> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            "Lock-Token",
+            header::HeaderValue::from_str(&format!("<{}>", lock.token))?,
+        );
+        let response = self
+            .send(Method::from_bytes(b"UNLOCK")?, path, h, None, None)
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("UNLOCK failed with {}", status));
+        }
+        Ok(())
+    }
+
+    /// Read the access control list of `path` (`DAV:acl`, RFC 3744 §5.5).
+    ///
+    /// Reading the ACL requires the `read-acl` privilege.
+    pub async fn get_acl(&self, path: &str) -> Result<Vec<Ace>> {
+        let response = self.propfind(path, Depth::Zero, ACL_PROPFIND_BODY).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("ACL retrieval failed with {}", status));
+        }
+        parse_acl_with_limits(response.body(), self.parse_limits)
+    }
+
+    /// Replace the access control list of `path` with `aces` using the `ACL` method
+    /// (RFC 3744 §8.1).
+    ///
+    /// Protected and inherited entries are skipped (see [`build_acl_body`]), so the
+    /// entries returned by [`WebDavClient::get_acl`] can be edited and written back.
+    pub async fn acl(&self, path: &str, aces: &[Ace]) -> Result<()> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/xml; charset=utf-8"),
+        );
+        let body = build_acl_body(aces)?;
+        let response = self
+            .send(
+                Method::from_bytes(b"ACL")?,
+                path,
+                h,
+                Some(Bytes::from(body)),
+                None,
+            )
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("ACL failed with {}", status));
+        }
+        Ok(())
+    }
+
+    /// Discover the collections holding the server's principals
+    /// (`DAV:principal-collection-set`, RFC 3744 §5.8) from any resource, typically the
+    /// current user's principal.
+    pub async fn principal_collection_set(&self, path: &str) -> Result<Vec<String>> {
+        let response = self
+            .propfind(path, Depth::Zero, PRINCIPAL_COLLECTION_SET_BODY)
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!(
+                "PROPFIND principal-collection-set failed with {}",
+                status
+            ));
+        }
+        parse_principal_collection_set_with_limits(response.body(), self.parse_limits)
+    }
+
+    /// List the principals (users, groups, resources) directly inside the principal
+    /// collection at `collection_path`, e.g. one returned by
+    /// [`WebDavClient::principal_collection_set`].
+    pub async fn list_principals(&self, collection_path: &str) -> Result<Vec<PrincipalInfo>> {
+        let response = self
+            .propfind(collection_path, Depth::One, &principal_propfind_body())
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("PROPFIND principals failed with {}", status));
+        }
+        parse_principals_with_limits(response.body(), self.parse_limits)
+    }
+
+    /// List the principals referenced by the ACL of `path` with the
+    /// `DAV:acl-principal-prop-set` `REPORT` (RFC 3744 §9.2).
+    pub async fn acl_principal_prop_set(&self, path: &str) -> Result<Vec<PrincipalInfo>> {
+        let response = self
+            .report(path, Depth::Zero, &acl_principal_prop_set_body())
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!(
+                "acl-principal-prop-set REPORT failed with {}",
+                status
+            ));
+        }
+        parse_principals_with_limits(response.body(), self.parse_limits)
+    }
+
+    /// List the versions of the version-controlled resource at `path` with the DeltaV
+    /// `DAV:version-tree` `REPORT` (RFC 3253 §3.7).
+    ///
+    /// Fetch the content of a version with [`WebDavClient::get`] on its href.
+    pub async fn version_tree(&self, path: &str) -> Result<Vec<VersionEntry>> {
+        let response = self.report(path, Depth::Zero, &version_tree_body()).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("version-tree REPORT failed with {}", status));
+        }
+        parse_versions_with_limits(response.body(), self.parse_limits)
+    }
+
+    /// List the versions of `path` through its `DAV:version-history` with the
+    /// `DAV:expand-property` `REPORT` (RFC 3253 §3.8), for servers that do not implement
+    /// `version-tree`.
+    pub async fn version_history(&self, path: &str) -> Result<Vec<VersionEntry>> {
+        let response = self
+            .report(path, Depth::Zero, &version_history_body())
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("expand-property REPORT failed with {}", status));
+        }
+        parse_versions_with_limits(response.body(), self.parse_limits)
+    }
+
+    /// Run a DASL `basicsearch` (RFC 5323) with `SEARCH` on `path` and return the matching
+    /// resources.
+    ///
+    /// `path` is usually the search arbiter advertised by the server, often the scope itself;
+    /// see [`WebDavClient::supports_search`].
+    pub async fn search(&self, path: &str, query: &BasicSearch) -> Result<Vec<DavItemCommon>> {
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/xml; charset=utf-8"),
+        );
+        let body = query.build()?;
+        let response = self
+            .send(
+                Method::from_bytes(b"SEARCH")?,
+                path,
+                h,
+                Some(Bytes::from(body)),
+                None,
+            )
+            .await?;
+        let status = response.status();
+        if status != StatusCode::MULTI_STATUS {
+            return Err(anyhow!("SEARCH failed with {}", status));
+        }
+        parse_common_multistatus(response.body(), self.parse_limits)
+    }
+
+    /// `true` when `OPTIONS` on `path` advertises the `DAV:basicsearch` grammar in its
+    /// `DASL` header.
+    pub async fn supports_search(&self, path: &str) -> Result<bool> {
+        Ok(self.capabilities(path).await?.supports_basicsearch())
+    }
+
     /// Extract the `ETag` from a response header map, if present.
     pub fn etag_from_headers(headers: &HeaderMap) -> Option<String> {
         headers
@@ -796,15 +1980,25 @@ impl WebDavClient {
     /// Check if the server supports WebDAV-Sync (RFC 6578) on the base collection.
     ///
     /// Detection strategy:
-    /// 1. **`DAV:supported-report-set`** — a `PROPFIND` with `Depth: 0` asks the
+    /// 1. **`OPTIONS`** — when the `Allow` header (see [`WebDavClient::capabilities`])
+    ///    lists methods but not `REPORT`, sync is reported unsupported without further
+    ///    requests.
+    /// 2. **`DAV:supported-report-set`** — a `PROPFIND` with `Depth: 0` asks the
     ///    collection which reports it supports; when the multistatus body
     ///    advertises the `sync-collection` report, support is confirmed.
-    /// 2. **Probe REPORT fallback** — when the `PROPFIND` does not confirm
+    /// 3. **Probe REPORT fallback** — when the `PROPFIND` does not confirm
     ///    support, a minimal `sync-collection` REPORT is attempted. Only a 2xx
     ///    status (which includes `207 Multi-Status`) counts as supported; any
     ///    other status — including `415 Unsupported Media Type` — reports
     ///    `false`.
     pub async fn supports_webdav_sync(&self) -> Result<bool> {
+        // An `Allow` header without `REPORT` rules any report out.
+        if let Ok(caps) = self.capabilities("").await
+            && !caps.allows("REPORT")
+        {
+            return Ok(false);
+        }
+
         // Primary: ask the collection which reports it supports (RFC 3253 §3.1.5).
         let supported_report_set = r#"<?xml version="1.0" encoding="utf-8"?>
 <D:propfind xmlns:D="DAV:">
@@ -841,6 +2035,76 @@ impl WebDavClient {
         }
     }
 
+    /// Discover the push transports offered for the collection at `path`, from the
+    /// WebDAV-Push `P:transports`/`P:topic` and Apple `CS:push-transports`/`CS:pushkey`
+    /// properties. An empty [`PushInfo`] means the server offers no push and clients
+    /// should keep polling.
+    pub async fn discover_push(&self, path: &str) -> Result<PushInfo> {
+        let response = self.propfind(path, Depth::Zero, PUSH_PROPFIND_BODY).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("Push discovery failed with {}", status));
+        }
+        parse_push_info(response.body())
+    }
+
+    /// Register (or renew) a WebDAV-Push subscription on the collection at `path`.
+    ///
+    /// Registering an already known push resource again renews it; the server answers
+    /// `201 Created` for new registrations and `204 No Content` for renewals.
+    pub async fn register_push(
+        &self,
+        path: &str,
+        subscription: &WebPushSubscription,
+    ) -> Result<PushRegistration> {
+        let push_resource: Uri = subscription
+            .push_resource
+            .parse()
+            .map_err(|e| anyhow!("Invalid push resource URL: {e}"))?;
+        if push_resource.scheme().is_none() || push_resource.host().is_none() {
+            return Err(anyhow!(
+                "Push resource must be an absolute URL: {}",
+                subscription.push_resource
+            ));
+        }
+
+        let mut h = HeaderMap::new();
+        h.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/xml; charset=utf-8"),
+        );
+        let body = build_push_register_body(subscription);
+        let response = self
+            .send(Method::POST, path, h, Some(Bytes::from(body)), None)
+            .await?;
+        let status = response.status();
+        if status != StatusCode::CREATED && status != StatusCode::NO_CONTENT {
+            return Err(anyhow!("Push registration failed with {}", status));
+        }
+        let header_value = |name: header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Ok(PushRegistration {
+            url: header_value(header::LOCATION),
+            expires: header_value(header::EXPIRES),
+            created: status == StatusCode::CREATED,
+        })
+    }
+
+    /// Remove a push subscription by its registration URL (see [`PushRegistration::url`]).
+    pub async fn unregister_push(&self, registration_url: &str) -> Result<()> {
+        let response = self.delete(registration_url).await?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            return Err(anyhow!("Push unregistration failed with {}", status));
+        }
+        Ok(())
+    }
+
     /// Streaming variant of `PROPFIND`, returning the non-aggregated body.
     pub async fn propfind_stream(
         &self,
@@ -886,4 +2150,89 @@ impl WebDavClient {
         )
         .await
     }
+
+    /// Members of a `207 Multi-Status` body reported with a non-success status.
+    fn member_failures(&self, body: &[u8]) -> Result<Vec<MemberFailure>> {
+        Ok(parse_common_multistatus(body, self.parse_limits)?
+            .into_iter()
+            .filter(|item| {
+                !item
+                    .status
+                    .as_deref()
+                    .and_then(http_status_code)
+                    .is_some_and(|code| (200..300).contains(&code))
+            })
+            .map(|item| MemberFailure {
+                href: item.href,
+                status: item.status,
+            })
+            .collect())
+    }
+}
+
+fn destination_headers(dest_absolute_url: &str, overwrite: bool) -> Result<HeaderMap> {
+    let mut h = HeaderMap::new();
+    h.insert(
+        "Destination",
+        header::HeaderValue::from_str(dest_absolute_url)?,
+    );
+    h.insert(
+        "Overwrite",
+        header::HeaderValue::from_static(if overwrite { "T" } else { "F" }),
+    );
+    Ok(h)
+}
+
+/// Store `etag` and the hash of `body` for `key`, or forget `key` without an ETag.
+async fn cache_entry(
+    cache: &dyn EtagCache,
+    key: &str,
+    etag: Option<&str>,
+    body: &[u8],
+) -> Result<()> {
+    match etag {
+        Some(etag) => {
+            cache
+                .put(
+                    key,
+                    CacheEntry {
+                        etag: etag.to_string(),
+                        body_hash: body_hash(body),
+                    },
+                )
+                .await
+        }
+        None => cache.remove(key).await,
+    }
+}
+
+/// Path of an href that may be an absolute URL.
+fn href_path(href: &str) -> String {
+    match href.parse::<Uri>() {
+        Ok(uri) if uri.scheme().is_some() => uri.path().to_string(),
+        _ => href.to_string(),
+    }
+}
+
+/// Hrefs of `first` that are no longer in `failed`.
+fn recovered(first: Vec<String>, failed: &[MemberFailure]) -> Vec<String> {
+    first
+        .into_iter()
+        .filter(|href| !failed.iter().any(|f| &f.href == href))
+        .collect()
+}
+
+/// Path of a collection href without percent-encoding differences in case or a trailing
+/// slash, to recognise collections already crawled.
+fn normalize_collection_path(path: &str) -> String {
+    let trimmed = path.trim_end_matches('/');
+    let mut normalized = String::with_capacity(trimmed.len());
+    let mut chars = trimmed.chars();
+    while let Some(c) = chars.next() {
+        normalized.push(c);
+        if c == '%' {
+            normalized.extend(chars.by_ref().take(2).map(|h| h.to_ascii_uppercase()));
+        }
+    }
+    normalized
 }
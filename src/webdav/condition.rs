@@ -0,0 +1,95 @@
+//! Precondition and postcondition codes (RFC 4918 §16) that servers report in the
+//! `DAV:error` body of a failed request, e.g. `DAV:valid-sync-token` for a stale
+//! `sync-collection` token (RFC 6578 §3.2).
+
+use anyhow::{Result, anyhow};
+use hyper::StatusCode;
+use quick_xml::NsReader;
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+
+use crate::webdav::types::{InvalidSyncTokenError, PropertyName};
+
+/// Parse the condition codes of a `DAV:error` body: the names of the children of the root
+/// element. A body with another root yields no condition.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{PropertyName, parse_error_conditions};
+///
+/// let xml = r#"<d:error xmlns:d="DAV:"><d:valid-sync-token/></d:error>"#;
+/// let conditions = parse_error_conditions(xml.as_bytes())?;
+/// assert_eq!(conditions, vec![PropertyName::dav("valid-sync-token")]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_error_conditions(body: &[u8]) -> Result<Vec<PropertyName>> {
+    let mut xml = NsReader::from_reader(body);
+    xml.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    let mut in_error = false;
+    let mut conditions = Vec::new();
+
+    loop {
+        let (ns, event) = xml
+            .read_resolved_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let namespace = match ns {
+                    ResolveResult::Bound(ns) => String::from_utf8_lossy(ns.as_ref()).into_owned(),
+                    _ => String::new(),
+                };
+                if depth == 0 {
+                    in_error = namespace == "DAV:" && local == "error";
+                } else if depth == 1 && in_error {
+                    conditions.push(PropertyName::new(&namespace, &local));
+                }
+                if matches!(event, Event::Start(_)) {
+                    depth += 1;
+                }
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(conditions)
+}
+
+/// `true` when a failed `sync-collection` REPORT rejected its token: the body carries the
+/// `DAV:valid-sync-token` precondition, or the status is `403`/`409` without any condition
+/// (as some servers answer for expired tokens).
+pub fn is_invalid_sync_token(status: StatusCode, body: &[u8]) -> bool {
+    let conditions = parse_error_conditions(body).unwrap_or_default();
+    if conditions.contains(&PropertyName::dav("valid-sync-token")) {
+        return true;
+    }
+    matches!(status, StatusCode::FORBIDDEN | StatusCode::CONFLICT) && conditions.is_empty()
+}
+
+/// Error for a `sync-collection` REPORT that failed with `status`: an
+/// [`InvalidSyncTokenError`] when a token was sent and rejected, a plain error otherwise.
+pub(crate) fn sync_collection_error(
+    sync_token: Option<&str>,
+    status: StatusCode,
+    body: &[u8],
+) -> anyhow::Error {
+    match sync_token {
+        Some(token) if is_invalid_sync_token(status, body) => {
+            anyhow::Error::new(InvalidSyncTokenError {
+                token: token.to_string(),
+                status: status.as_u16(),
+            })
+        }
+        _ => anyhow!("REPORT sync-collection failed with {}", status),
+    }
+}
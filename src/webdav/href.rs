@@ -0,0 +1,137 @@
+//! Canonical form of the hrefs servers report, so the same resource keeps the same key
+//! whether it comes back absolute or relative, or with different percent-encoding.
+
+/// Canonical form of `href`, with a single way to spell each resource path.
+///
+/// - the scheme and authority of an absolute URL are dropped, as are a query and fragment;
+/// - percent-encoded characters that may appear literally in a path (letters, digits,
+///   `-._~!$&'()*+,;=:@`) are decoded, other escapes are upper-cased, and characters that
+///   must be escaped (spaces, non-ASCII, ...) are encoded;
+/// - empty segments (`//`), `.` and `..` segments are removed;
+/// - a trailing slash is kept, as it marks collections.
+///
+/// Relative hrefs are returned relative (without dot segments); see [`resolve_href`] to
+/// anchor them on a collection.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::normalize_href;
+///
+/// assert_eq!(
+///     normalize_href("https://dav.example.com/cal//work/./ev%65nt%3a1 a.ics"),
+///     "/cal/work/event:1%20a.ics"
+/// );
+/// assert_eq!(normalize_href("/books/jane/"), "/books/jane/");
+/// ```
+pub fn normalize_href(href: &str) -> String {
+    let path = strip_authority(href.trim());
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let absolute = path.starts_with('/');
+    let directory = path.ends_with('/') || path.ends_with("/.") || path.ends_with("/..");
+
+    let mut segments: Vec<String> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => match segments.last() {
+                Some(last) if last != ".." => {
+                    segments.pop();
+                }
+                // A relative href may climb above its base: keep its leading `..`.
+                _ if !absolute => segments.push("..".to_string()),
+                _ => {}
+            },
+            _ => segments.push(canonical_segment(segment)),
+        }
+    }
+    let mut normalized = segments.join("/");
+    if absolute {
+        normalized.insert(0, '/');
+    }
+    if directory && !normalized.is_empty() && !normalized.ends_with('/') {
+        normalized.push('/');
+    }
+    normalized
+}
+
+/// [`normalize_href`] of `href`, resolved against the collection at `base` (a path or URL)
+/// when it is relative.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::resolve_href;
+///
+/// assert_eq!(resolve_href("/cal/work", "event.ics"), "/cal/work/event.ics");
+/// assert_eq!(resolve_href("/cal/work/", "../home/a.ics"), "/cal/home/a.ics");
+/// assert_eq!(resolve_href("/cal/work/", "/cal/other.ics"), "/cal/other.ics");
+/// ```
+pub fn resolve_href(base: &str, href: &str) -> String {
+    let href = href.trim();
+    if href.starts_with('/') || has_scheme(href) {
+        return normalize_href(href);
+    }
+    let base = normalize_href(base);
+    let base = base.trim_end_matches('/');
+    normalize_href(&format!("{base}/{href}"))
+}
+
+/// `true` for an absolute URL (`scheme://...`).
+fn has_scheme(href: &str) -> bool {
+    href.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// Path of an absolute URL, `href` itself otherwise.
+fn strip_authority(href: &str) -> &str {
+    match href.split_once("://") {
+        Some((_, rest)) if has_scheme(href) => rest.find('/').map_or("/", |start| &rest[start..]),
+        _ => href,
+    }
+}
+
+/// Path characters that never need escaping (RFC 3986 `pchar` without `%`).
+fn is_path_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@".contains(&byte)
+}
+
+fn canonical_segment(segment: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    let bytes = segment.as_bytes();
+    let mut out = String::with_capacity(segment.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = match bytes[i] {
+            b'%' => match (
+                bytes.get(i + 1).copied().and_then(hex_value),
+                bytes.get(i + 2).copied().and_then(hex_value),
+            ) {
+                (Some(high), Some(low)) => {
+                    i += 2;
+                    high << 4 | low
+                }
+                _ => b'%',
+            },
+            byte => byte,
+        };
+        i += 1;
+        if is_path_char(byte) {
+            out.push(byte as char);
+        } else {
+            out.push('%');
+            out.push(HEX[usize::from(byte >> 4)] as char);
+            out.push(HEX[usize::from(byte & 0xf)] as char);
+        }
+    }
+    out
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
@@ -0,0 +1,308 @@
+//! WebDAV locking (RFC 4918 §9.10–9.11): `lockinfo` request bodies, `lockdiscovery`
+//! responses (see [`crate::webdav::WebDavClient::lock`]) and the `If` header that submits
+//! lock tokens and ETags with writes (RFC 4918 §10.4).
+
+use anyhow::{Result, anyhow};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::webdav::types::{Lock, LockScope, LockTimeout, ParseLimits};
+use crate::webdav::xml::{LimitGuard, escape_xml, local_name};
+
+/// Build a `LOCK` request body for a write lock with `scope`.
+///
+/// `owner` is sent as a `DAV:href`, typically a `mailto:` or profile URL.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{LockScope, build_lockinfo_body};
+///
+/// let body = build_lockinfo_body(LockScope::Exclusive, Some("mailto:jane@example.com"));
+/// assert!(body.contains("<D:lockscope><D:exclusive/></D:lockscope>"));
+/// assert!(body.contains("<D:owner><D:href>mailto:jane@example.com</D:href></D:owner>"));
+/// ```
+pub fn build_lockinfo_body(scope: LockScope, owner: Option<&str>) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:lockinfo xmlns:D="DAV:"><D:lockscope><D:{}/></D:lockscope><D:locktype><D:write/></D:locktype>"#,
+        scope.as_str()
+    );
+    if let Some(owner) = owner {
+        xml.push_str(&format!(
+            "<D:owner><D:href>{}</D:href></D:owner>",
+            escape_xml(owner)
+        ));
+    }
+    xml.push_str("</D:lockinfo>");
+    xml
+}
+
+/// Parse every `activelock` of a `DAV:lockdiscovery` property, as returned by `LOCK` or a
+/// `PROPFIND` for `lockdiscovery`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{LockTimeout, parse_lock_discovery};
+///
+/// let xml = r#"<D:prop xmlns:D="DAV:"><D:lockdiscovery><D:activelock>
+///   <D:timeout>Second-600</D:timeout>
+///   <D:locktoken><D:href>urn:uuid:e71d4fae</D:href></D:locktoken>
+/// </D:activelock></D:lockdiscovery></D:prop>"#;
+/// let locks = parse_lock_discovery(xml.as_bytes())?;
+/// assert_eq!(locks[0].token, "urn:uuid:e71d4fae");
+/// assert_eq!(locks[0].timeout, Some(LockTimeout::Seconds(600)));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_lock_discovery(body: &[u8]) -> Result<Vec<Lock>> {
+    parse_lock_discovery_with_limits(body, ParseLimits::default())
+}
+
+/// Variant of [`parse_lock_discovery`] with other [`ParseLimits`] than the default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when it is not well-formed XML.
+pub fn parse_lock_discovery_with_limits(body: &[u8], limits: ParseLimits) -> Result<Vec<Lock>> {
+    let mut xml = Reader::from_reader(body);
+    let mut buf = Vec::new();
+    let mut guard = LimitGuard::new(limits);
+    let mut path: Vec<String> = Vec::new();
+    let mut locks = Vec::new();
+    let mut current: Option<Lock> = None;
+    let mut text = String::new();
+
+    loop {
+        let event = xml
+            .read_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        guard.on_event(&event)?;
+        if matches!(event, Event::Start(_) | Event::Empty(_) | Event::End(_)) {
+            let value = text.trim();
+            if let Some(lock) = current.as_mut().filter(|_| !value.is_empty()) {
+                match path.iter().rev().map(String::as_str).collect::<Vec<_>>()[..] {
+                    ["href", "locktoken", ..] => lock.token = value.to_string(),
+                    ["timeout", ..] => lock.timeout = LockTimeout::parse(value),
+                    _ => {}
+                }
+            }
+            text.clear();
+        }
+        match event {
+            Event::Start(e) => {
+                path.push(local_name(e.name().as_ref()));
+                if path.last().is_some_and(|n| n == "activelock") {
+                    current = Some(Lock {
+                        token: String::new(),
+                        timeout: None,
+                    });
+                }
+            }
+            Event::End(_) => {
+                if path.last().is_some_and(|n| n == "activelock") {
+                    locks.extend(current.take().filter(|lock| !lock.token.is_empty()));
+                }
+                path.pop();
+            }
+            Event::Eof => break,
+            event => guard.append_text(&mut text, &event)?,
+        }
+        buf.clear();
+    }
+    Ok(locks)
+}
+
+/// Token of a `Lock-Token` header value (`<urn:uuid:…>`), without angle brackets.
+pub fn parse_lock_token_header(value: &str) -> Option<String> {
+    let token = value.trim();
+    let token = token
+        .strip_prefix('<')
+        .and_then(|t| t.strip_suffix('>'))
+        .unwrap_or(token)
+        .trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// One `If` header list: conditions that must **all** hold, e.g. `(<token> ["etag"])`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IfList {
+    conditions: Vec<IfCondition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum IfCondition {
+    Token { token: String, not: bool },
+    ETag { etag: String, not: bool },
+}
+
+impl IfList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the resource to be locked with `token` (e.g. [`Lock::token`]).
+    pub fn token(mut self, token: &str) -> Self {
+        self.conditions.push(IfCondition::Token {
+            token: token.to_string(),
+            not: false,
+        });
+        self
+    }
+
+    /// Require the resource **not** to be locked with `token`.
+    pub fn not_token(mut self, token: &str) -> Self {
+        self.conditions.push(IfCondition::Token {
+            token: token.to_string(),
+            not: true,
+        });
+        self
+    }
+
+    /// Require the resource to have `etag`, as returned in its `ETag` header; a bare value is
+    /// quoted.
+    pub fn etag(mut self, etag: &str) -> Self {
+        self.conditions.push(IfCondition::ETag {
+            etag: etag.to_string(),
+            not: false,
+        });
+        self
+    }
+
+    /// Require the resource **not** to have `etag`.
+    pub fn not_etag(mut self, etag: &str) -> Self {
+        self.conditions.push(IfCondition::ETag {
+            etag: etag.to_string(),
+            not: true,
+        });
+        self
+    }
+
+    fn render(&self) -> Result<String> {
+        if self.conditions.is_empty() {
+            return Err(anyhow!("If header list has no condition"));
+        }
+        let mut rendered = Vec::with_capacity(self.conditions.len());
+        for condition in &self.conditions {
+            let (not, value) = match condition {
+                IfCondition::Token { token, not } => {
+                    let token = token.trim();
+                    if token.is_empty() || token.contains(['<', '>']) {
+                        return Err(anyhow!("Invalid lock token in If header: {token:?}"));
+                    }
+                    (*not, format!("<{token}>"))
+                }
+                IfCondition::ETag { etag, not } => {
+                    let etag = etag.trim();
+                    if etag.is_empty() || etag.contains(['[', ']']) {
+                        return Err(anyhow!("Invalid ETag in If header: {etag:?}"));
+                    }
+                    if etag.starts_with('"') || etag.starts_with("W/") {
+                        (*not, format!("[{etag}]"))
+                    } else {
+                        (*not, format!("[\"{etag}\"]"))
+                    }
+                }
+            };
+            rendered.push(if not { format!("Not {value}") } else { value });
+        }
+        Ok(format!("({})", rendered.join(" ")))
+    }
+}
+
+/// Builder for the WebDAV `If` header (RFC 4918 §10.4).
+///
+/// The header holds either untagged lists, which apply to the request URL, or lists tagged
+/// with the resource they apply to (needed for `MOVE`/`COPY` destinations and locked parent
+/// collections); the two forms cannot be mixed. The header matches when **any** list
+/// matches.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{IfHeader, IfList};
+///
+/// let header = IfHeader::new()
+///     .list(IfList::new().token("urn:uuid:1234").etag("\"abc\""))
+///     .build()?;
+/// assert_eq!(header, r#"(<urn:uuid:1234> ["abc"])"#);
+///
+/// let header = IfHeader::new()
+///     .tagged("/files/a.ics", IfList::new().token("urn:uuid:1"))
+///     .tagged("/files/", IfList::new().token("urn:uuid:2"))
+///     .build()?;
+/// assert_eq!(header, "</files/a.ics> (<urn:uuid:1>) </files/> (<urn:uuid:2>)");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IfHeader {
+    untagged: Vec<IfList>,
+    tagged: Vec<(String, Vec<IfList>)>,
+}
+
+impl IfHeader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `If` header submitting a single lock token for the request URL: `(<token>)`.
+    pub fn lock(lock: &Lock) -> Self {
+        Self::new().list(IfList::new().token(&lock.token))
+    }
+
+    /// Add an untagged list, applying to the request URL.
+    pub fn list(mut self, list: IfList) -> Self {
+        self.untagged.push(list);
+        self
+    }
+
+    /// Add a list for `resource`, an absolute URL or absolute path. Lists for the same
+    /// resource are grouped under one tag.
+    pub fn tagged(mut self, resource: &str, list: IfList) -> Self {
+        match self.tagged.iter_mut().find(|(tag, _)| tag == resource) {
+            Some((_, lists)) => lists.push(list),
+            None => self.tagged.push((resource.to_string(), vec![list])),
+        }
+        self
+    }
+
+    /// Build the header value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the header is empty, mixes tagged and untagged lists, has an
+    /// empty list, a relative resource tag, or a token or ETag that cannot be delimited.
+    pub fn build(&self) -> Result<String> {
+        match (self.untagged.is_empty(), self.tagged.is_empty()) {
+            (true, true) => Err(anyhow!("If header has no list")),
+            (false, false) => Err(anyhow!("If header cannot mix tagged and untagged lists")),
+            (false, true) => Ok(self
+                .untagged
+                .iter()
+                .map(IfList::render)
+                .collect::<Result<Vec<_>>>()?
+                .join(" ")),
+            (true, false) => {
+                let mut parts = Vec::new();
+                for (resource, lists) in &self.tagged {
+                    if !(resource.starts_with('/') || resource.contains("://"))
+                        || resource.contains(['<', '>', ' '])
+                    {
+                        return Err(anyhow!(
+                            "If header resource tag must be an absolute URL or path: {resource:?}"
+                        ));
+                    }
+                    parts.push(format!("<{resource}>"));
+                    for list in lists {
+                        parts.push(list.render()?);
+                    }
+                }
+                Ok(parts.join(" "))
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Extended `MKCOL` (RFC 5689): creating a collection with its resource type and initial
+//! properties in one request (see [`crate::webdav::WebDavClient::mkcol_extended`]).
+
+use anyhow::Result;
+
+use crate::webdav::property::Namespaces;
+use crate::webdav::types::PropertyName;
+use crate::webdav::xml::escape_xml;
+
+const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+const CARDDAV: &str = "urn:ietf:params:xml:ns:carddav";
+
+/// Builder for extended `MKCOL` request bodies.
+///
+/// Servers that implement neither `MKCALENDAR` nor `MKADDRESSBOOK` usually accept an
+/// extended `MKCOL` declaring the calendar or addressbook resource type instead.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{ExtendedMkcol, PropertyName};
+///
+/// let body = ExtendedMkcol::calendar()
+///     .displayname("Work")
+///     .prop_text(PropertyName::new("http://apple.com/ns/ical/", "calendar-color"), "#FF0000FF")
+///     .prop_xml(
+///         PropertyName::new("urn:ietf:params:xml:ns:caldav", "supported-calendar-component-set"),
+///         r#"<C:comp name="VEVENT"/>"#,
+///     )
+///     .build()?;
+/// assert!(body.contains("<D:resourcetype><D:collection/><C:calendar/></D:resourcetype>"));
+/// assert!(body.contains("<ICAL:calendar-color>#FF0000FF</ICAL:calendar-color>"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtendedMkcol {
+    resource_types: Vec<PropertyName>,
+    props: Vec<(PropertyName, String)>,
+    prefixes: Vec<(String, String)>,
+}
+
+impl ExtendedMkcol {
+    /// A plain collection (`DAV:collection` only).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A CalDAV calendar collection. Its `C:` prefix can be used in [`prop_xml`](Self::prop_xml)
+    /// values.
+    pub fn calendar() -> Self {
+        Self::new()
+            .resource_type(PropertyName::new(CALDAV, "calendar"))
+            .namespace("C", CALDAV)
+    }
+
+    /// A CardDAV addressbook collection. Its `CARD:` prefix can be used in
+    /// [`prop_xml`](Self::prop_xml) values.
+    pub fn addressbook() -> Self {
+        Self::new()
+            .resource_type(PropertyName::new(CARDDAV, "addressbook"))
+            .namespace("CARD", CARDDAV)
+    }
+
+    /// Add a resource type next to `DAV:collection`.
+    pub fn resource_type(mut self, resource_type: PropertyName) -> Self {
+        if resource_type != PropertyName::dav("collection")
+            && !self.resource_types.contains(&resource_type)
+        {
+            self.resource_types.push(resource_type);
+        }
+        self
+    }
+
+    /// `DAV:displayname`.
+    pub fn displayname(self, name: &str) -> Self {
+        self.prop_text(PropertyName::dav("displayname"), name)
+    }
+
+    /// Set a property to a text value, which is escaped.
+    pub fn prop_text(self, name: PropertyName, text: &str) -> Self {
+        self.prop_xml(name, &escape_xml(text))
+    }
+
+    /// Set a property to a raw XML value. It may use the `D:` prefix, the prefix of a
+    /// well-known namespace used by a property name, or ones declared with
+    /// [`namespace`](Self::namespace).
+    pub fn prop_xml(mut self, name: PropertyName, xml: &str) -> Self {
+        self.props.retain(|(existing, _)| *existing != name);
+        self.props.push((name, xml.to_string()));
+        self
+    }
+
+    /// Declare `prefix` for `namespace` in the body.
+    pub fn namespace(mut self, prefix: &str, namespace: &str) -> Self {
+        self.prefixes
+            .retain(|(p, ns)| p != prefix && ns != namespace);
+        self.prefixes
+            .push((prefix.to_string(), namespace.to_string()));
+        self
+    }
+
+    /// Build the `MKCOL` body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a property or resource type name, or a prefix, is not a valid
+    /// XML name.
+    pub fn build(&self) -> Result<String> {
+        let names = self
+            .resource_types
+            .iter()
+            .chain(self.props.iter().map(|(name, _)| name));
+        let namespaces = Namespaces::assign(names, &self.prefixes)?;
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><D:mkcol{}><D:set><D:prop><D:resourcetype><D:collection/>"#,
+            namespaces.declarations()
+        );
+        for resource_type in &self.resource_types {
+            xml.push_str(&namespaces.empty_element(resource_type));
+        }
+        xml.push_str("</D:resourcetype>");
+        for (name, value) in &self.props {
+            xml.push_str(&namespaces.element(name, value));
+        }
+        xml.push_str("</D:prop></D:set></D:mkcol>");
+        Ok(xml)
+    }
+}
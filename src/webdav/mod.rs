@@ -1,8 +1,50 @@
+pub mod acl;
+pub mod bind;
 pub mod client;
-pub(crate) mod streaming;
+pub mod condition;
+pub mod href;
+pub mod lock;
+pub mod mkcol;
+pub mod principal;
+pub mod property;
+pub mod propfind;
+pub mod proppatch;
+pub mod push;
+pub mod search;
+pub mod streaming;
 pub mod types;
+pub mod versioning;
 pub mod xml;
 
+pub use acl::{build_acl_body, parse_acl, parse_acl_with_limits};
+pub use bind::{build_bind_body, build_rebind_body, build_unbind_body};
 pub use client::{RequestCompressionMode, WebDavClient};
-pub use types::{BatchItem, DavItemCommon, Depth};
+pub use condition::{is_invalid_sync_token, parse_error_conditions};
+pub use href::{normalize_href, resolve_href};
+pub use lock::{
+    IfHeader, IfList, build_lockinfo_body, parse_lock_discovery, parse_lock_discovery_with_limits,
+    parse_lock_token_header,
+};
+pub use mkcol::ExtendedMkcol;
+pub use principal::{
+    parse_principal_collection_set, parse_principal_collection_set_with_limits, parse_principals,
+    parse_principals_with_limits,
+};
+pub use property::{
+    PropertyRegistry, parse_properties, parse_properties_with_limits, parse_resources,
+    resource_registry,
+};
+pub use propfind::{PROPNAME_BODY, build_allprop_body, parse_propnames};
+pub use proppatch::{PropPatch, parse_proppatch_response};
+pub use push::{build_push_register_body, parse_push_info};
+pub use search::{BasicSearch, SearchCondition};
+pub use types::{
+    Ace, AceAction, AcePrincipal, BatchItem, CollectionListing, DEFAULT_MAX_XML_DEPTH,
+    DEFAULT_MAX_XML_TEXT_SIZE, DavCapabilities, DavItemCommon, DavResource, Depth,
+    InvalidSyncTokenError, ListFilter, Lock, LockScope, LockTimeout, MatchType, MemberFailure,
+    ParseLimit, ParseLimitError, ParseLimits, PrincipalInfo, Privilege, PropNames, PropPatchResult,
+    PropStatus, PropertyName, PropertyValue, PushInfo, PushRegistration, PushTransport,
+    RecursiveReport, ResourceProps, TextMatch, VersionEntry, WebPushSubscription,
+};
+pub use versioning::{parse_versions, parse_versions_with_limits};
 pub use xml::{build_sync_collection_body, escape_xml};
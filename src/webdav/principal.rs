@@ -0,0 +1,192 @@
+//! Principal enumeration (RFC 3744 §5.8, §9.2): `principal-collection-set` discovery and
+//! principal listings for sharing and ACL pickers (see
+//! [`crate::webdav::WebDavClient::list_principals`]).
+
+use anyhow::{Result, anyhow};
+use quick_xml::NsReader;
+use quick_xml::events::Event;
+
+use crate::webdav::streaming::path_ends_with;
+use crate::webdav::types::{ParseLimits, PrincipalInfo};
+use crate::webdav::xml::LimitGuard;
+
+pub(crate) const PRINCIPAL_COLLECTION_SET_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:propfind xmlns:D="DAV:">
+  <D:prop>
+    <D:principal-collection-set/>
+  </D:prop>
+</D:propfind>"#;
+
+const PRINCIPAL_PROPS: &str = "<D:resourcetype/><D:displayname/><C:calendar-user-type/><C:calendar-user-address-set/><D:group-member-set/>";
+
+/// `PROPFIND` body requesting the properties of [`PrincipalInfo`].
+pub(crate) fn principal_propfind_body() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav"><D:prop>{PRINCIPAL_PROPS}</D:prop></D:propfind>"#
+    )
+}
+
+/// `DAV:acl-principal-prop-set` `REPORT` body requesting the properties of
+/// [`PrincipalInfo`].
+pub(crate) fn acl_principal_prop_set_body() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:acl-principal-prop-set xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav"><D:prop>{PRINCIPAL_PROPS}</D:prop></D:acl-principal-prop-set>"#
+    )
+}
+
+/// Parse the hrefs of `DAV:principal-collection-set` from a `PROPFIND` response.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_principal_collection_set(body: &[u8]) -> Result<Vec<String>> {
+    parse_principal_collection_set_with_limits(body, ParseLimits::default())
+}
+
+/// Variant of [`parse_principal_collection_set`] with other [`ParseLimits`] than the default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when it is not well-formed XML.
+pub fn parse_principal_collection_set_with_limits(
+    body: &[u8],
+    limits: ParseLimits,
+) -> Result<Vec<String>> {
+    let mut hrefs = Vec::new();
+    walk(body, limits, |event| {
+        if let WalkEvent::Text(stack, text) = event
+            && path_ends_with(stack, &["principal-collection-set", "href"])
+        {
+            hrefs.push(text.to_string());
+        }
+    })?;
+    hrefs.sort();
+    hrefs.dedup();
+    Ok(hrefs)
+}
+
+/// Parse the principals of a multistatus response; responses whose `resourcetype` is not
+/// `DAV:principal` (such as the principal collection itself) are skipped.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::parse_principals;
+///
+/// let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response>
+///   <d:href>/principals/users/jane/</d:href>
+///   <d:propstat><d:prop>
+///     <d:resourcetype><d:principal/></d:resourcetype>
+///     <d:displayname>Jane Doe</d:displayname>
+///   </d:prop></d:propstat>
+/// </d:response></d:multistatus>"#;
+/// let principals = parse_principals(xml.as_bytes())?;
+/// assert_eq!(principals[0].displayname.as_deref(), Some("Jane Doe"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_principals(body: &[u8]) -> Result<Vec<PrincipalInfo>> {
+    parse_principals_with_limits(body, ParseLimits::default())
+}
+
+/// Variant of [`parse_principals`] with other [`ParseLimits`] than the default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when it is not well-formed XML.
+pub fn parse_principals_with_limits(
+    body: &[u8],
+    limits: ParseLimits,
+) -> Result<Vec<PrincipalInfo>> {
+    let mut principals = Vec::new();
+    let mut current = PrincipalInfo::default();
+    let mut is_principal = false;
+    walk(body, limits, |event| match event {
+        WalkEvent::Open(stack) => {
+            if path_ends_with(stack, &["resourcetype", "principal"]) {
+                is_principal = true;
+            }
+        }
+        WalkEvent::Text(stack, text) => {
+            let value = text.to_string();
+            if path_ends_with(stack, &["response", "href"]) {
+                current.href = value;
+            } else if path_ends_with(stack, &["prop", "displayname"]) {
+                current.displayname = Some(value);
+            } else if path_ends_with(stack, &["prop", "calendar-user-type"]) {
+                current.calendar_user_type = Some(value);
+            } else if path_ends_with(stack, &["calendar-user-address-set", "href"]) {
+                current.addresses.push(value);
+            } else if path_ends_with(stack, &["group-member-set", "href"]) {
+                current.members.push(value);
+            }
+        }
+        WalkEvent::Close(name) => {
+            if name == "response" {
+                let principal = std::mem::take(&mut current);
+                if std::mem::take(&mut is_principal) {
+                    principals.push(principal);
+                }
+            }
+        }
+    })?;
+    Ok(principals)
+}
+
+enum WalkEvent<'a> {
+    /// An element was opened; the stack (lower-cased local names) ends with it.
+    Open(&'a [String]),
+    /// Trimmed, non-empty text of the element at the end of the stack, once complete.
+    Text(&'a [String], &'a str),
+    /// The element with this local name was closed.
+    Close(&'a str),
+}
+
+fn walk(body: &[u8], limits: ParseLimits, mut on_event: impl FnMut(WalkEvent<'_>)) -> Result<()> {
+    let mut xml = NsReader::from_reader(body);
+    let mut buf = Vec::new();
+    let mut guard = LimitGuard::new(limits);
+    let mut stack: Vec<String> = Vec::new();
+    // Text arrives in pieces around entity references; it is reported at the next tag.
+    let mut text = String::new();
+
+    loop {
+        let event = xml
+            .read_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        guard.on_event(&event)?;
+        if matches!(event, Event::Start(_) | Event::Empty(_) | Event::End(_)) {
+            if !text.trim().is_empty() {
+                on_event(WalkEvent::Text(&stack, text.trim()));
+            }
+            text.clear();
+        }
+        match event {
+            Event::Start(e) => {
+                stack.push(String::from_utf8_lossy(e.local_name().as_ref()).to_ascii_lowercase());
+                on_event(WalkEvent::Open(&stack));
+            }
+            Event::Empty(e) => {
+                stack.push(String::from_utf8_lossy(e.local_name().as_ref()).to_ascii_lowercase());
+                on_event(WalkEvent::Open(&stack));
+                if let Some(name) = stack.pop() {
+                    on_event(WalkEvent::Close(&name));
+                }
+            }
+            Event::End(_) => {
+                if let Some(name) = stack.pop() {
+                    on_event(WalkEvent::Close(&name));
+                }
+            }
+            Event::Eof => break,
+            event => guard.append_text(&mut text, &event)?,
+        }
+        buf.clear();
+    }
+    Ok(())
+}
@@ -0,0 +1,268 @@
+//! Arbitrary, namespace-qualified properties: a [`PropertyRegistry`] of the properties to
+//! request, `PROPFIND` bodies for it and parsing of their values (see
+//! [`crate::webdav::WebDavClient::propfind_properties`]).
+//!
+//! Vendor properties (CalendarServer `CS:`, Apple `ICAL:`, ownCloud/Nextcloud `OC:`/`NC:`)
+//! are handled like `DAV:` ones, so they round-trip without being dropped by the typed
+//! CalDAV/CardDAV parsers.
+
+use anyhow::{Result, anyhow};
+
+use crate::webdav::streaming::parse_props_bytes;
+use crate::webdav::types::{DavResource, ParseLimits, PropertyName, ResourceProps};
+use crate::webdav::xml::escape_xml;
+
+/// Conventional prefixes of well-known namespaces, used in generated bodies.
+const WELL_KNOWN_PREFIXES: &[(&str, &str)] = &[
+    ("D", "DAV:"),
+    ("C", "urn:ietf:params:xml:ns:caldav"),
+    ("CARD", "urn:ietf:params:xml:ns:carddav"),
+    ("CS", "http://calendarserver.org/ns/"),
+    ("ICAL", "http://apple.com/ns/ical/"),
+    ("OC", "http://owncloud.org/ns"),
+    ("NC", "http://nextcloud.org/ns"),
+    ("S", "http://sabredav.org/ns"),
+];
+
+/// A set of properties to request, including custom ones.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{PropertyName, PropertyRegistry};
+///
+/// let registry = PropertyRegistry::new()
+///     .register(PropertyName::dav("displayname"))
+///     .register(PropertyName::new("http://apple.com/ns/ical/", "calendar-color"))
+///     .register(PropertyName::new("https://example.com/ns", "flag"))
+///     .namespace("EX", "https://example.com/ns");
+/// let body = registry.build_propfind_body()?;
+/// assert!(body.contains(r#"xmlns:ICAL="http://apple.com/ns/ical/""#));
+/// assert!(body.contains("<D:displayname/><ICAL:calendar-color/><EX:flag/>"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyRegistry {
+    names: Vec<PropertyName>,
+    prefixes: Vec<(String, String)>,
+}
+
+impl PropertyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a property; registering the same name twice has no effect.
+    pub fn register(mut self, name: PropertyName) -> Self {
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        }
+        self
+    }
+
+    /// Use `prefix` for `namespace` in generated bodies instead of a generated one.
+    pub fn namespace(mut self, prefix: &str, namespace: &str) -> Self {
+        self.prefixes
+            .retain(|(p, ns)| p != prefix && ns != namespace);
+        self.prefixes
+            .push((prefix.to_string(), namespace.to_string()));
+        self
+    }
+
+    /// Registered properties, in registration order.
+    pub fn names(&self) -> &[PropertyName] {
+        &self.names
+    }
+
+    pub fn contains(&self, name: &PropertyName) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Build a `PROPFIND` body requesting every registered property.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the registry is empty or a name or prefix is not a valid XML
+    /// name.
+    pub fn build_propfind_body(&self) -> Result<String> {
+        if self.names.is_empty() {
+            return Err(anyhow!("no property registered"));
+        }
+        let namespaces = Namespaces::assign(&self.names, &self.prefixes)?;
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><D:propfind{}><D:prop>"#,
+            namespaces.declarations()
+        );
+        for name in &self.names {
+            xml.push_str(&namespaces.empty_element(name));
+        }
+        xml.push_str("</D:prop></D:propfind>");
+        Ok(xml)
+    }
+}
+
+/// Namespace prefixes of a generated request body, `D` for `DAV:` first.
+#[derive(Debug, Clone)]
+pub(crate) struct Namespaces {
+    prefixes: Vec<(String, String)>,
+}
+
+impl Namespaces {
+    /// Assign a prefix to the namespace of every name: the `custom` one if any, else the
+    /// conventional one of well-known namespaces, else a generated `X0`, `X1`…
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a name or prefix is not a valid XML name.
+    pub(crate) fn assign<'a>(
+        names: impl IntoIterator<Item = &'a PropertyName>,
+        custom: &[(String, String)],
+    ) -> Result<Self> {
+        let mut prefixes = vec![("D".to_string(), "DAV:".to_string())];
+        let mut generated = 0;
+        for name in names {
+            if !name.is_valid() {
+                return Err(anyhow!("invalid property name {name}"));
+            }
+            if name.ns.is_empty() || prefixes.iter().any(|(_, ns)| *ns == name.ns) {
+                continue;
+            }
+            let custom_prefix = custom
+                .iter()
+                .find(|(_, ns)| *ns == name.ns)
+                .map(|(prefix, _)| prefix.clone());
+            let well_known = WELL_KNOWN_PREFIXES
+                .iter()
+                .find(|(prefix, ns)| *ns == name.ns && !prefixes.iter().any(|(p, _)| p == prefix))
+                .map(|(prefix, _)| prefix.to_string());
+            let prefix = match custom_prefix.or(well_known) {
+                Some(prefix) => prefix,
+                None => loop {
+                    let candidate = format!("X{generated}");
+                    generated += 1;
+                    if !custom.iter().any(|(p, _)| *p == candidate) {
+                        break candidate;
+                    }
+                },
+            };
+            if !PropertyName::dav(&prefix).is_valid() || prefix.contains('.') {
+                return Err(anyhow!("invalid namespace prefix {prefix:?}"));
+            }
+            prefixes.push((prefix, name.ns.clone()));
+        }
+        // Custom prefixes stay declared for values referring to them.
+        for (prefix, namespace) in custom {
+            if !prefixes
+                .iter()
+                .any(|(p, ns)| p == prefix || ns == namespace)
+            {
+                if !PropertyName::dav(prefix).is_valid() || prefix.contains('.') {
+                    return Err(anyhow!("invalid namespace prefix {prefix:?}"));
+                }
+                prefixes.push((prefix.clone(), namespace.clone()));
+            }
+        }
+        Ok(Self { prefixes })
+    }
+
+    /// `xmlns:` attributes declaring every prefix, with a leading space.
+    pub(crate) fn declarations(&self) -> String {
+        self.prefixes
+            .iter()
+            .map(|(prefix, namespace)| format!(r#" xmlns:{prefix}="{}""#, escape_xml(namespace)))
+            .collect()
+    }
+
+    /// `<P:local/>` for `name`.
+    pub(crate) fn empty_element(&self, name: &PropertyName) -> String {
+        let (tag, attrs) = self.tag(name);
+        format!("<{tag}{attrs}/>")
+    }
+
+    /// `<P:local>content</P:local>` for `name`; `content` is inserted as is.
+    pub(crate) fn element(&self, name: &PropertyName, content: &str) -> String {
+        let (tag, attrs) = self.tag(name);
+        format!("<{tag}{attrs}>{content}</{tag}>")
+    }
+
+    fn tag(&self, name: &PropertyName) -> (String, &'static str) {
+        match self.prefixes.iter().find(|(_, ns)| *ns == name.ns) {
+            Some((prefix, _)) => (format!("{prefix}:{}", name.local), ""),
+            // Names without a namespace must not inherit a default namespace.
+            None => (name.local.clone(), r#" xmlns="""#),
+        }
+    }
+}
+
+/// Parse every property of a multistatus body, with its raw value.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{PropertyName, parse_properties};
+///
+/// let xml = r#"<d:multistatus xmlns:d="DAV:" xmlns:x="http://apple.com/ns/ical/"><d:response>
+///   <d:href>/cal/</d:href>
+///   <d:propstat><d:prop><x:calendar-color>#FF0000FF</x:calendar-color></d:prop>
+///   <d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+/// </d:response></d:multistatus>"#;
+/// let resources = parse_properties(xml.as_bytes())?;
+/// let color = resources[0].get(&PropertyName::new("http://apple.com/ns/ical/", "calendar-color"));
+/// assert_eq!(color.and_then(|v| v.text()).as_deref(), Some("#FF0000FF"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_properties(body: &[u8]) -> Result<Vec<ResourceProps>> {
+    parse_properties_with_limits(body, ParseLimits::default())
+}
+
+/// Variant of [`parse_properties`] with other [`ParseLimits`] than the default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when it is not well-formed XML.
+pub fn parse_properties_with_limits(
+    body: &[u8],
+    limits: ParseLimits,
+) -> Result<Vec<ResourceProps>> {
+    parse_props_bytes(body, Vec::new(), limits)
+}
+
+/// Properties requested by [`resource_registry`], mapped onto [`DavResource`] fields.
+const RESOURCE_PROPS: &[&str] = &[
+    "displayname",
+    "getetag",
+    "resourcetype",
+    "getcontenttype",
+    "getcontentlength",
+    "getlastmodified",
+];
+
+/// Registry of the [`DavResource`] properties plus `extra` ones.
+pub fn resource_registry(extra: &[PropertyName]) -> PropertyRegistry {
+    let registry = RESOURCE_PROPS
+        .iter()
+        .fold(PropertyRegistry::new(), |registry, local| {
+            registry.register(PropertyName::dav(local))
+        });
+    extra
+        .iter()
+        .cloned()
+        .fold(registry, PropertyRegistry::register)
+}
+
+/// Parse a multistatus body into [`DavResource`]s.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_resources(body: &[u8]) -> Result<Vec<DavResource>> {
+    Ok(parse_properties(body)?
+        .into_iter()
+        .map(DavResource::from)
+        .collect())
+}
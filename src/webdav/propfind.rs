@@ -0,0 +1,68 @@
+//! `PROPFIND` request bodies beyond named properties (RFC 4918 §9.1): `allprop`, optionally
+//! with `include`, and `propname`.
+
+use anyhow::Result;
+
+use crate::webdav::property::Namespaces;
+use crate::webdav::proppatch::parse_propstats;
+use crate::webdav::types::{PropNames, PropertyName};
+
+/// Build a `<D:allprop/>` `PROPFIND` body. Properties that servers leave out of `allprop`
+/// (RFC 4918 §9.1, e.g. `DAV:sync-token` or `CALDAV:calendar-data` on some servers) can be
+/// requested too through `include`.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{PropertyName, build_allprop_body};
+///
+/// let body = build_allprop_body(&[
+///     PropertyName::dav("sync-token"),
+///     PropertyName::new("http://calendarserver.org/ns/", "getctag"),
+/// ])?;
+/// assert!(body.contains(r#"xmlns:CS="http://calendarserver.org/ns/""#));
+/// assert!(body.contains("<D:allprop/><D:include><D:sync-token/><CS:getctag/></D:include>"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when an `include` name is not a valid XML name.
+pub fn build_allprop_body(include: &[PropertyName]) -> Result<String> {
+    let namespaces = Namespaces::assign(include, &[])?;
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:propfind{}><D:allprop/>"#,
+        namespaces.declarations()
+    );
+    if !include.is_empty() {
+        xml.push_str("<D:include>");
+        for name in include {
+            xml.push_str(&namespaces.empty_element(name));
+        }
+        xml.push_str("</D:include>");
+    }
+    xml.push_str("</D:propfind>");
+    Ok(xml)
+}
+
+/// `<D:propname/>` `PROPFIND` body, listing property names without their values.
+pub const PROPNAME_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?><D:propfind xmlns:D="DAV:"><D:propname/></D:propfind>"#;
+
+/// Parse a `propname` `PROPFIND` response into the property names of each resource.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_propnames(body: &[u8]) -> Result<Vec<PropNames>> {
+    let mut resources: Vec<PropNames> = Vec::new();
+    for (href, prop) in parse_propstats(body)? {
+        match resources.last_mut() {
+            Some(last) if last.href == href => last.props.push(prop),
+            _ => resources.push(PropNames {
+                href,
+                props: vec![prop],
+            }),
+        }
+    }
+    Ok(resources)
+}
@@ -0,0 +1,205 @@
+//! Generic `PROPPATCH` bodies and parsing of `PROPPATCH` responses (RFC 4918 §9.2).
+
+use anyhow::{Result, anyhow};
+use quick_xml::NsReader;
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+
+use crate::webdav::property::Namespaces;
+use crate::webdav::streaming::path_ends_with;
+use crate::webdav::types::{PropPatchResult, PropStatus, PropertyName};
+use crate::webdav::xml::{escape_xml, event_text};
+
+/// Builder for `PROPPATCH` bodies setting and removing properties in any namespace.
+///
+/// Instructions are applied by the server in the order they were added, and atomically: if
+/// one is rejected, none is applied.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{PropPatch, PropertyName};
+///
+/// let body = PropPatch::new()
+///     .set_text(PropertyName::dav("displayname"), "Q3 & Q4")
+///     .set_text(PropertyName::new("https://example.com/ns", "flag"), "red")
+///     .remove(PropertyName::new("http://apple.com/ns/ical/", "calendar-order"))
+///     .namespace("EX", "https://example.com/ns")
+///     .build()?;
+/// assert!(body.contains(
+///     "<D:set><D:prop><D:displayname>Q3 &amp; Q4</D:displayname><EX:flag>red</EX:flag></D:prop></D:set>"
+/// ));
+/// assert!(body.contains("<D:remove><D:prop><ICAL:calendar-order/></D:prop></D:remove>"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropPatch {
+    /// `(name, Some(xml))` sets, `(name, None)` removes.
+    ops: Vec<(PropertyName, Option<String>)>,
+    prefixes: Vec<(String, String)>,
+}
+
+impl PropPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a property to a text value, which is escaped.
+    pub fn set_text(self, name: PropertyName, text: &str) -> Self {
+        self.set_xml(name, &escape_xml(text))
+    }
+
+    /// Set a property to a raw XML value. It may use the `D:` prefix, the prefix of a
+    /// well-known namespace used by a property name, or ones declared with
+    /// [`namespace`](Self::namespace).
+    pub fn set_xml(mut self, name: PropertyName, xml: &str) -> Self {
+        self.ops.push((name, Some(xml.to_string())));
+        self
+    }
+
+    /// Remove a property; removing a property that does not exist is not an error.
+    pub fn remove(mut self, name: PropertyName) -> Self {
+        self.ops.push((name, None));
+        self
+    }
+
+    /// Declare `prefix` for `namespace` in the body.
+    pub fn namespace(mut self, prefix: &str, namespace: &str) -> Self {
+        self.prefixes
+            .retain(|(p, ns)| p != prefix && ns != namespace);
+        self.prefixes
+            .push((prefix.to_string(), namespace.to_string()));
+        self
+    }
+
+    /// `true` when no instruction has been added.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Build the `propertyupdate` body; consecutive instructions of the same kind share one
+    /// `set` or `remove` element.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the patch is empty or a property name or prefix is not a valid
+    /// XML name.
+    pub fn build(&self) -> Result<String> {
+        if self.ops.is_empty() {
+            return Err(anyhow!("PROPPATCH requires at least one property"));
+        }
+        let namespaces = Namespaces::assign(self.ops.iter().map(|(name, _)| name), &self.prefixes)?;
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><D:propertyupdate{}>"#,
+            namespaces.declarations()
+        );
+        let mut open: Option<&str> = None;
+        for (name, value) in &self.ops {
+            let kind = if value.is_some() { "set" } else { "remove" };
+            if open != Some(kind) {
+                if let Some(previous) = open {
+                    xml.push_str(&format!("</D:prop></D:{previous}>"));
+                }
+                xml.push_str(&format!("<D:{kind}><D:prop>"));
+                open = Some(kind);
+            }
+            match value {
+                Some(value) => xml.push_str(&namespaces.element(name, value)),
+                None => xml.push_str(&namespaces.empty_element(name)),
+            }
+        }
+        if let Some(kind) = open {
+            xml.push_str(&format!("</D:prop></D:{kind}>"));
+        }
+        xml.push_str("</D:propertyupdate>");
+        Ok(xml)
+    }
+}
+
+/// Parse a `207 Multi-Status` `PROPPATCH` response into per-property statuses.
+///
+/// Property names are reported with their resolved namespace URI, so results can be matched
+/// regardless of the prefixes the server chose.
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_proppatch_response(body: &[u8]) -> Result<PropPatchResult> {
+    Ok(PropPatchResult {
+        props: parse_propstats(body)?
+            .into_iter()
+            .map(|(_, prop)| prop)
+            .collect(),
+    })
+}
+
+/// Every property of a multistatus body with the href of its `response` and the status of
+/// its `propstat`; property values are ignored.
+pub(crate) fn parse_propstats(body: &[u8]) -> Result<Vec<(String, PropStatus)>> {
+    let mut xml = NsReader::from_reader(body);
+
+    let mut buf = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    let mut result = Vec::new();
+    let mut href = String::new();
+    let mut pending: Vec<PropStatus> = Vec::new();
+    let mut status: Option<String> = None;
+    let mut text = String::new();
+
+    loop {
+        let (ns, event) = xml
+            .read_resolved_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        if matches!(event, Event::Start(_) | Event::Empty(_) | Event::End(_)) {
+            if path_ends_with(&stack, &["propstat", "status"]) {
+                status = Some(text.trim().to_string());
+            } else if path_ends_with(&stack, &["response", "href"]) {
+                href = text.trim().to_string();
+            }
+            text.clear();
+        }
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).to_ascii_lowercase();
+                if path_ends_with(&stack, &["propstat", "prop"]) {
+                    let namespace = match ns {
+                        ResolveResult::Bound(ns) => {
+                            String::from_utf8_lossy(ns.as_ref()).into_owned()
+                        }
+                        _ => String::new(),
+                    };
+                    pending.push(PropStatus {
+                        namespace,
+                        name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+                        status: None,
+                    });
+                }
+                if matches!(event, Event::Start(_)) {
+                    if local == "propstat" {
+                        pending.clear();
+                        status = None;
+                    }
+                    stack.push(local);
+                }
+            }
+            Event::End(_) => {
+                let closed = stack.pop();
+                if closed.as_deref() == Some("propstat") {
+                    for mut prop in pending.drain(..) {
+                        prop.status = status.clone();
+                        result.push((href.clone(), prop));
+                    }
+                }
+            }
+            Event::Eof => break,
+            event => {
+                if let Some(piece) = event_text(&event)? {
+                    text.push_str(&piece);
+                }
+            }
+        }
+        buf.clear();
+    }
+
+    Ok(result)
+}
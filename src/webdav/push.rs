@@ -0,0 +1,198 @@
+//! Push notification discovery and subscriptions.
+//!
+//! Two mechanisms are recognised: WebDAV-Push (`https://bitfire.at/webdav-push`, Web Push
+//! based, supported by Nextcloud and DAVx5), which clients can subscribe to, and Apple's
+//! `CS:push-transports` (APNs/XMPP), which is reported for discovery only.
+
+use anyhow::{Result, anyhow};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::webdav::types::{PushInfo, PushTransport, WebPushSubscription};
+use crate::webdav::xml::{escape_xml, event_text, local_name};
+
+pub(crate) const PUSH_PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:propfind xmlns:D="DAV:" xmlns:P="https://bitfire.at/webdav-push" xmlns:CS="http://calendarserver.org/ns/">
+  <D:prop>
+    <P:transports/>
+    <P:topic/>
+    <CS:push-transports/>
+    <CS:pushkey/>
+  </D:prop>
+</D:propfind>"#;
+
+/// Build a WebDAV-Push `push-register` request body for `subscription`.
+pub fn build_push_register_body(subscription: &WebPushSubscription) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="utf-8"?><P:push-register xmlns:D="DAV:" xmlns:P="https://bitfire.at/webdav-push"><P:subscription><P:web-push-subscription>"#,
+    );
+    xml.push_str(&format!(
+        "<P:push-resource>{}</P:push-resource>",
+        escape_xml(&subscription.push_resource)
+    ));
+    if let (Some(key), Some(secret)) = (&subscription.public_key, &subscription.auth_secret) {
+        xml.push_str(&format!(
+            r#"<P:content-encoding>aes128gcm</P:content-encoding><P:subscription-public-key type="p256dh">{}</P:subscription-public-key><P:auth-secret>{}</P:auth-secret>"#,
+            escape_xml(key),
+            escape_xml(secret)
+        ));
+    }
+    xml.push_str("</P:web-push-subscription></P:subscription>");
+    xml.push_str(&format!(
+        "<P:trigger><P:content-update><D:depth>{}</D:depth></P:content-update></P:trigger>",
+        subscription.depth.as_str()
+    ));
+    if let Some(expires) = &subscription.expires {
+        xml.push_str(&format!("<P:expires>{}</P:expires>", escape_xml(expires)));
+    }
+    xml.push_str("</P:push-register>");
+    xml
+}
+
+/// Parse the push properties of a `PROPFIND` response (see
+/// [`crate::webdav::WebDavClient::discover_push`]).
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_push_info(body: &[u8]) -> Result<PushInfo> {
+    let mut xml = Reader::from_reader(body);
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut info = PushInfo::default();
+    let mut transport: Option<PushTransport> = None;
+    let mut text = String::new();
+
+    loop {
+        let event = xml
+            .read_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        if matches!(event, Event::Start(_) | Event::Empty(_) | Event::End(_)) {
+            on_text(&path, text.trim(), &mut info, &mut transport);
+            text.clear();
+        }
+        match event {
+            Event::Start(e) => {
+                path.push(local_name(e.name().as_ref()));
+                on_start(&path, &e, &mut transport)?;
+            }
+            Event::Empty(e) => {
+                path.push(local_name(e.name().as_ref()));
+                on_start(&path, &e, &mut transport)?;
+                on_end(&path, &mut info, &mut transport);
+                path.pop();
+            }
+            Event::End(_) => {
+                on_end(&path, &mut info, &mut transport);
+                path.pop();
+            }
+            Event::Eof => break,
+            event => {
+                if let Some(piece) = event_text(&event)? {
+                    text.push_str(&piece);
+                }
+            }
+        }
+        buf.clear();
+    }
+    Ok(info)
+}
+
+fn on_start(
+    path: &[String],
+    element: &BytesStart<'_>,
+    transport: &mut Option<PushTransport>,
+) -> Result<()> {
+    match names(path).as_slice() {
+        [.., "transports", "web-push"] => {
+            *transport = Some(PushTransport::WebPush {
+                vapid_public_key: None,
+            });
+        }
+        [.., "push-transports", "transport"] => {
+            let mut kind = String::new();
+            for attr in element.attributes().with_checks(true) {
+                let attr = attr.map_err(|e| anyhow!("Invalid XML attribute: {e}"))?;
+                if attr.key.as_ref().eq_ignore_ascii_case(b"type") {
+                    kind = String::from_utf8_lossy(&attr.value).to_ascii_uppercase();
+                }
+            }
+            *transport = match kind.as_str() {
+                "APSD" => Some(PushTransport::Apns {
+                    subscription_url: None,
+                    bundle_id: None,
+                    environment: None,
+                    refresh_interval: None,
+                }),
+                "XMPP" => Some(PushTransport::Xmpp {
+                    server: None,
+                    uri: None,
+                }),
+                _ => None,
+            };
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn on_end(path: &[String], info: &mut PushInfo, transport: &mut Option<PushTransport>) {
+    if matches!(
+        names(path).as_slice(),
+        [.., "transports", "web-push"] | [.., "push-transports", "transport"]
+    ) {
+        info.transports.extend(transport.take());
+    }
+}
+
+fn on_text(
+    path: &[String],
+    text: &str,
+    info: &mut PushInfo,
+    transport: &mut Option<PushTransport>,
+) {
+    if text.is_empty() {
+        return;
+    }
+    let value = Some(text.to_string());
+    let names = names(path);
+    match (names.as_slice(), transport.as_mut()) {
+        ([.., "prop", "topic"], _) => info.topic = value,
+        ([.., "prop", "pushkey"], _) => info.push_key = value,
+        ([.., "vapid-public-key"], Some(PushTransport::WebPush { vapid_public_key })) => {
+            *vapid_public_key = value
+        }
+        (
+            _,
+            Some(PushTransport::Apns {
+                subscription_url,
+                bundle_id,
+                environment,
+                refresh_interval,
+            }),
+        ) => {
+            // `subscription-url` wraps a `D:href`; take the child of `transport`.
+            let field = names
+                .iter()
+                .position(|n| *n == "transport")
+                .and_then(|i| names.get(i + 1));
+            match field.copied() {
+                Some("subscription-url") => *subscription_url = value,
+                Some("apsbundleid") => *bundle_id = value,
+                Some("env") => *environment = value,
+                Some("refresh-interval") => *refresh_interval = text.parse().ok(),
+                _ => {}
+            }
+        }
+        ([.., field], Some(PushTransport::Xmpp { server, uri })) => match *field {
+            "xmpp-server" => *server = value,
+            "xmpp-uri" => *uri = value,
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+fn names(path: &[String]) -> Vec<&str> {
+    path.iter().map(String::as_str).collect()
+}
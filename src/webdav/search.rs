@@ -0,0 +1,251 @@
+//! DASL `SEARCH` (RFC 5323): `basicsearch` query bodies for servers advertising the
+//! `DAV:basicsearch` grammar (see [`crate::webdav::WebDavClient::search`]).
+
+use anyhow::{Result, anyhow};
+
+use crate::webdav::property::Namespaces;
+use crate::webdav::types::{Depth, PropertyName};
+use crate::webdav::xml::escape_xml;
+
+/// Properties selected by every search, the ones mapped onto
+/// [`crate::webdav::DavItemCommon`].
+const SELECT: &[&str] = &[
+    "displayname",
+    "getetag",
+    "resourcetype",
+    "getcontenttype",
+    "getlastmodified",
+];
+
+/// A `basicsearch` `where` condition (RFC 5323 §5.4).
+///
+/// Comparisons take the literal as text; the server compares it according to the property
+/// type (string, number or date).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchCondition {
+    Eq(PropertyName, String),
+    Lt(PropertyName, String),
+    Lte(PropertyName, String),
+    Gt(PropertyName, String),
+    Gte(PropertyName, String),
+    /// `DAV:like`: `%` matches any sequence of characters and `_` one character; `\`
+    /// escapes them.
+    Like(PropertyName, String),
+    /// The property exists on the resource.
+    IsDefined(PropertyName),
+    /// The resource is a collection.
+    IsCollection,
+    Not(Box<SearchCondition>),
+    And(Vec<SearchCondition>),
+    Or(Vec<SearchCondition>),
+}
+
+impl SearchCondition {
+    /// `DAV:like` matching values that contain `text`, with the wildcards of `text` escaped.
+    pub fn contains(name: PropertyName, text: &str) -> Self {
+        let mut pattern = String::with_capacity(text.len() + 2);
+        pattern.push('%');
+        for ch in text.chars() {
+            if matches!(ch, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(ch);
+        }
+        pattern.push('%');
+        Self::Like(name, pattern)
+    }
+
+    /// Negation of this condition.
+    pub fn negate(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    fn names<'a>(&'a self, out: &mut Vec<&'a PropertyName>) {
+        match self {
+            Self::Eq(name, _)
+            | Self::Lt(name, _)
+            | Self::Lte(name, _)
+            | Self::Gt(name, _)
+            | Self::Gte(name, _)
+            | Self::Like(name, _)
+            | Self::IsDefined(name) => out.push(name),
+            Self::IsCollection => {}
+            Self::Not(inner) => inner.names(out),
+            Self::And(conditions) | Self::Or(conditions) => {
+                conditions.iter().for_each(|c| c.names(out))
+            }
+        }
+    }
+
+    fn write(&self, namespaces: &Namespaces, xml: &mut String) -> Result<()> {
+        let mut compare = |op: &str, name: &PropertyName, literal: &str| {
+            xml.push_str(&format!(
+                "<D:{op}><D:prop>{}</D:prop><D:literal>{}</D:literal></D:{op}>",
+                namespaces.empty_element(name),
+                escape_xml(literal)
+            ));
+        };
+        match self {
+            Self::Eq(name, literal) => compare("eq", name, literal),
+            Self::Lt(name, literal) => compare("lt", name, literal),
+            Self::Lte(name, literal) => compare("lte", name, literal),
+            Self::Gt(name, literal) => compare("gt", name, literal),
+            Self::Gte(name, literal) => compare("gte", name, literal),
+            Self::Like(name, pattern) => compare("like", name, pattern),
+            Self::IsDefined(name) => xml.push_str(&format!(
+                "<D:is-defined><D:prop>{}</D:prop></D:is-defined>",
+                namespaces.empty_element(name)
+            )),
+            Self::IsCollection => xml.push_str("<D:is-collection/>"),
+            Self::Not(inner) => {
+                xml.push_str("<D:not>");
+                inner.write(namespaces, xml)?;
+                xml.push_str("</D:not>");
+            }
+            Self::And(conditions) | Self::Or(conditions) => {
+                let op = if matches!(self, Self::And(_)) {
+                    "and"
+                } else {
+                    "or"
+                };
+                if conditions.is_empty() {
+                    return Err(anyhow!("empty {op} condition"));
+                }
+                xml.push_str(&format!("<D:{op}>"));
+                for condition in conditions {
+                    condition.write(namespaces, xml)?;
+                }
+                xml.push_str(&format!("</D:{op}>"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builder for `basicsearch` `SEARCH` request bodies.
+///
+/// The matching resources are reported with the properties of
+/// [`crate::webdav::DavItemCommon`].
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{BasicSearch, Depth, PropertyName, SearchCondition};
+///
+/// let body = BasicSearch::new("/files/jane/")
+///     .depth(Depth::Infinity)
+///     .filter(SearchCondition::contains(PropertyName::dav("displayname"), "report"))
+///     .order_by_desc(PropertyName::dav("getlastmodified"))
+///     .limit(20)
+///     .build()?;
+/// assert!(body.contains("<D:like><D:prop><D:displayname/></D:prop><D:literal>%report%</D:literal></D:like>"));
+/// assert!(body.contains("<D:nresults>20</D:nresults>"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicSearch {
+    scope: String,
+    depth: Depth,
+    condition: Option<SearchCondition>,
+    order: Vec<(PropertyName, bool)>,
+    limit: Option<u32>,
+}
+
+impl BasicSearch {
+    /// Search the collection at `scope` (an href) and its whole tree.
+    pub fn new(scope: &str) -> Self {
+        Self {
+            scope: scope.to_string(),
+            depth: Depth::Infinity,
+            condition: None,
+            order: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// How deep below the scope to search.
+    pub fn depth(mut self, depth: Depth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// `where` condition; without one, every resource in scope matches.
+    pub fn filter(mut self, condition: SearchCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// Sort by `name`, ascending, after the orderings already added.
+    pub fn order_by(mut self, name: PropertyName) -> Self {
+        self.order.push((name, true));
+        self
+    }
+
+    /// Sort by `name`, descending, after the orderings already added.
+    pub fn order_by_desc(mut self, name: PropertyName) -> Self {
+        self.order.push((name, false));
+        self
+    }
+
+    /// Maximum number of results.
+    pub fn limit(mut self, nresults: u32) -> Self {
+        self.limit = Some(nresults);
+        self
+    }
+
+    /// Build the `searchrequest` body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a property name is not a valid XML name or an `and`/`or`
+    /// condition is empty.
+    pub fn build(&self) -> Result<String> {
+        let select: Vec<PropertyName> = SELECT.iter().map(|n| PropertyName::dav(n)).collect();
+        let mut names: Vec<&PropertyName> = select.iter().collect();
+        if let Some(condition) = &self.condition {
+            condition.names(&mut names);
+        }
+        names.extend(self.order.iter().map(|(name, _)| name));
+        let namespaces = Namespaces::assign(names, &[])?;
+
+        let mut xml = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><D:searchrequest{}><D:basicsearch><D:select><D:prop>"#,
+            namespaces.declarations()
+        );
+        for name in &select {
+            xml.push_str(&namespaces.empty_element(name));
+        }
+        xml.push_str(&format!(
+            "</D:prop></D:select><D:from><D:scope><D:href>{}</D:href><D:depth>{}</D:depth></D:scope></D:from>",
+            escape_xml(&self.scope),
+            self.depth.as_str()
+        ));
+        if let Some(condition) = &self.condition {
+            xml.push_str("<D:where>");
+            condition.write(&namespaces, &mut xml)?;
+            xml.push_str("</D:where>");
+        }
+        if !self.order.is_empty() {
+            xml.push_str("<D:orderby>");
+            for (name, ascending) in &self.order {
+                xml.push_str(&format!(
+                    "<D:order><D:prop>{}</D:prop>{}</D:order>",
+                    namespaces.empty_element(name),
+                    if *ascending {
+                        "<D:ascending/>"
+                    } else {
+                        "<D:descending/>"
+                    }
+                ));
+            }
+            xml.push_str("</D:orderby>");
+        }
+        if let Some(limit) = self.limit {
+            xml.push_str(&format!(
+                "<D:limit><D:nresults>{limit}</D:nresults></D:limit>"
+            ));
+        }
+        xml.push_str("</D:basicsearch></D:searchrequest>");
+        Ok(xml)
+    }
+}
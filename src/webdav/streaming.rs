@@ -1,5 +1,19 @@
-use crate::webdav::types::DavItemCommon;
+//! Multistatus parsing: the common fields shared by the CalDAV/CardDAV parsers, and
+//! streaming parsing of generic responses into [`ResourceProps`].
+
+use crate::caldav::streaming::STREAM_READ_IDLE_TIMEOUT;
+use crate::common::compression::{ContentEncoding, decompress_stream};
+use crate::webdav::types::{
+    DavItemCommon, ParseLimit, ParseLimits, PropertyName, PropertyValue, ResourceProps,
+};
+use crate::webdav::xml::{LimitGuard, escape_xml};
 use anyhow::{Result, anyhow};
+use hyper::body::Incoming;
+use quick_xml::escape::unescape;
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+use quick_xml::{NsReader, Reader};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum CommonElement {
@@ -67,8 +81,40 @@ pub(crate) struct CommonParser {
     current: DavItemCommon,
 }
 
-pub(crate) fn path_ends_with<T: PartialEq>(stack: &[T], needle: &[T]) -> bool {
-    stack.len() >= needle.len() && stack[stack.len() - needle.len()..] == needle[..]
+/// Distinct qualified names kept by [`ElementNames`]; past it, names are classified each
+/// time, so documents with many distinct names cannot grow the table without bound.
+const MAX_INTERNED_NAMES: usize = 64;
+
+/// Element names interned by qualified name (`d:href`), each classified once.
+///
+/// A multistatus body repeats a few dozen names thousands of times: looking the raw name up
+/// here replaces the chain of case-insensitive comparisons of the classifying function.
+pub(crate) struct ElementNames<T> {
+    names: Vec<(Box<[u8]>, T)>,
+}
+
+impl<T: Copy> ElementNames<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            names: Vec::with_capacity(32),
+        }
+    }
+
+    /// The element named `raw`, classified by `classify` the first time it is seen.
+    pub(crate) fn get(&mut self, raw: &[u8], classify: impl FnOnce(&[u8]) -> T) -> T {
+        if let Some((_, element)) = self.names.iter().find(|(name, _)| **name == *raw) {
+            return *element;
+        }
+        let element = classify(raw);
+        if self.names.len() < MAX_INTERNED_NAMES {
+            self.names.push((raw.into(), element));
+        }
+        element
+    }
+}
+
+pub(crate) fn path_ends_with<T: PartialEq<U>, U>(stack: &[T], needle: &[U]) -> bool {
+    stack.len() >= needle.len() && stack[stack.len() - needle.len()..] == *needle
 }
 
 impl CommonParser {
@@ -80,7 +126,11 @@ impl CommonParser {
     }
 
     pub(crate) fn on_start(&mut self, raw: &[u8]) {
-        let element = common_element_from_bytes(raw);
+        self.on_start_element(common_element_from_bytes(raw));
+    }
+
+    /// [`on_start`](Self::on_start) for an element the caller has already classified.
+    pub(crate) fn on_start_element(&mut self, element: CommonElement) {
         self.stack.push(element);
 
         match element {
@@ -201,3 +251,438 @@ impl CommonParser {
         path_ends_with(&self.stack, needle)
     }
 }
+
+/// Parse the common fields of every `response` of an aggregated multistatus body.
+pub(crate) fn parse_common_multistatus(
+    body: &[u8],
+    limits: ParseLimits,
+) -> Result<Vec<DavItemCommon>> {
+    let mut xml = Reader::from_reader(body);
+    let mut buf = Vec::new();
+    let mut guard = LimitGuard::new(limits);
+    let mut parser = CommonParser::new();
+    let mut items = Vec::new();
+
+    let mut close = |parser: &mut CommonParser, raw: &[u8]| -> Result<()> {
+        if common_element_from_bytes(raw) == CommonElement::Response {
+            items.push(parser.finish_response());
+        }
+        parser.on_end(raw)
+    };
+
+    let mut text = String::new();
+    loop {
+        let event = xml
+            .read_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        guard.on_event(&event)?;
+        if matches!(event, Event::Start(_) | Event::Empty(_) | Event::End(_)) {
+            parser.on_text(&text);
+            text.clear();
+        }
+        match event {
+            Event::Start(e) => parser.on_start(e.name().as_ref()),
+            Event::Empty(e) => {
+                parser.on_start(e.name().as_ref());
+                close(&mut parser, e.name().as_ref())?;
+            }
+            Event::End(e) => close(&mut parser, e.name().as_ref())?,
+            Event::Eof => break,
+            event => guard.append_text(&mut text, &event)?,
+        }
+        buf.clear();
+    }
+    Ok(items)
+}
+
+/// What a `*_visit` callback asks of the parser after each item it is handed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visit {
+    /// Go on with the next item.
+    #[default]
+    Continue,
+    /// Stop parsing: the rest of the body is left unread.
+    Stop,
+}
+
+/// Destination of the resources parsed by [`PropsParser`].
+pub(crate) trait PropsConsumer {
+    fn consume(&mut self, resource: ResourceProps) -> Result<Visit>;
+}
+
+impl PropsConsumer for Vec<ResourceProps> {
+    fn consume(&mut self, resource: ResourceProps) -> Result<Visit> {
+        self.push(resource);
+        Ok(Visit::Continue)
+    }
+}
+
+impl<F> PropsConsumer for F
+where
+    F: FnMut(ResourceProps) -> Result<Visit>,
+{
+    fn consume(&mut self, resource: ResourceProps) -> Result<Visit> {
+        (self)(resource)
+    }
+}
+
+/// Property element whose value is being read.
+struct OpenProperty {
+    name: PropertyName,
+    /// Depth of the element in the element stack.
+    depth: usize,
+    /// Inner XML read so far, as sent by the server.
+    xml: String,
+    children: Vec<PropertyName>,
+}
+
+/// Event-driven parser of multistatus bodies into [`ResourceProps`], handing every
+/// resource to its sink as soon as its `response` element is closed.
+pub(crate) struct PropsParser<C> {
+    stack: Vec<String>,
+    current: ResourceProps,
+    pending: Vec<PropertyValue>,
+    status: Option<String>,
+    open: Option<OpenProperty>,
+    /// Escaped text of the element being read outside property values (href, status).
+    text: String,
+    /// Set once the sink asked to [`Visit::Stop`].
+    pub(crate) stopped: bool,
+    limits: ParseLimits,
+    /// `response` elements parsed so far.
+    items: usize,
+    pub(crate) sink: C,
+}
+
+impl<C: PropsConsumer> PropsParser<C> {
+    pub(crate) fn new(sink: C, limits: ParseLimits) -> Self {
+        Self {
+            stack: Vec::with_capacity(16),
+            current: ResourceProps::default(),
+            pending: Vec::new(),
+            status: None,
+            open: None,
+            text: String::new(),
+            stopped: false,
+            limits,
+            items: 0,
+            sink,
+        }
+    }
+
+    /// Feed one event read by an [`quick_xml::NsReader`].
+    pub(crate) fn on_event(&mut self, ns: ResolveResult<'_>, event: Event<'_>) -> Result<()> {
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                self.limits.check(ParseLimit::Depth, self.stack.len() + 1)?;
+                let is_empty = matches!(event, Event::Empty(_));
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                let namespace = match ns {
+                    ResolveResult::Bound(ns) => String::from_utf8_lossy(ns.as_ref()).into_owned(),
+                    _ => String::new(),
+                };
+                self.text.clear();
+                match &mut self.open {
+                    Some(value) => {
+                        if self.stack.len() == value.depth + 1 {
+                            value.children.push(PropertyName::new(&namespace, &local));
+                        }
+                        value.xml.push('<');
+                        value.xml.push_str(&String::from_utf8_lossy(e.as_ref()));
+                        value.xml.push_str(if is_empty { "/>" } else { ">" });
+                    }
+                    None if names_end_with(&self.stack, &["propstat", "prop"]) => {
+                        let name = PropertyName::new(&namespace, &local);
+                        if is_empty {
+                            self.pending.push(PropertyValue {
+                                name,
+                                xml: String::new(),
+                                children: Vec::new(),
+                                status: None,
+                            });
+                        } else {
+                            self.open = Some(OpenProperty {
+                                name,
+                                depth: self.stack.len(),
+                                xml: String::new(),
+                                children: Vec::new(),
+                            });
+                        }
+                    }
+                    None => {}
+                }
+                if !is_empty {
+                    let local = local.to_ascii_lowercase();
+                    if self.open.is_none() && local == "propstat" {
+                        self.pending.clear();
+                        self.status = None;
+                    }
+                    self.stack.push(local);
+                }
+            }
+            Event::Text(ref e) => self.push_text(&String::from_utf8_lossy(e.as_ref()))?,
+            Event::GeneralRef(ref e) => {
+                self.push_text(&format!("&{};", String::from_utf8_lossy(e.as_ref())))?
+            }
+            Event::CData(ref e) => {
+                match &mut self.open {
+                    Some(value) => {
+                        value.xml.push_str("<![CDATA[");
+                        value.xml.push_str(&String::from_utf8_lossy(e.as_ref()));
+                        value.xml.push_str("]]>");
+                    }
+                    None => self
+                        .text
+                        .push_str(&escape_xml(&String::from_utf8_lossy(e.as_ref()))),
+                }
+                self.check_text_size()?;
+            }
+            Event::End(ref e) => {
+                if self.stack.pop().is_none() {
+                    return Err(anyhow!(
+                        "XML structure error: closing tag </{}> without a matching opening tag",
+                        String::from_utf8_lossy(e.name().as_ref())
+                    ));
+                }
+                match self.open.take() {
+                    Some(value) if self.stack.len() == value.depth => {
+                        self.pending.push(PropertyValue {
+                            name: value.name,
+                            xml: value.xml.trim().to_string(),
+                            children: value.children,
+                            status: None,
+                        });
+                    }
+                    Some(mut still_open) => {
+                        still_open.xml.push_str("</");
+                        still_open
+                            .xml
+                            .push_str(&String::from_utf8_lossy(e.name().as_ref()));
+                        still_open.xml.push('>');
+                        self.open = Some(still_open);
+                    }
+                    None => self.close(&String::from_utf8_lossy(e.local_name().as_ref()))?,
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn push_text(&mut self, raw: &str) -> Result<()> {
+        match &mut self.open {
+            Some(value) => value.xml.push_str(raw),
+            None => self.text.push_str(raw),
+        }
+        self.check_text_size()
+    }
+
+    /// Check the text read so far for the element or property value being read.
+    fn check_text_size(&self) -> Result<()> {
+        let size = self
+            .open
+            .as_ref()
+            .map_or(self.text.len(), |value| value.xml.len());
+        self.limits.check(ParseLimit::TextSize, size)
+    }
+
+    /// Close an element outside property values; `self.stack` no longer contains it.
+    fn close(&mut self, local: &str) -> Result<()> {
+        let text = std::mem::take(&mut self.text);
+        let local = local.to_ascii_lowercase();
+        let parent = self.stack.last().map(String::as_str);
+        if local == "href" && parent == Some("response") {
+            self.current.href = decode(&text)?;
+        } else if local == "status" && parent == Some("propstat") {
+            self.status = Some(decode(&text)?);
+        } else if local == "propstat" {
+            for mut prop in self.pending.drain(..) {
+                prop.status = self.status.clone();
+                self.current.props.push(prop);
+            }
+        } else if local == "response" {
+            self.items += 1;
+            self.limits.check(ParseLimit::Items, self.items)?;
+            self.stopped = self.sink.consume(std::mem::take(&mut self.current))? == Visit::Stop;
+        }
+        Ok(())
+    }
+}
+
+fn names_end_with(stack: &[String], needle: &[&str]) -> bool {
+    stack.len() >= needle.len()
+        && stack[stack.len() - needle.len()..]
+            .iter()
+            .zip(needle)
+            .all(|(a, b)| a == b)
+}
+
+fn decode(escaped: &str) -> Result<String> {
+    Ok(unescape(escaped)
+        .map_err(|err| anyhow!("XML decode error: {err}"))?
+        .trim()
+        .to_string())
+}
+
+/// Parse an aggregated multistatus body, handing every resource to `sink`.
+pub(crate) fn parse_props_bytes<C: PropsConsumer>(
+    body: &[u8],
+    sink: C,
+    limits: ParseLimits,
+) -> Result<C> {
+    let mut xml = NsReader::from_reader(body);
+    xml.config_mut().trim_text(false);
+    let mut buf = Vec::new();
+    let mut parser = PropsParser::new(sink, limits);
+    loop {
+        let (ns, event) = xml
+            .read_resolved_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        if matches!(event, Event::Eof) {
+            break;
+        }
+        parser.on_event(ns, event)?;
+        if parser.stopped {
+            break;
+        }
+        buf.clear();
+    }
+    Ok(parser.sink)
+}
+
+async fn parse_props_stream<C: PropsConsumer + Send>(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    sink: C,
+    idle_timeout: Duration,
+    limits: ParseLimits,
+) -> Result<C> {
+    let mut xml = NsReader::from_reader(decompress_stream(resp_body, encodings)?);
+    xml.config_mut().trim_text(false);
+    let mut buf = Vec::with_capacity(8 * 1024);
+    let mut parser = PropsParser::new(sink, limits);
+    loop {
+        let (ns, event) =
+            tokio::time::timeout(idle_timeout, xml.read_resolved_event_into_async(&mut buf))
+                .await
+                .map_err(|_| {
+                    anyhow!("streaming read timed out after {idle_timeout:?} of inactivity")
+                })?
+                .map_err(|e| anyhow!("XML error: {e}"))?;
+        if matches!(event, Event::Eof) {
+            break;
+        }
+        parser.on_event(ns, event)?;
+        if parser.stopped {
+            break;
+        }
+        buf.clear();
+    }
+    Ok(parser.sink)
+}
+
+/// Parse a `207 Multi-Status` body in **streaming mode** into [`ResourceProps`], with
+/// optional decompression (br, gzip, zstd), e.g. the body of
+/// [`WebDavClient::propfind_stream`](crate::webdav::WebDavClient::propfind_stream).
+///
+/// Reads are bounded by the default idle timeout ([`STREAM_READ_IDLE_TIMEOUT`]); use
+/// [`parse_multistatus_stream_with_timeout`] to customize it.
+///
+/// # Errors
+///
+/// Returns an error when the body cannot be read or is not well-formed XML.
+pub async fn parse_multistatus_stream(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+) -> Result<Vec<ResourceProps>> {
+    parse_multistatus_stream_with_timeout(resp_body, encodings, STREAM_READ_IDLE_TIMEOUT).await
+}
+
+/// Variant of [`parse_multistatus_stream`] with a caller-provided **idle** timeout: the
+/// maximum time allowed between two reads making progress, not a cap on the total parse
+/// duration.
+pub async fn parse_multistatus_stream_with_timeout(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    idle_timeout: Duration,
+) -> Result<Vec<ResourceProps>> {
+    parse_multistatus_stream_with_limits(resp_body, encodings, idle_timeout, ParseLimits::default())
+        .await
+}
+
+/// Variant of [`parse_multistatus_stream_with_timeout`] with other [`ParseLimits`] than the
+/// default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when the body cannot be read or is not well-formed XML.
+pub async fn parse_multistatus_stream_with_limits(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    idle_timeout: Duration,
+    limits: ParseLimits,
+) -> Result<Vec<ResourceProps>> {
+    parse_props_stream(resp_body, encodings, Vec::new(), idle_timeout, limits).await
+}
+
+/// Stream parse a `207 Multi-Status` body and invoke `on_resource` for each resource as
+/// soon as it is complete, so memory use does not grow with the size of the listing.
+///
+/// An error returned by `on_resource` stops parsing and is returned.
+pub async fn parse_multistatus_stream_visit<F>(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    mut on_resource: F,
+) -> Result<()>
+where
+    F: FnMut(ResourceProps) -> Result<()> + Send,
+{
+    parse_multistatus_stream_visit_until(resp_body, encodings, move |resource| {
+        on_resource(resource).map(|()| Visit::Continue)
+    })
+    .await
+}
+
+/// Variant of [`parse_multistatus_stream_visit`] whose callback decides whether to go on:
+/// on [`Visit::Stop`] the rest of the body is left unread and the response dropped, which
+/// closes the connection.
+pub async fn parse_multistatus_stream_visit_until<F>(
+    resp_body: Incoming,
+    encodings: &[ContentEncoding],
+    on_resource: F,
+) -> Result<()>
+where
+    F: FnMut(ResourceProps) -> Result<Visit> + Send,
+{
+    parse_props_stream(
+        resp_body,
+        encodings,
+        on_resource,
+        STREAM_READ_IDLE_TIMEOUT,
+        ParseLimits::default(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Parse an aggregated `207 Multi-Status` body via callback, with the rules of
+/// [`parse_multistatus_stream_visit`].
+pub fn parse_multistatus_bytes_visit<F>(body: &[u8], mut on_resource: F) -> Result<()>
+where
+    F: FnMut(ResourceProps) -> Result<()>,
+{
+    parse_multistatus_bytes_visit_until(body, move |resource| {
+        on_resource(resource).map(|()| Visit::Continue)
+    })
+}
+
+/// Parse an aggregated `207 Multi-Status` body via callback, with the rules of
+/// [`parse_multistatus_stream_visit_until`].
+pub fn parse_multistatus_bytes_visit_until<F>(body: &[u8], on_resource: F) -> Result<()>
+where
+    F: FnMut(ResourceProps) -> Result<Visit>,
+{
+    parse_props_bytes(body, on_resource, ParseLimits::default())?;
+    Ok(())
+}
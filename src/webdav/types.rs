@@ -1,7 +1,8 @@
 use anyhow::Result;
+use hyper::HeaderMap;
 
 /// WebDAV Depth
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Depth {
     Zero,
     One,
@@ -38,6 +39,224 @@ pub(crate) fn http_status_code(status_line: &str) -> Option<u16> {
     })
 }
 
+/// `match-type` of a `text-match` filter.
+///
+/// CardDAV defines all four (RFC 6352 §10.5.4). CalDAV text-matches are substring matches
+/// (RFC 4791 §9.7.5); other match types are an extension that not every server honours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchType {
+    Equals,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl MatchType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchType::Equals => "equals",
+            MatchType::Contains => "contains",
+            MatchType::StartsWith => "starts-with",
+            MatchType::EndsWith => "ends-with",
+        }
+    }
+}
+
+/// A `text-match` filter: the text to look for plus collation, match type and negation.
+///
+/// Omitted options use the server defaults: `i;ascii-casemap` (case-insensitive ASCII)
+/// collation, `contains` matching, not negated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    pub text: String,
+    pub collation: Option<String>,
+    pub match_type: Option<MatchType>,
+    pub negate: bool,
+}
+
+impl TextMatch {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            collation: None,
+            match_type: None,
+            negate: false,
+        }
+    }
+
+    /// Collation to compare with, e.g. `i;octet` for case-sensitive matching.
+    pub fn collation(mut self, collation: &str) -> Self {
+        self.collation = Some(collation.to_string());
+        self
+    }
+
+    pub fn match_type(mut self, match_type: MatchType) -> Self {
+        self.match_type = Some(match_type);
+        self
+    }
+
+    /// Match values that do **not** match the text (`negate-condition="yes"`).
+    pub fn negate(mut self) -> Self {
+        self.negate = true;
+        self
+    }
+}
+
+/// Per-property outcome reported in a `207 Multi-Status` `propstat` (e.g. for `PROPPATCH`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropStatus {
+    /// Namespace URI of the property, e.g. `DAV:` or `urn:ietf:params:xml:ns:caldav`.
+    pub namespace: String,
+    /// Local name of the property, e.g. `displayname`.
+    pub name: String,
+    /// Raw `<D:status>` line of the enclosing `propstat`, e.g. `HTTP/1.1 403 Forbidden`.
+    pub status: Option<String>,
+}
+
+impl PropStatus {
+    /// Numeric HTTP status code, if the status line is well-formed.
+    pub fn status_code(&self) -> Option<u16> {
+        self.status.as_deref().and_then(http_status_code)
+    }
+
+    /// `true` for a `2xx` status.
+    pub fn is_success(&self) -> bool {
+        self.status_code()
+            .is_some_and(|code| (200..300).contains(&code))
+    }
+
+    /// Namespace-qualified name of the property.
+    pub fn property(&self) -> PropertyName {
+        PropertyName::new(&self.namespace, &self.name)
+    }
+}
+
+/// Parsed result of a `PROPPATCH`: the status of every property the server reported on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropPatchResult {
+    pub props: Vec<PropStatus>,
+}
+
+impl PropPatchResult {
+    /// `true` when every reported property was applied.
+    ///
+    /// `PROPPATCH` is atomic (RFC 4918 §9.2), so a single failure means none were applied;
+    /// the other properties are then usually reported as `424 Failed Dependency`.
+    pub fn is_success(&self) -> bool {
+        self.props.iter().all(PropStatus::is_success)
+    }
+
+    /// Properties that were rejected (including `424 Failed Dependency`).
+    pub fn failed(&self) -> impl Iterator<Item = &PropStatus> {
+        self.props.iter().filter(|p| !p.is_success())
+    }
+
+    /// Outcome reported for `name`, if the server reported on it.
+    pub fn get(&self, name: &PropertyName) -> Option<&PropStatus> {
+        self.props
+            .iter()
+            .find(|p| p.namespace == name.ns && p.name == name.local)
+    }
+}
+
+/// A push transport advertised by a collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushTransport {
+    /// WebDAV-Push over Web Push (RFC 8030), as implemented by Nextcloud and DAVx5.
+    WebPush {
+        /// VAPID public key (RFC 8292) the server signs its push messages with.
+        vapid_public_key: Option<String>,
+    },
+    /// Apple Push Notification service (`CS:transport type="APSD"`). Subscribing requires
+    /// APNs credentials of the calendar app, so it is discovery only.
+    Apns {
+        subscription_url: Option<String>,
+        bundle_id: Option<String>,
+        environment: Option<String>,
+        /// Seconds after which a subscription must be renewed.
+        refresh_interval: Option<u64>,
+    },
+    /// XMPP pubsub (`CS:transport type="XMPP"`).
+    Xmpp {
+        server: Option<String>,
+        uri: Option<String>,
+    },
+}
+
+/// Push support of a collection: its topic and the transports it offers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushInfo {
+    /// WebDAV-Push topic, included in push messages to identify the collection.
+    pub topic: Option<String>,
+    /// Apple `CS:pushkey` of the collection.
+    pub push_key: Option<String>,
+    pub transports: Vec<PushTransport>,
+}
+
+impl PushInfo {
+    /// `true` when the collection accepts WebDAV-Push (Web Push) subscriptions.
+    pub fn supports_web_push(&self) -> bool {
+        self.transports
+            .iter()
+            .any(|t| matches!(t, PushTransport::WebPush { .. }))
+    }
+}
+
+/// A Web Push subscription (RFC 8030/8291) to register on a collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebPushSubscription {
+    /// Push resource URL issued by the push service.
+    pub push_resource: String,
+    /// Client public key (`p256dh`), base64url; with `auth_secret`, enables encrypted messages.
+    pub public_key: Option<String>,
+    pub auth_secret: Option<String>,
+    /// Requested expiry as an HTTP date (e.g. `Wed, 20 Dec 2023 10:03:31 GMT`).
+    pub expires: Option<String>,
+    /// Depth of content changes that trigger a push: `One` for members, `Infinity` for the
+    /// whole tree.
+    pub depth: Depth,
+}
+
+impl WebPushSubscription {
+    pub fn new(push_resource: &str) -> Self {
+        Self {
+            push_resource: push_resource.to_string(),
+            public_key: None,
+            auth_secret: None,
+            expires: None,
+            depth: Depth::One,
+        }
+    }
+
+    /// Message encryption keys (RFC 8291): the `p256dh` public key and auth secret.
+    pub fn keys(mut self, public_key: &str, auth_secret: &str) -> Self {
+        self.public_key = Some(public_key.to_string());
+        self.auth_secret = Some(auth_secret.to_string());
+        self
+    }
+
+    pub fn expires(mut self, http_date: &str) -> Self {
+        self.expires = Some(http_date.to_string());
+        self
+    }
+
+    pub fn depth(mut self, depth: Depth) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+/// Outcome of registering a push subscription.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushRegistration {
+    /// Registration URL (`Location`), used to unregister.
+    pub url: Option<String>,
+    /// Expiry granted by the server (`Expires`), an HTTP date.
+    pub expires: Option<String>,
+    /// `false` when an existing registration was renewed instead of created.
+    pub created: bool,
+}
+
 /// Common fields extracted from a WebDAV response.
 #[derive(Debug, Clone, Default)]
 pub struct DavItemCommon {
@@ -52,3 +271,823 @@ pub struct DavItemCommon {
     pub content_type: Option<String>,
     pub last_modified: Option<String>,
 }
+
+/// Scope of a WebDAV lock (RFC 4918 §6.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockScope {
+    /// Only the lock owner may write.
+    Exclusive,
+    /// Other shared locks may coexist; every holder may write.
+    Shared,
+}
+
+impl LockScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Exclusive => "exclusive",
+            Self::Shared => "shared",
+        }
+    }
+}
+
+/// Lock lifetime, as requested in the `Timeout` header and reported by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockTimeout {
+    Seconds(u64),
+    Infinite,
+}
+
+impl LockTimeout {
+    /// `Timeout` header value: `Second-N` or `Infinite`.
+    pub fn as_header(&self) -> String {
+        match self {
+            Self::Seconds(seconds) => format!("Second-{seconds}"),
+            Self::Infinite => "Infinite".to_string(),
+        }
+    }
+
+    /// Parse a `Second-N`/`Infinite` value (case-insensitive); for a header listing several
+    /// values, the first valid one wins.
+    pub fn parse(value: &str) -> Option<Self> {
+        value.split(',').find_map(|v| {
+            let v = v.trim();
+            if v.eq_ignore_ascii_case("infinite") {
+                Some(Self::Infinite)
+            } else {
+                v.get(..7)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("second-"))
+                    .and_then(|_| v[7..].parse().ok())
+                    .map(Self::Seconds)
+            }
+        })
+    }
+}
+
+/// An active lock held by this client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lock {
+    /// Lock token (`opaquelocktoken:…` or `urn:uuid:…`), without angle brackets.
+    pub token: String,
+    /// Lifetime granted by the server, which may differ from the one requested.
+    pub timeout: Option<LockTimeout>,
+}
+
+/// Principal an access control entry applies to (RFC 3744 §5.5.1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcePrincipal {
+    /// A user or group principal URL.
+    Href(String),
+    /// Every user, authenticated or not.
+    All,
+    Authenticated,
+    Unauthenticated,
+    /// The resource itself, when it is a principal.
+    SelfPrincipal,
+    /// The principal stored in a property of the resource, typically `DAV:owner`.
+    Property {
+        namespace: String,
+        name: String,
+    },
+}
+
+/// Privilege granted or denied by an ACE (RFC 3744 §3, RFC 4791 §6.1.1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Privilege {
+    All,
+    Read,
+    Write,
+    WriteProperties,
+    WriteContent,
+    Unlock,
+    ReadAcl,
+    ReadCurrentUserPrivilegeSet,
+    WriteAcl,
+    Bind,
+    Unbind,
+    /// CalDAV `read-free-busy`.
+    ReadFreeBusy,
+    /// Any other privilege, e.g. a server-specific one.
+    Other {
+        namespace: String,
+        name: String,
+    },
+}
+
+impl Privilege {
+    /// Privilege of the element `name` in `namespace`.
+    pub fn from_name(namespace: &str, name: &str) -> Self {
+        let known = match (namespace, name) {
+            ("DAV:", "all") => Some(Self::All),
+            ("DAV:", "read") => Some(Self::Read),
+            ("DAV:", "write") => Some(Self::Write),
+            ("DAV:", "write-properties") => Some(Self::WriteProperties),
+            ("DAV:", "write-content") => Some(Self::WriteContent),
+            ("DAV:", "unlock") => Some(Self::Unlock),
+            ("DAV:", "read-acl") => Some(Self::ReadAcl),
+            ("DAV:", "read-current-user-privilege-set") => Some(Self::ReadCurrentUserPrivilegeSet),
+            ("DAV:", "write-acl") => Some(Self::WriteAcl),
+            ("DAV:", "bind") => Some(Self::Bind),
+            ("DAV:", "unbind") => Some(Self::Unbind),
+            ("urn:ietf:params:xml:ns:caldav", "read-free-busy") => Some(Self::ReadFreeBusy),
+            _ => None,
+        };
+        known.unwrap_or_else(|| Self::Other {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        })
+    }
+
+    /// Namespace URI and local name of the privilege element.
+    pub fn qualified_name(&self) -> (&str, &str) {
+        match self {
+            Self::All => ("DAV:", "all"),
+            Self::Read => ("DAV:", "read"),
+            Self::Write => ("DAV:", "write"),
+            Self::WriteProperties => ("DAV:", "write-properties"),
+            Self::WriteContent => ("DAV:", "write-content"),
+            Self::Unlock => ("DAV:", "unlock"),
+            Self::ReadAcl => ("DAV:", "read-acl"),
+            Self::ReadCurrentUserPrivilegeSet => ("DAV:", "read-current-user-privilege-set"),
+            Self::WriteAcl => ("DAV:", "write-acl"),
+            Self::Bind => ("DAV:", "bind"),
+            Self::Unbind => ("DAV:", "unbind"),
+            Self::ReadFreeBusy => ("urn:ietf:params:xml:ns:caldav", "read-free-busy"),
+            Self::Other { namespace, name } => (namespace, name),
+        }
+    }
+}
+
+/// Whether an ACE grants or denies its privileges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AceAction {
+    Grant,
+    Deny,
+}
+
+/// An access control entry of a resource's `DAV:acl` (RFC 3744 §5.5).
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{Ace, AcePrincipal, Privilege};
+///
+/// let ace = Ace::grant(
+///     AcePrincipal::Href("/principals/users/bob/".into()),
+///     [Privilege::Read, Privilege::Write],
+/// );
+/// assert!(!ace.protected && ace.inherited.is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ace {
+    pub principal: AcePrincipal,
+    /// Apply to every principal **except** `principal`.
+    pub invert: bool,
+    pub action: AceAction,
+    pub privileges: Vec<Privilege>,
+    /// Set by the server for entries clients cannot change.
+    pub protected: bool,
+    /// URL of the resource this entry is inherited from; inherited entries are changed there.
+    pub inherited: Option<String>,
+}
+
+impl Ace {
+    /// Entry granting `privileges` to `principal`.
+    pub fn grant(principal: AcePrincipal, privileges: impl IntoIterator<Item = Privilege>) -> Self {
+        Self::new(principal, AceAction::Grant, privileges)
+    }
+
+    /// Entry denying `privileges` to `principal`.
+    pub fn deny(principal: AcePrincipal, privileges: impl IntoIterator<Item = Privilege>) -> Self {
+        Self::new(principal, AceAction::Deny, privileges)
+    }
+
+    /// Apply the entry to every principal except its own.
+    pub fn inverted(mut self) -> Self {
+        self.invert = true;
+        self
+    }
+
+    fn new(
+        principal: AcePrincipal,
+        action: AceAction,
+        privileges: impl IntoIterator<Item = Privilege>,
+    ) -> Self {
+        Self {
+            principal,
+            invert: false,
+            action,
+            privileges: privileges.into_iter().collect(),
+            protected: false,
+            inherited: None,
+        }
+    }
+}
+
+/// A user, group or resource principal, as listed by
+/// [`crate::webdav::WebDavClient::list_principals`] and
+/// [`crate::webdav::WebDavClient::acl_principal_prop_set`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrincipalInfo {
+    pub href: String,
+    pub displayname: Option<String>,
+    /// `CALDAV:calendar-user-type` (RFC 6638): `INDIVIDUAL`, `GROUP`, `RESOURCE`, `ROOM`…
+    pub calendar_user_type: Option<String>,
+    /// `CALDAV:calendar-user-address-set`, e.g. `mailto:` URIs.
+    pub addresses: Vec<String>,
+    /// `DAV:group-member-set`, for groups.
+    pub members: Vec<String>,
+}
+
+impl PrincipalInfo {
+    /// `true` for group principals: a `GROUP` calendar user type or a member set.
+    pub fn is_group(&self) -> bool {
+        self.calendar_user_type
+            .as_deref()
+            .is_some_and(|t| t.eq_ignore_ascii_case("GROUP"))
+            || !self.members.is_empty()
+    }
+
+    /// First `mailto:` address, without the scheme.
+    pub fn email(&self) -> Option<&str> {
+        self.addresses.iter().find_map(|address| {
+            address
+                .get(..7)
+                .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+                .map(|_| &address[7..])
+        })
+    }
+}
+
+/// Properties a resource exposes, as listed by a `<D:propname/>` `PROPFIND`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropNames {
+    pub href: String,
+    /// Property names with their namespace; `status` is that of the enclosing `propstat`.
+    pub props: Vec<PropStatus>,
+}
+
+/// Namespace-qualified name of a WebDAV property, e.g. `{http://calendarserver.org/ns/}getctag`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PropertyName {
+    /// Namespace URI, e.g. `DAV:`.
+    pub ns: String,
+    /// Local name, e.g. `getctag`.
+    pub local: String,
+}
+
+impl PropertyName {
+    pub fn new(ns: &str, local: &str) -> Self {
+        Self {
+            ns: ns.to_string(),
+            local: local.to_string(),
+        }
+    }
+
+    /// Property in the `DAV:` namespace.
+    pub fn dav(local: &str) -> Self {
+        Self::new("DAV:", local)
+    }
+
+    /// Parse Clark notation, `{namespace}local`; a bare name has an empty namespace.
+    pub fn from_clark(name: &str) -> Option<Self> {
+        match name.strip_prefix('{') {
+            Some(rest) => {
+                let (ns, local) = rest.split_once('}')?;
+                (!local.is_empty()).then(|| Self::new(ns, local))
+            }
+            None => (!name.is_empty()).then(|| Self::new("", name)),
+        }
+    }
+
+    /// `true` when the local name is a valid XML element name without a prefix, so the
+    /// property can be written into a request body.
+    pub fn is_valid(&self) -> bool {
+        let mut chars = self.local.chars();
+        chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    }
+}
+
+impl std::fmt::Display for PropertyName {
+    /// Clark notation, `{namespace}local`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{{}}}{}", self.ns, self.local)
+    }
+}
+
+/// A property value from a `PROPFIND` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyValue {
+    pub name: PropertyName,
+    /// Inner XML of the property element, as sent by the server (trimmed). Child elements
+    /// keep the server's namespace prefixes.
+    pub xml: String,
+    /// Names of the direct child elements, with their namespace resolved (e.g. the resource
+    /// types of `DAV:resourcetype`).
+    pub children: Vec<PropertyName>,
+    /// Raw `<D:status>` line of the enclosing `propstat`.
+    pub status: Option<String>,
+}
+
+impl PropertyValue {
+    /// Unescaped text of a value without child elements, `None` otherwise.
+    pub fn text(&self) -> Option<String> {
+        if self.xml.contains('<') {
+            return None;
+        }
+        quick_xml::escape::unescape(&self.xml)
+            .ok()
+            .map(|text| text.into_owned())
+    }
+
+    /// `true` for a `2xx` status.
+    pub fn is_success(&self) -> bool {
+        self.status
+            .as_deref()
+            .and_then(http_status_code)
+            .is_some_and(|code| (200..300).contains(&code))
+    }
+}
+
+/// Every property reported for one resource of a `PROPFIND` response.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceProps {
+    pub href: String,
+    pub props: Vec<PropertyValue>,
+}
+
+impl ResourceProps {
+    /// Successfully returned value of `name`, if any.
+    pub fn get(&self, name: &PropertyName) -> Option<&PropertyValue> {
+        self.props
+            .iter()
+            .find(|prop| &prop.name == name && prop.is_success())
+    }
+}
+
+/// A resource of a generic WebDAV `PROPFIND` response (see
+/// [`crate::webdav::WebDavClient::propfind_resources`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DavResource {
+    pub href: String,
+    pub displayname: Option<String>,
+    pub etag: Option<String>,
+    /// Members of `DAV:resourcetype`, e.g. `{DAV:}collection` and
+    /// `{urn:ietf:params:xml:ns:caldav}calendar`.
+    pub resource_types: Vec<PropertyName>,
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    /// `DAV:getlastmodified`, an HTTP date.
+    pub last_modified: Option<String>,
+    /// Every other property successfully returned.
+    pub props: Vec<PropertyValue>,
+}
+
+impl DavResource {
+    /// `true` when `DAV:resourcetype` contains `DAV:collection`.
+    pub fn is_collection(&self) -> bool {
+        self.has_resource_type(&PropertyName::dav("collection"))
+    }
+
+    pub fn has_resource_type(&self, resource_type: &PropertyName) -> bool {
+        self.resource_types.contains(resource_type)
+    }
+
+    /// Value of a custom property, if returned.
+    pub fn prop(&self, name: &PropertyName) -> Option<&PropertyValue> {
+        self.props.iter().find(|prop| &prop.name == name)
+    }
+}
+
+impl From<ResourceProps> for DavResource {
+    fn from(resource: ResourceProps) -> Self {
+        let mut mapped = DavResource {
+            href: resource.href,
+            ..DavResource::default()
+        };
+        for prop in resource.props.into_iter().filter(PropertyValue::is_success) {
+            if prop.name.ns != "DAV:" {
+                mapped.props.push(prop);
+                continue;
+            }
+            match prop.name.local.as_str() {
+                "displayname" => mapped.displayname = prop.text(),
+                "getetag" => mapped.etag = prop.text(),
+                "resourcetype" => mapped.resource_types = prop.children,
+                "getcontenttype" => mapped.content_type = prop.text(),
+                "getcontentlength" => {
+                    mapped.content_length = prop.text().and_then(|len| len.trim().parse().ok())
+                }
+                "getlastmodified" => mapped.last_modified = prop.text(),
+                _ => mapped.props.push(prop),
+            }
+        }
+        mapped
+    }
+}
+
+/// The server rejected the token of a `sync-collection` REPORT as invalid or expired
+/// (RFC 6578 §3.2, `DAV:valid-sync-token`); sync again without a token.
+///
+/// Returned wrapped in [`anyhow::Error`]; recover it with `downcast_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSyncTokenError {
+    /// The rejected token.
+    pub token: String,
+    /// Status of the response, usually `403` or `409`.
+    pub status: u16,
+}
+
+impl std::fmt::Display for InvalidSyncTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sync token {} rejected by the server ({})",
+            self.token, self.status
+        )
+    }
+}
+
+impl std::error::Error for InvalidSyncTokenError {}
+
+/// Elements nested deeper than this are rejected unless configured otherwise.
+pub const DEFAULT_MAX_XML_DEPTH: usize = 128;
+
+/// Text nodes larger than this (64 MiB) are rejected unless configured otherwise.
+pub const DEFAULT_MAX_XML_TEXT_SIZE: usize = 64 * 1024 * 1024;
+
+/// Bounds on the multistatus documents the parsers accept, so a broken or malicious server
+/// cannot make a long-running process nest, buffer or collect without end. `None` means no
+/// limit.
+///
+/// Every multistatus parser, and the lock discovery, ACL, principal and version parsers,
+/// apply [`ParseLimits::default`] unless given other limits, e.g. with
+/// [`WebDavClient::set_parse_limits`](crate::webdav::WebDavClient::set_parse_limits) or
+/// [`CalDavClient::set_parse_limits`](crate::CalDavClient::set_parse_limits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Deepest element nesting accepted (default [`DEFAULT_MAX_XML_DEPTH`]).
+    pub max_depth: Option<usize>,
+    /// Largest text of one element (an href, a `calendar-data` payload...) in bytes,
+    /// checked as each text chunk is read (default [`DEFAULT_MAX_XML_TEXT_SIZE`]).
+    pub max_text_size: Option<usize>,
+    /// Most `<D:response>` items accepted in one document (unlimited by default).
+    pub max_items: Option<usize>,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: Some(DEFAULT_MAX_XML_DEPTH),
+            max_text_size: Some(DEFAULT_MAX_XML_TEXT_SIZE),
+            max_items: None,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// No limit at all, for trusted servers.
+    pub fn unlimited() -> Self {
+        Self {
+            max_depth: None,
+            max_text_size: None,
+            max_items: None,
+        }
+    }
+
+    /// Fail with a [`ParseLimitError`] when `value` exceeds the `limit_kind` limit.
+    pub(crate) fn check(&self, limit_kind: ParseLimit, value: usize) -> Result<()> {
+        let limit = match limit_kind {
+            ParseLimit::Depth => self.max_depth,
+            ParseLimit::TextSize => self.max_text_size,
+            ParseLimit::Items => self.max_items,
+        };
+        match limit {
+            Some(limit) if value > limit => Err(ParseLimitError { limit_kind, limit }.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Which [`ParseLimits`] entry a document exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseLimit {
+    Depth,
+    TextSize,
+    Items,
+}
+
+/// A multistatus document exceeds the [`ParseLimits`] of the parser; parsing stopped
+/// there.
+///
+/// Returned wrapped in [`anyhow::Error`]; recover it with `downcast_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLimitError {
+    pub limit_kind: ParseLimit,
+    /// The exceeded limit.
+    pub limit: usize,
+}
+
+impl std::fmt::Display for ParseLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit_kind {
+            ParseLimit::Depth => write!(f, "XML elements nested deeper than {}", self.limit),
+            ParseLimit::TextSize => write!(f, "XML text larger than {} bytes", self.limit),
+            ParseLimit::Items => write!(f, "multistatus with more than {} responses", self.limit),
+        }
+    }
+}
+
+impl std::error::Error for ParseLimitError {}
+
+/// A member of a collection that a recursive operation could not process, as reported in
+/// a `207 Multi-Status` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberFailure {
+    pub href: String,
+    /// Raw `<D:status>` line, e.g. `HTTP/1.1 423 Locked`.
+    pub status: Option<String>,
+}
+
+impl MemberFailure {
+    pub fn status_code(&self) -> Option<u16> {
+        self.status.as_deref().and_then(http_status_code)
+    }
+}
+
+/// Outcome of [`crate::webdav::WebDavClient::delete_recursive`] or
+/// [`crate::webdav::WebDavClient::copy_recursive`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecursiveReport {
+    /// Status of the last request on the collection itself (`207` when members failed,
+    /// `404` when a retried member took the collection with it).
+    pub status: u16,
+    /// Members still failing after the retries.
+    pub failed: Vec<MemberFailure>,
+    /// Hrefs of members that failed at first and succeeded when retried.
+    pub recovered: Vec<String>,
+}
+
+impl RecursiveReport {
+    /// `true` when the whole tree was processed: no member failed and the last status is
+    /// `2xx`, or `404` once the tree is gone.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty() && ((200..300).contains(&self.status) || self.status == 404)
+    }
+}
+
+/// What a server advertises in its `OPTIONS` response: compliance classes and extensions
+/// (`DAV`), methods (`Allow`) and search grammars (`DASL`).
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::DavCapabilities;
+/// use hyper::HeaderMap;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("DAV", "1, 2, 3, access-control, calendar-access".parse()?);
+/// headers.insert("Allow", "OPTIONS, GET, PROPFIND, REPORT, MKCALENDAR".parse()?);
+/// let caps = DavCapabilities::from_headers(&headers);
+/// assert!(caps.supports_locking() && caps.supports_calendar_access());
+/// assert!(!caps.supports_addressbook());
+/// assert!(caps.allows("report"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DavCapabilities {
+    /// Tokens of the `DAV` header(s), e.g. `1`, `2`, `calendar-access` or a
+    /// `<coded-url>`.
+    pub dav: Vec<String>,
+    /// Methods of the `Allow` header(s), upper-cased.
+    pub allow: Vec<String>,
+    /// Query grammars of the `DASL` header(s), e.g. `<DAV:basicsearch>`.
+    pub dasl: Vec<String>,
+}
+
+impl DavCapabilities {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let tokens = |name: &str| -> Vec<String> {
+            headers
+                .get_all(name)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        Self {
+            dav: tokens("dav"),
+            allow: tokens("allow")
+                .into_iter()
+                .map(|m| m.to_ascii_uppercase())
+                .collect(),
+            dasl: tokens("dasl"),
+        }
+    }
+
+    /// `true` when the `DAV` header lists `token` (case-insensitive).
+    pub fn has(&self, token: &str) -> bool {
+        self.dav.iter().any(|t| t.eq_ignore_ascii_case(token))
+    }
+
+    /// `true` when `Allow` lists `method`. Servers that send no `Allow` header are assumed
+    /// to allow every method.
+    pub fn allows(&self, method: &str) -> bool {
+        self.allow.is_empty() || self.allow.iter().any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    /// Class 1: basic WebDAV (RFC 4918).
+    pub fn is_webdav(&self) -> bool {
+        self.has("1")
+    }
+
+    /// Class 2: `LOCK`/`UNLOCK`.
+    pub fn supports_locking(&self) -> bool {
+        self.has("2")
+    }
+
+    /// CalDAV (RFC 4791).
+    pub fn supports_calendar_access(&self) -> bool {
+        self.has("calendar-access")
+    }
+
+    /// Implicit scheduling (RFC 6638).
+    pub fn supports_calendar_auto_schedule(&self) -> bool {
+        self.has("calendar-auto-schedule")
+    }
+
+    /// CardDAV (RFC 6352).
+    pub fn supports_addressbook(&self) -> bool {
+        self.has("addressbook")
+    }
+
+    /// `ACL` and access control properties (RFC 3744).
+    pub fn supports_access_control(&self) -> bool {
+        self.has("access-control")
+    }
+
+    /// Extended `MKCOL` (RFC 5689).
+    pub fn supports_extended_mkcol(&self) -> bool {
+        self.has("extended-mkcol")
+    }
+
+    /// `BIND`/`UNBIND`/`REBIND` (RFC 5842).
+    pub fn supports_bind(&self) -> bool {
+        self.has("bind")
+    }
+
+    /// DASL `SEARCH` with the `DAV:basicsearch` grammar (RFC 5323).
+    pub fn supports_basicsearch(&self) -> bool {
+        self.dasl
+            .iter()
+            .any(|g| g.eq_ignore_ascii_case("<DAV:basicsearch>"))
+    }
+}
+
+/// Which members of a collection [`CollectionListing::filter`] keeps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListFilter {
+    All,
+    /// Sub-collections only.
+    Collections,
+    /// Non-collection resources only.
+    Files,
+    /// Non-collection resources of a media type, compared case-insensitively without
+    /// parameters; `image/*` matches every subtype.
+    ContentType(String),
+}
+
+impl ListFilter {
+    pub fn matches(&self, resource: &DavResource) -> bool {
+        match self {
+            Self::All => true,
+            Self::Collections => resource.is_collection(),
+            Self::Files => !resource.is_collection(),
+            Self::ContentType(wanted) => {
+                let Some(actual) = resource.content_type.as_deref() else {
+                    return false;
+                };
+                let essence = actual.split(';').next().unwrap_or_default().trim();
+                let wanted = wanted.trim();
+                !resource.is_collection()
+                    && match wanted.strip_suffix("/*") {
+                        Some(kind) => essence
+                            .split_once('/')
+                            .is_some_and(|(k, _)| k.eq_ignore_ascii_case(kind)),
+                        None => essence.eq_ignore_ascii_case(wanted),
+                    }
+            }
+        }
+    }
+}
+
+/// Members of a collection, as listed by [`crate::webdav::WebDavClient::list_collection`],
+/// sorted by href.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::{CollectionListing, DavResource, ListFilter};
+///
+/// let file = |href: &str, content_type: &str| DavResource {
+///     href: href.into(),
+///     content_type: Some(content_type.into()),
+///     ..DavResource::default()
+/// };
+/// let listing = CollectionListing::new(vec![
+///     file("/f/b.png", "image/png"),
+///     file("/f/a.txt", "text/plain"),
+///     file("/f/c.jpg", "image/jpeg"),
+/// ]);
+/// let images = listing.filter(&ListFilter::ContentType("image/*".into()));
+/// let pages: Vec<Vec<&str>> = images
+///     .pages(1)
+///     .map(|page| page.iter().map(|r| r.href.as_str()).collect())
+///     .collect();
+/// assert_eq!(pages, [["/f/b.png"], ["/f/c.jpg"]]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectionListing {
+    entries: Vec<DavResource>,
+}
+
+impl CollectionListing {
+    pub fn new(mut entries: Vec<DavResource>) -> Self {
+        entries.sort_by(|a, b| a.href.cmp(&b.href));
+        Self { entries }
+    }
+
+    /// The members matching `filter`.
+    pub fn filter(&self, filter: &ListFilter) -> Self {
+        Self {
+            entries: self
+                .entries
+                .iter()
+                .filter(|resource| filter.matches(resource))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Consecutive pages of at most `page_size` members (at least one per page).
+    pub fn pages(&self, page_size: usize) -> std::slice::Chunks<'_, DavResource> {
+        self.entries.chunks(page_size.max(1))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DavResource> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn into_entries(self) -> Vec<DavResource> {
+        self.entries
+    }
+}
+
+impl IntoIterator for CollectionListing {
+    type Item = DavResource;
+    type IntoIter = std::vec::IntoIter<DavResource>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a CollectionListing {
+    type Item = &'a DavResource;
+    type IntoIter = std::slice::Iter<'a, DavResource>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// One version of a versioned resource (DeltaV, RFC 3253), as reported by
+/// [`crate::webdav::WebDavClient::version_tree`] and
+/// [`crate::webdav::WebDavClient::version_history`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionEntry {
+    /// URL of the version resource; `GET` it for the content of that version.
+    pub href: String,
+    /// `DAV:version-name`, e.g. `V3`.
+    pub version_name: Option<String>,
+    pub creator: Option<String>,
+    pub comment: Option<String>,
+    /// `DAV:getlastmodified`, an HTTP date.
+    pub last_modified: Option<String>,
+    pub etag: Option<String>,
+    /// Versions this one was derived from (`DAV:predecessor-set`).
+    pub predecessors: Vec<String>,
+    /// Versions derived from this one (`DAV:successor-set`).
+    pub successors: Vec<String>,
+}
@@ -0,0 +1,137 @@
+//! DeltaV versioning reports (RFC 3253): `version-tree` (§3.7) and version listings through
+//! `expand-property` (§3.8), for "history" features on servers exposing versioned
+//! resources (e.g. SabreDAV with a versioning plugin).
+
+use anyhow::{Result, anyhow};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use crate::webdav::types::{ParseLimits, VersionEntry};
+use crate::webdav::xml::{LimitGuard, local_name};
+
+const VERSION_PROPS: &str = "<D:version-name/><D:creator-displayname/><D:comment/><D:getlastmodified/><D:getetag/><D:predecessor-set/><D:successor-set/>";
+
+/// `version-tree` `REPORT` body requesting the [`VersionEntry`] properties.
+pub(crate) fn version_tree_body() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:version-tree xmlns:D="DAV:"><D:prop>{VERSION_PROPS}</D:prop></D:version-tree>"#
+    )
+}
+
+/// `expand-property` `REPORT` body following `version-history` → `version-set` and
+/// returning the [`VersionEntry`] properties of every version.
+pub(crate) fn version_history_body() -> String {
+    let props: String = VERSION_PROPS
+        .split("/>")
+        .filter_map(|tag| tag.strip_prefix("<D:"))
+        .map(|name| format!(r#"<D:property name="{name}"/>"#))
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:expand-property xmlns:D="DAV:"><D:property name="version-history"><D:property name="version-set">{props}</D:property></D:property></D:expand-property>"#
+    )
+}
+
+/// Parse the versions of a `version-tree` or `expand-property` response.
+///
+/// Every `response` carrying a `DAV:version-name` is a version, including responses nested
+/// inside expanded properties; versions are sorted by href and deduplicated.
+///
+/// # Example
+///
+/// ```
+/// use fast_dav_rs::webdav::parse_versions;
+///
+/// let xml = r#"<D:multistatus xmlns:D="DAV:"><D:response>
+///   <D:href>/versions/42/1</D:href>
+///   <D:propstat><D:prop>
+///     <D:version-name>V1</D:version-name>
+///     <D:successor-set><D:href>/versions/42/2</D:href></D:successor-set>
+///   </D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+/// </D:response></D:multistatus>"#;
+/// let versions = parse_versions(xml.as_bytes())?;
+/// assert_eq!(versions[0].version_name.as_deref(), Some("V1"));
+/// assert_eq!(versions[0].successors, ["/versions/42/2"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the body is not well-formed XML.
+pub fn parse_versions(body: &[u8]) -> Result<Vec<VersionEntry>> {
+    parse_versions_with_limits(body, ParseLimits::default())
+}
+
+/// Variant of [`parse_versions`] with other [`ParseLimits`] than the default ones.
+///
+/// # Errors
+///
+/// Returns a [`ParseLimitError`](crate::webdav::ParseLimitError) when the document exceeds
+/// `limits`, or an error when it is not well-formed XML.
+pub fn parse_versions_with_limits(body: &[u8], limits: ParseLimits) -> Result<Vec<VersionEntry>> {
+    let mut xml = Reader::from_reader(body);
+    xml.config_mut().trim_text(false);
+    let mut buf = Vec::new();
+    let mut guard = LimitGuard::new(limits);
+    let mut stack: Vec<String> = Vec::new();
+    // One entry per open `response`; expanded properties nest responses.
+    let mut open: Vec<VersionEntry> = Vec::new();
+    let mut versions = Vec::new();
+    // Text arrives in pieces around entity references; it is applied once complete.
+    let mut text = String::new();
+
+    loop {
+        let event = xml
+            .read_event_into(&mut buf)
+            .map_err(|e| anyhow!("XML error: {e}"))?;
+        guard.on_event(&event)?;
+        match event {
+            Event::Start(e) => {
+                flush_text(&stack, &mut text, &mut open);
+                let name = local_name(e.name().as_ref());
+                if name == "response" {
+                    open.push(VersionEntry::default());
+                }
+                stack.push(name);
+            }
+            Event::End(_) => {
+                flush_text(&stack, &mut text, &mut open);
+                if stack.pop().as_deref() == Some("response")
+                    && let Some(entry) = open.pop()
+                    && entry.version_name.is_some()
+                {
+                    versions.push(entry);
+                }
+            }
+            Event::Eof => break,
+            event => guard.append_text(&mut text, &event)?,
+        }
+        buf.clear();
+    }
+    versions.sort_by(|a, b| a.href.cmp(&b.href));
+    versions.dedup_by(|a, b| a.href == b.href);
+    Ok(versions)
+}
+
+fn flush_text(stack: &[String], text: &mut String, open: &mut [VersionEntry]) {
+    if let Some(entry) = open.last_mut()
+        && !text.trim().is_empty()
+    {
+        on_text(stack, text.trim(), entry);
+    }
+    text.clear();
+}
+
+fn on_text(stack: &[String], text: &str, entry: &mut VersionEntry) {
+    let value = Some(text.to_string());
+    match stack.iter().rev().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["href", "response", ..] => entry.href = text.to_string(),
+        ["version-name", "prop", ..] => entry.version_name = value,
+        ["creator-displayname", "prop", ..] => entry.creator = value,
+        ["comment", "prop", ..] => entry.comment = value,
+        ["getlastmodified", "prop", ..] => entry.last_modified = value,
+        ["getetag", "prop", ..] => entry.etag = value,
+        ["href", "predecessor-set", "prop", ..] => entry.predecessors.push(text.to_string()),
+        ["href", "successor-set", "prop", ..] => entry.successors.push(text.to_string()),
+        _ => {}
+    }
+}
@@ -1,4 +1,9 @@
 use anyhow::{Result, anyhow};
+use quick_xml::escape::{resolve_predefined_entity, unescape};
+use quick_xml::events::Event;
+use std::borrow::Cow;
+
+use crate::webdav::types::{ParseLimit, ParseLimits, TextMatch};
 
 pub fn escape_xml(input: &str) -> String {
     let mut out = String::with_capacity(input.len());
@@ -15,6 +20,95 @@ pub fn escape_xml(input: &str) -> String {
     out
 }
 
+/// Text `event` adds to the element it is in: decoded text, CDATA, or a resolved entity
+/// reference, `None` for markup.
+///
+/// quick-xml reports `&amp;` or `&#38;` as events of their own, between the text around
+/// them, so parsers append every piece to a buffer and use it once the element's text is
+/// complete (at the next tag); trimming or storing each piece separately loses data.
+///
+/// # Errors
+///
+/// Returns an error for an unknown entity or a malformed escape.
+pub(crate) fn event_text<'a>(event: &'a Event<'_>) -> Result<Option<Cow<'a, str>>> {
+    let text = match event {
+        Event::Text(e) => match std::str::from_utf8(e.as_ref()) {
+            Ok(s) => unescape(s).map_err(|err| anyhow!("XML decode error: {err}"))?,
+            Err(_) => String::from_utf8_lossy(e.as_ref()),
+        },
+        Event::CData(e) => String::from_utf8_lossy(e.as_ref()),
+        Event::GeneralRef(e) => match e.resolve_char_ref() {
+            Ok(Some(ch)) => Cow::Owned(ch.to_string()),
+            _ => {
+                let name = String::from_utf8_lossy(e.as_ref());
+                Cow::Borrowed(
+                    resolve_predefined_entity(&name)
+                        .ok_or_else(|| anyhow!("XML decode error: unknown entity &{name};"))?,
+                )
+            }
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(text))
+}
+
+/// Applies [`ParseLimits`] to a document read event by event, for parsers walking the
+/// reader themselves: nesting and `response` count as tags are read, text size as it is
+/// appended.
+pub(crate) struct LimitGuard {
+    limits: ParseLimits,
+    depth: usize,
+    items: usize,
+}
+
+impl LimitGuard {
+    pub(crate) fn new(limits: ParseLimits) -> Self {
+        Self {
+            limits,
+            depth: 0,
+            items: 0,
+        }
+    }
+
+    /// Account for `event` before it is handled.
+    pub(crate) fn on_event(&mut self, event: &Event<'_>) -> Result<()> {
+        match event {
+            Event::Start(e) | Event::Empty(e) => {
+                self.limits.check(ParseLimit::Depth, self.depth + 1)?;
+                if e.local_name().as_ref().eq_ignore_ascii_case(b"response") {
+                    self.items += 1;
+                    self.limits.check(ParseLimit::Items, self.items)?;
+                }
+                if matches!(event, Event::Start(_)) {
+                    self.depth += 1;
+                }
+            }
+            Event::End(_) => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Append the text of `event`, if any, to `text`, the text of the current element.
+    pub(crate) fn append_text(&self, text: &mut String, event: &Event<'_>) -> Result<()> {
+        if let Some(piece) = event_text(event)? {
+            self.limits
+                .check(ParseLimit::TextSize, text.len() + piece.len())?;
+            text.push_str(&piece);
+        }
+        Ok(())
+    }
+}
+
+/// Lower-cased local part of a qualified element name (`D:Href` → `href`).
+pub(crate) fn local_name(raw: &[u8]) -> String {
+    let local = match raw.iter().position(|b| *b == b':') {
+        Some(idx) => &raw[idx + 1..],
+        None => raw,
+    };
+    String::from_utf8_lossy(local).to_ascii_lowercase()
+}
+
 /// Validate an iCalendar component name (e.g. `VEVENT`, `VTODO`, `X-CUSTOM`).
 ///
 /// Accepts non-empty names made exclusively of ASCII alphanumeric characters
@@ -69,6 +163,25 @@ pub(crate) fn validate_utc_datetime(value: &str) -> Result<()> {
     Ok(())
 }
 
+/// Serialize a `text-match` element using the namespace `prefix` (`C` for both CalDAV and
+/// CardDAV bodies in this crate).
+pub(crate) fn text_match_xml(prefix: &str, text_match: &TextMatch) -> String {
+    let mut attrs = String::new();
+    if let Some(collation) = &text_match.collation {
+        attrs.push_str(&format!(r#" collation="{}""#, escape_xml(collation)));
+    }
+    if let Some(match_type) = text_match.match_type {
+        attrs.push_str(&format!(r#" match-type="{}""#, match_type.as_str()));
+    }
+    if text_match.negate {
+        attrs.push_str(r#" negate-condition="yes""#);
+    }
+    format!(
+        "<{prefix}:text-match{attrs}>{}</{prefix}:text-match>",
+        escape_xml(&text_match.text)
+    )
+}
+
 pub fn build_sync_collection_body(
     sync_token: Option<&str>,
     limit: Option<u32>,
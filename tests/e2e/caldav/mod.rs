@@ -5,7 +5,9 @@ pub mod discovery;
 pub mod operations;
 pub mod parallel;
 pub mod parsing;
+pub mod query;
 pub mod resilience;
+pub mod scheduling;
 pub mod security;
 pub mod streaming;
 pub mod sync;
@@ -0,0 +1,71 @@
+use crate::util::{unique_calendar_name, unique_uid};
+use bytes::Bytes;
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::testing::CleanupGuard;
+
+const SABREDAV_URL: &str = "http://localhost:8080/";
+const TEST_USER: &str = "test";
+const TEST_PASS: &str = "test";
+
+fn create_test_client() -> CalDavClient {
+    CalDavClient::new(SABREDAV_URL, Some(TEST_USER), Some(TEST_PASS))
+        .expect("Failed to create CalDAV client")
+}
+
+async fn create_calendar(client: &CalDavClient, calendar_path: &str, name: &str) {
+    let calendar_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<C:mkcalendar xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:set>
+    <D:prop>
+      <D:displayname>{name}</D:displayname>
+    </D:prop>
+  </D:set>
+</C:mkcalendar>"#
+    );
+    let resp = client
+        .mkcalendar(calendar_path, &calendar_xml)
+        .await
+        .expect("MKCALENDAR request failed");
+    assert!(
+        resp.status().is_success(),
+        "Expected successful calendar creation, got {}",
+        resp.status()
+    );
+}
+
+#[tokio::test]
+async fn test_query_expand_returns_instances() {
+    let client = create_test_client();
+    let calendar_name = unique_calendar_name("expand");
+    let calendar_path = format!("calendars/test/{calendar_name}/");
+    let mut guard = CleanupGuard::new(client.clone());
+    guard.track(&calendar_path);
+
+    create_calendar(&client, &calendar_path, &calendar_name).await;
+
+    let uid = unique_uid("daily");
+    let event = format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//fast-dav-rs//EN\r\nBEGIN:VEVENT\r\nUID:{uid}\r\nDTSTAMP:20240101T000000Z\r\nDTSTART:20240110T090000Z\r\nDTEND:20240110T093000Z\r\nRRULE:FREQ=DAILY;COUNT=5\r\nSUMMARY:Standup\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    );
+    let resp = client
+        .put_if_none_match(&format!("{calendar_path}{uid}.ics"), Bytes::from(event))
+        .await
+        .expect("PUT request failed");
+    assert!(resp.status().is_success());
+
+    let objects = client
+        .query(&calendar_path)
+        .time_range(Some("20240111T000000Z"), Some("20240113T000000Z"))
+        .expand("20240111T000000Z", "20240113T000000Z")
+        .execute()
+        .await
+        .expect("calendar-query with expand failed");
+    assert_eq!(objects.len(), 1);
+
+    let data = objects[0].calendar_data.as_deref().expect("calendar-data");
+    assert!(!data.contains("RRULE"), "expanded data still has RRULE");
+    assert_eq!(data.matches("RECURRENCE-ID").count(), 2);
+
+    guard.cleanup().await;
+}
@@ -0,0 +1 @@
+pub mod scheduling_tests;
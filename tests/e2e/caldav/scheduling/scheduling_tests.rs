@@ -0,0 +1,67 @@
+use crate::util::{unique_calendar_name, unique_uid};
+use bytes::Bytes;
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::testing::CleanupGuard;
+
+const SABREDAV_URL: &str = "http://localhost:8080/";
+const TEST_USER: &str = "test";
+const TEST_PASS: &str = "test";
+
+fn create_test_client() -> CalDavClient {
+    CalDavClient::new(SABREDAV_URL, Some(TEST_USER), Some(TEST_PASS))
+        .expect("Failed to create CalDAV client")
+}
+
+async fn create_calendar(client: &CalDavClient, calendar_path: &str, name: &str) {
+    let calendar_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<C:mkcalendar xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:set>
+    <D:prop>
+      <D:displayname>{name}</D:displayname>
+    </D:prop>
+  </D:set>
+</C:mkcalendar>"#
+    );
+    let resp = client
+        .mkcalendar(calendar_path, &calendar_xml)
+        .await
+        .expect("MKCALENDAR request failed");
+    assert!(
+        resp.status().is_success(),
+        "Expected successful calendar creation, got {}",
+        resp.status()
+    );
+}
+
+#[tokio::test]
+async fn test_free_busy_query_reports_busy_event() {
+    let client = create_test_client();
+    let calendar_name = unique_calendar_name("freebusy");
+    let calendar_path = format!("calendars/test/{calendar_name}/");
+    let mut guard = CleanupGuard::new(client.clone());
+    guard.track(&calendar_path);
+
+    create_calendar(&client, &calendar_path, &calendar_name).await;
+
+    let uid = unique_uid("busy");
+    let event = format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//fast-dav-rs//EN\r\nBEGIN:VEVENT\r\nUID:{uid}\r\nDTSTAMP:20240101T000000Z\r\nDTSTART:20240110T090000Z\r\nDTEND:20240110T100000Z\r\nSUMMARY:Busy\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    );
+    let resp = client
+        .put_if_none_match(&format!("{calendar_path}{uid}.ics"), Bytes::from(event))
+        .await
+        .expect("PUT request failed");
+    assert!(resp.status().is_success());
+
+    let fb = client
+        .free_busy_query(&calendar_path, "20240110T000000Z", "20240111T000000Z")
+        .await
+        .expect("free-busy-query failed");
+    let busy: Vec<_> = fb.busy().collect();
+    assert_eq!(busy.len(), 1, "Expected one busy period, got {fb:?}");
+    assert_eq!(busy[0].start, "20240110T090000Z");
+    assert_eq!(busy[0].end, "20240110T100000Z");
+
+    guard.cleanup().await;
+}
@@ -1,43 +1,3 @@
-use std::sync::atomic::{AtomicU64, Ordering};
-
-static UNIQUE_COUNTER: AtomicU64 = AtomicU64::new(0);
-
-pub fn unique_calendar_name(prefix: &str) -> String {
-    let counter = UNIQUE_COUNTER.fetch_add(1, Ordering::Relaxed);
-    format!(
-        "{}_{}_{}",
-        prefix,
-        chrono::Utc::now().timestamp_micros(),
-        counter
-    )
-}
-
-pub fn unique_addressbook_name(prefix: &str) -> String {
-    let counter = UNIQUE_COUNTER.fetch_add(1, Ordering::Relaxed);
-    format!(
-        "{}_{}_{}",
-        prefix,
-        chrono::Utc::now().timestamp_micros(),
-        counter
-    )
-}
-
-pub fn unique_uid(prefix: &str) -> String {
-    let counter = UNIQUE_COUNTER.fetch_add(1, Ordering::Relaxed);
-    format!(
-        "{}-{}-{}@example.com",
-        prefix,
-        chrono::Utc::now().timestamp_micros(),
-        counter
-    )
-}
-
-pub fn unique_contact_uri(prefix: &str) -> String {
-    let counter = UNIQUE_COUNTER.fetch_add(1, Ordering::Relaxed);
-    format!(
-        "{}_{}_{}.vcf",
-        prefix,
-        chrono::Utc::now().timestamp_micros(),
-        counter
-    )
-}
+pub use fast_dav_rs::testing::{
+    unique_addressbook_name, unique_calendar_name, unique_contact_uri, unique_uid,
+};
@@ -0,0 +1,149 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{
+    AlarmBuilder, AlarmQuirks, acknowledge_alarm, add_alarm, parse_icalendar, remove_alarm,
+    remove_alarms, snooze_alarm,
+};
+use fast_dav_rs::common::contentline::Component;
+
+const EVENT: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n\
+BEGIN:VEVENT\r\nUID:e1\r\nDTSTAMP:20240101T000000Z\r\nDTSTART:20240105T090000Z\r\n\
+RRULE:FREQ=DAILY\r\nSUMMARY:Standup\r\n\
+BEGIN:VALARM\r\nUID:a1\r\nACTION:DISPLAY\r\nTRIGGER:-PT10M\r\nDESCRIPTION:Soon\r\nEND:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:e1\r\nRECURRENCE-ID:20240106T090000Z\r\nDTSTART:20240106T100000Z\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+fn master_alarms(ics: &str) -> Vec<Component> {
+    let calendar = Component::parse(ics).unwrap();
+    calendar.components[0]
+        .components_named("VALARM")
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn builds_alarms() {
+    let alarm = AlarmBuilder::display("-PT15M")
+        .related_end()
+        .uid("a2")
+        .repeat(2, "PT5M")
+        .build()
+        .unwrap();
+    assert_eq!(alarm.value("UID"), Some("a2"));
+    assert_eq!(
+        alarm.property("TRIGGER").unwrap().param("RELATED"),
+        Some("END")
+    );
+    assert_eq!(alarm.value("DESCRIPTION"), Some("Reminder"));
+    assert_eq!(alarm.value("REPEAT"), Some("2"));
+
+    let email = AlarmBuilder::display("PT0S")
+        .action("email")
+        .summary("Call")
+        .attendee("jane@example.com")
+        .build()
+        .unwrap();
+    assert_eq!(email.value("ATTENDEE"), Some("mailto:jane@example.com"));
+    assert!(
+        AlarmBuilder::display("PT0S")
+            .action("EMAIL")
+            .build()
+            .is_err()
+    );
+    assert!(AlarmBuilder::display("soon").build().is_err());
+    assert!(
+        AlarmBuilder::display("PT0S")
+            .absolute("20240101")
+            .build()
+            .is_err()
+    );
+    assert!(
+        AlarmBuilder::display("PT0S")
+            .repeat(1, "PT0S")
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+fn adds_and_removes_alarms_on_master() {
+    let ics = add_alarm(
+        EVENT,
+        &AlarmBuilder::display("-P1D").uid("a2"),
+        AlarmQuirks::Apple,
+    )
+    .unwrap();
+    let alarms = master_alarms(&ics);
+    assert_eq!(alarms.len(), 2);
+    assert_eq!(alarms[1].value("X-WR-ALARMUID"), Some("a2"));
+    // The override is untouched.
+    let parsed = parse_icalendar(&ics).unwrap();
+    assert!(parsed.components[1].alarms.is_empty());
+
+    let ics = remove_alarm(&ics, 0).unwrap();
+    let alarms = master_alarms(&ics);
+    assert_eq!(alarms.len(), 1);
+    assert_eq!(alarms[0].value("UID"), Some("a2"));
+    assert!(remove_alarm(&ics, 1).is_err());
+    assert!(master_alarms(&remove_alarms(&ics).unwrap()).is_empty());
+    assert!(
+        add_alarm(
+            "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n",
+            &AlarmBuilder::display("PT0S"),
+            AlarmQuirks::Standard
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn snoozes_and_acknowledges_rfc9074() {
+    let ics = snooze_alarm(EVENT, 0, "20240105T085500Z", AlarmQuirks::Standard).unwrap();
+    let alarms = master_alarms(&ics);
+    assert_eq!(alarms.len(), 2);
+    assert!(alarms[0].value("ACKNOWLEDGED").is_some());
+    let related = alarms[1].property("RELATED-TO").unwrap();
+    assert_eq!(related.value, "a1");
+    assert_eq!(related.param("RELTYPE"), Some("SNOOZE"));
+    assert_eq!(alarms[1].value("TRIGGER"), Some("20240105T085500Z"));
+    assert_eq!(alarms[1].value("DESCRIPTION"), Some("Soon"));
+
+    // Snoozing again replaces the earlier snooze.
+    let ics = snooze_alarm(&ics, 0, "20240105T085800Z", AlarmQuirks::Standard).unwrap();
+    let alarms = master_alarms(&ics);
+    assert_eq!(alarms.len(), 2);
+    assert_eq!(alarms[1].value("TRIGGER"), Some("20240105T085800Z"));
+
+    let ics = acknowledge_alarm(&ics, 0, AlarmQuirks::Standard).unwrap();
+    assert_eq!(master_alarms(&ics).len(), 1);
+    assert!(snooze_alarm(EVENT, 0, "tomorrow", AlarmQuirks::Standard).is_err());
+}
+
+#[test]
+fn snoozes_with_mozilla_properties() {
+    let ics = snooze_alarm(EVENT, 0, "20240105T085500Z", AlarmQuirks::Mozilla).unwrap();
+    let calendar = Component::parse(&ics).unwrap();
+    let event = &calendar.components[0];
+    assert_eq!(event.value("X-MOZ-SNOOZE-TIME"), Some("20240105T085500Z"));
+    assert!(event.value("X-MOZ-LASTACK").is_some());
+    assert_eq!(master_alarms(&ics).len(), 1);
+
+    let ics = acknowledge_alarm(&ics, 0, AlarmQuirks::Mozilla).unwrap();
+    let calendar = Component::parse(&ics).unwrap();
+    assert!(calendar.components[0].value("X-MOZ-SNOOZE-TIME").is_none());
+}
+
+#[tokio::test]
+async fn snooze_alarm_validates_before_network() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .snooze_alarm(
+            "calendars/test/default/e1.ics",
+            0,
+            "soon",
+            AlarmQuirks::Standard,
+        )
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("invalid snooze time"));
+}
@@ -0,0 +1,78 @@
+use fast_dav_rs::caldav::{
+    Availability, AvailableSlot, FreeBusyType, build_availability, parse_availability,
+    parse_multistatus_bytes,
+};
+
+const WORKING_HOURS: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//example//EN\r\nBEGIN:VAVAILABILITY\r\nUID:avail-1\r\nDTSTAMP:20240101T000000Z\r\nBUSYTYPE:BUSY\r\nDTSTART:20240101T000000Z\r\nPRIORITY:1\r\nSUMMARY:Office\\, weekdays\r\nBEGIN:AVAILABLE\r\nUID:slot-1\r\nDTSTAMP:20240101T000000Z\r\nDTSTART;TZID=Europe/Paris:20240101T090000\r\nDTEND;TZID=Europe/Paris:20240101T170000\r\nRRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR\r\nEND:AVAILABLE\r\nEND:VAVAILABILITY\r\nEND:VCALENDAR\r\n";
+
+#[test]
+fn parses_vavailability_with_slots() {
+    let parsed = parse_availability(WORKING_HOURS).unwrap();
+    assert_eq!(parsed.len(), 1);
+    let availability = &parsed[0];
+    assert_eq!(availability.uid, "avail-1");
+    assert_eq!(availability.busy_type, FreeBusyType::Busy);
+    assert_eq!(availability.start.as_deref(), Some("20240101T000000Z"));
+    assert_eq!(availability.end, None);
+    assert_eq!(availability.priority, Some(1));
+    assert_eq!(availability.summary.as_deref(), Some("Office, weekdays"));
+    let slot = &availability.available[0];
+    assert_eq!(slot.start, "20240101T090000");
+    assert_eq!(slot.tzid.as_deref(), Some("Europe/Paris"));
+    assert_eq!(slot.end.as_deref(), Some("20240101T170000"));
+    assert_eq!(
+        slot.rrule.as_deref(),
+        Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")
+    );
+}
+
+#[test]
+fn busy_type_defaults_to_busy_unavailable() {
+    let ics =
+        "BEGIN:VCALENDAR\r\nBEGIN:VAVAILABILITY\r\nUID:a\r\nEND:VAVAILABILITY\r\nEND:VCALENDAR\r\n";
+    let parsed = parse_availability(ics).unwrap();
+    assert_eq!(parsed[0].busy_type, FreeBusyType::BusyUnavailable);
+    assert!(parsed[0].available.is_empty());
+
+    let missing_uid =
+        "BEGIN:VCALENDAR\r\nBEGIN:VAVAILABILITY\r\nEND:VAVAILABILITY\r\nEND:VCALENDAR\r\n";
+    assert!(parse_availability(missing_uid).is_err());
+}
+
+#[test]
+fn build_round_trips_through_parse() {
+    let mut slot = AvailableSlot::new("slot-1", "20240101T090000");
+    slot.tzid = Some("Europe/Paris".into());
+    slot.duration = Some("PT8H".into());
+    slot.rrule = Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR".into());
+    let mut availability = Availability::new("avail-1");
+    availability.summary = Some("Office; hours".into());
+    availability.available.push(slot);
+
+    let ics = build_availability(std::slice::from_ref(&availability));
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.contains("DTSTART;TZID=Europe/Paris:20240101T090000\r\n"));
+    assert!(ics.contains("DURATION:PT8H\r\n"));
+    assert!(!ics.contains("BUSYTYPE"));
+    assert_eq!(parse_availability(&ics).unwrap(), vec![availability]);
+}
+
+#[test]
+fn streaming_parser_reads_calendar_availability_property() {
+    let xml = format!(
+        r#"<?xml version="1.0"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/calendars/jane/inbox/</D:href>
+    <D:propstat>
+      <D:prop><C:calendar-availability>{}</C:calendar-availability></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#,
+        WORKING_HOURS.replace('\r', "&#13;")
+    );
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    let ics = items[0].calendar_availability.as_deref().unwrap();
+    assert_eq!(parse_availability(ics).unwrap()[0].uid, "avail-1");
+}
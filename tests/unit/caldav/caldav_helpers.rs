@@ -1,3 +1,4 @@
+use fast_dav_rs::caldav::build_calendar_multiget_bodies;
 use fast_dav_rs::{
     build_calendar_multiget_body, build_calendar_query_body, build_sync_collection_body,
     map_calendar_list, map_calendar_objects, map_sync_response, parse_multistatus_bytes,
@@ -658,3 +659,93 @@ fn sync_deletion_requires_numeric_404_or_410_status() {
         "\"HTTP/1.1 4040 Custom\" must not be treated as a 404 deletion"
     );
 }
+
+#[test]
+fn calendar_multiget_bodies_split_into_chunks() {
+    let hrefs: Vec<String> = (0..5).map(|i| format!("/cal/{i}.ics")).collect();
+    let bodies = build_calendar_multiget_bodies(hrefs.iter().chain([&String::new()]), true, 2);
+    assert_eq!(bodies.len(), 3);
+    assert!(
+        bodies[0].contains(
+            "<D:href>/cal/0.ics</D:href><D:href>/cal/1.ics</D:href></C:calendar-multiget>"
+        )
+    );
+    assert!(bodies[2].contains("<D:href>/cal/4.ics</D:href></C:calendar-multiget>"));
+    assert!(bodies.iter().all(|b| b.contains("<C:calendar-data/>")));
+
+    assert_eq!(build_calendar_multiget_bodies(&hrefs, false, 0).len(), 5);
+    assert!(build_calendar_multiget_bodies(Vec::<String>::new(), false, 10).is_empty());
+}
+
+#[tokio::test]
+async fn calendar_multiget_without_hrefs_skips_network() {
+    let mut client = fast_dav_rs::CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    client.set_multiget_chunking(0, 0);
+    let objects = client
+        .calendar_multiget("calendars/test/default/", Vec::<String>::new(), true)
+        .await
+        .unwrap();
+    assert!(objects.is_empty());
+    assert!(
+        client
+            .calendar_multiget("calendars/test/default/", ["/cal/a.ics"], true)
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn sync_response_flags_truncation_with_507() {
+    let sync_xml = r#"<D:multistatus xmlns:D="DAV:">
+    <D:response>
+        <D:href>/calendars/user/work/a.ics</D:href>
+        <D:propstat><D:prop><D:getetag>"a"</D:getetag></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+    </D:response>
+    <D:response>
+        <D:href>/calendars/user/work/</D:href>
+        <D:status>HTTP/1.1 507 Insufficient Storage</D:status>
+    </D:response>
+    <D:sync-token>partial-token</D:sync-token>
+</D:multistatus>"#;
+
+    let parsed = parse_multistatus_bytes(sync_xml.as_bytes()).unwrap();
+    let sync = map_sync_response(&HeaderMap::new(), parsed.items, parsed.sync_token);
+    assert!(sync.truncated);
+    assert_eq!(sync.items.len(), 1);
+    assert_eq!(sync.items[0].href, "/calendars/user/work/a.ics");
+    assert_eq!(sync.sync_token.as_deref(), Some("partial-token"));
+}
+
+#[test]
+fn sync_response_normalizes_and_deduplicates_hrefs() {
+    let sync_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>https://dav.example.com/cal/work/ev%65nt%201.ics</d:href>
+    <d:propstat>
+      <d:prop><d:getetag>"1"</d:getetag></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/cal/work/other.ics</d:href>
+    <d:propstat>
+      <d:prop><d:getetag>"7"</d:getetag></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/cal//work/event%201.ics</d:href>
+    <d:status>HTTP/1.1 404 Not Found</d:status>
+  </d:response>
+  <d:sync-token>http://example.com/sync/2</d:sync-token>
+</d:multistatus>"#;
+
+    let parsed = parse_multistatus_bytes(sync_xml.as_bytes()).unwrap();
+    let sync = map_sync_response(&HeaderMap::new(), parsed.items, parsed.sync_token);
+    let hrefs: Vec<_> = sync.items.iter().map(|item| item.href.as_str()).collect();
+    assert_eq!(hrefs, ["/cal/work/event%201.ics", "/cal/work/other.ics"]);
+    // The latest report of a member wins.
+    assert!(sync.items[0].is_deleted);
+    assert!(!sync.items[1].is_deleted);
+}
@@ -0,0 +1,240 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{
+    CalendarInfo, CalendarPropPatch, MkCalendar, ScheduleTransparency, UnsupportedComponentError,
+    check_supported_components, map_calendar_list,
+};
+use fast_dav_rs::parse_multistatus_bytes;
+
+#[test]
+fn mkcalendar_builds_all_properties() {
+    let body = MkCalendar::new()
+        .displayname("Work & Play")
+        .description("Team <meetings>")
+        .color("#FF5733FF")
+        .timezone("BEGIN:VCALENDAR\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Paris\r\nEND:VTIMEZONE\r\nEND:VCALENDAR\r\n")
+        .components(["VEVENT", "VTODO"])
+        .build()
+        .unwrap();
+
+    assert!(
+        body.starts_with(r#"<C:mkcalendar xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav""#)
+    );
+    assert!(body.contains("<D:displayname>Work &amp; Play</D:displayname>"));
+    assert!(
+        body.contains("<C:calendar-description>Team &lt;meetings&gt;</C:calendar-description>")
+    );
+    assert!(body.contains("<A:calendar-color>#FF5733FF</A:calendar-color>"));
+    assert!(body.contains("<C:calendar-timezone>BEGIN:VCALENDAR"));
+    assert!(body.contains(
+        r#"<C:supported-calendar-component-set><C:comp name="VEVENT"/><C:comp name="VTODO"/></C:supported-calendar-component-set>"#
+    ));
+}
+
+#[test]
+fn mkcalendar_without_properties_is_empty_request() {
+    let body = MkCalendar::new().build().unwrap();
+    assert!(body.starts_with("<C:mkcalendar "));
+    assert!(body.ends_with("/>"));
+    assert!(!body.contains("<D:set>"));
+}
+
+#[test]
+fn extended_mkcol_declares_calendar_resourcetype() {
+    let body = MkCalendar::new()
+        .displayname("Work")
+        .build_extended_mkcol()
+        .unwrap();
+    assert!(body.starts_with("<D:mkcol "));
+    assert!(body.contains(
+        "<D:resourcetype><D:collection/><C:calendar/></D:resourcetype><D:displayname>Work</D:displayname>"
+    ));
+}
+
+#[tokio::test]
+async fn create_calendar_validates_components_before_network() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .create_calendar(
+            "calendars/test/new/",
+            &MkCalendar::new().components(["VEVENT\"/>"]),
+        )
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(
+        err.to_string()
+            .contains("invalid supported calendar component")
+    );
+}
+
+#[test]
+fn calendar_proppatch_uses_correct_namespaces() {
+    let patch = CalendarPropPatch::new()
+        .displayname("Renamed")
+        .description("Desc")
+        .color("#00AA00")
+        .order(-2)
+        .timezone("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n");
+    let body = patch.build();
+    assert!(body.starts_with(r#"<D:propertyupdate xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav" xmlns:A="http://apple.com/ns/ical/">"#));
+    assert!(body.contains("<D:set><D:prop><D:displayname>Renamed</D:displayname>"));
+    assert!(body.contains("<C:calendar-description>Desc</C:calendar-description>"));
+    assert!(body.contains("<A:calendar-color>#00AA00</A:calendar-color>"));
+    assert!(body.contains("<A:calendar-order>-2</A:calendar-order>"));
+    assert!(body.contains("<C:calendar-timezone>BEGIN:VCALENDAR"));
+    assert!(!patch.is_empty());
+}
+
+#[tokio::test]
+async fn proppatch_calendar_rejects_empty_patch() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .proppatch_calendar("calendars/test/work/", &CalendarPropPatch::new())
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("at least one property"));
+}
+
+#[test]
+fn builders_set_timezone_by_reference() {
+    let body = MkCalendar::new()
+        .timezone_id("Europe/Paris")
+        .build()
+        .unwrap();
+    assert!(body.contains("<C:calendar-timezone-id>Europe/Paris</C:calendar-timezone-id>"));
+
+    let body = CalendarPropPatch::new()
+        .timezone_id("America/New_York")
+        .build();
+    assert!(body.contains("<C:calendar-timezone-id>America/New_York</C:calendar-timezone-id>"));
+}
+
+#[test]
+fn maps_calendar_timezone_id_and_timezone_service_set() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/calendars/test/</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:timezone-service-set><D:href>https://tz.example.com/</D:href></C:timezone-service-set>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/calendars/test/work/</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:resourcetype><D:collection/><C:calendar/></D:resourcetype>
+        <C:calendar-timezone-id>Europe/Paris</C:calendar-timezone-id>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(
+        items[0].timezone_service_set,
+        vec!["https://tz.example.com/"]
+    );
+
+    let calendars = map_calendar_list(items);
+    assert_eq!(calendars.len(), 1);
+    assert_eq!(calendars[0].timezone_id.as_deref(), Some("Europe/Paris"));
+    assert_eq!(calendars[0].timezone, None);
+}
+
+#[test]
+fn builders_set_schedule_transparency() {
+    let body = MkCalendar::new()
+        .displayname("Holidays")
+        .transparency(ScheduleTransparency::Transparent)
+        .build()
+        .unwrap();
+    assert!(
+        body.contains("<C:schedule-calendar-transp><C:transparent/></C:schedule-calendar-transp>")
+    );
+
+    let patch = CalendarPropPatch::new().transparency(ScheduleTransparency::Opaque);
+    assert!(!patch.is_empty());
+    assert!(
+        patch
+            .build()
+            .contains("<C:schedule-calendar-transp><C:opaque/></C:schedule-calendar-transp>")
+    );
+}
+
+#[test]
+fn parses_schedule_transp_and_default_calendar_url() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/calendars/test/inbox/</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:schedule-default-calendar-URL><D:href>/calendars/test/work/</D:href></C:schedule-default-calendar-URL>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/calendars/test/holidays/</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:resourcetype><D:collection/><C:calendar/></D:resourcetype>
+        <C:schedule-calendar-transp><C:transparent/></C:schedule-calendar-transp>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(
+        items[0].schedule_default_calendar_url.as_deref(),
+        Some("/calendars/test/work/")
+    );
+    assert_eq!(items[0].schedule_calendar_transp, None);
+
+    let calendars = map_calendar_list(items);
+    assert_eq!(
+        calendars[0].transparency,
+        Some(ScheduleTransparency::Transparent)
+    );
+}
+
+fn events_only() -> CalendarInfo {
+    CalendarInfo {
+        href: "/calendars/test/work/".into(),
+        supported_components: vec!["VEVENT".into()],
+        ..CalendarInfo::default()
+    }
+}
+
+#[test]
+fn checks_components_against_supported_set() {
+    let event = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTIMEZONE\r\nTZID:UTC\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\nUID:a\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let todo =
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\nUID:t\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+    let calendar = events_only();
+    assert!(calendar.supports_component("vevent"));
+    assert!(check_supported_components(event, &calendar).is_ok());
+
+    let err = check_supported_components(todo, &calendar).unwrap_err();
+    let typed = err.downcast_ref::<UnsupportedComponentError>().unwrap();
+    assert_eq!(typed.component, "VTODO");
+    assert_eq!(typed.calendar, "/calendars/test/work/");
+    assert_eq!(typed.supported, ["VEVENT"]);
+    assert!(err.to_string().contains("does not support VTODO"));
+
+    let unrestricted = CalendarInfo::default();
+    assert!(check_supported_components(todo, &unrestricted).is_ok());
+}
+
+#[tokio::test]
+async fn put_checked_rejects_unsupported_component_before_network() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let todo = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:t\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+    let err = client
+        .put_checked(&events_only(), "/calendars/test/work/t.ics", todo.into())
+        .await
+        .unwrap_err();
+    assert!(err.is::<UnsupportedComponentError>());
+}
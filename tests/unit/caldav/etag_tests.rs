@@ -1,6 +1,10 @@
+use bytes::Bytes;
 use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{PutResult, SchedulingObject};
 use hyper::http::{HeaderMap, HeaderValue};
 
+use crate::mock_server::serve_once;
+
 #[test]
 fn test_etag_from_headers_present() {
     let mut headers = HeaderMap::new();
@@ -69,3 +73,107 @@ fn test_delete_if_match_valid_etag() {
     let etag = "\"valid123\"";
     assert_eq!(etag, "\"valid123\"");
 }
+
+#[test]
+fn test_schedule_tag_from_headers() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(CalDavClient::schedule_tag_from_headers(&headers), None);
+
+    headers.insert("Schedule-Tag", HeaderValue::from_static("\"s-42\""));
+    assert_eq!(
+        CalDavClient::schedule_tag_from_headers(&headers),
+        Some("\"s-42\"".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_put_if_schedule_tag_match_rejects_empty_tag() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .put_if_schedule_tag_match("calendars/test/default/e.ics", bytes::Bytes::new(), "")
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("Schedule-Tag cannot be empty"));
+}
+
+const EVENT: &str =
+    "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:e\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+#[tokio::test]
+async fn test_get_scheduling_object_returns_validators() {
+    let (base, _server) = serve_once(
+        concat!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/calendar\r\nETag: \"e-1\"\r\nSchedule-Tag: \"s-1\"\r\nContent-Length: 78\r\nConnection: close\r\n\r\n",
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:e\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+        ),
+        b"\r\n\r\n",
+    )
+    .await;
+    let client = CalDavClient::new(&base, None, None).unwrap();
+    let object = client
+        .get_scheduling_object("calendars/test/default/e.ics")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        object,
+        SchedulingObject {
+            calendar_data: EVENT.to_string(),
+            etag: Some("\"e-1\"".to_string()),
+            schedule_tag: Some("\"s-1\"".to_string()),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_put_if_schedule_tag_match_returns_new_tags() {
+    let (base, server) = serve_once(
+        "HTTP/1.1 204 No Content\r\nETag: \"e-2\"\r\nSchedule-Tag: \"s-1\"\r\nConnection: close\r\n\r\n",
+        b"END:VCALENDAR\r\n",
+    )
+    .await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let written = client
+        .put_if_schedule_tag_match(
+            "calendars/test/default/e.ics",
+            Bytes::from(EVENT),
+            "\"s-1\"",
+        )
+        .await
+        .unwrap();
+    let request = String::from_utf8(server.await.unwrap()).unwrap();
+
+    assert!(
+        request
+            .to_ascii_lowercase()
+            .contains("if-schedule-tag-match: \"s-1\"\r\n")
+    );
+    assert_eq!(
+        written,
+        PutResult {
+            etag: Some("\"e-2\"".to_string()),
+            schedule_tag: Some("\"s-1\"".to_string()),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_put_if_schedule_tag_match_fails_on_organizer_change() {
+    let (base, _server) = serve_once(
+        "HTTP/1.1 412 Precondition Failed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        b"END:VCALENDAR\r\n",
+    )
+    .await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let err = client
+        .put_if_schedule_tag_match(
+            "calendars/test/default/e.ics",
+            Bytes::from(EVENT),
+            "\"s-0\"",
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("412"));
+}
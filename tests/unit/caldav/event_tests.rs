@@ -0,0 +1,114 @@
+use fast_dav_rs::caldav::{
+    EventBuilder, Frequency, RecurrenceRule, Weekday, WeekdayNum, parse_icalendar,
+};
+use fast_dav_rs::common::contentline::ContentLine;
+
+#[test]
+fn builds_parseable_event() {
+    let ics = EventBuilder::new("Planning, Q1; draft")
+        .uid("plan-1@example.com")
+        .start("20240105T090000Z")
+        .end("20240105T100000Z")
+        .description("Line one\nLine two")
+        .location("Room 4")
+        .status("confirmed")
+        .category("Work")
+        .category("Q1")
+        .organizer("boss@example.com", Some("Boss, The"))
+        .attendee("mailto:jane@example.com", None)
+        .alarm("-PT15M", "Starts soon")
+        .property(ContentLine::new("X-CUSTOM", "1"))
+        .build()
+        .unwrap();
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//fast-dav-rs//EN\r\n"));
+    assert!(ics.contains("DTSTAMP:"));
+    assert!(ics.contains("SUMMARY:Planning\\, Q1\\; draft\r\n"));
+    assert!(ics.contains("ORGANIZER;CN=\"Boss, The\":mailto:boss@example.com\r\n"));
+    assert!(ics.contains("X-CUSTOM:1\r\n"));
+
+    let calendar = parse_icalendar(&ics).unwrap();
+    let event = &calendar.components[0];
+    assert_eq!(event.uid.as_deref(), Some("plan-1@example.com"));
+    assert_eq!(event.summary.as_deref(), Some("Planning, Q1; draft"));
+    assert_eq!(event.description.as_deref(), Some("Line one\nLine two"));
+    assert_eq!(event.status.as_deref(), Some("CONFIRMED"));
+    assert_eq!(event.categories, ["Work", "Q1"]);
+    assert_eq!(event.attendees[0].address, "mailto:jane@example.com");
+    assert!(event.attendees[0].rsvp);
+    assert_eq!(event.alarms[0].action.as_deref(), Some("DISPLAY"));
+}
+
+#[test]
+fn folds_long_lines_and_generates_uids() {
+    let description = "x".repeat(200);
+    let first = EventBuilder::new("Long")
+        .start("20240105")
+        .description(&description);
+    let second = EventBuilder::new("Long").start("20240105");
+    let ics = first.build().unwrap();
+
+    assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20240105\r\n"));
+    assert_ne!(first.resource_name(), second.resource_name());
+    assert!(first.resource_name().ends_with("_fast-dav-rs.ics"));
+    let parsed = parse_icalendar(&ics).unwrap();
+    assert_eq!(
+        parsed.components[0].description.as_deref(),
+        Some(&*description)
+    );
+    assert!(first.build_bytes().unwrap().starts_with(b"BEGIN:VCALENDAR"));
+}
+
+#[test]
+fn applies_tzid_and_recurrence() {
+    let mut rule = RecurrenceRule::new(Frequency::Weekly);
+    rule.count = Some(4);
+    rule.by_day = vec![WeekdayNum {
+        ordinal: None,
+        weekday: Weekday::Tuesday,
+    }];
+    let ics = EventBuilder::new("Standup")
+        .all_day("20240101", "20240102")
+        .recurrence(&rule)
+        .exdate("20240108")
+        .build()
+        .unwrap();
+    assert!(ics.contains("RRULE:FREQ=WEEKLY;COUNT=4;BYDAY=TU\r\n"));
+    assert!(ics.contains("EXDATE;VALUE=DATE:20240108\r\n"));
+
+    let ics = EventBuilder::new("Standup")
+        .start("20240101T090000")
+        .duration("PT15M")
+        .tzid("Europe/Paris")
+        .rdate("20240103T090000")
+        .build()
+        .unwrap();
+    assert!(ics.contains("RDATE;TZID=Europe/Paris:20240103T090000\r\n"));
+    assert!(ics.contains("DURATION:PT15M\r\n"));
+}
+
+#[test]
+fn rejects_invalid_events() {
+    let base = || EventBuilder::new("Bad").start("20240101T090000Z");
+    assert!(EventBuilder::new("Bad").build().is_err());
+    assert!(
+        EventBuilder::new("Bad")
+            .start("2024-01-01")
+            .build()
+            .is_err()
+    );
+    assert!(base().end("20240101T080000Z").build().is_err());
+    assert!(base().end("20240101").build().is_err());
+    assert!(
+        base()
+            .end("20240101T100000Z")
+            .duration("PT1H")
+            .build()
+            .is_err()
+    );
+    assert!(base().duration("1 hour").build().is_err());
+    assert!(base().tzid("Europe/Paris").build().is_err());
+    assert!(base().rrule("COUNT=3").build().is_err());
+    assert!(base().alarm("soon", "x").build().is_err());
+}
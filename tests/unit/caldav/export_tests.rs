@@ -0,0 +1,81 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{CalendarMerger, merge_calendars};
+use fast_dav_rs::common::contentline::Component;
+
+const PARIS: &str = "BEGIN:VTIMEZONE\r\nTZID:Europe/Paris\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n";
+
+fn object(uid: &str, timezone: Option<&str>) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Vendor {uid}//EN\r\nMETHOD:PUBLISH\r\n{}BEGIN:VEVENT\r\nUID:{uid}\r\nDTSTART;TZID=Europe/Paris:20240105T090000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        timezone.unwrap_or_default()
+    )
+}
+
+#[test]
+fn merges_objects_into_one_calendar() {
+    let ics = merge_calendars([
+        object("a", Some(PARIS)),
+        object("b", Some(PARIS)),
+        object("c", None),
+    ])
+    .unwrap();
+
+    let calendar = Component::parse(&ics).unwrap();
+    assert_eq!(calendar.name, "VCALENDAR");
+    assert_eq!(calendar.properties_named("VERSION").count(), 1);
+    assert_eq!(calendar.value("PRODID"), Some("-//fast-dav-rs//EN"));
+    assert!(calendar.property("METHOD").is_none());
+    assert_eq!(calendar.components_named("VTIMEZONE").count(), 1);
+    let uids: Vec<_> = calendar
+        .components_named("VEVENT")
+        .filter_map(|e| e.value("UID"))
+        .collect();
+    assert_eq!(uids, ["a", "b", "c"]);
+    assert!(ics.find("BEGIN:VTIMEZONE") < ics.find("BEGIN:VEVENT"));
+}
+
+#[test]
+fn keeps_recurrence_overrides_and_distinct_timezones() {
+    let mut merger = CalendarMerger::new();
+    let series = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:s\r\nRRULE:FREQ=DAILY\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:s\r\nRECURRENCE-ID:20240102T090000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let out = merger.push(series).unwrap();
+    assert_eq!(out.matches("BEGIN:VEVENT").count(), 2);
+
+    let berlin = PARIS.replace("Europe/Paris", "Europe/Berlin");
+    assert!(
+        merger
+            .push(&object("a", Some(PARIS)))
+            .unwrap()
+            .contains("TZID:Europe/Paris")
+    );
+    let out = merger.push(&object("b", Some(&berlin))).unwrap();
+    assert!(out.contains("TZID:Europe/Berlin"));
+    assert!(
+        !merger
+            .push(&object("c", Some(PARIS)))
+            .unwrap()
+            .contains("VTIMEZONE")
+    );
+}
+
+#[test]
+fn rejects_non_calendar_objects() {
+    let mut merger = CalendarMerger::new();
+    assert!(
+        merger
+            .push("BEGIN:VCARD\r\nFN:Jane\r\nEND:VCARD\r\n")
+            .is_err()
+    );
+    assert!(merger.push("BEGIN:VCALENDAR\r\n").is_err());
+}
+
+#[tokio::test]
+async fn export_calendar_fails_when_listing_fails() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    assert!(
+        client
+            .export_calendar("/calendars/jane/work/")
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,75 @@
+use fast_dav_rs::CalendarObject;
+use fast_dav_rs::caldav::{AlarmTrigger, parse_icalendar};
+
+const MEETING: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Paris\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\nUID:weekly@example.com\r\nDTSTAMP:20240101T000000Z\r\nDTSTART;TZID=Europe/Paris:20240108T100000\r\nDTEND;TZID=Europe/Paris:20240108T103000\r\nSUMMARY:Weekly sync\\, team\r\nLOCATION:Room 1\r\nRRULE:FREQ=WEEKLY;BYDAY=MO\r\nEXDATE;TZID=Europe/Paris:20240115T100000,20240122T100000\r\nSEQUENCE:2\r\nCATEGORIES:work,meeting\r\nORGANIZER;CN=Jane:mailto:jane@example.com\r\nATTENDEE;CN=Bob;PARTSTAT=ACCEPTED;ROLE=REQ-PARTICIPANT;RSVP=TRUE:mailto:bob@example.com\r\nATTENDEE:mailto:eve@example.com\r\nBEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER;RELATED=END:-PT5M\r\nDESCRIPTION:Wrap up\r\nEND:VALARM\r\nBEGIN:VALARM\r\nACTION:AUDIO\r\nTRIGGER;VALUE=DATE-TIME:20240108T084500Z\r\nEND:VALARM\r\nEND:VEVENT\r\nBEGIN:VTODO\r\nUID:todo@example.com\r\nDUE;VALUE=DATE:20240110\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+#[test]
+fn parses_event_properties() {
+    let cal = parse_icalendar(MEETING).unwrap();
+    assert_eq!(cal.prodid.as_deref(), Some("-//test//EN"));
+    assert_eq!(cal.timezones.len(), 1);
+    assert_eq!(cal.components.len(), 2);
+
+    let event = &cal.components[0];
+    assert_eq!(event.kind, "VEVENT");
+    assert_eq!(event.uid.as_deref(), Some("weekly@example.com"));
+    assert_eq!(event.summary.as_deref(), Some("Weekly sync, team"));
+    let start = event.dtstart.as_ref().unwrap();
+    assert_eq!(start.value, "20240108T100000");
+    assert_eq!(start.tzid.as_deref(), Some("Europe/Paris"));
+    assert!(!start.is_utc() && !start.is_floating() && !start.is_date());
+    assert_eq!(event.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO"));
+    assert_eq!(event.exdates.len(), 2);
+    assert_eq!(event.exdates[1].tzid.as_deref(), Some("Europe/Paris"));
+    assert_eq!(event.sequence, Some(2));
+    assert_eq!(event.categories, vec!["work", "meeting"]);
+}
+
+#[test]
+fn parses_participants_and_alarms() {
+    let cal = parse_icalendar(MEETING).unwrap();
+    let event = &cal.components[0];
+    let organizer = event.organizer.as_ref().unwrap();
+    assert_eq!(organizer.address, "mailto:jane@example.com");
+    assert_eq!(organizer.common_name.as_deref(), Some("Jane"));
+    assert_eq!(event.attendees.len(), 2);
+    assert_eq!(event.attendees[0].partstat.as_deref(), Some("ACCEPTED"));
+    assert!(event.attendees[0].rsvp);
+    assert_eq!(event.attendees[1].partstat, None);
+
+    assert_eq!(
+        event.alarms[0].trigger,
+        Some(AlarmTrigger::Relative {
+            duration: "-PT5M".into(),
+            related_end: true
+        })
+    );
+    assert!(matches!(
+        &event.alarms[1].trigger,
+        Some(AlarmTrigger::Absolute(at)) if at.is_utc()
+    ));
+
+    let todo = &cal.components[1];
+    assert_eq!(todo.kind, "VTODO");
+    assert!(todo.due.as_ref().unwrap().is_date());
+}
+
+#[test]
+fn calendar_object_parse_ical_requires_data() {
+    let mut object = CalendarObject {
+        href: "/cal/a.ics".into(),
+        etag: None,
+        calendar_data: None,
+        status: None,
+    };
+    assert!(
+        object
+            .parse_ical()
+            .unwrap_err()
+            .to_string()
+            .contains("no calendar-data")
+    );
+    object.calendar_data = Some(MEETING.into());
+    assert_eq!(object.parse_ical().unwrap().components.len(), 2);
+    assert!(parse_icalendar("BEGIN:VCARD\r\nEND:VCARD\r\n").is_err());
+}
@@ -0,0 +1,120 @@
+use fast_dav_rs::caldav::{delete_instance, update_instance};
+use fast_dav_rs::common::contentline::Component;
+
+const WEEKLY: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:weekly@example.com\r\n\
+DTSTAMP:20231201T000000Z\r\nDTSTART;TZID=Europe/Paris:20240101T090000\r\n\
+DTEND;TZID=Europe/Paris:20240101T093000\r\nRRULE:FREQ=WEEKLY;COUNT=10\r\n\
+EXDATE;TZID=Europe/Paris:20240115T090000\r\nSUMMARY:Sync\r\n\
+BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT10M\r\nDESCRIPTION:Sync\r\nEND:VALARM\r\n\
+END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+fn events(ics: &str) -> Vec<Component> {
+    Component::parse(ics)
+        .unwrap()
+        .components
+        .into_iter()
+        .filter(|c| c.name == "VEVENT")
+        .collect()
+}
+
+#[test]
+fn creates_override_from_master() {
+    let updated = update_instance(WEEKLY, "20240108T090000", |instance| {
+        instance.set_value("SUMMARY", "Sync (Room 2)");
+        Ok(())
+    })
+    .unwrap();
+    let events = events(&updated);
+    assert_eq!(events.len(), 2);
+
+    let master = &events[0];
+    assert_eq!(master.value("SUMMARY"), Some("Sync"));
+    assert!(master.property("RRULE").is_some());
+
+    let instance = &events[1];
+    let recurrence_id = instance.property("RECURRENCE-ID").unwrap();
+    assert_eq!(recurrence_id.value, "20240108T090000");
+    assert_eq!(recurrence_id.param("TZID"), Some("Europe/Paris"));
+    assert_eq!(instance.value("DTSTART"), Some("20240108T090000"));
+    assert_eq!(instance.value("DTEND"), Some("20240108T093000"));
+    assert_eq!(instance.value("SUMMARY"), Some("Sync (Room 2)"));
+    assert_eq!(instance.value("UID"), Some("weekly@example.com"));
+    assert!(instance.property("RRULE").is_none());
+    assert!(instance.property("EXDATE").is_none());
+    assert_eq!(instance.components_named("VALARM").count(), 1);
+    assert_ne!(instance.value("DTSTAMP"), Some("20231201T000000Z"));
+}
+
+#[test]
+fn updates_existing_override() {
+    let once = update_instance(WEEKLY, "20240108T090000", |instance| {
+        instance.set_value("SUMMARY", "First");
+        Ok(())
+    })
+    .unwrap();
+    let twice = update_instance(&once, "20240108T090000", |instance| {
+        instance.property_mut("DTSTART").unwrap().value = "20240108T100000".into();
+        instance.set_value("SEQUENCE", "1");
+        Ok(())
+    })
+    .unwrap();
+    let events = events(&twice);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].value("SUMMARY"), Some("First"));
+    assert_eq!(events[1].value("SEQUENCE"), Some("1"));
+    assert_eq!(events[1].value("DTSTART"), Some("20240108T100000"));
+}
+
+#[test]
+fn utc_and_all_day_masters() {
+    let utc = WEEKLY
+        .replace(
+            "DTSTART;TZID=Europe/Paris:20240101T090000",
+            "DTSTART:20240101T080000Z",
+        )
+        .replace(
+            "DTEND;TZID=Europe/Paris:20240101T093000",
+            "DTEND:20240101T083000Z",
+        );
+    let updated = update_instance(&utc, "20240108T080000", |_| Ok(())).unwrap();
+    assert!(updated.contains("RECURRENCE-ID:20240108T080000Z\r\n"));
+    assert!(updated.contains("DTEND:20240108T083000Z\r\n"));
+
+    let all_day = WEEKLY
+        .replace(
+            "DTSTART;TZID=Europe/Paris:20240101T090000",
+            "DTSTART;VALUE=DATE:20240101",
+        )
+        .replace(
+            "DTEND;TZID=Europe/Paris:20240101T093000",
+            "DTEND;VALUE=DATE:20240102",
+        );
+    let deleted = delete_instance(&all_day, "20240108").unwrap();
+    assert!(deleted.contains("EXDATE;VALUE=DATE:20240108\r\n"));
+    assert!(delete_instance(&all_day, "20240108T090000").is_err());
+}
+
+#[test]
+fn delete_adds_exdate_and_drops_override() {
+    let updated = update_instance(WEEKLY, "20240108T090000", |_| Ok(())).unwrap();
+    let deleted = delete_instance(&updated, "20240108T090000").unwrap();
+    let events = events(&deleted);
+    assert_eq!(events.len(), 1);
+    let exdates: Vec<_> = events[0]
+        .properties_named("EXDATE")
+        .map(|p| p.value.as_str())
+        .collect();
+    assert_eq!(exdates, vec!["20240115T090000", "20240108T090000"]);
+
+    // Deleting twice does not duplicate the EXDATE.
+    let again = delete_instance(&deleted, "20240108T090000").unwrap();
+    assert_eq!(again.matches("EXDATE").count(), 2);
+}
+
+#[test]
+fn rejects_non_recurring_objects() {
+    let single = WEEKLY.replace("RRULE:FREQ=WEEKLY;COUNT=10\r\n", "");
+    assert!(update_instance(&single, "20240108T090000", |_| Ok(())).is_err());
+    assert!(delete_instance(&single, "20240108T090000").is_err());
+    assert!(update_instance(WEEKLY, "next monday", |_| Ok(())).is_err());
+}
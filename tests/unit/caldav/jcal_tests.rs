@@ -0,0 +1,81 @@
+use fast_dav_rs::caldav::{
+    CalendarData, CalendarObject, jcal_to_component, jcal_to_ics, parse_jcal,
+};
+
+// RFC 7265 §3.7 style document with the common value types.
+const JCAL: &str = r#"["vcalendar",
+  [["version", {}, "text", "2.0"], ["prodid", {}, "text", "-//Example//EN"]],
+  [
+    ["vtimezone", [["tzid", {}, "text", "Europe/Paris"]],
+      [["standard", [
+        ["dtstart", {}, "date-time", "1970-10-25T03:00:00"],
+        ["tzoffsetfrom", {}, "utc-offset", "+02:00"],
+        ["tzoffsetto", {}, "utc-offset", "+01:00"]
+      ], []]]],
+    ["vevent", [
+      ["uid", {}, "text", "jcal-1@example.com"],
+      ["dtstamp", {}, "date-time", "2024-01-01T00:00:00Z"],
+      ["dtstart", {"tzid": "Europe/Paris"}, "date-time", "2024-01-05T09:00:00"],
+      ["duration", {}, "duration", "PT1H"],
+      ["summary", {}, "text", "Planning, Q1; draft"],
+      ["categories", {}, "text", "Work", "Q1"],
+      ["rrule", {}, "recur", {"byday": ["MO", "FR"], "freq": "WEEKLY", "until": "2024-03-01T00:00:00Z"}],
+      ["exdate", {}, "date", "2024-01-08"],
+      ["attendee", {"partstat": "ACCEPTED", "cn": "Doe, Jane"}, "cal-address", "mailto:jane@example.com"]
+    ], [
+      ["valarm", [["action", {}, "text", "DISPLAY"], ["trigger", {}, "duration", "-PT15M"]], []]
+    ]]
+  ]
+]"#;
+
+#[test]
+fn converts_jcal_to_icalendar_text() {
+    let ics = jcal_to_ics(JCAL).unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.contains("DTSTART;TZID=Europe/Paris:20240105T090000\r\n"));
+    assert!(ics.contains("TZOFFSETFROM:+0200\r\n"));
+    assert!(ics.contains("SUMMARY:Planning\\, Q1\\; draft\r\n"));
+    assert!(ics.contains("CATEGORIES:Work,Q1\r\n"));
+    assert!(ics.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20240301T000000Z\r\n"));
+    assert!(ics.contains("EXDATE;VALUE=DATE:20240108\r\n"));
+    assert!(ics.contains("CN=\"Doe, Jane\""));
+
+    let root = jcal_to_component(JCAL).unwrap();
+    assert_eq!(root.components_named("VTIMEZONE").count(), 1);
+}
+
+#[test]
+fn parses_jcal_into_typed_model() {
+    let calendar = parse_jcal(JCAL).unwrap();
+    assert_eq!(calendar.prodid.as_deref(), Some("-//Example//EN"));
+    let event = &calendar.components[0];
+    assert_eq!(event.uid.as_deref(), Some("jcal-1@example.com"));
+    assert_eq!(event.summary.as_deref(), Some("Planning, Q1; draft"));
+    assert_eq!(event.categories, ["Work", "Q1"]);
+    assert_eq!(event.attendees[0].address, "mailto:jane@example.com");
+    assert_eq!(event.alarms.len(), 1);
+    assert!(event.rrule.as_deref().unwrap().starts_with("FREQ=WEEKLY;"));
+
+    let object = CalendarObject {
+        href: "/cal/jcal-1.json".into(),
+        etag: None,
+        calendar_data: Some(JCAL.to_string()),
+        status: None,
+    };
+    assert_eq!(object.parse_ical().unwrap(), calendar);
+}
+
+#[test]
+fn rejects_malformed_jcal() {
+    assert!(parse_jcal("not json").is_err());
+    assert!(parse_jcal(r#"["vcalendar", []]"#).is_err());
+    assert!(parse_jcal(r#"["vcalendar", [["uid", {}]], []]"#).is_err());
+}
+
+#[test]
+fn requests_jcal_calendar_data() {
+    assert_eq!(
+        CalendarData::new().json().to_xml(),
+        r#"<C:calendar-data content-type="application/calendar+json" version="2.0"/>"#
+    );
+}
@@ -0,0 +1,85 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{
+    CalendarObject, build_calendar_query_body_filtered, journals_filter, map_journals,
+    parse_journals,
+};
+use fast_dav_rs::common::contentline::split_text_list;
+
+const NOTE: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Nextcloud Notes//EN\r\nBEGIN:VJOURNAL\r\nUID:note-1\r\nDTSTAMP:20240301T080000Z\r\nDTSTART;VALUE=DATE:20240301\r\nSUMMARY:Standup\\, day 1\r\nDESCRIPTION:First line\\nSecond line\r\nDESCRIPTION:Addendum\r\nCATEGORIES:work,team\\,core\r\nCATEGORIES:daily\r\nSTATUS:FINAL\r\nEND:VJOURNAL\r\nEND:VCALENDAR\r\n";
+
+#[test]
+fn journals_filter_targets_vjournal_with_time_range() {
+    let body = build_calendar_query_body_filtered(
+        &journals_filter(Some("20240301T000000Z"), Some("20240401T000000Z")),
+        None,
+    );
+    assert!(body.contains(
+        r#"<C:comp-filter name="VJOURNAL"><C:time-range start="20240301T000000Z" end="20240401T000000Z"/></C:comp-filter>"#
+    ));
+    assert!(
+        journals_filter(Some("2024-03-01"), None)
+            .validate()
+            .is_err()
+    );
+}
+
+#[test]
+fn parses_vjournal_into_typed_entry() {
+    let journals = parse_journals("/cal/notes/note-1.ics", Some("\"1\""), NOTE).unwrap();
+    assert_eq!(journals.len(), 1);
+    let note = &journals[0];
+    assert_eq!(note.href, "/cal/notes/note-1.ics");
+    assert_eq!(note.etag.as_deref(), Some("\"1\""));
+    assert_eq!(note.uid.as_deref(), Some("note-1"));
+    assert_eq!(note.summary.as_deref(), Some("Standup, day 1"));
+    assert_eq!(
+        note.description.as_deref(),
+        Some("First line\nSecond line\n\nAddendum")
+    );
+    assert_eq!(note.dtstart.as_deref(), Some("20240301"));
+    assert_eq!(note.status.as_deref(), Some("FINAL"));
+    assert_eq!(note.categories, vec!["work", "team,core", "daily"]);
+}
+
+#[test]
+fn map_journals_skips_objects_without_journals() {
+    let objects = vec![
+        CalendarObject {
+            href: "a.ics".into(),
+            etag: None,
+            calendar_data: Some(NOTE.into()),
+            status: None,
+        },
+        CalendarObject {
+            href: "b.ics".into(),
+            etag: None,
+            calendar_data: None,
+            status: None,
+        },
+        CalendarObject {
+            href: "c.ics".into(),
+            etag: None,
+            calendar_data: Some("garbage".into()),
+            status: None,
+        },
+    ];
+    let journals = map_journals(objects);
+    assert_eq!(journals.len(), 1);
+    assert_eq!(journals[0].href, "a.ics");
+}
+
+#[test]
+fn split_text_list_honours_escaped_commas() {
+    assert_eq!(split_text_list(r"a,b\,c,d\\"), vec!["a", "b,c", "d\\"]);
+    assert_eq!(split_text_list(""), vec![""]);
+}
+
+#[tokio::test]
+async fn journals_validates_range_before_network() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .journals("calendars/test/notes/", Some("yesterday"), None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid comp-filter start"));
+}
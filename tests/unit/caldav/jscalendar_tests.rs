@@ -0,0 +1,210 @@
+use fast_dav_rs::caldav::{
+    CalendarObject, JsCalendarObject, JsEvent, JsTask, JsTrigger, parse_icalendar,
+};
+
+const EVENT: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\n\
+UID:js-1@example.com\r\nDTSTAMP:20240101T000000Z\r\n\
+DTSTART;TZID=Europe/Paris:20240105T090000\r\nDTEND;TZID=Europe/Paris:20240105T103000\r\n\
+SUMMARY:Planning\\, Q1\r\nLOCATION:Room 1\r\nSTATUS:CONFIRMED\r\nCATEGORIES:Work,Q1\r\n\
+SEQUENCE:2\r\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,-1FR;UNTIL=20240301T000000Z\r\n\
+EXDATE;TZID=Europe/Paris:20240115T090000\r\n\
+ORGANIZER;CN=Jane:mailto:jane@example.com\r\n\
+ATTENDEE;CN=Jane;PARTSTAT=ACCEPTED;ROLE=CHAIR:mailto:jane@example.com\r\n\
+ATTENDEE;PARTSTAT=TENTATIVE;ROLE=OPT-PARTICIPANT;RSVP=TRUE:mailto:bob@example.com\r\n\
+BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nDESCRIPTION:Soon\r\nEND:VALARM\r\n\
+END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+const TODO: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\n\
+UID:task-1@example.com\r\nDTSTAMP:20240101T000000Z\r\nDUE;VALUE=DATE:20240110\r\n\
+SUMMARY:File taxes\r\nSTATUS:IN-PROCESS\r\n\
+BEGIN:VALARM\r\nACTION:EMAIL\r\nTRIGGER;VALUE=DATE-TIME:20240109T080000Z\r\nEND:VALARM\r\n\
+END:VTODO\r\nEND:VCALENDAR\r\n";
+
+fn event() -> JsEvent {
+    let calendar = parse_icalendar(EVENT).unwrap();
+    JsEvent::from_ical(&calendar.components[0]).unwrap()
+}
+
+#[test]
+fn converts_vevent_to_event() {
+    let event = event();
+    assert_eq!(event.uid, "js-1@example.com");
+    assert_eq!(event.title, "Planning, Q1");
+    assert_eq!(event.start, "2024-01-05T09:00:00");
+    assert_eq!(event.time_zone.as_deref(), Some("Europe/Paris"));
+    assert_eq!(event.duration.as_deref(), Some("PT1H30M"));
+    assert!(!event.show_without_time);
+    assert_eq!(event.status.as_deref(), Some("confirmed"));
+    assert_eq!(event.keywords, vec!["Work", "Q1"]);
+    assert_eq!(event.recurrence.excluded, vec!["2024-01-15T09:00:00"]);
+
+    // The organizer attending the event is a single participant.
+    assert_eq!(event.participants.len(), 2);
+    assert_eq!(
+        event.participants[0].roles,
+        vec!["owner", "attendee", "chair"]
+    );
+    assert_eq!(
+        event.participants[1].participation_status.as_deref(),
+        Some("tentative")
+    );
+    assert!(event.participants[1].expect_reply);
+    assert_eq!(
+        event.alerts[0].trigger,
+        JsTrigger::Offset {
+            offset: "-PT15M".into(),
+            relative_to_end: false
+        }
+    );
+}
+
+#[test]
+fn event_json_follows_rfc_8984() {
+    let json = event().to_json();
+    assert_eq!(json["@type"], "Event");
+    assert_eq!(json["timeZone"], "Europe/Paris");
+    assert_eq!(json["locations"]["1"]["name"], "Room 1");
+    assert_eq!(json["keywords"]["Q1"], true);
+    assert_eq!(json["replyTo"]["imip"], "mailto:jane@example.com");
+    assert_eq!(json["participants"]["p1"]["email"], "jane@example.com");
+    assert_eq!(json["participants"]["p2"]["roles"]["optional"], true);
+
+    let rule = &json["recurrenceRules"][0];
+    assert_eq!(rule["frequency"], "weekly");
+    assert_eq!(rule["interval"], 2);
+    assert_eq!(rule["until"], "2024-03-01T00:00:00");
+    assert_eq!(rule["byDay"][1]["day"], "fr");
+    assert_eq!(rule["byDay"][1]["nthOfPeriod"], -1);
+    assert_eq!(
+        json["recurrenceOverrides"]["2024-01-15T09:00:00"]["excluded"],
+        true
+    );
+    assert_eq!(json["alerts"]["a1"]["trigger"]["relativeTo"], "start");
+}
+
+#[test]
+fn event_round_trips_through_json() {
+    // Keywords and roles are sets in JSCalendar, so only their order may change.
+    let normalize = |mut event: JsEvent| {
+        event.keywords.sort();
+        for participant in &mut event.participants {
+            participant.roles.sort();
+        }
+        event
+    };
+    let event = event();
+    let parsed = JsEvent::from_json(&event.to_json()).unwrap();
+    assert_eq!(normalize(parsed), normalize(event));
+}
+
+#[test]
+fn event_serializes_back_to_icalendar() {
+    let ics = event().to_ical().unwrap();
+    assert!(ics.contains("DTSTART;TZID=Europe/Paris:20240105T090000\r\n"));
+    assert!(ics.contains("DURATION:PT1H30M\r\n"));
+    assert!(ics.contains("SUMMARY:Planning\\, Q1\r\n"));
+    assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+    assert!(ics.contains("RRULE:FREQ=WEEKLY;"));
+    assert!(ics.contains("EXDATE;TZID=Europe/Paris:20240115T090000\r\n"));
+    assert!(ics.contains("ORGANIZER;CN=Jane:mailto:jane@example.com\r\n"));
+    assert!(ics.contains("ROLE=OPT-PARTICIPANT"));
+
+    let reparsed = parse_icalendar(&ics).unwrap();
+    assert_eq!(
+        JsEvent::from_ical(&reparsed.components[0]).unwrap(),
+        event()
+    );
+}
+
+#[test]
+fn all_day_and_utc_events() {
+    let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a\r\nDTSTART;VALUE=DATE:20240105\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:b\r\nDTSTART:20240105T090000Z\r\nDURATION:PT30M\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let calendar = parse_icalendar(ics).unwrap();
+
+    let all_day = JsEvent::from_ical(&calendar.components[0]).unwrap();
+    assert!(all_day.show_without_time);
+    assert_eq!(all_day.start, "2024-01-05T00:00:00");
+    assert_eq!(all_day.duration.as_deref(), Some("P1D"));
+    assert!(all_day.time_zone.is_none());
+    assert!(
+        all_day
+            .to_ical()
+            .unwrap()
+            .contains("DTSTART;VALUE=DATE:20240105\r\n")
+    );
+
+    let utc = JsEvent::from_ical(&calendar.components[1]).unwrap();
+    assert_eq!(utc.time_zone.as_deref(), Some("Etc/UTC"));
+    assert!(
+        utc.to_ical()
+            .unwrap()
+            .contains("DTSTART:20240105T090000Z\r\n")
+    );
+}
+
+#[test]
+fn converts_vtodo_to_task() {
+    let calendar = parse_icalendar(TODO).unwrap();
+    let JsCalendarObject::Task(task) =
+        JsCalendarObject::from_ical(&calendar.components[0]).unwrap()
+    else {
+        panic!("expected a task");
+    };
+    assert_eq!(task.due.as_deref(), Some("2024-01-10T00:00:00"));
+    assert!(task.show_without_time);
+    assert_eq!(task.progress.as_deref(), Some("in-process"));
+    assert_eq!(
+        task.alerts[0].trigger,
+        JsTrigger::Absolute("2024-01-09T08:00:00Z".into())
+    );
+    assert_eq!(task.alerts[0].action, "email");
+
+    let json = task.to_json();
+    assert_eq!(json["@type"], "Task");
+    assert_eq!(json["progress"], "in-process");
+    assert_eq!(JsTask::from_json(&json).unwrap(), task);
+
+    let ics = task.to_ical().unwrap();
+    assert!(ics.contains("BEGIN:VTODO\r\n"));
+    assert!(ics.contains("DUE;VALUE=DATE:20240110\r\n"));
+    assert!(ics.contains("STATUS:IN-PROCESS\r\n"));
+    assert!(ics.contains("TRIGGER;VALUE=DATE-TIME:20240109T080000Z\r\n"));
+}
+
+#[test]
+fn object_json_dispatches_on_type() {
+    let mut json = event().to_json();
+    let object = JsCalendarObject::from_json(&json).unwrap();
+    assert!(matches!(object, JsCalendarObject::Event(_)));
+    assert_eq!(object.uid(), "js-1@example.com");
+
+    json["@type"] = "Group".into();
+    assert!(JsCalendarObject::from_json(&json).is_err());
+}
+
+#[test]
+fn calendar_object_converts_events_and_tasks() {
+    let object = CalendarObject {
+        href: "/cal/js-1.ics".into(),
+        etag: None,
+        calendar_data: Some(EVENT.into()),
+        status: None,
+    };
+    let converted = object.to_jscalendar().unwrap();
+    assert_eq!(converted.len(), 1);
+    assert_eq!(converted[0].uid(), "js-1@example.com");
+}
+
+#[test]
+fn rejects_journals_and_invalid_dates() {
+    let calendar = parse_icalendar(
+        "BEGIN:VCALENDAR\r\nBEGIN:VJOURNAL\r\nUID:j\r\nEND:VJOURNAL\r\nEND:VCALENDAR\r\n",
+    )
+    .unwrap();
+    assert!(JsCalendarObject::from_ical(&calendar.components[0]).is_err());
+
+    let mut event = event();
+    event.start = "tomorrow".into();
+    assert!(event.to_ical().is_err());
+}
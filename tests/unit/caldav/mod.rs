@@ -1,8 +1,26 @@
+pub mod alarm_tests;
+pub mod availability_tests;
 pub mod caldav_helpers;
 pub mod client_tests;
+pub mod collection_tests;
 pub mod etag_tests;
+pub mod event_tests;
+pub mod export_tests;
+pub mod ical_tests;
+pub mod instance_tests;
 pub mod integration_tests;
+pub mod jcal_tests;
+pub mod journal_tests;
+pub mod jscalendar_tests;
 pub mod parser_edge_cases;
 pub mod parser_tests;
+pub mod query_tests;
+pub mod recurrence_tests;
+pub mod scheduling_tests;
+pub mod sharing_tests;
+pub mod sort_tests;
 pub mod streaming_tests;
+pub mod tasks_tests;
+pub mod timezone_tests;
+pub mod validate_tests;
 pub mod xml_helper_tests;
@@ -0,0 +1,363 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{
+    CalendarData, CompFilter, ComponentSelection, ParamFilter, PropFilter,
+    build_calendar_query_body, build_calendar_query_body_filtered, build_calendar_query_body_with,
+    uid_filter,
+};
+use fast_dav_rs::webdav::{MatchType, TextMatch};
+
+fn client() -> CalDavClient {
+    CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap()
+}
+
+#[test]
+fn calendar_data_defaults_to_full_object() {
+    assert_eq!(CalendarData::new().to_xml(), "<C:calendar-data/>");
+}
+
+#[test]
+fn body_with_expand_emits_expand_element() {
+    let data = CalendarData::new().expand("20240101T000000Z", "20240201T000000Z");
+    let body = build_calendar_query_body_with(
+        "VEVENT",
+        Some("20240101T000000Z"),
+        Some("20240201T000000Z"),
+        Some(&data),
+    );
+    assert!(body.contains(
+        r#"<C:calendar-data><C:expand start="20240101T000000Z" end="20240201T000000Z"/></C:calendar-data>"#
+    ));
+    assert!(body.contains(r#"<C:time-range start="20240101T000000Z" end="20240201T000000Z"/>"#));
+}
+
+#[test]
+fn body_without_data_matches_legacy_builder() {
+    assert_eq!(
+        build_calendar_query_body_with("VTODO", None, None, None),
+        build_calendar_query_body("VTODO", None, None, false)
+    );
+    assert_eq!(
+        build_calendar_query_body_with("VTODO", None, None, Some(&CalendarData::new())),
+        build_calendar_query_body("VTODO", None, None, true)
+    );
+}
+
+#[test]
+fn fluent_query_builds_expand_body() {
+    let client = client();
+    let body = client
+        .query("calendars/test/default/")
+        .time_range(Some("20240101T000000Z"), Some("20240201T000000Z"))
+        .expand("20240101T000000Z", "20240201T000000Z")
+        .build_body()
+        .unwrap();
+    assert!(body.contains(r#"<C:comp-filter name="VEVENT">"#));
+    assert!(body.contains("<C:expand "));
+}
+
+#[test]
+fn fluent_query_without_data_only_requests_etags() {
+    let client = client();
+    let body = client
+        .query("calendars/test/default/")
+        .component("VTODO")
+        .build_body()
+        .unwrap();
+    assert!(body.contains("<D:getetag/>"));
+    assert!(!body.contains("calendar-data"));
+}
+
+#[tokio::test]
+async fn fluent_query_validates_before_network() {
+    let client = client();
+    let err = client
+        .query("calendars/test/default/")
+        .expand("2024-01-01", "20240201T000000Z")
+        .execute()
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("invalid expand start"));
+
+    let err = client
+        .query("calendars/test/default/")
+        .component("VEVENT\"/>")
+        .build_body()
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid calendar-query component"));
+}
+
+#[test]
+fn calendar_data_limit_freebusy_set() {
+    let data = CalendarData::new().limit_freebusy_set("20240101T000000Z", "20240108T000000Z");
+    assert_eq!(
+        data.to_xml(),
+        r#"<C:calendar-data><C:limit-freebusy-set start="20240101T000000Z" end="20240108T000000Z"/></C:calendar-data>"#
+    );
+    assert!(
+        CalendarData::new()
+            .limit_freebusy_set("20240101", "20240108T000000Z")
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("invalid limit-freebusy-set start")
+    );
+}
+
+#[test]
+fn fluent_query_limit_freebusy_set_for_vfreebusy() {
+    let client = client();
+    let body = client
+        .query("calendars/test/availability/")
+        .component("VFREEBUSY")
+        .time_range(Some("20240101T000000Z"), Some("20240108T000000Z"))
+        .limit_freebusy_set("20240101T000000Z", "20240108T000000Z")
+        .build_body()
+        .unwrap();
+    assert!(body.contains(r#"<C:comp-filter name="VFREEBUSY">"#));
+    assert!(body.contains("<C:limit-freebusy-set "));
+}
+
+#[test]
+fn calendar_data_selects_components_and_properties() {
+    let data = CalendarData::new()
+        .select(
+            ComponentSelection::new("VEVENT")
+                .props(["UID", "DTSTART", "SUMMARY"])
+                .comp(ComponentSelection::new("VALARM").all_props()),
+        )
+        .select(ComponentSelection::new("VTIMEZONE").all());
+    assert_eq!(
+        data.to_xml(),
+        concat!(
+            r#"<C:calendar-data><C:comp name="VCALENDAR"><C:allprop/>"#,
+            r#"<C:comp name="VEVENT"><C:prop name="UID"/><C:prop name="DTSTART"/><C:prop name="SUMMARY"/>"#,
+            r#"<C:comp name="VALARM"><C:allprop/></C:comp></C:comp>"#,
+            r#"<C:comp name="VTIMEZONE"><C:allprop/><C:allcomp/></C:comp>"#,
+            r#"</C:comp></C:calendar-data>"#
+        )
+    );
+    assert!(data.validate().is_ok());
+}
+
+#[test]
+fn calendar_data_rejects_invalid_selection_names() {
+    let data = CalendarData::new().select(ComponentSelection::new("VEVENT").props(["SUMMARY\"/>"]));
+    assert!(
+        data.validate()
+            .unwrap_err()
+            .to_string()
+            .contains("invalid calendar-data property")
+    );
+}
+
+#[test]
+fn fluent_query_select_requests_partial_data() {
+    let client = client();
+    let body = client
+        .query("calendars/test/default/")
+        .select(ComponentSelection::new("VEVENT").props(["UID", "SUMMARY"]))
+        .build_body()
+        .unwrap();
+    assert!(body.contains(r#"<C:prop name="SUMMARY"/>"#));
+}
+
+#[test]
+fn fluent_query_sends_timezone_id_after_filter() {
+    let client = client();
+    let body = client
+        .query("calendars/test/default/")
+        .time_range(Some("20240101T000000Z"), Some("20240102T000000Z"))
+        .timezone_id("Europe/Paris")
+        .build_body()
+        .unwrap();
+    assert!(
+        body.ends_with("</C:filter><C:timezone-id>Europe/Paris</C:timezone-id></C:calendar-query>")
+    );
+}
+
+#[test]
+fn text_match_serializes_options() {
+    let filter = PropFilter::new("SUMMARY").text_match(
+        TextMatch::new("Stand & up")
+            .collation("i;octet")
+            .match_type(MatchType::StartsWith)
+            .negate(),
+    );
+    assert_eq!(
+        filter.to_xml(),
+        r#"<C:prop-filter name="SUMMARY"><C:text-match collation="i;octet" match-type="starts-with" negate-condition="yes">Stand &amp; up</C:text-match></C:prop-filter>"#
+    );
+}
+
+#[test]
+fn fluent_query_prop_filters_inside_component_filter() {
+    let client = client();
+    let body = client
+        .query("calendars/test/default/")
+        .time_range(Some("20240101T000000Z"), Some("20240201T000000Z"))
+        .prop_text_match("SUMMARY", "standup")
+        .prop_equals("STATUS", "CANCELLED")
+        .build_body()
+        .unwrap();
+    assert!(body.contains(concat!(
+        r#"<C:time-range start="20240101T000000Z" end="20240201T000000Z"/>"#,
+        r#"<C:prop-filter name="SUMMARY"><C:text-match>standup</C:text-match></C:prop-filter>"#,
+        r#"<C:prop-filter name="STATUS"><C:text-match match-type="equals">CANCELLED</C:text-match></C:prop-filter>"#,
+        r#"</C:comp-filter></C:comp-filter></C:filter>"#
+    )));
+}
+
+#[test]
+fn fluent_query_rejects_invalid_prop_filter_name() {
+    let client = client();
+    let err = client
+        .query("calendars/test/default/")
+        .prop_text_match("SUMMARY\"/>", "x")
+        .build_body()
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid prop-filter name"));
+}
+
+#[test]
+fn prop_filter_is_not_defined_overrides_matches() {
+    let filter = PropFilter::new("CATEGORIES")
+        .text_match(TextMatch::new("work"))
+        .is_not_defined();
+    assert_eq!(
+        filter.to_xml(),
+        r#"<C:prop-filter name="CATEGORIES"><C:is-not-defined/></C:prop-filter>"#
+    );
+    assert_eq!(
+        ParamFilter::new("RSVP").is_not_defined().to_xml(),
+        r#"<C:param-filter name="RSVP"><C:is-not-defined/></C:param-filter>"#
+    );
+}
+
+#[test]
+fn prop_filter_with_text_and_param_filters() {
+    let filter = PropFilter::new("ATTENDEE")
+        .text_match(TextMatch::new("mailto:jane@example.com"))
+        .param_filter(ParamFilter::new("PARTSTAT").text_match(TextMatch::new("NEEDS-ACTION")));
+    assert_eq!(
+        filter.to_xml(),
+        concat!(
+            r#"<C:prop-filter name="ATTENDEE"><C:text-match>mailto:jane@example.com</C:text-match>"#,
+            r#"<C:param-filter name="PARTSTAT"><C:text-match>NEEDS-ACTION</C:text-match></C:param-filter>"#,
+            r#"</C:prop-filter>"#
+        )
+    );
+}
+
+#[test]
+fn fluent_query_pending_invitations_and_uncategorised() {
+    let client = client();
+    let body = client
+        .query("calendars/test/default/")
+        .param_text_match("ATTENDEE", "PARTSTAT", "NEEDS-ACTION")
+        .prop_not_defined("CATEGORIES")
+        .build_body()
+        .unwrap();
+    assert!(body.contains(r#"<C:prop-filter name="ATTENDEE"><C:param-filter name="PARTSTAT">"#));
+    assert!(
+        body.contains(r#"<C:prop-filter name="CATEGORIES"><C:is-not-defined/></C:prop-filter>"#)
+    );
+
+    let err = client
+        .query("calendars/test/default/")
+        .param_text_match("ATTENDEE", "PART STAT", "x")
+        .build_body()
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid param-filter name"));
+}
+
+#[test]
+fn filtered_body_nests_component_filters() {
+    let filter = CompFilter::new("VEVENT")
+        .time_range(Some("20240101T000000Z"), None)
+        .prop_filter(PropFilter::new("SUMMARY").text_match(TextMatch::new("standup")))
+        .comp_filter(
+            CompFilter::new("VALARM")
+                .time_range(Some("20240101T000000Z"), Some("20240102T000000Z")),
+        );
+    let body = build_calendar_query_body_filtered(&filter, None);
+    assert!(body.contains(concat!(
+        r#"<C:filter><C:comp-filter name="VCALENDAR"><C:comp-filter name="VEVENT">"#,
+        r#"<C:time-range start="20240101T000000Z"/>"#,
+        r#"<C:prop-filter name="SUMMARY"><C:text-match>standup</C:text-match></C:prop-filter>"#,
+        r#"<C:comp-filter name="VALARM"><C:time-range start="20240101T000000Z" end="20240102T000000Z"/></C:comp-filter>"#,
+        r#"</C:comp-filter></C:comp-filter></C:filter>"#
+    )));
+    assert_eq!(
+        CompFilter::new("VALARM").is_not_defined().to_xml(),
+        r#"<C:comp-filter name="VALARM"><C:is-not-defined/></C:comp-filter>"#
+    );
+}
+
+#[test]
+fn fluent_query_several_components_build_one_body_each() {
+    let client = client();
+    let query = client
+        .query("calendars/test/default/")
+        .components(["VEVENT", "VTODO"])
+        .time_range(Some("20240101T000000Z"), Some("20240201T000000Z"))
+        .comp_filter(CompFilter::new("VALARM"));
+    let bodies = query.build_bodies().unwrap();
+    assert_eq!(bodies.len(), 2);
+    assert!(bodies[0].contains(r#"<C:comp-filter name="VEVENT"><C:time-range "#));
+    assert!(bodies[1].contains(r#"<C:comp-filter name="VTODO"><C:time-range "#));
+    assert!(
+        bodies
+            .iter()
+            .all(|b| b.contains(r#"<C:comp-filter name="VALARM"/>"#))
+    );
+    assert!(
+        query
+            .build_body()
+            .unwrap_err()
+            .to_string()
+            .contains("use build_bodies")
+    );
+}
+
+#[test]
+fn fluent_query_validates_nested_filters() {
+    let client = client();
+    let err = client
+        .query("calendars/test/default/")
+        .comp_filter(CompFilter::new("VALARM").time_range(Some("tomorrow"), None))
+        .build_body()
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid comp-filter start"));
+
+    let err = client
+        .query("calendars/test/default/")
+        .components(Vec::<String>::new())
+        .build_bodies()
+        .unwrap_err();
+    assert!(err.to_string().contains("at least one component"));
+}
+
+#[test]
+fn uid_filter_matches_exactly_and_escapes() {
+    let body = build_calendar_query_body_filtered(&uid_filter("VTODO", "a&b@example.com"), None);
+    assert!(body.contains(r#"<C:comp-filter name="VCALENDAR"><C:comp-filter name="VTODO">"#));
+    assert!(body.contains(
+        r#"<C:text-match collation="i;octet" match-type="equals">a&amp;b@example.com</C:text-match>"#
+    ));
+}
+
+#[tokio::test]
+async fn uid_helpers_reject_empty_uid_before_network() {
+    let client = client();
+    let err = client
+        .get_object_by_uid("calendars/test/work/", "")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("UID cannot be empty"));
+    assert!(
+        client
+            .delete_by_uid("calendars/test/work/", "")
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,164 @@
+use fast_dav_rs::caldav::{Frequency, RecurrenceRule, Weekday, expand_instances, parse_icalendar};
+
+fn calendar(body: &str) -> String {
+    format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n{body}END:VCALENDAR\r\n")
+}
+
+fn starts(ics: &str, start: &str, end: &str) -> Vec<String> {
+    expand_instances(&parse_icalendar(ics).unwrap(), start, end)
+        .unwrap()
+        .into_iter()
+        .map(|i| i.start.value)
+        .collect()
+}
+
+#[test]
+fn parses_and_serializes_rrule() {
+    let rule =
+        RecurrenceRule::parse("FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR,2MO;WKST=SU;COUNT=5").unwrap();
+    assert_eq!(rule.freq, Frequency::Monthly);
+    assert_eq!(rule.interval, 2);
+    assert_eq!(rule.by_day[0].ordinal, Some(-1));
+    assert_eq!(rule.by_day[1].weekday, Weekday::Monday);
+    assert_eq!(rule.week_start, Weekday::Sunday);
+    assert_eq!(
+        rule.to_string(),
+        "FREQ=MONTHLY;INTERVAL=2;COUNT=5;BYDAY=-1FR,2MO;WKST=SU"
+    );
+    assert!(RecurrenceRule::parse("INTERVAL=2").is_err());
+    assert!(RecurrenceRule::parse("FREQ=DAILY;BYHOUR=9").is_err());
+    assert!(RecurrenceRule::parse("FREQ=DAILY;COUNT=2;UNTIL=20240101").is_err());
+}
+
+#[test]
+fn expands_weekly_rule_with_exdate_inside_window() {
+    let ics = calendar(
+        "BEGIN:VEVENT\r\nUID:w\r\nDTSTART;TZID=Europe/Paris:20240101T100000\r\nDTEND;TZID=Europe/Paris:20240101T110000\r\nRRULE:FREQ=WEEKLY;BYDAY=MO,WE\r\nEXDATE;TZID=Europe/Paris:20240103T100000\r\nEND:VEVENT\r\n",
+    );
+    let instances = expand_instances(
+        &parse_icalendar(&ics).unwrap(),
+        "20240101T000000Z",
+        "20240109T000000Z",
+    )
+    .unwrap();
+    let values: Vec<&str> = instances.iter().map(|i| i.start.value.as_str()).collect();
+    assert_eq!(values, vec!["20240101T100000", "20240108T100000"]);
+    assert_eq!(instances[1].start.tzid.as_deref(), Some("Europe/Paris"));
+    assert_eq!(instances[1].end.as_ref().unwrap().value, "20240108T110000");
+}
+
+#[test]
+fn expands_monthly_by_day_ordinal_and_count() {
+    let ics = calendar(
+        "BEGIN:VEVENT\r\nUID:m\r\nDTSTART:20240126T090000Z\r\nRRULE:FREQ=MONTHLY;BYDAY=-1FR;COUNT=3\r\nEND:VEVENT\r\n",
+    );
+    assert_eq!(
+        starts(&ics, "20240101T000000Z", "20250101T000000Z"),
+        vec!["20240126T090000Z", "20240223T090000Z", "20240329T090000Z"]
+    );
+}
+
+#[test]
+fn monthly_by_month_day_skips_short_months_and_bysetpos() {
+    let ics = calendar(
+        "BEGIN:VEVENT\r\nUID:d\r\nDTSTART;VALUE=DATE:20240131\r\nRRULE:FREQ=MONTHLY;UNTIL=20240501\r\nEND:VEVENT\r\n",
+    );
+    assert_eq!(
+        starts(&ics, "20240101T000000Z", "20250101T000000Z"),
+        vec!["20240131", "20240331"]
+    );
+
+    let last_weekday = calendar(
+        "BEGIN:VEVENT\r\nUID:p\r\nDTSTART:20240131T120000\r\nRRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;COUNT=3\r\nEND:VEVENT\r\n",
+    );
+    assert_eq!(
+        starts(&last_weekday, "20240101T000000Z", "20250101T000000Z"),
+        vec!["20240131T120000", "20240229T120000", "20240329T120000"]
+    );
+}
+
+#[test]
+fn yearly_rule_and_rdate() {
+    let ics = calendar(
+        "BEGIN:VEVENT\r\nUID:y\r\nDTSTART;VALUE=DATE:20200229\r\nRRULE:FREQ=YEARLY\r\nRDATE;VALUE=DATE:20230301\r\nEND:VEVENT\r\n",
+    );
+    assert_eq!(
+        starts(&ics, "20200101T000000Z", "20250101T000000Z"),
+        vec!["20200229", "20230301", "20240229"]
+    );
+}
+
+#[test]
+fn overrides_replace_and_move_instances() {
+    let ics = calendar(
+        "BEGIN:VEVENT\r\nUID:o\r\nDTSTART:20240101T090000Z\r\nDURATION:PT1H\r\nRRULE:FREQ=DAILY;COUNT=5\r\nSUMMARY:Daily\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:o\r\nRECURRENCE-ID:20240102T090000Z\r\nDTSTART:20240102T150000Z\r\nDTEND:20240102T160000Z\r\nSUMMARY:Moved\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:o\r\nRECURRENCE-ID:20240110T090000Z\r\nDTSTART:20240103T200000Z\r\nSUMMARY:Pulled in\r\nEND:VEVENT\r\n",
+    );
+    let instances = expand_instances(
+        &parse_icalendar(&ics).unwrap(),
+        "20240101T000000Z",
+        "20240104T000000Z",
+    )
+    .unwrap();
+    let summary: Vec<(&str, bool)> = instances
+        .iter()
+        .map(|i| (i.component.summary.as_deref().unwrap(), i.is_override))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Daily", false),
+            ("Moved", true),
+            ("Daily", false),
+            ("Pulled in", true)
+        ]
+    );
+    assert_eq!(instances[1].recurrence_id.value, "20240102T090000Z");
+    assert_eq!(instances[1].end.as_ref().unwrap().value, "20240102T160000Z");
+}
+
+#[test]
+fn non_recurring_event_and_invalid_window() {
+    let ics = calendar(
+        "BEGIN:VEVENT\r\nUID:s\r\nDTSTART:20240105T090000Z\r\nDTEND:20240105T100000Z\r\nEND:VEVENT\r\n",
+    );
+    assert_eq!(
+        starts(&ics, "20240105T093000Z", "20240106T000000Z"),
+        vec!["20240105T090000Z"]
+    );
+    assert!(starts(&ics, "20240106T000000Z", "20240107T000000Z").is_empty());
+    assert!(
+        expand_instances(
+            &parse_icalendar(&ics).unwrap(),
+            "2024-01-01",
+            "20240107T000000Z"
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn biweekly_rule_respects_week_start() {
+    // RFC 5545 §3.3.10: WKST changes the result of INTERVAL=2 weekly rules.
+    let mo = calendar(
+        "BEGIN:VEVENT\r\nUID:a\r\nDTSTART:19970805T090000\r\nRRULE:FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=MO\r\nEND:VEVENT\r\n",
+    );
+    assert_eq!(
+        starts(&mo, "19970101T000000Z", "19980101T000000Z"),
+        vec![
+            "19970805T090000",
+            "19970810T090000",
+            "19970819T090000",
+            "19970824T090000"
+        ]
+    );
+    let su = mo.replace("WKST=MO", "WKST=SU");
+    assert_eq!(
+        starts(&su, "19970101T000000Z", "19980101T000000Z"),
+        vec![
+            "19970805T090000",
+            "19970817T090000",
+            "19970819T090000",
+            "19970831T090000"
+        ]
+    );
+}
@@ -0,0 +1,521 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{
+    AttendeeRole, FreeBusy, FreeBusyPeriod, FreeBusyType, ItipBuilder, ItipMethod, Partstat,
+    ScheduleUrls, add_attendee, build_free_busy_query_body, build_free_busy_request, itip_method,
+    map_schedule_urls, merge_busy_periods, parse_free_busy, parse_schedule_response,
+    remove_attendee, set_attendee_partstat,
+};
+use fast_dav_rs::common::contentline::Component;
+use fast_dav_rs::parse_multistatus_bytes;
+
+#[test]
+fn maps_schedule_inbox_and_outbox_urls() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/principals/test/</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:schedule-inbox-URL><D:href>/calendars/test/inbox/</D:href></C:schedule-inbox-URL>
+        <C:schedule-outbox-URL><D:href>/calendars/test/outbox/</D:href></C:schedule-outbox-URL>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    let urls = map_schedule_urls(parse_multistatus_bytes(xml.as_bytes()).unwrap().items);
+    assert_eq!(
+        urls,
+        ScheduleUrls {
+            inbox: Some("/calendars/test/inbox/".into()),
+            outbox: Some("/calendars/test/outbox/".into()),
+        }
+    );
+}
+
+#[test]
+fn schedule_urls_are_none_when_not_supported() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/principals/test/</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:schedule-inbox-URL/>
+        <C:schedule-outbox-URL/>
+      </D:prop>
+      <D:status>HTTP/1.1 404 Not Found</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    let urls = map_schedule_urls(parse_multistatus_bytes(xml.as_bytes()).unwrap().items);
+    assert_eq!(urls, ScheduleUrls::default());
+}
+
+#[test]
+fn builds_free_busy_query_body() {
+    let body = build_free_busy_query_body("20240101T000000Z", "20240102T000000Z");
+    assert!(body.starts_with("<C:free-busy-query"));
+    assert!(body.contains(r#"<C:time-range start="20240101T000000Z" end="20240102T000000Z"/>"#));
+}
+
+#[test]
+fn parses_free_busy_periods_with_types_and_durations() {
+    let ics = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+PRODID:-//Example//EN\r\n\
+BEGIN:VFREEBUSY\r\n\
+DTSTAMP:20240101T000000Z\r\n\
+DTSTART:20240101T000000Z\r\n\
+DTEND:20240102T000000Z\r\n\
+FREEBUSY:20240101T090000Z/20240101T100000Z,20240101T110000Z/PT30M\r\n\
+FREEBUSY;FBTYPE=BUSY-TENTATIVE:20240101T230000Z/PT2H\r\n\
+FREEBUSY;FBTYPE=FREE:20240101T120000Z/20240101T130000Z\r\n\
+END:VFREEBUSY\r\n\
+END:VCALENDAR\r\n";
+
+    let fb = parse_free_busy(ics).expect("valid VFREEBUSY");
+    assert_eq!(fb.start.as_deref(), Some("20240101T000000Z"));
+    assert_eq!(fb.end.as_deref(), Some("20240102T000000Z"));
+    assert_eq!(fb.periods.len(), 4);
+    assert_eq!(
+        fb.periods[1],
+        FreeBusyPeriod {
+            start: "20240101T110000Z".into(),
+            end: "20240101T113000Z".into(),
+            kind: FreeBusyType::Busy,
+        }
+    );
+    // Durations crossing midnight roll over the date.
+    assert_eq!(fb.periods[2].end, "20240102T010000Z");
+    assert_eq!(fb.periods[2].kind, FreeBusyType::BusyTentative);
+    assert_eq!(fb.busy().count(), 3);
+}
+
+fn period(start: &str, end: &str, kind: FreeBusyType) -> FreeBusyPeriod {
+    FreeBusyPeriod {
+        start: format!("20240101T{start}00Z"),
+        end: format!("20240101T{end}00Z"),
+        kind,
+    }
+}
+
+#[test]
+fn merges_busy_periods_across_calendars() {
+    let merged = merge_busy_periods([
+        period("1000", "1100", FreeBusyType::Busy),
+        period("0900", "0930", FreeBusyType::Busy),
+        period("0930", "1030", FreeBusyType::Busy),
+        period("1030", "1200", FreeBusyType::BusyTentative),
+        period("1300", "1400", FreeBusyType::BusyTentative),
+        period("1330", "1345", FreeBusyType::BusyUnavailable),
+        period("1500", "1600", FreeBusyType::Free),
+        period("1700", "1700", FreeBusyType::Busy),
+    ]);
+    assert_eq!(
+        merged,
+        [
+            period("0900", "1100", FreeBusyType::Busy),
+            period("1100", "1200", FreeBusyType::BusyTentative),
+            period("1300", "1330", FreeBusyType::BusyTentative),
+            period("1330", "1345", FreeBusyType::BusyUnavailable),
+            period("1345", "1400", FreeBusyType::BusyTentative),
+        ]
+    );
+}
+
+#[test]
+fn reports_free_periods_inside_the_window() {
+    let fb = FreeBusy {
+        start: Some("20240101T080000Z".into()),
+        end: Some("20240101T180000Z".into()),
+        periods: vec![
+            period("0700", "0830", FreeBusyType::Busy),
+            period("1200", "1300", FreeBusyType::Busy),
+            period("1230", "1330", FreeBusyType::BusyTentative),
+            period("1700", "1900", FreeBusyType::Busy),
+        ],
+    };
+    assert_eq!(
+        fb.free_periods(),
+        [
+            period("0830", "1200", FreeBusyType::Free),
+            period("1330", "1700", FreeBusyType::Free),
+        ]
+    );
+
+    let open = FreeBusy {
+        periods: vec![
+            period("0900", "1000", FreeBusyType::Busy),
+            period("1100", "1200", FreeBusyType::Busy),
+        ],
+        ..FreeBusy::default()
+    };
+    assert_eq!(
+        open.free_periods(),
+        [period("1000", "1100", FreeBusyType::Free)]
+    );
+}
+
+#[tokio::test]
+async fn aggregate_free_busy_validates_window_before_network() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .aggregate_free_busy(["/cal/a/", "/cal/b/"], "2024-01-01", "20240102T000000Z")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid free-busy-query start"));
+}
+
+#[test]
+fn free_busy_without_vfreebusy_is_empty() {
+    let fb = parse_free_busy("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n").unwrap();
+    assert!(fb.periods.is_empty());
+}
+
+#[test]
+fn free_busy_rejects_malformed_period() {
+    let ics =
+        "BEGIN:VCALENDAR\nBEGIN:VFREEBUSY\nFREEBUSY:not-a-period\nEND:VFREEBUSY\nEND:VCALENDAR\n";
+    assert!(parse_free_busy(ics).is_err());
+}
+
+#[tokio::test]
+async fn free_busy_query_rejects_malformed_window() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .free_busy_query("calendars/test/default/", "2024-01-01", "20240102T000000Z")
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("invalid free-busy-query start"));
+}
+
+#[test]
+fn parses_schedule_response_per_recipient() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<C:schedule-response xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <C:response>
+    <C:recipient><D:href>mailto:jane@example.com</D:href></C:recipient>
+    <C:request-status>2.0;Success</C:request-status>
+    <C:calendar-data>BEGIN:VCALENDAR
+VERSION:2.0
+METHOD:REPLY
+BEGIN:VFREEBUSY
+FREEBUSY:20240110T090000Z/PT1H
+END:VFREEBUSY
+END:VCALENDAR
+</C:calendar-data>
+  </C:response>
+  <C:response>
+    <C:recipient><D:href>mailto:nobody@example.com</D:href></C:recipient>
+    <C:request-status>3.7;Invalid calendar user</C:request-status>
+    <D:responsedescription>Unknown user</D:responsedescription>
+  </C:response>
+</C:schedule-response>"#;
+
+    let statuses = parse_schedule_response(xml.as_bytes()).unwrap();
+    assert_eq!(statuses.len(), 2);
+
+    assert_eq!(statuses[0].recipient, "mailto:jane@example.com");
+    assert_eq!(statuses[0].status_code(), Some("2.0"));
+    assert!(statuses[0].is_success());
+    let fb = parse_free_busy(statuses[0].calendar_data.as_deref().unwrap()).unwrap();
+    assert_eq!(fb.periods[0].end, "20240110T100000Z");
+
+    assert_eq!(statuses[1].recipient, "mailto:nobody@example.com");
+    assert!(!statuses[1].is_success());
+    assert_eq!(statuses[1].description.as_deref(), Some("Unknown user"));
+    assert!(statuses[1].calendar_data.is_none());
+}
+
+#[test]
+fn schedule_response_resolves_escaped_characters() {
+    let xml = r#"<C:schedule-response xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <C:response>
+    <C:recipient><D:href>mailto:tom&amp;jerry@x</D:href></C:recipient>
+    <C:request-status>2.0;Success</C:request-status>
+    <C:calendar-data>BEGIN:VEVENT
+SUMMARY:A &amp; B &lt;c&gt;&#33;
+END:VEVENT
+</C:calendar-data>
+    <D:responsedescription>Delivered to Tom &amp; Jerry</D:responsedescription>
+  </C:response>
+</C:schedule-response>"#;
+
+    let statuses = parse_schedule_response(xml.as_bytes()).unwrap();
+    assert_eq!(statuses[0].recipient, "mailto:tom&jerry@x");
+    assert_eq!(
+        statuses[0].calendar_data.as_deref(),
+        Some("BEGIN:VEVENT\nSUMMARY:A & B <c>!\nEND:VEVENT\n")
+    );
+    assert_eq!(
+        statuses[0].description.as_deref(),
+        Some("Delivered to Tom & Jerry")
+    );
+}
+
+#[test]
+fn builds_free_busy_itip_request() {
+    let ics = build_free_busy_request(
+        "mailto:org@example.com",
+        ["mailto:a@example.com", "mailto:b@example.com"],
+        "20240110T000000Z",
+        "20240111T000000Z",
+        "fb-1",
+    );
+    assert!(ics.contains("METHOD:REQUEST\r\n"));
+    assert!(ics.contains("BEGIN:VFREEBUSY\r\n"));
+    assert!(ics.contains("ORGANIZER:mailto:org@example.com\r\n"));
+    assert!(ics.contains("ATTENDEE:mailto:b@example.com\r\n"));
+    assert!(ics.contains("DTSTART:20240110T000000Z\r\n"));
+    assert_eq!(itip_method(&ics).as_deref(), Some("REQUEST"));
+}
+
+#[tokio::test]
+async fn schedule_post_requires_itip_method() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .schedule_post(
+            "calendars/test/outbox/",
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n",
+        )
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("no METHOD"));
+}
+
+const INVITATION: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:meeting-1\r
+DTSTART:20240110T090000Z\r
+ORGANIZER:mailto:org@example.com\r
+ATTENDEE;CN=Org;PARTSTAT=ACCEPTED:mailto:org@example.com\r
+ATTENDEE;CN=Jane;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:MAILTO:Jane@Example.com\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:meeting-1\r
+RECURRENCE-ID:20240117T090000Z\r
+ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:jane@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+#[test]
+fn sets_partstat_for_matching_attendee_in_every_instance() {
+    let updated =
+        set_attendee_partstat(INVITATION, ["jane@example.com"], Partstat::Accepted).unwrap();
+    let cal = Component::parse(&updated).unwrap();
+    let partstats: Vec<_> = cal
+        .components_named("VEVENT")
+        .flat_map(|e| e.properties_named("ATTENDEE"))
+        .map(|a| {
+            (
+                a.value.to_ascii_lowercase(),
+                a.param("PARTSTAT").unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        partstats,
+        vec![
+            ("mailto:org@example.com".to_string(), "ACCEPTED".to_string()),
+            (
+                "mailto:jane@example.com".to_string(),
+                "ACCEPTED".to_string()
+            ),
+            (
+                "mailto:jane@example.com".to_string(),
+                "ACCEPTED".to_string()
+            ),
+        ]
+    );
+    assert!(updated.contains("RSVP=TRUE"));
+}
+
+#[test]
+fn set_partstat_errors_when_user_is_not_invited() {
+    let err = set_attendee_partstat(INVITATION, ["mailto:bob@example.com"], Partstat::Declined)
+        .unwrap_err();
+    assert!(err.to_string().contains("no ATTENDEE matches"));
+}
+
+#[test]
+fn maps_calendar_user_address_set() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/principals/jane/</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:calendar-user-address-set>
+          <D:href>mailto:jane@example.com</D:href>
+          <D:href>/principals/jane/</D:href>
+        </C:calendar-user-address-set>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(
+        items[0].calendar_user_address_set,
+        vec!["mailto:jane@example.com", "/principals/jane/"]
+    );
+}
+
+const ORGANIZED_EVENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//test//EN\r
+BEGIN:VEVENT\r
+UID:meeting-2\r
+DTSTAMP:20240101T000000Z\r
+DTSTART:20240110T090000Z\r
+SEQUENCE:1\r
+ORGANIZER;SCHEDULE-AGENT=SERVER:mailto:org@example.com\r
+ATTENDEE;CN=Jane;PARTSTAT=ACCEPTED;SCHEDULE-STATUS=2.0:mailto:jane@example.com\r
+ATTENDEE;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:bob@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+fn first_event(ics: &str) -> (Component, Component) {
+    let cal = Component::parse(ics).unwrap();
+    let event = cal.components_named("VEVENT").next().unwrap().clone();
+    (cal, event)
+}
+
+#[test]
+fn itip_request_replaces_attendees_and_bumps_sequence() {
+    let ics = ItipBuilder::new(ItipMethod::Request, ORGANIZED_EVENT)
+        .unwrap()
+        .attendees(["mailto:jane@example.com", "mailto:carol@example.com"])
+        .increment_sequence()
+        .build()
+        .unwrap();
+    let (cal, event) = first_event(&ics);
+
+    assert_eq!(cal.value("METHOD"), Some("REQUEST"));
+    assert_eq!(cal.properties[2].name, "METHOD");
+    assert_eq!(event.value("SEQUENCE"), Some("2"));
+    assert_ne!(event.value("DTSTAMP"), Some("20240101T000000Z"));
+    assert!(event.components_named("VALARM").next().is_none());
+    assert_eq!(
+        event.property("ORGANIZER").unwrap().param("SCHEDULE-AGENT"),
+        None
+    );
+
+    let attendees: Vec<_> = event.properties_named("ATTENDEE").collect();
+    assert_eq!(attendees.len(), 2);
+    assert_eq!(attendees[0].param("CN"), Some("Jane"));
+    assert_eq!(attendees[0].param("SCHEDULE-STATUS"), None);
+    assert_eq!(attendees[1].value, "mailto:carol@example.com");
+    assert_eq!(attendees[1].param("PARTSTAT"), Some("NEEDS-ACTION"));
+    assert_eq!(attendees[1].param("RSVP"), Some("TRUE"));
+}
+
+#[test]
+fn itip_reply_keeps_only_the_sending_attendee() {
+    let ics = ItipBuilder::new(ItipMethod::Reply, ORGANIZED_EVENT)
+        .unwrap()
+        .from_attendee("mailto:Bob@example.com", Partstat::Declined)
+        .build()
+        .unwrap();
+    let (cal, event) = first_event(&ics);
+
+    assert_eq!(cal.value("METHOD"), Some("REPLY"));
+    assert_eq!(event.value("SEQUENCE"), Some("1"));
+    let attendees: Vec<_> = event.properties_named("ATTENDEE").collect();
+    assert_eq!(attendees.len(), 1);
+    assert_eq!(attendees[0].value, "mailto:bob@example.com");
+    assert_eq!(attendees[0].param("PARTSTAT"), Some("DECLINED"));
+    assert_eq!(attendees[0].param("RSVP"), None);
+}
+
+#[test]
+fn itip_cancel_sets_status_cancelled() {
+    let ics = ItipBuilder::new(ItipMethod::Cancel, ORGANIZED_EVENT)
+        .unwrap()
+        .attendees(["mailto:bob@example.com"])
+        .sequence(5)
+        .build()
+        .unwrap();
+    let (cal, event) = first_event(&ics);
+
+    assert_eq!(cal.value("METHOD"), Some("CANCEL"));
+    assert_eq!(event.value("STATUS"), Some("CANCELLED"));
+    assert_eq!(event.value("SEQUENCE"), Some("5"));
+    assert_eq!(event.properties_named("ATTENDEE").count(), 1);
+}
+
+#[test]
+fn itip_builder_validates_inputs() {
+    let err = ItipBuilder::new(ItipMethod::Counter, ORGANIZED_EVENT)
+        .unwrap()
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("from_attendee"));
+
+    let no_organizer = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:x\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let err = ItipBuilder::new(ItipMethod::Request, no_organizer)
+        .unwrap()
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("no ORGANIZER"));
+
+    let no_event = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n";
+    assert!(ItipBuilder::new(ItipMethod::Request, no_event).is_err());
+}
+
+#[test]
+fn adds_attendee_and_bumps_sequence() {
+    let updated = add_attendee(
+        ORGANIZED_EVENT,
+        "carol@example.com",
+        AttendeeRole::OptParticipant,
+    )
+    .unwrap();
+    let (_, event) = first_event(&updated);
+    assert_eq!(event.value("SEQUENCE"), Some("2"));
+    let carol = event
+        .properties_named("ATTENDEE")
+        .find(|a| a.value == "mailto:carol@example.com")
+        .unwrap();
+    assert_eq!(carol.param("ROLE"), Some("OPT-PARTICIPANT"));
+    assert_eq!(carol.param("PARTSTAT"), Some("NEEDS-ACTION"));
+
+    // An existing attendee only gets its role updated.
+    let updated = add_attendee(&updated, "MAILTO:Jane@Example.com", AttendeeRole::Chair).unwrap();
+    let (_, event) = first_event(&updated);
+    assert_eq!(event.properties_named("ATTENDEE").count(), 3);
+    let jane = event.properties_named("ATTENDEE").next().unwrap();
+    assert_eq!(jane.param("ROLE"), Some("CHAIR"));
+    assert_eq!(jane.param("PARTSTAT"), Some("ACCEPTED"));
+    assert_eq!(event.value("SEQUENCE"), Some("3"));
+}
+
+#[test]
+fn removes_attendee_from_every_instance() {
+    let updated = remove_attendee(INVITATION, "jane@example.com").unwrap();
+    let cal = Component::parse(&updated).unwrap();
+    let events: Vec<_> = cal.components_named("VEVENT").collect();
+    assert_eq!(events[0].properties_named("ATTENDEE").count(), 1);
+    assert_eq!(events[1].properties_named("ATTENDEE").count(), 0);
+    assert!(events.iter().all(|e| e.value("SEQUENCE") == Some("1")));
+
+    let err = remove_attendee(INVITATION, "bob@example.com").unwrap_err();
+    assert!(err.to_string().contains("no ATTENDEE matches"));
+    assert!(
+        add_attendee(
+            "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n",
+            "a@b.c",
+            AttendeeRole::default()
+        )
+        .is_err()
+    );
+}
@@ -0,0 +1,184 @@
+use fast_dav_rs::caldav::{
+    InviteStatus, ShareAccess, ShareInvitation, ShareInvite, build_invite_reply_body,
+    build_share_body, map_shared_calendars, parse_calendar_invite, parse_invite_notification,
+    parse_shared_as,
+};
+use fast_dav_rs::parse_multistatus_bytes;
+
+#[test]
+fn builds_share_request() {
+    let body = build_share_body(
+        &[
+            ShareInvite::new("mailto:jane@example.com", ShareAccess::ReadWrite)
+                .common_name("Jane <Doe>")
+                .summary("Team calendar"),
+        ],
+        &["mailto:bob@example.com"],
+    );
+    assert!(body.contains(r#"<CS:share xmlns:D="DAV:" xmlns:CS="http://calendarserver.org/ns/">"#));
+    assert!(body.contains(
+        "<CS:set><D:href>mailto:jane@example.com</D:href><CS:common-name>Jane &lt;Doe&gt;</CS:common-name><CS:summary>Team calendar</CS:summary><CS:read-write/></CS:set>"
+    ));
+    assert!(body.contains("<CS:remove><D:href>mailto:bob@example.com</D:href></CS:remove>"));
+}
+
+#[test]
+fn parses_calendar_invite() {
+    let xml = br#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+  <d:response>
+    <d:href>/calendars/alice/work/</d:href>
+    <d:propstat>
+      <d:prop>
+        <cs:invite>
+          <cs:organizer><d:href>mailto:alice@example.com</d:href><cs:common-name>Alice</cs:common-name></cs:organizer>
+          <cs:user>
+            <d:href>mailto:jane@example.com</d:href>
+            <cs:common-name>Jane</cs:common-name>
+            <cs:invite-accepted/>
+            <cs:access><cs:read-write/></cs:access>
+          </cs:user>
+          <cs:user>
+            <d:href>mailto:bob@example.com</d:href>
+            <cs:invite-noresponse/>
+            <cs:access><cs:read/></cs:access>
+          </cs:user>
+        </cs:invite>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let invite = parse_calendar_invite(xml).unwrap();
+    assert_eq!(
+        invite.organizer.as_deref(),
+        Some("mailto:alice@example.com")
+    );
+    assert_eq!(invite.organizer_name.as_deref(), Some("Alice"));
+    assert_eq!(invite.sharees.len(), 2);
+    assert_eq!(invite.sharees[0].common_name.as_deref(), Some("Jane"));
+    assert_eq!(invite.sharees[0].status, InviteStatus::Accepted);
+    assert_eq!(invite.sharees[0].access, ShareAccess::ReadWrite);
+    assert_eq!(invite.sharees[1].href, "mailto:bob@example.com");
+    assert_eq!(invite.sharees[1].status, InviteStatus::NoResponse);
+    assert_eq!(invite.sharees[1].access, ShareAccess::Read);
+}
+
+const NOTIFICATION: &[u8] = br#"<?xml version="1.0" encoding="UTF-8"?>
+<cs:notification xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+  <cs:dtstamp>20240101T000000Z</cs:dtstamp>
+  <cs:invite-notification>
+    <cs:uid>invite-42</cs:uid>
+    <d:href>mailto:jane@example.com</d:href>
+    <cs:invite-noresponse/>
+    <cs:hosturl><d:href>/calendars/alice/work/</d:href></cs:hosturl>
+    <cs:organizer><d:href>mailto:alice@example.com</d:href><cs:common-name>Alice</cs:common-name></cs:organizer>
+    <cs:access><cs:read/></cs:access>
+    <cs:summary>Work</cs:summary>
+  </cs:invite-notification>
+</cs:notification>"#;
+
+#[test]
+fn parses_invite_notification_and_builds_reply() {
+    let invitation = parse_invite_notification("/notifications/jane/n1.xml", NOTIFICATION)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        invitation,
+        ShareInvitation {
+            uid: "invite-42".to_string(),
+            notification_href: "/notifications/jane/n1.xml".to_string(),
+            href: "mailto:jane@example.com".to_string(),
+            status: InviteStatus::NoResponse,
+            host_url: "/calendars/alice/work/".to_string(),
+            organizer: Some("mailto:alice@example.com".to_string()),
+            organizer_name: Some("Alice".to_string()),
+            access: ShareAccess::Read,
+            summary: Some("Work".to_string()),
+        }
+    );
+
+    let reply = build_invite_reply_body(&invitation, true);
+    assert!(reply.contains("<D:href>mailto:alice@example.com</D:href><CS:invite-accepted/>"));
+    assert!(reply.contains("<CS:hosturl><D:href>/calendars/alice/work/</D:href></CS:hosturl>"));
+    assert!(reply.contains("<CS:in-reply-to>invite-42</CS:in-reply-to>"));
+    assert!(build_invite_reply_body(&invitation, false).contains("<CS:invite-declined/>"));
+
+    let other = br#"<cs:notification xmlns:cs="http://calendarserver.org/ns/"><cs:resource-change/></cs:notification>"#;
+    assert!(
+        parse_invite_notification("/n2.xml", other)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn invite_notification_keeps_escaped_text_whole() {
+    let xml = br#"<cs:notification xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+  <cs:invite-notification>
+    <cs:uid>invite-43</cs:uid>
+    <d:href>mailto:jane&amp;bob@example.com</d:href>
+    <cs:hosturl><d:href>/calendars/alice/r&amp;d/</d:href></cs:hosturl>
+    <cs:organizer><d:href>mailto:alice@example.com</d:href><cs:common-name>Alice &amp; Co &lt;HQ&gt;</cs:common-name></cs:organizer>
+    <cs:summary>R&#38;D</cs:summary>
+  </cs:invite-notification>
+</cs:notification>"#;
+    let invitation = parse_invite_notification("/n3.xml", xml).unwrap().unwrap();
+    assert_eq!(invitation.href, "mailto:jane&bob@example.com");
+    assert_eq!(invitation.host_url, "/calendars/alice/r&d/");
+    assert_eq!(
+        invitation.organizer_name.as_deref(),
+        Some("Alice & Co <HQ>")
+    );
+    assert_eq!(invitation.summary.as_deref(), Some("R&D"));
+
+    let invite =
+        br#"<d:multistatus xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/"><d:response>
+  <d:href>/calendars/alice/work/</d:href>
+  <d:propstat><d:prop><cs:invite><cs:user>
+    <d:href>mailto:jane&amp;bob@example.com</d:href>
+    <cs:common-name>Jane &amp; Bob</cs:common-name>
+    <cs:invite-accepted/>
+  </cs:user></cs:invite></d:prop></d:propstat>
+</d:response></d:multistatus>"#;
+    let invite = parse_calendar_invite(invite).unwrap();
+    assert_eq!(invite.sharees[0].href, "mailto:jane&bob@example.com");
+    assert_eq!(invite.sharees[0].common_name.as_deref(), Some("Jane & Bob"));
+}
+
+#[test]
+fn parses_shared_as_and_shared_calendars() {
+    let reply = br#"<d:root xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/"><cs:shared-as><d:href>/calendars/jane/work_shared_by_alice/</d:href></cs:shared-as></d:root>"#;
+    assert_eq!(
+        parse_shared_as(reply).unwrap().as_deref(),
+        Some("/calendars/jane/work_shared_by_alice/")
+    );
+
+    let xml = br#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">
+  <d:response>
+    <d:href>/calendars/jane/personal/</d:href>
+    <d:propstat><d:prop>
+      <d:resourcetype><d:collection/><cal:calendar/><cs:shared-owner/></d:resourcetype>
+    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/calendars/jane/work_shared_by_alice/</d:href>
+    <d:propstat><d:prop>
+      <d:displayname>Work</d:displayname>
+      <d:resourcetype><d:collection/><cal:calendar/><cs:shared/></d:resourcetype>
+      <cs:shared-url><d:href>/calendars/alice/work/</d:href></cs:shared-url>
+    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let items = parse_multistatus_bytes(xml).unwrap().items;
+    assert!(items[0].is_shared_owner);
+    let shared = map_shared_calendars(items);
+    assert_eq!(shared.len(), 1);
+    assert_eq!(shared[0].href, "/calendars/jane/work_shared_by_alice/");
+    assert_eq!(shared[0].displayname.as_deref(), Some("Work"));
+    assert_eq!(
+        shared[0].shared_url.as_deref(),
+        Some("/calendars/alice/work/")
+    );
+}
@@ -0,0 +1,84 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{CalendarObject, SortKey, sort_calendar_objects};
+
+fn object(href: &str, body: Option<&str>) -> CalendarObject {
+    CalendarObject {
+        href: href.to_string(),
+        etag: None,
+        calendar_data: body.map(|b| {
+            format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Paris\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\nBEGIN:VEVENT\r\n{b}END:VEVENT\r\nEND:VCALENDAR\r\n"
+            )
+        }),
+        status: None,
+    }
+}
+
+fn hrefs(objects: &[CalendarObject]) -> Vec<&str> {
+    objects.iter().map(|o| o.href.as_str()).collect()
+}
+
+fn sample() -> Vec<CalendarObject> {
+    vec![
+        object(
+            "/c/d.ics",
+            Some("SUMMARY:beta\r\nDTSTART;TZID=Europe/Paris:20240105T090000\r\n"),
+        ),
+        object("/c/b.ics", None),
+        object(
+            "/c/c.ics",
+            Some(
+                "SUMMARY:Alpha\\, first\r\nDTSTART;VALUE=DATE:20240105\r\nLAST-MODIFIED:20240301T000000Z\r\n",
+            ),
+        ),
+        object(
+            "/c/a.ics",
+            Some("SUMMARY:Beta\r\nDTSTART:20240101T120000Z\r\nLAST-MODIFIED:20240201T000000Z\r\n"),
+        ),
+    ]
+}
+
+#[test]
+fn sorts_by_start_ignoring_vtimezone_observances() {
+    let mut objects = sample();
+    sort_calendar_objects(&mut objects, SortKey::Start);
+    assert_eq!(
+        hrefs(&objects),
+        ["/c/a.ics", "/c/c.ics", "/c/d.ics", "/c/b.ics"]
+    );
+}
+
+#[test]
+fn sorts_by_summary_and_last_modified_with_href_ties() {
+    let mut objects = sample();
+    sort_calendar_objects(&mut objects, SortKey::Summary);
+    // "Beta" and "beta" tie and fall back to href order; missing data sorts last.
+    assert_eq!(
+        hrefs(&objects),
+        ["/c/c.ics", "/c/a.ics", "/c/d.ics", "/c/b.ics"]
+    );
+
+    sort_calendar_objects(&mut objects, SortKey::LastModified);
+    assert_eq!(
+        hrefs(&objects),
+        ["/c/a.ics", "/c/c.ics", "/c/b.ics", "/c/d.ics"]
+    );
+
+    sort_calendar_objects(&mut objects, SortKey::Href);
+    assert_eq!(
+        hrefs(&objects),
+        ["/c/a.ics", "/c/b.ics", "/c/c.ics", "/c/d.ics"]
+    );
+}
+
+#[test]
+fn query_builder_accepts_ordering() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let body = client
+        .query("/c/")
+        .include_data()
+        .order_by(SortKey::Start)
+        .build_body()
+        .unwrap();
+    assert!(body.contains("calendar-data"));
+}
@@ -152,6 +152,73 @@ fn test_multistatus_visit_error_propagates() {
     assert!(err.is_err(), "expected visitor error to propagate");
 }
 
+#[test]
+fn test_text_split_by_escapes_and_cdata_is_kept_whole() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href> /q&amp;a </D:href>
+    <D:propstat><D:prop>
+      <C:calendar-data><![CDATA[BEGIN:X]]>
+<![CDATA[SUMMARY:a]]> &amp; b
+</C:calendar-data>
+    </D:prop></D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(items[0].href, "/q&a");
+    assert_eq!(
+        items[0].calendar_data.as_deref(),
+        Some("BEGIN:X\nSUMMARY:a & b\n")
+    );
+}
+
+#[test]
+fn test_many_distinct_element_names_are_classified() {
+    // More distinct names than the parser interns, with the known ones seen last.
+    let unknown: String = (0..100).map(|i| format!("<X:p{i}>v</X:p{i}>")).collect();
+    let xml = format!(
+        r#"<D:multistatus xmlns:D="DAV:" xmlns:X="urn:x"><D:response><D:href>/a.ics</D:href><D:propstat><D:prop>{unknown}<D:getetag>"1"</D:getetag></D:prop></D:propstat></D:response><d:response xmlns:d="DAV:"><d:href>/b.ics</d:href></d:response></D:multistatus>"#
+    );
+
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].etag.as_deref(), Some("\"1\""));
+    assert_eq!(items[1].href, "/b.ics");
+}
+
+#[test]
+fn test_multistatus_visit_can_stop_early() {
+    // The body is cut after the second response: stopping on the first never reaches it.
+    let xml = r#"<D:multistatus xmlns:D="DAV:">
+  <D:response><D:href>/calendars/work/a.ics</D:href><D:status>HTTP/1.1 200 OK</D:status></D:response>
+  <D:response><D:href>/calendars/work/b.ics</D:href><D:status>HTTP/1.1 200 OK</D:status></D:response>
+  <D:response><D:href>/calendars/work/c"#;
+
+    let mut visited = Vec::new();
+    let token = parse_multistatus_bytes_visit_until(xml.as_bytes(), |item| {
+        visited.push(item.href);
+        Ok(Visit::Stop)
+    })
+    .expect("stopped parse");
+    assert_eq!(visited, ["/calendars/work/a.ics"]);
+    assert_eq!(token, None);
+
+    let mut visited = Vec::new();
+    let token = parse_multistatus_bytes_visit_until(xml.as_bytes(), |item| {
+        visited.push(item.href);
+        Ok(if visited.len() == 2 {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        })
+    })
+    .expect("stopped parse");
+    assert_eq!(visited.len(), 2);
+    assert_eq!(token, None);
+    assert!(parse_multistatus_bytes_visit(xml.as_bytes(), |_| Ok(())).is_err());
+}
+
 #[tokio::test]
 async fn test_streaming_stalled_body_times_out() -> Result<()> {
     let (client_io, mut server_io) = io::duplex(16 * 1024);
@@ -259,3 +326,135 @@ async fn test_streaming_preserves_multiline_calendar_data() -> Result<()> {
     assert_eq!(data, "BEGIN:VCALENDAR\nEND:VCALENDAR\n");
     Ok(())
 }
+
+const SYNC_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/calendars/work/a.ics</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:getetag>"a1"</D:getetag>
+        <C:calendar-data>BEGIN:VCALENDAR&#13;
+BEGIN:VEVENT&#13;
+UID:a&#13;
+END:VEVENT&#13;
+END:VCALENDAR&#13;
+</C:calendar-data>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/calendars/work/b.ics</D:href>
+    <D:status>HTTP/1.1 404 Not Found</D:status>
+  </D:response>
+  <D:response>
+    <D:href>/calendars/work/</D:href>
+    <D:status>HTTP/1.1 507 Insufficient Storage</D:status>
+  </D:response>
+  <D:sync-token>http://example.com/sync/7</D:sync-token>
+</D:multistatus>"#;
+
+#[tokio::test]
+async fn test_sync_item_stream_yields_calendar_changes() -> Result<()> {
+    use fast_dav_rs::caldav::SyncItemStream;
+    use futures::TryStreamExt;
+
+    let mut stream = SyncItemStream::new(
+        std::io::Cursor::new(SYNC_XML.as_bytes().to_vec()),
+        Some("header-token".to_string()),
+        Duration::from_secs(5),
+    );
+    let first = stream.try_next().await?.expect("first item");
+    assert_eq!(first.href, "/calendars/work/a.ics");
+    assert!(first.calendar_data.unwrap().contains("UID:a"));
+    assert_eq!(stream.sync_token(), None, "token is only known at the end");
+
+    let second = stream.try_next().await?.expect("second item");
+    assert!(second.is_deleted);
+    assert!(stream.try_next().await?.is_none());
+    assert!(stream.truncated());
+    assert_eq!(
+        stream.sync_token().as_deref(),
+        Some("http://example.com/sync/7")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_item_stream_falls_back_to_header_token() -> Result<()> {
+    use fast_dav_rs::caldav::SyncItemStream;
+    use futures::TryStreamExt;
+
+    let xml = SYNC_XML.replace("<D:sync-token>http://example.com/sync/7</D:sync-token>", "");
+    let mut stream = SyncItemStream::new(
+        std::io::Cursor::new(xml.into_bytes()),
+        Some("header-token".to_string()),
+        Duration::from_secs(5),
+    );
+    let mut hrefs = Vec::new();
+    while let Some(item) = stream.try_next().await? {
+        hrefs.push(item.href);
+    }
+    assert_eq!(hrefs, ["/calendars/work/a.ics", "/calendars/work/b.ics"]);
+    assert_eq!(stream.sync_token().as_deref(), Some("header-token"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dav_item_stream_yields_items_before_the_body_ends() -> Result<()> {
+    use futures::TryStreamExt;
+
+    let (reader, mut writer) = io::duplex(4096);
+    let mut items = DavItemStream::new(io::BufReader::new(reader), Duration::from_secs(5));
+    writer
+        .write_all(
+            br#"<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/calendars/work/a.ics</D:href>
+    <D:propstat><D:prop><D:getetag>"a1"</D:getetag></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+  </D:response>
+  <D:response>"#,
+        )
+        .await?;
+
+    // The first item is yielded while the rest of the body is still to come.
+    let first = items.try_next().await?.expect("first item");
+    assert_eq!(first.href, "/calendars/work/a.ics");
+    assert_eq!(first.etag.as_deref(), Some("\"a1\""));
+
+    writer
+        .write_all(
+            br#"
+    <D:href>/calendars/work/b.ics</D:href>
+    <D:status>HTTP/1.1 404 Not Found</D:status>
+  </D:response>
+  <D:sync-token>http://example.com/sync/9</D:sync-token>
+</D:multistatus>"#,
+        )
+        .await?;
+    drop(writer);
+
+    let second = items.try_next().await?.expect("second item");
+    assert_eq!(second.href, "/calendars/work/b.ics");
+    assert!(items.try_next().await?.is_none());
+    assert_eq!(
+        items.sync_token().as_deref(),
+        Some("http://example.com/sync/9")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dav_item_stream_ends_after_an_error() {
+    use futures::StreamExt;
+
+    let xml = r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/calendars/work/a.ics</D:href>"#;
+    let mut items = DavItemStream::new(
+        std::io::Cursor::new(xml.as_bytes().to_vec()),
+        Duration::from_secs(5),
+    );
+    assert!(items.next().await.expect("error").is_err());
+    assert!(items.next().await.is_none());
+    assert_eq!(items.sync_token(), None);
+}
@@ -0,0 +1,77 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::{
+    build_calendar_query_body_filtered, complete_todo, incomplete_todos_filter,
+    overdue_todos_filter, todo_is_open,
+};
+
+const OPEN_TODO: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VTODO\r\nUID:todo-1\r\nDTSTAMP:20240101T000000Z\r\nSUMMARY:Write report\r\nDUE:20240110T170000Z\r\nSTATUS:NEEDS-ACTION\r\nPERCENT-COMPLETE:40\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+#[test]
+fn incomplete_filter_excludes_completed_date() {
+    let body = build_calendar_query_body_filtered(&incomplete_todos_filter(), None);
+    assert!(body.contains(concat!(
+        r#"<C:comp-filter name="VTODO">"#,
+        r#"<C:prop-filter name="COMPLETED"><C:is-not-defined/></C:prop-filter>"#,
+        r#"</C:comp-filter>"#
+    )));
+}
+
+#[test]
+fn overdue_filter_bounds_due_date() {
+    let body = build_calendar_query_body_filtered(&overdue_todos_filter("20240115T000000Z"), None);
+    assert!(body.contains(
+        r#"<C:prop-filter name="DUE"><C:time-range end="20240115T000000Z"/></C:prop-filter>"#
+    ));
+    assert!(overdue_todos_filter("20240115T000000Z").validate().is_ok());
+    assert!(overdue_todos_filter("tomorrow").validate().is_err());
+}
+
+#[test]
+fn todo_is_open_checks_status_and_completed() {
+    assert!(todo_is_open(OPEN_TODO));
+    assert!(!todo_is_open(
+        &OPEN_TODO.replace("NEEDS-ACTION", "CANCELLED")
+    ));
+    assert!(!todo_is_open(
+        &OPEN_TODO.replace("STATUS:NEEDS-ACTION", "COMPLETED:20240105T120000Z")
+    ));
+    assert!(!todo_is_open("not ical"));
+}
+
+#[test]
+fn complete_todo_sets_status_percent_and_timestamps() {
+    let done = complete_todo(OPEN_TODO).unwrap();
+    assert!(done.contains("STATUS:COMPLETED\r\n"));
+    assert!(done.contains("PERCENT-COMPLETE:100\r\n"));
+    assert!(done.contains("\r\nCOMPLETED:"));
+    assert!(done.contains("\r\nLAST-MODIFIED:"));
+    assert!(!done.contains("DTSTAMP:20240101T000000Z"));
+    assert!(!todo_is_open(&done));
+
+    let kept =
+        complete_todo(&OPEN_TODO.replace("STATUS:NEEDS-ACTION", "COMPLETED:20240105T120000Z"))
+            .unwrap();
+    assert!(kept.contains("COMPLETED:20240105T120000Z"));
+}
+
+#[test]
+fn complete_todo_rejects_objects_without_todo() {
+    let event = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:e\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    assert!(
+        complete_todo(event)
+            .unwrap_err()
+            .to_string()
+            .contains("no VTODO")
+    );
+}
+
+#[tokio::test]
+async fn complete_task_surfaces_get_failure() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    assert!(
+        client
+            .complete_task("calendars/test/tasks/todo-1.ics")
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,113 @@
+use chrono::{FixedOffset, TimeZone, Timelike, Utc};
+use fast_dav_rs::caldav::{
+    ICalDateTime, ObservanceKind, find_timezone, normalize_tzid, parse_icalendar, parse_timezones,
+};
+
+const BERLIN: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n\
+BEGIN:VTIMEZONE\r\nTZID:/mozilla.org/20050126_1/Europe/Berlin\r\n\
+BEGIN:DAYLIGHT\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0200\r\nTZNAME:CEST\r\n\
+DTSTART:19700329T020000\r\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\nEND:DAYLIGHT\r\n\
+BEGIN:STANDARD\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nTZNAME:CET\r\n\
+DTSTART:19701025T030000\r\nRRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\nEND:STANDARD\r\n\
+END:VTIMEZONE\r\n\
+BEGIN:VEVENT\r\nUID:e1\r\nDTSTART;TZID=/mozilla.org/20050126_1/Europe/Berlin:20240701T120000\r\n\
+DTEND;TZID=Europe/Berlin:20241215T090000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+fn at(value: &str, tzid: Option<&str>) -> ICalDateTime {
+    ICalDateTime {
+        value: value.to_string(),
+        tzid: tzid.map(str::to_string),
+    }
+}
+
+#[test]
+fn parses_vtimezone_observances() {
+    let zones = parse_timezones(&parse_icalendar(BERLIN).unwrap()).unwrap();
+    assert_eq!(zones.len(), 1);
+    let zone = &zones[0];
+    assert_eq!(zone.observances.len(), 2);
+    assert_eq!(zone.observances[0].kind, ObservanceKind::Daylight);
+    assert_eq!(zone.observances[0].offset_to, 7_200);
+    assert_eq!(zone.observances[1].name.as_deref(), Some("CET"));
+}
+
+#[test]
+fn computes_offsets_across_transitions() {
+    let zones = parse_timezones(&parse_icalendar(BERLIN).unwrap()).unwrap();
+    let zone = &zones[0];
+    assert_eq!(zone.utc_offset_at("20240701T120000").unwrap(), 7_200);
+    assert_eq!(zone.utc_offset_at("20240115T120000").unwrap(), 3_600);
+    // 2024 transitions: 31 March 02:00 and 27 October 03:00.
+    assert_eq!(zone.utc_offset_at("20240331T015959").unwrap(), 3_600);
+    assert_eq!(zone.utc_offset_at("20240331T030000").unwrap(), 7_200);
+    assert_eq!(zone.utc_offset_at("20241027T040000").unwrap(), 3_600);
+    // Before the first onset the earliest TZOFFSETFROM applies.
+    assert_eq!(zone.utc_offset_at("19600101T000000").unwrap(), 3_600);
+    assert!(zone.utc_offset_at("not-a-date").is_err());
+}
+
+#[test]
+fn converts_event_times_to_utc() {
+    let calendar = parse_icalendar(BERLIN).unwrap();
+    let zones = parse_timezones(&calendar).unwrap();
+    let event = &calendar.components[0];
+    assert_eq!(
+        event.dtstart.as_ref().unwrap().to_utc(&zones).unwrap(),
+        "20240701T100000Z"
+    );
+    // Matched through the normalized TZID.
+    assert_eq!(
+        event.dtend.as_ref().unwrap().to_utc(&zones).unwrap(),
+        "20241215T080000Z"
+    );
+    assert_eq!(
+        at("20240701T100000Z", None).to_utc(&zones).unwrap(),
+        "20240701T100000Z"
+    );
+    assert!(at("20240701T100000", None).to_utc(&zones).is_err());
+    assert!(
+        at("20240701", Some("Europe/Berlin"))
+            .to_utc(&zones)
+            .is_err()
+    );
+    assert!(
+        at("20240701T100000", Some("America/New_York"))
+            .to_utc(&zones)
+            .is_err()
+    );
+}
+
+#[test]
+fn normalizes_tzids() {
+    assert_eq!(
+        normalize_tzid("/mozilla.org/20050126_1/Europe/Paris"),
+        "Europe/Paris"
+    );
+    assert_eq!(
+        normalize_tzid("/softwarestudio.org/Olson_20011030_5/America/Argentina/Cordoba"),
+        "America/Argentina/Cordoba"
+    );
+    assert_eq!(normalize_tzid("W. Europe Standard Time"), "Europe/Berlin");
+    assert_eq!(normalize_tzid("Asia/Tokyo"), "Asia/Tokyo");
+
+    let zones = parse_timezones(&parse_icalendar(BERLIN).unwrap()).unwrap();
+    assert!(find_timezone(&zones, "Europe/Berlin").is_some());
+    assert!(find_timezone(&zones, "W. Europe Standard Time").is_some());
+    assert!(find_timezone(&zones, "Europe/Paris").is_none());
+}
+
+#[test]
+fn converts_into_chrono_types() {
+    let calendar = parse_icalendar(BERLIN).unwrap();
+    let zones = parse_timezones(&calendar).unwrap();
+    let start = calendar.components[0].dtstart.as_ref().unwrap();
+
+    assert_eq!(
+        start.to_utc_datetime(&zones).unwrap(),
+        Utc.with_ymd_and_hms(2024, 7, 1, 10, 0, 0).unwrap()
+    );
+    let tokyo = FixedOffset::east_opt(9 * 3_600).unwrap();
+    assert_eq!(start.to_zone(&zones, &tokyo).unwrap().hour(), 19);
+    assert_eq!(start.to_naive_datetime().unwrap().hour(), 12);
+    assert_eq!(at("20240701", None).to_naive_datetime().unwrap().hour(), 0);
+}
@@ -0,0 +1,109 @@
+use bytes::Bytes;
+use fast_dav_rs::caldav::{CalDavClient, EventBuilder, IcsValidationError, validate_ics};
+
+const VALID: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example//EN\r\n\
+BEGIN:VEVENT\r\nUID:v-1@example.com\r\nDTSTAMP:20240101T000000Z\r\n\
+DTSTART:20240105T090000Z\r\nSUMMARY:Planning\\, Q1\\; draft\r\n\
+DESCRIPTION:Line one\\nline two with a folded\r\n  continuation\r\nCATEGORIES:Work,Q1\r\n\
+END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+fn issues(ics: &str) -> Vec<(usize, String)> {
+    let err = validate_ics(&Bytes::from(ics.to_string())).unwrap_err();
+    err.downcast_ref::<IcsValidationError>()
+        .unwrap()
+        .issues
+        .iter()
+        .map(|i| (i.line, i.message.clone()))
+        .collect()
+}
+
+#[test]
+fn accepts_well_formed_objects() {
+    validate_ics(&Bytes::from_static(VALID.as_bytes())).unwrap();
+    let built = EventBuilder::new("Sync, weekly; all hands")
+        .start("20240105T090000Z")
+        .description(&"long text ".repeat(20))
+        .build_bytes()
+        .unwrap();
+    validate_ics(&built).unwrap();
+}
+
+#[test]
+fn reports_missing_uid_and_dtstamp() {
+    let found = issues(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:x\r\nBEGIN:VTODO\r\nSUMMARY:t\r\nEND:VTODO\r\nEND:VCALENDAR\r\n",
+    );
+    assert_eq!(
+        found,
+        vec![
+            (4, "VTODO is missing UID".to_string()),
+            (4, "VTODO is missing DTSTAMP".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn reports_line_ending_problems() {
+    let ics = VALID.replacen("VERSION:2.0\r\n", "VERSION:2.0\n", 1);
+    assert_eq!(
+        issues(&ics),
+        vec![(2, "line ends with LF instead of CRLF".to_string())]
+    );
+
+    let ics = VALID.trim_end_matches("\r\n");
+    assert_eq!(
+        issues(ics),
+        vec![(13, "last line is not terminated by CRLF".to_string())]
+    );
+}
+
+#[test]
+fn reports_unfolded_long_lines_and_empty_lines() {
+    let long = format!("DESCRIPTION:{}\r\n", "x".repeat(80));
+    let ics = VALID.replacen("CATEGORIES:Work,Q1\r\n", &format!("{long}\r\n"), 1);
+    let found = issues(&ics);
+    assert_eq!(found.len(), 2);
+    assert!(found[0].1.contains("92 octets"));
+    assert_eq!(found[1], (12, "empty line".to_string()));
+}
+
+#[test]
+fn reports_unescaped_text() {
+    let ics = VALID.replacen("SUMMARY:Planning\\, Q1\\; draft", "SUMMARY:Planning, Q1", 1);
+    assert_eq!(
+        issues(&ics),
+        vec![(8, "SUMMARY has an unescaped ','".to_string())]
+    );
+
+    let ics = VALID.replacen("Line one\\nline", "Path C:\\temp", 1);
+    assert_eq!(
+        issues(&ics),
+        vec![(9, "DESCRIPTION has an invalid escape \\t".to_string())]
+    );
+}
+
+#[test]
+fn reports_unbalanced_components() {
+    let ics = VALID.replacen("END:VEVENT\r\n", "", 1);
+    let found = issues(&ics);
+    assert_eq!(
+        found[0],
+        (
+            12,
+            "END:VCALENDAR closes VEVENT opened on line 4".to_string()
+        )
+    );
+    assert!(found.contains(&(1, "BEGIN:VCALENDAR is never closed".to_string())));
+}
+
+#[tokio::test]
+async fn validation_before_put_fails_before_network() {
+    let mut client = CalDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    client.set_validate_before_put(true);
+    let invalid = Bytes::from_static(b"BEGIN:VCALENDAR\nEND:VCALENDAR\n");
+    let err = client
+        .put_if_none_match("/calendars/test/work/x.ics", invalid)
+        .await
+        .unwrap_err();
+    assert!(err.is::<IcsValidationError>());
+}
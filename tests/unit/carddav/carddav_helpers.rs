@@ -546,6 +546,31 @@ fn test_carddav_namespace_addressbook_color() {
     assert_eq!(book.displayname.as_deref(), Some("Work"));
 }
 
+#[test]
+fn test_calendarserver_getctag() {
+    let books_xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav" xmlns:CS="http://calendarserver.org/ns/">
+  <D:response>
+    <D:href>/addressbooks/user/work/</D:href>
+    <D:propstat>
+      <D:prop>
+        <CS:getctag> 3145 </CS:getctag>
+        <D:resourcetype>
+          <D:collection/>
+          <C:addressbook/>
+        </D:resourcetype>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    let items = parse_multistatus_bytes(books_xml.as_bytes()).unwrap().items;
+    assert_eq!(items[0].ctag.as_deref(), Some("3145"));
+    let books = map_addressbook_list(items);
+    assert_eq!(books[0].ctag.as_deref(), Some("3145"));
+}
+
 #[test]
 fn sync_deletion_requires_numeric_404_or_410_status() {
     use fast_dav_rs::carddav::DavItem;
@@ -593,3 +618,25 @@ fn sync_deletion_requires_numeric_404_or_410_status() {
         "\"HTTP/1.1 4040 Custom\" must not be treated as a 404 deletion"
     );
 }
+
+#[test]
+fn sync_response_flags_truncation_with_507() {
+    let sync_xml = r#"<D:multistatus xmlns:D="DAV:">
+    <D:response>
+        <D:href>/addressbooks/user/book/a.vcf</D:href>
+        <D:propstat><D:prop><D:getetag>"a"</D:getetag></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+    </D:response>
+    <D:response>
+        <D:href>/addressbooks/user/book/</D:href>
+        <D:status>HTTP/1.1 507 Insufficient Storage</D:status>
+    </D:response>
+    <D:sync-token>partial-token</D:sync-token>
+</D:multistatus>"#;
+
+    let parsed = parse_multistatus_bytes(sync_xml.as_bytes()).unwrap();
+    let sync = map_sync_response(&HeaderMap::new(), parsed.items, parsed.sync_token);
+    assert!(sync.truncated);
+    assert_eq!(sync.items.len(), 1);
+    assert_eq!(sync.items[0].href, "/addressbooks/user/book/a.vcf");
+    assert_eq!(sync.sync_token.as_deref(), Some("partial-token"));
+}
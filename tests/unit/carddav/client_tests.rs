@@ -291,3 +291,87 @@ fn test_map_sync_response() {
     );
     assert!(response.items[1].is_deleted); // Should be marked as deleted
 }
+
+#[test]
+fn test_build_addressbook_query_filter_text_match() {
+    use fast_dav_rs::carddav::client::build_addressbook_query_filter_text_match;
+    use fast_dav_rs::webdav::{MatchType, TextMatch};
+
+    let filter = build_addressbook_query_filter_text_match(
+        "FN",
+        &TextMatch::new("Ada & co")
+            .match_type(MatchType::StartsWith)
+            .collation("i;octet"),
+    );
+    assert_eq!(
+        filter,
+        "<C:filter><C:prop-filter name=\"FN\"><C:text-match collation=\"i;octet\" match-type=\"starts-with\">Ada &amp; co</C:text-match></C:prop-filter></C:filter>"
+    );
+
+    // The shorthands keep their case-insensitive exact match.
+    assert_eq!(
+        fast_dav_rs::carddav::client::build_addressbook_query_filter_uid("user-123"),
+        "<C:filter><C:prop-filter name=\"UID\"><C:text-match collation=\"i;unicode-casemap\" match-type=\"equals\">user-123</C:text-match></C:prop-filter></C:filter>"
+    );
+}
+
+#[tokio::test]
+async fn test_export_addressbook_fails_when_listing_fails() {
+    let client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    assert!(
+        client
+            .export_addressbook("/addressbooks/jane/contacts/")
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn test_resource_limits_report_typed_errors() {
+    use fast_dav_rs::carddav::{ResourceLimits, SizeLimit, TooLargeError};
+
+    let limits = ResourceLimits {
+        max_resource_size: Some(10),
+        max_image_size: Some(4),
+    };
+    assert!(limits.check_vcard(b"0123456789").is_ok());
+    let err = limits.check_vcard(b"0123456789X").unwrap_err();
+    let too_large = err.downcast_ref::<TooLargeError>().expect("typed error");
+    assert_eq!(too_large.limit_kind, SizeLimit::Resource);
+    assert_eq!((too_large.size, too_large.limit), (11, 10));
+    assert_eq!(
+        err.to_string(),
+        "vCard is 11 octets, larger than the server limit of 10 octets"
+    );
+
+    let err = limits.check_image(b"12345").unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<TooLargeError>().unwrap().limit_kind,
+        SizeLimit::Image
+    );
+    assert!(ResourceLimits::default().check_vcard(&[0; 1 << 20]).is_ok());
+}
+
+#[tokio::test]
+async fn test_put_helpers_enforce_resource_limits_before_network() {
+    use fast_dav_rs::carddav::{ResourceLimits, TooLargeError};
+
+    let mut client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    client.set_resource_limits(ResourceLimits {
+        max_resource_size: Some(8),
+        max_image_size: None,
+    });
+    let card = bytes::Bytes::from_static(b"BEGIN:VCARD\r\nEND:VCARD\r\n");
+    for result in [
+        client.put("a.vcf", card.clone()).await,
+        client.put_if_match("a.vcf", card.clone(), "\"1\"").await,
+        client.put_if_none_match("a.vcf", card.clone()).await,
+    ] {
+        assert!(
+            result
+                .unwrap_err()
+                .downcast_ref::<TooLargeError>()
+                .is_some()
+        );
+    }
+}
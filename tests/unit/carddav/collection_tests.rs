@@ -0,0 +1,86 @@
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::carddav::{AddressBookPropPatch, MkAddressBook};
+use fast_dav_rs::webdav::parse_proppatch_response;
+
+#[test]
+fn mkaddressbook_builds_all_properties() {
+    let body = MkAddressBook::new()
+        .displayname("Team & Friends")
+        .description("Shared <contacts>")
+        .address_data_type("text/vcard", "3.0")
+        .address_data_type("text/vcard", "4.0")
+        .build();
+
+    assert!(body.starts_with(
+        r#"<C:mkaddressbook xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav"><D:set><D:prop>"#
+    ));
+    assert!(body.contains("<D:displayname>Team &amp; Friends</D:displayname>"));
+    assert!(body.contains(
+        "<C:addressbook-description>Shared &lt;contacts&gt;</C:addressbook-description>"
+    ));
+    assert!(body.contains(
+        r#"<C:supported-address-data><C:address-data-type content-type="text/vcard" version="3.0"/><C:address-data-type content-type="text/vcard" version="4.0"/></C:supported-address-data>"#
+    ));
+}
+
+#[test]
+fn mkaddressbook_without_properties_is_empty_request() {
+    let body = MkAddressBook::new().build();
+    assert!(body.starts_with("<C:mkaddressbook "));
+    assert!(body.ends_with("/>"));
+    assert!(!body.contains("<D:set>"));
+}
+
+#[test]
+fn extended_mkcol_declares_addressbook_resourcetype() {
+    let body = MkAddressBook::new()
+        .displayname("Team")
+        .build_extended_mkcol();
+    assert!(body.starts_with("<D:mkcol "));
+    assert!(body.contains(
+        "<D:resourcetype><D:collection/><C:addressbook/></D:resourcetype><D:displayname>Team</D:displayname>"
+    ));
+}
+
+#[test]
+fn addressbook_proppatch_sets_name_and_description() {
+    let patch = AddressBookPropPatch::new()
+        .displayname("Renamed")
+        .description("Desc");
+    assert_eq!(
+        patch.build(),
+        r#"<D:propertyupdate xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav"><D:set><D:prop><D:displayname>Renamed</D:displayname><C:addressbook-description>Desc</C:addressbook-description></D:prop></D:set></D:propertyupdate>"#
+    );
+    assert!(!patch.is_empty());
+    assert!(AddressBookPropPatch::new().is_empty());
+}
+
+#[test]
+fn addressbook_proppatch_result_reports_rejected_properties() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:response>
+    <D:href>/addressbooks/jane/team/</D:href>
+    <D:propstat>
+      <D:prop><D:displayname/></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+    <D:propstat>
+      <D:prop><C:addressbook-description/></D:prop>
+      <D:status>HTTP/1.1 403 Forbidden</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let result = parse_proppatch_response(xml.as_bytes()).unwrap();
+    assert!(!result.is_success());
+    assert_eq!(result.failed().count(), 1);
+}
+
+#[tokio::test]
+async fn proppatch_addressbook_rejects_empty_patch() {
+    let client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .proppatch_addressbook("addressbooks/jane/team/", &AddressBookPropPatch::new())
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("at least one property"));
+}
@@ -0,0 +1,124 @@
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::carddav::{ImportOutcome, card_uid, href_for_uid, split_vcards};
+
+const TWO_CARDS: &str = "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:a\r\nFN:Ada\r\nEND:VCARD\r\n\
+\r\n\
+begin:vcard\r\nVERSION:3.0\r\nitem1.UID:urn:uuid:b\r\nFN:Bob\r\n\
+AGENT:BEGIN:VCARD\r\nBEGIN:VCARD\r\nFN:Assistant\r\nEND:VCARD\r\nend:vcard\r\n";
+
+#[test]
+fn splits_cards_and_keeps_them_verbatim() {
+    let cards = split_vcards(TWO_CARDS).unwrap();
+    assert_eq!(cards.len(), 2);
+    assert_eq!(
+        cards[0],
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:a\r\nFN:Ada\r\nEND:VCARD\r\n"
+    );
+    assert!(cards[1].starts_with("begin:vcard\r\n"));
+    assert!(cards[1].contains("FN:Assistant\r\nEND:VCARD\r\nend:vcard\r\n"));
+}
+
+#[test]
+fn rejects_unbalanced_cards() {
+    assert!(split_vcards("BEGIN:VCARD\r\nUID:a\r\n").is_err());
+    assert!(split_vcards("UID:a\r\nEND:VCARD\r\n").is_err());
+    assert!(split_vcards("").unwrap().is_empty());
+}
+
+#[test]
+fn reads_uid_through_groups_and_folding() {
+    let cards = split_vcards(TWO_CARDS).unwrap();
+    assert_eq!(card_uid(&cards[0]).as_deref(), Some("a"));
+    assert_eq!(card_uid(&cards[1]).as_deref(), Some("urn:uuid:b"));
+    assert_eq!(
+        card_uid("BEGIN:VCARD\r\nUID:long-\r\n uid\r\nEND:VCARD\r\n").as_deref(),
+        Some("long-uid")
+    );
+    assert_eq!(card_uid("BEGIN:VCARD\r\nUID: \r\nEND:VCARD\r\n"), None);
+}
+
+#[test]
+fn builds_safe_hrefs_from_uids() {
+    assert_eq!(
+        href_for_uid("/addressbooks/jane/contacts/", "urn:uuid:1234"),
+        "/addressbooks/jane/contacts/1234.vcf"
+    );
+    assert_eq!(
+        href_for_uid("contacts", "jane@example.com"),
+        "contacts/jane@example.com.vcf"
+    );
+    assert_eq!(href_for_uid("c/", "a b/é"), "c/a%20b%2F%C3%A9.vcf");
+}
+
+#[tokio::test]
+async fn import_reports_each_card_in_order() {
+    let client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let vcf = "BEGIN:VCARD\r\nUID:a\r\nEND:VCARD\r\n\
+               BEGIN:VCARD\r\nFN:No UID\r\nEND:VCARD\r\n\
+               BEGIN:VCARD\r\nUID:a\r\nEND:VCARD\r\n";
+    let results = client.import_vcards("contacts/", vcf, 2).await.unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].href.as_deref(), Some("contacts/a.vcf"));
+    assert!(matches!(results[0].outcome, ImportOutcome::Failed(_)));
+    assert_eq!(results[1].uid, None);
+    assert_eq!(
+        results[1].outcome,
+        ImportOutcome::Failed("card has no UID".to_string())
+    );
+    assert_eq!(results[2].index, 2);
+    assert_eq!(results[2].outcome, ImportOutcome::Duplicate);
+}
+
+#[tokio::test]
+async fn import_rejects_malformed_files_before_uploading() {
+    let client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    assert!(
+        client
+            .import_vcards("contacts/", "BEGIN:VCARD\r\nUID:a\r\n", 4)
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn duplicate_filter_matches_uid_emails_and_phones() {
+    use fast_dav_rs::carddav::{duplicate_filter, parse_vcard};
+
+    let contact = parse_vcard(
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:jane-1\r\nFN:Jane\r\n\
+         EMAIL;TYPE=WORK:jane@work.example\r\nEMAIL:jane@home.example\r\n\
+         TEL:+1 555 0100\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        duplicate_filter(&contact).unwrap().to_xml(),
+        "<C:filter test=\"anyof\">\
+         <C:prop-filter name=\"UID\" test=\"allof\"><C:text-match collation=\"i;octet\" match-type=\"equals\">jane-1</C:text-match></C:prop-filter>\
+         <C:prop-filter name=\"EMAIL\" test=\"anyof\"><C:text-match collation=\"i;unicode-casemap\" match-type=\"equals\">jane@work.example</C:text-match><C:text-match collation=\"i;unicode-casemap\" match-type=\"equals\">jane@home.example</C:text-match></C:prop-filter>\
+         <C:prop-filter name=\"TEL\" test=\"anyof\"><C:text-match collation=\"i;octet\" match-type=\"equals\">+1 555 0100</C:text-match></C:prop-filter>\
+         </C:filter>"
+    );
+
+    let anonymous =
+        parse_vcard("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Nobody\r\nEND:VCARD\r\n").unwrap();
+    assert!(duplicate_filter(&anonymous).is_none());
+}
+
+#[tokio::test]
+async fn find_duplicates_without_keys_skips_the_query() {
+    use fast_dav_rs::carddav::parse_vcard;
+
+    let client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let contact = parse_vcard("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Nobody\r\nEND:VCARD\r\n").unwrap();
+    assert!(
+        client
+            .find_duplicates("contacts/", &contact)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let keyed = parse_vcard("BEGIN:VCARD\r\nVERSION:4.0\r\nUID:a\r\nEND:VCARD\r\n").unwrap();
+    assert!(client.find_duplicates("contacts/", &keyed).await.is_err());
+}
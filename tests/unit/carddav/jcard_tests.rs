@@ -0,0 +1,85 @@
+use fast_dav_rs::carddav::{
+    AddressData, AddressObject, jcard_to_component, jcard_to_vcf, parse_jcard,
+};
+
+// RFC 7095 §3.4/§3.5 style document with the common value types.
+const JCARD: &str = r#"["vcard", [
+  ["version", {}, "text", "4.0"],
+  ["uid", {}, "uri", "urn:uuid:4fbe8971-0bc3-424c-9c26-36c3e1eff6b1"],
+  ["fn", {}, "text", "Simon Perreault"],
+  ["n", {}, "text", ["Perreault", "Simon", "", "", ["ing. jr", "M.Sc."]]],
+  ["nickname", {}, "text", "Simo", "Si; mon"],
+  ["bday", {}, "date-and-or-time", "--02-03"],
+  ["anniversary", {}, "date-and-or-time", "2009-08-08T14:30:00-05:00"],
+  ["email", {"type": "work", "pref": 1}, "text", "simon.perreault@viagenie.ca"],
+  ["tel", {"group": "item1", "type": ["work", "voice"], "pref": "1"}, "uri", "tel:+1-418-656-9254;ext=102"],
+  ["org", {}, "text", ["Viagenie", "R&D"]],
+  ["geo", {"type": "work"}, "uri", "geo:46.772673,-71.282945"],
+  ["tz", {}, "utc-offset", "-05:00"]
+]]"#;
+
+#[test]
+fn converts_jcard_to_vcard_text() {
+    let vcf = jcard_to_vcf(JCARD).unwrap();
+    assert!(vcf.starts_with("BEGIN:VCARD\r\nVERSION:4.0\r\n"));
+    assert!(vcf.contains("UID;VALUE=uri:urn:uuid:4fbe8971-0bc3-424c-9c26-36c3e1eff6b1\r\n"));
+    assert!(vcf.contains("N:Perreault;Simon;;;ing. jr,M.Sc.\r\n"));
+    assert!(vcf.contains("NICKNAME:Simo,Si\\; mon\r\n"));
+    assert!(vcf.contains("BDAY:--0203\r\n"));
+    assert!(vcf.contains("ANNIVERSARY:20090808T143000-0500\r\n"));
+    assert!(
+        vcf.contains("ITEM1.TEL;PREF=1;TYPE=work,voice;VALUE=uri:tel:+1-418-656-9254;ext=102\r\n")
+    );
+    assert!(vcf.contains("ORG:Viagenie;R&D\r\n"));
+    assert!(vcf.contains("GEO;TYPE=work:geo:46.772673,-71.282945\r\n"));
+    assert!(vcf.contains("TZ;VALUE=utc-offset:-0500\r\n"));
+    assert!(vcf.ends_with("END:VCARD\r\n"));
+}
+
+#[test]
+fn parses_jcard_into_typed_contact() {
+    let contact = parse_jcard(JCARD).unwrap();
+    assert_eq!(contact.version.as_deref(), Some("4.0"));
+    assert_eq!(contact.formatted_name.as_deref(), Some("Simon Perreault"));
+    assert_eq!(contact.name.as_ref().unwrap().suffixes, "ing. jr,M.Sc.");
+    assert_eq!(contact.nicknames, ["Simo", "Si; mon"]);
+    assert_eq!(contact.emails[0].value, "simon.perreault@viagenie.ca");
+    assert_eq!(contact.emails[0].pref, Some(1));
+    assert_eq!(contact.phones[0].types, ["work", "voice"]);
+    assert_eq!(contact.organization.as_ref().unwrap().units, ["R&D"]);
+}
+
+#[test]
+fn address_objects_with_jcard_data_parse_transparently() {
+    let object = AddressObject {
+        href: "/addressbooks/simon/contacts/simon.vcf".to_string(),
+        etag: None,
+        address_data: Some(format!("  {JCARD}")),
+        status: None,
+    };
+    let contact = object.parse_vcard().unwrap();
+    assert_eq!(
+        contact.uid.as_deref(),
+        Some("urn:uuid:4fbe8971-0bc3-424c-9c26-36c3e1eff6b1")
+    );
+}
+
+#[test]
+fn address_data_requests_jcard() {
+    assert_eq!(
+        AddressData::new().json().to_xml(),
+        r#"<C:address-data content-type="application/vcard+json" version="4.0"/>"#
+    );
+    assert_eq!(
+        AddressData::new().json().props(["FN"]).to_xml(),
+        r#"<C:address-data content-type="application/vcard+json" version="4.0"><C:prop name="FN"/></C:address-data>"#
+    );
+}
+
+#[test]
+fn rejects_malformed_jcard() {
+    assert!(jcard_to_component("{}").is_err());
+    assert!(jcard_to_component(r#"["vcalendar", []]"#).is_err());
+    assert!(jcard_to_component(r#"["vcard", [["fn", {}]]]"#).is_err());
+    assert!(jcard_to_component("not json").is_err());
+}
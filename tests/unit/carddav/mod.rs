@@ -1,8 +1,15 @@
 pub mod carddav_helpers;
 pub mod client_tests;
+pub mod collection_tests;
 pub mod etag_tests;
+pub mod import_tests;
 pub mod integration_tests;
+pub mod jcard_tests;
 pub mod parser_edge_cases;
 pub mod parser_tests;
+pub mod phone_tests;
+pub mod photo_tests;
+pub mod query_tests;
 pub mod streaming_tests;
+pub mod vcard_tests;
 pub mod xml_helper_tests;
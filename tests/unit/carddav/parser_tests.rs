@@ -151,3 +151,93 @@ fn parse_multistatus_preserves_multiline_address_data() {
     let data = item.address_data.as_ref().expect("address data present");
     assert_eq!(data, "BEGIN:VCARD\nEND:VCARD\n");
 }
+
+#[test]
+fn parse_multistatus_extracts_directory_properties() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:response>
+    <D:href>/principals/users/jane/</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:principal-address><D:href>/addressbooks/jane/me.vcf</D:href></C:principal-address>
+        <C:directory-gateway>
+          <D:href>/directory/</D:href>
+          <D:href>/directory/partners/</D:href>
+        </C:directory-gateway>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(
+        items[0].principal_address.as_deref(),
+        Some("/addressbooks/jane/me.vcf")
+    );
+    assert_eq!(
+        items[0].directory_gateway,
+        vec!["/directory/", "/directory/partners/"]
+    );
+}
+
+#[test]
+fn map_directory_search_reports_truncation() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:response>
+    <D:href>/directory/a.vcf</D:href>
+    <D:propstat>
+      <D:prop><D:getetag>"1"</D:getetag></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/directory/b.vcf</D:href>
+    <D:propstat>
+      <D:prop><D:getetag>"2"</D:getetag></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/directory/</D:href>
+    <D:status>HTTP/1.1 507 Insufficient Storage</D:status>
+  </D:response>
+</D:multistatus>"#;
+
+    let items = || parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    let search = fast_dav_rs::carddav::map_directory_search(items(), 10);
+    assert!(search.truncated);
+    let hrefs: Vec<_> = search.objects.iter().map(|o| o.href.as_str()).collect();
+    assert_eq!(hrefs, ["/directory/a.vcf", "/directory/b.vcf"]);
+
+    // Servers that ignore the limit are cut down to it.
+    let search = fast_dav_rs::carddav::map_directory_search(items(), 1);
+    assert!(search.truncated);
+    assert_eq!(search.objects.len(), 1);
+
+    let complete = items().into_iter().take(2).collect();
+    assert!(!fast_dav_rs::carddav::map_directory_search(complete, 10).truncated);
+}
+
+#[test]
+fn parse_multistatus_extracts_size_limits() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav" xmlns:CS="http://calendarserver.org/ns/">
+  <D:response>
+    <D:href>/addressbooks/jane/contacts/</D:href>
+    <D:propstat>
+      <D:prop>
+        <C:max-resource-size> 102400 </C:max-resource-size>
+        <CS:max-image-size>65536</CS:max-image-size>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(items[0].max_resource_size, Some(102_400));
+    assert_eq!(items[0].max_image_size, Some(65_536));
+}
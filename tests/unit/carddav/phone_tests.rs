@@ -0,0 +1,113 @@
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::carddav::{normalize_phone, phone_filter, phone_numbers_match, phone_variants};
+
+use crate::mock_server::serve_once;
+
+#[test]
+fn normalizes_common_formats() {
+    assert_eq!(
+        normalize_phone(" +1 (555) 010-0123 ").as_deref(),
+        Some("+15550100123")
+    );
+    assert_eq!(
+        normalize_phone("TEL:+1-555-010-0123").as_deref(),
+        Some("+15550100123")
+    );
+    assert_eq!(
+        normalize_phone("0044 20 7946 0958").as_deref(),
+        Some("+442079460958")
+    );
+    assert_eq!(
+        normalize_phone("555.010.0123").as_deref(),
+        Some("5550100123")
+    );
+    assert_eq!(normalize_phone("ext"), None);
+    assert_eq!(normalize_phone(""), None);
+}
+
+#[test]
+fn variants_include_e164_digits_and_tail() {
+    assert_eq!(phone_variants("(555) 010-0123"), ["5550100123", "23"]);
+    assert_eq!(phone_variants("01 23 45 67 89"), ["0123456789", "89"]);
+    assert_eq!(phone_variants("12"), ["12"]);
+    assert!(phone_variants("n/a").is_empty());
+}
+
+#[test]
+fn matches_numbers_across_formats() {
+    assert!(phone_numbers_match(
+        "+1 555 010 0123",
+        "tel:+1-555-010-0123"
+    ));
+    assert!(phone_numbers_match("+1 555 010 0123", "(555) 010-0123"));
+    assert!(phone_numbers_match("+44 20 7946 0958", "020 7946 0958"));
+    assert!(!phone_numbers_match("+1 555 010 0123", "+1 555 010 0124"));
+    assert!(!phone_numbers_match("0123", "555 010 0123"));
+    assert!(!phone_numbers_match("", ""));
+}
+
+#[test]
+fn phone_filter_uses_contains_on_tel() {
+    assert_eq!(
+        phone_filter("+1 555-010-0123").unwrap().to_xml(),
+        "<C:filter test=\"allof\"><C:prop-filter name=\"TEL\" test=\"anyof\">\
+         <C:text-match collation=\"i;octet\" match-type=\"contains\">+15550100123</C:text-match>\
+         <C:text-match collation=\"i;octet\" match-type=\"contains\">15550100123</C:text-match>\
+         <C:text-match collation=\"i;octet\" match-type=\"contains\">23</C:text-match>\
+         </C:prop-filter></C:filter>"
+    );
+    assert!(phone_filter("none").is_none());
+}
+
+#[tokio::test]
+async fn addressbook_query_phone_rejects_numbers_without_digits() {
+    let client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .addressbook_query_phone("contacts/", "unknown", false)
+        .await
+        .expect_err("validation happens before network I/O");
+    assert!(err.to_string().contains("no digits"));
+}
+
+#[tokio::test]
+async fn addressbook_query_phone_finds_grouped_numbers() {
+    let (base, server) = serve_once(
+        concat!(
+            "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+            r#"<d:multistatus xmlns:d="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav">
+<d:response><d:href>/contacts/jane.vcf</d:href><d:propstat><d:prop>
+<d:getetag>"1"</d:getetag>
+<card:address-data>BEGIN:VCARD&#13;
+VERSION:3.0&#13;
+FN:Jane&#13;
+TEL;TYPE=work:01 23 45 67 89&#13;
+END:VCARD&#13;
+</card:address-data>
+</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:response><d:href>/contacts/john.vcf</d:href><d:propstat><d:prop>
+<d:getetag>"2"</d:getetag>
+<card:address-data>BEGIN:VCARD&#13;
+VERSION:3.0&#13;
+FN:John&#13;
+TEL:06.11.22.33.89&#13;
+END:VCARD&#13;
+</card:address-data>
+</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#
+        ),
+        b"</C:addressbook-query>",
+    )
+    .await;
+    let mut client = CardDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let cards = client
+        .addressbook_query_phone("contacts/", "+33 1 23 45 67 89", false)
+        .await
+        .unwrap();
+    let request = String::from_utf8(server.await.unwrap()).unwrap();
+
+    assert!(request.contains(r#"match-type="contains">89</C:text-match>"#));
+    assert_eq!(cards.len(), 1);
+    assert_eq!(cards[0].href, "/contacts/jane.vcf");
+    assert!(cards[0].address_data.is_none());
+}
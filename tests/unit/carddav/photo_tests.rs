@@ -0,0 +1,99 @@
+use bytes::Bytes;
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::carddav::{Photo, PhotoSource, parse_vcard, photo_stream, replace_photo};
+use futures::StreamExt;
+
+const CARD3: &str =
+    "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:jane\r\nFN:Jane Doe\r\nN:Doe;Jane;;;\r\nEND:VCARD\r\n";
+const CARD4: &str = "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:jane\r\nFN:Jane Doe\r\n\
+PHOTO:https://example.com/jane.png\r\nEMAIL:jane@example.com\r\nEND:VCARD\r\n";
+
+fn image(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+fn inline(vcf: Bytes) -> (Option<String>, fast_dav_rs::carddav::PhotoChunks) {
+    match photo_stream(vcf).unwrap() {
+        Some(PhotoSource::Inline { media_type, data }) => (media_type, data),
+        other => panic!("expected an inline photo, got {other:?}"),
+    }
+}
+
+#[test]
+fn adds_a_legacy_photo_to_a_vcard_3() {
+    let jpeg = image(1000);
+    let vcf = replace_photo(CARD3.as_bytes(), "image/jpeg", &jpeg).unwrap();
+    let text = std::str::from_utf8(&vcf).unwrap();
+    assert!(text.contains("N:Doe;Jane;;;\r\nPHOTO;ENCODING=b;TYPE=JPEG:"));
+    assert!(text.ends_with("\r\nEND:VCARD\r\n"));
+    assert!(text.split("\r\n").all(|line| line.len() <= 75));
+
+    let (media_type, data) = inline(vcf.clone());
+    assert_eq!(media_type.as_deref(), Some("image/jpeg"));
+    assert_eq!(data.collect_bytes().unwrap(), jpeg);
+    assert!(matches!(
+        parse_vcard(text).unwrap().photo,
+        Some(Photo::Inline { .. })
+    ));
+}
+
+#[test]
+fn replaces_a_vcard_4_photo_in_place() {
+    let png = image(10);
+    let vcf = replace_photo(CARD4.as_bytes(), "image/png", &png).unwrap();
+    let text = std::str::from_utf8(&vcf).unwrap();
+    assert!(text.contains("FN:Jane Doe\r\nPHOTO:data:image/png;base64,"));
+    assert!(text.contains("\r\nEMAIL:jane@example.com\r\n"));
+    assert!(!text.contains("https://example.com/jane.png"));
+    assert_eq!(text.matches("PHOTO").count(), 1);
+
+    let (_, data) = inline(vcf);
+    assert_eq!(data.collect_bytes().unwrap(), png);
+}
+
+#[tokio::test]
+async fn streams_large_photos_in_chunks() {
+    let jpeg = image(200_000);
+    let vcf = replace_photo(CARD3.as_bytes(), "image/jpeg", &jpeg).unwrap();
+    let (_, data) = inline(vcf);
+
+    let chunks: Vec<Bytes> = data.into_stream().map(Result::unwrap).collect().await;
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks.concat(), jpeg);
+}
+
+#[test]
+fn locates_uri_and_missing_photos() {
+    match photo_stream(Bytes::from(CARD4)).unwrap() {
+        Some(PhotoSource::Uri(uri)) => assert_eq!(uri, "https://example.com/jane.png"),
+        other => panic!("expected a URI photo, got {other:?}"),
+    }
+    assert!(photo_stream(Bytes::from(CARD3)).unwrap().is_none());
+}
+
+#[test]
+fn rejects_invalid_photo_data() {
+    let vcf = CARD3.replace(
+        "END:VCARD",
+        "PHOTO;ENCODING=b;TYPE=JPEG:not*base64\r\nEND:VCARD",
+    );
+    let (_, mut data) = inline(Bytes::from(vcf));
+    assert!(data.next().unwrap().is_err());
+
+    assert!(replace_photo(b"BEGIN:VCARD\r\nFN:x\r\n", "image/png", b"x").is_err());
+}
+
+#[tokio::test]
+async fn set_photo_enforces_the_size_limit_before_sending() {
+    let client = CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .set_photo(
+            "/card/jane.vcf",
+            "image/jpeg",
+            Bytes::from(image(2048)),
+            1024,
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("more than the 1024 allowed"));
+}
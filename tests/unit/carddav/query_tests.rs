@@ -0,0 +1,189 @@
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::carddav::{
+    AddressData, AddressbookFilter, ParamFilter, PropFilter, build_addressbook_multiget_body_with,
+};
+use fast_dav_rs::webdav::{MatchType, TextMatch};
+
+fn client() -> CardDavClient {
+    CardDavClient::new("http://127.0.0.1:9/", None, None).unwrap()
+}
+
+#[test]
+fn builds_a_fluent_addressbook_query() {
+    let client = client();
+    let body = client
+        .query("contacts/")
+        .prop("EMAIL")
+        .contains("@example.com")
+        .limit(50)
+        .include_data()
+        .build_body()
+        .unwrap();
+    assert_eq!(
+        body,
+        r#"<C:addressbook-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav"><D:prop><D:getetag/><C:address-data/></D:prop><C:filter test="allof"><C:prop-filter name="EMAIL" test="allof"><C:text-match match-type="contains">@example.com</C:text-match></C:prop-filter></C:filter><C:limit><C:nresults>50</C:nresults></C:limit></C:addressbook-query>"#
+    );
+}
+
+#[test]
+fn combines_conditions() {
+    let client = client();
+    let body = client
+        .query("contacts/")
+        .prop("FN")
+        .starts_with("Jane")
+        .prop("NICKNAME")
+        .equals("JD")
+        .prop("PHOTO")
+        .not_defined()
+        .any_of()
+        .build_body()
+        .unwrap();
+    assert!(body.contains(r#"<C:filter test="anyof">"#));
+    assert!(body.contains(r#"<C:text-match match-type="starts-with">Jane</C:text-match>"#));
+    assert!(body.contains(r#"<C:text-match match-type="equals">JD</C:text-match>"#));
+    assert!(body.contains(r#"<C:prop-filter name="PHOTO"><C:is-not-defined/></C:prop-filter>"#));
+    assert!(!body.contains("address-data"));
+
+    let everything = client.query("contacts/").build_body().unwrap();
+    assert!(everything.contains(r#"<C:filter test="allof"></C:filter>"#));
+}
+
+#[test]
+fn serializes_prop_and_param_filters() {
+    let filter = PropFilter::new("TEL")
+        .text_match(TextMatch::new("+33").match_type(MatchType::StartsWith))
+        .text_match(TextMatch::new("+32").match_type(MatchType::StartsWith))
+        .param_filter(ParamFilter::new("TYPE").is_not_defined())
+        .any_of();
+    assert_eq!(
+        filter.to_xml(),
+        r#"<C:prop-filter name="TEL" test="anyof"><C:text-match match-type="starts-with">+33</C:text-match><C:text-match match-type="starts-with">+32</C:text-match><C:param-filter name="TYPE"><C:is-not-defined/></C:param-filter></C:prop-filter>"#
+    );
+    assert_eq!(
+        PropFilter::new("ORG").to_xml(),
+        r#"<C:prop-filter name="ORG"/>"#
+    );
+    assert_eq!(
+        ParamFilter::new("TYPE").to_xml(),
+        r#"<C:param-filter name="TYPE"/>"#
+    );
+}
+
+#[tokio::test]
+async fn rejects_invalid_queries_before_sending() {
+    let client = client();
+    let err = client
+        .query("contacts/")
+        .prop("E MAIL")
+        .contains("x")
+        .execute()
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid addressbook-query property")
+    );
+
+    let err = client
+        .query("contacts/")
+        .limit(0)
+        .execute()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("limit"));
+}
+
+#[test]
+fn combines_prop_filters_with_allof_and_anyof() {
+    let smith_at_acme = AddressbookFilter::new()
+        .prop_filter(PropFilter::new("FN").text_match(TextMatch::new("smith")))
+        .prop_filter(
+            PropFilter::new("ORG").text_match(TextMatch::new("Acme").match_type(MatchType::Equals)),
+        );
+    assert_eq!(
+        smith_at_acme.to_xml(),
+        r#"<C:filter test="allof"><C:prop-filter name="FN" test="allof"><C:text-match>smith</C:text-match></C:prop-filter><C:prop-filter name="ORG" test="allof"><C:text-match match-type="equals">Acme</C:text-match></C:prop-filter></C:filter>"#
+    );
+    assert!(
+        smith_at_acme
+            .clone()
+            .any_of()
+            .to_xml()
+            .starts_with(r#"<C:filter test="anyof">"#)
+    );
+
+    let client = client();
+    let fluent = client
+        .query("contacts/")
+        .prop("FN")
+        .text_match(TextMatch::new("smith"))
+        .prop("ORG")
+        .text_match(TextMatch::new("Acme").match_type(MatchType::Equals))
+        .build_body()
+        .unwrap();
+    assert_eq!(
+        fluent,
+        fast_dav_rs::carddav::build_addressbook_query_body(&smith_at_acme.to_xml(), false)
+    );
+
+    let invalid = AddressbookFilter::new().prop_filter(PropFilter::new(""));
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn selects_address_data_properties() {
+    let client = client();
+    let body = client
+        .query("contacts/")
+        .select_props(["FN", "EMAIL", "TEL"])
+        .build_body()
+        .unwrap();
+    assert!(body.contains(
+        r#"<D:prop><D:getetag/><C:address-data><C:prop name="FN"/><C:prop name="EMAIL"/><C:prop name="TEL"/></C:address-data></D:prop>"#
+    ));
+
+    let versioned = AddressData::new().version("4.0");
+    assert_eq!(
+        versioned.to_xml(),
+        r#"<C:address-data content-type="text/vcard" version="4.0"/>"#
+    );
+
+    let multiget = build_addressbook_multiget_body_with(
+        ["/contacts/a.vcf"],
+        Some(&AddressData::new().props(["FN"])),
+    )
+    .unwrap();
+    assert!(multiget.contains(r#"<C:address-data><C:prop name="FN"/></C:address-data></D:prop><D:href>/contacts/a.vcf</D:href>"#));
+    assert_eq!(
+        build_addressbook_multiget_body_with(["/contacts/a.vcf"], None).unwrap(),
+        fast_dav_rs::carddav::build_addressbook_multiget_body(["/contacts/a.vcf"], false).unwrap()
+    );
+
+    let err = client
+        .query("contacts/")
+        .select_props(["PHOTO;X"])
+        .build_body()
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid address-data property"));
+}
+
+#[tokio::test]
+async fn directory_search_validates_before_sending() {
+    let client = client();
+    let filter = AddressbookFilter::new().prop_filter(
+        PropFilter::new("FN").text_match(TextMatch::new("smi").match_type(MatchType::StartsWith)),
+    );
+    let err = client
+        .directory_search("/directory/", &filter, None, 0)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("limit must be at least 1"));
+
+    let data = AddressData::new().props(["F N"]);
+    let err = client
+        .directory_search("/directory/", &filter, Some(&data), 25)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid address-data property"));
+}
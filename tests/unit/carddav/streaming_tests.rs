@@ -152,6 +152,59 @@ fn test_multistatus_visit_error_propagates() {
     assert!(err.is_err(), "expected visitor error to propagate");
 }
 
+#[test]
+fn test_text_split_by_escapes_and_cdata_is_kept_whole() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:response>
+    <D:href> /q&amp;a </D:href>
+    <D:propstat><D:prop>
+      <C:address-data><![CDATA[BEGIN:X]]>
+<![CDATA[SUMMARY:a]]> &amp; b
+</C:address-data>
+    </D:prop></D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    let items = parse_multistatus_bytes(xml.as_bytes()).unwrap().items;
+    assert_eq!(items[0].href, "/q&a");
+    assert_eq!(
+        items[0].address_data.as_deref(),
+        Some("BEGIN:X\nSUMMARY:a & b\n")
+    );
+}
+
+#[test]
+fn test_multistatus_visit_can_stop_early() {
+    // The body is cut after the second response: stopping on the first never reaches it.
+    let xml = r#"<D:multistatus xmlns:D="DAV:">
+  <D:response><D:href>/contacts/a.vcf</D:href><D:status>HTTP/1.1 200 OK</D:status></D:response>
+  <D:response><D:href>/contacts/b.vcf</D:href><D:status>HTTP/1.1 200 OK</D:status></D:response>
+  <D:response><D:href>/contacts/c"#;
+
+    let mut visited = Vec::new();
+    let token = parse_multistatus_bytes_visit_until(xml.as_bytes(), |item| {
+        visited.push(item.href);
+        Ok(Visit::Stop)
+    })
+    .expect("stopped parse");
+    assert_eq!(visited, ["/contacts/a.vcf"]);
+    assert_eq!(token, None);
+
+    let mut visited = Vec::new();
+    let token = parse_multistatus_bytes_visit_until(xml.as_bytes(), |item| {
+        visited.push(item.href);
+        Ok(if visited.len() == 2 {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        })
+    })
+    .expect("stopped parse");
+    assert_eq!(visited.len(), 2);
+    assert_eq!(token, None);
+    assert!(parse_multistatus_bytes_visit(xml.as_bytes(), |_| Ok(())).is_err());
+}
+
 #[tokio::test]
 async fn test_streaming_stalled_body_times_out() -> Result<()> {
     let (client_io, mut server_io) = io::duplex(16 * 1024);
@@ -259,3 +312,169 @@ async fn test_streaming_preserves_multiline_address_data() -> Result<()> {
     assert_eq!(data, "BEGIN:VCARD\nEND:VCARD\n");
     Ok(())
 }
+
+const SYNC_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:response>
+    <D:href>/contacts/a.vcf</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:getetag>"a1"</D:getetag>
+        <C:address-data>BEGIN:VCARD&#13;
+VERSION:4.0&#13;
+UID:a&#13;
+END:VCARD&#13;
+</C:address-data>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/contacts/b.vcf</D:href>
+    <D:status>HTTP/1.1 404 Not Found</D:status>
+  </D:response>
+  <D:sync-token>http://example.com/sync/42</D:sync-token>
+</D:multistatus>"#;
+
+#[tokio::test]
+async fn test_sync_item_stream_yields_items_then_token() -> Result<()> {
+    use futures::TryStreamExt;
+
+    let mut stream = SyncItemStream::new(
+        std::io::Cursor::new(SYNC_XML.as_bytes().to_vec()),
+        Some("header-token".to_string()),
+        Duration::from_secs(5),
+    );
+    let first = stream.try_next().await?.expect("first item");
+    assert_eq!(first.href, "/contacts/a.vcf");
+    assert_eq!(first.etag.as_deref(), Some("\"a1\""));
+    assert!(first.address_data.unwrap().contains("UID:a"));
+    assert_eq!(stream.sync_token(), None, "token is only known at the end");
+
+    let second = stream.try_next().await?.expect("second item");
+    assert!(second.is_deleted);
+    assert!(stream.try_next().await?.is_none());
+    assert_eq!(
+        stream.sync_token().as_deref(),
+        Some("http://example.com/sync/42")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_item_stream_falls_back_to_header_token() -> Result<()> {
+    use futures::TryStreamExt;
+
+    let xml = SYNC_XML.replace(
+        "<D:sync-token>http://example.com/sync/42</D:sync-token>",
+        "",
+    );
+    let mut stream = SyncItemStream::new(
+        std::io::Cursor::new(xml.into_bytes()),
+        Some("header-token".to_string()),
+        Duration::from_secs(5),
+    );
+    let mut hrefs = Vec::new();
+    while let Some(item) = stream.try_next().await? {
+        hrefs.push(item.href);
+    }
+    assert_eq!(hrefs, ["/contacts/a.vcf", "/contacts/b.vcf"]);
+    assert_eq!(stream.sync_token().as_deref(), Some("header-token"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_item_stream_reports_truncated_xml() {
+    use futures::StreamExt;
+
+    let cut = &SYNC_XML[..SYNC_XML.find("<D:sync-token>").unwrap() - 20];
+    let stream = SyncItemStream::new(
+        std::io::Cursor::new(cut.as_bytes().to_vec()),
+        None,
+        Duration::from_secs(5),
+    );
+    let results: Vec<_> = stream.collect().await;
+    assert!(results.first().is_some_and(|r| r.is_ok()));
+    assert!(results.last().is_some_and(|r| r.is_err()));
+}
+
+#[tokio::test]
+async fn test_sync_item_stream_flags_507_truncation() -> Result<()> {
+    use futures::TryStreamExt;
+
+    let xml = SYNC_XML.replace(
+        "<D:sync-token>",
+        "<D:response><D:href>/contacts/</D:href><D:status>HTTP/1.1 507 Insufficient Storage</D:status></D:response><D:sync-token>",
+    );
+    let mut stream = SyncItemStream::new(
+        std::io::Cursor::new(xml.into_bytes()),
+        None,
+        Duration::from_secs(5),
+    );
+    assert!(!stream.truncated());
+    let mut hrefs = Vec::new();
+    while let Some(item) = stream.try_next().await? {
+        hrefs.push(item.href);
+    }
+    assert_eq!(hrefs, ["/contacts/a.vcf", "/contacts/b.vcf"]);
+    assert!(stream.truncated());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dav_item_stream_yields_items_before_the_body_ends() -> Result<()> {
+    use futures::TryStreamExt;
+
+    let (reader, mut writer) = io::duplex(4096);
+    let mut items = DavItemStream::new(io::BufReader::new(reader), Duration::from_secs(5));
+    writer
+        .write_all(
+            br#"<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/contacts/a.vcf</D:href>
+    <D:propstat><D:prop><D:getetag>"a1"</D:getetag></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+  </D:response>
+  <D:response>"#,
+        )
+        .await?;
+
+    // The first item is yielded while the rest of the body is still to come.
+    let first = items.try_next().await?.expect("first item");
+    assert_eq!(first.href, "/contacts/a.vcf");
+    assert_eq!(first.etag.as_deref(), Some("\"a1\""));
+
+    writer
+        .write_all(
+            br#"
+    <D:href>/contacts/b.vcf</D:href>
+    <D:status>HTTP/1.1 404 Not Found</D:status>
+  </D:response>
+  <D:sync-token>http://example.com/sync/9</D:sync-token>
+</D:multistatus>"#,
+        )
+        .await?;
+    drop(writer);
+
+    let second = items.try_next().await?.expect("second item");
+    assert_eq!(second.href, "/contacts/b.vcf");
+    assert!(items.try_next().await?.is_none());
+    assert_eq!(
+        items.sync_token().as_deref(),
+        Some("http://example.com/sync/9")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dav_item_stream_ends_after_an_error() {
+    use futures::StreamExt;
+
+    let xml = r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/contacts/a.vcf</D:href>"#;
+    let mut items = DavItemStream::new(
+        std::io::Cursor::new(xml.as_bytes().to_vec()),
+        Duration::from_secs(5),
+    );
+    assert!(items.next().await.expect("error").is_err());
+    assert!(items.next().await.is_none());
+    assert_eq!(items.sync_token(), None);
+}
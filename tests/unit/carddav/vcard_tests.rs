@@ -0,0 +1,180 @@
+use fast_dav_rs::carddav::{AddressObject, Photo, parse_vcard, vcard_to_v3, vcard_to_v4};
+
+const VCARD3: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nUID:jane-3@example.com\r\n\
+FN:Jane Doe\r\nN:Doe;Jane;Ann;Dr.;\r\nNICKNAME:JD,Janie\r\n\
+EMAIL;TYPE=INTERNET;TYPE=WORK;TYPE=pref:jane@work.example\r\n\
+EMAIL;TYPE=HOME:jane@home.example\r\nTEL;TYPE=CELL,VOICE:+1 555 0100\r\n\
+ORG:Example\\, Inc.;Research;Lab 2\r\nTITLE:Engineer\r\n\
+PHOTO;ENCODING=b;TYPE=JPEG:/9j/4AAQSkZJRg==\r\nEND:VCARD\r\n";
+
+const VCARD4: &str = "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:urn:uuid:jane-4\r\nKIND:individual\r\n\
+FN:Jane Doe\r\nEMAIL;TYPE=work;PREF=2:jane@work.example\r\n\
+TEL;VALUE=uri;TYPE=\"voice,cell\":tel:+1-555-0100\r\n\
+PHOTO:data:image/png;base64,iVBORw0KGgo=\r\nEND:VCARD\r\n";
+
+#[test]
+fn parses_vcard_3() {
+    let contact = parse_vcard(VCARD3).unwrap();
+    assert_eq!(contact.version.as_deref(), Some("3.0"));
+    assert_eq!(contact.uid.as_deref(), Some("jane-3@example.com"));
+    assert_eq!(contact.formatted_name.as_deref(), Some("Jane Doe"));
+
+    let name = contact.name.unwrap();
+    assert_eq!(
+        (
+            name.family.as_str(),
+            name.given.as_str(),
+            name.additional.as_str()
+        ),
+        ("Doe", "Jane", "Ann")
+    );
+    assert_eq!(name.prefixes, "Dr.");
+    assert_eq!(contact.nicknames, vec!["JD", "Janie"]);
+
+    assert_eq!(contact.emails.len(), 2);
+    assert_eq!(contact.emails[0].value, "jane@work.example");
+    assert_eq!(contact.emails[0].types, vec!["work"]);
+    assert_eq!(contact.emails[0].pref, Some(1));
+    assert_eq!(contact.emails[1].pref, None);
+    assert_eq!(contact.phones[0].types, vec!["cell", "voice"]);
+
+    let org = contact.organization.unwrap();
+    assert_eq!(org.name, "Example, Inc.");
+    assert_eq!(org.units, vec!["Research", "Lab 2"]);
+    assert_eq!(contact.title.as_deref(), Some("Engineer"));
+    assert_eq!(
+        contact.photo,
+        Some(Photo::Inline {
+            media_type: Some("image/jpeg".into()),
+            data: "/9j/4AAQSkZJRg==".into()
+        })
+    );
+}
+
+#[test]
+fn parses_vcard_4() {
+    let contact = parse_vcard(VCARD4).unwrap();
+    assert_eq!(contact.kind.as_deref(), Some("individual"));
+    assert!(contact.name.is_none());
+    assert_eq!(contact.emails[0].pref, Some(2));
+    assert_eq!(contact.phones[0].value, "tel:+1-555-0100");
+    assert_eq!(contact.phones[0].types, vec!["voice", "cell"]);
+    assert_eq!(
+        contact.photo,
+        Some(Photo::Inline {
+            media_type: Some("image/png".into()),
+            data: "iVBORw0KGgo=".into()
+        })
+    );
+
+    let uri = VCARD4.replace(
+        "PHOTO:data:image/png;base64,iVBORw0KGgo=",
+        "PHOTO:https://example.com/jane.png",
+    );
+    assert_eq!(
+        parse_vcard(&uri).unwrap().photo,
+        Some(Photo::Uri("https://example.com/jane.png".into()))
+    );
+}
+
+#[test]
+fn address_object_parses_its_data() {
+    let mut object = AddressObject {
+        href: "/card/jane.vcf".into(),
+        etag: None,
+        address_data: Some(VCARD3.into()),
+        status: None,
+    };
+    assert_eq!(
+        object.parse_vcard().unwrap().uid.as_deref(),
+        Some("jane-3@example.com")
+    );
+
+    object.address_data = None;
+    assert!(object.parse_vcard().is_err());
+}
+
+#[test]
+fn rejects_non_vcard_payloads() {
+    assert!(parse_vcard("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n").is_err());
+    assert!(parse_vcard("not a vcard").is_err());
+}
+
+#[test]
+fn converts_vcard_3_to_4() {
+    let v4 = vcard_to_v4(&VCARD3.replace(
+        "TITLE:Engineer\r\n",
+        "TITLE:Engineer\r\nGEO:37.38;-122.08\r\nX-ADDRESSBOOKSERVER-KIND:Group\r\n\
+         X-ADDRESSBOOKSERVER-MEMBER:urn:uuid:bob\r\nitem1.TEL;WORK;FAX:+1 555 0199\r\n",
+    ))
+    .unwrap();
+    assert!(v4.contains("VERSION:4.0\r\n"));
+    assert!(v4.contains("EMAIL;PREF=1;TYPE=work:jane@work.example\r\n"));
+    assert!(v4.contains("EMAIL;TYPE=home:jane@home.example\r\n"));
+    assert!(v4.contains("TEL;TYPE=cell,voice:+1 555 0100\r\n"));
+    assert!(v4.contains("ITEM1.TEL;TYPE=work,fax:+1 555 0199\r\n"));
+    assert!(v4.contains("PHOTO:data:image/jpeg;base64,/9j/4AAQSkZJRg==\r\n"));
+    assert!(v4.contains("GEO:geo:37.38,-122.08\r\n"));
+    assert!(v4.contains("KIND:group\r\n"));
+    assert!(v4.contains("MEMBER:urn:uuid:bob\r\n"));
+
+    let contact = parse_vcard(&v4).unwrap();
+    assert_eq!(contact.emails[0].pref, Some(1));
+    assert_eq!(
+        contact.photo,
+        parse_vcard(VCARD3).unwrap().photo,
+        "photo survives the conversion"
+    );
+}
+
+#[test]
+fn converts_vcard_4_to_3() {
+    let v3 = vcard_to_v3(&VCARD4.replace("KIND:individual\r\n", "GEO:geo:37.38,-122.08;u=10\r\n"))
+        .unwrap();
+    assert!(v3.contains("VERSION:3.0\r\n"));
+    assert!(v3.contains("FN:Jane Doe\r\nN:;;;;\r\n"));
+    assert!(v3.contains("EMAIL;TYPE=work:jane@work.example\r\n"));
+    assert!(v3.contains("TEL;TYPE=voice,cell:+1-555-0100\r\n"));
+    assert!(v3.contains("PHOTO;ENCODING=b;TYPE=PNG:iVBORw0KGgo=\r\n"));
+    assert!(v3.contains("GEO:37.38;-122.08\r\n"));
+    assert!(!v3.contains("KIND"));
+
+    let preferred = vcard_to_v3(&VCARD4.replace("PREF=2", "PREF=1")).unwrap();
+    assert!(preferred.contains("EMAIL;TYPE=work,pref:jane@work.example\r\n"));
+
+    let group = vcard_to_v3(&VCARD4.replace("KIND:individual", "KIND:group")).unwrap();
+    assert!(group.contains("X-ADDRESSBOOKSERVER-KIND:group\r\n"));
+}
+
+#[test]
+fn vcard_conversion_round_trips() {
+    let back = vcard_to_v3(&vcard_to_v4(VCARD3).unwrap()).unwrap();
+    let (original, converted) = (parse_vcard(VCARD3).unwrap(), parse_vcard(&back).unwrap());
+    assert_eq!(converted.emails, original.emails);
+    assert_eq!(converted.phones, original.phones);
+    assert_eq!(converted.photo, original.photo);
+    assert_eq!(converted.organization, original.organization);
+
+    assert_eq!(
+        vcard_to_v4(VCARD4).unwrap(),
+        parse_vcard(VCARD4).unwrap().raw.to_ics()
+    );
+    assert!(vcard_to_v4("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n").is_err());
+}
+
+#[test]
+fn vcard_version_converts_to_target() {
+    use fast_dav_rs::carddav::VCardVersion;
+
+    let v3 = VCardVersion::V3.convert(VCARD4).unwrap();
+    assert!(v3.contains("VERSION:3.0\r\n"));
+    assert_eq!(
+        VCardVersion::V4.convert(VCARD4).unwrap(),
+        vcard_to_v4(VCARD4).unwrap()
+    );
+    assert!(
+        VCardVersion::V4
+            .convert("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n")
+            .is_err()
+    );
+}
@@ -0,0 +1,164 @@
+use fast_dav_rs::webdav::{Ace, AceAction, AcePrincipal, Privilege, build_acl_body, parse_acl};
+
+const SABRE_ACL: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav" xmlns:s="http://sabredav.org/ns">
+  <d:response>
+    <d:href>/calendars/jane/work/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:acl>
+          <d:ace>
+            <d:principal><d:href>/principals/jane/</d:href></d:principal>
+            <d:grant><d:privilege><d:all/></d:privilege></d:grant>
+            <d:protected/>
+          </d:ace>
+          <d:ace>
+            <d:principal><d:property><d:owner/></d:property></d:principal>
+            <d:grant><d:privilege><d:read/></d:privilege></d:grant>
+            <d:inherited><d:href>/calendars/jane/</d:href></d:inherited>
+          </d:ace>
+          <d:ace>
+            <d:principal><d:authenticated/></d:principal>
+            <d:grant>
+              <d:privilege><cal:read-free-busy/></d:privilege>
+              <d:privilege><s:share/></d:privilege>
+            </d:grant>
+          </d:ace>
+          <d:ace>
+            <d:invert><d:principal><d:href>/principals/bob/</d:href></d:principal></d:invert>
+            <d:deny><d:privilege><d:write/></d:privilege></d:deny>
+          </d:ace>
+        </d:acl>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+#[test]
+fn parses_sabredav_acl() {
+    let aces = parse_acl(SABRE_ACL.as_bytes()).unwrap();
+    assert_eq!(aces.len(), 4);
+
+    assert_eq!(
+        aces[0].principal,
+        AcePrincipal::Href("/principals/jane/".into())
+    );
+    assert_eq!(aces[0].privileges, [Privilege::All]);
+    assert!(aces[0].protected);
+
+    assert_eq!(
+        aces[1].principal,
+        AcePrincipal::Property {
+            namespace: "DAV:".into(),
+            name: "owner".into()
+        }
+    );
+    assert_eq!(aces[1].inherited.as_deref(), Some("/calendars/jane/"));
+
+    assert_eq!(aces[2].principal, AcePrincipal::Authenticated);
+    assert_eq!(
+        aces[2].privileges,
+        [
+            Privilege::ReadFreeBusy,
+            Privilege::Other {
+                namespace: "http://sabredav.org/ns".into(),
+                name: "share".into()
+            }
+        ]
+    );
+
+    assert!(aces[3].invert);
+    assert_eq!(aces[3].action, AceAction::Deny);
+    assert_eq!(
+        aces[3].principal,
+        AcePrincipal::Href("/principals/bob/".into())
+    );
+}
+
+#[test]
+fn acl_body_skips_protected_and_inherited_entries() {
+    let mut aces = parse_acl(SABRE_ACL.as_bytes()).unwrap();
+    aces.push(Ace::grant(
+        AcePrincipal::Href("/principals/bob/".into()),
+        [Privilege::Read, Privilege::WriteContent],
+    ));
+    let body = build_acl_body(&aces).unwrap();
+
+    assert_eq!(body.matches("<D:ace>").count(), 3);
+    assert!(!body.contains("/principals/jane/"));
+    assert!(!body.contains("<D:owner/>"));
+    assert!(body.contains(
+        r#"<D:privilege><X:read-free-busy xmlns:X="urn:ietf:params:xml:ns:caldav"/></D:privilege>"#
+    ));
+    assert!(body.contains(
+        "<D:invert><D:principal><D:href>/principals/bob/</D:href></D:principal></D:invert><D:deny><D:privilege><D:write/></D:privilege></D:deny>"
+    ));
+    assert!(body.contains(
+        "<D:grant><D:privilege><D:read/></D:privilege><D:privilege><D:write-content/></D:privilege></D:grant>"
+    ));
+}
+
+#[test]
+fn acl_body_round_trips() {
+    let aces = vec![
+        Ace::deny(AcePrincipal::Unauthenticated, [Privilege::Read]).inverted(),
+        Ace::grant(
+            AcePrincipal::Property {
+                namespace: "DAV:".into(),
+                name: "owner".into(),
+            },
+            [Privilege::WriteAcl],
+        ),
+        Ace::grant(
+            AcePrincipal::SelfPrincipal,
+            [Privilege::Bind, Privilege::Unbind],
+        ),
+    ];
+    assert_eq!(
+        parse_acl(build_acl_body(&aces).unwrap().as_bytes()).unwrap(),
+        aces
+    );
+}
+
+#[test]
+fn rejects_malformed_acl() {
+    assert!(parse_acl(b"<d:acl xmlns:d=\"DAV:\"><d:ace></d:acl>").is_err());
+}
+
+#[test]
+fn acl_hrefs_with_escapes_are_kept_whole() {
+    let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/cal/</d:href>
+<d:propstat><d:prop><d:acl>
+  <d:ace>
+    <d:principal><d:href>/principals/a&amp;b/</d:href></d:principal>
+    <d:grant><d:privilege><d:read/></d:privilege></d:grant>
+    <d:inherited><d:href>/x&#38;y/</d:href></d:inherited>
+  </d:ace>
+</d:acl></d:prop></d:propstat></d:response></d:multistatus>"#;
+
+    let aces = parse_acl(xml.as_bytes()).unwrap();
+    assert_eq!(
+        aces[0].principal,
+        AcePrincipal::Href("/principals/a&b/".into())
+    );
+    assert_eq!(aces[0].inherited.as_deref(), Some("/x&y/"));
+}
+
+#[test]
+fn acl_body_rejects_invalid_names() {
+    let privilege = Ace::grant(
+        AcePrincipal::All,
+        [Privilege::from_name("urn:x", "x/><evil")],
+    );
+    assert!(build_acl_body(&[privilege]).is_err());
+
+    let property = Ace::grant(
+        AcePrincipal::Property {
+            namespace: "DAV:".into(),
+            name: "owner/><evil".into(),
+        },
+        [Privilege::Read],
+    );
+    assert!(build_acl_body(&[property]).is_err());
+}
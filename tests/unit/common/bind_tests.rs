@@ -0,0 +1,29 @@
+use fast_dav_rs::webdav::{build_bind_body, build_rebind_body, build_unbind_body};
+
+#[test]
+fn binding_bodies() {
+    assert_eq!(
+        build_bind_body("a&b.ics", "/calendars/jane/work/a&b.ics").unwrap(),
+        r#"<?xml version="1.0" encoding="utf-8"?><D:bind xmlns:D="DAV:"><D:segment>a&amp;b.ics</D:segment><D:href>/calendars/jane/work/a&amp;b.ics</D:href></D:bind>"#
+    );
+    assert_eq!(
+        build_unbind_body("old.ics").unwrap(),
+        r#"<?xml version="1.0" encoding="utf-8"?><D:unbind xmlns:D="DAV:"><D:segment>old.ics</D:segment></D:unbind>"#
+    );
+    assert!(
+        build_rebind_body("new.ics", "http://dav.example.com/cal/old.ics")
+            .unwrap()
+            .contains(
+                "<D:rebind xmlns:D=\"DAV:\"><D:segment>new.ics</D:segment><D:href>http://dav.example.com/cal/old.ics</D:href></D:rebind>"
+            )
+    );
+}
+
+#[test]
+fn segments_must_be_single_path_segments() {
+    for segment in ["", ".", "..", "a/b"] {
+        assert!(build_bind_body(segment, "/x").is_err(), "{segment:?}");
+        assert!(build_unbind_body(segment).is_err(), "{segment:?}");
+        assert!(build_rebind_body(segment, "/x").is_err(), "{segment:?}");
+    }
+}
@@ -0,0 +1,59 @@
+use fast_dav_rs::webdav::DavCapabilities;
+use hyper::HeaderMap;
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.append(*name, value.parse().unwrap());
+    }
+    headers
+}
+
+#[test]
+fn parses_repeated_dav_headers_and_extensions() {
+    let caps = DavCapabilities::from_headers(&headers(&[
+        (
+            "DAV",
+            "1, 2, 3, access-control, calendar-access, calendar-auto-schedule",
+        ),
+        (
+            "DAV",
+            "<http://apache.org/dav/propset/fs/1>, extended-mkcol, ADDRESSBOOK, bind",
+        ),
+    ]));
+    assert_eq!(caps.dav.len(), 10);
+    assert!(caps.is_webdav() && caps.supports_locking());
+    assert!(caps.supports_access_control());
+    assert!(caps.supports_calendar_access() && caps.supports_calendar_auto_schedule());
+    assert!(caps.supports_addressbook());
+    assert!(caps.supports_extended_mkcol() && caps.supports_bind());
+    assert!(caps.has("<http://apache.org/dav/propset/fs/1>"));
+    assert!(!caps.has("calendar"));
+}
+
+#[test]
+fn allow_lists_methods_case_insensitively() {
+    let caps =
+        DavCapabilities::from_headers(&headers(&[("Allow", "OPTIONS, GET, HEAD, propfind, PUT")]));
+    assert_eq!(caps.allow, ["OPTIONS", "GET", "HEAD", "PROPFIND", "PUT"]);
+    assert!(caps.allows("PROPFIND") && caps.allows("get"));
+    assert!(!caps.allows("REPORT"));
+    assert!(!caps.is_webdav());
+}
+
+#[test]
+fn missing_allow_header_allows_everything() {
+    let caps = DavCapabilities::from_headers(&HeaderMap::new());
+    assert!(caps.allows("REPORT"));
+    assert!(caps.dav.is_empty() && !caps.supports_basicsearch());
+}
+
+#[test]
+fn dasl_grammars() {
+    let caps = DavCapabilities::from_headers(&headers(&[(
+        "DASL",
+        "<DAV:basicsearch>, <http://example.com/ns/grammar>",
+    )]));
+    assert!(caps.supports_basicsearch());
+    assert_eq!(caps.dasl.len(), 2);
+}
@@ -1,5 +1,11 @@
 use bytes::Bytes;
+use fast_dav_rs::CalDavClient;
 use fast_dav_rs::compression::*;
+use fast_dav_rs::webdav::WebDavClient;
+use futures::TryStreamExt;
+use hyper::{HeaderMap, Method, header};
+
+use crate::mock_server::{serve_bytes, serve_chunked};
 
 #[tokio::test]
 async fn test_compress_payload_identity() {
@@ -108,3 +114,212 @@ async fn test_compress_empty_data() {
         );
     }
 }
+
+async fn serve_gzip(data: &[u8]) -> String {
+    let compressed = compress_payload(Bytes::copy_from_slice(data), ContentEncoding::Gzip)
+        .await
+        .unwrap();
+    let (base, _server) = serve_bytes(
+        "HTTP/1.1 200 OK\r\nContent-Encoding: gzip",
+        &compressed,
+        b"\r\n\r\n",
+    )
+    .await;
+    base
+}
+
+#[tokio::test]
+async fn test_decompression_with_small_buffers() {
+    let data: Vec<u8> = (0..200_000u32).flat_map(|i| i.to_le_bytes()).collect();
+    let base = serve_gzip(&data).await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.set_decompression_options(DecompressionOptions {
+        buffer_size: 64,
+        ..DecompressionOptions::default()
+    });
+
+    let resp = client.get("data").await.unwrap();
+    assert_eq!(resp.body().as_ref(), data.as_slice());
+}
+
+#[tokio::test]
+async fn test_decompression_stops_past_the_expansion_ratio() {
+    let data = vec![0u8; 8 * 1024 * 1024];
+    let options = DecompressionOptions {
+        max_expansion_ratio: Some(100),
+        ..DecompressionOptions::default()
+    };
+
+    let base = serve_gzip(&data).await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.set_decompression_options(options);
+    let err = client.get("bomb").await.unwrap_err();
+    let limit = err
+        .downcast_ref::<ExpansionLimitError>()
+        .unwrap_or_else(|| panic!("not an ExpansionLimitError: {err:#}"));
+    assert_eq!(limit.max_ratio, 100);
+    assert!(limit.decompressed > limit.compressed * 100);
+    assert!(limit.decompressed < data.len() as u64);
+
+    let base = serve_gzip(&data).await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.set_decompression_options(options);
+    let mut out = Vec::new();
+    let err = client.get_to_writer("bomb", &mut out).await.unwrap_err();
+    let io = err.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(io.kind(), std::io::ErrorKind::InvalidData);
+    assert!(io.get_ref().unwrap().is::<ExpansionLimitError>());
+}
+
+#[tokio::test]
+async fn test_expansion_ratio_allows_regular_bodies() {
+    // Highly compressible but below the threshold the ratio applies from.
+    let data = vec![b'a'; (EXPANSION_CHECK_THRESHOLD / 2) as usize];
+    let base = serve_gzip(&data).await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.set_decompression_options(DecompressionOptions {
+        max_expansion_ratio: Some(2),
+        ..DecompressionOptions::default()
+    });
+
+    let resp = client.get("small").await.unwrap();
+    assert_eq!(resp.body().len(), data.len());
+}
+
+#[tokio::test]
+async fn test_compress_payload_on_a_blocking_thread() {
+    let data = Bytes::from("BEGIN:VCALENDAR\r\n".repeat(4096));
+    for encoding in [
+        ContentEncoding::Br,
+        ContentEncoding::Gzip,
+        ContentEncoding::Zstd,
+    ] {
+        let blocking = compress_payload_with(data.clone(), encoding, Some(0))
+            .await
+            .unwrap();
+        let inline = compress_payload_with(data.clone(), encoding, None)
+            .await
+            .unwrap();
+        assert_eq!(blocking, inline, "{encoding:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_decompression_on_a_blocking_thread() {
+    let data: Vec<u8> = (0..200_000u32).flat_map(|i| i.to_le_bytes()).collect();
+    let options = DecompressionOptions {
+        blocking_threshold: Some(0),
+        ..DecompressionOptions::default()
+    };
+    let base = serve_gzip(&data).await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.set_decompression_options(options);
+    let resp = client.get("data").await.unwrap();
+    assert_eq!(resp.body().as_ref(), data.as_slice());
+
+    // The expansion limit applies the same way off the async worker.
+    let base = serve_gzip(&vec![0u8; 8 * 1024 * 1024]).await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.set_decompression_options(DecompressionOptions {
+        max_expansion_ratio: Some(100),
+        ..options
+    });
+    let err = client.get("bomb").await.unwrap_err();
+    assert!(err.is::<ExpansionLimitError>(), "{err:#}");
+}
+
+#[tokio::test]
+async fn test_decompression_without_content_length_switches_to_a_blocking_thread() {
+    let data: Vec<u8> = (0..200_000u32).flat_map(|i| i.to_le_bytes()).collect();
+    let compressed = compress_payload(Bytes::from(data.clone()), ContentEncoding::Zstd)
+        .await
+        .unwrap();
+    let chunks: Vec<&[u8]> = compressed.chunks(4096).collect();
+    for threshold in [1024, usize::MAX] {
+        let (base, _server) = serve_chunked(
+            "HTTP/1.1 200 OK\r\nContent-Encoding: zstd",
+            &chunks,
+            b"\r\n\r\n",
+        )
+        .await;
+        let mut client = WebDavClient::new(&base, None, None).unwrap();
+        client.set_decompression_options(DecompressionOptions {
+            blocking_threshold: Some(threshold),
+            ..DecompressionOptions::default()
+        });
+        let resp = client.get("data").await.unwrap();
+        assert_eq!(
+            resp.body().as_ref(),
+            data.as_slice(),
+            "threshold {threshold}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_send_stream_decompressed_yields_decoded_chunks() {
+    let data: Vec<u8> = (0..200_000u32).flat_map(|i| i.to_le_bytes()).collect();
+    let base = serve_gzip(&data).await;
+    let client = CalDavClient::new(&base, None, None).unwrap();
+
+    let resp = client
+        .send_stream_decompressed(Method::GET, "export.ics", HeaderMap::new(), None, None)
+        .await
+        .unwrap();
+    assert!(!resp.headers().contains_key(header::CONTENT_ENCODING));
+    assert!(!resp.headers().contains_key(header::CONTENT_LENGTH));
+    let chunks: Vec<Bytes> = resp.into_body().try_collect().await.unwrap();
+    assert!(chunks.len() > 1);
+    assert_eq!(chunks.concat(), data);
+}
+
+#[tokio::test]
+async fn test_send_stream_decompressed_keeps_identity_bodies() {
+    let (base, _server) = serve_bytes("HTTP/1.1 200 OK", b"BEGIN:VCALENDAR", b"\r\n\r\n").await;
+    let client = CalDavClient::new(&base, None, None).unwrap();
+
+    let resp = client
+        .send_stream_decompressed(Method::GET, "a.ics", HeaderMap::new(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()[header::CONTENT_LENGTH], "15");
+    let chunks: Vec<Bytes> = resp.into_body().try_collect().await.unwrap();
+    assert_eq!(chunks.concat(), b"BEGIN:VCALENDAR");
+
+    let (base, _server) = serve_bytes(
+        "HTTP/1.1 200 OK\r\nContent-Encoding: identity",
+        b"BEGIN:VCALENDAR",
+        b"\r\n\r\n",
+    )
+    .await;
+    let client = CalDavClient::new(&base, None, None).unwrap();
+    let resp = client
+        .send_stream_decompressed(Method::GET, "a.ics", HeaderMap::new(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()[header::CONTENT_ENCODING], "identity");
+    assert_eq!(resp.headers()[header::CONTENT_LENGTH], "15");
+    let chunks: Vec<Bytes> = resp.into_body().try_collect().await.unwrap();
+    assert_eq!(chunks.concat(), b"BEGIN:VCALENDAR");
+}
+
+#[tokio::test]
+async fn test_send_stream_decompressed_reports_the_expansion_limit() {
+    let base = serve_gzip(&vec![0u8; 8 * 1024 * 1024]).await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.set_decompression_options(DecompressionOptions {
+        max_expansion_ratio: Some(100),
+        ..DecompressionOptions::default()
+    });
+
+    let resp = client
+        .send_stream_decompressed(Method::GET, "bomb", HeaderMap::new(), None, None)
+        .await
+        .unwrap();
+    let err = resp
+        .into_body()
+        .try_for_each(|_| async { Ok(()) })
+        .await
+        .unwrap_err();
+    assert!(err.is::<ExpansionLimitError>(), "{err:#}");
+}
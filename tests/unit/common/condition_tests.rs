@@ -0,0 +1,85 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::webdav::{
+    InvalidSyncTokenError, PropertyName, is_invalid_sync_token, parse_error_conditions,
+};
+use hyper::StatusCode;
+
+use crate::mock_server::serve_once;
+
+#[test]
+fn parses_conditions_of_the_error_root_only() {
+    let xml = r#"<?xml version="1.0"?>
+<d:error xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <c:no-uid-conflict><d:href>/cal/other.ics</d:href></c:no-uid-conflict>
+  <d:number-of-matches-within-limits/>
+</d:error>"#;
+    assert_eq!(
+        parse_error_conditions(xml.as_bytes()).unwrap(),
+        vec![
+            PropertyName::new("urn:ietf:params:xml:ns:caldav", "no-uid-conflict"),
+            PropertyName::dav("number-of-matches-within-limits"),
+        ]
+    );
+    assert!(
+        parse_error_conditions(b"<html><body>Forbidden</body></html>")
+            .unwrap()
+            .is_empty()
+    );
+    assert!(parse_error_conditions(b"").unwrap().is_empty());
+}
+
+#[test]
+fn detects_rejected_sync_tokens() {
+    let valid_sync_token = br#"<D:error xmlns:D="DAV:"><D:valid-sync-token/></D:error>"#;
+    assert!(is_invalid_sync_token(
+        StatusCode::FORBIDDEN,
+        valid_sync_token
+    ));
+    assert!(is_invalid_sync_token(
+        StatusCode::BAD_REQUEST,
+        valid_sync_token
+    ));
+    assert!(is_invalid_sync_token(StatusCode::CONFLICT, b""));
+    assert!(!is_invalid_sync_token(
+        StatusCode::FORBIDDEN,
+        br#"<D:error xmlns:D="DAV:"><D:need-privileges/></D:error>"#
+    ));
+    assert!(!is_invalid_sync_token(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        b""
+    ));
+}
+
+const INVALID_TOKEN_REPLY: &str = concat!(
+    "HTTP/1.1 403 Forbidden\r\nContent-Type: application/xml\r\nContent-Length: 55\r\nConnection: close\r\n\r\n",
+    r#"<D:error xmlns:D="DAV:"><D:valid-sync-token/></D:error>"#
+);
+
+#[tokio::test]
+async fn sync_collection_reports_rejected_tokens_as_typed_errors() {
+    let (base, _server) = serve_once(INVALID_TOKEN_REPLY, b"</D:sync-collection>").await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+
+    let err = client
+        .sync_collection("cal/", Some("http://example.com/sync/1"), None, false)
+        .await
+        .unwrap_err();
+    let invalid = err.downcast_ref::<InvalidSyncTokenError>().unwrap();
+    assert_eq!(invalid.token, "http://example.com/sync/1");
+    assert_eq!(invalid.status, 403);
+}
+
+#[tokio::test]
+async fn sync_collection_without_token_keeps_a_plain_error() {
+    let (base, _server) = serve_once(INVALID_TOKEN_REPLY, b"</D:sync-collection>").await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+
+    let err = client
+        .sync_collection("cal/", None, None, false)
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<InvalidSyncTokenError>().is_none());
+    assert!(err.to_string().contains("403"));
+}
@@ -0,0 +1,94 @@
+use fast_dav_rs::common::contentline::{
+    Component, ContentLine, escape_text, fold_line, split_structured, unescape_text, unfold_lines,
+};
+
+#[test]
+fn parses_content_line_with_quoted_params() {
+    let line = ContentLine::parse(
+        r#"ATTENDEE;CN="Doe, Jane";PARTSTAT=NEEDS-ACTION;DELEGATED-FROM="mailto:a@example.com":mailto:jane@example.com"#,
+    )
+    .unwrap();
+    assert_eq!(line.name, "ATTENDEE");
+    assert_eq!(line.param("cn"), Some("Doe, Jane"));
+    assert_eq!(line.param("PARTSTAT"), Some("NEEDS-ACTION"));
+    assert_eq!(line.param("delegated-from"), Some("mailto:a@example.com"));
+    assert_eq!(line.value, "mailto:jane@example.com");
+}
+
+#[test]
+fn content_line_round_trips_and_quotes_when_needed() {
+    let mut line = ContentLine::parse("ATTENDEE;CN=Jane:mailto:jane@example.com").unwrap();
+    line.set_param("PARTSTAT", "ACCEPTED");
+    line.set_param("CN", "Doe; Jane");
+    assert_eq!(
+        line.to_string(),
+        r#"ATTENDEE;CN="Doe; Jane";PARTSTAT=ACCEPTED:mailto:jane@example.com"#
+    );
+}
+
+#[test]
+fn rejects_line_without_colon() {
+    assert!(ContentLine::parse("SUMMARY").is_err());
+}
+
+#[test]
+fn unfolds_crlf_and_lf_continuations() {
+    let lines = unfold_lines("SUMMARY:Long\r\n  title\nDESCRIPTION:a\n\tb\r\n");
+    assert_eq!(lines, vec!["SUMMARY:Long title", "DESCRIPTION:ab"]);
+}
+
+#[test]
+fn folds_at_75_octets_without_splitting_utf8() {
+    let value = "é".repeat(60);
+    let folded = fold_line(&format!("SUMMARY:{value}"));
+    for physical in folded.split("\r\n").filter(|l| !l.is_empty()) {
+        assert!(physical.len() <= 75, "line too long: {}", physical.len());
+    }
+    assert_eq!(unfold_lines(&folded), vec![format!("SUMMARY:{value}")]);
+}
+
+#[test]
+fn text_escaping_round_trips() {
+    let raw = "a, b; c\\d\nnext";
+    let escaped = escape_text(raw);
+    assert_eq!(escaped, r"a\, b\; c\\d\nnext");
+    assert_eq!(unescape_text(&escaped), raw);
+}
+
+#[test]
+fn parses_nested_components_and_serializes_back() {
+    let ics = "BEGIN:VCALENDAR\nVERSION:2.0\nBEGIN:VEVENT\nUID:1\nBEGIN:VALARM\nACTION:DISPLAY\nEND:VALARM\nEND:VEVENT\nEND:VCALENDAR\n";
+    let cal = Component::parse(ics).unwrap();
+    assert_eq!(cal.name, "VCALENDAR");
+    let event = cal.components_named("VEVENT").next().unwrap();
+    assert_eq!(event.value("uid"), Some("1"));
+    assert_eq!(event.components_named("VALARM").count(), 1);
+
+    let out = cal.to_ics();
+    assert!(out.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\n"));
+    assert_eq!(Component::parse(&out).unwrap(), cal);
+}
+
+#[test]
+fn rejects_unbalanced_components() {
+    assert!(Component::parse_all("BEGIN:VCALENDAR\nBEGIN:VEVENT\nEND:VCALENDAR\n").is_err());
+    assert!(Component::parse_all("BEGIN:VCALENDAR\n").is_err());
+    assert!(Component::parse_all("UID:orphan\n").is_err());
+}
+
+#[test]
+fn parses_multiple_top_level_vcards_with_groups() {
+    let vcf = "BEGIN:VCARD\nVERSION:4.0\nitem1.EMAIL:a@example.com\nEND:VCARD\n\nBEGIN:VCARD\nVERSION:4.0\nFN:B\nEND:VCARD\n";
+    let cards = Component::parse_all(vcf).unwrap();
+    assert_eq!(cards.len(), 2);
+    assert_eq!(cards[0].value("EMAIL"), Some("a@example.com"));
+    assert_eq!(cards[0].property("EMAIL").unwrap().name, "ITEM1.EMAIL");
+}
+
+#[test]
+fn splits_structured_values_on_unescaped_semicolons() {
+    assert_eq!(
+        split_structured("Doe;Jane\\;Ann;;Dr.;Jr\\, PhD"),
+        vec!["Doe", "Jane;Ann", "", "Dr.", "Jr, PhD"]
+    );
+}
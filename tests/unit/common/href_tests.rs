@@ -0,0 +1,84 @@
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::webdav::{normalize_href, resolve_href};
+
+use crate::mock_server::serve_once;
+
+#[test]
+fn drops_scheme_authority_query_and_fragment() {
+    assert_eq!(
+        normalize_href("https://dav.example.com:8443/cal/work/a.ics?x=1#top"),
+        "/cal/work/a.ics"
+    );
+    assert_eq!(normalize_href("http://dav.example.com"), "/");
+}
+
+#[test]
+fn canonicalizes_percent_encoding() {
+    // Escaped path characters are decoded, other escapes upper-cased.
+    assert_eq!(
+        normalize_href("/cal/%7Ejane/%65vent.ics"),
+        "/cal/~jane/event.ics"
+    );
+    assert_eq!(
+        normalize_href("/cal/a%2fb%c3%a9.ics"),
+        "/cal/a%2Fb%C3%A9.ics"
+    );
+    // Characters that must be escaped are encoded, a stray `%` included.
+    assert_eq!(
+        normalize_href("/cal/été 100%.ics"),
+        "/cal/%C3%A9t%C3%A9%20100%25.ics"
+    );
+    assert_eq!(
+        normalize_href("/cal/%C3%A9t%C3%A9%20100%25.ics"),
+        normalize_href("/cal/été 100%.ics")
+    );
+}
+
+#[test]
+fn removes_empty_and_dot_segments() {
+    assert_eq!(normalize_href("//cal///work/./a.ics"), "/cal/work/a.ics");
+    assert_eq!(normalize_href("/cal/home/../work/a.ics"), "/cal/work/a.ics");
+    assert_eq!(normalize_href("/../a.ics"), "/a.ics");
+    assert_eq!(normalize_href("../../a/./b.ics"), "../../a/b.ics");
+}
+
+#[test]
+fn keeps_the_trailing_slash_of_collections() {
+    assert_eq!(normalize_href("/cal/work/"), "/cal/work/");
+    assert_eq!(normalize_href("/cal/work//"), "/cal/work/");
+    assert_eq!(normalize_href("/cal/work/a/.."), "/cal/work/");
+    assert_eq!(normalize_href("/cal/work"), "/cal/work");
+}
+
+#[test]
+fn resolves_relative_hrefs_against_the_collection() {
+    assert_eq!(resolve_href("/cal/work/", "a.ics"), "/cal/work/a.ics");
+    assert_eq!(resolve_href("/cal/work", "./a.ics"), "/cal/work/a.ics");
+    assert_eq!(
+        resolve_href("https://dav.example.com/cal/work/", "../home/a.ics"),
+        "/cal/home/a.ics"
+    );
+    assert_eq!(
+        resolve_href("/cal/work/", "https://dav.example.com/cal/work/a.ics"),
+        "/cal/work/a.ics"
+    );
+}
+
+const RELATIVE_SYNC_REPLY: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nContent-Length: 206\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>jane%2Ddoe.vcf</d:href><d:propstat><d:prop><d:getetag>"1"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response></d:multistatus>"#
+);
+
+#[tokio::test]
+async fn sync_collection_resolves_relative_hrefs() {
+    let (base, _server) = serve_once(RELATIVE_SYNC_REPLY, b"</D:sync-collection>").await;
+    let mut client = CardDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+
+    let sync = client
+        .sync_collection("books/jane", None, None, false)
+        .await
+        .unwrap();
+    assert_eq!(sync.items.len(), 1);
+    assert_eq!(sync.items[0].href, "/books/jane/jane-doe.vcf");
+}
@@ -0,0 +1,170 @@
+use fast_dav_rs::webdav::{
+    IfHeader, IfList, Lock, LockScope, LockTimeout, WebDavClient, build_lockinfo_body,
+    parse_lock_discovery, parse_lock_token_header,
+};
+
+#[test]
+fn builds_shared_lockinfo_without_owner() {
+    let body = build_lockinfo_body(LockScope::Shared, None);
+    assert!(body.contains("<D:lockscope><D:shared/></D:lockscope>"));
+    assert!(body.contains("<D:locktype><D:write/></D:locktype>"));
+    assert!(!body.contains("<D:owner>"));
+}
+
+#[test]
+fn escapes_lock_owner() {
+    let body = build_lockinfo_body(LockScope::Exclusive, Some("https://example.com/?a=1&b=2"));
+    assert!(body.contains("<D:href>https://example.com/?a=1&amp;b=2</D:href>"));
+}
+
+#[test]
+fn parses_every_active_lock() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:prop xmlns:d="DAV:">
+  <d:lockdiscovery>
+    <d:activelock>
+      <d:locktype><d:write/></d:locktype>
+      <d:lockscope><d:shared/></d:lockscope>
+      <d:depth>infinity</d:depth>
+      <d:owner><d:href>mailto:jane@example.com</d:href></d:owner>
+      <d:timeout>Second-3600</d:timeout>
+      <d:locktoken><d:href>opaquelocktoken:a515cfa4-5da4-22e1-f5b5-00a0451e6bf7</d:href></d:locktoken>
+      <d:lockroot><d:href>/files/report.ics</d:href></d:lockroot>
+    </d:activelock>
+    <d:activelock>
+      <d:timeout>Infinite</d:timeout>
+      <d:locktoken><d:href>urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6</d:href></d:locktoken>
+    </d:activelock>
+  </d:lockdiscovery>
+</d:prop>"#;
+
+    let locks = parse_lock_discovery(xml.as_bytes()).unwrap();
+    assert_eq!(locks.len(), 2);
+    assert_eq!(
+        locks[0].token,
+        "opaquelocktoken:a515cfa4-5da4-22e1-f5b5-00a0451e6bf7"
+    );
+    assert_eq!(locks[0].timeout, Some(LockTimeout::Seconds(3600)));
+    assert_eq!(locks[1].timeout, Some(LockTimeout::Infinite));
+}
+
+#[test]
+fn lock_tokens_with_escapes_are_kept_whole() {
+    let xml = r#"<d:prop xmlns:d="DAV:"><d:lockdiscovery><d:activelock>
+  <d:timeout>Second-&#54;0</d:timeout>
+  <d:locktoken><d:href>urn:lock:a&amp;b</d:href></d:locktoken>
+</d:activelock></d:lockdiscovery></d:prop>"#;
+
+    let locks = parse_lock_discovery(xml.as_bytes()).unwrap();
+    assert_eq!(locks[0].token, "urn:lock:a&b");
+    assert_eq!(locks[0].timeout, Some(LockTimeout::Seconds(60)));
+}
+
+#[test]
+fn rejects_malformed_lockdiscovery() {
+    assert!(parse_lock_discovery(b"<d:prop xmlns:d=\"DAV:\"><d:lockdiscovery></d:prop>").is_err());
+}
+
+#[test]
+fn formats_and_parses_timeouts() {
+    assert_eq!(LockTimeout::Seconds(600).as_header(), "Second-600");
+    assert_eq!(LockTimeout::Infinite.as_header(), "Infinite");
+    assert_eq!(
+        LockTimeout::parse("second-4100000000"),
+        Some(LockTimeout::Seconds(4_100_000_000))
+    );
+    assert_eq!(
+        LockTimeout::parse("Extended, Infinite, Second-60"),
+        Some(LockTimeout::Infinite)
+    );
+    assert_eq!(LockTimeout::parse("Second-"), None);
+}
+
+#[test]
+fn strips_lock_token_header_brackets() {
+    assert_eq!(
+        parse_lock_token_header(" <urn:uuid:f81d4fae> ").as_deref(),
+        Some("urn:uuid:f81d4fae")
+    );
+    assert_eq!(parse_lock_token_header("<>"), None);
+}
+
+#[test]
+fn if_header_for_a_lock() {
+    let lock = Lock {
+        token: "urn:uuid:f81d4fae".to_string(),
+        timeout: Some(LockTimeout::Infinite),
+    };
+    assert_eq!(
+        IfHeader::lock(&lock).build().unwrap(),
+        "(<urn:uuid:f81d4fae>)"
+    );
+}
+
+#[test]
+fn if_header_alternative_lists_and_negation() {
+    let header = IfHeader::new()
+        .list(IfList::new().token("urn:uuid:a").etag("W/\"1\""))
+        .list(IfList::new().not_token("urn:uuid:b").not_etag("2"))
+        .build()
+        .unwrap();
+    assert_eq!(
+        header,
+        r#"(<urn:uuid:a> [W/"1"]) (Not <urn:uuid:b> Not ["2"])"#
+    );
+}
+
+#[test]
+fn if_header_groups_lists_by_resource() {
+    let header = IfHeader::new()
+        .tagged(
+            "https://dav.example.com/a",
+            IfList::new().token("urn:uuid:1"),
+        )
+        .tagged("/b/", IfList::new().etag("\"x\""))
+        .tagged(
+            "https://dav.example.com/a",
+            IfList::new().token("urn:uuid:2"),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(
+        header,
+        r#"<https://dav.example.com/a> (<urn:uuid:1>) (<urn:uuid:2>) </b/> (["x"])"#
+    );
+}
+
+#[test]
+fn if_header_rejects_invalid_combinations() {
+    assert!(IfHeader::new().build().is_err());
+    assert!(IfHeader::new().list(IfList::new()).build().is_err());
+    assert!(
+        IfHeader::new()
+            .list(IfList::new().token("urn:uuid:a"))
+            .tagged("/a", IfList::new().token("urn:uuid:b"))
+            .build()
+            .is_err()
+    );
+    assert!(
+        IfHeader::new()
+            .tagged("relative/path", IfList::new().token("urn:uuid:a"))
+            .build()
+            .is_err()
+    );
+    assert!(
+        IfHeader::new()
+            .list(IfList::new().token("<urn:uuid:a>"))
+            .build()
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn delete_if_rejects_invalid_header_before_sending() {
+    let client = WebDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .delete_if("a.ics", &IfHeader::new())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("If header has no list"));
+}
@@ -0,0 +1,70 @@
+use fast_dav_rs::webdav::{ExtendedMkcol, PropertyName, WebDavClient};
+
+#[test]
+fn plain_collection() {
+    let body = ExtendedMkcol::new()
+        .displayname("Docs & more")
+        .build()
+        .unwrap();
+    assert!(body.contains(
+        "<D:mkcol xmlns:D=\"DAV:\"><D:set><D:prop><D:resourcetype><D:collection/></D:resourcetype><D:displayname>Docs &amp; more</D:displayname></D:prop></D:set></D:mkcol>"
+    ));
+}
+
+#[test]
+fn addressbook_declares_its_prefix_for_values() {
+    let body = ExtendedMkcol::addressbook()
+        .prop_text(
+            PropertyName::new("urn:ietf:params:xml:ns:carddav", "addressbook-description"),
+            "Team",
+        )
+        .build()
+        .unwrap();
+    assert!(body.contains(r#"xmlns:CARD="urn:ietf:params:xml:ns:carddav""#));
+    assert!(body.contains("<D:resourcetype><D:collection/><CARD:addressbook/></D:resourcetype>"));
+    assert!(body.contains("<CARD:addressbook-description>Team</CARD:addressbook-description>"));
+}
+
+#[test]
+fn calendar_with_component_set_and_custom_namespace() {
+    let body = ExtendedMkcol::calendar()
+        .prop_xml(
+            PropertyName::new(
+                "urn:ietf:params:xml:ns:caldav",
+                "supported-calendar-component-set",
+            ),
+            r#"<C:comp name="VTODO"/>"#,
+        )
+        .namespace("EX", "https://example.com/ns")
+        .prop_text(PropertyName::new("https://example.com/ns", "flag"), "on")
+        .displayname("Old")
+        .displayname("Tasks")
+        .build()
+        .unwrap();
+    assert!(body.contains(r#"xmlns:C="urn:ietf:params:xml:ns:caldav""#));
+    assert!(body.contains(
+        r#"<C:supported-calendar-component-set><C:comp name="VTODO"/></C:supported-calendar-component-set>"#
+    ));
+    assert!(body.contains(r#"xmlns:EX="https://example.com/ns""#));
+    assert!(body.contains("<EX:flag>on</EX:flag>"));
+    assert_eq!(body.matches("<D:displayname>").count(), 1);
+    assert!(body.contains("<D:displayname>Tasks</D:displayname>"));
+}
+
+#[test]
+fn rejects_invalid_property_names() {
+    assert!(
+        ExtendedMkcol::new()
+            .resource_type(PropertyName::new("urn:x", "bad type"))
+            .build()
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn mkcol_extended_validates_before_sending() {
+    let client = WebDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let mkcol = ExtendedMkcol::new().prop_text(PropertyName::dav("<x>"), "v");
+    let err = client.mkcol_extended("new/", &mkcol).await.unwrap_err();
+    assert!(err.to_string().contains("invalid property name"));
+}
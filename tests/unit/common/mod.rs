@@ -1,2 +1,21 @@
+pub mod acl_tests;
+pub mod bind_tests;
+pub mod capabilities_tests;
 pub mod compression_integration_tests;
 pub mod compression_tests;
+pub mod condition_tests;
+pub mod contentline_tests;
+pub mod href_tests;
+pub mod lock_tests;
+pub mod mkcol_tests;
+pub mod parse_limits_tests;
+pub mod principal_tests;
+pub mod property_tests;
+pub mod propfind_tests;
+pub mod proppatch_tests;
+pub mod push_tests;
+pub mod resource_tests;
+pub mod search_tests;
+pub mod version_tests;
+pub mod webdav_client_tests;
+pub mod webdav_streaming_tests;
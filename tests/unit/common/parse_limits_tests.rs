@@ -0,0 +1,164 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::caldav::parse_multistatus_bytes_with_limits;
+use fast_dav_rs::carddav::{DavItemStream, parse_multistatus_bytes};
+use fast_dav_rs::webdav::streaming::parse_multistatus_bytes_visit;
+use fast_dav_rs::webdav::{
+    BasicSearch, DEFAULT_MAX_XML_DEPTH, Depth, ParseLimit, ParseLimitError, ParseLimits,
+    PropertyName, PropertyRegistry, WebDavClient, parse_acl_with_limits,
+    parse_lock_discovery_with_limits, parse_principals_with_limits, parse_versions_with_limits,
+};
+use futures::TryStreamExt;
+use std::time::Duration;
+
+use crate::mock_server::serve_once;
+
+const TWO_ITEMS: &str = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/cal/a.ics</d:href><d:status>HTTP/1.1 200 OK</d:status></d:response><d:response><d:href>/cal/b.ics</d:href><d:status>HTTP/1.1 200 OK</d:status></d:response></d:multistatus>"#;
+
+fn limit_error(err: &anyhow::Error) -> &ParseLimitError {
+    err.downcast_ref::<ParseLimitError>()
+        .unwrap_or_else(|| panic!("not a ParseLimitError: {err:#}"))
+}
+
+fn nested(depth: usize) -> String {
+    format!(
+        r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/a</d:href><d:propstat><d:prop><x:deep xmlns:x="urn:x">{}{}</x:deep></d:prop></d:propstat></d:response></d:multistatus>"#,
+        "<x:e>".repeat(depth),
+        "</x:e>".repeat(depth)
+    )
+}
+
+#[test]
+fn default_limits_reject_deep_nesting() {
+    assert!(parse_multistatus_bytes(nested(8).as_bytes()).is_ok());
+
+    let err = parse_multistatus_bytes(nested(DEFAULT_MAX_XML_DEPTH).as_bytes()).unwrap_err();
+    let err = limit_error(&err);
+    assert_eq!(err.limit_kind, ParseLimit::Depth);
+    assert_eq!(err.limit, DEFAULT_MAX_XML_DEPTH);
+
+    let err = parse_multistatus_bytes_visit(nested(DEFAULT_MAX_XML_DEPTH).as_bytes(), |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Depth);
+}
+
+#[test]
+fn text_and_item_limits_are_configurable() {
+    let limits = ParseLimits {
+        max_text_size: Some(9),
+        ..ParseLimits::default()
+    };
+    let err = parse_multistatus_bytes_with_limits(TWO_ITEMS.as_bytes(), limits).unwrap_err();
+    assert_eq!(
+        *limit_error(&err),
+        ParseLimitError {
+            limit_kind: ParseLimit::TextSize,
+            limit: 9
+        }
+    );
+
+    let limits = ParseLimits {
+        max_items: Some(1),
+        ..ParseLimits::default()
+    };
+    let err = parse_multistatus_bytes_with_limits(TWO_ITEMS.as_bytes(), limits).unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Items);
+    assert_eq!(err.to_string(), "multistatus with more than 1 responses");
+
+    let deep = nested(DEFAULT_MAX_XML_DEPTH);
+    let parsed = parse_multistatus_bytes_with_limits(deep.as_bytes(), ParseLimits::unlimited());
+    assert_eq!(parsed.unwrap().items.len(), 1);
+}
+
+#[tokio::test]
+async fn item_stream_fails_once_a_limit_is_exceeded() {
+    let limits = ParseLimits {
+        max_items: Some(1),
+        ..ParseLimits::default()
+    };
+    let mut items = DavItemStream::with_limits(
+        std::io::Cursor::new(TWO_ITEMS.as_bytes()),
+        Duration::from_secs(5),
+        limits,
+    );
+    assert_eq!(items.try_next().await.unwrap().unwrap().href, "/cal/a.ics");
+    let err = items.try_next().await.unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Items);
+}
+
+const TWO_ITEMS_REPLY: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nContent-Length: 222\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/cal/a.ics</d:href><d:status>HTTP/1.1 200 OK</d:status></d:response><d:response><d:href>/cal/b.ics</d:href><d:status>HTTP/1.1 200 OK</d:status></d:response></d:multistatus>"#
+);
+
+#[tokio::test]
+async fn client_applies_its_parse_limits() {
+    let (base, _server) = serve_once(TWO_ITEMS_REPLY, b"</D:sync-collection>").await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    client.set_parse_limits(ParseLimits {
+        max_items: Some(1),
+        ..ParseLimits::default()
+    });
+
+    let err = client
+        .sync_collection("cal/", None, None, false)
+        .await
+        .unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Items);
+}
+
+#[test]
+fn webdav_parsers_apply_limits() {
+    let shallow = ParseLimits {
+        max_depth: Some(4),
+        ..ParseLimits::default()
+    };
+    let err = parse_acl_with_limits(nested(2).as_bytes(), shallow).unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Depth);
+    let err = parse_lock_discovery_with_limits(nested(2).as_bytes(), shallow).unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Depth);
+
+    let one = ParseLimits {
+        max_items: Some(1),
+        ..ParseLimits::default()
+    };
+    let err = parse_principals_with_limits(TWO_ITEMS.as_bytes(), one).unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Items);
+    let err = parse_versions_with_limits(TWO_ITEMS.as_bytes(), one).unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Items);
+}
+
+#[tokio::test]
+async fn search_rejects_oversized_multistatus() {
+    let (base, _server) = serve_once(TWO_ITEMS_REPLY, b"</D:searchrequest>").await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    client.set_parse_limits(ParseLimits {
+        max_items: Some(1),
+        ..ParseLimits::default()
+    });
+
+    let err = client
+        .search("/cal/", &BasicSearch::new("/cal/"))
+        .await
+        .unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Items);
+}
+
+#[tokio::test]
+async fn caldav_limits_reach_webdav_requests() {
+    let (base, _server) = serve_once(TWO_ITEMS_REPLY, b"</D:propfind>").await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    client.set_parse_limits(ParseLimits {
+        max_items: Some(1),
+        ..ParseLimits::default()
+    });
+
+    let registry = PropertyRegistry::new().register(PropertyName::dav("displayname"));
+    let err = client
+        .propfind_properties("cal/", Depth::One, &registry)
+        .await
+        .unwrap_err();
+    assert_eq!(limit_error(&err).limit_kind, ParseLimit::Items);
+}
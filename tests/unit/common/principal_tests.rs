@@ -0,0 +1,116 @@
+use fast_dav_rs::webdav::{PrincipalInfo, parse_principal_collection_set, parse_principals};
+
+#[test]
+fn parses_principal_collection_set() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/remote.php/dav/principals/users/jane/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:principal-collection-set>
+          <d:href>/remote.php/dav/principals/users/</d:href>
+          <d:href>/remote.php/dav/principals/groups/</d:href>
+        </d:principal-collection-set>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    assert_eq!(
+        parse_principal_collection_set(xml.as_bytes()).unwrap(),
+        [
+            "/remote.php/dav/principals/groups/",
+            "/remote.php/dav/principals/users/"
+        ]
+    );
+}
+
+#[test]
+fn parses_users_and_groups_and_skips_the_collection() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/principals/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/principals/users/jane/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:principal/></d:resourcetype>
+        <d:displayname>Jane Doe</d:displayname>
+        <cal:calendar-user-type>INDIVIDUAL</cal:calendar-user-type>
+        <cal:calendar-user-address-set>
+          <d:href>/principals/users/jane/</d:href>
+          <d:href>mailto:jane@example.com</d:href>
+        </cal:calendar-user-address-set>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:group-member-set/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/principals/groups/staff/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:principal/></d:resourcetype>
+        <d:displayname>Staff</d:displayname>
+        <d:group-member-set>
+          <d:href>/principals/users/jane/</d:href>
+          <d:href>/principals/users/bob/</d:href>
+        </d:group-member-set>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    let principals = parse_principals(xml.as_bytes()).unwrap();
+    assert_eq!(principals.len(), 2);
+
+    let jane = &principals[0];
+    assert_eq!(jane.href, "/principals/users/jane/");
+    assert_eq!(jane.calendar_user_type.as_deref(), Some("INDIVIDUAL"));
+    assert_eq!(jane.email(), Some("jane@example.com"));
+    assert!(!jane.is_group());
+
+    let staff = &principals[1];
+    assert_eq!(staff.displayname.as_deref(), Some("Staff"));
+    assert_eq!(staff.members.len(), 2);
+    assert!(staff.is_group());
+    assert_eq!(staff.email(), None);
+}
+
+#[test]
+fn group_calendar_user_type_marks_groups() {
+    let group = PrincipalInfo {
+        calendar_user_type: Some("group".into()),
+        ..PrincipalInfo::default()
+    };
+    assert!(group.is_group());
+}
+
+#[test]
+fn principal_text_with_escapes_is_kept_whole() {
+    let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response>
+  <d:href>/p/a&amp;b/</d:href>
+  <d:propstat><d:prop>
+    <d:resourcetype><d:principal/></d:resourcetype>
+    <d:displayname> Tom &amp; Jerry </d:displayname>
+    <d:group-member-set><d:href>/p/x&#38;y/</d:href></d:group-member-set>
+  </d:prop></d:propstat>
+</d:response></d:multistatus>"#;
+
+    let principals = parse_principals(xml.as_bytes()).unwrap();
+    assert_eq!(principals[0].href, "/p/a&b/");
+    assert_eq!(principals[0].displayname.as_deref(), Some("Tom & Jerry"));
+    assert_eq!(principals[0].members, ["/p/x&y/"]);
+}
@@ -0,0 +1,118 @@
+use fast_dav_rs::webdav::{PropertyName, PropertyRegistry, parse_properties};
+
+const OC: &str = "http://owncloud.org/ns";
+
+#[test]
+fn clark_notation_round_trips() {
+    let name = PropertyName::from_clark("{http://calendarserver.org/ns/}getctag").unwrap();
+    assert_eq!(
+        name,
+        PropertyName::new("http://calendarserver.org/ns/", "getctag")
+    );
+    assert_eq!(name.to_string(), "{http://calendarserver.org/ns/}getctag");
+    assert_eq!(PropertyName::from_clark("plain").unwrap().ns, "");
+    assert_eq!(PropertyName::from_clark("{DAV:}"), None);
+}
+
+#[test]
+fn registry_deduplicates_and_generates_prefixes() {
+    let registry = PropertyRegistry::new()
+        .register(PropertyName::new(OC, "permissions"))
+        .register(PropertyName::new("https://a.example/ns", "one"))
+        .register(PropertyName::new("https://b.example/ns", "two"))
+        .register(PropertyName::new(OC, "permissions"));
+    assert_eq!(registry.names().len(), 3);
+
+    let body = registry.build_propfind_body().unwrap();
+    assert!(body.contains(r#"xmlns:OC="http://owncloud.org/ns""#));
+    assert!(body.contains(r#"xmlns:X0="https://a.example/ns""#));
+    assert!(body.contains(r#"xmlns:X1="https://b.example/ns""#));
+    assert!(body.contains("<D:prop><OC:permissions/><X0:one/><X1:two/></D:prop>"));
+}
+
+#[test]
+fn registry_rejects_invalid_names() {
+    assert!(PropertyRegistry::new().build_propfind_body().is_err());
+    assert!(
+        PropertyRegistry::new()
+            .register(PropertyName::dav("bad name/>"))
+            .build_propfind_body()
+            .is_err()
+    );
+    assert!(
+        PropertyRegistry::new()
+            .register(PropertyName::new("urn:x", "ok"))
+            .namespace("1bad", "urn:x")
+            .build_propfind_body()
+            .is_err()
+    );
+}
+
+#[test]
+fn parses_vendor_properties_with_raw_values() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns" xmlns:nc="http://nextcloud.org/ns">
+  <d:response>
+    <d:href>/remote.php/dav/files/jane/report.pdf</d:href>
+    <d:propstat>
+      <d:prop>
+        <oc:permissions>RGDNVW</oc:permissions>
+        <oc:share-types><oc:share-type>0</oc:share-type></oc:share-types>
+        <nc:has-preview>true</nc:has-preview>
+        <d:displayname>Q1 &amp; Q2</d:displayname>
+        <oc:favorite/>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><oc:checksums/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/files/jane/</d:href>
+    <d:propstat>
+      <d:prop><oc:size>1024</oc:size></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    let resources = parse_properties(xml.as_bytes()).unwrap();
+    assert_eq!(resources.len(), 2);
+    let file = &resources[0];
+    assert_eq!(file.href, "/remote.php/dav/files/jane/report.pdf");
+    assert_eq!(file.props.len(), 6);
+
+    let get = |ns: &str, local: &str| file.get(&PropertyName::new(ns, local));
+    assert_eq!(
+        get(OC, "permissions").unwrap().text().as_deref(),
+        Some("RGDNVW")
+    );
+    let share_types = get(OC, "share-types").unwrap();
+    assert_eq!(share_types.xml, "<oc:share-type>0</oc:share-type>");
+    assert_eq!(share_types.text(), None);
+    assert_eq!(
+        get("http://nextcloud.org/ns", "has-preview").unwrap().xml,
+        "true"
+    );
+    assert_eq!(
+        get("DAV:", "displayname").unwrap().text().as_deref(),
+        Some("Q1 & Q2")
+    );
+    assert_eq!(get(OC, "favorite").unwrap().xml, "");
+    assert!(get(OC, "checksums").is_none());
+    assert!(
+        file.props
+            .iter()
+            .any(|p| p.name.local == "checksums" && !p.is_success())
+    );
+
+    assert_eq!(
+        resources[1]
+            .get(&PropertyName::new(OC, "size"))
+            .and_then(|v| v.text())
+            .as_deref(),
+        Some("1024")
+    );
+}
@@ -0,0 +1,97 @@
+use fast_dav_rs::webdav::{PROPNAME_BODY, PropertyName, build_allprop_body, parse_propnames};
+
+#[test]
+fn allprop_without_include() {
+    let body = build_allprop_body(&[]).unwrap();
+    assert!(body.contains("<D:propfind xmlns:D=\"DAV:\"><D:allprop/></D:propfind>"));
+    assert!(!body.contains("include"));
+}
+
+#[test]
+fn allprop_include_declares_each_namespace() {
+    let body = build_allprop_body(&[
+        PropertyName::new("urn:ietf:params:xml:ns:caldav", "calendar-data"),
+        PropertyName::new("http://owncloud.org/ns", "permissions"),
+        PropertyName::new("urn:example", "custom"),
+    ])
+    .unwrap();
+    assert!(body.contains(
+        r#"<D:propfind xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav" xmlns:OC="http://owncloud.org/ns" xmlns:X0="urn:example">"#
+    ));
+    assert!(
+        body.contains("<D:include><C:calendar-data/><OC:permissions/><X0:custom/></D:include>")
+    );
+}
+
+#[test]
+fn allprop_include_rejects_invalid_names() {
+    assert!(build_allprop_body(&[PropertyName::dav("x/><evil")]).is_err());
+    assert!(build_allprop_body(&[PropertyName::dav("")]).is_err());
+}
+
+#[test]
+fn propname_body() {
+    assert!(PROPNAME_BODY.contains("<D:propname/>"));
+}
+
+#[test]
+fn parses_property_names_per_resource() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/" xmlns:oc="http://owncloud.org/ns">
+  <d:response>
+    <d:href>/calendars/jane/work/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:displayname/>
+        <d:resourcetype/>
+        <cs:getctag/>
+        <oc:owner-principal/>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/calendars/jane/work/a.ics</d:href>
+    <d:propstat>
+      <d:prop><d:getetag/></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    let resources = parse_propnames(xml.as_bytes()).unwrap();
+    assert_eq!(resources.len(), 2);
+    assert_eq!(resources[0].href, "/calendars/jane/work/");
+    let names: Vec<_> = resources[0]
+        .props
+        .iter()
+        .map(|p| (p.namespace.as_str(), p.name.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("DAV:", "displayname"),
+            ("DAV:", "resourcetype"),
+            ("http://calendarserver.org/ns/", "getctag"),
+            ("http://owncloud.org/ns", "owner-principal"),
+        ]
+    );
+    assert!(resources[0].props.iter().all(|p| p.is_success()));
+    assert_eq!(resources[1].props[0].name, "getetag");
+}
+
+#[test]
+fn property_names_keep_escaped_hrefs_whole() {
+    let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response>
+  <d:href>/files/a&amp;b/</d:href>
+  <d:propstat><d:prop><d:displayname/></d:prop>
+  <d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+</d:response></d:multistatus>"#;
+
+    let resources = parse_propnames(xml.as_bytes()).unwrap();
+    assert_eq!(resources[0].href, "/files/a&b/");
+    assert_eq!(
+        resources[0].props[0].status.as_deref(),
+        Some("HTTP/1.1 200 OK")
+    );
+}
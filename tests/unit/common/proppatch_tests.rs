@@ -0,0 +1,113 @@
+use fast_dav_rs::webdav::{PropPatch, PropertyName, parse_proppatch_response};
+
+#[test]
+fn reports_per_property_status_with_namespaces() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav" xmlns:x1="http://apple.com/ns/ical/">
+  <d:response>
+    <d:href>/calendars/test/work/</d:href>
+    <d:propstat>
+      <d:prop><d:displayname/><x1:calendar-color/></d:prop>
+      <d:status>HTTP/1.1 424 Failed Dependency</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><cal:calendar-timezone/></d:prop>
+      <d:status>HTTP/1.1 403 Forbidden</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    let result = parse_proppatch_response(xml.as_bytes()).unwrap();
+    assert_eq!(result.props.len(), 3);
+    assert!(!result.is_success());
+
+    assert_eq!(result.props[0].namespace, "DAV:");
+    assert_eq!(result.props[0].name, "displayname");
+    assert_eq!(result.props[0].status_code(), Some(424));
+    assert_eq!(result.props[1].namespace, "http://apple.com/ns/ical/");
+    assert_eq!(result.props[1].name, "calendar-color");
+
+    let failed: Vec<_> = result.failed().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        failed,
+        vec!["displayname", "calendar-color", "calendar-timezone"]
+    );
+    assert_eq!(result.props[2].status_code(), Some(403));
+}
+
+#[test]
+fn successful_proppatch_reports_all_ok() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/calendars/test/work/</D:href>
+    <D:propstat>
+      <D:prop><D:displayname/></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let result = parse_proppatch_response(xml.as_bytes()).unwrap();
+    assert!(result.is_success());
+    assert_eq!(result.failed().count(), 0);
+}
+
+#[test]
+fn rejects_malformed_xml() {
+    assert!(parse_proppatch_response(b"<a></b>").is_err());
+}
+
+#[test]
+fn proppatch_builder_groups_set_and_remove_in_order() {
+    let body = PropPatch::new()
+        .set_text(PropertyName::dav("displayname"), "Team <A>")
+        .set_xml(
+            PropertyName::new(
+                "urn:ietf:params:xml:ns:caldav",
+                "supported-calendar-component-set",
+            ),
+            r#"<C:comp name="VTODO"/>"#,
+        )
+        .remove(PropertyName::new(
+            "http://apple.com/ns/ical/",
+            "calendar-color",
+        ))
+        .remove(PropertyName::new("https://example.com/ns", "flag"))
+        .set_text(PropertyName::new("", "plain"), "x")
+        .build()
+        .unwrap();
+    assert!(body.contains(r#"xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav" xmlns:ICAL="http://apple.com/ns/ical/" xmlns:X0="https://example.com/ns""#));
+    assert!(body.contains(
+        r#"<D:set><D:prop><D:displayname>Team &lt;A&gt;</D:displayname><C:supported-calendar-component-set><C:comp name="VTODO"/></C:supported-calendar-component-set></D:prop></D:set>"#
+    ));
+    assert!(body.contains(
+        "<D:remove><D:prop><ICAL:calendar-color/><X0:flag/></D:prop></D:remove><D:set><D:prop><plain xmlns=\"\">x</plain></D:prop></D:set></D:propertyupdate>"
+    ));
+}
+
+#[test]
+fn proppatch_builder_rejects_empty_and_invalid_patches() {
+    assert!(PropPatch::new().is_empty());
+    assert!(PropPatch::new().build().is_err());
+    assert!(
+        PropPatch::new()
+            .remove(PropertyName::dav("not valid"))
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+fn proppatch_result_lookup_by_property_name() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:" xmlns:X="https://example.com/ns">
+  <D:response>
+    <D:href>/files/a.txt</D:href>
+    <D:propstat><D:prop><X:flag/></D:prop><D:status>HTTP/1.1 403 Forbidden</D:status></D:propstat>
+    <D:propstat><D:prop><D:displayname/></D:prop><D:status>HTTP/1.1 424 Failed Dependency</D:status></D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let result = parse_proppatch_response(xml.as_bytes()).unwrap();
+    let flag = PropertyName::new("https://example.com/ns", "flag");
+    assert_eq!(result.get(&flag).and_then(|p| p.status_code()), Some(403));
+    assert_eq!(result.props[1].property(), PropertyName::dav("displayname"));
+    assert!(result.get(&PropertyName::dav("getetag")).is_none());
+}
@@ -0,0 +1,129 @@
+use fast_dav_rs::webdav::{
+    Depth, PushTransport, WebDavClient, WebPushSubscription, build_push_register_body,
+    parse_push_info,
+};
+
+#[test]
+fn parses_webdav_push_and_apple_transports() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:P="https://bitfire.at/webdav-push" xmlns:cs="http://calendarserver.org/ns/">
+  <d:response>
+    <d:href>/calendars/jane/work/</d:href>
+    <d:propstat>
+      <d:prop>
+        <P:transports>
+          <P:web-push>
+            <P:vapid-public-key type="p256ecdsa">BCvapidKey</P:vapid-public-key>
+          </P:web-push>
+        </P:transports>
+        <P:topic>O7M1nQ7cKkKTKsoS_j6Z3w</P:topic>
+        <cs:push-transports>
+          <cs:transport type="APSD">
+            <cs:subscription-url><d:href>https://example.com/apns</d:href></cs:subscription-url>
+            <cs:apsbundleid>com.apple.calendar.XServer</cs:apsbundleid>
+            <cs:env>PRODUCTION</cs:env>
+            <cs:refresh-interval>172800</cs:refresh-interval>
+          </cs:transport>
+          <cs:transport type="XMPP">
+            <cs:xmpp-server>xmpp.example.com</cs:xmpp-server>
+            <cs:xmpp-uri>xmpp:pubsub.example.com?pubsub;node=/cal</cs:xmpp-uri>
+          </cs:transport>
+        </cs:push-transports>
+        <cs:pushkey>/CalDAV/example.com/jane/</cs:pushkey>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    let info = parse_push_info(xml.as_bytes()).unwrap();
+    assert!(info.supports_web_push());
+    assert_eq!(info.topic.as_deref(), Some("O7M1nQ7cKkKTKsoS_j6Z3w"));
+    assert_eq!(info.push_key.as_deref(), Some("/CalDAV/example.com/jane/"));
+    assert_eq!(
+        info.transports,
+        [
+            PushTransport::WebPush {
+                vapid_public_key: Some("BCvapidKey".into()),
+            },
+            PushTransport::Apns {
+                subscription_url: Some("https://example.com/apns".into()),
+                bundle_id: Some("com.apple.calendar.XServer".into()),
+                environment: Some("PRODUCTION".into()),
+                refresh_interval: Some(172800),
+            },
+            PushTransport::Xmpp {
+                server: Some("xmpp.example.com".into()),
+                uri: Some("xmpp:pubsub.example.com?pubsub;node=/cal".into()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn reports_no_push_support_when_properties_are_missing() {
+    let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/c/</d:href>
+<d:propstat><d:prop><P:transports xmlns:P="https://bitfire.at/webdav-push"/></d:prop>
+<d:status>HTTP/1.1 404 Not Found</d:status></d:propstat></d:response></d:multistatus>"#;
+    let info = parse_push_info(xml.as_bytes()).unwrap();
+    assert!(!info.supports_web_push());
+    assert!(info.transports.is_empty());
+    assert!(info.topic.is_none());
+}
+
+#[test]
+fn builds_push_register_body() {
+    let subscription = WebPushSubscription::new("https://push.example.net/push/a&b")
+        .keys("BPublicKey", "AuthSecret")
+        .expires("Wed, 20 Dec 2023 10:03:31 GMT")
+        .depth(Depth::Infinity);
+    let body = build_push_register_body(&subscription);
+    assert!(
+        body.contains("<P:push-resource>https://push.example.net/push/a&amp;b</P:push-resource>")
+    );
+    assert!(body.contains("<P:content-encoding>aes128gcm</P:content-encoding>"));
+    assert!(body.contains(
+        r#"<P:subscription-public-key type="p256dh">BPublicKey</P:subscription-public-key>"#
+    ));
+    assert!(body.contains("<P:auth-secret>AuthSecret</P:auth-secret>"));
+    assert!(body.contains("<D:depth>infinity</D:depth>"));
+    assert!(body.contains("<P:expires>Wed, 20 Dec 2023 10:03:31 GMT</P:expires>"));
+
+    let plain = build_push_register_body(&WebPushSubscription::new("https://push.example.net/x"));
+    assert!(!plain.contains("auth-secret"));
+    assert!(!plain.contains("expires"));
+    assert!(plain.contains("<D:depth>1</D:depth>"));
+}
+
+#[tokio::test]
+async fn rejects_relative_push_resource_before_sending() {
+    let client = WebDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .register_push(
+            "/calendars/jane/work/",
+            &WebPushSubscription::new("/push/123"),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("absolute URL"));
+}
+
+#[test]
+fn push_values_with_escapes_are_kept_whole() {
+    let xml = r#"<d:multistatus xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/"><d:response>
+  <d:propstat><d:prop>
+    <cs:push-transports><cs:transport type="APSD">
+      <cs:subscription-url><d:href>https://example.com/apns?a=1&amp;b=2</d:href></cs:subscription-url>
+    </cs:transport></cs:push-transports>
+    <cs:pushkey>/CalDAV/r&amp;d/</cs:pushkey>
+  </d:prop></d:propstat>
+</d:response></d:multistatus>"#;
+
+    let info = parse_push_info(xml.as_bytes()).unwrap();
+    assert_eq!(info.push_key.as_deref(), Some("/CalDAV/r&d/"));
+    assert!(matches!(
+        &info.transports[0],
+        PushTransport::Apns { subscription_url: Some(url), .. }
+            if url == "https://example.com/apns?a=1&b=2"
+    ));
+}
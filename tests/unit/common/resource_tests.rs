@@ -0,0 +1,165 @@
+use fast_dav_rs::webdav::{
+    CollectionListing, DavResource, ListFilter, MemberFailure, PropertyName, RecursiveReport,
+    parse_resources, resource_registry,
+};
+
+#[test]
+fn maps_standard_and_custom_properties() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav" xmlns:oc="http://owncloud.org/ns">
+  <d:response>
+    <d:href>/dav/calendars/jane/work/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:displayname>Work</d:displayname>
+        <d:resourcetype><d:collection/><cal:calendar/></d:resourcetype>
+        <oc:owner-principal>principals/users/jane</oc:owner-principal>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:getcontentlength/><d:getetag/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/calendars/jane/work/a.ics</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getetag>"abc"</d:getetag>
+        <d:resourcetype/>
+        <d:getcontenttype>text/calendar; charset=utf-8</d:getcontenttype>
+        <d:getcontentlength> 812 </d:getcontentlength>
+        <d:getlastmodified>Tue, 01 Oct 2024 08:00:00 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    let resources = parse_resources(xml.as_bytes()).unwrap();
+    assert_eq!(resources.len(), 2);
+
+    let calendar = &resources[0];
+    assert_eq!(calendar.displayname.as_deref(), Some("Work"));
+    assert!(calendar.is_collection());
+    assert!(calendar.has_resource_type(&PropertyName::new(
+        "urn:ietf:params:xml:ns:caldav",
+        "calendar"
+    )));
+    assert_eq!(calendar.etag, None);
+    assert_eq!(calendar.content_length, None);
+    assert_eq!(calendar.props.len(), 1);
+    assert_eq!(
+        calendar
+            .prop(&PropertyName::new(
+                "http://owncloud.org/ns",
+                "owner-principal"
+            ))
+            .and_then(|v| v.text())
+            .as_deref(),
+        Some("principals/users/jane")
+    );
+
+    let event = &resources[1];
+    assert!(!event.is_collection());
+    assert!(event.resource_types.is_empty());
+    assert_eq!(event.etag.as_deref(), Some("\"abc\""));
+    assert_eq!(
+        event.content_type.as_deref(),
+        Some("text/calendar; charset=utf-8")
+    );
+    assert_eq!(event.content_length, Some(812));
+    assert_eq!(
+        event.last_modified.as_deref(),
+        Some("Tue, 01 Oct 2024 08:00:00 GMT")
+    );
+}
+
+#[test]
+fn resource_registry_adds_extra_properties_once() {
+    let extra = [
+        PropertyName::dav("getetag"),
+        PropertyName::new("http://apple.com/ns/ical/", "calendar-color"),
+    ];
+    let registry = resource_registry(&extra);
+    assert_eq!(registry.names().len(), 7);
+    let body = registry.build_propfind_body().unwrap();
+    assert!(body.contains("<D:resourcetype/>"));
+    assert!(body.contains("<ICAL:calendar-color/>"));
+}
+
+fn resource(href: &str, collection: bool, content_type: Option<&str>) -> DavResource {
+    DavResource {
+        href: href.to_string(),
+        resource_types: if collection {
+            vec![PropertyName::dav("collection")]
+        } else {
+            Vec::new()
+        },
+        content_type: content_type.map(str::to_string),
+        ..DavResource::default()
+    }
+}
+
+#[test]
+fn list_filters_match_kind_and_content_type() {
+    let dir = resource("/f/dir/", true, Some("httpd/unix-directory"));
+    let text = resource("/f/a.txt", false, Some("Text/Plain; charset=utf-8"));
+    let untyped = resource("/f/blob", false, None);
+
+    assert!(ListFilter::All.matches(&dir) && ListFilter::All.matches(&untyped));
+    assert!(ListFilter::Collections.matches(&dir) && !ListFilter::Collections.matches(&text));
+    assert!(ListFilter::Files.matches(&text) && !ListFilter::Files.matches(&dir));
+    assert!(ListFilter::ContentType("text/plain".into()).matches(&text));
+    assert!(ListFilter::ContentType("text/*".into()).matches(&text));
+    assert!(!ListFilter::ContentType("text/html".into()).matches(&text));
+    assert!(!ListFilter::ContentType("httpd/*".into()).matches(&dir));
+    assert!(!ListFilter::ContentType("text/*".into()).matches(&untyped));
+}
+
+#[test]
+fn collection_listing_is_sorted_filtered_and_paged() {
+    let listing = CollectionListing::new(vec![
+        resource("/f/c.txt", false, Some("text/plain")),
+        resource("/f/sub/", true, None),
+        resource("/f/a.txt", false, Some("text/plain")),
+    ]);
+    let hrefs: Vec<_> = listing.iter().map(|r| r.href.as_str()).collect();
+    assert_eq!(hrefs, ["/f/a.txt", "/f/c.txt", "/f/sub/"]);
+
+    let files = listing.filter(&ListFilter::Files);
+    assert_eq!(files.len(), 2);
+    let pages: Vec<usize> = listing.pages(2).map(<[DavResource]>::len).collect();
+    assert_eq!(pages, [2, 1]);
+    assert_eq!(listing.pages(0).count(), 3);
+    assert!(
+        listing
+            .filter(&ListFilter::ContentType("image/*".into()))
+            .is_empty()
+    );
+    assert_eq!(listing.into_iter().count(), 3);
+}
+
+#[test]
+fn recursive_report_success_requires_no_failed_members() {
+    let mut report = RecursiveReport {
+        status: 207,
+        ..RecursiveReport::default()
+    };
+    assert!(report.is_success());
+
+    report.failed.push(MemberFailure {
+        href: "/files/old/locked.txt".into(),
+        status: Some("HTTP/1.1 423 Locked".into()),
+    });
+    assert!(!report.is_success());
+    assert_eq!(report.failed[0].status_code(), Some(423));
+
+    let gone = RecursiveReport {
+        status: 404,
+        recovered: vec!["/files/old/locked.txt".into()],
+        ..RecursiveReport::default()
+    };
+    assert!(gone.is_success());
+}
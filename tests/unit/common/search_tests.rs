@@ -0,0 +1,121 @@
+use crate::mock_server::serve_once;
+use fast_dav_rs::webdav::{BasicSearch, Depth, PropertyName, SearchCondition, WebDavClient};
+
+#[test]
+fn basicsearch_body_has_select_scope_where_orderby_and_limit() {
+    let body = BasicSearch::new("/files/jane/")
+        .depth(Depth::One)
+        .filter(SearchCondition::And(vec![
+            SearchCondition::Like(PropertyName::dav("displayname"), "%.pdf".into()),
+            SearchCondition::IsCollection.negate(),
+        ]))
+        .order_by(PropertyName::dav("displayname"))
+        .order_by_desc(PropertyName::dav("getlastmodified"))
+        .limit(5)
+        .build()
+        .unwrap();
+    assert!(body.starts_with(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:searchrequest xmlns:D="DAV:"><D:basicsearch>"#
+    ));
+    assert!(body.contains("<D:select><D:prop><D:displayname/><D:getetag/><D:resourcetype/>"));
+    assert!(body.contains(
+        "<D:from><D:scope><D:href>/files/jane/</D:href><D:depth>1</D:depth></D:scope></D:from>"
+    ));
+    assert!(body.contains(
+        "<D:where><D:and><D:like><D:prop><D:displayname/></D:prop><D:literal>%.pdf</D:literal></D:like><D:not><D:is-collection/></D:not></D:and></D:where>"
+    ));
+    assert!(body.contains(
+        "<D:orderby><D:order><D:prop><D:displayname/></D:prop><D:ascending/></D:order><D:order><D:prop><D:getlastmodified/></D:prop><D:descending/></D:order></D:orderby>"
+    ));
+    assert!(body.ends_with(
+        "<D:limit><D:nresults>5</D:nresults></D:limit></D:basicsearch></D:searchrequest>"
+    ));
+}
+
+#[test]
+fn minimal_search_omits_optional_clauses() {
+    let body = BasicSearch::new("/").build().unwrap();
+    assert!(body.contains("<D:depth>infinity</D:depth>"));
+    assert!(!body.contains("<D:where>"));
+    assert!(!body.contains("<D:orderby>"));
+    assert!(!body.contains("<D:limit>"));
+}
+
+#[test]
+fn custom_namespaces_are_declared() {
+    let body = BasicSearch::new("/")
+        .filter(SearchCondition::Gte(
+            PropertyName::new("http://owncloud.org/ns", "size"),
+            "1024".into(),
+        ))
+        .order_by(PropertyName::new("https://example.com/ns", "rank"))
+        .build()
+        .unwrap();
+    assert!(body.contains(r#"xmlns:OC="http://owncloud.org/ns""#));
+    assert!(body.contains(r#"xmlns:X0="https://example.com/ns""#));
+    assert!(body.contains("<D:gte><D:prop><OC:size/></D:prop><D:literal>1024</D:literal></D:gte>"));
+    assert!(body.contains("<D:prop><X0:rank/></D:prop>"));
+}
+
+#[test]
+fn literals_and_scope_are_escaped() {
+    let body = BasicSearch::new("/a&b/")
+        .filter(SearchCondition::Eq(
+            PropertyName::dav("displayname"),
+            "<Q&A>".into(),
+        ))
+        .build()
+        .unwrap();
+    assert!(body.contains("<D:href>/a&amp;b/</D:href>"));
+    assert!(body.contains("<D:literal>&lt;Q&amp;A&gt;</D:literal>"));
+}
+
+#[test]
+fn contains_escapes_like_wildcards() {
+    assert_eq!(
+        SearchCondition::contains(PropertyName::dav("displayname"), r"50%_off\"),
+        SearchCondition::Like(PropertyName::dav("displayname"), r"%50\%\_off\\%".into())
+    );
+}
+
+#[test]
+fn invalid_queries_are_rejected() {
+    assert!(
+        BasicSearch::new("/")
+            .filter(SearchCondition::Or(vec![]))
+            .build()
+            .is_err()
+    );
+    assert!(
+        BasicSearch::new("/")
+            .filter(SearchCondition::IsDefined(PropertyName::dav("bad name")))
+            .build()
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn search_results_keep_escaped_hrefs_whole() {
+    let (base, server) = serve_once(
+        concat!(
+            "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+            r#"<d:multistatus xmlns:d="DAV:"><d:response>
+<d:href>/files/R&amp;D/plan.pdf</d:href>
+<d:propstat><d:prop><d:displayname>R &amp; D plan</d:displayname></d:prop>
+<d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+</d:response></d:multistatus>"#
+        ),
+        b"</D:searchrequest>",
+    )
+    .await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let results = client
+        .search("/files/", &BasicSearch::new("/files/"))
+        .await
+        .unwrap();
+    server.await.unwrap();
+
+    assert_eq!(results[0].href, "/files/R&D/plan.pdf");
+    assert_eq!(results[0].displayname.as_deref(), Some("R & D plan"));
+}
@@ -0,0 +1,100 @@
+use fast_dav_rs::webdav::parse_versions;
+
+#[test]
+fn version_tree_response() {
+    let xml = r#"<?xml version="1.0"?>
+<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/versions/42/2</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:version-name>V2</D:version-name>
+        <D:creator-displayname>Jane &amp; Co</D:creator-displayname>
+        <D:getetag>"e2"</D:getetag>
+        <D:predecessor-set><D:href>/versions/42/1</D:href></D:predecessor-set>
+        <D:successor-set/>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+    <D:propstat>
+      <D:prop><D:comment/></D:prop>
+      <D:status>HTTP/1.1 404 Not Found</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/versions/42/1</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:version-name>V1</D:version-name>
+        <D:comment>Initial import</D:comment>
+        <D:getlastmodified>Mon, 12 Jan 2026 10:00:00 GMT</D:getlastmodified>
+        <D:successor-set><D:href>/versions/42/2</D:href></D:successor-set>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let versions = parse_versions(xml.as_bytes()).unwrap();
+    assert_eq!(versions.len(), 2);
+    let (v1, v2) = (&versions[0], &versions[1]);
+    assert_eq!(v1.href, "/versions/42/1");
+    assert_eq!(v1.comment.as_deref(), Some("Initial import"));
+    assert_eq!(
+        v1.last_modified.as_deref(),
+        Some("Mon, 12 Jan 2026 10:00:00 GMT")
+    );
+    assert_eq!(v1.successors, ["/versions/42/2"]);
+    assert!(v1.predecessors.is_empty());
+    assert_eq!(v2.version_name.as_deref(), Some("V2"));
+    assert_eq!(v2.creator.as_deref(), Some("Jane & Co"));
+    assert_eq!(v2.etag.as_deref(), Some("\"e2\""));
+    assert_eq!(v2.predecessors, ["/versions/42/1"]);
+    assert_eq!(v2.comment, None);
+}
+
+#[test]
+fn expand_property_response_yields_nested_versions() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/calendars/jane/work/event.ics</D:href>
+    <D:propstat><D:prop><D:version-history>
+      <D:response>
+        <D:href>/history/42</D:href>
+        <D:propstat><D:prop><D:version-set>
+          <D:response>
+            <D:href>/versions/42/1</D:href>
+            <D:propstat><D:prop><D:version-name>V1</D:version-name></D:prop>
+            <D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+          </D:response>
+          <D:response>
+            <D:href>/versions/42/2</D:href>
+            <D:propstat><D:prop>
+              <D:version-name>V2</D:version-name>
+              <D:predecessor-set><D:href>/versions/42/1</D:href></D:predecessor-set>
+            </D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+          </D:response>
+        </D:version-set></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+      </D:response>
+    </D:version-history></D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>
+  </D:response>
+</D:multistatus>"#;
+    let versions = parse_versions(xml.as_bytes()).unwrap();
+    let hrefs: Vec<_> = versions.iter().map(|v| v.href.as_str()).collect();
+    assert_eq!(hrefs, ["/versions/42/1", "/versions/42/2"]);
+    assert_eq!(versions[1].predecessors, ["/versions/42/1"]);
+}
+
+#[test]
+fn resources_without_version_name_are_skipped() {
+    let xml = r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/a</D:href>
+      <D:propstat><D:prop><D:getetag>"x"</D:getetag></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response></D:multistatus>"#;
+    assert!(parse_versions(xml.as_bytes()).unwrap().is_empty());
+}
+
+#[test]
+fn malformed_xml_is_an_error() {
+    assert!(
+        parse_versions(b"<D:multistatus xmlns:D=\"DAV:\"><D:response></D:multistatus>").is_err()
+    );
+}
@@ -0,0 +1,195 @@
+use bytes::Bytes;
+use fast_dav_rs::webdav::WebDavClient;
+use hyper::{Response, StatusCode};
+
+use crate::mock_server::serve_once;
+
+fn response(status: StatusCode, body: &str) -> Response<Bytes> {
+    let mut response = Response::new(Bytes::from(body.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+#[test]
+fn detects_propfind_finite_depth_precondition() {
+    let forbidden = response(
+        StatusCode::FORBIDDEN,
+        r#"<?xml version="1.0"?><d:error xmlns:d="DAV:"><d:propfind-finite-depth/></d:error>"#,
+    );
+    assert!(WebDavClient::is_propfind_finite_depth(&forbidden));
+
+    let other = response(
+        StatusCode::FORBIDDEN,
+        r#"<d:error xmlns:d="DAV:"><d:need-privileges/></d:error>"#,
+    );
+    assert!(!WebDavClient::is_propfind_finite_depth(&other));
+    assert!(!WebDavClient::is_propfind_finite_depth(&response(
+        StatusCode::MULTI_STATUS,
+        "<d:propfind-finite-depth/>"
+    )));
+}
+
+#[tokio::test]
+async fn propfind_infinity_requires_resourcetype_before_sending() {
+    let client = WebDavClient::new("http://127.0.0.1:9/", None, None).unwrap();
+    let err = client
+        .propfind_infinity(
+            "files/",
+            r#"<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#,
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("resourcetype"));
+}
+
+/// Answer one HTTP request on a local socket with `reply`, returning the raw request once
+/// `request_end` has been received.
+#[tokio::test]
+async fn put_file_streams_the_body_and_returns_the_etag() {
+    let (base, server) = serve_once(
+        "HTTP/1.1 201 Created\r\nETag: \"v1\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        b"0\r\n\r\n",
+    )
+    .await;
+    let client = WebDavClient::new(&base, None, None).unwrap();
+    let etag = client
+        .put_file("docs/report.txt", "text/plain", &b"hello file"[..])
+        .await
+        .unwrap();
+    assert_eq!(etag.as_deref(), Some("\"v1\""));
+
+    let request = String::from_utf8(server.await.unwrap()).unwrap();
+    assert!(request.starts_with("PUT /docs/report.txt HTTP/1.1\r\n"));
+    assert!(
+        request
+            .to_ascii_lowercase()
+            .contains("content-type: text/plain")
+    );
+    assert!(request.contains("hello file"));
+}
+
+#[tokio::test]
+async fn get_to_writer_copies_the_body() {
+    let (base, server) = serve_once(
+        "HTTP/1.1 200 OK\r\nContent-Length: 11\r\nConnection: close\r\n\r\nfile body\r\n",
+        b"\r\n\r\n",
+    )
+    .await;
+    let client = WebDavClient::new(&base, None, None).unwrap();
+    let mut out = Vec::new();
+    let written = client.get_to_writer("docs/a.txt", &mut out).await.unwrap();
+    assert_eq!(written, 11);
+    assert_eq!(out, b"file body\r\n");
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn get_to_writer_fails_on_error_status() {
+    let (base, server) = serve_once(
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        b"\r\n\r\n",
+    )
+    .await;
+    let client = WebDavClient::new(&base, None, None).unwrap();
+    let mut out = Vec::new();
+    let err = client.get_to_writer("missing", &mut out).await.unwrap_err();
+    assert!(err.to_string().contains("404"));
+    assert!(out.is_empty());
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn list_collection_leaves_out_the_collection_itself() {
+    let (base, server) = serve_once(
+        concat!(
+            "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+            r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>http://dav.example.com/files/Jane%20Doe</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:response><d:href>/files/Jane%20Doe/z.txt</d:href><d:propstat><d:prop><d:getcontenttype>text/plain</d:getcontenttype><d:resourcetype/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:response><d:href>/files/Jane%20Doe/docs/</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#
+        ),
+        b"</D:propfind>",
+    )
+    .await;
+    let mut client = WebDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let listing = client.list_collection("/files/Jane%20Doe/").await.unwrap();
+    server.await.unwrap();
+
+    let hrefs: Vec<_> = listing.iter().map(|r| r.href.as_str()).collect();
+    assert_eq!(
+        hrefs,
+        ["/files/Jane%20Doe/docs/", "/files/Jane%20Doe/z.txt"]
+    );
+    assert!(listing.iter().next().unwrap().is_collection());
+}
+
+#[tokio::test]
+async fn supports_webdav_sync_is_false_when_allow_omits_report() {
+    let (base, server) = serve_once(
+        "HTTP/1.1 200 OK\r\nDAV: 1, 2\r\nAllow: OPTIONS, GET, PUT, PROPFIND\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        b"\r\n\r\n",
+    )
+    .await;
+    let client = WebDavClient::new(&base, None, None).unwrap();
+    assert!(!client.supports_webdav_sync().await.unwrap());
+    let request = String::from_utf8(server.await.unwrap()).unwrap();
+    assert!(request.starts_with("OPTIONS / HTTP/1.1"));
+}
+
+#[tokio::test]
+async fn delete_recursive_reports_failed_members() {
+    let (base, server) = serve_once(
+        concat!(
+            "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+            r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>/files/old/locked.txt</d:href><d:status>HTTP/1.1 423 Locked</d:status></d:response>
+<d:response><d:href>/files/old/</d:href><d:status>HTTP/1.1 424 Failed Dependency</d:status></d:response>
+</d:multistatus>"#
+        ),
+        b"\r\n\r\n",
+    )
+    .await;
+    let client = WebDavClient::new(&base, None, None).unwrap();
+    let report = client.delete_recursive("/files/old/", 0).await.unwrap();
+    let request = String::from_utf8(server.await.unwrap()).unwrap();
+    assert!(request.starts_with("DELETE /files/old/ HTTP/1.1"));
+
+    assert!(!report.is_success());
+    assert_eq!(report.status, 207);
+    let failed: Vec<_> = report
+        .failed
+        .iter()
+        .map(|f| (f.href.as_str(), f.status_code()))
+        .collect();
+    assert_eq!(
+        failed,
+        [
+            ("/files/old/locked.txt", Some(423)),
+            ("/files/old/", Some(424))
+        ]
+    );
+    assert!(report.recovered.is_empty());
+}
+
+#[tokio::test]
+async fn copy_recursive_succeeds_without_multistatus() {
+    let (base, server) = serve_once(
+        "HTTP/1.1 201 Created\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        b"\r\n\r\n",
+    )
+    .await;
+    let client = WebDavClient::new(&base, None, None).unwrap();
+    let dest = format!("{base}files/new/");
+    let report = client
+        .copy_recursive("/files/old/", &dest, false, 2)
+        .await
+        .unwrap();
+    let request = String::from_utf8(server.await.unwrap()).unwrap();
+    assert!(request.starts_with("COPY /files/old/ HTTP/1.1"));
+    assert!(request.to_ascii_lowercase().contains("overwrite: f"));
+
+    assert!(report.is_success());
+    assert_eq!(report.status, 201);
+}
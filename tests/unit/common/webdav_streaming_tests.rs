@@ -0,0 +1,188 @@
+use anyhow::{Result, anyhow};
+use bytes::Bytes;
+use fast_dav_rs::webdav::streaming::{
+    Visit, parse_multistatus_bytes_visit, parse_multistatus_bytes_visit_until,
+    parse_multistatus_stream, parse_multistatus_stream_visit, parse_multistatus_stream_visit_until,
+};
+use fast_dav_rs::webdav::{PropertyName, ResourceProps, parse_properties};
+use http_body_util::Full;
+use hyper::Request;
+use hyper::body::Incoming;
+use hyper::client::conn::http1;
+use hyper_util::rt::TokioIo;
+use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
+
+const LISTING: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+  <d:response>
+    <d:href>/files/jane/Q%26A/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:displayname>Q&amp;A</d:displayname>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <oc:size>4096</oc:size>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/files/jane/notes.txt</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getcontentlength>12</d:getcontentlength>
+        <oc:comment><![CDATA[a <b> c]]></oc:comment>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:getetag/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+/// Serve `xml` over an in-memory HTTP/1.1 connection, in two chunks, and hand the body to
+/// `parse`.
+async fn with_streamed_body<T>(
+    xml: &str,
+    parse: impl AsyncFnOnce(Incoming) -> Result<T>,
+) -> Result<T> {
+    let (client_io, mut server_io) = io::duplex(16 * 1024);
+    let body = xml.as_bytes().to_vec();
+    let header = format!(
+        "HTTP/1.1 207 Multi-Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    let server_task = tokio::spawn(async move {
+        let mut buf = [0u8; 1024];
+        let mut seen = Vec::new();
+        while !seen.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = server_io.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            seen.extend_from_slice(&buf[..n]);
+        }
+        server_io.write_all(header.as_bytes()).await?;
+        let split = body.len() / 2;
+        server_io.write_all(&body[..split]).await?;
+        server_io.write_all(&body[split..]).await?;
+        server_io.shutdown().await?;
+        Ok::<(), std::io::Error>(())
+    });
+
+    let (mut sender, conn) = http1::handshake(TokioIo::new(client_io)).await?;
+    let conn_task = tokio::spawn(conn);
+    let req = Request::builder()
+        .uri("http://localhost/")
+        .body(Full::<Bytes>::default())?;
+    let resp = sender.send_request(req).await?;
+    let parsed = parse(resp.into_body()).await?;
+    server_task.await??;
+    conn_task.await??;
+    Ok(parsed)
+}
+
+#[tokio::test]
+async fn streamed_listing_matches_aggregated_parse() -> Result<()> {
+    let streamed = with_streamed_body(LISTING, async |body| {
+        parse_multistatus_stream(body, &[]).await
+    })
+    .await?;
+    assert_eq!(streamed, parse_properties(LISTING.as_bytes())?);
+
+    assert_eq!(streamed.len(), 2);
+    assert_eq!(streamed[0].href, "/files/jane/Q%26A/");
+    let name = streamed[0].get(&PropertyName::dav("displayname")).unwrap();
+    assert_eq!(name.text().as_deref(), Some("Q&A"));
+    let types = streamed[0].get(&PropertyName::dav("resourcetype")).unwrap();
+    assert_eq!(types.children, [PropertyName::dav("collection")]);
+    let comment = streamed[1]
+        .get(&PropertyName::new("http://owncloud.org/ns", "comment"))
+        .unwrap();
+    assert_eq!(comment.xml, "<![CDATA[a <b> c]]>");
+    let etag = streamed[1]
+        .props
+        .iter()
+        .find(|p| p.name == PropertyName::dav("getetag"))
+        .unwrap();
+    assert_eq!(etag.status.as_deref(), Some("HTTP/1.1 404 Not Found"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn visitor_receives_resources_in_order_and_can_stop() -> Result<()> {
+    let hrefs = with_streamed_body(LISTING, async |body| {
+        let mut hrefs = Vec::new();
+        parse_multistatus_stream_visit(body, &[], |resource: ResourceProps| {
+            hrefs.push(resource.href);
+            Ok(())
+        })
+        .await?;
+        Ok(hrefs)
+    })
+    .await?;
+    assert_eq!(hrefs, ["/files/jane/Q%26A/", "/files/jane/notes.txt"]);
+
+    let mut seen = 0;
+    let err = parse_multistatus_bytes_visit(LISTING.as_bytes(), |_| {
+        seen += 1;
+        Err(anyhow!("stop"))
+    })
+    .unwrap_err();
+    assert_eq!(err.to_string(), "stop");
+    assert_eq!(seen, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn visitor_stop_leaves_the_rest_of_the_body_unread() -> Result<()> {
+    let hrefs = with_streamed_body(LISTING, async |body| {
+        let mut hrefs = Vec::new();
+        parse_multistatus_stream_visit_until(body, &[], |resource: ResourceProps| {
+            hrefs.push(resource.href);
+            Ok(Visit::Stop)
+        })
+        .await?;
+        Ok(hrefs)
+    })
+    .await?;
+    assert_eq!(hrefs, ["/files/jane/Q%26A/"]);
+
+    // Whatever follows the stopping resource is never parsed.
+    let cut = &LISTING[..LISTING.find("</d:response>").unwrap() + "</d:response>".len()];
+    let truncated = format!("{cut}<d:response><d:href>/broken</d:oops>");
+    let mut seen = 0;
+    parse_multistatus_bytes_visit_until(truncated.as_bytes(), |_| {
+        seen += 1;
+        Ok(Visit::Stop)
+    })?;
+    assert_eq!(seen, 1);
+    Ok(())
+}
+
+#[test]
+fn nested_property_xml_is_kept_verbatim() {
+    let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/a</d:href><d:propstat><d:prop>
+      <d:lockdiscovery><d:activelock><d:owner><d:href>mailto:a&amp;b@example.com</d:href></d:owner></d:activelock></d:lockdiscovery>
+    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response></d:multistatus>"#;
+    let resources = parse_properties(xml.as_bytes()).unwrap();
+    let value = resources[0]
+        .get(&PropertyName::dav("lockdiscovery"))
+        .unwrap();
+    assert_eq!(
+        value.xml,
+        "<d:activelock><d:owner><d:href>mailto:a&amp;b@example.com</d:href></d:owner></d:activelock>"
+    );
+}
+
+#[test]
+fn unbalanced_xml_is_an_error() {
+    assert!(
+        parse_multistatus_bytes_visit(
+            b"<d:multistatus xmlns:d=\"DAV:\"></d:response></d:multistatus>",
+            |_| Ok(())
+        )
+        .is_err()
+    );
+}
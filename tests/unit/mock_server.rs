@@ -0,0 +1,106 @@
+//! Local HTTP server answering canned replies, for client tests that need a real socket.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Serve one connection: read the request up to `request_end`, answer `reply` (a raw HTTP
+/// response with `\r\n` line endings) and hand back the request bytes.
+pub async fn serve_once(
+    reply: &'static str,
+    request_end: &'static [u8],
+) -> (String, JoinHandle<Vec<u8>>) {
+    let (base, server) = serve_sequence(vec![reply], request_end).await;
+    let server = tokio::spawn(async move { server.await.unwrap().remove(0) });
+    (base, server)
+}
+
+/// Serve one connection per reply, in order; every reply should close its connection.
+pub async fn serve_sequence(
+    replies: Vec<&'static str>,
+    request_end: &'static [u8],
+) -> (String, JoinHandle<Vec<Vec<u8>>>) {
+    serve_exchanges(
+        replies
+            .into_iter()
+            .map(|reply| (request_end, reply))
+            .collect(),
+    )
+    .await
+}
+
+/// Serve one connection per `(request_end, reply)` exchange, in order.
+pub async fn serve_exchanges(
+    exchanges: Vec<(&'static [u8], &'static str)>,
+) -> (String, JoinHandle<Vec<Vec<u8>>>) {
+    serve_raw(
+        exchanges
+            .into_iter()
+            .map(|(request_end, reply)| (request_end, reply.as_bytes().to_vec()))
+            .collect(),
+    )
+    .await
+}
+
+/// [`serve_once`] with a binary body (e.g. compressed) after the `head` of the reply; the
+/// `Content-Length` and `Connection: close` headers are added.
+pub async fn serve_bytes(
+    head: &str,
+    body: &[u8],
+    request_end: &'static [u8],
+) -> (String, JoinHandle<Vec<u8>>) {
+    let mut reply = format!(
+        "{head}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    reply.extend_from_slice(body);
+    let (base, server) = serve_raw(vec![(request_end, reply)]).await;
+    let server = tokio::spawn(async move { server.await.unwrap().remove(0) });
+    (base, server)
+}
+
+/// [`serve_bytes`] sending `chunks` with `Transfer-Encoding: chunked`, so the reply has no
+/// `Content-Length`.
+pub async fn serve_chunked(
+    head: &str,
+    chunks: &[&[u8]],
+    request_end: &'static [u8],
+) -> (String, JoinHandle<Vec<u8>>) {
+    let mut reply =
+        format!("{head}\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n").into_bytes();
+    for chunk in chunks.iter().filter(|chunk| !chunk.is_empty()) {
+        reply.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+        reply.extend_from_slice(chunk);
+        reply.extend_from_slice(b"\r\n");
+    }
+    reply.extend_from_slice(b"0\r\n\r\n");
+    let (base, server) = serve_raw(vec![(request_end, reply)]).await;
+    let server = tokio::spawn(async move { server.await.unwrap().remove(0) });
+    (base, server)
+}
+
+async fn serve_raw(exchanges: Vec<(&'static [u8], Vec<u8>)>) -> (String, JoinHandle<Vec<Vec<u8>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}/", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for (request_end, reply) in exchanges {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !request.windows(request_end.len()).any(|w| w == request_end) {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            socket.write_all(&reply).await.unwrap();
+            socket.shutdown().await.unwrap();
+            requests.push(request);
+        }
+        requests
+    });
+    (base, server)
+}
@@ -1,3 +1,6 @@
 pub mod caldav;
 pub mod carddav;
 pub mod common;
+pub mod mock_server;
+pub mod sync;
+pub mod testing;
@@ -0,0 +1,108 @@
+use bytes::Bytes;
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::sync::{CacheEntry, CachedGet, EtagCache, MemoryEtagCache, body_hash};
+use std::sync::Arc;
+
+use crate::mock_server::serve_sequence;
+
+#[test]
+fn body_hash_is_fnv1a() {
+    assert_eq!(body_hash(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(body_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_ne!(body_hash(b"BEGIN:VCARD"), body_hash(b"BEGIN:VCARE"));
+}
+
+#[tokio::test]
+async fn memory_cache_stores_and_forgets_entries() {
+    let cache = MemoryEtagCache::new();
+    let entry = CacheEntry {
+        etag: "\"1\"".into(),
+        body_hash: body_hash(b"x"),
+    };
+    cache.put("/c/a.vcf", entry.clone()).await.unwrap();
+    assert_eq!(cache.get("/c/a.vcf").await.unwrap(), Some(entry));
+    assert_eq!(cache.len(), 1);
+    cache.remove("/c/a.vcf").await.unwrap();
+    assert!(cache.is_empty());
+}
+
+const VCARD: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Jane Doe\r\nUID:jane-1\r\nEND:VCARD\r\n";
+
+#[tokio::test]
+async fn get_cached_revalidates_with_if_none_match() {
+    let (base, server) = serve_sequence(
+        vec![
+            concat!(
+                "HTTP/1.1 200 OK\r\nETag: \"1\"\r\nContent-Type: text/vcard\r\nContent-Length: 62\r\nConnection: close\r\n\r\n",
+                "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Jane Doe\r\nUID:jane-1\r\nEND:VCARD\r\n"
+            ),
+            "HTTP/1.1 304 Not Modified\r\nETag: \"1\"\r\nConnection: close\r\n\r\n",
+        ],
+        b"\r\n\r\n",
+    )
+    .await;
+    let cache = Arc::new(MemoryEtagCache::new());
+    let mut client = CardDavClient::new(&base, None, None).unwrap();
+    client.set_etag_cache(cache.clone());
+
+    let first = client.get_cached("contacts/jane.vcf").await.unwrap();
+    assert_eq!(
+        first,
+        CachedGet::Fetched {
+            body: Bytes::from_static(VCARD.as_bytes()),
+            etag: Some("\"1\"".into()),
+        }
+    );
+    let second = client.get_cached("contacts/jane.vcf").await.unwrap();
+    assert_eq!(
+        second,
+        CachedGet::NotModified {
+            etag: "\"1\"".into(),
+            body_hash: body_hash(VCARD.as_bytes()),
+        }
+    );
+
+    let requests = server.await.unwrap();
+    let first_request = String::from_utf8_lossy(&requests[0]).to_ascii_lowercase();
+    let second_request = String::from_utf8_lossy(&requests[1]).to_ascii_lowercase();
+    assert!(!first_request.contains("if-none-match"));
+    assert!(second_request.contains("if-none-match: \"1\""));
+}
+
+#[tokio::test]
+async fn put_cached_sends_if_match_and_stores_the_new_etag() {
+    let (base, server) = serve_sequence(
+        vec!["HTTP/1.1 204 No Content\r\nETag: \"2\"\r\nConnection: close\r\n\r\n"],
+        b"END:VCARD\r\n",
+    )
+    .await;
+    let cache = Arc::new(MemoryEtagCache::new());
+    cache
+        .put(
+            "/contacts/jane.vcf",
+            CacheEntry {
+                etag: "\"1\"".into(),
+                body_hash: 0,
+            },
+        )
+        .await
+        .unwrap();
+    let mut client = CardDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    client.set_etag_cache(cache.clone());
+
+    let response = client
+        .put_cached("contacts/jane.vcf", Bytes::from_static(VCARD.as_bytes()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+    assert_eq!(
+        cache.get("/contacts/jane.vcf").await.unwrap(),
+        Some(CacheEntry {
+            etag: "\"2\"".into(),
+            body_hash: body_hash(VCARD.as_bytes()),
+        })
+    );
+    let request = String::from_utf8_lossy(&server.await.unwrap()[0]).to_ascii_lowercase();
+    assert!(request.contains("if-match: \"1\""));
+}
@@ -0,0 +1,110 @@
+use bytes::Bytes;
+use fast_dav_rs::CardDavClient;
+use fast_dav_rs::sync::{ConflictPolicy, ConflictResolution, EtagCache, MemoryEtagCache};
+use std::sync::Arc;
+
+use crate::mock_server::serve_exchanges;
+
+const LOCAL: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Local\r\nEND:VCARD\r\n";
+const GET_SERVER: &str = concat!(
+    "HTTP/1.1 200 OK\r\nETag: \"5\"\r\nContent-Length: 48\r\nConnection: close\r\n\r\n",
+    "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Server\r\nEND:VCARD\r\n"
+);
+const HEADERS_END: &[u8] = b"\r\n\r\n";
+const VCARD_END: &[u8] = b"END:VCARD\r\n";
+
+fn client(base: &str) -> CardDavClient {
+    let mut client = CardDavClient::new(base, None, None).unwrap();
+    client.disable_request_compression();
+    client
+}
+
+#[tokio::test]
+async fn server_wins_refetches_and_updates_the_cache() {
+    let (base, server) = serve_exchanges(vec![(HEADERS_END, GET_SERVER)]).await;
+    let cache = Arc::new(MemoryEtagCache::new());
+    let mut client = client(&base);
+    client.set_etag_cache(cache.clone());
+
+    let resolution = client
+        .resolve_conflict(
+            "contacts/jane.vcf",
+            Bytes::from_static(LOCAL.as_bytes()),
+            &ConflictPolicy::ServerWins,
+        )
+        .await
+        .unwrap();
+    let ConflictResolution::ServerKept { body, etag } = resolution else {
+        panic!("expected ServerKept, got {resolution:?}");
+    };
+    assert!(body.starts_with(b"BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Server"));
+    assert_eq!(etag.as_deref(), Some("\"5\""));
+    let cached = cache.get("/contacts/jane.vcf").await.unwrap().unwrap();
+    assert_eq!(cached.etag, "\"5\"");
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn client_wins_writes_unconditionally() {
+    let (base, server) = serve_exchanges(vec![(
+        VCARD_END,
+        "HTTP/1.1 204 No Content\r\nETag: \"6\"\r\nConnection: close\r\n\r\n",
+    )])
+    .await;
+    let resolution = client(&base)
+        .resolve_conflict(
+            "contacts/jane.vcf",
+            Bytes::from_static(LOCAL.as_bytes()),
+            &ConflictPolicy::ClientWins,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        resolution,
+        ConflictResolution::ClientWritten {
+            etag: Some("\"6\"".into())
+        }
+    );
+    let request = String::from_utf8_lossy(&server.await.unwrap()[0]).to_ascii_lowercase();
+    assert!(request.starts_with("put /contacts/jane.vcf"));
+    assert!(!request.contains("if-match"));
+}
+
+#[tokio::test]
+async fn merge_retries_when_the_merged_write_conflicts() {
+    let (base, server) = serve_exchanges(vec![
+        (HEADERS_END, GET_SERVER),
+        (
+            VCARD_END,
+            "HTTP/1.1 412 Precondition Failed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ),
+        (HEADERS_END, GET_SERVER),
+        (
+            VCARD_END,
+            "HTTP/1.1 204 No Content\r\nETag: \"7\"\r\nConnection: close\r\n\r\n",
+        ),
+    ])
+    .await;
+    let policy = ConflictPolicy::merge(|local, server| {
+        assert!(local.windows(5).any(|w| w == b"Local"));
+        assert!(server.windows(6).any(|w| w == b"Server"));
+        Ok(Bytes::from_static(
+            b"BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Merged\r\nEND:VCARD\r\n",
+        ))
+    });
+    let resolution = client(&base)
+        .resolve_conflict(
+            "contacts/jane.vcf",
+            Bytes::from_static(LOCAL.as_bytes()),
+            &policy,
+        )
+        .await
+        .unwrap();
+    assert_eq!(resolution.etag(), Some("\"7\""));
+    assert!(matches!(resolution, ConflictResolution::Merged { .. }));
+
+    let requests = server.await.unwrap();
+    let put = String::from_utf8_lossy(&requests[3]).to_ascii_lowercase();
+    assert!(put.contains("if-match: \"5\""));
+    assert!(put.contains("fn:merged"));
+}
@@ -0,0 +1,680 @@
+use anyhow::{Result, anyhow};
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::sync::{
+    Change, ChangeKind, ConflictPolicy, MemoryTokenStore, Mutation, MutationQueue, Precondition,
+    ReplayOutcome, ReplayReport, ReplayResult, SyncEngine, SyncFailure, SyncMethod, SyncMode,
+    SyncSource, SyncStore, TokenStore, Tombstone, diff_etags,
+};
+use fast_dav_rs::webdav::InvalidSyncTokenError;
+use futures::future::BoxFuture;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::mock_server::serve_exchanges;
+
+/// Scripted [`SyncSource`] recording the operations it serves.
+#[derive(Default)]
+pub(crate) struct FakeSource {
+    pub(crate) webdav_sync: bool,
+    pub(crate) ctag: Mutex<Option<String>>,
+    pub(crate) listing: Mutex<Vec<(String, String)>>,
+    pub(crate) rounds: Mutex<VecDeque<(Vec<Change>, Option<String>)>>,
+    /// Number of upcoming `changes_since` calls to fail.
+    pub(crate) failures: Mutex<u32>,
+    /// Hrefs failing the next `fetch` that includes them, once per occurrence.
+    pub(crate) fetch_failures: Mutex<Vec<String>>,
+    /// `include_data` of the latest `changes_since` call.
+    pub(crate) inline_data: Mutex<Option<bool>>,
+    pub(crate) calls: Mutex<Vec<String>>,
+}
+
+impl FakeSource {
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    pub(crate) fn calls(&self) -> Vec<String> {
+        std::mem::take(&mut *self.calls.lock().unwrap())
+    }
+
+    pub(crate) fn set_listing(&self, listing: &[(&str, &str)]) {
+        *self.listing.lock().unwrap() = listing
+            .iter()
+            .map(|(href, etag)| (href.to_string(), etag.to_string()))
+            .collect();
+    }
+}
+
+impl SyncSource for FakeSource {
+    fn supports_webdav_sync(&self) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move {
+            self.record("probe".into());
+            Ok(self.webdav_sync)
+        })
+    }
+
+    fn changes_since<'a>(
+        &'a self,
+        _collection: &'a str,
+        sync_token: Option<&'a str>,
+        _limit: Option<u32>,
+        include_data: bool,
+    ) -> BoxFuture<'a, Result<(Vec<Change>, Option<String>)>> {
+        Box::pin(async move {
+            *self.inline_data.lock().unwrap() = Some(include_data);
+            self.record(format!("sync {}", sync_token.unwrap_or("-")));
+            if let Some(token @ "expired") = sync_token {
+                return Err(InvalidSyncTokenError {
+                    token: token.to_string(),
+                    status: 403,
+                }
+                .into());
+            }
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(anyhow!("server unavailable"));
+            }
+            drop(failures);
+            Ok(self.rounds.lock().unwrap().pop_front().unwrap_or_default())
+        })
+    }
+
+    fn ctag<'a>(&'a self, _collection: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+        Box::pin(async move {
+            self.record("ctag".into());
+            Ok(self.ctag.lock().unwrap().clone())
+        })
+    }
+
+    fn etags<'a>(&'a self, _collection: &'a str) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(async move {
+            self.record("etags".into());
+            Ok(self.listing.lock().unwrap().clone())
+        })
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        _collection: &'a str,
+        hrefs: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Change>>> {
+        Box::pin(async move {
+            self.record(format!("fetch {}", hrefs.join(",")));
+            let mut failures = self.fetch_failures.lock().unwrap();
+            if let Some(i) = failures.iter().position(|href| hrefs.contains(href)) {
+                let href = failures.remove(i);
+                return Err(anyhow!("REPORT calendar-multiget failed with 500 ({href})"));
+            }
+            drop(failures);
+            Ok(hrefs
+                .iter()
+                .map(|href| Change {
+                    href: href.clone(),
+                    kind: ChangeKind::Updated,
+                    etag: None,
+                    data: Some(format!("data of {href}")),
+                })
+                .collect())
+        })
+    }
+
+    fn content_type(&self) -> &'static str {
+        "text/calendar"
+    }
+
+    /// Applies the mutations to `listing`, giving written members the ETag `"w<n>"` and
+    /// rejecting those whose precondition does not hold with `412`.
+    fn push<'a>(
+        &'a self,
+        queue: &'a dyn MutationQueue,
+        _policy: &'a ConflictPolicy,
+    ) -> BoxFuture<'a, Result<ReplayReport>> {
+        Box::pin(async move {
+            let mut report = ReplayReport::default();
+            for queued in queue.pending().await? {
+                let mutation = &queued.mutation;
+                let path = mutation.path().to_string();
+                self.record(format!("push {path} {:?}", mutation.precondition()));
+                let result = {
+                    let mut listing = self.listing.lock().unwrap();
+                    let current = listing.iter().position(|(href, _)| *href == path);
+                    let holds = match (mutation.precondition(), current) {
+                        (Precondition::None, _) => true,
+                        (Precondition::IfMatch(etag), Some(i)) => listing[i].1 == *etag,
+                        (Precondition::IfMatch(_), None) => false,
+                        (Precondition::IfNoneMatch, current) => current.is_none(),
+                    };
+                    listing.retain(|(href, _)| !holds || *href != path);
+                    match mutation {
+                        _ if !holds => ReplayResult::Rejected { status: 412 },
+                        Mutation::Put { .. } => {
+                            let etag = format!("\"w{}\"", queued.id);
+                            listing.push((path, etag.clone()));
+                            ReplayResult::Applied { etag: Some(etag) }
+                        }
+                        Mutation::Delete { .. } => ReplayResult::Applied { etag: None },
+                    }
+                };
+                queue.remove(queued.id).await?;
+                report.outcomes.push(ReplayOutcome {
+                    mutation: queued,
+                    result,
+                });
+            }
+            Ok(report)
+        })
+    }
+}
+
+pub(crate) fn reported(href: &str, etag: &str) -> Change {
+    Change {
+        href: href.into(),
+        kind: ChangeKind::Updated,
+        etag: Some(etag.into()),
+        data: None,
+    }
+}
+
+fn kinds(changes: &[Change]) -> Vec<(&str, ChangeKind)> {
+    changes.iter().map(|c| (c.href.as_str(), c.kind)).collect()
+}
+
+#[tokio::test]
+async fn ctag_polling_diffs_etags_when_the_ctag_moves() {
+    let source = FakeSource::default();
+    *source.ctag.lock().unwrap() = Some("c1".into());
+    source.set_listing(&[("/cal/a.ics", "\"1\""), ("/cal/b.ics", "\"1\"")]);
+    let store = Arc::new(MemoryTokenStore::new());
+    let engine = SyncEngine::new(source, store.clone());
+
+    let first = engine.sync("cal/").await.unwrap();
+    assert_eq!(first.method, SyncMethod::CtagPolling);
+    assert_eq!(
+        kinds(&first.changes),
+        vec![
+            ("/cal/a.ics", ChangeKind::Created),
+            ("/cal/b.ics", ChangeKind::Created)
+        ]
+    );
+    assert_eq!(first.changes[0].data.as_deref(), Some("data of /cal/a.ics"));
+    assert_eq!(
+        engine.client().calls(),
+        vec!["probe", "ctag", "etags", "fetch /cal/a.ics,/cal/b.ics"]
+    );
+
+    // Same ctag: nothing is listed.
+    assert!(engine.sync("cal/").await.unwrap().is_empty());
+    assert_eq!(engine.client().calls(), vec!["ctag"]);
+
+    *engine.client().ctag.lock().unwrap() = Some("c2".into());
+    engine
+        .client()
+        .set_listing(&[("/cal/b.ics", "\"2\""), ("/cal/c.ics", "\"1\"")]);
+    let third = engine.sync("cal/").await.unwrap();
+    assert_eq!(
+        kinds(&third.changes),
+        vec![
+            ("/cal/b.ics", ChangeKind::Updated),
+            ("/cal/c.ics", ChangeKind::Created),
+            ("/cal/a.ics", ChangeKind::Deleted)
+        ]
+    );
+    assert_eq!(
+        engine.client().calls(),
+        vec!["ctag", "etags", "fetch /cal/b.ics,/cal/c.ics"]
+    );
+    let state = store.state("cal/");
+    assert_eq!(state.ctag.as_deref(), Some("c2"));
+    assert_eq!(state.etags.len(), 2);
+    assert_eq!(state.etags["/cal/b.ics"], "\"2\"");
+}
+
+#[tokio::test]
+async fn ctag_polling_lists_every_time_without_a_ctag() {
+    let source = FakeSource::default();
+    source.set_listing(&[("/cal/a.ics", "\"1\"")]);
+    let engine = SyncEngine::new(source, Arc::new(MemoryTokenStore::new())).include_data(false);
+
+    engine.sync("cal/").await.unwrap();
+    assert!(engine.sync("cal/").await.unwrap().is_empty());
+    assert_eq!(
+        engine.client().calls(),
+        vec!["probe", "ctag", "etags", "ctag", "etags"]
+    );
+}
+
+#[tokio::test]
+async fn webdav_sync_tells_creations_from_updates() {
+    let source = FakeSource {
+        webdav_sync: true,
+        ..FakeSource::default()
+    };
+    source.rounds.lock().unwrap().extend([
+        (
+            vec![
+                reported("/cal/a.ics", "\"1\""),
+                reported("/cal/b.ics", "\"1\""),
+            ],
+            Some("t1".to_string()),
+        ),
+        (
+            vec![
+                reported("/cal/a.ics", "\"2\""),
+                reported("/cal/c.ics", "\"1\""),
+                Change {
+                    href: "/cal/b.ics".into(),
+                    kind: ChangeKind::Deleted,
+                    etag: None,
+                    data: None,
+                },
+            ],
+            Some("t2".to_string()),
+        ),
+    ]);
+    let store = Arc::new(MemoryTokenStore::new());
+    let engine = SyncEngine::new(source, store.clone());
+
+    let first = engine.sync("cal/").await.unwrap();
+    assert_eq!(first.method, SyncMethod::WebDavSync);
+    assert_eq!(
+        kinds(&first.changes),
+        vec![
+            ("/cal/a.ics", ChangeKind::Created),
+            ("/cal/b.ics", ChangeKind::Created)
+        ]
+    );
+    let second = engine.sync("cal/").await.unwrap();
+    assert_eq!(
+        kinds(&second.changes),
+        vec![
+            ("/cal/a.ics", ChangeKind::Updated),
+            ("/cal/c.ics", ChangeKind::Created),
+            ("/cal/b.ics", ChangeKind::Deleted)
+        ]
+    );
+    assert_eq!(engine.client().calls(), vec!["probe", "sync -", "sync t1"]);
+    assert_eq!(
+        store.sync_token("cal/").await.unwrap().as_deref(),
+        Some("t2")
+    );
+    let etags = store.etags("cal/").await.unwrap();
+    assert_eq!(etags.len(), 2);
+    assert_eq!(etags["/cal/a.ics"], "\"2\"");
+}
+
+#[tokio::test]
+async fn full_webdav_sync_deletes_members_it_no_longer_lists() {
+    let source = FakeSource::default();
+    source.rounds.lock().unwrap().push_back((
+        vec![
+            reported("/cal/a.ics", "\"2\""),
+            reported("/cal/same.ics", "\"1\""),
+        ],
+        Some("t1".into()),
+    ));
+    let store = Arc::new(MemoryTokenStore::new());
+    store
+        .set_etags(
+            "cal/",
+            [
+                ("/cal/a.ics".to_string(), "\"1\"".to_string()),
+                ("/cal/same.ics".to_string(), "\"1\"".to_string()),
+                ("/cal/gone.ics".to_string(), "\"1\"".to_string()),
+            ]
+            .into(),
+        )
+        .await
+        .unwrap();
+    let engine = SyncEngine::new(source, store).method(SyncMethod::WebDavSync);
+
+    // Members listed with their stored ETag are left out.
+    let run = engine.sync("cal/").await.unwrap();
+    assert_eq!(
+        kinds(&run.changes),
+        vec![
+            ("/cal/a.ics", ChangeKind::Updated),
+            ("/cal/gone.ics", ChangeKind::Deleted)
+        ]
+    );
+    // A forced method skips the probe.
+    assert_eq!(engine.client().calls(), vec!["sync -"]);
+}
+
+const CTAG_REPLY: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/"><d:response><d:href>/cal/</d:href><d:propstat><d:prop><cs:getctag>ctag-7</cs:getctag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response></d:multistatus>"#
+);
+
+const LISTING_REPLY: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>/cal/</d:href><d:propstat><d:prop><d:getetag>"c"</d:getetag><d:resourcetype><d:collection/></d:resourcetype></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:response><d:href>/cal/a.ics</d:href><d:propstat><d:prop><d:getetag>"1"</d:getetag><d:resourcetype/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#
+);
+
+const MULTIGET_REPLY: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav"><d:response><d:href>/cal/a.ics</d:href><d:propstat><d:prop><d:getetag>"1"</d:getetag><c:calendar-data>BEGIN:VCALENDAR
+END:VCALENDAR</c:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response></d:multistatus>"#
+);
+
+#[tokio::test]
+async fn caldav_client_polls_ctag_and_fetches_changed_members() {
+    let (base, server) = serve_exchanges(vec![
+        (b"</D:propfind>", CTAG_REPLY),
+        (b"</D:propfind>", LISTING_REPLY),
+        (b"</C:calendar-multiget>", MULTIGET_REPLY),
+    ])
+    .await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let store = Arc::new(MemoryTokenStore::new());
+    let engine = SyncEngine::new(client, store.clone()).method(SyncMethod::CtagPolling);
+
+    let run = engine.sync("cal/").await.unwrap();
+    assert_eq!(
+        kinds(&run.changes),
+        vec![("/cal/a.ics", ChangeKind::Created)]
+    );
+    assert!(
+        run.changes[0]
+            .data
+            .as_deref()
+            .unwrap()
+            .contains("VCALENDAR")
+    );
+    assert_eq!(store.state("cal/").ctag.as_deref(), Some("ctag-7"));
+
+    let requests = server.await.unwrap();
+    let ctag_request = String::from_utf8_lossy(&requests[0]).to_ascii_lowercase();
+    assert!(ctag_request.contains("depth: 0"));
+    assert!(ctag_request.contains("getctag"));
+}
+
+#[test]
+fn diff_etags_leaves_unchanged_members_out() {
+    let known = [
+        ("/card/a.vcf".to_string(), "\"1\"".to_string()),
+        ("/card/b.vcf".to_string(), "\"1\"".to_string()),
+    ]
+    .into();
+    let listing = [
+        ("/card/a.vcf".to_string(), "\"1\"".to_string()),
+        ("/card/b.vcf".to_string(), "\"1\"".to_string()),
+    ];
+    assert!(diff_etags(&known, listing.iter().map(|(h, e)| (h, e))).is_empty());
+
+    let changes = diff_etags(&known, Vec::<(String, String)>::new());
+    assert_eq!(
+        kinds(&changes),
+        vec![
+            ("/card/a.vcf", ChangeKind::Deleted),
+            ("/card/b.vcf", ChangeKind::Deleted)
+        ]
+    );
+    assert_eq!(changes[0].etag, None);
+}
+
+#[tokio::test]
+async fn deletions_leave_tombstones_with_the_last_etag() {
+    let source = FakeSource::default();
+    source.set_listing(&[("/cal/a.ics", "\"1\""), ("/cal/b.ics", "\"4\"")]);
+    let store = Arc::new(MemoryTokenStore::new());
+    let engine = SyncEngine::new(source, store.clone()).include_data(false);
+    engine.sync("cal/").await.unwrap();
+    assert!(engine.tombstones("cal/").await.unwrap().is_empty());
+
+    engine.client().set_listing(&[("/cal/a.ics", "\"1\"")]);
+    let before = SystemTime::now();
+    engine.sync("cal/").await.unwrap();
+    let tombstones = engine.tombstones("cal/").await.unwrap();
+    assert_eq!(tombstones.len(), 1);
+    assert_eq!(tombstones[0].href, "/cal/b.ics");
+    assert_eq!(tombstones[0].etag.as_deref(), Some("\"4\""));
+    assert!(tombstones[0].deleted_at >= before);
+
+    // Recreating the member clears its tombstone.
+    engine
+        .client()
+        .set_listing(&[("/cal/a.ics", "\"1\""), ("/cal/b.ics", "\"5\"")]);
+    engine.sync("cal/").await.unwrap();
+    assert!(store.tombstones("cal/").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn expired_tombstones_are_dropped() {
+    let source = FakeSource {
+        webdav_sync: true,
+        ..FakeSource::default()
+    };
+    source.rounds.lock().unwrap().push_back((
+        vec![Change {
+            href: "/cal/b.ics".into(),
+            kind: ChangeKind::Deleted,
+            etag: None,
+            data: None,
+        }],
+        Some("t2".into()),
+    ));
+    let store = Arc::new(MemoryTokenStore::new());
+    store
+        .set_sync_token("cal/", Some("t1".into()))
+        .await
+        .unwrap();
+    store
+        .set_tombstones(
+            "cal/",
+            vec![Tombstone {
+                href: "/cal/old.ics".into(),
+                deleted_at: SystemTime::now() - Duration::from_secs(7200),
+                etag: None,
+            }],
+        )
+        .await
+        .unwrap();
+    let engine =
+        SyncEngine::new(source, store.clone()).tombstone_retention(Duration::from_secs(3600));
+    assert!(engine.tombstones("cal/").await.unwrap().is_empty());
+
+    engine.sync("cal/").await.unwrap();
+    let stored = store.tombstones("cal/").await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].href, "/cal/b.ics");
+    assert_eq!(stored[0].etag, None);
+}
+
+#[tokio::test]
+async fn hydrate_fetches_data_after_a_metadata_only_sync() {
+    let source = FakeSource {
+        webdav_sync: true,
+        ..FakeSource::default()
+    };
+    source.rounds.lock().unwrap().push_back((
+        vec![
+            reported("/cal/a.ics", "\"1\""),
+            Change {
+                href: "/cal/b.ics".into(),
+                kind: ChangeKind::Deleted,
+                etag: None,
+                data: None,
+            },
+        ],
+        Some("t1".into()),
+    ));
+    let engine = SyncEngine::new(source, Arc::new(MemoryTokenStore::new()))
+        .include_data(false)
+        .hydrate(true);
+
+    let run = engine.sync("cal/").await.unwrap();
+    assert_eq!(*engine.client().inline_data.lock().unwrap(), Some(false));
+    assert_eq!(
+        engine.client().calls(),
+        vec!["probe", "sync -", "fetch /cal/a.ics"]
+    );
+    assert_eq!(run.changes[0].data.as_deref(), Some("data of /cal/a.ics"));
+    assert_eq!(run.changes[1].data, None);
+}
+
+#[tokio::test]
+async fn include_data_requests_data_inline_without_hydration() {
+    let source = FakeSource {
+        webdav_sync: true,
+        ..FakeSource::default()
+    };
+    source
+        .rounds
+        .lock()
+        .unwrap()
+        .push_back((vec![reported("/cal/a.ics", "\"1\"")], Some("t1".into())));
+    let engine = SyncEngine::new(source, Arc::new(MemoryTokenStore::new()));
+
+    engine.sync("cal/").await.unwrap();
+    assert_eq!(*engine.client().inline_data.lock().unwrap(), Some(true));
+    assert_eq!(engine.client().calls(), vec!["probe", "sync -"]);
+}
+
+fn five_members() -> FakeSource {
+    let source = FakeSource {
+        webdav_sync: true,
+        ..FakeSource::default()
+    };
+    let members = ["a", "b", "c", "d", "e"]
+        .map(|name| reported(&format!("/cal/{name}.ics"), "\"1\""))
+        .to_vec();
+    source
+        .rounds
+        .lock()
+        .unwrap()
+        .push_back((members, Some("t1".into())));
+    source
+}
+
+#[tokio::test]
+async fn body_budget_defers_the_bodies_past_it() {
+    // Each body ("data of /cal/a.ics") is 18 bytes.
+    let engine = SyncEngine::new(five_members(), Arc::new(MemoryTokenStore::new())).body_budget(40);
+
+    let run = engine.sync("cal/").await.unwrap();
+    assert_eq!(*engine.client().inline_data.lock().unwrap(), Some(false));
+    assert_eq!(
+        engine.client().calls(),
+        vec!["probe", "sync -", "fetch /cal/a.ics", "fetch /cal/b.ics"]
+    );
+    assert_eq!(run.mode, SyncMode::MetadataFirst);
+    let deferred: Vec<String> = run.deferred().map(String::from).collect();
+    assert_eq!(deferred, ["/cal/c.ics", "/cal/d.ics", "/cal/e.ics"]);
+
+    let bodies = engine.fetch_bodies("cal/", &deferred).await.unwrap();
+    assert_eq!(bodies[2].data.as_deref(), Some("data of /cal/e.ics"));
+}
+
+#[tokio::test]
+async fn body_budget_sizes_multigets_from_the_bodies_seen() {
+    let engine =
+        SyncEngine::new(five_members(), Arc::new(MemoryTokenStore::new())).body_budget(1_000);
+
+    let run = engine.sync("cal/").await.unwrap();
+    assert_eq!(
+        engine.client().calls(),
+        vec![
+            "probe",
+            "sync -",
+            "fetch /cal/a.ics",
+            "fetch /cal/b.ics,/cal/c.ics,/cal/d.ics,/cal/e.ics",
+        ]
+    );
+    assert_eq!(run.mode, SyncMode::FullBody);
+    assert_eq!(run.deferred().count(), 0);
+}
+
+#[tokio::test]
+async fn failed_multigets_are_retried_member_by_member() {
+    let source = five_members();
+    *source.fetch_failures.lock().unwrap() = vec!["/cal/b.ics".into()];
+    let engine = SyncEngine::new(source, Arc::new(MemoryTokenStore::new())).hydrate(true);
+
+    let run = engine.sync("cal/").await.unwrap();
+    assert_eq!(
+        engine.client().calls()[2..],
+        [
+            "fetch /cal/a.ics,/cal/b.ics,/cal/c.ics,/cal/d.ics,/cal/e.ics",
+            "fetch /cal/a.ics",
+            "fetch /cal/b.ics",
+            "fetch /cal/c.ics",
+            "fetch /cal/d.ics",
+            "fetch /cal/e.ics",
+        ]
+    );
+    assert!(run.failed.is_empty());
+    assert_eq!(run.mode, SyncMode::FullBody);
+}
+
+#[tokio::test]
+async fn members_failing_every_retry_are_reported() {
+    let source = five_members();
+    *source.fetch_failures.lock().unwrap() = vec!["/cal/c.ics".into(); 3];
+    let engine = SyncEngine::new(source, Arc::new(MemoryTokenStore::new()))
+        .hydrate(true)
+        .item_retries(2);
+
+    let run = engine.sync("cal/").await.unwrap();
+    assert_eq!(
+        run.failed,
+        [SyncFailure {
+            href: "/cal/c.ics".into(),
+            error: "REPORT calendar-multiget failed with 500 (/cal/c.ics)".into(),
+        }]
+    );
+    assert_eq!(run.mode, SyncMode::MetadataFirst);
+    assert_eq!(run.deferred().collect::<Vec<_>>(), ["/cal/c.ics"]);
+    assert_eq!(run.changes.len(), 5);
+}
+
+#[tokio::test]
+async fn rejected_sync_token_falls_back_to_a_full_resync() {
+    let source = FakeSource::default();
+    source.rounds.lock().unwrap().push_back((
+        vec![
+            reported("/cal/a.ics", "\"1\""),
+            reported("/cal/c.ics", "\"1\""),
+        ],
+        Some("t5".into()),
+    ));
+    let store = Arc::new(MemoryTokenStore::new());
+    store
+        .set_sync_token("cal/", Some("expired".into()))
+        .await
+        .unwrap();
+    store
+        .set_etags(
+            "cal/",
+            [
+                ("/cal/a.ics".to_string(), "\"1\"".to_string()),
+                ("/cal/b.ics".to_string(), "\"1\"".to_string()),
+            ]
+            .into(),
+        )
+        .await
+        .unwrap();
+    let engine = SyncEngine::new(source, store.clone()).method(SyncMethod::WebDavSync);
+
+    let run = engine.sync("cal/").await.unwrap();
+    assert!(run.full_resync);
+    assert_eq!(
+        kinds(&run.changes),
+        vec![
+            ("/cal/c.ics", ChangeKind::Created),
+            ("/cal/b.ics", ChangeKind::Deleted)
+        ]
+    );
+    assert_eq!(engine.client().calls(), vec!["sync expired", "sync -"]);
+    assert_eq!(
+        store.sync_token("cal/").await.unwrap().as_deref(),
+        Some("t5")
+    );
+
+    assert!(!engine.sync("cal/").await.unwrap().full_resync);
+}
@@ -0,0 +1,10 @@
+pub mod cache_tests;
+pub mod conflict_tests;
+pub mod engine_tests;
+pub mod paging_tests;
+pub mod queue_tests;
+pub mod scheduler_tests;
+pub mod snapshot_tests;
+pub mod store_tests;
+pub mod two_way_tests;
+pub mod watch_tests;
@@ -0,0 +1,69 @@
+use fast_dav_rs::CardDavClient;
+
+use crate::mock_server::serve_sequence;
+
+const ROUND_1: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>/book/a.vcf</d:href><d:propstat><d:prop><d:getetag>"a1"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:response><d:href>/book/b.vcf</d:href><d:propstat><d:prop><d:getetag>"b1"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:response><d:href>/book/</d:href><d:status>HTTP/1.1 507 Insufficient Storage</d:status></d:response>
+<d:sync-token>token-1</d:sync-token>
+</d:multistatus>"#
+);
+
+const ROUND_2: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>/book/a.vcf</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>
+<d:response><d:href>/book/c.vcf</d:href><d:propstat><d:prop><d:getetag>"c1"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:sync-token>token-2</d:sync-token>
+</d:multistatus>"#
+);
+
+fn client(base: &str) -> CardDavClient {
+    let mut client = CardDavClient::new(base, None, None).unwrap();
+    client.disable_request_compression();
+    client
+}
+
+#[tokio::test]
+async fn sync_collection_all_follows_truncated_rounds() {
+    let (base, server) = serve_sequence(vec![ROUND_1, ROUND_2], b"</D:sync-collection>").await;
+    let sync = client(&base)
+        .sync_collection_all("book/", None, Some(2), false)
+        .await
+        .unwrap();
+
+    assert!(!sync.truncated);
+    assert_eq!(sync.sync_token.as_deref(), Some("token-2"));
+    let items: Vec<_> = sync
+        .items
+        .iter()
+        .map(|i| (i.href.as_str(), i.is_deleted))
+        .collect();
+    assert_eq!(
+        items,
+        [
+            ("/book/a.vcf", true),
+            ("/book/b.vcf", false),
+            ("/book/c.vcf", false)
+        ]
+    );
+
+    let requests = server.await.unwrap();
+    let second = String::from_utf8_lossy(&requests[1]);
+    assert!(second.contains("<D:sync-token>token-1</D:sync-token>"));
+    assert!(second.contains("<D:nresults>2</D:nresults>"));
+}
+
+#[tokio::test]
+async fn sync_collection_all_rejects_a_stalled_token() {
+    let (base, server) = serve_sequence(vec![ROUND_1], b"</D:sync-collection>").await;
+    let err = client(&base)
+        .sync_collection_all("book/", Some("token-1"), None, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("did not advance"));
+    server.await.unwrap();
+}
@@ -0,0 +1,103 @@
+use bytes::Bytes;
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::sync::{
+    ConflictPolicy, MemoryMutationQueue, Mutation, MutationQueue, Precondition, ReplayResult,
+};
+
+use crate::mock_server::serve_exchanges;
+
+const EVENT: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\nUID:a\r\nDTSTAMP:20240101T000000Z\r\nDTSTART:20240101T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+#[tokio::test]
+async fn memory_queue_keeps_insertion_order() {
+    let queue = MemoryMutationQueue::new();
+    let first = queue
+        .push(Mutation::delete("calendars/jane/work/a.ics").if_match("\"1\""))
+        .await
+        .unwrap();
+    let second = queue
+        .push(
+            Mutation::put("calendars/jane/work/b.ics", "text/calendar", Bytes::new())
+                .if_none_match(),
+        )
+        .await
+        .unwrap();
+    let pending = queue.pending().await.unwrap();
+    assert_eq!(
+        pending.iter().map(|q| q.id).collect::<Vec<_>>(),
+        [first, second]
+    );
+    assert_eq!(
+        pending[0].mutation.precondition(),
+        &Precondition::IfMatch("\"1\"".into())
+    );
+    assert_eq!(pending[1].mutation.path(), "calendars/jane/work/b.ics");
+
+    queue.remove(first).await.unwrap();
+    assert_eq!(queue.len(), 1);
+}
+
+#[tokio::test]
+async fn replay_reports_each_mutation_and_stops_when_offline() {
+    let (base, server) = serve_exchanges(vec![
+        (
+            b"END:VCALENDAR\r\n".as_slice(),
+            "HTTP/1.1 201 Created\r\nETag: \"1\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ),
+        (
+            b"\r\n\r\n".as_slice(),
+            "HTTP/1.1 412 Precondition Failed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ),
+    ])
+    .await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let queue = MemoryMutationQueue::new();
+    queue
+        .push(
+            Mutation::put(
+                "calendars/jane/work/a.ics",
+                "text/calendar; charset=utf-8",
+                Bytes::from_static(EVENT.as_bytes()),
+            )
+            .if_none_match(),
+        )
+        .await
+        .unwrap();
+    queue
+        .push(Mutation::delete("calendars/jane/work/b.ics").if_match("\"9\""))
+        .await
+        .unwrap();
+    queue
+        .push(Mutation::delete("calendars/jane/work/c.ics"))
+        .await
+        .unwrap();
+
+    let requests = tokio::spawn(async move { server.await.unwrap() });
+    let report = client
+        .replay_mutations(&queue, &ConflictPolicy::ServerWins)
+        .await
+        .unwrap();
+    let requests = requests.await.unwrap();
+
+    let results: Vec<_> = report.outcomes.iter().map(|o| o.result.clone()).collect();
+    assert_eq!(
+        results,
+        [
+            ReplayResult::Applied {
+                etag: Some("\"1\"".into())
+            },
+            ReplayResult::Rejected { status: 412 },
+        ]
+    );
+    assert!(report.interrupted.is_some());
+    assert!(!report.is_success());
+    let pending = queue.pending().await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].mutation.path(), "calendars/jane/work/c.ics");
+
+    let put = String::from_utf8_lossy(&requests[0]).to_ascii_lowercase();
+    assert!(put.contains("if-none-match: *"));
+    let delete = String::from_utf8_lossy(&requests[1]).to_ascii_lowercase();
+    assert!(delete.contains("if-match: \"9\""));
+}
@@ -0,0 +1,99 @@
+use fast_dav_rs::sync::{MemoryTokenStore, SyncEngine, SyncScheduler};
+use futures::StreamExt;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::sync::engine_tests::{FakeSource, reported};
+
+fn quiet_engine() -> Arc<SyncEngine<FakeSource>> {
+    Arc::new(SyncEngine::new(
+        FakeSource::default(),
+        Arc::new(MemoryTokenStore::new()),
+    ))
+}
+
+#[tokio::test]
+async fn empty_scheduler_ends_at_once() {
+    let runs: Vec<_> = SyncScheduler::<FakeSource>::new().run().collect().await;
+    assert!(runs.is_empty());
+}
+
+#[tokio::test]
+async fn syncs_every_collection() {
+    let engine = quiet_engine();
+    let mut scheduler = SyncScheduler::new();
+    for collection in ["/cal/a/", "/cal/b/", "/cal/c/"] {
+        scheduler.add(
+            "dav.example.com",
+            engine.clone(),
+            collection,
+            Duration::from_millis(20),
+        );
+    }
+    assert_eq!(scheduler.len(), 3);
+
+    let runs: Vec<_> = scheduler.run().take(3).collect().await;
+    let collections: HashSet<&str> = runs.iter().map(|run| run.collection.as_str()).collect();
+    assert_eq!(
+        collections,
+        HashSet::from(["/cal/a/", "/cal/b/", "/cal/c/"])
+    );
+    assert!(runs.iter().all(|run| run.host == "dav.example.com"));
+    assert!(runs.iter().all(|run| run.result.is_ok()));
+}
+
+#[tokio::test]
+async fn host_rate_spaces_sync_starts() {
+    let engine = quiet_engine();
+    let mut scheduler = SyncScheduler::new().host_concurrency(8).host_rate(20.0);
+    for collection in ["/cal/a/", "/cal/b/", "/cal/c/"] {
+        scheduler.add(
+            "dav.example.com",
+            engine.clone(),
+            collection,
+            Duration::from_millis(1),
+        );
+    }
+
+    let started = Instant::now();
+    let runs: Vec<_> = scheduler.run().take(3).collect().await;
+    assert_eq!(runs.len(), 3);
+    // Three starts 50ms apart.
+    assert!(started.elapsed() >= Duration::from_millis(95));
+}
+
+#[tokio::test]
+async fn active_collections_are_synced_more_often() {
+    let busy = FakeSource {
+        webdav_sync: true,
+        ..FakeSource::default()
+    };
+    for round in 0..50 {
+        busy.rounds.lock().unwrap().push_back((
+            vec![reported("/cal/busy/a.ics", &format!("\"{round}\""))],
+            Some(format!("t{round}")),
+        ));
+    }
+    let busy = Arc::new(SyncEngine::new(busy, Arc::new(MemoryTokenStore::new())));
+    let mut scheduler = SyncScheduler::new();
+    scheduler.add(
+        "dav.example.com",
+        busy,
+        "/cal/busy/",
+        Duration::from_millis(40),
+    );
+    scheduler.add(
+        "dav.example.com",
+        quiet_engine(),
+        "/cal/quiet/",
+        Duration::from_millis(40),
+    );
+
+    let runs: Vec<_> = scheduler.run().take(10).collect().await;
+    let busy_runs = runs
+        .iter()
+        .filter(|run| run.collection == "/cal/busy/")
+        .count();
+    assert!(busy_runs > 10 - busy_runs, "{busy_runs} busy runs of 10");
+}
@@ -0,0 +1,97 @@
+use fast_dav_rs::sync::{
+    CollectionState, MemoryTokenStore, SNAPSHOT_VERSION, SyncEngine, SyncSnapshot, SyncStore,
+    TokenStore, Tombstone,
+};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use super::engine_tests::FakeSource;
+
+async fn populated_store() -> MemoryTokenStore {
+    let store = MemoryTokenStore::new();
+    store
+        .set_sync_token("cal/", Some("t1".into()))
+        .await
+        .unwrap();
+    store
+        .set_etags(
+            "cal/",
+            [("/cal/a.ics".to_string(), "\"1\"".to_string())].into(),
+        )
+        .await
+        .unwrap();
+    store
+        .set_tombstones(
+            "cal/",
+            vec![Tombstone {
+                href: "/cal/b.ics".into(),
+                deleted_at: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+                etag: Some("\"4\"".into()),
+            }],
+        )
+        .await
+        .unwrap();
+    store.set_ctag("card/", Some("c1".into())).await.unwrap();
+    store
+}
+
+#[tokio::test]
+async fn snapshot_round_trips_through_json() {
+    let store = populated_store().await;
+    let snapshot = store.snapshot();
+    assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+    assert_eq!(snapshot.collections.len(), 2);
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert!(json.contains(r#""sync_token":"t1""#));
+    let decoded: SyncSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, snapshot);
+
+    let copy = MemoryTokenStore::new();
+    decoded.restore(&copy).await.unwrap();
+    assert_eq!(copy.state("cal/"), store.state("cal/"));
+    assert_eq!(copy.ctag("card/").await.unwrap().as_deref(), Some("c1"));
+}
+
+#[tokio::test]
+async fn engine_exports_and_imports_the_state_of_named_collections() {
+    let store = Arc::new(populated_store().await);
+    let engine = SyncEngine::new(FakeSource::default(), store);
+    let snapshot = engine.export_state(["cal/"]).await.unwrap();
+    assert_eq!(
+        snapshot.collections.keys().collect::<Vec<_>>(),
+        vec!["cal/"]
+    );
+
+    let target = Arc::new(MemoryTokenStore::new());
+    let other = SyncEngine::new(FakeSource::default(), target.clone());
+    other.import_state(&snapshot).await.unwrap();
+    assert_eq!(
+        target.sync_token("cal/").await.unwrap().as_deref(),
+        Some("t1")
+    );
+    assert_eq!(target.tombstones("cal/").await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn snapshots_from_newer_versions_are_rejected() {
+    let json = r#"{"version":99,"collections":{}}"#;
+    let snapshot: SyncSnapshot = serde_json::from_str(json).unwrap();
+    let err = snapshot
+        .restore(&MemoryTokenStore::new())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("version 99"));
+}
+
+#[test]
+fn unknown_fields_are_ignored_and_missing_ones_rejected() {
+    let state: CollectionState = serde_json::from_str(
+        r#"{"sync_token":null,"ctag":"c1","etags":{},"tombstones":[],"extra":1}"#,
+    )
+    .unwrap();
+    assert_eq!(state.ctag.as_deref(), Some("c1"));
+
+    let err = serde_json::from_str::<CollectionState>(r#"{"ctag":"c1"}"#).unwrap_err();
+    assert!(err.to_string().contains("missing field"));
+}
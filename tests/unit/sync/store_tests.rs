@@ -0,0 +1,90 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::sync::{CollectionState, MemoryTokenStore, TokenStore};
+
+use crate::mock_server::serve_sequence;
+
+#[tokio::test]
+async fn memory_store_keeps_state_per_collection() {
+    let store = MemoryTokenStore::new();
+    store
+        .set_sync_token("calendars/jane/work/", Some("t1".into()))
+        .await
+        .unwrap();
+    store
+        .set_ctag("calendars/jane/work/", Some("c1".into()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store.state("calendars/jane/work/"),
+        CollectionState {
+            sync_token: Some("t1".into()),
+            ctag: Some("c1".into()),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        store.sync_token("calendars/jane/home/").await.unwrap(),
+        None
+    );
+
+    store
+        .set_sync_token("calendars/jane/work/", None)
+        .await
+        .unwrap();
+    assert_eq!(
+        store.sync_token("calendars/jane/work/").await.unwrap(),
+        None
+    );
+    assert_eq!(
+        store.ctag("calendars/jane/work/").await.unwrap().as_deref(),
+        Some("c1")
+    );
+}
+
+const SYNC_REPLY_1: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:">
+<d:response><d:href>/calendars/jane/work/a.ics</d:href><d:propstat><d:prop><d:getetag>"1"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+<d:sync-token>http://example.com/sync/1</d:sync-token>
+</d:multistatus>"#
+);
+
+const SYNC_REPLY_2: &str = concat!(
+    "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml\r\nConnection: close\r\n\r\n",
+    r#"<d:multistatus xmlns:d="DAV:"><d:sync-token>http://example.com/sync/2</d:sync-token></d:multistatus>"#
+);
+
+#[tokio::test]
+async fn sync_collection_with_store_resumes_from_the_stored_token() {
+    let (base, server) =
+        serve_sequence(vec![SYNC_REPLY_1, SYNC_REPLY_2], b"</D:sync-collection>").await;
+    let mut client = CalDavClient::new(&base, None, None).unwrap();
+    client.disable_request_compression();
+    let store = MemoryTokenStore::new();
+
+    let first = client
+        .sync_collection_with_store("calendars/jane/work/", &store, None, false)
+        .await
+        .unwrap();
+    assert_eq!(first.items.len(), 1);
+    let second = client
+        .sync_collection_with_store("calendars/jane/work/", &store, None, false)
+        .await
+        .unwrap();
+    assert!(second.items.is_empty());
+    assert_eq!(
+        store
+            .sync_token("calendars/jane/work/")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("http://example.com/sync/2")
+    );
+
+    let requests = server.await.unwrap();
+    let first_request = String::from_utf8_lossy(&requests[0]);
+    let second_request = String::from_utf8_lossy(&requests[1]);
+    assert!(first_request.contains("<D:sync-token/>"));
+    assert!(second_request.contains("http://example.com/sync/1"));
+}
@@ -0,0 +1,150 @@
+use fast_dav_rs::sync::{
+    Change, ChangeKind, ConflictPolicy, LocalChange, MemoryTokenStore, ReplayResult, SyncEngine,
+    SyncStore, TokenStore,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::sync::engine_tests::{FakeSource, reported};
+
+async fn synced_engine(source: FakeSource) -> (SyncEngine<FakeSource>, Arc<MemoryTokenStore>) {
+    source.set_listing(&[("/cal/a.ics", "\"1\""), ("/cal/b.ics", "\"1\"")]);
+    let store = Arc::new(MemoryTokenStore::new());
+    let engine = SyncEngine::new(source, store.clone()).include_data(false);
+    engine.sync("/cal/").await.unwrap();
+    engine.client().calls();
+    (engine, store)
+}
+
+#[tokio::test]
+async fn pushes_with_preconditions_from_the_stored_etags() {
+    let (engine, store) = synced_engine(FakeSource::default()).await;
+    // Another client adds a member meanwhile.
+    engine
+        .client()
+        .listing
+        .lock()
+        .unwrap()
+        .push(("/cal/d.ics".into(), "\"1\"".into()));
+
+    let run = engine
+        .push_and_pull(
+            "/cal/",
+            vec![
+                LocalChange::put("/cal/a.ics", "BEGIN:VCALENDAR"),
+                LocalChange::put("https://dav.example.com/cal/c.ics", "BEGIN:VCALENDAR"),
+                LocalChange::delete("/cal/b.ics"),
+            ],
+            &ConflictPolicy::ServerWins,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        engine.client().calls()[..3],
+        [
+            "push /cal/a.ics IfMatch(\"\\\"1\\\"\")",
+            "push /cal/c.ics IfNoneMatch",
+            "push /cal/b.ics IfMatch(\"\\\"1\\\"\")",
+        ]
+    );
+    assert!(run.push.is_success());
+    // The pull only brings the change of the other client.
+    let pull = run.pull.unwrap();
+    let kinds: Vec<_> = pull
+        .changes
+        .iter()
+        .map(|c| (c.href.as_str(), c.kind))
+        .collect();
+    assert_eq!(kinds, [("/cal/d.ics", ChangeKind::Created)]);
+    assert_eq!(
+        store.etags("/cal/").await.unwrap(),
+        HashMap::from([
+            ("/cal/a.ics".to_string(), "\"w1\"".to_string()),
+            ("/cal/c.ics".to_string(), "\"w2\"".to_string()),
+            ("/cal/d.ics".to_string(), "\"1\"".to_string()),
+        ])
+    );
+}
+
+#[tokio::test]
+async fn rejected_writes_leave_the_server_change_to_the_pull() {
+    let (engine, store) = synced_engine(FakeSource::default()).await;
+    engine
+        .client()
+        .set_listing(&[("/cal/a.ics", "\"2\""), ("/cal/b.ics", "\"1\"")]);
+
+    let run = engine
+        .push_and_pull(
+            "/cal/",
+            vec![LocalChange::put("/cal/a.ics", "BEGIN:VCALENDAR")],
+            &ConflictPolicy::ServerWins,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        run.push.outcomes[0].result,
+        ReplayResult::Rejected { status: 412 }
+    );
+    let pull = run.pull.unwrap();
+    assert_eq!(pull.changes.len(), 1);
+    assert_eq!(pull.changes[0].href, "/cal/a.ics");
+    assert_eq!(pull.changes[0].kind, ChangeKind::Updated);
+    assert_eq!(store.etags("/cal/").await.unwrap()["/cal/a.ics"], "\"2\"");
+}
+
+#[tokio::test]
+async fn webdav_sync_pull_drops_the_echo_of_pushed_writes() {
+    let source = FakeSource {
+        webdav_sync: true,
+        ..Default::default()
+    };
+    source
+        .rounds
+        .lock()
+        .unwrap()
+        .push_back((vec![reported("/cal/a.ics", "\"1\"")], Some("t1".into())));
+    let store = Arc::new(MemoryTokenStore::new());
+    let engine = SyncEngine::new(source, store.clone()).include_data(false);
+    engine.sync("/cal/").await.unwrap();
+    engine.client().set_listing(&[("/cal/a.ics", "\"1\"")]);
+
+    // The server reports the local writes back, next to a change of another client.
+    engine.client().rounds.lock().unwrap().push_back((
+        vec![
+            reported("/cal/a.ics", "\"w1\""),
+            Change {
+                href: "/cal/b.ics".into(),
+                kind: ChangeKind::Deleted,
+                etag: None,
+                data: None,
+            },
+            reported("/cal/e.ics", "\"1\""),
+        ],
+        Some("t2".into()),
+    ));
+    let run = engine
+        .push_and_pull(
+            "/cal/",
+            vec![
+                LocalChange::put("/cal/a.ics", "BEGIN:VCALENDAR"),
+                LocalChange::delete("/cal/b.ics"),
+            ],
+            &ConflictPolicy::ServerWins,
+        )
+        .await
+        .unwrap();
+
+    let pull = run.pull.unwrap();
+    let kinds: Vec<_> = pull
+        .changes
+        .iter()
+        .map(|c| (c.href.as_str(), c.kind))
+        .collect();
+    assert_eq!(kinds, [("/cal/e.ics", ChangeKind::Created)]);
+    assert_eq!(
+        store.sync_token("/cal/").await.unwrap().as_deref(),
+        Some("t2")
+    );
+}
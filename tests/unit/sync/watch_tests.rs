@@ -0,0 +1,92 @@
+use fast_dav_rs::sync::{
+    Change, ChangeEvent, ChangeKind, MemoryTokenStore, SyncEngine, SyncMethod,
+};
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::engine_tests::{FakeSource, reported};
+
+#[tokio::test]
+async fn watch_yields_the_changes_of_each_poll() {
+    let source = FakeSource::default();
+    source.rounds.lock().unwrap().extend([
+        (
+            vec![
+                reported("/cal/a.ics", "\"1\""),
+                reported("/cal/b.ics", "\"1\""),
+            ],
+            Some("t1".to_string()),
+        ),
+        (vec![], Some("t2".to_string())),
+        (
+            vec![
+                reported("/cal/a.ics", "\"2\""),
+                Change {
+                    href: "/cal/b.ics".into(),
+                    kind: ChangeKind::Deleted,
+                    etag: None,
+                    data: None,
+                },
+            ],
+            Some("t3".to_string()),
+        ),
+    ]);
+    let engine =
+        SyncEngine::new(source, Arc::new(MemoryTokenStore::new())).method(SyncMethod::WebDavSync);
+
+    let events: Vec<ChangeEvent> = engine
+        .watch("cal/", Duration::from_millis(5))
+        .take(4)
+        .collect()
+        .await;
+    assert_eq!(
+        events,
+        vec![
+            ChangeEvent::Created("/cal/a.ics".into()),
+            ChangeEvent::Created("/cal/b.ics".into()),
+            ChangeEvent::Updated("/cal/a.ics".into()),
+            ChangeEvent::Deleted("/cal/b.ics".into()),
+        ]
+    );
+    assert_eq!(events[3].href(), "/cal/b.ics");
+}
+
+#[tokio::test]
+async fn watch_backs_off_after_a_failed_poll() {
+    let source = FakeSource::default();
+    *source.failures.lock().unwrap() = 1;
+    source
+        .rounds
+        .lock()
+        .unwrap()
+        .push_back((vec![reported("/cal/a.ics", "\"1\"")], Some("t1".into())));
+    let engine =
+        SyncEngine::new(source, Arc::new(MemoryTokenStore::new())).method(SyncMethod::WebDavSync);
+
+    let started = Instant::now();
+    let mut watch = engine.watch("cal/", Duration::from_millis(20));
+    assert_eq!(
+        watch.next().await,
+        Some(ChangeEvent::Created("/cal/a.ics".into()))
+    );
+    // One failure doubles the wait before the next poll.
+    assert!(started.elapsed() >= Duration::from_millis(40));
+    assert_eq!(watch.last_error(), None);
+}
+
+#[tokio::test]
+async fn watch_reports_the_latest_error_while_failing() {
+    let source = FakeSource::default();
+    *source.failures.lock().unwrap() = u32::MAX;
+    let engine =
+        SyncEngine::new(source, Arc::new(MemoryTokenStore::new())).method(SyncMethod::WebDavSync);
+
+    let mut watch = engine.watch("cal/", Duration::from_millis(5));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), watch.next())
+            .await
+            .is_err()
+    );
+    assert_eq!(watch.last_error().as_deref(), Some("server unavailable"));
+}
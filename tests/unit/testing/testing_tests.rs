@@ -0,0 +1,57 @@
+use fast_dav_rs::CalDavClient;
+use fast_dav_rs::testing::{
+    CleanupGuard, DEFAULT_TEST_URL, TestServerConfig, unique_calendar_name, unique_contact_uri,
+    unique_uid, wait_for_server,
+};
+use std::collections::HashSet;
+use std::time::Duration;
+
+#[test]
+fn unique_names_do_not_repeat() {
+    let names: HashSet<String> = (0..1000).map(|_| unique_calendar_name("cal")).collect();
+    assert_eq!(names.len(), 1000);
+}
+
+#[test]
+fn unique_helpers_use_expected_shapes() {
+    assert!(unique_calendar_name("cal").starts_with("cal_"));
+    let uid = unique_uid("event");
+    assert!(uid.starts_with("event-"));
+    assert!(uid.ends_with("@example.com"));
+    let uri = unique_contact_uri("jane");
+    assert!(uri.starts_with("jane_"));
+    assert!(uri.ends_with(".vcf"));
+}
+
+#[test]
+fn test_server_config_defaults_match_sabredav_environment() {
+    let config = TestServerConfig::default();
+    assert_eq!(config.url, DEFAULT_TEST_URL);
+    assert_eq!(config.user, "test");
+    assert_eq!(config.pass, "test");
+}
+
+#[tokio::test]
+async fn cleanup_guard_tracks_and_untracks_paths() {
+    let client = CalDavClient::new("http://127.0.0.1:9/", None, None).expect("client");
+    let mut guard = CleanupGuard::new(client);
+    guard.track("calendars/test/a/");
+    guard.track("calendars/test/a/event.ics");
+    guard.untrack("calendars/test/a/event.ics");
+    assert_eq!(guard.paths(), ["calendars/test/a/".to_string()]);
+    guard.untrack("calendars/test/a/");
+    assert!(guard.cleanup().await.is_empty());
+}
+
+#[tokio::test]
+async fn wait_for_server_times_out_when_nothing_listens() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    drop(listener);
+
+    let client = CalDavClient::new(&format!("http://{addr}/"), None, None).expect("client");
+    let err = wait_for_server(&client, Duration::from_millis(300))
+        .await
+        .expect_err("no server is listening");
+    assert!(err.to_string().contains("not ready"));
+}